@@ -42,6 +42,7 @@ pub async fn dump_cell_state(
         ELEMENT_VAULT_PRIVATE_ENTRIES
     );
     kv!("element vault - headers", ELEMENT_VAULT_HEADERS);
+    kv!("element vault - entry chunks", ELEMENT_VAULT_ENTRY_CHUNKS);
     kv!("metadata vault - links", META_VAULT_LINKS);
     kv!("metadata vault - misc", META_VAULT_MISC);
 
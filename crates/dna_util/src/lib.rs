@@ -38,9 +38,12 @@
 //! ```
 
 use holochain_serialized_bytes::prelude::*;
-use holochain_types::dna::{wasm::DnaWasm, zome::Zome, DnaDef, DnaFile};
-use holochain_zome_types::zome::ZomeName;
-use std::{collections::BTreeMap, path::PathBuf};
+use holochain_types::dna::{wasm::DnaWasm, zome::Zome, DnaDef, DnaFile, UpdateConflictPolicy};
+use holochain_zome_types::zome::{FunctionName, ZomeName};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
 
 /// DnaUtilError type.
 #[derive(Debug, thiserror::Error)]
@@ -193,6 +196,14 @@ struct DnaDefJson {
     pub uuid: String,
     pub properties: serde_json::Value,
     pub zomes: BTreeMap<ZomeName, ZomeJson>,
+    #[serde(default)]
+    pub reject_self_links: bool,
+    #[serde(default)]
+    pub max_entry_size: Option<u32>,
+    #[serde(default)]
+    pub update_conflict_policy: UpdateConflictPolicy,
+    #[serde(default)]
+    pub sensitive_fns: BTreeSet<(ZomeName, FunctionName)>,
 }
 
 impl DnaDefJson {
@@ -213,6 +224,10 @@ impl DnaDefJson {
             uuid: dna.uuid,
             properties: properties.0,
             zomes,
+            reject_self_links: dna.reject_self_links,
+            max_entry_size: dna.max_entry_size,
+            update_conflict_policy: dna.update_conflict_policy,
+            sensitive_fns: dna.sensitive_fns,
         })
     }
 
@@ -245,6 +260,10 @@ impl DnaDefJson {
             uuid: self.uuid.clone(),
             properties,
             zomes,
+            reject_self_links: self.reject_self_links,
+            max_entry_size: self.max_entry_size,
+            update_conflict_policy: self.update_conflict_policy,
+            sensitive_fns: self.sensitive_fns.clone(),
         };
 
         Ok(DnaFile::new(dna, wasm_list).await?)
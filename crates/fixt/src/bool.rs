@@ -1,6 +1,6 @@
 use crate::prelude::*;
 
-fixturator!(bool, false, rand::random(), {
+fixturator!(bool, false, rng().gen(), {
     self.0.index += 1;
     self.0.index % 2 != 0
 });
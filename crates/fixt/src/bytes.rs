@@ -13,7 +13,7 @@ fixturator!(
     Bytes,
     vec![],
     {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         let len = rng.gen_range(UNPREDICTABLE_MIN_LEN, UNPREDICTABLE_MAX_LEN);
         let mut u8_fixturator = U8Fixturator::new(Unpredictable);
         let mut bytes = vec![];
@@ -4,10 +4,12 @@ pub mod bool;
 pub mod bytes;
 pub mod number;
 pub mod prelude;
+pub mod rng;
 pub mod serialized_bytes;
 pub mod string;
 pub mod unit;
 pub use paste;
+pub use rng::{reseed, rng, seed};
 
 #[derive(Clone)]
 /// the Fixturator is the struct that we wrap in our FooFixturator newtypes to impl Iterator over
@@ -19,7 +21,7 @@ pub use paste;
 /// this is most easily handled in most cases with the fixturator! and newtype_fixturator! macros
 ///
 /// The inner index is always a single usize.
-/// It can be ignored, e.g. in the case of Unpredictable implementations based on `rand::random()`.
+/// It can be ignored, e.g. in the case of Unpredictable implementations based on `fixt::rng()`.
 /// If it is used it should be incremented by 1 and/or wrapped back to 0 to derive returned values.
 /// Ideally the Curve should allow for efficient calculation of a fixture from any given index,
 /// e.g. a fibbonacci curve would be a bad idea as it requires sequential/recursive calculations to
@@ -250,7 +252,7 @@ macro_rules! fixturator {
 
                 impl [<$type:camel Variant>] {
                     fn random() -> Self {
-                        [<$type:camel Variant>]::iter().choose(&mut thread_rng()).unwrap()
+                        [<$type:camel Variant>]::iter().choose(&mut $crate::rng()).unwrap()
                     }
                     fn nth(index: usize) -> Self {
                         expr! {
@@ -545,7 +547,7 @@ macro_rules! newtype_fixturator {
             $outer,
             $outer(vec![]),
             {
-                let mut rng = thread_rng();
+                let mut rng = $crate::rng();
                 let vec_len = rng.gen_range(0, 5);
                 let mut ret = vec![];
                 let mut inner_fixturator =
@@ -557,7 +559,7 @@ macro_rules! newtype_fixturator {
                 $outer(ret)
             },
             {
-                let mut rng = thread_rng();
+                let mut rng = $crate::rng();
                 let vec_len = rng.gen_range(0, 5);
                 let mut ret = vec![];
                 let mut inner_fixturator =
@@ -637,7 +639,7 @@ macro_rules! enum_fixturator {
             $enum,
             $empty,
             {
-                let mut rng = rand::thread_rng();
+                let mut rng = $crate::rng();
                 $enum::iter().choose(&mut rng).unwrap()
             },
             {
@@ -8,6 +8,7 @@ pub use crate::fixt;
 pub use crate::fixturator;
 pub use crate::newtype_fixturator;
 pub use crate::number::*;
+pub use crate::rng::{reseed, rng};
 pub use crate::serialized_bytes::SerializedBytesFixturator;
 pub use crate::string::{CharFixturator, StringFixturator};
 pub use crate::unit::UnitFixturator;
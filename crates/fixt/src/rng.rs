@@ -0,0 +1,125 @@
+//! The random number generator behind every Unpredictable curve.
+//!
+//! Each thread gets its own generator seeded from a single global seed
+//! combined with the index of the thread, so threads that run at the same
+//! time don't generate the same fixtures.
+//! The seed is read from the `FIXT_SEED` environment variable, or picked at
+//! random if it isn't set. Each thread prints the seed and its index the
+//! first time it generates a fixture so a failing test can be run again
+//! with exactly the same fixtures, e.g. with `--test-threads=1`.
+
+use lazy_static::lazy_static;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The environment variable to set the fixturator seed with
+pub const SEED_ENV_VAR: &str = "FIXT_SEED";
+
+lazy_static! {
+    static ref SEED: u64 = {
+        let seed = std::env::var(SEED_ENV_VAR)
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or_else(rand::random);
+        seed
+    };
+}
+
+/// How many threads have started a generator so far
+static THREAD_COUNT: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static RNG: Rc<RefCell<StdRng>> = {
+        let thread_index = THREAD_COUNT.fetch_add(1, Ordering::Relaxed);
+        eprintln!(
+            "fixt seed: {}={} thread index: {} ({:?})",
+            SEED_ENV_VAR,
+            *SEED,
+            thread_index,
+            std::thread::current().name()
+        );
+        Rc::new(RefCell::new(StdRng::seed_from_u64(*SEED ^ thread_index)))
+    };
+}
+
+/// The global seed that each thread's generator is seeded from
+pub fn seed() -> u64 {
+    *SEED
+}
+
+/// Restart this thread's generator from `seed`.
+/// Fixtures generated after this are the same for the same seed.
+pub fn reseed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+/// A handle to this thread's seeded generator.
+/// Use this instead of `rand::thread_rng()` in Unpredictable curves.
+pub fn rng() -> FixtRng {
+    RNG.with(|rng| FixtRng(rng.clone()))
+}
+
+/// A handle to the seeded generator of the current thread
+#[derive(Clone)]
+pub struct FixtRng(Rc<RefCell<StdRng>>);
+
+impl RngCore for FixtRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.borrow_mut().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.borrow_mut().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.borrow_mut().fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.borrow_mut().try_fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn same_seed_same_fixtures() {
+        let run = || {
+            (
+                U32Fixturator::new(Unpredictable)
+                    .take(10)
+                    .collect::<Vec<_>>(),
+                StringFixturator::new(Unpredictable)
+                    .take(10)
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        reseed(42);
+        let first = run();
+        reseed(42);
+        assert_eq!(first, run());
+
+        reseed(43);
+        assert_ne!(first, run());
+    }
+
+    #[test]
+    fn threads_generate_different_fixtures() {
+        let run = || {
+            U32Fixturator::new(Unpredictable)
+                .take(10)
+                .collect::<Vec<_>>()
+        };
+        let first = std::thread::spawn(run).join().unwrap();
+        let second = std::thread::spawn(run).join().unwrap();
+        assert_ne!(first, second);
+    }
+}
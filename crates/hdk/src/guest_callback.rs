@@ -2,6 +2,7 @@ pub mod entry_defs;
 pub mod init;
 pub mod migrate_agent;
 pub mod post_commit;
+pub mod resolve_conflict;
 pub mod validate;
 pub mod validate_link_add;
 pub mod validation_package;
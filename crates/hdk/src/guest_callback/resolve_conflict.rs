@@ -0,0 +1 @@
+//! @todo figure out if/how we want to represent the resolve_conflict callback as a macro
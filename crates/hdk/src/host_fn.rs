@@ -1,6 +1,7 @@
 pub mod agent_info;
 pub mod call;
 pub mod call_remote;
+pub mod close_chain;
 pub mod create;
 pub mod create_link;
 pub mod debug;
@@ -9,22 +10,30 @@ pub mod delete;
 pub mod delete_link;
 pub mod emit_signal;
 pub mod encrypt;
+pub mod entry_def_for;
 pub mod entry_type_properties;
 pub mod get;
 pub mod get_details;
+pub mod get_details_batch;
+pub mod get_latest;
 pub mod get_link_details;
 pub mod get_links;
+pub mod get_many;
+pub mod get_peers;
+pub mod get_raw;
 pub mod hash_entry;
 pub mod keystore;
 pub mod property;
 pub mod query;
 pub mod random_bytes;
 pub mod schedule;
+pub mod set_discoverable;
 pub mod show_env;
 pub mod sign;
 pub mod sys_time;
 pub mod unreachable;
 pub mod update;
+pub mod verify_element;
 pub mod zome_info;
 
 /// Simple wrapper around the holochain_wasmer_guest host_call! macro.
@@ -6,6 +6,8 @@
 /// - zome: The zome to call the remote function in. Use zome_info!() to get the current zome info.
 /// - fn_name: The name of the function in the zome to call.
 /// - request: The payload to send to the remote function; receiver needs to deserialize cleanly.
+/// - nonce: Optional. A fresh CapNonce so the call can't be replayed; the remote rejects any
+///   nonce it has recently seen from this agent. If omitted the host sends a random one.
 ///
 /// Response is ZomeCallResponse which can either return ZomeCallResponse::Ok or
 /// ZomeCallResponse::Unauthorized if the provided cap grant is invalid. The Unauthorized case
@@ -37,4 +39,14 @@ macro_rules! call_remote {
             $crate::prelude::CallRemoteOutput
         )
     }};
+    ( $agent:expr, $zome:expr, $fn_name:expr, $cap:expr, $request:expr, $nonce:expr ) => {{
+        $crate::host_fn!(
+            __call_remote,
+            $crate::prelude::CallRemoteInput::new(
+                $crate::prelude::CallRemote::new($agent, $zome, $fn_name, $cap, $request)
+                    .with_nonce($nonce)
+            ),
+            $crate::prelude::CallRemoteOutput
+        )
+    }};
 }
@@ -0,0 +1,24 @@
+/// Close the agent's source chain in favour of a new chain on another DNA.
+///
+/// Commits a `CloseChain` header naming the DNA hash of the new chain.
+/// The new chain then starts with an `OpenChain` header pointing back at this DNA, so the
+/// migration path can be followed in either direction.
+///
+/// Once the chain is closed nothing else can be committed to it, every later create, update,
+/// delete or link will fail.
+///
+/// ```ignore
+/// let close_header_hash = close_chain!(new_dna_hash)?;
+/// ```
+#[macro_export]
+macro_rules! close_chain {
+    ( $new_dna_hash:expr ) => {{
+        $crate::prelude::host_externs!(__close_chain);
+
+        $crate::host_fn!(
+            __close_chain,
+            $crate::prelude::CloseChainInput::new($new_dna_hash),
+            $crate::prelude::CloseChainOutput
+        )
+    }};
+}
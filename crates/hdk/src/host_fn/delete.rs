@@ -4,6 +4,8 @@
 /// @todo implement delete_cap_claim
 ///
 /// The host builds a `Delete` header for the passed entry and commits a new element to the chain.
+/// Only a live element that created an entry and was authored by this agent can be deleted,
+/// anything else is refused with an error instead of authoring a delete that fails validation.
 ///
 /// Usually you don't need to use this macro directly but it is the most general way to update an
 /// entry and standardises the internals of higher level create macros.
@@ -0,0 +1,25 @@
+/// Gets the entry def this dna declares for an entry type.
+///
+/// Useful for generic handling of entries from `get!` or `query!` where the app type isn't
+/// known ahead of time, e.g. to check the `required_validations` or visibility of an entry.
+/// Returns `None` for system entry types such as agent keys and cap grants.
+///
+/// ```ignore
+/// if let Some(element) = get!(hash)? {
+///     if let Some(entry_type) = element.header().entry_type() {
+///         if let Some(entry_def) = entry_def_for!(entry_type.clone())? {
+///             debug!("{:?}", entry_def.required_validations)?;
+///         }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! entry_def_for {
+    ( $entry_type:expr ) => {{
+        $crate::host_fn!(
+            __entry_def_for,
+            $crate::prelude::EntryDefForInput::new($entry_type),
+            $crate::prelude::EntryDefForOutput
+        )
+    }};
+}
@@ -12,9 +12,12 @@
 ///       @todo implement a 'get optimistic' that returns based on the current opinion of the world
 ///       and performs network calls in the background so they are available 'next time'.
 ///
-/// Note: Deletes are considered in the liveness but Updates are not currently followed
-///       automatically due to the need for the happ to disambiguate update logic.
-///       @todo implement 'redirect' logic so that updates are followed by `get!`.
+/// Note: Deletes are considered in the liveness but Updates are not followed by default
+///       due to the need for the happ to disambiguate update logic.
+///       Pass `GetOptions::latest()` to follow updates to the newest live update instead,
+///       e.g. `get!(hash, GetOptions::latest())`.
+///       Where an entry has several live updates the one with the latest timestamp then header
+///       hash is followed so all agents resolve the same element.
 ///
 /// Note: Updates typically point to a different entry hash than what they are updating but not
 ///       always, e.g. consider changing `foo` to `bar` back to `foo`. The entry hashes in a crud
@@ -53,6 +56,6 @@ macro_rules! get {
         )
     }};
     ( $input:expr ) => {
-        get!($input, $crate::prelude::GetOptions)
+        get!($input, $crate::prelude::GetOptions::default())
     };
 }
@@ -42,16 +42,32 @@
 ///       e.g. the DNA itself, links, migrations, etc.
 ///       However the element will still be returned by get_details! if a header hash is passed,
 ///       these header-only elements will have None as the entry value.
+///
+/// Passing `true` as a third argument also returns the signature of every header in the details
+/// for an entry hash, as `signed_headers`, so the zome can check who authored them itself.
+/// The element in the details for a header hash always carries its signature.
+///
+/// ```ignore
+/// if let Some(Details::Entry(details)) = get_details!(entry_hash, GetOptions::default(), true)? {
+///     for signed_header in details.signed_headers {
+///         let element = Element::new(signed_header, Some(details.entry.clone()));
+///         if !verify_element!(element)?.is_valid() { ... }
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! get_details {
-    ( $hash:expr, $options:expr ) => {{
+    ( $hash:expr, $options:expr, $include_signatures:expr ) => {{
         $crate::host_fn!(
             __get_details,
-            $crate::prelude::GetDetailsInput::new(($hash.into(), $options)),
+            $crate::prelude::GetDetailsInput::new(($hash.into(), $options, $include_signatures)),
             $crate::prelude::GetDetailsOutput
         )
     }};
+    ( $hash:expr, $options:expr ) => {
+        get_details!($hash, $options, false)
+    };
     ( $hash:expr ) => {
-        get_details!($hash, $crate::prelude::GetOptions)
+        get_details!($hash, $crate::prelude::GetOptions::default())
     };
 }
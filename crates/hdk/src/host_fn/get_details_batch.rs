@@ -0,0 +1,36 @@
+/// Gets the details of many entry or header hashes in one call.
+///
+/// Each hash gets the same details as `get_details!` would return for it, so an entry hash gives
+/// `Details::Entry` and a header hash gives `Details::Element`. The outputs line up with the
+/// hashes passed in and a hash with no details has a `GetDetailsOutput` of `None`.
+///
+/// The host asks the network for every hash at once, which is much faster than calling
+/// `get_details!` in a loop.
+///
+/// ```ignore
+/// let details = get_details_batch!(vec![entry_hash.into(), header_hash.into()])?;
+/// for (hash, output) in hashes.iter().zip(details) {
+///     if let Some(Details::Entry(entry_details)) = output.into_inner() {
+///         render(hash, entry_details);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! get_details_batch {
+    ( $hashes:expr, $options:expr ) => {{
+        let options = $options;
+        $crate::host_fn!(
+            __get_details_batch,
+            $crate::prelude::GetDetailsBatchInput::new(
+                $hashes
+                    .into_iter()
+                    .map(|hash| (hash.into(), options.clone()))
+                    .collect()
+            ),
+            $crate::prelude::GetDetailsBatchOutput
+        )
+    }};
+    ( $hashes:expr ) => {
+        get_details_batch!($hashes, $crate::prelude::GetOptions::default())
+    };
+}
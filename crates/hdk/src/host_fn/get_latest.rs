@@ -0,0 +1,26 @@
+/// Follows the updates of an element to its latest live update, like `get!` with
+/// `GetOptions::latest()`, and reports the branch of updates that was followed.
+///
+/// `branch` holds the header hash of each update followed, oldest first, so an app can see
+/// which update won wherever several live updates diverged from the same entry.
+/// The branch is empty if the element has no live updates.
+/// Returns `None` if there is no live element for the hash.
+///
+/// ```ignore
+/// if let Some(latest) = get_latest!(entry_hash)? {
+///     render(latest.element, latest.branch.len());
+/// }
+/// ```
+#[macro_export]
+macro_rules! get_latest {
+    ( $hash:expr, $options:expr ) => {{
+        $crate::host_fn!(
+            __get_latest,
+            $crate::prelude::GetLatestInput::new(($hash.into(), $options)),
+            $crate::prelude::GetLatestOutput
+        )
+    }};
+    ( $hash:expr ) => {
+        get_latest!($hash, $crate::prelude::GetOptions::default())
+    };
+}
@@ -0,0 +1,35 @@
+/// Gets the live elements for many entry or header hashes in one call.
+///
+/// Each hash is found the same way as `get!` so the same notes about liveness apply, and
+/// `GetOptions::latest()` follows updates for every hash.
+///
+/// The result lines up with the hashes passed in but each distinct element is only returned once,
+/// e.g. when several hashes point at the same updated entry. Use `IndexedElements::get` with the
+/// position of a hash to find its element, `None` means there is no live element for that hash.
+///
+/// This is for rendering a feed of possibly updated entries without a `get!` per entry.
+///
+/// ```ignore
+/// let found = get_many!(hashes.clone(), GetOptions::latest())?;
+/// for (i, hash) in hashes.iter().enumerate() {
+///     if let Some(element) = found.get(i) {
+///         render(hash, element);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! get_many {
+    ( $hashes:expr, $options:expr ) => {{
+        $crate::host_fn!(
+            __get_many,
+            $crate::prelude::GetManyInput::new((
+                $hashes.into_iter().map(|hash| hash.into()).collect(),
+                $options
+            )),
+            $crate::prelude::GetManyOutput
+        )
+    }};
+    ( $hashes:expr ) => {
+        get_many!($hashes, $crate::prelude::GetOptions::default())
+    };
+}
@@ -0,0 +1,25 @@
+/// Lists the other agents on this DNA that have opted in to being discoverable,
+/// most recently seen first.
+///
+/// Each `PeerInfo` has the time the network last saw the peer join or take part in gossip,
+/// which is enough to build simple presence features such as a "who's online" list.
+/// Agents only appear here after calling `set_discoverable!(true)`.
+///
+/// ```ignore
+/// let online: Vec<AgentPubKey> = get_peers!()?
+///     .0
+///     .into_iter()
+///     .filter(|peer| peer.last_seen >= five_minutes_ago)
+///     .map(|peer| peer.agent_pub_key)
+///     .collect();
+/// ```
+#[macro_export]
+macro_rules! get_peers {
+    () => {{
+        $crate::host_fn!(
+            __get_peers,
+            $crate::prelude::GetPeersInput::new(()),
+            $crate::prelude::GetPeersOutput
+        )
+    }};
+}
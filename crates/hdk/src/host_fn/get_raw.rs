@@ -0,0 +1,27 @@
+/// Gets the stored bytes and entry type of an entry for a given entry or header hash.
+///
+/// Unlike `get!` the entry is never deserialized into an app type, app entries come back as
+/// the exact bytes that were committed. This is for generic tooling such as bridges and
+/// proxies that need to forward entries without knowing their schema.
+///
+/// The element is found the same way as `get!` so the same notes about liveness apply.
+/// Returns `None` if there is no live element or the element has no entry.
+///
+/// ```ignore
+/// if let Some(raw) = get_raw!(entry_hash)? {
+///     forward(raw.entry_type, raw.bytes);
+/// }
+/// ```
+#[macro_export]
+macro_rules! get_raw {
+    ( $hash:expr, $options:expr ) => {{
+        $crate::host_fn!(
+            __get_raw,
+            $crate::prelude::GetRawInput::new(($hash.into(), $options)),
+            $crate::prelude::GetRawOutput
+        )
+    }};
+    ( $hash:expr ) => {
+        get_raw!($hash, $crate::prelude::GetOptions::default())
+    };
+}
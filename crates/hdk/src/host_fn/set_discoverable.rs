@@ -0,0 +1,17 @@
+/// Opts the current agent in or out of showing up in other agents' `get_peers!`.
+///
+/// Agents are not discoverable until they opt in.
+///
+/// ```ignore
+/// set_discoverable!(true)?;
+/// ```
+#[macro_export]
+macro_rules! set_discoverable {
+    ( $discoverable:expr ) => {{
+        $crate::host_fn!(
+            __set_discoverable,
+            $crate::prelude::SetDiscoverableInput::new($discoverable),
+            $crate::prelude::SetDiscoverableOutput
+        )
+    }};
+}
@@ -0,0 +1,25 @@
+/// Checks an element is internally consistent without touching the DHT or the source chain.
+///
+/// The header hash must be the hash of the header, the signature must have been made by the
+/// header's author and any included entry must be the one the header refers to.
+/// The returned `ElementVerification` says which of these checks passed, so apps can refuse
+/// elements received out of band, e.g. from a light client or in a zome call payload, before
+/// trusting them.
+///
+/// This says nothing about whether the element is valid for the app or has been published.
+///
+/// ```ignore
+/// if !verify_element!(element.clone())?.is_valid() {
+///     return Err(HdkError::Wasm(WasmError::Zome("tampered element".into())));
+/// }
+/// ```
+#[macro_export]
+macro_rules! verify_element {
+    ( $element:expr ) => {{
+        $crate::host_fn!(
+            __verify_element,
+            $crate::prelude::VerifyElementInput::new($element),
+            $crate::prelude::VerifyElementOutput
+        )
+    }};
+}
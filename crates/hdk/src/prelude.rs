@@ -1,5 +1,6 @@
 pub use crate::agent_info;
 pub use crate::call_remote;
+pub use crate::close_chain;
 pub use crate::create;
 pub use crate::create_cap_claim;
 pub use crate::create_cap_grant;
@@ -11,13 +12,19 @@ pub use crate::delete_cap_grant;
 pub use crate::delete_entry;
 pub use crate::delete_link;
 pub use crate::entry_def;
+pub use crate::entry_def_for;
 pub use crate::entry_defs;
 pub use crate::error::HdkError;
 pub use crate::generate_cap_secret;
 pub use crate::get;
 pub use crate::get_details;
+pub use crate::get_details_batch;
+pub use crate::get_latest;
 pub use crate::get_link_details;
 pub use crate::get_links;
+pub use crate::get_many;
+pub use crate::get_peers;
+pub use crate::get_raw;
 pub use crate::hash_entry;
 pub use crate::hash_path::anchor::anchor;
 pub use crate::hash_path::anchor::get_anchor;
@@ -30,26 +37,33 @@ pub use crate::map_extern;
 pub use crate::map_extern::ExternResult;
 pub use crate::query;
 pub use crate::random_bytes;
+pub use crate::set_discoverable;
 pub use crate::sys_time;
 pub use crate::update;
 pub use crate::update_cap_grant;
 pub use crate::update_entry;
+pub use crate::verify_element;
 pub use crate::zome_info;
 pub use hdk3_derive::hdk_entry;
 pub use hdk3_derive::hdk_extern;
 pub use holo_hash::AgentPubKey;
 pub use holo_hash::AnyDhtHash;
+pub use holo_hash::DnaHash;
 pub use holo_hash::EntryHash;
 pub use holo_hash::EntryHashes;
 pub use holo_hash::HasHash;
 pub use holo_hash::HeaderHash;
 pub use holochain_wasmer_guest::*;
 pub use holochain_zome_types::agent_info::AgentInfo;
+pub use holochain_zome_types::agent_info::PeerInfo;
+pub use holochain_zome_types::agent_info::PeerInfoVec;
 pub use holochain_zome_types::call_remote::CallRemote;
 pub use holochain_zome_types::capability::*;
 pub use holochain_zome_types::crdt::CrdtType;
 pub use holochain_zome_types::debug_msg;
-pub use holochain_zome_types::element::{Element, ElementVec};
+pub use holochain_zome_types::element::{
+    Element, ElementVec, ElementVerification, IndexedElements, LatestElement,
+};
 pub use holochain_zome_types::entry::*;
 pub use holochain_zome_types::entry_def::*;
 pub use holochain_zome_types::header::*;
@@ -62,6 +76,8 @@ pub use holochain_zome_types::migrate_agent::MigrateAgent;
 pub use holochain_zome_types::migrate_agent::MigrateAgentCallbackResult;
 pub use holochain_zome_types::post_commit::PostCommitCallbackResult;
 pub use holochain_zome_types::query::ChainQueryFilter as QueryFilter;
+pub use holochain_zome_types::resolve_conflict::ResolveConflict;
+pub use holochain_zome_types::resolve_conflict::ResolveConflictCallbackResult;
 pub use holochain_zome_types::validate::ValidateCallbackResult;
 pub use holochain_zome_types::validate::ValidationPackage;
 pub use holochain_zome_types::validate::ValidationPackageCallbackResult;
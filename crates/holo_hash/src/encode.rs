@@ -44,7 +44,7 @@ pub fn holo_hash_encode(prefix: &[u8], data: &[u8]) -> String {
 
 /// internal PARSE for holo hash REPR
 pub fn holo_hash_decode(prefix: &[u8], s: &str) -> Result<Vec<u8>, HoloHashError> {
-    if &s[..1] != "u" {
+    if !s.starts_with('u') {
         return Err(HoloHashError::NoU);
     }
     let s = match base64::decode_config(&s[1..], base64::URL_SAFE_NO_PAD) {
@@ -33,7 +33,7 @@ fixturator!(
     ThirtySixBytes,
     append_location([0; 32].to_vec()),
     {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         let mut u8_fixturator = U8Fixturator::new(Unpredictable);
         let mut bytes = vec![];
         for _ in 0..32 {
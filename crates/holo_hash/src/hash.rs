@@ -58,6 +58,13 @@ impl<T: HashType> HoloHash<T> {
     pub fn into_inner(self) -> Vec<u8> {
         self.hash
     }
+
+    /// Whether this hash has the 36 bytes it should.
+    /// Hashes are only length checked on construction,
+    /// so one deserialized from untrusted bytes might not.
+    pub fn has_valid_length(&self) -> bool {
+        self.hash.len() == HASH_SERIALIZED_LEN
+    }
 }
 
 impl<P: PrimitiveHashType> HoloHash<P> {
@@ -156,4 +163,30 @@ mod tests {
     fn test_fails_with_bad_size() {
         DnaHash::from_raw_bytes(vec![0xdb; 35]);
     }
+
+    #[test]
+    fn any_dht_from_prefix() {
+        use crate::hash_type::{self, AnyDht};
+
+        let raw = |prefix: &[u8]| [prefix, &[0xdb; 36][..]].concat();
+
+        assert_eq!(
+            Some(AnyDht::Entry),
+            AnyDht::from_prefix(&raw(hash_type::Entry::new().get_prefix()))
+        );
+        assert_eq!(
+            Some(AnyDht::Header),
+            AnyDht::from_prefix(&raw(hash_type::Header::new().get_prefix()))
+        );
+        // Just the prefix is enough
+        assert_eq!(
+            Some(AnyDht::Header),
+            AnyDht::from_prefix(hash_type::Header::new().get_prefix())
+        );
+        assert_eq!(
+            None,
+            AnyDht::from_prefix(&raw(hash_type::Agent::new().get_prefix()))
+        );
+        assert_eq!(None, AnyDht::from_prefix(&[0x84, 0x21]));
+    }
 }
@@ -30,11 +30,19 @@ impl HashType for AnyDht {
 
 impl HashTypeAsync for AnyDht {}
 
-// FIXME: REMOVE [ B-02112 ]
-impl Default for AnyDht {
-    fn default() -> Self {
-        // HACK: SO WRONG
-        AnyDht::Header
+impl AnyDht {
+    /// The variant a hash is from its 3 byte prefix,
+    /// or None if it isn't an entry or header hash.
+    /// Any bytes after the prefix, like the rest of the hash, are ignored.
+    pub fn from_prefix(prefix: &[u8]) -> Option<AnyDht> {
+        let prefix = prefix.get(..3)?;
+        if prefix == Entry::new().get_prefix() {
+            Some(AnyDht::Entry)
+        } else if prefix == Header::new().get_prefix() {
+            Some(AnyDht::Header)
+        } else {
+            None
+        }
     }
 }
 
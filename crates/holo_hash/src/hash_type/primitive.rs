@@ -90,7 +90,11 @@ macro_rules! primitive_hash_type {
             {
                 match v {
                     $prefix => Ok($name),
-                    _ => panic!("unknown hash prefix during hash deserialization {:?}", v),
+                    _ => Err(E::custom(format!(
+                        "expected a {} prefix but found {:?}",
+                        stringify!($display),
+                        v
+                    ))),
                 }
             }
 
@@ -86,6 +86,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_primitive_hashtype_crossover_error() {
+        let h_orig = DnaHash::from_raw_bytes(vec![0xdb; 36]);
+        let buf = holochain_serialized_bytes::encode(&h_orig).unwrap();
+        let h: Result<EntryHash, _> = holochain_serialized_bytes::decode(&buf);
+        assert!(h.is_err());
+    }
+
     #[test]
     #[should_panic]
     fn test_composite_hashtype_crossover_error_1() {
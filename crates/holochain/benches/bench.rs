@@ -1,14 +1,27 @@
 use ::fixt::prelude::*;
 use criterion::BenchmarkId;
 use criterion::Throughput;
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use hdk3::prelude::*;
 use holo_hash::fixt::AgentPubKeyFixturator;
+use holo_hash::fixt::DhtOpHashFixturator;
+use holo_hash::fixt::HeaderHashFixturator;
+use holochain::core::ribosome::HostAccess;
 use holochain::core::ribosome::RibosomeT;
 use holochain::core::ribosome::ZomeCallInvocation;
+use holochain::core::state::validation_db::{
+    ValidationLimboStatus, ValidationLimboStore, ValidationLimboValue,
+};
+use holochain::core::workflow::sys_validation_workflow::types::PendingDependencies;
+use holochain_state::buffer::BufferedStore;
+use holochain_state::env::ReaderPool;
+use holochain_state::prelude::{KvStoreT, WriteManager};
+use holochain_types::dht_op::DhtOpLight;
 use holochain_types::fixt::CapSecretFixturator;
+use holochain_types::Timestamp;
 use holochain_wasm_test_utils::TestWasm;
 use holochain_zome_types::ExternInput;
+use std::time::Duration;
 
 pub fn wasm_call_n(c: &mut Criterion) {
     let mut group = c.benchmark_group("wasm_call_n");
@@ -66,6 +79,103 @@ pub fn wasm_call_n(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, wasm_call_n,);
+/// Read every op in the validation limbo, as a validation pass does,
+/// comparing a fresh read transaction per op against a pooled reader.
+pub fn validation_limbo_reads(c: &mut Criterion) {
+    const NUM_OPS: usize = 1_000;
+    let mut group = c.benchmark_group("validation_limbo_reads");
+    group.throughput(Throughput::Elements(NUM_OPS as _));
+
+    let test_env = holochain_state::test_utils::test_cell_env();
+    let env = test_env.env();
+
+    // Fill the limbo
+    let hashes: Vec<_> = DhtOpHashFixturator::new(Unpredictable)
+        .take(NUM_OPS)
+        .collect();
+    let mut limbo = ValidationLimboStore::new(env.clone().into()).unwrap();
+    for (hash, header_hash) in hashes.iter().zip(HeaderHashFixturator::new(Unpredictable)) {
+        let vlv = ValidationLimboValue {
+            status: ValidationLimboStatus::Pending,
+            pending_dependencies: PendingDependencies::new(),
+            op: DhtOpLight::RegisterAgentActivity(header_hash.clone(), header_hash.clone().into()),
+            basis: header_hash.into(),
+            time_added: Timestamp::now(),
+            last_try: None,
+            num_tries: 0,
+            num_app_validations: 0,
+        };
+        limbo.put(hash.clone(), vlv).unwrap();
+    }
+    env.guard()
+        .with_commit(|writer| limbo.0.flush_to_txn(writer))
+        .unwrap();
+    let limbo = ValidationLimboStore::new(env.clone().into()).unwrap();
+
+    // A single pass needs one pooled transaction instead of one per op
+    {
+        let g = env.guard();
+        let mut pool = ReaderPool::new(&g).with_max_age(Duration::from_secs(60));
+        for hash in &hashes {
+            limbo.store().get(pool.reader().unwrap(), hash).unwrap();
+        }
+        assert_eq!(pool.transactions_opened(), 1);
+    }
+
+    group.bench_function("fresh_reader", |b| {
+        b.iter(|| {
+            for hash in &hashes {
+                limbo.get(hash).unwrap();
+            }
+        });
+    });
+
+    group.bench_function("reader_pool", |b| {
+        b.iter(|| {
+            let g = env.guard();
+            let mut pool = ReaderPool::new(&g);
+            for hash in &hashes {
+                limbo.store().get(pool.reader().unwrap(), hash).unwrap();
+            }
+        });
+    });
+
+    group.finish();
+}
+
+/// Get the network from the host access and clone it,
+/// as the network host functions do on every call.
+pub fn host_access_network(c: &mut Criterion) {
+    let mut group = c.benchmark_group("host_access_network");
+
+    tokio::runtime::Builder::new()
+        .threaded_scheduler()
+        .build()
+        .unwrap()
+        .enter(|| {
+            let host_access: HostAccess =
+                holochain::fixt::ZomeCallHostAccessFixturator::new(Unpredictable)
+                    .next()
+                    .unwrap()
+                    .into();
+
+            group.bench_function("borrow", |b| {
+                b.iter(|| black_box(host_access.network()));
+            });
+
+            group.bench_function("clone", |b| {
+                b.iter(|| black_box(host_access.network().clone()));
+            });
+        });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    wasm_call_n,
+    validation_limbo_reads,
+    host_access_network
+);
 
 criterion_main!(benches);
@@ -50,12 +50,6 @@ struct Opt {
 }
 
 fn main() {
-    holochain::conductor::tokio_runtime()
-        // the async_main function should only end if our program is done
-        .block_on(async_main())
-}
-
-async fn async_main() {
     // Sets up a human-readable panic message with a request for bug reports
     //
     // See https://docs.rs/human-panic/1.0.3/human_panic/
@@ -65,10 +59,24 @@ async fn async_main() {
     observability::init_fmt(opt.structured).expect("Failed to start contextual logging");
     debug!("observability initialized");
 
-    let conductor = if let Some(legacy_config_path) = opt.legacy_tryorama_config_path {
-        conductor_handle_from_legacy_config_path(&legacy_config_path).await
-    } else {
-        conductor_handle_from_config_path(opt.config_path.clone(), opt.interactive).await
+    // The runtime is sized from the config, so it is loaded first.
+    // Legacy configs don't set the runtime's bounds so they get the defaults.
+    let config = match opt.legacy_tryorama_config_path {
+        Some(_) => None,
+        None => Some(config_from_path(opt.config_path.clone(), opt.interactive)),
+    };
+    holochain::conductor::tokio_runtime_for_config(config.as_ref().unwrap_or(&Default::default()))
+        // the async_main function should only end if our program is done
+        .block_on(async_main(opt, config))
+}
+
+async fn async_main(opt: Opt, config: Option<ConductorConfig>) {
+    let conductor = match (opt.legacy_tryorama_config_path, config) {
+        (Some(legacy_config_path), _) => {
+            conductor_handle_from_legacy_config_path(&legacy_config_path).await
+        }
+        (None, Some(config)) => conductor_handle_from_config(config).await,
+        (None, None) => unreachable!("config is loaded when there is no legacy config"),
     };
 
     info!("Conductor successfully initialized.");
@@ -104,10 +112,7 @@ async fn conductor_handle_from_legacy_config_path(legacy_config_path: &Path) ->
         .expect("Couldn't initialize conductor from legacy config")
 }
 
-async fn conductor_handle_from_config_path(
-    config_path: Option<PathBuf>,
-    interactive: bool,
-) -> ConductorHandle {
+fn config_from_path(config_path: Option<PathBuf>, interactive: bool) -> ConductorConfig {
     let config_path_default = config_path.is_none();
     let config_path: ConfigFilePath = config_path.map(Into::into).unwrap_or_default();
     debug!("config_path: {}", config_path);
@@ -138,7 +143,10 @@ async fn conductor_handle_from_config_path(
             }
         }
     }
+    config
+}
 
+async fn conductor_handle_from_config(config: ConductorConfig) -> ConductorHandle {
     // Initialize the Conductor
     Conductor::builder()
         .config(config)
@@ -37,8 +37,23 @@ pub use cell::{error::CellError, Cell};
 pub use conductor::{Conductor, ConductorBuilder, ConductorStateDb};
 pub use handle::ConductorHandle;
 
+/// Threads kept in the blocking pool on top of those host functions may take,
+/// for other blocking work like database access
+const RESERVED_BLOCKING_THREADS: usize = 32;
+
 /// setup a tokio runtime that meets the conductor's needs
 pub fn tokio_runtime() -> tokio::runtime::Runtime {
+    tokio_runtime_for_config(&config::ConductorConfig::default())
+}
+
+/// setup a tokio runtime that meets the needs of a conductor with this config.
+/// The blocking pool is sized so that host functions blocked at the
+/// config's `max_blocking_host_fns` bound never exhaust it.
+pub fn tokio_runtime_for_config(config: &config::ConductorConfig) -> tokio::runtime::Runtime {
+    let core_threads = num_cpus::get();
+    let max_blocking_host_fns = config
+        .max_blocking_host_fns
+        .unwrap_or(crate::core::ribosome::host_fn_executor::DEFAULT_MAX_BLOCKING_HOST_FNS);
     tokio::runtime::Builder::new()
         // we use both IO and Time tokio utilities
         .enable_all()
@@ -46,7 +61,9 @@ pub fn tokio_runtime() -> tokio::runtime::Runtime {
         .threaded_scheduler()
         // we want to use thread count matching cpu count
         // (sometimes tokio by default only uses half cpu core threads)
-        .core_threads(num_cpus::get())
+        .core_threads(core_threads)
+        // room for every host function the executor lets block at once
+        .max_threads(core_threads + max_blocking_host_fns + RESERVED_BLOCKING_THREADS)
         // give our threads a descriptive name (they'll be numbered too)
         .thread_name("holochain-tokio-thread")
         // build the runtime
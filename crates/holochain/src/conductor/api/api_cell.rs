@@ -2,7 +2,9 @@
 
 use super::error::{ConductorApiError, ConductorApiResult};
 use crate::conductor::{entry_def_store::EntryDefBufferKey, ConductorHandle};
-use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::ribosome::{
+    host_fn_executor::HostFnExecutor, module_cache::ModuleCache, ZomeCallInvocation,
+};
 use crate::core::workflow::ZomeCallInvocationResult;
 use async_trait::async_trait;
 use holo_hash::DnaHash;
@@ -80,6 +82,18 @@ impl CellConductorApiT for CellConductorApi {
     async fn get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef> {
         self.conductor_handle.get_entry_def(key).await
     }
+
+    fn module_cache(&self) -> ModuleCache {
+        self.conductor_handle.module_cache()
+    }
+
+    fn host_fn_executor(&self) -> HostFnExecutor {
+        self.conductor_handle.host_fn_executor()
+    }
+
+    fn parallel_callbacks(&self) -> Option<usize> {
+        self.conductor_handle.parallel_callbacks()
+    }
 }
 
 /// The "internal" Conductor API interface, for a Cell to talk to its calling Conductor.
@@ -115,4 +129,13 @@ pub trait CellConductorApiT: Clone + Send + Sync + Sized {
 
     /// Get a [EntryDef] from the [EntryDefBuf]
     async fn get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
+
+    /// The compiled module cache shared by this conductor's ribosomes
+    fn module_cache(&self) -> ModuleCache;
+
+    /// The bound on host functions blocked at once across this conductor
+    fn host_fn_executor(&self) -> HostFnExecutor;
+
+    /// The most zomes an order-independent callback is called on at once
+    fn parallel_callbacks(&self) -> Option<usize>;
 }
@@ -1,10 +1,14 @@
 use crate::conductor::interface::error::InterfaceResult;
+use crate::core::ribosome::cancel::ZomeCallCancel;
+use crate::core::signal::SharedSignalFilter;
 use holochain_serialized_bytes::prelude::*;
 
 mod admin_interface;
 mod app_interface;
+mod payload_encoding;
 pub use admin_interface::*;
 pub use app_interface::*;
+pub use payload_encoding::*;
 
 /// A trait that unifies both the admin and app interfaces
 #[async_trait::async_trait]
@@ -18,4 +22,11 @@ pub trait InterfaceApi: 'static + Send + Sync + Clone {
         &self,
         request: Result<Self::ApiRequest, SerializedBytesError>,
     ) -> InterfaceResult<Self::ApiResponse>;
+
+    /// This api for a single interface connection.
+    /// Zome calls made through it are cancelled with `cancel`
+    /// and signal subscriptions replace `signal_filter`.
+    fn for_connection(&self, _cancel: ZomeCallCancel, _signal_filter: SharedSignalFilter) -> Self {
+        self.clone()
+    }
 }
@@ -179,6 +179,12 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let state = self.conductor_handle.dump_cell_state(&cell_id).await?;
                 Ok(AdminResponse::JsonState(state))
             }
+            ForceIntegrate { cell_id, op_hash } => {
+                self.conductor_handle
+                    .force_integrate(&cell_id, op_hash)
+                    .await?;
+                Ok(AdminResponse::OpForceIntegrated)
+            }
         }
     }
 }
@@ -261,6 +267,14 @@ pub enum AdminRequest {
         /// The CellId for which to dump state
         cell_id: Box<CellId>,
     },
+    /// Push a validated op that is stuck waiting on its dependencies
+    /// through integration. For recovering a Cell.
+    ForceIntegrate {
+        /// The Cell holding the op
+        cell_id: Box<CellId>,
+        /// The op to integrate
+        op_hash: DhtOpHash,
+    },
 }
 
 /// Responses to messages received on an Admin interface
@@ -293,6 +307,8 @@ pub enum AdminResponse {
     AppDeactivated,
     /// State of a cell
     JsonState(String),
+    /// The op was integrated
+    OpForceIntegrated,
 }
 
 #[cfg(test)]
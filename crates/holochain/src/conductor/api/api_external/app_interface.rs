@@ -1,10 +1,11 @@
-use super::InterfaceApi;
+use super::{InterfaceApi, PayloadEncoding};
 use crate::conductor::api::error::{ConductorApiResult, ExternalApiWireError, SerializationError};
 use crate::conductor::{
     interface::error::{InterfaceError, InterfaceResult},
     ConductorHandle,
 };
-use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::ribosome::{cancel::ZomeCallCancel, ZomeCallInvocation};
+use crate::core::signal::{SharedSignalFilter, SignalFilter};
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::app::{AppId, InstalledApp};
 use holochain_zome_types::ExternOutput;
@@ -37,12 +38,43 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 #[derive(Clone)]
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
+    /// Cancels the zome calls made through this api
+    cancel: ZomeCallCancel,
+    /// Which signals the connection this api serves receives
+    signal_filter: SharedSignalFilter,
 }
 
 impl RealAppInterfaceApi {
     /// Create a new instance from a shared Conductor reference
     pub fn new(conductor_handle: ConductorHandle) -> Self {
-        Self { conductor_handle }
+        Self {
+            conductor_handle,
+            cancel: ZomeCallCancel::default(),
+            signal_filter: SharedSignalFilter::default(),
+        }
+    }
+}
+
+impl RealAppInterfaceApi {
+    /// Call a zome with the payload and output transcoded
+    /// between `encoding` and msgpack
+    async fn call_zome(
+        &self,
+        mut request: ZomeCallInvocation,
+        encoding: PayloadEncoding,
+    ) -> ConductorApiResult<AppResponse> {
+        request.payload = encoding.decode_input(request.payload)?;
+        match self
+            .conductor_handle
+            .call_zome_cancellable(request, self.cancel.clone())
+            .await?
+        {
+            Ok(ZomeCallResponse::Ok(output)) => Ok(AppResponse::ZomeCallInvocation(Box::new(
+                encoding.encode_output(output)?,
+            ))),
+            Ok(ZomeCallResponse::Unauthorized) => Ok(AppResponse::ZomeCallUnauthorized),
+            Err(e) => Ok(AppResponse::Error(e.into())),
+        }
     }
 }
 
@@ -58,15 +90,16 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                 self.conductor_handle.get_app_info(&app_id).await?,
             )),
             AppRequest::ZomeCallInvocation(request) => {
-                match self.conductor_handle.call_zome(*request).await? {
-                    Ok(ZomeCallResponse::Ok(output)) => {
-                        Ok(AppResponse::ZomeCallInvocation(Box::new(output)))
-                    }
-                    Ok(ZomeCallResponse::Unauthorized) => Ok(AppResponse::ZomeCallUnauthorized),
-                    Err(e) => Ok(AppResponse::Error(e.into())),
-                }
+                self.call_zome(*request, PayloadEncoding::MsgPack).await
+            }
+            AppRequest::ZomeCallInvocationEncoded { encoding, request } => {
+                self.call_zome(*request, encoding).await
             }
             AppRequest::Crypto(_) => unimplemented!("Crypto methods currently unimplemented"),
+            AppRequest::SubscribeSignals(filter) => {
+                self.signal_filter.set(filter);
+                Ok(AppResponse::SignalsSubscribed)
+            }
         }
     }
 }
@@ -91,6 +124,14 @@ impl InterfaceApi for RealAppInterfaceApi {
             Err(e) => Ok(AppResponse::Error(SerializationError::from(e).into())),
         }
     }
+
+    fn for_connection(&self, cancel: ZomeCallCancel, signal_filter: SharedSignalFilter) -> Self {
+        Self {
+            conductor_handle: self.conductor_handle.clone(),
+            cancel,
+            signal_filter,
+        }
+    }
 }
 
 /// The set of messages that a conductor understands how to handle over an App interface
@@ -108,6 +149,19 @@ pub enum AppRequest {
 
     /// Call a zome function
     ZomeCallInvocation(Box<ZomeCallInvocation>),
+
+    /// Call a zome function with the payload in an encoding other than msgpack.
+    /// The output is returned in the same encoding.
+    ZomeCallInvocationEncoded {
+        /// How the payload and output are encoded
+        encoding: PayloadEncoding,
+        /// The call, with its payload in `encoding`
+        request: Box<ZomeCallInvocation>,
+    },
+
+    /// Only receive the signals matching this filter on this connection.
+    /// Replaces any filter the connection had, the default receives every signal.
+    SubscribeSignals(SignalFilter),
 }
 
 /// Responses to requests received on an App interface
@@ -125,6 +179,9 @@ pub enum AppResponse {
 
     /// The zome call is unauthorized
     ZomeCallUnauthorized,
+
+    /// The connection's signal filter was replaced
+    SignalsSubscribed,
 }
 
 #[allow(missing_docs)]
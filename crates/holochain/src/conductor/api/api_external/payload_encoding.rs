@@ -0,0 +1,155 @@
+//! Encodings a client can use for zome call payloads instead of msgpack
+
+use crate::conductor::api::error::SerializationError;
+use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::{ExternInput, ExternOutput};
+
+/// How the payload of a zome call and the output returned for it
+/// are encoded between a client and the conductor.
+///
+/// Zomes only ever see msgpack, the conductor transcodes
+/// to and from the client's encoding at the interface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadEncoding {
+    /// The canonical encoding, passed through untouched
+    MsgPack,
+    /// UTF-8 JSON
+    Json,
+}
+
+impl Default for PayloadEncoding {
+    fn default() -> Self {
+        PayloadEncoding::MsgPack
+    }
+}
+
+impl PayloadEncoding {
+    /// Transcode a payload from the client into the msgpack a zome expects
+    pub fn decode_input(self, input: ExternInput) -> Result<ExternInput, SerializationError> {
+        match self {
+            PayloadEncoding::MsgPack => Ok(input),
+            PayloadEncoding::Json => {
+                let value: serde_json::Value = serde_json::from_slice(input.inner_ref().bytes())?;
+                Ok(ExternInput::new(to_serialized_bytes(
+                    holochain_serialized_bytes::encode(&value)?,
+                )))
+            }
+        }
+    }
+
+    /// Transcode the msgpack a zome returned into this encoding for the client
+    pub fn encode_output(self, output: ExternOutput) -> Result<ExternOutput, SerializationError> {
+        match self {
+            PayloadEncoding::MsgPack => Ok(output),
+            PayloadEncoding::Json => {
+                // Transcoded directly rather than through a serde_json::Value,
+                // which has no room for msgpack's binary values.
+                // They reach the client as arrays of bytes.
+                let mut json = Vec::new();
+                serde_transcode::transcode(
+                    &mut rmp_serde::Deserializer::new(output.inner_ref().bytes()),
+                    &mut serde_json::Serializer::new(&mut json),
+                )?;
+                Ok(ExternOutput::new(to_serialized_bytes(json)))
+            }
+        }
+    }
+}
+
+fn to_serialized_bytes(bytes: Vec<u8>) -> SerializedBytes {
+    SerializedBytes::from(UnsafeBytes::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::test_utils::fake_header_hash;
+
+    #[derive(serde::Serialize, serde::Deserialize, SerializedBytes, PartialEq, Debug)]
+    struct Greeting {
+        name: String,
+        times: u32,
+    }
+
+    #[test]
+    fn json_payload_is_msgpack_inside() {
+        let input = ExternInput::new(to_serialized_bytes(
+            br#"{"name":"alice","times":3}"#.to_vec(),
+        ));
+
+        // The zome deserializes the transcoded input as usual
+        let zome_input = PayloadEncoding::Json.decode_input(input).unwrap();
+        let greeting = Greeting::try_from(zome_input.into_inner()).unwrap();
+        assert_eq!(
+            Greeting {
+                name: "alice".into(),
+                times: 3,
+            },
+            greeting
+        );
+
+        // The zome's msgpack output reaches the client as JSON
+        let zome_output = ExternOutput::new(
+            Greeting {
+                name: "bob".into(),
+                times: 1,
+            }
+            .try_into()
+            .unwrap(),
+        );
+        let output = PayloadEncoding::Json.encode_output(zome_output).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(output.into_inner().bytes()).unwrap();
+        assert_eq!(serde_json::json!({"name": "bob", "times": 1}), json);
+    }
+
+    #[derive(Clone, serde::Serialize, serde::Deserialize, SerializedBytes, PartialEq, Debug)]
+    struct Link {
+        target: holo_hash::HeaderHash,
+    }
+
+    #[test]
+    fn binary_output_reaches_json_and_back() {
+        let link = Link {
+            target: fake_header_hash(1),
+        };
+        let zome_output = ExternOutput::new(link.clone().try_into().unwrap());
+
+        // The hash is msgpack binary, which reaches the client as an array of bytes
+        let output = PayloadEncoding::Json.encode_output(zome_output).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(output.inner_ref().bytes()).unwrap();
+        assert_eq!(
+            serde_json::json!(link.target.get_full_bytes()),
+            json["target"]["hash"]
+        );
+
+        // The client can send the same JSON back to a zome
+        let input = ExternInput::new(output.into_inner());
+        let zome_input = PayloadEncoding::Json.decode_input(input).unwrap();
+        assert_eq!(link, Link::try_from(zome_input.into_inner()).unwrap());
+    }
+
+    #[test]
+    fn msgpack_is_passed_through() {
+        let sb: SerializedBytes = Greeting {
+            name: "carol".into(),
+            times: 2,
+        }
+        .try_into()
+        .unwrap();
+        let input = ExternInput::new(sb.clone());
+        assert_eq!(
+            ExternInput::new(sb),
+            PayloadEncoding::MsgPack.decode_input(input).unwrap()
+        );
+    }
+
+    #[test]
+    fn bad_json_is_a_serialization_error() {
+        let input = ExternInput::new(to_serialized_bytes(b"{not json".to_vec()));
+        assert!(matches!(
+            PayloadEncoding::Json.decode_input(input),
+            Err(SerializationError::Json(_))
+        ));
+    }
+}
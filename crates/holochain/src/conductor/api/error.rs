@@ -12,9 +12,11 @@ use crate::{
         workflow::error::WorkflowError,
     },
 };
+use holo_hash::HeaderHash;
 use holochain_serialized_bytes::prelude::*;
 use holochain_state::error::DatabaseError;
 use holochain_types::cell::CellId;
+use holochain_zome_types::zome::{FunctionName, ZomeName};
 use thiserror::Error;
 
 /// Errors occurring during a [CellConductorApi] or [InterfaceApi] call
@@ -81,6 +83,14 @@ pub enum ConductorApiError {
 
     #[error(transparent)]
     SourceChainError(#[from] SourceChainError),
+
+    /// The zome call was refused for lack of a capability
+    #[error("The call to {0:?} {1:?} was unauthorized")]
+    ZomeCallUnauthorized(ZomeName, FunctionName),
+
+    /// The ops of a header didn't collect enough validation receipts in time
+    #[error("Timed out waiting for {1} validation receipts on the ops of header {0:?}")]
+    ValidationReceiptsTimeout(HeaderHash, usize),
 }
 
 /// All the serialization errors that can occur
@@ -93,6 +103,10 @@ pub enum SerializationError {
     /// Denotes inability to parse a UUID
     #[error(transparent)]
     Uuid(#[from] uuid::parser::ParseError),
+
+    /// Denotes inability to move into or out of JSON
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
 }
 
 /// Type alias
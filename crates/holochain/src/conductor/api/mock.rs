@@ -3,7 +3,9 @@
 
 use super::CellConductorApiT;
 use crate::conductor::{api::error::ConductorApiResult, entry_def_store::EntryDefBufferKey};
-use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::ribosome::{
+    host_fn_executor::HostFnExecutor, module_cache::ModuleCache, ZomeCallInvocation,
+};
 use crate::core::workflow::ZomeCallInvocationResult;
 use async_trait::async_trait;
 use holo_hash::DnaHash;
@@ -35,6 +37,9 @@ mock! {
         fn sync_get_dna(&self, dna_hash: &DnaHash) -> Option<DnaFile>;
         fn sync_get_this_dna(&self) -> Option<DnaFile>;
         fn sync_get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
+        fn module_cache(&self) -> ModuleCache;
+        fn host_fn_executor(&self) -> HostFnExecutor;
+        fn parallel_callbacks(&self) -> Option<usize>;
     }
 
     trait Clone {
@@ -76,4 +81,13 @@ impl CellConductorApiT for MockCellConductorApi {
     async fn get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef> {
         self.sync_get_entry_def(key)
     }
+    fn module_cache(&self) -> ModuleCache {
+        self.module_cache()
+    }
+    fn host_fn_executor(&self) -> HostFnExecutor {
+        self.host_fn_executor()
+    }
+    fn parallel_callbacks(&self) -> Option<usize> {
+        self.parallel_callbacks()
+    }
 }
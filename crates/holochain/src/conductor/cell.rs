@@ -7,9 +7,14 @@
 use super::manager::ManagedTaskAdd;
 use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::CellConductorApiT;
+use crate::conductor::config::HostFnBudgetConfig;
+use crate::conductor::entry_def_store::EntryDefBufferKey;
 use crate::conductor::handle::ConductorHandle;
-use crate::core::queue_consumer::{spawn_queue_consumer_tasks, InitialQueueTriggers};
-use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::queue_consumer::{
+    spawn_queue_consumer_tasks, InitialQueueTriggers, QueueConsumerPauses,
+};
+use crate::core::ribosome::{cancel::ZomeCallCancel, ZomeCallInvocation};
+use crate::core::signal::SignalSender;
 use holochain_zome_types::zome::FunctionName;
 
 use crate::{
@@ -17,53 +22,80 @@ use crate::{
     core::ribosome::{guest_callback::init::InitResult, wasm_ribosome::WasmRibosome},
     core::{
         state::{
-            dht_op_integration::IntegratedDhtOpsBuf,
+            dht_op_integration::{AuthoredDhtOpsStore, IntegratedDhtOpsBuf},
             element_buf::ElementBuf,
             metadata::{LinkMetaKey, MetadataBuf, MetadataBufT},
-            source_chain::SourceChainBuf,
+            source_chain::{SourceChain, SourceChainBuf},
+            validation_receipts_db::{
+                SignedValidationReceipt, ValidationReceiptStatus, ValidationReceiptsBuf,
+            },
         },
         workflow::{
-            call_zome_workflow, error::WorkflowError, genesis_workflow::genesis_workflow,
-            incoming_dht_ops_workflow::incoming_dht_ops_workflow, initialize_zomes_workflow,
+            call_zome_workflow,
+            error::WorkflowError,
+            genesis_workflow::genesis_workflow,
+            incoming_dht_ops_workflow::{incoming_dht_ops_workflow, OpIngressFilterRef},
+            initialize_zomes_workflow,
+            integrate_dht_ops_workflow::{
+                force_integrate_op,
+                latency::{LatencyHistogram, ValidationLatency},
+                IntegrationEventReceiver, IntegrationEventSender, INTEGRATION_EVENT_BUFFER_SIZE,
+            },
+            produce_dht_ops_workflow::dht_op_light::light_to_op_checked,
+            sys_validation_workflow::{
+                metrics::{DependencyCounters, LimboBreakdown, SysValidationMetrics},
+                SysValidationWorkspace,
+            },
             CallZomeWorkflowArgs, CallZomeWorkspace, GenesisWorkflowArgs, GenesisWorkspace,
             InitializeZomesWorkflowArgs, ZomeCallInvocationResult,
         },
     },
 };
-use error::{AuthorityDataError, CellError};
+use error::{AuthorityDataError, CapError, CellError};
 use fallible_iterator::FallibleIterator;
 use futures::future::FutureExt;
 use hash_type::AnyDht;
 use holo_hash::*;
-use holochain_keystore::Signature;
-use holochain_p2p::HolochainP2pCellT;
+use holochain_keystore::{AgentPubKeyExt, Signature};
+use holochain_p2p::{dht_arc::DhtArc, HolochainP2pCellT};
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_state::{
-    db::GetDb,
-    env::{EnvironmentWrite, ReadManager},
+    buffer::{BufferedStore, KvBufFresh, KvStoreT},
+    db::{GetDb, AUTHORED_DHT_OPS},
+    env::{EnvironmentRead, EnvironmentWrite, ReadManager, WriteManager},
+    error::DatabaseResult,
 };
 use holochain_types::{
+    app::AppId,
     autonomic::AutonomicProcess,
     cell::CellId,
+    dht_op::HeldOpTypes,
     element::{GetElementResponse, WireElement},
     link::{GetLinksResponse, WireLinkMetaKey},
     metadata::{MetadataSet, TimedHeaderHash},
     Timestamp,
 };
-use holochain_zome_types::capability::CapSecret;
-use holochain_zome_types::header::{CreateLink, DeleteLink};
+use holochain_zome_types::call_remote::CallRemoteSignedData;
+use holochain_zome_types::capability::{CapSecret, SignedCapNonce};
+use holochain_zome_types::entry_def::RequiredValidations;
+use holochain_zome_types::header::{CreateLink, DeleteLink, EntryType};
 use holochain_zome_types::zome::ZomeName;
-use holochain_zome_types::ExternInput;
+use holochain_zome_types::{ExternInput, ZomeCallResponse};
+use recent_nonces::RecentNonces;
 use std::{
     collections::{BTreeMap, BTreeSet},
     convert::TryInto,
     hash::{Hash, Hasher},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 use tokio::sync;
 use tracing::*;
 use tracing_futures::Instrument;
+use zome_call_limit::ZomeCallLimit;
 
 mod authority;
+mod recent_nonces;
+mod zome_call_limit;
 
 #[allow(missing_docs)]
 pub mod error;
@@ -105,14 +137,41 @@ where
     env: EnvironmentWrite,
     holochain_p2p_cell: P2pCell,
     queue_triggers: InitialQueueTriggers,
+    /// Pause or resume a single workflow of this Cell
+    queue_pauses: QueueConsumerPauses,
+    zome_call_limit: Option<ZomeCallLimit>,
+    integration_events: IntegrationEventSender,
+    recent_nonces: RecentNonces,
+    /// How many validation receipts have been dropped as forged or implausible
+    rejected_receipts: AtomicUsize,
+    /// Announces the op each stored validation receipt is for
+    receipt_events: sync::broadcast::Sender<DhtOpHash>,
+    /// Consulted before each incoming op enters the validation limbo
+    ingress_filter: OpIngressFilterRef,
+    /// Only observes the DHT, every commit fails
+    read_only: bool,
+    /// The most host functions that reach the network each zome call can make
+    host_fn_budget: HostFnBudgetConfig,
+    /// How many headers and elements each zome call caches from the source chain
+    source_chain_read_cache: usize,
+    /// Sends the signals this Cell's zomes emit
+    signal_tx: SignalSender,
+    /// Where the dependencies sys validation checked were found
+    dependency_counters: DependencyCounters,
+    /// How long ops took from entering the validation limbo to being integrated
+    validation_latency: LatencyHistogram,
 }
 
+/// How many validation receipt events are buffered before slow subscribers miss some
+const RECEIPT_EVENT_BUFFER_SIZE: usize = 100;
+
 impl Cell {
     /// Constructor for a Cell. The SourceChain will be created, and genesis
     /// will be run if necessary. A Cell will not be created if the SourceChain
     /// is not ready to be used.
     pub async fn create(
         id: CellId,
+        app_id: AppId,
         conductor_handle: ConductorHandle,
         env: EnvironmentWrite,
         mut holochain_p2p_cell: holochain_p2p::HolochainP2pCell,
@@ -128,22 +187,63 @@ impl Cell {
         };
 
         if has_genesis {
+            // repair anything a crash left half written before running workflows
+            let mut source_chain = SourceChainBuf::new(env.clone().into())?;
+            if !source_chain.heal()?.is_empty() {
+                env.guard()
+                    .with_commit(|writer| source_chain.flush_to_txn(writer))?;
+            }
+
             holochain_p2p_cell.join().await?;
-            let queue_triggers = spawn_queue_consumer_tasks(
+            let (integration_events, _) = sync::broadcast::channel(INTEGRATION_EVENT_BUFFER_SIZE);
+            let read_only = conductor_handle.is_read_only(&id);
+            let dependency_counters = DependencyCounters::default();
+            let validation_latency = LatencyHistogram::default();
+            let (queue_triggers, queue_pauses) = spawn_queue_consumer_tasks(
                 &env,
                 holochain_p2p_cell.clone(),
+                conductor_handle.network_breaker().clone(),
+                conductor_handle.gossip_request_policy(),
+                conductor_handle.validation_profile(&id),
+                conductor_handle.held_op_types(id.dna_hash()),
+                conductor_handle.author_is_authority(),
+                conductor_handle.max_pending_dependency_depth(),
+                conductor_handle.sys_validation_dep_cache(),
+                dependency_counters.clone(),
+                read_only,
+                conductor_handle.op_egress_filter(),
+                conductor_handle.op_ingress_filter(),
+                integration_events.clone(),
+                validation_latency.clone(),
+                conductor_handle.workflow_runtimes(),
                 conductor_api.clone(),
                 managed_task_add_sender,
                 managed_task_stop_broadcaster,
             )
             .await;
 
+            let signal_tx =
+                SignalSender::new(conductor_handle.signal_broadcaster(), id.clone(), app_id);
+
             Ok(Self {
                 id,
                 conductor_api,
                 env,
                 holochain_p2p_cell,
                 queue_triggers,
+                queue_pauses,
+                zome_call_limit: conductor_handle.zome_call_limit().map(ZomeCallLimit::new),
+                integration_events,
+                recent_nonces: RecentNonces::new(conductor_handle.cap_nonce_config()),
+                rejected_receipts: AtomicUsize::new(0),
+                receipt_events: sync::broadcast::channel(RECEIPT_EVENT_BUFFER_SIZE).0,
+                ingress_filter: conductor_handle.op_ingress_filter(),
+                read_only,
+                host_fn_budget: conductor_handle.host_fn_budget(),
+                source_chain_read_cache: conductor_handle.source_chain_read_cache(),
+                signal_tx,
+                dependency_counters,
+                validation_latency,
             })
         } else {
             Err(CellError::CellWithoutGenesis(id))
@@ -159,12 +259,15 @@ impl Cell {
 
     /// Performs the Genesis workflow the Cell, ensuring that its initial
     /// elements are committed. This is a prerequisite for any other interaction
-    /// with the SourceChain
+    /// with the SourceChain.
+    /// If the agent is migrating from a closed chain on `prev_dna_hash`,
+    /// the new chain is opened pointing back at it.
     pub async fn genesis(
         id: CellId,
         conductor_handle: ConductorHandle,
         cell_env: EnvironmentWrite,
         membrane_proof: Option<SerializedBytes>,
+        prev_dna_hash: Option<DnaHash>,
     ) -> CellResult<()> {
         // get the dna
         let dna_file = conductor_handle
@@ -179,7 +282,12 @@ impl Cell {
             .await
             .map_err(ConductorApiError::from)
             .map_err(Box::new)?;
-        let args = GenesisWorkflowArgs::new(dna_file, id.agent_pubkey().clone(), membrane_proof);
+        let args = GenesisWorkflowArgs::new(
+            dna_file,
+            id.agent_pubkey().clone(),
+            membrane_proof,
+            prev_dna_hash,
+        );
 
         genesis_workflow(workspace, cell_env.clone().into(), conductor_api, args)
             .await
@@ -222,13 +330,14 @@ impl Cell {
                 zome_name,
                 fn_name,
                 cap,
+                nonce,
                 respond,
                 request,
                 ..
             } => {
                 async {
                     let res = self
-                        .handle_call_remote(from_agent, zome_name, fn_name, cap, request)
+                        .handle_call_remote(from_agent, zome_name, fn_name, cap, nonce, request)
                         .await
                         .map_err(holochain_p2p::HolochainP2pError::other);
                     respond.respond(Ok(async move { res }.boxed().into()));
@@ -397,11 +506,16 @@ impl Cell {
         _dht_hash: holo_hash::AnyDhtHash,
         ops: Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
     ) -> CellResult<()> {
-        incoming_dht_ops_workflow(&self.env, self.queue_triggers.sys_validation.clone(), ops)
-            .await
-            .map_err(Box::new)
-            .map_err(ConductorApiError::from)
-            .map_err(Box::new)?;
+        incoming_dht_ops_workflow(
+            &self.env,
+            self.queue_triggers.sys_validation.clone(),
+            ops,
+            self.ingress_filter.clone(),
+        )
+        .await
+        .map_err(Box::new)
+        .map_err(ConductorApiError::from)
+        .map_err(Box::new)?;
         Ok(())
     }
 
@@ -555,9 +669,88 @@ impl Cell {
         })
     }
 
+    #[instrument(skip(self, receipt))]
     /// a remote agent is sending us a validation receipt.
-    async fn handle_validation_receipt(&self, _receipt: SerializedBytes) -> CellResult<()> {
-        unimplemented!()
+    async fn handle_validation_receipt(&self, receipt: SerializedBytes) -> CellResult<()> {
+        let receipt: SignedValidationReceipt = receipt.try_into()?;
+        if let Some(reason) = self.reject_validation_receipt(&receipt).await? {
+            let rejected = self.rejected_receipts.fetch_add(1, Ordering::Relaxed) + 1;
+            warn!(
+                msg = "Dropping a validation receipt",
+                reason,
+                validator = ?receipt.receipt.validator,
+                rejected_receipts = rejected
+            );
+            return Ok(());
+        }
+        let op_hash = receipt.receipt.dht_op_hash.clone();
+        let env: EnvironmentRead = self.env.clone().into();
+
+        // The receipt was only accepted for an op we authored
+        let authored_db = env.get_db(&*AUTHORED_DHT_OPS)?;
+        let authored: AuthoredDhtOpsStore = KvBufFresh::new(env.clone(), authored_db);
+        let required = match authored.get(&op_hash)? {
+            Some(value) => self.required_validations(value.op.header_hash()).await?,
+            None => return Ok(()),
+        };
+
+        // Store the receipt, duplicates from the same validator are ignored
+        let mut receipts = ValidationReceiptsBuf::new(&env)?;
+        receipts.add_if_unique(receipt)?;
+        self.env.guard().with_commit(|writer| {
+            receipts.flush_to_txn_ref(writer)?;
+            // Keep the receipt count on our authored op in step so
+            // publishing stops once the op has its required receipts.
+            // The op is re-read in this txn as publishing writes to it too.
+            let store = authored.store();
+            if let Some(mut value) = store.get(&*writer, &op_hash)? {
+                value.receipt_count =
+                    ValidationReceiptsBuf::new(&env)?.count_valid(&*writer, &op_hash)? as u32;
+                value.required_receipts = u8::from(required) as u32;
+                store.put(writer, &op_hash, &value)?;
+            }
+            DatabaseResult::Ok(())
+        })?;
+        // let anyone waiting on receipts know, it's fine if there are none
+        self.receipt_events.send(op_hash).ok();
+        Ok(())
+    }
+
+    /// Why a validation receipt can't count towards an op's required
+    /// validations, or None if it can.
+    /// Only a receipt signed by its validator, for an op we authored,
+    /// from an agent other than ourselves whose arc in our peer store covers
+    /// the op's basis could have come from the op's validating set.
+    async fn reject_validation_receipt(
+        &self,
+        receipt: &SignedValidationReceipt,
+    ) -> CellResult<Option<&'static str>> {
+        let validator = &receipt.receipt.validator;
+        if !validator
+            .verify_signature(&receipt.validator_signature, receipt.receipt.clone())
+            .await?
+        {
+            return Ok(Some("bad signature"));
+        }
+        if validator == self.id.agent_pubkey() {
+            return Ok(Some("validated by the author"));
+        }
+        let env: EnvironmentRead = self.env.clone().into();
+        let authored: AuthoredDhtOpsStore =
+            KvBufFresh::new(env.clone(), env.get_db(&*AUTHORED_DHT_OPS)?);
+        let basis = match authored.get(&receipt.receipt.dht_op_hash)? {
+            Some(value) => value.op.dht_basis().clone(),
+            None => return Ok(Some("op not authored by this cell")),
+        };
+        if !self
+            .holochain_p2p_cell
+            .clone()
+            .is_authority(validator.clone(), basis)
+            .await?
+        {
+            return Ok(Some("validator's arc doesn't cover the op"));
+        }
+        Ok(None)
     }
 
     #[instrument(skip(self, dht_arc, since, until))]
@@ -596,11 +789,7 @@ impl Cell {
         for op_hash in op_hashes {
             let val = integrated_dht_ops.get(&op_hash)?;
             if let Some(val) = val {
-                let full_op =
-                    crate::core::workflow::produce_dht_ops_workflow::dht_op_light::light_to_op(
-                        val.op, &cas,
-                    )
-                    .await?;
+                let full_op = light_to_op_checked(val.op, &op_hash, &cas).await?;
                 let basis = full_op.dht_basis().await;
                 out.push((basis, op_hash, full_op));
             }
@@ -622,7 +811,7 @@ impl Cell {
         }
     }
 
-    #[instrument(skip(self, from_agent, fn_name, cap, payload))]
+    #[instrument(skip(self, from_agent, fn_name, cap, nonce, payload))]
     /// a remote agent is attempting a "call_remote" on this cell.
     async fn handle_call_remote(
         &self,
@@ -630,8 +819,39 @@ impl Cell {
         zome_name: ZomeName,
         fn_name: FunctionName,
         cap: Option<CapSecret>,
+        nonce: SignedCapNonce,
         payload: SerializedBytes,
     ) -> CellResult<SerializedBytes> {
+        // The caller signs the nonce and expiry along with the call
+        // so neither can be swapped out to replay it
+        let signed_data = CallRemoteSignedData {
+            to_agent: self.id.agent_pubkey().clone(),
+            zome_name: zome_name.clone(),
+            fn_name: fn_name.clone(),
+            cap,
+            nonce: nonce.nonce,
+            expires_at: nonce.expires_at,
+            payload: payload.clone(),
+        };
+        if !from_agent
+            .verify_signature(&nonce.signature, signed_data)
+            .await?
+        {
+            return Err(CapError::BadSignature.into());
+        }
+        // Only remember the nonces of authorized calls so
+        // anyone else can't push real callers' nonces out of the store
+        let chain = SourceChain::new(self.env().clone().into())?;
+        let check_function = (zome_name.clone(), fn_name.clone());
+        if chain
+            .valid_cap_grant(&check_function, &from_agent, cap.as_ref())?
+            .is_none()
+        {
+            return Ok(ZomeCallResponse::Unauthorized.try_into()?);
+        }
+        // A call can only be made once with each nonce
+        self.recent_nonces
+            .check(&from_agent, nonce.nonce, nonce.expires_at)?;
         let invocation = ZomeCallInvocation {
             cell_id: self.id.clone(),
             zome_name: zome_name.clone(),
@@ -643,25 +863,44 @@ impl Cell {
         // double ? because
         // - ConductorApiResult
         // - ZomeCallInvocationResult
-        Ok(self.call_zome(invocation).await??.try_into()?)
+        Ok(self
+            .call_zome(invocation, ZomeCallCancel::default())
+            .await??
+            .try_into()?)
     }
 
-    /// Function called by the Conductor
-    #[instrument(skip(self, invocation))]
+    /// Function called by the Conductor.
+    /// Host functions the call is blocked in return early once `cancel` is triggered.
+    #[instrument(skip(self, invocation, cancel))]
     pub async fn call_zome(
         &self,
         invocation: ZomeCallInvocation,
+        cancel: ZomeCallCancel,
     ) -> CellResult<ZomeCallInvocationResult> {
-        // Check if init has run if not run it
-        self.check_or_run_zome_init().await?;
+        // Hold a permit for the whole call if calls are limited
+        let _permit = match &self.zome_call_limit {
+            Some(limit) => Some(limit.acquire().await?),
+            None => None,
+        };
+
+        // Check if init has run if not run it.
+        // A read-only Cell never inits because init commits to its chain.
+        if !self.read_only {
+            self.check_or_run_zome_init().await?;
+        }
 
         let arc = self.env();
         let keystore = arc.keystore().clone();
-        let workspace = CallZomeWorkspace::new(arc.clone().into())?;
+        let workspace =
+            CallZomeWorkspace::with_read_cache(arc.clone().into(), self.source_chain_read_cache)?;
 
         let args = CallZomeWorkflowArgs {
             ribosome: self.get_ribosome().await?,
             invocation,
+            read_only: self.read_only,
+            host_fn_budget: self.host_fn_budget.clone(),
+            cancel,
+            signal_tx: Some(self.signal_tx.clone()),
         };
         Ok(call_zome_workflow(
             workspace,
@@ -701,7 +940,10 @@ impl Cell {
         let dna_def = dna_file.dna().clone();
 
         // Get the ribosome
-        let ribosome = WasmRibosome::new(dna_file);
+        let ribosome = WasmRibosome::new(dna_file)
+            .with_module_cache(conductor_api.module_cache())
+            .with_host_fn_executor(conductor_api.host_fn_executor())
+            .with_parallel_callbacks(conductor_api.parallel_callbacks());
 
         // Run the workflow
         let args = InitializeZomesWorkflowArgs { dna_def, ribosome };
@@ -739,17 +981,164 @@ impl Cell {
     // TODO: reevaluate once Workflows are fully implemented (after B-01567)
     pub(crate) async fn get_ribosome(&self) -> CellResult<WasmRibosome> {
         match self.conductor_api.get_dna(self.dna_hash()).await {
-            Some(dna) => Ok(WasmRibosome::new(dna)),
+            Some(dna) => Ok(WasmRibosome::new(dna)
+                .with_module_cache(self.conductor_api.module_cache())
+                .with_host_fn_executor(self.conductor_api.host_fn_executor())
+                .with_parallel_callbacks(self.conductor_api.parallel_callbacks())),
             None => Err(CellError::DnaMissing),
         }
     }
 
+    /// Get the events for ops integrated from now on
+    pub(super) fn subscribe_integration(&self) -> IntegrationEventReceiver {
+        self.integration_events.subscribe()
+    }
+
+    /// Integrate a validated op that is stuck in the integration limbo
+    /// without waiting on its dependencies
+    pub(super) async fn force_integrate(
+        &self,
+        op_hash: DhtOpHash,
+        held_op_types: HeldOpTypes,
+    ) -> CellResult<()> {
+        let event = force_integrate_op(self.env.clone(), op_hash, held_op_types)
+            .await
+            .map_err(Box::new)?;
+        // let subscribers know, it's fine if there are none
+        self.integration_events.send(event).ok();
+        // ops waiting on this one may now be able to validate
+        self.queue_triggers.sys_validation.clone().trigger();
+        Ok(())
+    }
+
+    /// Resize the arc of the DHT this Cell holds.
+    /// Ops falling out of the arc are released once `min_redundancy` other
+    /// agents hold them and ops falling into it are fetched.
+    pub(super) fn resize_arc(&self, arc: DhtArc, min_redundancy: usize) {
+        self.queue_triggers
+            .arc_resize_target
+            .set(arc, min_redundancy);
+        self.queue_triggers.arc_resize.clone().trigger();
+    }
+
+    /// How many valid receipts one of our authored ops has collected
+    /// against how many its entry def requires.
+    /// Returns None if this Cell didn't author the op.
+    pub(super) async fn validation_receipt_status(
+        &self,
+        op_hash: &DhtOpHash,
+    ) -> CellResult<Option<ValidationReceiptStatus>> {
+        let env: EnvironmentRead = self.env.clone().into();
+        let authored: AuthoredDhtOpsStore =
+            KvBufFresh::new(env.clone(), env.get_db(&*AUTHORED_DHT_OPS)?);
+        let op = match authored.get(op_hash)? {
+            Some(value) => value.op,
+            None => return Ok(None),
+        };
+        let required = self.required_validations(op.header_hash()).await?;
+        let receipts = ValidationReceiptsBuf::new(&env)?;
+        let env_ref = self.env.guard();
+        Ok(Some(receipts.status(
+            &env_ref.reader()?,
+            op_hash,
+            required,
+        )?))
+    }
+
+    /// Get the hashes of the ops that validation receipts arrive for from now on
+    pub(super) fn subscribe_validation_receipts(&self) -> sync::broadcast::Receiver<DhtOpHash> {
+        self.receipt_events.subscribe()
+    }
+
+    /// How sys validation is keeping up with the ops this Cell holds
+    pub(super) fn sys_validation_metrics(&self) -> CellResult<SysValidationMetrics> {
+        let mut workspace = SysValidationWorkspace::new(self.env.clone().into())?;
+        workspace.dependency_counters = self.dependency_counters.clone();
+        Ok(workspace.metrics()?)
+    }
+
+    /// The ops in this Cell's validation limbo counted by status
+    pub(super) fn sys_validation_limbo_breakdown(&self) -> CellResult<LimboBreakdown> {
+        let workspace = SysValidationWorkspace::new(self.env.clone().into())?;
+        Ok(workspace.limbo_breakdown()?)
+    }
+
+    /// Percentiles of how long ops took to be validated and integrated
+    pub(super) fn validation_latency(&self) -> ValidationLatency {
+        self.validation_latency.percentiles()
+    }
+
+    /// The fewest valid receipts collected by any of the ops we authored for
+    /// this header. Zero if the header's ops haven't been produced yet.
+    pub(super) async fn header_valid_receipts(
+        &self,
+        header_hash: &HeaderHash,
+    ) -> CellResult<usize> {
+        let env: EnvironmentRead = self.env.clone().into();
+        let authored: AuthoredDhtOpsStore =
+            KvBufFresh::new(env.clone(), env.get_db(&*AUTHORED_DHT_OPS)?);
+        let receipts = ValidationReceiptsBuf::new(&env)?;
+        let env_ref = self.env.guard();
+        let reader = env_ref.reader()?;
+        let op_hashes: Vec<DhtOpHash> = authored
+            .iter(&reader)?
+            .filter(|(_, v)| Ok(v.op.header_hash() == header_hash))
+            .map(|(k, _)| Ok(DhtOpHash::with_pre_hashed(k.to_vec())))
+            .collect()?;
+        let mut fewest = None;
+        for op_hash in op_hashes {
+            let count = receipts.count_valid(&reader, &op_hash)?;
+            fewest = Some(fewest.map_or(count, |fewest: usize| fewest.min(count)));
+        }
+        Ok(fewest.unwrap_or(0))
+    }
+
+    /// The required validations of the entry def for the entry this header creates.
+    /// Ops without an app entry need the default number of receipts.
+    async fn required_validations(
+        &self,
+        header_hash: &HeaderHash,
+    ) -> CellResult<RequiredValidations> {
+        let header = SourceChainBuf::new(self.env.clone().into())?.get_header(header_hash)?;
+        let app_entry_type = match header.as_ref().and_then(|h| h.header().entry_type()) {
+            Some(EntryType::App(app_entry_type)) => app_entry_type.clone(),
+            _ => return Ok(RequiredValidations::default()),
+        };
+        let dna_file = self
+            .conductor_api
+            .get_this_dna()
+            .await
+            .ok_or(CellError::DnaMissing)?;
+        let zome = match dna_file
+            .dna()
+            .zomes
+            .get(u8::from(app_entry_type.zome_id()) as usize)
+        {
+            Some((_, zome)) => zome.clone(),
+            None => return Ok(RequiredValidations::default()),
+        };
+        // The entry defs are stored when the dna is installed
+        let key = EntryDefBufferKey::new(zome, app_entry_type.id());
+        Ok(self
+            .conductor_api
+            .get_entry_def(&key)
+            .await
+            .map(|entry_def| entry_def.required_validations)
+            .unwrap_or_default())
+    }
+
     /// Accessor for the LMDB environment backing this Cell
     // TODO: reevaluate once Workflows are fully implemented (after B-01567)
     pub(crate) fn env(&self) -> &EnvironmentWrite {
         &self.env
     }
 
+    /// The pause of each of this Cell's workflows,
+    /// for pausing one while the others keep running
+    pub fn queue_consumer_pauses(&self) -> &QueueConsumerPauses {
+        &self.queue_pauses
+    }
+
     #[cfg(test)]
     /// Get the triggers for the cell
     /// Useful for testing when you want to
@@ -757,6 +1146,11 @@ impl Cell {
     pub(crate) fn triggers(&self) -> &InitialQueueTriggers {
         &self.queue_triggers
     }
+
+    /// Get the sender for the signals this Cell emits
+    pub(crate) fn signal_tx(&self) -> &SignalSender {
+        &self.signal_tx
+    }
 }
 
 #[cfg(test)]
@@ -44,11 +44,17 @@ pub enum CellError {
     #[error(transparent)]
     HolochainP2pError(#[from] HolochainP2pError),
     #[error(transparent)]
+    KeystoreError(#[from] holochain_keystore::KeystoreError),
+    #[error(transparent)]
     SerializedBytesError(#[from] holochain_serialized_bytes::SerializedBytesError),
     #[error(transparent)]
     DhtOpConvertError(#[from] DhtOpConvertError),
     #[error("Cell is an authority for is missing or incorrect: {0}")]
     AuthorityDataError(#[from] AuthorityDataError),
+    #[error("The cell is already running as many zome calls as it allows")]
+    CellBusy,
+    #[error(transparent)]
+    CapError(#[from] CapError),
     #[error("Todo")]
     Todo,
 }
@@ -78,3 +84,22 @@ impl AuthorityDataError {
         Self::MissingMetadata(format!("{:?}", data)).into()
     }
 }
+
+/// Errors from checking the capability of a remote call
+#[derive(Error, Debug, PartialEq)]
+pub enum CapError {
+    #[error("The nonce has already been used by this agent")]
+    NonceReused,
+
+    #[error("The call was not signed by the agent making it")]
+    BadSignature,
+
+    #[error("The call has expired")]
+    CallExpired,
+
+    #[error("The call expires later than its nonce would be remembered")]
+    ExpiryTooLate,
+
+    #[error("Too many calls are waiting to expire to remember another nonce")]
+    TooManyNonces,
+}
@@ -0,0 +1,181 @@
+//! Remembers the nonces of recent remote calls so they can't be replayed
+
+use super::error::CapError;
+use crate::conductor::config::CapNonceConfig;
+use holo_hash::AgentPubKey;
+use holochain_zome_types::{capability::CapNonce, timestamp::Timestamp};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashSet};
+
+/// The nonces each agent has recently called this Cell with.
+/// A nonce is remembered until the call it came with expires,
+/// after which the call is refused anyway.
+pub(super) struct RecentNonces {
+    max_nonces: usize,
+    max_expiry_secs: i64,
+    seen: Mutex<SeenNonces>,
+}
+
+#[derive(Default)]
+struct SeenNonces {
+    nonces: HashSet<(AgentPubKey, CapNonce)>,
+    /// The same nonces by when their calls expire
+    by_expiry: BTreeMap<Timestamp, Vec<(AgentPubKey, CapNonce)>>,
+}
+
+impl SeenNonces {
+    fn forget_expired(&mut self, now: Timestamp) {
+        while let Some(expires_at) = self.by_expiry.keys().next().copied() {
+            if expires_at > now {
+                break;
+            }
+            for seen in self.by_expiry.remove(&expires_at).unwrap_or_default() {
+                self.nonces.remove(&seen);
+            }
+        }
+    }
+}
+
+impl RecentNonces {
+    pub(super) fn new(config: CapNonceConfig) -> Self {
+        Self {
+            max_nonces: config.max_nonces,
+            max_expiry_secs: config.expire_after_secs as i64,
+            seen: Mutex::new(SeenNonces::default()),
+        }
+    }
+
+    /// Remember the nonce of a call that expires at `expires_at`.
+    /// Fails if the call has expired, if it wouldn't expire before its nonce
+    /// is forgotten, or if this agent has already used the nonce.
+    pub(super) fn check(
+        &self,
+        from_agent: &AgentPubKey,
+        nonce: CapNonce,
+        expires_at: Timestamp,
+    ) -> Result<(), CapError> {
+        self.check_at(
+            from_agent,
+            nonce,
+            expires_at,
+            holochain_types::Timestamp::now().into(),
+        )
+    }
+
+    fn check_at(
+        &self,
+        from_agent: &AgentPubKey,
+        nonce: CapNonce,
+        expires_at: Timestamp,
+        now: Timestamp,
+    ) -> Result<(), CapError> {
+        if expires_at <= now {
+            return Err(CapError::CallExpired);
+        }
+        if expires_at > Timestamp(now.0 + self.max_expiry_secs, now.1) {
+            return Err(CapError::ExpiryTooLate);
+        }
+        let mut seen = self.seen.lock();
+        seen.forget_expired(now);
+        let key = (from_agent.clone(), nonce);
+        if seen.nonces.contains(&key) {
+            return Err(CapError::NonceReused);
+        }
+        // Forgetting a nonce before its call expires would let the call be replayed
+        if seen.nonces.len() >= self.max_nonces {
+            return Err(CapError::TooManyNonces);
+        }
+        seen.nonces.insert(key.clone());
+        seen.by_expiry.entry(expires_at).or_default().push(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::test_utils::{fake_agent_pubkey_1, fake_agent_pubkey_2};
+
+    fn nonce(n: u8) -> CapNonce {
+        [n; 32].into()
+    }
+
+    fn recent_nonces(max_nonces: usize) -> RecentNonces {
+        RecentNonces::new(CapNonceConfig {
+            max_nonces,
+            expire_after_secs: 60,
+        })
+    }
+
+    fn at(secs: i64) -> Timestamp {
+        Timestamp(1_000 + secs, 0)
+    }
+
+    #[test]
+    fn rejects_replayed_nonces() {
+        let recent = recent_nonces(10);
+        let alice = fake_agent_pubkey_1();
+        let check = |agent, n| recent.check_at(agent, nonce(n), at(30), at(0));
+        assert_eq!(check(&alice, 1), Ok(()));
+        assert_eq!(check(&alice, 1), Err(CapError::NonceReused));
+
+        // A fresh nonce is fine
+        assert_eq!(check(&alice, 2), Ok(()));
+        // and so is the same nonce from someone else
+        assert_eq!(check(&fake_agent_pubkey_2(), 1), Ok(()));
+    }
+
+    #[test]
+    fn rejects_calls_outside_the_window() {
+        let recent = recent_nonces(10);
+        let alice = fake_agent_pubkey_1();
+        assert_eq!(
+            recent.check_at(&alice, nonce(1), at(0), at(0)),
+            Err(CapError::CallExpired)
+        );
+        // A call valid for longer than its nonce is remembered
+        assert_eq!(
+            recent.check_at(&alice, nonce(1), at(61), at(0)),
+            Err(CapError::ExpiryTooLate)
+        );
+        assert_eq!(recent.check_at(&alice, nonce(1), at(60), at(0)), Ok(()));
+    }
+
+    #[test]
+    fn an_expired_call_cant_be_replayed_once_its_nonce_is_forgotten() {
+        let recent = recent_nonces(10);
+        let alice = fake_agent_pubkey_1();
+        recent.check_at(&alice, nonce(1), at(30), at(0)).unwrap();
+        assert_eq!(
+            recent.check_at(&alice, nonce(1), at(30), at(29)),
+            Err(CapError::NonceReused)
+        );
+
+        // The nonce is forgotten once the call expires
+        // but by then the call is refused
+        assert_eq!(
+            recent.check_at(&alice, nonce(1), at(30), at(30)),
+            Err(CapError::CallExpired)
+        );
+        assert!(recent.seen.lock().nonces.is_empty());
+    }
+
+    #[test]
+    fn refuses_new_nonces_rather_than_forget_live_ones() {
+        let recent = recent_nonces(2);
+        let alice = fake_agent_pubkey_1();
+        recent.check_at(&alice, nonce(0), at(10), at(0)).unwrap();
+        recent.check_at(&alice, nonce(1), at(20), at(0)).unwrap();
+        assert_eq!(
+            recent.check_at(&alice, nonce(2), at(20), at(0)),
+            Err(CapError::TooManyNonces)
+        );
+        assert_eq!(
+            recent.check_at(&alice, nonce(0), at(10), at(5)),
+            Err(CapError::NonceReused)
+        );
+
+        // There is room again once the first call expires
+        assert_eq!(recent.check_at(&alice, nonce(2), at(20), at(10)), Ok(()));
+    }
+}
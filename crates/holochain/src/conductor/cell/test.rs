@@ -1,19 +1,63 @@
+use super::error::CapError;
 use crate::{
-    conductor::manager::spawn_task_manager,
-    core::workflow::incoming_dht_ops_workflow::IncomingDhtOpsWorkspace,
-    fixt::{DnaFileFixturator, SignatureFixturator},
+    conductor::{
+        config::{
+            CapNonceConfig, DepCacheConfig, GossipRequestPolicy, HostFnBudgetConfig,
+            ValidationProfile, WhenBusy, ZomeCallLimitConfig,
+        },
+        manager::{spawn_task_manager, TaskManagerRunHandle},
+        CellError,
+    },
+    core::queue_consumer::WorkflowRuntimes,
+    core::ribosome::host_fn_executor::HostFnExecutor,
+    core::ribosome::module_cache::ModuleCache,
+    core::ribosome::{cancel::ZomeCallCancel, ZomeCallInvocationFixturator},
+    core::state::cascade::breaker::NetworkBreaker,
+    core::state::source_chain::DEFAULT_READ_CACHE_CAPACITY,
+    core::state::{
+        dht_op_integration::{AuthoredDhtOpsStore, AuthoredDhtOpsValue},
+        element_buf::ElementBuf,
+        validation_receipts_db::{ValidationReceipt, ValidationResult},
+        workspace::Workspace,
+    },
+    core::workflow::incoming_dht_ops_workflow::{AcceptAllOps, IncomingDhtOpsWorkspace},
+    core::workflow::publish_dht_ops_workflow::{
+        publish_dht_ops_workflow_inner, PublishDhtOpsWorkspace, SendAllOps,
+    },
+    core::workflow::sys_validation_workflow::DEFAULT_MAX_PENDING_DEPENDENCY_DEPTH,
+    fixt::{CreateLinkFixturator, DnaFileFixturator, SignatureFixturator},
 };
 use ::fixt::prelude::*;
-use holo_hash::HasHash;
-use holochain_p2p::actor::HolochainP2pRefToCell;
-use holochain_state::test_utils::{test_cell_env, TestEnvironment};
+use holo_hash::{AgentPubKey, DhtOpHash, HasHash};
+use holochain_keystore::{AgentPubKeyExt, KeystoreSender, KeystoreSenderExt};
+use holochain_p2p::{
+    actor::HolochainP2pRefToCell, dht_arc::DhtArc, HolochainP2pCell, HolochainP2pRef,
+    HolochainP2pSender,
+};
+use holochain_serialized_bytes::SerializedBytes;
+use holochain_state::{
+    buffer::{BufferedStore, KvBufFresh},
+    db::{GetDb, AUTHORED_DHT_OPS},
+    env::{EnvironmentWrite, WriteManager},
+    error::DatabaseError,
+    test_utils::{test_cell_env, test_keystore, TestEnvironment},
+};
 use holochain_types::{
-    dht_op::{DhtOp, DhtOpHashed},
-    test_utils::{fake_agent_pubkey_2, fake_cell_id},
+    cell::CellId,
+    dht_op::{DhtOp, DhtOpHashed, DhtOpLight, HeldOpTypes},
+    test_utils::{fake_agent_pubkey_2, fake_cell_id, fake_dht_op_hash, fake_header_hash},
     HeaderHashed, Timestamp,
 };
-use holochain_zome_types::header;
-use std::sync::Arc;
+use holochain_zome_types::{
+    self as zome_types,
+    call_remote::CallRemoteSignedData,
+    capability::{SignedCapNonce, CAP_NONCE_BYTES},
+    element::SignedHeaderHashed,
+    entry_def::RequiredValidations,
+    header,
+};
+use std::convert::{TryFrom, TryInto};
+use std::sync::{atomic::Ordering, Arc};
 use tokio::sync;
 
 #[tokio::test(threaded_scheduler)]
@@ -29,30 +73,8 @@ async fn test_cell_handle_publish() {
 
     let holochain_p2p_cell = holochain_p2p.to_cell(dna.clone(), agent.clone());
 
-    let mut mock_handler = crate::conductor::handle::MockConductorHandleT::new();
-    mock_handler
-        .expect_get_dna()
-        .returning(|_| Some(fixt!(DnaFile)));
-
-    let mock_handler: crate::conductor::handle::ConductorHandle = Arc::new(mock_handler);
-
-    super::Cell::genesis(cell_id.clone(), mock_handler.clone(), env.clone(), None)
-        .await
-        .unwrap();
-
-    let (add_task_sender, shutdown) = spawn_task_manager();
-    let (stop_tx, _) = sync::broadcast::channel(1);
-
-    let cell = super::Cell::create(
-        cell_id,
-        mock_handler,
-        env.clone(),
-        holochain_p2p_cell,
-        add_task_sender,
-        stop_tx.clone(),
-    )
-    .await
-    .unwrap();
+    let (cell, stop_tx, shutdown) =
+        create_test_cell(cell_id, env.clone(), holochain_p2p_cell).await;
 
     let sig = fixt!(Signature);
     let header = header::Header::Dna(header::Dna {
@@ -81,3 +103,564 @@ async fn test_cell_handle_publish() {
     stop_tx.send(()).unwrap();
     shutdown.await.unwrap();
 }
+
+#[tokio::test(threaded_scheduler)]
+async fn test_cell_collects_validation_receipts() {
+    let TestEnvironment {
+        env,
+        tmpdir: _tmpdir,
+    } = test_cell_env();
+    let (holochain_p2p, _p2p_evt) = holochain_p2p::spawn_holochain_p2p().await.unwrap();
+    let cell_id = fake_cell_id(1);
+    let holochain_p2p_cell =
+        holochain_p2p.to_cell(cell_id.dna_hash().clone(), cell_id.agent_pubkey().clone());
+
+    let (cell, stop_tx, shutdown) =
+        create_test_cell(cell_id.clone(), env.clone(), holochain_p2p_cell).await;
+
+    // An op this cell authored that doesn't create an app entry
+    let op_hash = fake_dht_op_hash(1);
+    let op_light =
+        DhtOpLight::RegisterAgentActivity(fake_header_hash(1), fake_header_hash(1).into());
+    let authored = || -> AuthoredDhtOpsStore {
+        KvBufFresh::new(env.clone().into(), env.get_db(&*AUTHORED_DHT_OPS).unwrap())
+    };
+    {
+        let mut store = authored();
+        store
+            .put(op_hash.clone(), AuthoredDhtOpsValue::from_light(op_light))
+            .unwrap();
+        env.guard()
+            .with_commit(|writer| store.flush_to_txn(writer))
+            .unwrap();
+    }
+
+    let keystore = test_keystore();
+    let receipt = |validator| {
+        ValidationReceipt {
+            dht_op_hash: op_hash.clone(),
+            validation_result: ValidationResult::Valid,
+            validator,
+        }
+        .sign(&keystore)
+    };
+
+    // # The op is under validated until it has the default number of receipts
+    let required = u8::from(RequiredValidations::default()) as usize;
+    for i in 0..required {
+        let status = cell
+            .validation_receipt_status(&op_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(status.valid_receipts, i);
+        assert!(status.is_under_validated());
+
+        let validator = keystore
+            .clone()
+            .generate_sign_keypair_from_pure_entropy()
+            .await
+            .unwrap();
+        // Only agents in our peer store can be authorities for the op
+        holochain_p2p
+            .join(cell_id.dna_hash().clone(), validator.clone())
+            .await
+            .unwrap();
+        let receipt = receipt(validator).await.unwrap();
+        cell.handle_validation_receipt(receipt.clone().try_into().unwrap())
+            .await
+            .unwrap();
+        // The same receipt again doesn't count twice
+        cell.handle_validation_receipt(receipt.try_into().unwrap())
+            .await
+            .unwrap();
+    }
+
+    // # Then it's fully validated
+    let status = cell
+        .validation_receipt_status(&op_hash)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(status.valid_receipts, required);
+    assert!(!status.is_under_validated());
+    assert_eq!(
+        authored().get(&op_hash).unwrap().unwrap().receipt_count as usize,
+        required
+    );
+
+    // # Receipts with a bad signature are dropped
+    let validator = keystore
+        .clone()
+        .generate_sign_keypair_from_pure_entropy()
+        .await
+        .unwrap();
+    let mut forged = receipt(validator).await.unwrap();
+    forged.receipt.validator = fake_agent_pubkey_2();
+    cell.handle_validation_receipt(forged.try_into().unwrap())
+        .await
+        .unwrap();
+    let status = cell
+        .validation_receipt_status(&op_hash)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(status.valid_receipts, required);
+
+    // # Ops we didn't author have no status
+    assert_eq!(
+        cell.validation_receipt_status(&fake_dht_op_hash(2))
+            .await
+            .unwrap(),
+        None
+    );
+
+    stop_tx.send(()).unwrap();
+    shutdown.await.unwrap();
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_cell_rejects_forged_validation_receipts() {
+    let TestEnvironment {
+        env,
+        tmpdir: _tmpdir,
+    } = test_cell_env();
+    let (holochain_p2p, _p2p_evt) = holochain_p2p::spawn_holochain_p2p().await.unwrap();
+    let cell_id = fake_cell_id(1);
+    let holochain_p2p_cell =
+        holochain_p2p.to_cell(cell_id.dna_hash().clone(), cell_id.agent_pubkey().clone());
+
+    let (cell, stop_tx, shutdown) =
+        create_test_cell(cell_id.clone(), env.clone(), holochain_p2p_cell).await;
+
+    // An op this cell authored
+    let op_hash = fake_dht_op_hash(1);
+    let op_light =
+        DhtOpLight::RegisterAgentActivity(fake_header_hash(1), fake_header_hash(1).into());
+    {
+        let mut store: AuthoredDhtOpsStore =
+            KvBufFresh::new(env.clone().into(), env.get_db(&*AUTHORED_DHT_OPS).unwrap());
+        store
+            .put(op_hash.clone(), AuthoredDhtOpsValue::from_light(op_light))
+            .unwrap();
+        env.guard()
+            .with_commit(|writer| store.flush_to_txn(writer))
+            .unwrap();
+    }
+
+    let keystore = test_keystore();
+    let receipt = |dht_op_hash, validator| {
+        ValidationReceipt {
+            dht_op_hash,
+            validation_result: ValidationResult::Valid,
+            validator,
+        }
+        .sign(&keystore)
+    };
+    async fn valid_receipts(cell: &super::Cell, op_hash: &DhtOpHash) -> usize {
+        cell.validation_receipt_status(op_hash)
+            .await
+            .unwrap()
+            .unwrap()
+            .valid_receipts
+    }
+    async fn new_validator(keystore: &KeystoreSender) -> AgentPubKey {
+        keystore
+            .clone()
+            .generate_sign_keypair_from_pure_entropy()
+            .await
+            .unwrap()
+    }
+    async fn new_peer(
+        keystore: &KeystoreSender,
+        holochain_p2p: &HolochainP2pRef,
+        cell_id: &CellId,
+    ) -> AgentPubKey {
+        let validator = new_validator(keystore).await;
+        holochain_p2p
+            .join(cell_id.dna_hash().clone(), validator.clone())
+            .await
+            .unwrap();
+        validator
+    }
+
+    // # A receipt claiming to be from an agent that didn't sign it is rejected
+    let mut forged = receipt(
+        op_hash.clone(),
+        new_peer(&keystore, &holochain_p2p, &cell_id).await,
+    )
+    .await
+    .unwrap();
+    forged.receipt.validator = new_peer(&keystore, &holochain_p2p, &cell_id).await;
+    cell.handle_validation_receipt(forged.try_into().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(valid_receipts(&cell, &op_hash).await, 0);
+
+    // # So is a receipt the author signed for its own op
+    let own = receipt(op_hash.clone(), cell_id.agent_pubkey().clone())
+        .await
+        .unwrap();
+    cell.handle_validation_receipt(own.try_into().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(valid_receipts(&cell, &op_hash).await, 0);
+
+    // # And a receipt for an op this cell never authored
+    let unknown = receipt(
+        fake_dht_op_hash(2),
+        new_peer(&keystore, &holochain_p2p, &cell_id).await,
+    )
+    .await
+    .unwrap();
+    cell.handle_validation_receipt(unknown.try_into().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        cell.validation_receipt_status(&fake_dht_op_hash(2))
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(cell.rejected_receipts.load(Ordering::Relaxed), 3);
+
+    // # And a receipt from a validator our peer store has never heard of
+    let stranger = receipt(op_hash.clone(), new_validator(&keystore).await)
+        .await
+        .unwrap();
+    cell.handle_validation_receipt(stranger.try_into().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(valid_receipts(&cell, &op_hash).await, 0);
+    assert_eq!(cell.rejected_receipts.load(Ordering::Relaxed), 4);
+
+    // # And a receipt from a peer whose arc doesn't reach the op's basis
+    let outsider = new_peer(&keystore, &holochain_p2p, &cell_id).await;
+    let far_from_basis = fake_header_hash(1).get_loc().wrapping_add(u32::MAX / 2);
+    holochain_p2p
+        .set_arc(
+            cell_id.dna_hash().clone(),
+            outsider.clone(),
+            DhtArc::new(far_from_basis, 1),
+        )
+        .await
+        .unwrap();
+    let out_of_arc = receipt(op_hash.clone(), outsider).await.unwrap();
+    cell.handle_validation_receipt(out_of_arc.try_into().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(valid_receipts(&cell, &op_hash).await, 0);
+    assert_eq!(cell.rejected_receipts.load(Ordering::Relaxed), 5);
+
+    // # A genuine receipt from a known authority still counts
+    let genuine = receipt(
+        op_hash.clone(),
+        new_peer(&keystore, &holochain_p2p, &cell_id).await,
+    )
+    .await
+    .unwrap();
+    cell.handle_validation_receipt(genuine.try_into().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(valid_receipts(&cell, &op_hash).await, 1);
+    assert_eq!(cell.rejected_receipts.load(Ordering::Relaxed), 5);
+
+    stop_tx.send(()).unwrap();
+    shutdown.await.unwrap();
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_cell_keeps_receipts_counted_while_publishing() {
+    let TestEnvironment {
+        env,
+        tmpdir: _tmpdir,
+    } = test_cell_env();
+    let (holochain_p2p, _p2p_evt) = holochain_p2p::spawn_holochain_p2p().await.unwrap();
+    let cell_id = fake_cell_id(1);
+    let holochain_p2p_cell =
+        holochain_p2p.to_cell(cell_id.dna_hash().clone(), cell_id.agent_pubkey().clone());
+
+    let (cell, stop_tx, shutdown) =
+        create_test_cell(cell_id.clone(), env.clone(), holochain_p2p_cell).await;
+
+    // An op this cell authored, with its element so it can be published
+    let sig = fixt!(Signature);
+    let link_add = fixt!(CreateLink);
+    let header_hash = HeaderHashed::from_content_sync(link_add.clone().into());
+    let op = DhtOp::StoreElement(sig.clone(), link_add.into(), None);
+    let op_hash = DhtOpHashed::from_content_sync(op.clone()).into_hash();
+    let authored = || -> AuthoredDhtOpsStore {
+        KvBufFresh::new(env.clone().into(), env.get_db(&*AUTHORED_DHT_OPS).unwrap())
+    };
+    {
+        let mut store = authored();
+        store
+            .put(
+                op_hash.clone(),
+                AuthoredDhtOpsValue::from_light(op.to_light().await),
+            )
+            .unwrap();
+        let mut elements = ElementBuf::vault(env.clone().into(), true).unwrap();
+        elements
+            .put(SignedHeaderHashed::with_presigned(header_hash, sig), None)
+            .unwrap();
+        env.guard()
+            .with_commit::<DatabaseError, _, _>(|writer| {
+                store.flush_to_txn(writer)?;
+                elements.flush_to_txn(writer)?;
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    // # Publishing reads the op
+    let mut publish_workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    let to_publish = publish_dht_ops_workflow_inner(&mut publish_workspace)
+        .await
+        .unwrap();
+    assert_eq!(to_publish.len(), 1);
+
+    // # A receipt comes in while the op is being published
+    let keystore = test_keystore();
+    let validator = keystore
+        .clone()
+        .generate_sign_keypair_from_pure_entropy()
+        .await
+        .unwrap();
+    holochain_p2p
+        .join(cell_id.dna_hash().clone(), validator.clone())
+        .await
+        .unwrap();
+    let receipt = ValidationReceipt {
+        dht_op_hash: op_hash.clone(),
+        validation_result: ValidationResult::Valid,
+        validator,
+    }
+    .sign(&keystore)
+    .await
+    .unwrap();
+    cell.handle_validation_receipt(receipt.try_into().unwrap())
+        .await
+        .unwrap();
+
+    // # Then publishing records its publish time
+    env.guard()
+        .with_commit(|writer| publish_workspace.flush_to_txn_ref(writer))
+        .unwrap();
+
+    // # Neither write is lost
+    let value = authored().get(&op_hash).unwrap().unwrap();
+    assert_eq!(value.receipt_count, 1);
+    assert!(value.last_publish_time.is_some());
+
+    stop_tx.send(()).unwrap();
+    shutdown.await.unwrap();
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_cell_rejects_zome_calls_over_the_limit() {
+    let TestEnvironment {
+        env,
+        tmpdir: _tmpdir,
+    } = test_cell_env();
+    let (holochain_p2p, _p2p_evt) = holochain_p2p::spawn_holochain_p2p().await.unwrap();
+    let cell_id = fake_cell_id(1);
+    let holochain_p2p_cell =
+        holochain_p2p.to_cell(cell_id.dna_hash().clone(), cell_id.agent_pubkey().clone());
+
+    let (mut cell, stop_tx, shutdown) =
+        create_test_cell(cell_id.clone(), env.clone(), holochain_p2p_cell).await;
+    cell.zome_call_limit = Some(super::ZomeCallLimit::new(ZomeCallLimitConfig {
+        max_concurrent_calls: 1,
+        when_busy: WhenBusy::Reject,
+    }));
+
+    // Another call is running on the cell
+    let _running = cell
+        .zome_call_limit
+        .as_ref()
+        .unwrap()
+        .acquire()
+        .await
+        .unwrap();
+
+    let mut invocation = ZomeCallInvocationFixturator::new(Unpredictable)
+        .next()
+        .unwrap();
+    invocation.cell_id = cell_id;
+    let result = cell.call_zome(invocation, ZomeCallCancel::default()).await;
+    assert!(matches!(result, Err(CellError::CellBusy)));
+
+    stop_tx.send(()).unwrap();
+    shutdown.await.unwrap();
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_cell_rejects_expired_and_tampered_remote_calls() {
+    let TestEnvironment {
+        env,
+        tmpdir: _tmpdir,
+    } = test_cell_env();
+    let (holochain_p2p, _p2p_evt) = holochain_p2p::spawn_holochain_p2p().await.unwrap();
+    let cell_id = fake_cell_id(1);
+    let holochain_p2p_cell =
+        holochain_p2p.to_cell(cell_id.dna_hash().clone(), cell_id.agent_pubkey().clone());
+
+    let (cell, stop_tx, shutdown) =
+        create_test_cell(cell_id.clone(), env.clone(), holochain_p2p_cell).await;
+
+    // The cell's own agent is always granted access so only the nonce is in the way
+    let caller = cell_id.agent_pubkey().clone();
+    let keystore = test_keystore();
+    let payload = SerializedBytes::try_from(()).unwrap();
+    let now: zome_types::timestamp::Timestamp = Timestamp::now().into();
+    let expired = zome_types::timestamp::Timestamp(now.0 - 1, now.1);
+    let signed_call = |expires_at| {
+        let keystore = keystore.clone();
+        let data = CallRemoteSignedData {
+            to_agent: caller.clone(),
+            zome_name: "zome".into(),
+            fn_name: "fn".into(),
+            cap: None,
+            nonce: [1; CAP_NONCE_BYTES].into(),
+            expires_at,
+            payload: payload.clone(),
+        };
+        let caller = caller.clone();
+        async move {
+            SignedCapNonce {
+                nonce: data.nonce,
+                expires_at,
+                signature: caller.sign(&keystore, data).await.unwrap(),
+            }
+        }
+    };
+    let call = |nonce| {
+        cell.handle_call_remote(
+            caller.clone(),
+            "zome".into(),
+            "fn".into(),
+            None,
+            nonce,
+            payload.clone(),
+        )
+    };
+
+    // Replaying a call after it has expired
+    let result = call(signed_call(expired).await).await;
+    assert!(matches!(
+        result,
+        Err(CellError::CapError(CapError::CallExpired))
+    ));
+
+    // or extending its expiry without the caller's signature
+    let mut tampered = signed_call(expired).await;
+    tampered.expires_at = zome_types::timestamp::Timestamp(now.0 + 30, now.1);
+    let result = call(tampered).await;
+    assert!(matches!(
+        result,
+        Err(CellError::CapError(CapError::BadSignature))
+    ));
+
+    stop_tx.send(()).unwrap();
+    shutdown.await.unwrap();
+}
+
+/// Create a Cell through genesis on a conductor handle that answers every
+/// config query with its default.
+/// Stop it by sending on the returned sender, then await the task manager.
+async fn create_test_cell(
+    cell_id: CellId,
+    env: EnvironmentWrite,
+    holochain_p2p_cell: HolochainP2pCell,
+) -> (
+    super::Cell,
+    sync::broadcast::Sender<()>,
+    TaskManagerRunHandle,
+) {
+    let mut mock_handler = crate::conductor::handle::MockConductorHandleT::new();
+    mock_handler
+        .expect_get_dna()
+        .returning(|_| Some(fixt!(DnaFile)));
+    mock_handler
+        .expect_module_cache()
+        .return_const(ModuleCache::default());
+    mock_handler
+        .expect_host_fn_executor()
+        .return_const(HostFnExecutor::default());
+    mock_handler
+        .expect_parallel_callbacks()
+        .return_const(None::<usize>);
+    mock_handler
+        .expect_network_breaker()
+        .return_const(NetworkBreaker::default());
+    mock_handler
+        .expect_gossip_request_policy()
+        .returning(GossipRequestPolicy::default);
+    mock_handler
+        .expect_validation_profile()
+        .returning(|_| ValidationProfile::default());
+    mock_handler.expect_is_read_only().returning(|_| false);
+    mock_handler
+        .expect_held_op_types()
+        .returning(|_| HeldOpTypes::default());
+    mock_handler
+        .expect_op_egress_filter()
+        .returning(|| Arc::new(SendAllOps));
+    mock_handler
+        .expect_op_ingress_filter()
+        .returning(|| Arc::new(AcceptAllOps));
+    mock_handler
+        .expect_workflow_runtimes()
+        .returning(WorkflowRuntimes::default);
+    mock_handler
+        .expect_author_is_authority()
+        .return_const(false);
+    mock_handler
+        .expect_max_pending_dependency_depth()
+        .return_const(DEFAULT_MAX_PENDING_DEPENDENCY_DEPTH);
+    mock_handler
+        .expect_sys_validation_dep_cache()
+        .return_const(None::<DepCacheConfig>);
+    mock_handler.expect_zome_call_limit().return_const(None);
+    mock_handler
+        .expect_host_fn_budget()
+        .return_const(HostFnBudgetConfig::default());
+    mock_handler
+        .expect_source_chain_read_cache()
+        .return_const(DEFAULT_READ_CACHE_CAPACITY);
+    mock_handler
+        .expect_signal_broadcaster()
+        .return_const(sync::broadcast::channel(1).0);
+    mock_handler
+        .expect_cap_nonce_config()
+        .return_const(CapNonceConfig::default());
+
+    let mock_handler: crate::conductor::handle::ConductorHandle = Arc::new(mock_handler);
+
+    super::Cell::genesis(
+        cell_id.clone(),
+        mock_handler.clone(),
+        env.clone(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let (add_task_sender, shutdown) = spawn_task_manager();
+    let (stop_tx, _) = sync::broadcast::channel(1);
+
+    let cell = super::Cell::create(
+        cell_id,
+        "test app".to_string(),
+        mock_handler,
+        env,
+        holochain_p2p_cell,
+        add_task_sender,
+        stop_tx.clone(),
+    )
+    .await
+    .unwrap();
+    (cell, stop_tx, shutdown)
+}
@@ -0,0 +1,79 @@
+//! Bounds how many zome calls can run on a Cell at the same time
+
+use super::error::{CellError, CellResult};
+use crate::conductor::config::{WhenBusy, ZomeCallLimitConfig};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Hands out a permit to each running zome call
+pub(super) struct ZomeCallLimit {
+    permits: Semaphore,
+    when_busy: WhenBusy,
+}
+
+impl ZomeCallLimit {
+    pub(super) fn new(config: ZomeCallLimitConfig) -> Self {
+        Self {
+            permits: Semaphore::new(config.max_concurrent_calls),
+            when_busy: config.when_busy,
+        }
+    }
+
+    /// Get a permit to run a zome call, which is held until it's dropped.
+    /// If all the permits are taken this either waits for one
+    /// or fails with [CellError::CellBusy].
+    pub(super) async fn acquire(&self) -> CellResult<SemaphorePermit<'_>> {
+        match self.when_busy {
+            WhenBusy::Queue => Ok(self.permits.acquire().await),
+            WhenBusy::Reject => self.permits.try_acquire().map_err(|_| CellError::CellBusy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn rejects_calls_over_the_limit() {
+        let limit = ZomeCallLimit::new(ZomeCallLimitConfig {
+            max_concurrent_calls: 2,
+            when_busy: WhenBusy::Reject,
+        });
+        let calls = futures::future::join_all((0..5).map(|_| limit.acquire())).await;
+        let (running, rejected): (Vec<_>, Vec<_>) = calls.into_iter().partition(Result::is_ok);
+        assert_eq!(running.len(), 2);
+        assert_eq!(rejected.len(), 3);
+        assert!(rejected
+            .iter()
+            .all(|r| matches!(r, Err(CellError::CellBusy))));
+
+        // Once the running calls finish there is room again
+        drop(running);
+        assert!(limit.acquire().await.is_ok());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn queues_calls_over_the_limit() {
+        let limit = ZomeCallLimit::new(ZomeCallLimitConfig {
+            max_concurrent_calls: 2,
+            when_busy: WhenBusy::Queue,
+        });
+        let first = limit.acquire().await.unwrap();
+        let _second = limit.acquire().await.unwrap();
+
+        // The third call waits while the first two are running
+        let third = limit.acquire();
+        tokio::pin!(third);
+        assert!(tokio::time::timeout(Duration::from_millis(50), &mut third)
+            .await
+            .is_err());
+
+        // and runs as soon as one of them finishes
+        drop(first);
+        assert!(tokio::time::timeout(Duration::from_secs(1), &mut third)
+            .await
+            .unwrap()
+            .is_ok());
+    }
+}
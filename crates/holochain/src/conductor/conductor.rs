@@ -32,10 +32,45 @@ use super::{
 };
 use crate::{
     conductor::{
-        api::error::ConductorApiResult, cell::Cell, config::ConductorConfig,
-        dna_store::MockDnaStore, error::ConductorResult, handle::ConductorHandle,
+        api::error::ConductorApiResult,
+        cell::Cell,
+        config::ConductorConfig,
+        dna_store::MockDnaStore,
+        error::ConductorResult,
+        handle::{
+            CellHealth, CellResourceReport, ConductorHandle, IntegrationSummary, LimboOpDump,
+            OpStreamPage, RejectedOpDump, StateDump, StreamedOp,
+        },
+    },
+    core::{
+        queue_consumer::{QueueWorkflow, WorkflowPause, WorkflowRuntimes},
+        ribosome::{
+            host_fn_executor::{HostFnExecutor, DEFAULT_MAX_BLOCKING_HOST_FNS},
+            module_cache::{ModuleCache, DEFAULT_MAX_CACHED_MODULES},
+            wasm_ribosome::WasmRibosome,
+        },
+        state::{
+            cascade::breaker::NetworkBreaker,
+            dht_op_integration::{
+                IntegratedDhtOpsBuf, IntegratedDhtOpsStore, IntegrationLimboStore, OpCursor,
+            },
+            entry_ops_index::EntryOpsIndexBuf,
+            source_chain::{SourceChainBuf, DEFAULT_READ_CACHE_CAPACITY},
+            validation_db::ValidationLimboStore,
+            validation_receipts_db::ValidationReceiptStatus,
+            wasm::WasmBuf,
+        },
+        workflow::{
+            app_validation_workflow::{revalidate_integrated_ops, RevalidationChange},
+            incoming_dht_ops_workflow::{AcceptAllOps, OpIngressFilterRef},
+            integrate_dht_ops_workflow::{latency::ValidationLatency, IntegrationEventReceiver},
+            publish_dht_ops_workflow::{OpEgressFilterRef, SendAllOps},
+            sys_validation_workflow::{
+                metrics::{LimboBreakdown, SysValidationMetrics},
+                DEFAULT_MAX_PENDING_DEPENDENCY_DEPTH,
+            },
+        },
     },
-    core::state::{source_chain::SourceChainBuf, wasm::WasmBuf},
 };
 use holochain_keystore::{
     lair_keystore::spawn_lair_keystore, test_keystore::spawn_test_keystore, KeystoreSender,
@@ -43,9 +78,10 @@ use holochain_keystore::{
 };
 use holochain_state::{
     buffer::BufferedStore,
-    buffer::{KvStore, KvStoreT},
-    db,
-    env::{EnvironmentKind, EnvironmentWrite, ReadManager},
+    buffer::{KvBufFresh, KvStore, KvStoreT},
+    db::{self, DbName},
+    env::{EnvironmentConfig, EnvironmentKind, EnvironmentWrite, ReadManager},
+    error::DatabaseResult,
     exports::SingleStore,
     fresh_reader,
     prelude::*,
@@ -53,7 +89,9 @@ use holochain_state::{
 use holochain_types::{
     app::{AppId, InstalledApp, InstalledCell, MembraneProof},
     cell::CellId,
+    dht_op::HeldOpTypes,
     dna::{wasm::DnaWasmHashed, DnaFile},
+    validate::ValidationStatus,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -62,12 +100,12 @@ use tracing::*;
 
 pub use builder::*;
 use futures::future::{self, TryFutureExt};
-use holo_hash::DnaHash;
+use holo_hash::{DhtOpHash, DnaHash, EntryHash, HeaderHash};
 
 #[cfg(test)]
 use super::handle::MockConductorHandleT;
 use fallible_iterator::FallibleIterator;
-use holochain_zome_types::entry_def::EntryDef;
+use holochain_zome_types::{entry_def::EntryDef, header::Header};
 
 /// Conductor-specific Cell state, this can probably be stored in a database.
 /// Hypothesis: If nothing remains in this struct, then the Conductor state is
@@ -136,6 +174,9 @@ where
     /// The root environment directory where all environments are created
     root_env_dir: EnvironmentRootPath,
 
+    /// The settings each Cell's environment is opened with
+    cell_env_config: EnvironmentConfig,
+
     /// Handle to the network actor.
     holochain_p2p: holochain_p2p::HolochainP2pRef,
 }
@@ -287,8 +328,8 @@ where
         port: u16,
         handle: ConductorHandle,
     ) -> ConductorResult<u16> {
+        let signal_broadcaster = handle.signal_broadcaster();
         let app_api = RealAppInterfaceApi::new(handle);
-        let (signal_broadcaster, _r) = tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE);
         let stop_rx = self.managed_task_stop_broadcaster.subscribe();
         let (port, task) = spawn_app_interface_task(port, app_api, signal_broadcaster, stop_rx)
             .await
@@ -298,6 +339,30 @@ where
         Ok(port)
     }
 
+    /// The DNA of this agent's closed chain that names the DNA of `cell_id`
+    /// as the one it moved to, if the agent is migrating to this Cell
+    fn migrated_from(&self, cell_id: &CellId) -> ConductorResult<Option<DnaHash>> {
+        let same_agent = self
+            .cells
+            .iter()
+            .filter(|(id, _)| id.agent_pubkey() == cell_id.agent_pubkey());
+        for (id, item) in same_agent {
+            let chain = SourceChainBuf::new(item.cell.env().clone().into())?;
+            let head = match chain.chain_head() {
+                Some(head) => chain.get_header(head)?,
+                None => None,
+            };
+            if let Some(head) = head {
+                if let Header::CloseChain(close) = head.header() {
+                    if &close.new_dna_hash == cell_id.dna_hash() {
+                        return Ok(Some(id.dna_hash().clone()));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
     /// Perform Genesis on the source chains for each of the specified CellIds.
     ///
     /// If genesis fails for any cell, this entire function fails, and all other
@@ -310,18 +375,26 @@ where
         let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
         let keystore = self.keystore.clone();
 
-        let cells_tasks = cell_ids_with_proofs.into_iter().map(|(cell_id, proof)| {
+        let mut cells = Vec::with_capacity(cell_ids_with_proofs.len());
+        for (cell_id, proof) in cell_ids_with_proofs {
+            let prev_dna_hash = self.migrated_from(&cell_id)?;
+            cells.push((cell_id, proof, prev_dna_hash));
+        }
+
+        let cells_tasks = cells.into_iter().map(|(cell_id, proof, prev_dna_hash)| {
             let root_env_dir = root_env_dir.clone();
             let keystore = self.keystore.clone();
+            let cell_env_config = self.cell_env_config;
             let conductor_handle = conductor_handle.clone();
             let cell_id_inner = cell_id.clone();
             tokio::spawn(async move {
-                let env = EnvironmentWrite::new(
+                let env = EnvironmentWrite::new_with_config(
                     &root_env_dir,
                     EnvironmentKind::Cell(cell_id_inner.clone()),
                     keystore.clone(),
+                    cell_env_config,
                 )?;
-                Cell::genesis(cell_id_inner, conductor_handle, env, proof).await
+                Cell::genesis(cell_id_inner, conductor_handle, env, proof, prev_dna_hash).await
             })
             .map_err(CellError::from)
             .and_then(|result| async move { result.map(|_| cell_id) })
@@ -392,6 +465,7 @@ where
                             .map(|cell_id| {
                                 (
                                     cell_id,
+                                    app_id.clone(),
                                     root_env_dir.clone(),
                                     keystore.clone(),
                                     conductor_handle.clone(),
@@ -402,19 +476,21 @@ where
 
                         // Create each cell
                         let cells_tasks = cells_to_create.map(
-                            |(cell_id, dir, keystore, conductor_handle)| async move {
+                            |(cell_id, app_id, dir, keystore, conductor_handle)| async move {
                                 let holochain_p2p_cell = self.holochain_p2p.to_cell(
                                     cell_id.dna_hash().clone(),
                                     cell_id.agent_pubkey().clone(),
                                 );
 
-                                let env = EnvironmentWrite::new_cell(
+                                let env = EnvironmentWrite::new_with_config(
                                     &dir,
-                                    cell_id.clone(),
+                                    EnvironmentKind::Cell(cell_id.clone()),
                                     keystore.clone(),
+                                    self.cell_env_config,
                                 )?;
                                 Cell::create(
                                     cell_id.clone(),
+                                    app_id,
                                     conductor_handle.clone(),
                                     env,
                                     holochain_p2p_cell,
@@ -645,6 +721,264 @@ where
         Ok(source_chain.dump_as_json().await?)
     }
 
+    pub(super) async fn dump_state(&self, cell_id: &CellId) -> ConductorApiResult<StateDump> {
+        let cell = self.cell_by_id(cell_id)?;
+        let env = cell.env();
+        let source_chain = SourceChainBuf::new(env.clone().into())?;
+        let validation_limbo = ValidationLimboStore::new(env.clone().into())?;
+        let integration_limbo: IntegrationLimboStore =
+            KvBufFresh::new(env.clone().into(), env.get_db(&*db::INTEGRATION_LIMBO)?);
+        let integrated: IntegratedDhtOpsStore =
+            KvBufFresh::new(env.clone().into(), env.get_db(&*db::INTEGRATED_DHT_OPS)?);
+
+        // Read the op stores in one transaction so their counts agree
+        // with each other. The chain was loaded before it, so its head
+        // can be behind the ops by a commit.
+        Ok(fresh_reader!(env, |r| -> DatabaseResult<StateDump> {
+            let validation_limbo: Vec<_> = validation_limbo
+                .iter(&r)?
+                .map(|(k, v)| {
+                    Ok(LimboOpDump {
+                        op_hash: DhtOpHash::with_pre_hashed(k.to_vec()),
+                        op: v.op,
+                        status: v.status,
+                        pending_dependencies: v.pending_dependencies.pending,
+                        time_added: v.time_added,
+                        num_tries: v.num_tries,
+                    })
+                })
+                .collect()?;
+            let integration_limbo = integration_limbo.iter(&r)?.count()?;
+            let mut summary = IntegrationSummary::default();
+            let mut recent_rejections = Vec::new();
+            integrated.iter(&r)?.for_each(|(k, v)| {
+                summary.add(&v.op, &v.validation_status);
+                if v.validation_status == ValidationStatus::Rejected {
+                    recent_rejections.push(RejectedOpDump {
+                        op_hash: DhtOpHash::with_pre_hashed(k.to_vec()),
+                        op: v.op,
+                        when_integrated: v.when_integrated,
+                    });
+                }
+                Ok(())
+            })?;
+            recent_rejections.sort_by(|a, b| b.when_integrated.cmp(&a.when_integrated));
+            recent_rejections.truncate(StateDump::RECENT_REJECTIONS);
+            Ok(StateDump {
+                chain_head: source_chain.chain_head().cloned(),
+                chain_len: source_chain.len(),
+                validation_limbo,
+                integration_limbo,
+                integrated: summary,
+                recent_rejections,
+            })
+        })?)
+    }
+
+    pub(super) async fn ops_for_entry(
+        &self,
+        cell_id: &CellId,
+        entry_hash: &EntryHash,
+    ) -> ConductorApiResult<Vec<DhtOpHash>> {
+        let cell = self.cell_by_id(cell_id)?;
+        let env = cell.env();
+        let index = EntryOpsIndexBuf::new(env)?;
+        Ok(fresh_reader!(env, |r| index
+            .ops_for_entry(&r, entry_hash)?
+            .collect::<Vec<_>>())?)
+    }
+
+    pub(super) async fn integration_summary(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<IntegrationSummary> {
+        let cell = self.cell_by_id(cell_id)?;
+        let env = cell.env();
+        let integrated: IntegratedDhtOpsStore =
+            KvBufFresh::new(env.clone().into(), env.get_db(&*db::INTEGRATED_DHT_OPS)?);
+        Ok(fresh_reader!(env, |r| integrated.iter(&r)?.fold(
+            IntegrationSummary::default(),
+            |mut summary, (_, value)| {
+                summary.add(&value.op, &value.validation_status);
+                Ok(summary)
+            }
+        ))?)
+    }
+
+    pub(super) async fn stream_ops(
+        &self,
+        cell_id: &CellId,
+        from_cursor: Option<OpCursor>,
+        limit: usize,
+    ) -> ConductorApiResult<OpStreamPage> {
+        let cell = self.cell_by_id(cell_id)?;
+        let env = cell.env();
+        let integrated = IntegratedDhtOpsBuf::new(env.clone().into())?;
+        let page = fresh_reader!(env, |r| integrated.page(&r, from_cursor.as_ref(), limit))?;
+        let cursor = page
+            .last()
+            .map(|(cursor, _)| cursor.clone())
+            .or(from_cursor);
+        let ops = page
+            .into_iter()
+            .map(|(cursor, value)| StreamedOp {
+                cursor,
+                op: value.op.to_external(),
+                validation_status: value.validation_status,
+            })
+            .collect();
+        Ok(OpStreamPage { ops, cursor })
+    }
+
+    pub(super) async fn revalidate_all(
+        &self,
+        cell_id: &CellId,
+        ribosome: WasmRibosome,
+    ) -> ConductorResult<Vec<RevalidationChange>> {
+        let cell = self.cell_by_id(cell_id)?;
+        Ok(revalidate_integrated_ops(
+            cell.env().clone().into(),
+            &ribosome,
+            cell.holochain_p2p_cell().clone(),
+        )
+        .await?)
+    }
+
+    pub(super) async fn force_integrate(
+        &self,
+        cell_id: &CellId,
+        op_hash: DhtOpHash,
+        held_op_types: HeldOpTypes,
+    ) -> ConductorResult<()> {
+        let cell = self.cell_by_id(cell_id)?;
+        Ok(cell.force_integrate(op_hash, held_op_types).await?)
+    }
+
+    pub(super) fn resize_arc(
+        &self,
+        cell_id: &CellId,
+        arc: holochain_p2p::dht_arc::DhtArc,
+        min_redundancy: usize,
+    ) -> ConductorResult<()> {
+        self.cell_by_id(cell_id)?.resize_arc(arc, min_redundancy);
+        Ok(())
+    }
+
+    pub(super) async fn validation_receipt_status(
+        &self,
+        cell_id: &CellId,
+        op_hash: &DhtOpHash,
+    ) -> ConductorResult<Option<ValidationReceiptStatus>> {
+        let cell = self.cell_by_id(cell_id)?;
+        Ok(cell.validation_receipt_status(op_hash).await?)
+    }
+
+    pub(super) async fn header_valid_receipts(
+        &self,
+        cell_id: &CellId,
+        header_hash: &HeaderHash,
+    ) -> ConductorResult<usize> {
+        let cell = self.cell_by_id(cell_id)?;
+        Ok(cell.header_valid_receipts(header_hash).await?)
+    }
+
+    pub(super) fn sys_validation_metrics(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<SysValidationMetrics> {
+        Ok(self.cell_by_id(cell_id)?.sys_validation_metrics()?)
+    }
+
+    pub(super) fn sys_validation_limbo_breakdown(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<LimboBreakdown> {
+        Ok(self.cell_by_id(cell_id)?.sys_validation_limbo_breakdown()?)
+    }
+
+    pub(super) fn workflow_pause(
+        &self,
+        cell_id: &CellId,
+        workflow: QueueWorkflow,
+    ) -> ConductorResult<WorkflowPause> {
+        Ok(self
+            .cell_by_id(cell_id)?
+            .queue_consumer_pauses()
+            .get(workflow)
+            .clone())
+    }
+
+    pub(super) fn validation_latency(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<ValidationLatency> {
+        Ok(self.cell_by_id(cell_id)?.validation_latency())
+    }
+
+    pub(super) fn subscribe_validation_receipts(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<tokio::sync::broadcast::Receiver<DhtOpHash>> {
+        Ok(self.cell_by_id(cell_id)?.subscribe_validation_receipts())
+    }
+
+    pub(super) fn subscribe_integration(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<IntegrationEventReceiver> {
+        Ok(self.cell_by_id(cell_id)?.subscribe_integration())
+    }
+
+    /// The limbo depths of every Cell, read from LMDB stats
+    pub(super) fn cell_health(&self) -> ConductorResult<Vec<CellHealth>> {
+        self.cells
+            .iter()
+            .map(|(cell_id, item)| {
+                let env: EnvironmentRead = item.cell.env().clone().into();
+                Ok(CellHealth {
+                    cell_id: cell_id.clone(),
+                    validation_limbo: env.db_len(&DbName::ValidationLimbo)?,
+                    integration_limbo: env.db_len(&DbName::IntegrationLimbo)?,
+                })
+            })
+            .collect()
+    }
+
+    pub(super) async fn cell_resource_usage(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<CellResourceReport> {
+        let cell = self.cell_by_id(cell_id)?;
+        let env: EnvironmentRead = cell.env().clone().into();
+        let size = |dbs: &[DbName]| -> DatabaseResult<usize> {
+            dbs.iter().map(|db| env.db_size(db)).sum()
+        };
+        let module_cache = self
+            .dna_store()
+            .get(cell_id.dna_hash())
+            .map(|dna_file| dna_file.code().values().map(|wasm| wasm.code.len()).sum())
+            .unwrap_or_default();
+        Ok(CellResourceReport {
+            element_store: size(&[
+                DbName::ElementVaultHeaders,
+                DbName::ElementVaultPublicEntries,
+                DbName::ElementVaultPrivateEntries,
+                DbName::ElementVaultEntryChunks,
+            ])?,
+            metadata: size(&[
+                DbName::MetaVaultSys,
+                DbName::MetaVaultLinks,
+                DbName::MetaVaultMisc,
+            ])?,
+            integration: size(&[
+                DbName::IntegratedDhtOps,
+                DbName::IntegratedDhtOpsSequence,
+                DbName::IntegrationLimbo,
+            ])?,
+            module_cache,
+        })
+    }
+
     #[cfg(test)]
     pub(super) async fn get_state_from_handle(&self) -> ConductorResult<ConductorState> {
         self.get_state().await
@@ -684,6 +1018,7 @@ where
             dna_store,
             keystore,
             root_env_dir,
+            cell_env_config: EnvironmentConfig::default(),
             holochain_p2p,
         })
     }
@@ -737,6 +1072,8 @@ mod builder {
         config: ConductorConfig,
         dna_store: DS,
         keystore: Option<KeystoreSender>,
+        op_egress_filter: Option<OpEgressFilterRef>,
+        op_ingress_filter: Option<OpIngressFilterRef>,
         #[cfg(test)]
         state: Option<ConductorState>,
         #[cfg(test)]
@@ -782,6 +1119,8 @@ mod builder {
                 }
             }
 
+            self.config.check()?;
+
             let _ = holochain_crypto::crypto_init_sodium();
 
             let keystore = if let Some(keystore) = self.keystore {
@@ -816,7 +1155,11 @@ mod builder {
             let state = self.state;
 
             let Self {
-                dna_store, config, ..
+                dna_store,
+                config,
+                op_egress_filter,
+                op_ingress_filter,
+                ..
             } = self;
 
             let (holochain_p2p, p2p_evt) = holochain_p2p::spawn_holochain_p2p().await?;
@@ -834,23 +1177,110 @@ mod builder {
             #[cfg(test)]
             let conductor = Self::update_fake_state(state, conductor).await?;
 
-            Self::finish(conductor, config, p2p_evt).await
+            Self::finish(
+                conductor,
+                config,
+                op_egress_filter,
+                op_ingress_filter,
+                p2p_evt,
+            )
+            .await
         }
 
         async fn finish(
-            conductor: Conductor<DS>,
+            mut conductor: Conductor<DS>,
             conductor_config: ConductorConfig,
+            op_egress_filter: Option<OpEgressFilterRef>,
+            op_ingress_filter: Option<OpIngressFilterRef>,
             p2p_evt: holochain_p2p::event::HolochainP2pEventReceiver,
         ) -> ConductorResult<ConductorHandle> {
+            if let Some(cell_map_size) = conductor_config.cell_map_size {
+                conductor.cell_env_config.map_size = cell_map_size.into();
+            }
+            if let Some(entry_chunk_threshold) = conductor_config.entry_chunk_threshold {
+                conductor.cell_env_config.entry_chunk_threshold = entry_chunk_threshold;
+            }
+
             // Get data before handle
             let keystore = conductor.keystore.clone();
             let holochain_p2p = conductor.holochain_p2p.clone();
+            let held_op_types = conductor_config
+                .held_op_types
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| (c.dna_hash.clone(), c.into()))
+                .collect();
+            let validation_profiles = conductor_config
+                .validation_profiles
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| (c.cell_id, c.profile))
+                .collect();
+            let read_only_cells = conductor_config
+                .read_only_cells
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+
+            let module_cache = ModuleCache::new(
+                conductor_config
+                    .max_cached_modules
+                    .unwrap_or(DEFAULT_MAX_CACHED_MODULES),
+            );
+            let host_fn_executor = HostFnExecutor::new(
+                conductor_config
+                    .max_blocking_host_fns
+                    .unwrap_or(DEFAULT_MAX_BLOCKING_HOST_FNS),
+            );
+
+            let workflow_runtimes = WorkflowRuntimes::new(
+                &conductor_config
+                    .workflow_runtimes
+                    .clone()
+                    .unwrap_or_default(),
+            )?;
 
             // Create handle
             let handle: ConductorHandle = Arc::new(ConductorHandleImpl {
                 conductor: RwLock::new(conductor),
                 keystore,
                 holochain_p2p,
+                network_breaker: conductor_config
+                    .network_breaker
+                    .as_ref()
+                    .map(NetworkBreaker::from)
+                    .unwrap_or_default(),
+                held_op_types,
+                author_is_authority: conductor_config.author_is_authority,
+                max_pending_dependency_depth: conductor_config
+                    .max_pending_dependency_depth
+                    .unwrap_or(DEFAULT_MAX_PENDING_DEPENDENCY_DEPTH),
+                sys_validation_dep_cache: conductor_config.sys_validation_dep_cache.clone(),
+                op_egress_filter: op_egress_filter.unwrap_or_else(|| Arc::new(SendAllOps)),
+                op_ingress_filter: op_ingress_filter.unwrap_or_else(|| Arc::new(AcceptAllOps)),
+                workflow_runtimes,
+                zome_call_limit: conductor_config.zome_call_limit.clone(),
+                zome_call_timeout: conductor_config
+                    .zome_call_timeout_secs
+                    .map(std::time::Duration::from_secs),
+                host_fn_budget: conductor_config.host_fn_budget.clone().unwrap_or_default(),
+                source_chain_read_cache: conductor_config
+                    .source_chain_read_cache
+                    .unwrap_or(DEFAULT_READ_CACHE_CAPACITY),
+                signal_broadcaster: tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE).0,
+                cap_nonces: conductor_config.cap_nonces.clone().unwrap_or_default(),
+                gossip_request_policy: conductor_config
+                    .gossip_request_policy
+                    .clone()
+                    .unwrap_or_default(),
+                validation_profiles,
+                read_only_cells,
+                module_cache,
+                host_fn_executor,
+                parallel_callbacks: conductor_config.parallel_callbacks,
             });
 
             handle.add_dnas().await?;
@@ -882,6 +1312,20 @@ mod builder {
             self
         }
 
+        /// Set the filter each Cell's publish workflow consults before
+        /// sending an op to the network
+        pub fn with_op_egress_filter(mut self, filter: OpEgressFilterRef) -> Self {
+            self.op_egress_filter = Some(filter);
+            self
+        }
+
+        /// Set the filter each Cell consults before an incoming op
+        /// enters its validation limbo
+        pub fn with_op_ingress_filter(mut self, filter: OpIngressFilterRef) -> Self {
+            self.op_ingress_filter = Some(filter);
+            self
+        }
+
         #[cfg(test)]
         /// Sets some fake conductor state for tests
         pub fn fake_state(mut self, state: ConductorState) -> Self {
@@ -933,7 +1377,14 @@ mod builder {
             #[cfg(test)]
             let conductor = Self::update_fake_state(self.state, conductor).await?;
 
-            Self::finish(conductor, self.config, p2p_evt).await
+            Self::finish(
+                conductor,
+                self.config,
+                self.op_egress_filter,
+                self.op_ingress_filter,
+                p2p_evt,
+            )
+            .await
         }
     }
 }
@@ -960,6 +1411,7 @@ pub mod tests {
     use super::*;
     use super::{Conductor, ConductorState};
     use crate::conductor::dna_store::MockDnaStore;
+    use crate::test_utils::{mock_dna_store, shutdown_conductor, test_dna_file};
     use holochain_state::test_utils::{test_conductor_env, test_wasm_env, TestEnvironment};
     use holochain_types::test_utils::fake_cell_id;
 
@@ -1028,4 +1480,580 @@ pub mod tests {
             .unwrap();
         assert_eq!(state, conductor.get_state_from_handle().await.unwrap());
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn element_store_usage_grows_with_commits() {
+        use crate::{core::ribosome::ZomeCallInvocation, test_utils::setup_app};
+        use holochain_types::{app::InstalledCell, test_utils::fake_agent_pubkey_1};
+        use holochain_wasm_test_utils::TestWasm;
+        use holochain_zome_types::ExternInput;
+        use std::convert::TryInto;
+
+        let dna_file = test_dna_file(
+            "cell_resource_usage_test",
+            "8c0d1a3e-3b7e-4d6a-9c7e-5f2a1b6c4d3e",
+            vec![TestWasm::Create],
+        )
+        .await;
+        let agent = fake_agent_pubkey_1();
+        let cell_id = CellId::new(dna_file.dna_hash().to_owned(), agent.clone());
+        let installed_cell = InstalledCell::new(cell_id.clone(), "alice_handle".into());
+
+        let dna_store = mock_dna_store(vec![dna_file]);
+
+        let (_tmpdir, _app_api, handle) =
+            setup_app(vec![("app", vec![(installed_cell, None)])], dna_store).await;
+
+        let before = handle.cell_resource_usage(&cell_id).await.unwrap();
+        assert!(before.module_cache > 0);
+
+        // Sizes are counted in whole pages, so commit enough to fill a new one
+        for _ in 0..30 {
+            handle
+                .call_zome(ZomeCallInvocation {
+                    cell_id: cell_id.clone(),
+                    zome_name: TestWasm::Create.into(),
+                    cap: None,
+                    fn_name: "create_entry".into(),
+                    payload: ExternInput::new(().try_into().unwrap()),
+                    provenance: agent.clone(),
+                })
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        let after = handle.cell_resource_usage(&cell_id).await.unwrap();
+        assert!(after.element_store > before.element_store);
+
+        shutdown_conductor(handle).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn integration_events_arrive_for_authored_ops() {
+        use crate::{core::ribosome::ZomeCallInvocation, test_utils::setup_app};
+        use holochain_serialized_bytes::prelude::*;
+        use holochain_types::{
+            app::InstalledCell, test_utils::fake_agent_pubkey_1, validate::ValidationStatus, Entry,
+        };
+        use holochain_wasm_test_utils::TestWasm;
+        use holochain_zome_types::ExternInput;
+        use std::{collections::HashSet, convert::TryInto, time::Duration};
+
+        /// The entry the create wasm commits
+        #[derive(Serialize, Deserialize, SerializedBytes)]
+        struct Post(String);
+
+        let dna_file = test_dna_file(
+            "integration_events_test",
+            "2f6e9d1c-7a4b-4e3f-8b2d-6c1a9e5f3b7d",
+            vec![TestWasm::Create],
+        )
+        .await;
+        let agent = fake_agent_pubkey_1();
+        let cell_id = CellId::new(dna_file.dna_hash().to_owned(), agent.clone());
+        let installed_cell = InstalledCell::new(cell_id.clone(), "alice_handle".into());
+
+        let dna_store = mock_dna_store(vec![dna_file]);
+
+        let (_tmpdir, _app_api, handle) =
+            setup_app(vec![("app", vec![(installed_cell, None)])], dna_store).await;
+
+        let mut events = handle.subscribe_integration(&cell_id).await.unwrap();
+        handle
+            .call_zome(ZomeCallInvocation {
+                cell_id: cell_id.clone(),
+                zome_name: TestWasm::Create.into(),
+                cap: None,
+                fn_name: "create_entry".into(),
+                payload: ExternInput::new(().try_into().unwrap()),
+                provenance: agent.clone(),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        let entry_hash =
+            EntryHash::with_data_sync(&Entry::app(Post("foo".into()).try_into().unwrap()).unwrap());
+
+        // Every op for the new entry is announced as soon as it's integrated
+        let mut seen = HashSet::new();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let event = events.recv().await.unwrap();
+                assert_eq!(event.status, ValidationStatus::Valid);
+                seen.insert(event.op_hash);
+                let integrated = handle.ops_for_entry(&cell_id, &entry_hash).await.unwrap();
+                if !integrated.is_empty() && integrated.iter().all(|h| seen.contains(h)) {
+                    break;
+                }
+            }
+        })
+        .await
+        .expect("the entry's ops were never integrated");
+
+        shutdown_conductor(handle).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn streamed_ops_resume_from_a_cursor_without_gaps() {
+        use crate::{core::ribosome::ZomeCallInvocation, test_utils::setup_app};
+        use holochain_types::{app::InstalledCell, test_utils::fake_agent_pubkey_1};
+        use holochain_wasm_test_utils::TestWasm;
+        use holochain_zome_types::ExternInput;
+        use std::{collections::HashSet, convert::TryInto, time::Duration};
+
+        let dna_file = test_dna_file(
+            "stream_ops_test",
+            "7d3b9e41-0c5a-4f86-a2e7-1b9c4d6f8a30",
+            vec![TestWasm::Create],
+        )
+        .await;
+        let agent = fake_agent_pubkey_1();
+        let cell_id = CellId::new(dna_file.dna_hash().to_owned(), agent.clone());
+        let installed_cell = InstalledCell::new(cell_id.clone(), "alice_handle".into());
+
+        let dna_store = mock_dna_store(vec![dna_file]);
+
+        let (_tmpdir, _app_api, handle) =
+            setup_app(vec![("app", vec![(installed_cell, None)])], dna_store).await;
+
+        for _ in 0..3 {
+            handle
+                .call_zome(ZomeCallInvocation {
+                    cell_id: cell_id.clone(),
+                    zome_name: TestWasm::Create.into(),
+                    cap: None,
+                    fn_name: "create_entry".into(),
+                    payload: ExternInput::new(().try_into().unwrap()),
+                    provenance: agent.clone(),
+                })
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        // Wait for everything to be integrated
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let health = handle.health_summary().await.unwrap();
+                let cell = health
+                    .cells
+                    .into_iter()
+                    .find(|cell| cell.cell_id == cell_id)
+                    .unwrap();
+                let summary = handle.integration_summary(&cell_id).await.unwrap();
+                if cell.validation_limbo == 0 && cell.integration_limbo == 0 && summary.total() > 0
+                {
+                    break;
+                }
+                tokio::time::delay_for(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("the ops were never integrated");
+        let total = handle.integration_summary(&cell_id).await.unwrap().total();
+
+        let everything = handle.stream_ops(&cell_id, None, usize::MAX).await.unwrap();
+        assert_eq!(everything.ops.len(), total);
+
+        // Page through a few at a time, resuming from each cursor
+        let mut streamed = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = handle
+                .stream_ops(&cell_id, cursor.clone(), 3)
+                .await
+                .unwrap();
+            assert!(page.ops.len() <= 3);
+            if page.ops.is_empty() {
+                assert_eq!(page.cursor, cursor);
+                break;
+            }
+            cursor = page.cursor;
+            streamed.extend(page.ops);
+        }
+
+        // Every op exactly once, in the same order as a single read
+        let unique: HashSet<_> = streamed.iter().map(|op| op.cursor.clone()).collect();
+        assert_eq!(unique.len(), total);
+        assert_eq!(streamed, everything.ops);
+        assert!(streamed.windows(2).all(|w| w[0].cursor < w[1].cursor));
+
+        shutdown_conductor(handle).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn state_dump_shows_awaited_dependencies_and_integrated_ops() {
+        use crate::{
+            core::{
+                ribosome::ZomeCallInvocation,
+                state::validation_db::{
+                    ValidationLimboStatus, ValidationLimboStore, ValidationLimboValue,
+                },
+                workflow::sys_validation_workflow::types::PendingDependencies,
+            },
+            test_utils::setup_app,
+        };
+        use ::fixt::prelude::*;
+        use holo_hash::{fixt::*, AnyDhtHash};
+        use holochain_state::error::DatabaseError;
+        use holochain_types::{
+            app::InstalledCell, dht_op::DhtOpLight, test_utils::fake_agent_pubkey_1, Timestamp,
+        };
+        use holochain_wasm_test_utils::TestWasm;
+        use holochain_zome_types::ExternInput;
+        use std::{convert::TryInto, time::Duration};
+
+        let dna_file = test_dna_file(
+            "state_dump_test",
+            "2f64c1a8-93be-4d07-8a5c-e0b7d9132f4e",
+            vec![TestWasm::Create],
+        )
+        .await;
+        let agent = fake_agent_pubkey_1();
+        let cell_id = CellId::new(dna_file.dna_hash().to_owned(), agent.clone());
+        let installed_cell = InstalledCell::new(cell_id.clone(), "alice_handle".into());
+
+        let dna_store = mock_dna_store(vec![dna_file]);
+
+        let (_tmpdir, _app_api, handle) =
+            setup_app(vec![("app", vec![(installed_cell, None)])], dna_store).await;
+
+        handle
+            .call_zome(ZomeCallInvocation {
+                cell_id: cell_id.clone(),
+                zome_name: TestWasm::Create.into(),
+                cap: None,
+                fn_name: "create_entry".into(),
+                payload: ExternInput::new(().try_into().unwrap()),
+                provenance: agent.clone(),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Wait for the authored ops to be integrated
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let dump = handle.dump_state(&cell_id).await.unwrap();
+                if dump.validation_limbo.is_empty()
+                    && dump.integration_limbo == 0
+                    && dump.integrated.total() > 0
+                {
+                    break;
+                }
+                tokio::time::delay_for(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("the ops were never integrated");
+
+        // Park an op that is waiting on a dependency nobody has
+        let dependency: AnyDhtHash = fixt!(EntryHash).into();
+        let op_hash = fixt!(DhtOpHash);
+        let env = handle.get_cell_env(&cell_id).await.unwrap();
+        let mut validation_limbo = ValidationLimboStore::new(env.clone().into()).unwrap();
+        validation_limbo
+            .put(
+                op_hash.clone(),
+                ValidationLimboValue {
+                    status: ValidationLimboStatus::AwaitingAppDeps(vec![dependency.clone()]),
+                    pending_dependencies: PendingDependencies::new(),
+                    op: DhtOpLight::RegisterAgentActivity(fixt!(HeaderHash), agent.clone().into()),
+                    basis: agent.clone().into(),
+                    time_added: Timestamp::now(),
+                    last_try: None,
+                    num_tries: 1,
+                    num_app_validations: 1,
+                },
+            )
+            .unwrap();
+        env.guard()
+            .with_commit::<DatabaseError, _, _>(|writer| validation_limbo.flush_to_txn_ref(writer))
+            .unwrap();
+
+        let dump = handle.dump_state(&cell_id).await.unwrap();
+        assert_eq!(dump.validation_limbo.len(), 1);
+        let pending = &dump.validation_limbo[0];
+        assert_eq!(pending.op_hash, op_hash);
+        assert_eq!(
+            pending.status,
+            ValidationLimboStatus::AwaitingAppDeps(vec![dependency])
+        );
+        assert_eq!(
+            dump.integrated,
+            handle.integration_summary(&cell_id).await.unwrap()
+        );
+        assert!(dump.integrated.total() > 0);
+        assert!(dump.recent_rejections.is_empty());
+        assert!(dump.chain_head.is_some());
+
+        shutdown_conductor(handle).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn migrated_chain_opens_at_genesis() {
+        use crate::{
+            core::state::source_chain::SourceChain,
+            test_utils::{install_app, setup_app},
+        };
+        use holochain_state::{buffer::BufferedStore, env::WriteManager};
+        use holochain_types::{app::InstalledCell, test_utils::fake_agent_pubkey_1};
+        use holochain_wasm_test_utils::TestWasm;
+        use holochain_zome_types::header::builder;
+        use matches::assert_matches;
+
+        let mut dna_files = Vec::new();
+        for uuid in &[
+            "6f1f3e0a-9a3c-4b55-8d2e-7c4b1e0f5a21",
+            "0b7d5c2e-4e1a-4f8b-a6c3-2d9e8f1a7b34",
+        ] {
+            dna_files.push(test_dna_file("migrated_chain_test", uuid, vec![TestWasm::Foo]).await);
+        }
+        let agent = fake_agent_pubkey_1();
+        let old_cell_id = CellId::new(dna_files[0].dna_hash().to_owned(), agent.clone());
+        let new_cell_id = CellId::new(dna_files[1].dna_hash().to_owned(), agent);
+
+        let dna_store = mock_dna_store(dna_files);
+
+        let old_cell = InstalledCell::new(old_cell_id.clone(), "old".into());
+        let (_tmpdir, _app_api, handle) =
+            setup_app(vec![("old_app", vec![(old_cell, None)])], dna_store).await;
+
+        // Close the old chain in favour of the new DNA
+        let env = handle.get_cell_env(&old_cell_id).await.unwrap();
+        let mut chain = SourceChain::new(env.clone().into()).unwrap();
+        chain
+            .put(
+                builder::CloseChain::new(new_cell_id.dna_hash().clone()),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        env.guard()
+            .with_commit(|writer| chain.flush_to_txn(writer))
+            .unwrap();
+
+        let new_cell = InstalledCell::new(new_cell_id.clone(), "new".into());
+        install_app("new_app", vec![(new_cell, None)], handle.clone()).await;
+
+        let env = handle.get_cell_env(&new_cell_id).await.unwrap();
+        let chain = SourceChain::new(env.into()).unwrap();
+        let head = chain
+            .get_header(chain.chain_head().unwrap())
+            .unwrap()
+            .unwrap();
+        assert_matches!(
+            head.header(),
+            Header::OpenChain(open) if &open.prev_dna_hash == old_cell_id.dna_hash()
+        );
+
+        shutdown_conductor(handle).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn module_cache_evicts_past_its_bound() {
+        use crate::{core::ribosome::ZomeCallInvocation, test_utils::install_app};
+        use holochain_types::{app::InstalledCell, test_utils::fake_agent_pubkey_1};
+        use holochain_wasm_test_utils::TestWasm;
+        use holochain_zome_types::{ExternInput, ZomeCallResponse};
+        use std::convert::TryInto;
+
+        // One DNA for each wasm so there are more modules than the cache holds
+        let wasms = vec![
+            (TestWasm::Foo, "foo"),
+            (TestWasm::Create, "create_entry"),
+            (TestWasm::WhoAmI, "whoami"),
+        ];
+        let mut dna_files = Vec::new();
+        for (wasm, _) in &wasms {
+            dna_files.push(
+                test_dna_file(
+                    "module_cache_test",
+                    "2f6b1c9e-7a4d-4e1b-8f3a-6d5c0b9e1a27",
+                    vec![*wasm],
+                )
+                .await,
+            );
+        }
+        let agent = fake_agent_pubkey_1();
+        let cell_ids: Vec<_> = dna_files
+            .iter()
+            .map(|dna_file| CellId::new(dna_file.dna_hash().to_owned(), agent.clone()))
+            .collect();
+        let cells = cell_ids
+            .iter()
+            .enumerate()
+            .map(|(i, cell_id)| {
+                (
+                    InstalledCell::new(cell_id.clone(), format!("cell_{}", i).into()),
+                    None,
+                )
+            })
+            .collect();
+
+        let dna_store = mock_dna_store(dna_files);
+
+        let test_env = test_conductor_env();
+        let TestEnvironment {
+            env: wasm_env,
+            tmpdir: _tmpdir,
+        } = test_wasm_env();
+        let handle = ConductorBuilder::with_mock_dna_store(dna_store)
+            .config(ConductorConfig {
+                max_cached_modules: Some(1),
+                ..Default::default()
+            })
+            .test(test_env, wasm_env)
+            .await
+            .unwrap();
+        install_app("app", cells, handle.clone()).await;
+
+        let before = handle.module_cache_metrics();
+
+        // Every zome still works even though its module keeps being evicted
+        for _ in 0..2 {
+            for ((wasm, fn_name), cell_id) in wasms.iter().zip(cell_ids.iter()) {
+                let response = handle
+                    .call_zome(ZomeCallInvocation {
+                        cell_id: cell_id.clone(),
+                        zome_name: (*wasm).into(),
+                        cap: None,
+                        fn_name: (*fn_name).into(),
+                        payload: ExternInput::new(().try_into().unwrap()),
+                        provenance: agent.clone(),
+                    })
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert!(matches!(response, ZomeCallResponse::Ok(_)));
+            }
+        }
+
+        let after = handle.module_cache_metrics();
+        assert!(after.evictions > before.evictions);
+        assert!(after.misses > before.misses);
+
+        shutdown_conductor(handle).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn read_only_cells_get_but_never_author() {
+        use crate::{
+            core::state::{dht_op_integration::AuthoredDhtOpsStore, source_chain::SourceChain},
+            test_utils::install_app,
+        };
+        use fallible_iterator::FallibleIterator;
+        use holochain_state::{
+            buffer::KvBufFresh,
+            db::{GetDb, AUTHORED_DHT_OPS},
+            fresh_reader_test,
+        };
+        use holochain_types::{
+            app::InstalledCell,
+            test_utils::{fake_agent_pubkey_1, fake_agent_pubkey_2},
+        };
+        use holochain_wasm_test_utils::TestWasm;
+        use holochain_zome_types::GetOutput;
+        use std::time::Duration;
+
+        let dna_file = test_dna_file(
+            "read_only_cells_test",
+            "4c9e2a71-3b8d-4f05-9e6a-d1f7b2c8a053",
+            vec![TestWasm::Create],
+        )
+        .await;
+        let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), fake_agent_pubkey_1());
+        let bob_cell_id = CellId::new(dna_file.dna_hash().to_owned(), fake_agent_pubkey_2());
+
+        let dna_store = mock_dna_store(vec![dna_file]);
+
+        let test_env = test_conductor_env();
+        let TestEnvironment {
+            env: wasm_env,
+            tmpdir: _tmpdir,
+        } = test_wasm_env();
+        let handle = ConductorBuilder::with_mock_dna_store(dna_store)
+            .config(ConductorConfig {
+                read_only_cells: Some(vec![bob_cell_id.clone()]),
+                ..Default::default()
+            })
+            .test(test_env, wasm_env)
+            .await
+            .unwrap();
+        install_app(
+            "app",
+            vec![
+                (
+                    InstalledCell::new(alice_cell_id.clone(), "alice_handle".into()),
+                    None,
+                ),
+                (
+                    InstalledCell::new(bob_cell_id.clone(), "bob_handle".into()),
+                    None,
+                ),
+            ],
+            handle.clone(),
+        )
+        .await;
+
+        let header_hash: HeaderHash = handle
+            .call_zome_fn(alice_cell_id.clone(), TestWasm::Create, "create_entry", ())
+            .await
+            .unwrap();
+
+        // Bob can still get what alice authored
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let output: GetOutput = handle
+                    .call_zome_fn(bob_cell_id.clone(), TestWasm::Create, "get_entry", ())
+                    .await
+                    .unwrap();
+                if let Some(element) = output.into_inner() {
+                    assert_eq!(*element.header_address(), header_hash);
+                    break;
+                }
+                tokio::time::delay_for(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("bob never got alice's entry");
+
+        // But can't commit
+        let err = handle
+            .call_zome_fn::<_, HeaderHash>(
+                bob_cell_id.clone(),
+                TestWasm::Create,
+                "create_entry",
+                (),
+            )
+            .await
+            .unwrap_err();
+        assert!(format!("{:?}", err).contains("CellReadOnly"));
+
+        // And has never produced an op, not even for its genesis
+        let authored_count = |env: EnvironmentWrite| {
+            let authored: AuthoredDhtOpsStore =
+                KvBufFresh::new(env.clone().into(), env.get_db(&*AUTHORED_DHT_OPS).unwrap());
+            fresh_reader_test!(env, |r| authored.iter(&r).unwrap().count().unwrap())
+        };
+        assert_eq!(
+            authored_count(handle.get_cell_env(&bob_cell_id).await.unwrap()),
+            0
+        );
+        assert!(authored_count(handle.get_cell_env(&alice_cell_id).await.unwrap()) > 0);
+
+        // Nor run init, which would commit to its chain
+        let has_initialized =
+            |env: EnvironmentWrite| SourceChain::new(env.into()).unwrap().has_initialized();
+        assert!(!has_initialized(
+            handle.get_cell_env(&bob_cell_id).await.unwrap()
+        ));
+        assert!(has_initialized(
+            handle.get_cell_env(&alice_cell_id).await.unwrap()
+        ));
+
+        shutdown_conductor(handle).await;
+    }
 }
@@ -1,25 +1,49 @@
 #![deny(missing_docs)]
 //! This module is used to configure the conductor
 
+use holochain_types::cell::CellId;
 use serde::{Deserialize, Serialize};
 
 mod admin_interface_config;
+mod cap_nonce_config;
+mod dep_cache_config;
 mod dpki_config;
+mod gossip_request_policy_config;
+mod held_op_types_config;
+mod host_fn_budget_config;
+mod map_size_config;
+mod network_breaker_config;
 mod network_config;
 mod passphrase_service_config;
+mod validation_profile_config;
+mod workflow_runtime_config;
+mod zome_call_limit_config;
 //mod logger_config;
 //mod signal_config;
 use super::{
     error::{ConductorError, ConductorResult},
     paths::EnvironmentRootPath,
 };
+use crate::core::state::element_buf::CHUNK_SIZE;
 
 pub use crate::conductor::interface::InterfaceDriver;
 pub use admin_interface_config::AdminInterfaceConfig;
+pub use cap_nonce_config::CapNonceConfig;
+pub use dep_cache_config::DepCacheConfig;
 pub use dpki_config::DpkiConfig;
+pub use gossip_request_policy_config::GossipRequestPolicy;
+pub use held_op_types_config::HeldOpTypesConfig;
+pub use host_fn_budget_config::HostFnBudgetConfig;
+pub use map_size_config::MapSizeConfig;
 //pub use logger_config::LoggerConfig;
+pub use network_breaker_config::NetworkBreakerConfig;
 pub use network_config::NetworkConfig;
 pub use passphrase_service_config::PassphraseServiceConfig;
+pub use validation_profile_config::{
+    ValidationCategory, ValidationProfile, ValidationProfileConfig, ValidationResponse,
+};
+pub use workflow_runtime_config::WorkflowRuntimeConfig;
+pub use zome_call_limit_config::{WhenBusy, ZomeCallLimitConfig};
 //pub use signal_config::SignalConfig;
 use std::path::Path;
 
@@ -38,9 +62,61 @@ pub struct ConductorConfig {
     #[serde(default)]
     pub use_dangerous_test_keystore: bool,
 
+    /// Let the ops this conductor authored take a lighter sys validation path
+    /// which skips fetching dependencies from the network.
+    /// The author already holds its own chain. App validation still runs.
+    #[serde(default)]
+    pub author_is_authority: bool,
+
+    /// The longest chain of ops still pending validation that sys validation
+    /// lets an op wait on. Ops past it are parked until their dependencies validate.
+    /// If omitted, a default bound is used.
+    #[serde(default)]
+    pub max_pending_dependency_depth: Option<usize>,
+
+    /// The most compiled wasm modules to keep in memory across all DNAs.
+    /// The least recently used module is evicted, and compiled again on its next use.
+    /// If omitted, a default bound is used.
+    #[serde(default)]
+    pub max_cached_modules: Option<usize>,
+
+    /// How many headers and elements each zome call keeps in memory
+    /// as it reads its own source chain.
+    /// If omitted, 64 are kept.
+    #[serde(default)]
+    pub source_chain_read_cache: Option<usize>,
+
+    /// The most host functions that can block on the network or database at once
+    /// across all Cells of this conductor. Past this, host functions wait and a warning is logged.
+    /// The conductor's blocking thread pool is sized to fit this many.
+    /// Must be at least 1. If omitted, 512 can block at once.
+    #[serde(default)]
+    pub max_blocking_host_fns: Option<usize>,
+
+    /// The most zomes an order-independent callback like `validate` is called on at once.
+    /// If omitted, zomes are called one at a time.
+    #[serde(default)]
+    pub parallel_callbacks: Option<usize>,
+
+    /// How large the memory map of each Cell's environment starts and how large it may grow.
+    /// If omitted, maps start at 100MB and grow up to 1.6GB.
+    #[serde(default)]
+    pub cell_map_size: Option<MapSizeConfig>,
+
+    /// Public app entries with more serialized bytes than this are stored
+    /// in content addressed chunks rather than whole.
+    /// Must be at least the chunk size of 256KiB. If omitted, entries over 1MB are chunked.
+    #[serde(default)]
+    pub entry_chunk_threshold: Option<usize>,
+
     /// Config options for the network module. Optional.
     pub network: Option<NetworkConfig>,
 
+    /// When cascade retrievals stop going to an unreachable network. Optional.
+    /// By default 5 consecutive failures stop them for 30 seconds.
+    #[serde(default)]
+    pub network_breaker: Option<NetworkBreakerConfig>,
+
     /// Optional URI for a websocket connection to an outsourced signing service.
     /// Bootstrapping step for Holo closed-alpha.
     /// If set, all agents with holo_remote_key = true will be emulated by asking for signatures
@@ -71,6 +147,48 @@ pub struct ConductorConfig {
 
     /// Setup admin interfaces to control this conductor through a websocket connection
     pub admin_interfaces: Option<Vec<AdminInterfaceConfig>>,
+
+    /// Disable holding some types of DhtOp for specific DNAs. Optional.
+    /// By default every type is held.
+    pub held_op_types: Option<Vec<HeldOpTypesConfig>>,
+
+    /// Limit the zome calls running at once on each Cell. Optional.
+    /// By default there is no limit.
+    pub zome_call_limit: Option<ZomeCallLimitConfig>,
+
+    /// Cancel zome calls still running after this many seconds. Optional.
+    /// A cancelled call stops at its next host function that waits on the network or database.
+    /// By default calls run until they finish.
+    pub zome_call_timeout_secs: Option<u64>,
+
+    /// Limit the host functions that reach the network each zome call can make. Optional.
+    /// By default zome calls are unlimited.
+    pub host_fn_budget: Option<HostFnBudgetConfig>,
+
+    /// Bound the nonces each Cell remembers to reject replayed remote calls. Optional.
+    /// By default up to 10,000 nonces are remembered, for calls expiring within 5 minutes.
+    pub cap_nonces: Option<CapNonceConfig>,
+
+    /// Pin classes of workflow to dedicated thread pools. Optional.
+    /// By default every workflow runs on the conductor's runtime.
+    pub workflow_runtimes: Option<WorkflowRuntimeConfig>,
+
+    /// How Cells retry fetching ops they are missing from their peers. Optional.
+    /// By default a missing op is requested once from the neighborhood.
+    pub gossip_request_policy: Option<GossipRequestPolicy>,
+
+    /// How strictly sys validation treats failed checks on specific Cells. Optional.
+    /// By default every Cell is [ValidationProfile::Strict].
+    pub validation_profiles: Option<Vec<ValidationProfileConfig>>,
+
+    /// Let sys validation reuse the dependencies it retrieved in a pass. Optional.
+    /// By default every dependency is retrieved and validated by this node.
+    pub sys_validation_dep_cache: Option<DepCacheConfig>,
+
+    /// Cells that only observe the DHT and never author. Optional.
+    /// They still take in gossip, validate and serve gets,
+    /// but every commit fails and they never produce or publish ops.
+    pub read_only_cells: Option<Vec<CellId>>,
     //
     //
     // /// Which signals to emit
@@ -99,7 +217,31 @@ impl ConductorConfig {
             }
             _ => err.into(),
         })?;
-        config_from_toml(&config_toml)
+        let config: ConductorConfig = config_from_toml(&config_toml)?;
+        config.check()?;
+        Ok(config)
+    }
+
+    /// Check for values that deserialize but that the conductor can't run with
+    pub fn check(&self) -> ConductorResult<()> {
+        if let Some(zome_call_limit) = &self.zome_call_limit {
+            zome_call_limit.check()?;
+        }
+        if let Some(cap_nonces) = &self.cap_nonces {
+            cap_nonces.check()?;
+        }
+        if self.max_blocking_host_fns == Some(0) {
+            return Err(ConductorError::ConfigError(
+                "max_blocking_host_fns must be at least 1".to_string(),
+            ));
+        }
+        if self.entry_chunk_threshold.map_or(false, |t| t < CHUNK_SIZE) {
+            return Err(ConductorError::ConfigError(format!(
+                "entry_chunk_threshold must be at least the chunk size of {} bytes",
+                CHUNK_SIZE
+            )));
+        }
+        Ok(())
     }
 }
 
@@ -122,6 +264,52 @@ pub mod tests {
         // successful load test in conductor/interactive
     }
 
+    #[test]
+    fn test_config_load_toml_rejects_unusable_values() {
+        let dir = tempdir::TempDir::new("conductor-config").unwrap();
+        let path = dir.path().join("conductor-config.toml");
+        std::fs::write(
+            &path,
+            r#"
+    environment_path = "/path/to/env"
+
+    [zome_call_limit]
+    max_concurrent_calls = 0
+    "#,
+        )
+        .unwrap();
+        let result = ConductorConfig::load_toml(&path);
+        assert_matches!(result, Err(ConductorError::ConfigError(_)));
+    }
+
+    #[test]
+    fn test_config_rejects_no_blocking_host_fns() {
+        let config = ConductorConfig {
+            max_blocking_host_fns: Some(0),
+            ..Default::default()
+        };
+        assert_matches!(config.check(), Err(ConductorError::ConfigError(_)));
+        let config = ConductorConfig {
+            max_blocking_host_fns: Some(1),
+            ..Default::default()
+        };
+        assert_matches!(config.check(), Ok(()));
+    }
+
+    #[test]
+    fn test_config_rejects_chunk_threshold_below_chunk_size() {
+        let config = ConductorConfig {
+            entry_chunk_threshold: Some(CHUNK_SIZE - 1),
+            ..Default::default()
+        };
+        assert_matches!(config.check(), Err(ConductorError::ConfigError(_)));
+        let config = ConductorConfig {
+            entry_chunk_threshold: Some(CHUNK_SIZE),
+            ..Default::default()
+        };
+        assert_matches!(config.check(), Ok(()));
+    }
+
     #[test]
     fn test_config_bad_toml() {
         let result: ConductorResult<ConductorConfig> = config_from_toml("this isn't toml");
@@ -142,6 +330,7 @@ pub mod tests {
             ConductorConfig {
                 environment_path: PathBuf::from("/path/to/env").into(),
                 network: None,
+                network_breaker: None,
                 signing_service_uri: None,
                 encryption_service_uri: None,
                 decryption_service_uri: None,
@@ -149,6 +338,24 @@ pub mod tests {
                 passphrase_service: Some(PassphraseServiceConfig::Cmd),
                 admin_interfaces: None,
                 use_dangerous_test_keystore: false,
+                author_is_authority: false,
+                max_pending_dependency_depth: None,
+                max_cached_modules: None,
+                source_chain_read_cache: None,
+                max_blocking_host_fns: None,
+                parallel_callbacks: None,
+                cell_map_size: None,
+                entry_chunk_threshold: None,
+                held_op_types: None,
+                zome_call_limit: None,
+                zome_call_timeout_secs: None,
+                host_fn_budget: None,
+                cap_nonces: None,
+                workflow_runtimes: None,
+                gossip_request_policy: None,
+                validation_profiles: None,
+                sys_validation_dep_cache: None,
+                read_only_cells: None,
             }
         );
     }
@@ -178,6 +385,10 @@ pub mod tests {
     driver.type = "websocket"
     driver.port = 1234
 
+    [sys_validation_dep_cache]
+    capacity = 100
+    ttl_secs = 10
+
     "#;
         let result: ConductorResult<ConductorConfig> = config_from_toml(toml);
         assert_eq!(
@@ -187,6 +398,7 @@ pub mod tests {
                 network: Some(NetworkConfig::Sim2h {
                     url: Url::parse("ws://localhost:9000/").unwrap()
                 }),
+                network_breaker: None,
                 signing_service_uri: None,
                 encryption_service_uri: None,
                 decryption_service_uri: None,
@@ -199,6 +411,27 @@ pub mod tests {
                     driver: InterfaceDriver::Websocket { port: 1234 }
                 }]),
                 use_dangerous_test_keystore: true,
+                author_is_authority: false,
+                max_pending_dependency_depth: None,
+                max_cached_modules: None,
+                source_chain_read_cache: None,
+                max_blocking_host_fns: None,
+                parallel_callbacks: None,
+                cell_map_size: None,
+                entry_chunk_threshold: None,
+                held_op_types: None,
+                zome_call_limit: None,
+                zome_call_timeout_secs: None,
+                host_fn_budget: None,
+                cap_nonces: None,
+                workflow_runtimes: None,
+                gossip_request_policy: None,
+                validation_profiles: None,
+                sys_validation_dep_cache: Some(DepCacheConfig {
+                    capacity: 100,
+                    ttl_secs: 10,
+                }),
+                read_only_cells: None,
             }
         );
     }
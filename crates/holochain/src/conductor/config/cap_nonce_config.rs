@@ -0,0 +1,39 @@
+use crate::conductor::error::{ConductorError, ConductorResult};
+use holochain_zome_types::capability::CAP_NONCE_EXPIRY_SECS;
+use serde::{Deserialize, Serialize};
+
+/// Bound the nonces each Cell remembers to stop remote calls being replayed.
+/// Each remote call is signed with the time it expires and its nonce is
+/// remembered until then, so a call can't be replayed once its nonce is forgotten.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct CapNonceConfig {
+    /// The most nonces a Cell remembers.
+    /// Calls are refused while this many are waiting to expire.
+    pub max_nonces: usize,
+    /// The most seconds ahead a remote call can expire.
+    /// Callers make their calls expire [CAP_NONCE_EXPIRY_SECS] ahead
+    /// so this can't be any less.
+    pub expire_after_secs: u64,
+}
+
+impl CapNonceConfig {
+    /// Calls that expire further ahead than a Cell accepts would always be refused
+    pub fn check(&self) -> ConductorResult<()> {
+        if (self.expire_after_secs as i64) < CAP_NONCE_EXPIRY_SECS {
+            return Err(ConductorError::ConfigError(format!(
+                "cap_nonces.expire_after_secs must be at least {}",
+                CAP_NONCE_EXPIRY_SECS
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for CapNonceConfig {
+    fn default() -> Self {
+        Self {
+            max_nonces: 10_000,
+            expire_after_secs: 300,
+        }
+    }
+}
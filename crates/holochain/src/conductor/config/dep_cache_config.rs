@@ -0,0 +1,24 @@
+use crate::core::workflow::sys_validation_workflow::dep_cache::{
+    DEFAULT_DEP_CACHE_CAPACITY, DEFAULT_DEP_CACHE_TTL,
+};
+use serde::{Deserialize, Serialize};
+
+/// Let sys validation reuse dependencies it already retrieved in a pass
+/// instead of retrieving them again for every op that references them.
+/// Cached dependencies are trusted like ones another authority has validated.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct DepCacheConfig {
+    /// The most dependencies a Cell caches, the least recently used are evicted first
+    pub capacity: usize,
+    /// How many seconds a dependency is cached for
+    pub ttl_secs: u64,
+}
+
+impl Default for DepCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_DEP_CACHE_CAPACITY,
+            ttl_secs: DEFAULT_DEP_CACHE_TTL.as_secs(),
+        }
+    }
+}
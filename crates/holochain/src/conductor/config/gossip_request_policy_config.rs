@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How a Cell retries fetching an op it is missing, such as a sys validation
+/// dependency, before giving up and leaving the dependent op to wait in limbo.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct GossipRequestPolicy {
+    /// How many times a failed request is retried
+    pub max_retries: u32,
+    /// Milliseconds to wait before the first retry.
+    /// The wait doubles with each retry after that.
+    /// Retries are made by a later run of the workflow once the wait is over,
+    /// so other ops aren't held up while it waits.
+    pub backoff_ms: u64,
+    /// Ask one authority for the missing op at a time, moving on to the next
+    /// authority on each retry, instead of asking the neighborhood of the op
+    pub peer_rotation: bool,
+}
+
+impl Default for GossipRequestPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            backoff_ms: 500,
+            peer_rotation: false,
+        }
+    }
+}
+
+impl GossipRequestPolicy {
+    /// How long to wait before a retry, counting retries from 1
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let doublings = retry.saturating_sub(1).min(16);
+        Duration::from_millis(self.backoff_ms.saturating_mul(1 << doublings))
+    }
+}
@@ -0,0 +1,24 @@
+use holo_hash::DnaHash;
+use holochain_types::dht_op::{DhtOpType, HeldOpTypes};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Configure which types of DhtOp to hold for a DNA.
+/// This lets lightweight nodes author and publish ops without
+/// becoming an authority for every type.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct HeldOpTypesConfig {
+    /// The DNA this applies to
+    pub dna_hash: DnaHash,
+    /// The op types not to hold for this DNA
+    #[serde(default)]
+    pub disabled: HashSet<DhtOpType>,
+}
+
+impl From<HeldOpTypesConfig> for HeldOpTypes {
+    fn from(config: HeldOpTypesConfig) -> Self {
+        HeldOpTypes {
+            disabled: config.disabled,
+        }
+    }
+}
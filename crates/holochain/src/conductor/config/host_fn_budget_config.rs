@@ -0,0 +1,28 @@
+use crate::core::ribosome::HostFnCategory;
+use serde::{Deserialize, Serialize};
+
+/// Limit how many host functions that reach the network a single zome call can
+/// make, so a runaway zome can't flood the DHT. Each category has its own budget.
+#[derive(Clone, Default, Deserialize, Serialize, Debug, PartialEq)]
+pub struct HostFnBudgetConfig {
+    /// The most gets from the DHT, like `get` and `get_links`. Unlimited if omitted.
+    #[serde(default)]
+    pub network_gets: Option<u32>,
+    /// The most commits, like `create` and `create_link`. Unlimited if omitted.
+    #[serde(default)]
+    pub commits: Option<u32>,
+    /// The most `call_remote` calls. Unlimited if omitted.
+    #[serde(default)]
+    pub remote_calls: Option<u32>,
+}
+
+impl HostFnBudgetConfig {
+    /// The budget for a category, if it is limited
+    pub fn budget(&self, category: HostFnCategory) -> Option<u32> {
+        match category {
+            HostFnCategory::NetworkGet => self.network_gets,
+            HostFnCategory::Commit => self.commits,
+            HostFnCategory::RemoteCall => self.remote_calls,
+        }
+    }
+}
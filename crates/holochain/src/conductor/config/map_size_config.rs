@@ -0,0 +1,36 @@
+use holochain_state::env::MapSize;
+use serde::{Deserialize, Serialize};
+
+/// How large the memory map of each Cell's LMDB environment starts and how
+/// large it may grow. A write that fills the map doubles it, up to the max.
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq)]
+pub struct MapSizeConfig {
+    /// The size of the map when the environment is opened, in bytes
+    pub initial_bytes: usize,
+    /// The map is never grown past this many bytes
+    pub max_bytes: usize,
+}
+
+impl Default for MapSizeConfig {
+    fn default() -> Self {
+        MapSize::default().into()
+    }
+}
+
+impl From<MapSize> for MapSizeConfig {
+    fn from(map_size: MapSize) -> Self {
+        Self {
+            initial_bytes: map_size.initial,
+            max_bytes: map_size.max,
+        }
+    }
+}
+
+impl From<MapSizeConfig> for MapSize {
+    fn from(config: MapSizeConfig) -> Self {
+        Self {
+            initial: config.initial_bytes,
+            max: config.max_bytes,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::core::state::cascade::breaker::{
+    NetworkBreaker, DEFAULT_COOL_DOWN, DEFAULT_FAILURE_THRESHOLD,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// When the conductor stops going to the network for cascade retrievals.
+/// After enough consecutive failures retrievals only return what is held
+/// locally until the cool down passes and a probe gets through.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct NetworkBreakerConfig {
+    /// Consecutive network failures before retrievals stop going to the network
+    pub failure_threshold: u32,
+    /// How many seconds to wait before probing the network again
+    pub cool_down_secs: u64,
+}
+
+impl Default for NetworkBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cool_down_secs: DEFAULT_COOL_DOWN.as_secs(),
+        }
+    }
+}
+
+impl From<&NetworkBreakerConfig> for NetworkBreaker {
+    fn from(config: &NetworkBreakerConfig) -> Self {
+        NetworkBreaker::new(
+            config.failure_threshold,
+            Duration::from_secs(config.cool_down_secs),
+        )
+    }
+}
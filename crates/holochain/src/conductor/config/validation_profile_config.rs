@@ -0,0 +1,71 @@
+use holochain_types::cell::CellId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Select how strictly a Cell's sys validation treats the checks an op fails.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct ValidationProfileConfig {
+    /// The Cell this applies to
+    pub cell_id: CellId,
+    /// The profile its sys validation runs with
+    pub profile: ValidationProfile,
+}
+
+/// How sys validation responds to each [ValidationCategory] of failed check.
+/// The security critical checks, signatures, agent identity and private
+/// entries, always reject whatever the profile.
+/// Every category is deterministic, so an op is only ever rejected or
+/// accepted by it: waiting and validating the op again would fail the same way.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub enum ValidationProfile {
+    /// Every failed check rejects the op
+    Strict,
+    /// Every failed check is logged and the op is accepted
+    Permissive,
+    /// Choose the response for each category.
+    /// Categories which are left out reject.
+    Custom(HashMap<ValidationCategory, ValidationResponse>),
+}
+
+impl Default for ValidationProfile {
+    fn default() -> Self {
+        Self::Strict
+    }
+}
+
+impl ValidationProfile {
+    /// How to respond to a failed check in this category
+    pub fn response(&self, category: ValidationCategory) -> ValidationResponse {
+        match self {
+            Self::Strict => ValidationResponse::Reject,
+            Self::Permissive => ValidationResponse::Warn,
+            Self::Custom(mask) => mask
+                .get(&category)
+                .copied()
+                .unwrap_or(ValidationResponse::Reject),
+        }
+    }
+}
+
+/// The groups of sys validation checks a [ValidationProfile] can relax
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq, Hash)]
+pub enum ValidationCategory {
+    /// Entries or link tags over the size limits
+    Size,
+    /// Entries that don't match their entry def, type or visibility
+    EntryDef,
+    /// Updates, deletes and links that reference the wrong kind of data
+    /// or data that was already deleted
+    Reference,
+    /// Links from an entry to itself in a DNA that rejects them
+    SelfLink,
+}
+
+/// What sys validation does with an op that failed a check
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub enum ValidationResponse {
+    /// Integrate the op as rejected
+    Reject,
+    /// Log the failure and accept the op
+    Warn,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Run some classes of workflow on their own thread pools instead of the
+/// runtime they share with zome calls, so a burst of zome calls
+/// can't hold up validation or publishing.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq)]
+pub struct WorkflowRuntimeConfig {
+    /// Threads for sys validation, app validation and integration.
+    /// If omitted these share the conductor's runtime.
+    #[serde(default)]
+    pub validation_threads: Option<usize>,
+    /// Threads for producing and publishing authored ops.
+    /// If omitted these share the conductor's runtime.
+    #[serde(default)]
+    pub authoring_threads: Option<usize>,
+    /// How long sys validation, app validation and integration wait after being
+    /// triggered for more triggers, so a burst of work is committed in one
+    /// transaction instead of many small ones. This eases fsync pressure on slow
+    /// disks at the cost of data landing up to this much later.
+    /// Producing and publishing authored ops never wait.
+    /// If omitted every trigger runs its workflow straight away.
+    #[serde(default)]
+    pub validation_flush_window_ms: Option<u64>,
+}
@@ -0,0 +1,41 @@
+use crate::conductor::error::{ConductorError, ConductorResult};
+use serde::{Deserialize, Serialize};
+
+/// Limit how many zome calls can run at the same time on each Cell.
+/// This keeps one busy client from queuing up unbounded work on a Cell.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct ZomeCallLimitConfig {
+    /// The most zome calls that can run on a Cell at once
+    pub max_concurrent_calls: usize,
+    /// What to do with the calls over the limit
+    #[serde(default)]
+    pub when_busy: WhenBusy,
+}
+
+impl ZomeCallLimitConfig {
+    /// A limit of zero would never let a call run
+    pub fn check(&self) -> ConductorResult<()> {
+        if self.max_concurrent_calls == 0 {
+            return Err(ConductorError::ConfigError(
+                "zome_call_limit.max_concurrent_calls must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What to do with a zome call when its Cell is at the limit
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WhenBusy {
+    /// Wait until one of the running calls finishes
+    Queue,
+    /// Fail straight away with a busy error
+    Reject,
+}
+
+impl Default for WhenBusy {
+    fn default() -> Self {
+        WhenBusy::Queue
+    }
+}
@@ -46,26 +46,54 @@
 //! code which interacted with the Conductor would also have to be highly generic.
 
 use super::{
-    api::error::ConductorApiResult,
-    config::AdminInterfaceConfig,
+    api::error::{ConductorApiError, ConductorApiResult, SerializationError},
+    config::{
+        AdminInterfaceConfig, CapNonceConfig, DepCacheConfig, GossipRequestPolicy,
+        HostFnBudgetConfig, ValidationProfile, ZomeCallLimitConfig,
+    },
     dna_store::DnaStore,
     entry_def_store::EntryDefBufferKey,
     error::{ConductorResult, CreateAppError},
     manager::TaskManagerRunHandle,
-    Cell, Conductor,
+    Cell, CellError, Conductor,
+};
+use crate::core::queue_consumer::{QueueWorkflow, WorkflowRuntimes};
+use crate::core::ribosome::host_fn_executor::HostFnExecutor;
+use crate::core::ribosome::module_cache::{ModuleCache, ModuleCacheMetrics};
+use crate::core::ribosome::wasm_ribosome::WasmRibosome;
+use crate::core::ribosome::{cancel::ZomeCallCancel, LoggedPayload, ZomeCallInvocation};
+use crate::core::signal::SignalMessage;
+use crate::core::state::cascade::breaker::{BreakerState, NetworkBreaker};
+use crate::core::state::dht_op_integration::OpCursor;
+use crate::core::state::validation_db::ValidationLimboStatus;
+use crate::core::state::validation_receipts_db::ValidationReceiptStatus;
+use crate::core::workflow::sys_validation_workflow::{
+    metrics::{LimboBreakdown, SysValidationMetrics},
+    types::DepType,
+};
+use crate::core::workflow::{
+    app_validation_workflow::RevalidationChange,
+    incoming_dht_ops_workflow::OpIngressFilterRef,
+    integrate_dht_ops_workflow::{latency::ValidationLatency, IntegrationEventReceiver},
+    publish_dht_ops_workflow::OpEgressFilterRef,
+    ZomeCallInvocationResult,
 };
-use crate::core::ribosome::ZomeCallInvocation;
-use crate::core::workflow::ZomeCallInvocationResult;
 use derive_more::From;
 use holochain_types::{
     app::{AppId, InstalledApp, InstalledCell, MembraneProof},
     autonomic::AutonomicCue,
     cell::CellId,
+    dht_op::{external::ExternalOpRepr, DhtOpLight, HeldOpTypes},
     dna::DnaFile,
     prelude::*,
+    validate::ValidationStatus,
 };
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{broadcast, RwLock};
 use tracing::*;
 
 #[cfg(test)]
@@ -73,12 +101,178 @@ use super::state::ConductorState;
 #[cfg(test)]
 use crate::core::queue_consumer::InitialQueueTriggers;
 #[cfg(test)]
+use crate::core::signal::SignalSender;
+use holochain_state::env::EnvironmentRead;
+#[cfg(test)]
 use holochain_state::env::EnvironmentWrite;
 use holochain_zome_types::entry_def::EntryDef;
+use holochain_zome_types::zome::{FunctionName, ZomeName};
+use holochain_zome_types::{ExternInput, ZomeCallResponse};
 
 /// A handle to the Conductor that can easily be passed around and cheaply cloned
 pub type ConductorHandle = Arc<dyn ConductorHandleT>;
 
+/// How much storage and memory a Cell is using, in bytes
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CellResourceReport {
+    /// Headers and entries held in the element vault
+    pub element_store: usize,
+    /// System, link and misc metadata of the vault
+    pub metadata: usize,
+    /// Ops that are integrated or waiting to be integrated
+    pub integration: usize,
+    /// The compiled modules of the Cell's zomes.
+    /// This is estimated from the size of the wasm they are compiled from.
+    pub module_cache: usize,
+}
+
+/// The ops a Cell has yet to integrate
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellHealth {
+    /// The Cell being reported on
+    pub cell_id: CellId,
+    /// Ops waiting on sys or app validation
+    pub validation_limbo: usize,
+    /// Validated ops waiting to be integrated
+    pub integration_limbo: usize,
+}
+
+/// The ops a Cell has integrated, counted by type and by validation status
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrationSummary {
+    /// [DhtOpLight::StoreElement] ops
+    pub store_element: usize,
+    /// [DhtOpLight::StoreEntry] ops
+    pub store_entry: usize,
+    /// [DhtOpLight::RegisterAgentActivity] ops
+    pub register_agent_activity: usize,
+    /// [DhtOpLight::RegisterUpdatedBy] ops
+    pub register_updated_by: usize,
+    /// [DhtOpLight::RegisterDeletedBy] ops
+    pub register_deleted_by: usize,
+    /// [DhtOpLight::RegisterDeletedEntryHeader] ops
+    pub register_deleted_entry_header: usize,
+    /// [DhtOpLight::RegisterAddLink] ops
+    pub register_add_link: usize,
+    /// [DhtOpLight::RegisterRemoveLink] ops
+    pub register_remove_link: usize,
+    /// Ops integrated as [ValidationStatus::Valid]
+    pub valid: usize,
+    /// Ops integrated as [ValidationStatus::Rejected]
+    pub rejected: usize,
+    /// Ops integrated as [ValidationStatus::Abandoned]
+    pub abandoned: usize,
+}
+
+impl IntegrationSummary {
+    /// Count one integrated op
+    pub fn add(&mut self, op: &DhtOpLight, validation_status: &ValidationStatus) {
+        match op {
+            DhtOpLight::StoreElement(..) => self.store_element += 1,
+            DhtOpLight::StoreEntry(..) => self.store_entry += 1,
+            DhtOpLight::RegisterAgentActivity(..) => self.register_agent_activity += 1,
+            DhtOpLight::RegisterUpdatedBy(..) => self.register_updated_by += 1,
+            DhtOpLight::RegisterDeletedBy(..) => self.register_deleted_by += 1,
+            DhtOpLight::RegisterDeletedEntryHeader(..) => self.register_deleted_entry_header += 1,
+            DhtOpLight::RegisterAddLink(..) => self.register_add_link += 1,
+            DhtOpLight::RegisterRemoveLink(..) => self.register_remove_link += 1,
+        }
+        match validation_status {
+            ValidationStatus::Valid => self.valid += 1,
+            ValidationStatus::Rejected => self.rejected += 1,
+            ValidationStatus::Abandoned => self.abandoned += 1,
+        }
+    }
+
+    /// Every integrated op
+    pub fn total(&self) -> usize {
+        self.valid + self.rejected + self.abandoned
+    }
+}
+
+/// An op waiting in a Cell's validation limbo and why it is waiting
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimboOpDump {
+    /// Hash of the op
+    pub op_hash: DhtOpHash,
+    /// The op
+    pub op: DhtOpLight,
+    /// Where the op is in validation, including any dependencies it is awaiting
+    pub status: ValidationLimboStatus,
+    /// Dependencies the op was validated against that have not passed validation yet
+    pub pending_dependencies: Vec<DepType>,
+    /// When the op was added to limbo
+    pub time_added: Timestamp,
+    /// Number of times validation has been tried
+    pub num_tries: u32,
+}
+
+/// An op a Cell integrated as [ValidationStatus::Rejected]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RejectedOpDump {
+    /// Hash of the op
+    pub op_hash: DhtOpHash,
+    /// The op
+    pub op: DhtOpLight,
+    /// When the op was integrated
+    pub when_integrated: Timestamp,
+}
+
+/// A snapshot of a Cell's state for debugging, from [ConductorHandleT::dump_state]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDump {
+    /// The header at the top of the source chain
+    pub chain_head: Option<HeaderHash>,
+    /// Number of headers in the source chain
+    pub chain_len: usize,
+    /// Every op waiting on sys or app validation
+    pub validation_limbo: Vec<LimboOpDump>,
+    /// Number of validated ops waiting to be integrated
+    pub integration_limbo: usize,
+    /// The ops the Cell has integrated
+    pub integrated: IntegrationSummary,
+    /// The most recently rejected ops, newest first.
+    /// At most [StateDump::RECENT_REJECTIONS] are kept.
+    pub recent_rejections: Vec<RejectedOpDump>,
+}
+
+impl StateDump {
+    /// How many rejected ops are kept in [StateDump::recent_rejections]
+    pub const RECENT_REJECTIONS: usize = 20;
+}
+
+/// One op from [ConductorHandleT::stream_ops]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamedOp {
+    /// Resume from here to get the ops after this one
+    pub cursor: OpCursor,
+    /// The op for external consumers
+    pub op: ExternalOpRepr,
+    /// The validation status the op was integrated with
+    pub validation_status: ValidationStatus,
+}
+
+/// A page of a Cell's integrated ops in integration order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpStreamPage {
+    /// The ops in this page
+    pub ops: Vec<StreamedOp>,
+    /// Where to resume for the next page.
+    /// This is the cursor that was passed in when the page is empty.
+    pub cursor: Option<OpCursor>,
+}
+
+/// A summary of the whole conductor for health checks.
+/// The limbo depths are the entry counts LMDB keeps for each database,
+/// so polling it costs the same however many ops are waiting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HealthSummary {
+    /// Every running Cell
+    pub cells: Vec<CellHealth>,
+    /// Whether network retrievals are being let through
+    pub network: BreakerState,
+}
+
 /// Base trait for ConductorHandle
 #[mockall::automock]
 #[async_trait::async_trait]
@@ -130,6 +324,14 @@ pub trait ConductorHandleT: Send + Sync {
         invocation: ZomeCallInvocation,
     ) -> ConductorApiResult<ZomeCallInvocationResult>;
 
+    /// Invoke a zome function on a Cell, cancelling it when `cancel` is triggered.
+    /// The call is also cancelled once it runs past the configured timeout.
+    async fn call_zome_cancellable(
+        &self,
+        invocation: ZomeCallInvocation,
+        cancel: ZomeCallCancel,
+    ) -> ConductorApiResult<ZomeCallInvocationResult>;
+
     /// Cue the autonomic system to perform some action early (experimental)
     async fn autonomic_cue(&self, cue: AutonomicCue, cell_id: &CellId) -> ConductorApiResult<()>;
 
@@ -152,6 +354,68 @@ pub trait ConductorHandleT: Send + Sync {
     /// Request access to this conductor's networking handle
     fn holochain_p2p(&self) -> &holochain_p2p::HolochainP2pRef;
 
+    /// The circuit breaker shared by all cells for network retrievals
+    fn network_breaker(&self) -> &NetworkBreaker;
+
+    /// Which types of DhtOp are held for a DNA
+    fn held_op_types(&self, dna_hash: &DnaHash) -> HeldOpTypes;
+
+    /// Whether authored ops skip the sys validation checks that fetch dependencies
+    fn author_is_authority(&self) -> bool;
+
+    /// The longest chain of pending ops sys validation lets an op wait on
+    fn max_pending_dependency_depth(&self) -> usize;
+
+    /// How sys validation caches the dependencies it retrieved,
+    /// None if it retrieves every dependency itself
+    fn sys_validation_dep_cache(&self) -> Option<DepCacheConfig>;
+
+    /// The filter every Cell's publish workflow consults before sending an op
+    fn op_egress_filter(&self) -> OpEgressFilterRef;
+
+    /// The filter every Cell consults before an incoming op enters its validation limbo
+    fn op_ingress_filter(&self) -> OpIngressFilterRef;
+
+    /// The dedicated runtimes Cells spawn their workflows on
+    fn workflow_runtimes(&self) -> WorkflowRuntimes;
+
+    /// The limit on zome calls running at once on each Cell, if any
+    fn zome_call_limit(&self) -> Option<ZomeCallLimitConfig>;
+
+    /// The budget of host functions that reach the network for each zome call
+    fn host_fn_budget(&self) -> HostFnBudgetConfig;
+
+    /// How many headers and elements each zome call caches from its source chain
+    fn source_chain_read_cache(&self) -> usize;
+
+    /// The channel every Cell sends its signals on and every app interface receives them from
+    fn signal_broadcaster(&self) -> broadcast::Sender<SignalMessage>;
+
+    /// How many remote call nonces each Cell remembers, and for how long
+    fn cap_nonce_config(&self) -> CapNonceConfig;
+
+    /// How every Cell retries fetching ops it is missing
+    fn gossip_request_policy(&self) -> GossipRequestPolicy;
+
+    /// How strictly a Cell's sys validation treats failed checks
+    fn validation_profile(&self, cell_id: &CellId) -> ValidationProfile;
+
+    /// Whether a Cell only observes the DHT and never authors
+    fn is_read_only(&self, cell_id: &CellId) -> bool;
+
+    /// The compiled module cache shared by the ribosomes of all DNAs
+    fn module_cache(&self) -> ModuleCache;
+
+    /// The bound on host functions blocked at once, shared by the ribosomes of all DNAs
+    fn host_fn_executor(&self) -> HostFnExecutor;
+
+    /// The most zomes an order-independent callback is called on at once,
+    /// None if they are called one at a time
+    fn parallel_callbacks(&self) -> Option<usize>;
+
+    /// The hits, misses and evictions of the compiled module cache shared by all DNAs
+    fn module_cache_metrics(&self) -> ModuleCacheMetrics;
+
     /// Install Cells into ConductorState based on installation info, and run
     /// genesis on all new source chains
     #[allow(clippy::ptr_arg)]
@@ -180,10 +444,146 @@ pub trait ConductorHandleT: Send + Sync {
     #[allow(clippy::ptr_arg)]
     async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String>;
 
+    /// Take a structured snapshot of a Cell's chain, limbos and integrated ops.
+    /// This only reads so it is safe to call on a live Cell.
+    async fn dump_state(&self, cell_id: &CellId) -> ConductorApiResult<StateDump>;
+
+    /// List the hashes of all integrated ops in a Cell that store or reference an entry
+    async fn ops_for_entry(
+        &self,
+        cell_id: &CellId,
+        entry_hash: &EntryHash,
+    ) -> ConductorApiResult<Vec<DhtOpHash>>;
+
+    /// Report the storage and memory used by a Cell
+    async fn cell_resource_usage(&self, cell_id: &CellId)
+        -> ConductorApiResult<CellResourceReport>;
+
+    /// Count the ops a Cell has integrated by type and validation status
+    async fn integration_summary(&self, cell_id: &CellId)
+        -> ConductorApiResult<IntegrationSummary>;
+
+    /// Get up to `limit` of the ops a Cell has integrated, in integration order,
+    /// starting after `from_cursor` or from the first op if there is none.
+    /// An external indexer can follow a Cell by resuming from the last cursor.
+    async fn stream_ops(
+        &self,
+        cell_id: &CellId,
+        from_cursor: Option<OpCursor>,
+        limit: usize,
+    ) -> ConductorApiResult<OpStreamPage>;
+
+    /// Summarize the Cells and network connectivity of this conductor
+    async fn health_summary(&self) -> ConductorApiResult<HealthSummary>;
+
+    /// Run the validation rules of a new ribosome over the ops a Cell has
+    /// integrated and report the ops whose outcome would change.
+    /// The Cell is left untouched.
+    async fn revalidate_all(
+        &self,
+        cell_id: &CellId,
+        ribosome: WasmRibosome,
+    ) -> ConductorApiResult<Vec<RevalidationChange>>;
+
+    /// Push a validated op that is stuck in a Cell's integration limbo
+    /// through integration without waiting on its dependencies.
+    /// Ops that are unvalidated or not valid are refused.
+    async fn force_integrate(&self, cell_id: &CellId, op_hash: DhtOpHash)
+        -> ConductorApiResult<()>;
+
+    /// Resize the arc of the DHT a Cell holds, releasing the ops that fall
+    /// out of it once `min_redundancy` other agents hold them and fetching
+    /// the ops that fall into it.
+    async fn resize_arc(
+        &self,
+        cell_id: &CellId,
+        arc: holochain_p2p::dht_arc::DhtArc,
+        min_redundancy: usize,
+    ) -> ConductorApiResult<()>;
+
+    /// How many valid validation receipts an op the Cell authored has collected
+    /// against the `required_validations` of its entry def.
+    /// None if the Cell didn't author the op.
+    async fn validation_receipt_status(
+        &self,
+        cell_id: &CellId,
+        op_hash: &DhtOpHash,
+    ) -> ConductorApiResult<Option<ValidationReceiptStatus>>;
+
+    /// Subscribe to a stream of the ops a Cell integrates from now on,
+    /// along with the validation status they were integrated with
+    async fn subscribe_integration(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<IntegrationEventReceiver>;
+
+    /// How sys validation is keeping up in a Cell: the ops waiting in each
+    /// limbo and where the dependencies it checked were found.
+    /// Cheap enough to poll.
+    async fn sys_validation_metrics(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<SysValidationMetrics>;
+
+    /// The ops in a Cell's validation limbo counted by status, and how long
+    /// they have waited. This reads every op in the limbo, so use
+    /// [sys_validation_metrics](Self::sys_validation_metrics) to poll.
+    async fn sys_validation_limbo_breakdown(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<LimboBreakdown>;
+
+    /// Pause one of a Cell's workflows once it has finished any run in
+    /// progress, leaving the rest of the Cell running,
+    /// e.g. when debugging a stuck integration loop
+    async fn pause_workflow(
+        &self,
+        cell_id: &CellId,
+        workflow: QueueWorkflow,
+    ) -> ConductorApiResult<()>;
+
+    /// Resume a workflow paused with [pause_workflow](Self::pause_workflow).
+    /// It runs once to catch up on anything triggered while it was paused.
+    async fn resume_workflow(
+        &self,
+        cell_id: &CellId,
+        workflow: QueueWorkflow,
+    ) -> ConductorApiResult<()>;
+
+    /// Is one of a Cell's workflows paused
+    async fn is_workflow_paused(
+        &self,
+        cell_id: &CellId,
+        workflow: QueueWorkflow,
+    ) -> ConductorApiResult<bool>;
+
+    /// Percentiles of how long a Cell's ops took from entering the validation
+    /// limbo to being integrated, measured over every op since the Cell started.
+    async fn validation_latency(&self, cell_id: &CellId) -> ConductorApiResult<ValidationLatency>;
+
+    /// Wait until every op the Cell authored for a header has collected
+    /// `count` valid validation receipts.
+    /// Errors if that doesn't happen before the timeout.
+    async fn await_validation_receipts(
+        &self,
+        cell_id: &CellId,
+        header_hash: HeaderHash,
+        count: usize,
+        timeout: std::time::Duration,
+    ) -> ConductorApiResult<()>;
+
     /// Get info about an installed App, whether active or inactive
     #[allow(clippy::ptr_arg)]
     async fn get_app_info(&self, app_id: &AppId) -> ConductorResult<Option<InstalledApp>>;
 
+    /// Get a read-only handle to a Cell's environment, for inspecting its
+    /// databases from outside the Conductor.
+    /// Read-only handles can only open read transactions so they can't
+    /// interfere with the Cell's own writes (see [EnvironmentReadRef]).
+    ///
+    /// [EnvironmentReadRef]: holochain_state::env::EnvironmentReadRef
+    async fn get_cell_env_read(&self, cell_id: &CellId) -> ConductorApiResult<EnvironmentRead>;
+
     #[cfg(test)]
     async fn get_cell_env(&self, cell_id: &CellId) -> ConductorApiResult<EnvironmentWrite>;
 
@@ -191,11 +591,51 @@ pub trait ConductorHandleT: Send + Sync {
     async fn get_cell_triggers(&self, cell_id: &CellId)
         -> ConductorApiResult<InitialQueueTriggers>;
 
+    #[cfg(test)]
+    async fn get_cell_signal_tx(&self, cell_id: &CellId) -> ConductorApiResult<SignalSender>;
+
     // HACK: remove when B-01593 lands
     #[cfg(test)]
     async fn get_state_from_handle(&self) -> ConductorApiResult<ConductorState>;
 }
 
+impl dyn ConductorHandleT {
+    /// Call a zome function on a Cell as the Cell's own agent, without a capability.
+    /// The payload is serialized and the output deserialized, so tests and
+    /// simple clients don't need to build a [ZomeCallInvocation].
+    pub async fn call_zome_fn<I, O>(
+        &self,
+        cell_id: CellId,
+        zome_name: impl Into<ZomeName>,
+        fn_name: impl Into<FunctionName>,
+        payload: I,
+    ) -> ConductorApiResult<O>
+    where
+        I: TryInto<SerializedBytes, Error = SerializedBytesError>,
+        O: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    {
+        let zome_name = zome_name.into();
+        let fn_name = fn_name.into();
+        let invocation = ZomeCallInvocation {
+            provenance: cell_id.agent_pubkey().clone(),
+            cell_id,
+            zome_name: zome_name.clone(),
+            cap: None,
+            fn_name: fn_name.clone(),
+            payload: ExternInput::new(payload.try_into().map_err(SerializationError::from)?),
+        };
+        match self.call_zome(invocation).await?.map_err(CellError::from)? {
+            ZomeCallResponse::Ok(output) => Ok(output
+                .into_inner()
+                .try_into()
+                .map_err(SerializationError::from)?),
+            ZomeCallResponse::Unauthorized => {
+                Err(ConductorApiError::ZomeCallUnauthorized(zome_name, fn_name))
+            }
+        }
+    }
+}
+
 /// The current "production" implementation of a ConductorHandle.
 /// The implementation specifies how read/write access to the Conductor
 /// should be synchronized across multiple concurrent Handles.
@@ -208,6 +648,26 @@ pub struct ConductorHandleImpl<DS: DnaStore + 'static> {
     pub(crate) conductor: RwLock<Conductor<DS>>,
     pub(crate) keystore: KeystoreSender,
     pub(crate) holochain_p2p: holochain_p2p::HolochainP2pRef,
+    pub(crate) network_breaker: NetworkBreaker,
+    pub(crate) held_op_types: HashMap<DnaHash, HeldOpTypes>,
+    pub(crate) author_is_authority: bool,
+    pub(crate) max_pending_dependency_depth: usize,
+    pub(crate) sys_validation_dep_cache: Option<DepCacheConfig>,
+    pub(crate) op_egress_filter: OpEgressFilterRef,
+    pub(crate) op_ingress_filter: OpIngressFilterRef,
+    pub(crate) workflow_runtimes: WorkflowRuntimes,
+    pub(crate) zome_call_limit: Option<ZomeCallLimitConfig>,
+    pub(crate) zome_call_timeout: Option<Duration>,
+    pub(crate) host_fn_budget: HostFnBudgetConfig,
+    pub(crate) source_chain_read_cache: usize,
+    pub(crate) signal_broadcaster: broadcast::Sender<SignalMessage>,
+    pub(crate) cap_nonces: CapNonceConfig,
+    pub(crate) gossip_request_policy: GossipRequestPolicy,
+    pub(crate) validation_profiles: HashMap<CellId, ValidationProfile>,
+    pub(crate) read_only_cells: HashSet<CellId>,
+    pub(crate) module_cache: ModuleCache,
+    pub(crate) host_fn_executor: HostFnExecutor,
+    pub(crate) parallel_callbacks: Option<usize>,
 }
 
 #[async_trait::async_trait]
@@ -264,15 +724,31 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         self.conductor.read().await.dna_store().get_entry_def(key)
     }
 
-    #[instrument(skip(self))]
+    #[instrument(skip(self, event))]
     async fn dispatch_holochain_p2p_event(
         &self,
         cell_id: &CellId,
         event: holochain_p2p::event::HolochainP2pEvent,
     ) -> ConductorResult<()> {
+        use holochain_p2p::event::HolochainP2pEvent::CallRemote;
         let lock = self.conductor.read().await;
         let cell: &Cell = lock.cell_by_id(cell_id)?;
-        trace!(agent = ?cell_id.agent_pubkey(), event = ?event);
+        // The request of a remote call carries the payload of the zome call
+        let redacted = match &event {
+            CallRemote {
+                zome_name, fn_name, ..
+            } => lock
+                .dna_store()
+                .get(cell_id.dna_hash())
+                .map_or(true, |dna_file| {
+                    dna_file.dna().is_sensitive(zome_name, fn_name)
+                }),
+            _ => false,
+        };
+        trace!(
+            agent = ?cell_id.agent_pubkey(),
+            event = ?LoggedPayload::redacted_if(&event, redacted)
+        );
         cell.handle_holochain_p2p_event(event).await?;
         Ok(())
     }
@@ -280,6 +756,15 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
     async fn call_zome(
         &self,
         invocation: ZomeCallInvocation,
+    ) -> ConductorApiResult<ZomeCallInvocationResult> {
+        self.call_zome_cancellable(invocation, ZomeCallCancel::default())
+            .await
+    }
+
+    async fn call_zome_cancellable(
+        &self,
+        invocation: ZomeCallInvocation,
+        cancel: ZomeCallCancel,
     ) -> ConductorApiResult<ZomeCallInvocationResult> {
         // FIXME: D-01058: We are holding this read lock for
         // the entire call to call_zome and blocking
@@ -287,7 +772,23 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         let lock = self.conductor.read().await;
         debug!(cell_id = ?invocation.cell_id);
         let cell: &Cell = lock.cell_by_id(&invocation.cell_id)?;
-        Ok(cell.call_zome(invocation).await?)
+        let call = cell.call_zome(invocation, cancel.clone());
+        let result = match self.zome_call_timeout {
+            Some(timeout) => {
+                futures::pin_mut!(call);
+                match tokio::time::timeout(timeout, &mut call).await {
+                    Ok(result) => result,
+                    // Release the host function the call is blocked in
+                    // and let the call finish with the error
+                    Err(_) => {
+                        cancel.cancel();
+                        call.await
+                    }
+                }
+            }
+            None => call.await,
+        };
+        Ok(result?)
     }
 
     async fn autonomic_cue(&self, cue: AutonomicCue, cell_id: &CellId) -> ConductorApiResult<()> {
@@ -320,6 +821,92 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         &self.holochain_p2p
     }
 
+    fn network_breaker(&self) -> &NetworkBreaker {
+        &self.network_breaker
+    }
+
+    fn held_op_types(&self, dna_hash: &DnaHash) -> HeldOpTypes {
+        self.held_op_types
+            .get(dna_hash)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn author_is_authority(&self) -> bool {
+        self.author_is_authority
+    }
+
+    fn max_pending_dependency_depth(&self) -> usize {
+        self.max_pending_dependency_depth
+    }
+
+    fn sys_validation_dep_cache(&self) -> Option<DepCacheConfig> {
+        self.sys_validation_dep_cache.clone()
+    }
+
+    fn op_egress_filter(&self) -> OpEgressFilterRef {
+        self.op_egress_filter.clone()
+    }
+
+    fn op_ingress_filter(&self) -> OpIngressFilterRef {
+        self.op_ingress_filter.clone()
+    }
+
+    fn workflow_runtimes(&self) -> WorkflowRuntimes {
+        self.workflow_runtimes.clone()
+    }
+
+    fn zome_call_limit(&self) -> Option<ZomeCallLimitConfig> {
+        self.zome_call_limit.clone()
+    }
+
+    fn host_fn_budget(&self) -> HostFnBudgetConfig {
+        self.host_fn_budget.clone()
+    }
+
+    fn source_chain_read_cache(&self) -> usize {
+        self.source_chain_read_cache
+    }
+
+    fn signal_broadcaster(&self) -> broadcast::Sender<SignalMessage> {
+        self.signal_broadcaster.clone()
+    }
+
+    fn cap_nonce_config(&self) -> CapNonceConfig {
+        self.cap_nonces.clone()
+    }
+
+    fn gossip_request_policy(&self) -> GossipRequestPolicy {
+        self.gossip_request_policy.clone()
+    }
+
+    fn validation_profile(&self, cell_id: &CellId) -> ValidationProfile {
+        self.validation_profiles
+            .get(cell_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn is_read_only(&self, cell_id: &CellId) -> bool {
+        self.read_only_cells.contains(cell_id)
+    }
+
+    fn module_cache(&self) -> ModuleCache {
+        self.module_cache.clone()
+    }
+
+    fn host_fn_executor(&self) -> HostFnExecutor {
+        self.host_fn_executor.clone()
+    }
+
+    fn module_cache_metrics(&self) -> ModuleCacheMetrics {
+        self.module_cache.metrics()
+    }
+
+    fn parallel_callbacks(&self) -> Option<usize> {
+        self.parallel_callbacks
+    }
+
     async fn install_app(
         self: Arc<Self>,
         app_id: AppId,
@@ -406,6 +993,234 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         self.conductor.read().await.dump_cell_state(cell_id).await
     }
 
+    async fn dump_state(&self, cell_id: &CellId) -> ConductorApiResult<StateDump> {
+        self.conductor.read().await.dump_state(cell_id).await
+    }
+
+    async fn ops_for_entry(
+        &self,
+        cell_id: &CellId,
+        entry_hash: &EntryHash,
+    ) -> ConductorApiResult<Vec<DhtOpHash>> {
+        self.conductor
+            .read()
+            .await
+            .ops_for_entry(cell_id, entry_hash)
+            .await
+    }
+
+    async fn cell_resource_usage(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<CellResourceReport> {
+        self.conductor
+            .read()
+            .await
+            .cell_resource_usage(cell_id)
+            .await
+    }
+
+    async fn integration_summary(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<IntegrationSummary> {
+        self.conductor
+            .read()
+            .await
+            .integration_summary(cell_id)
+            .await
+    }
+
+    async fn stream_ops(
+        &self,
+        cell_id: &CellId,
+        from_cursor: Option<OpCursor>,
+        limit: usize,
+    ) -> ConductorApiResult<OpStreamPage> {
+        self.conductor
+            .read()
+            .await
+            .stream_ops(cell_id, from_cursor, limit)
+            .await
+    }
+
+    async fn health_summary(&self) -> ConductorApiResult<HealthSummary> {
+        Ok(HealthSummary {
+            cells: self.conductor.read().await.cell_health()?,
+            network: self.network_breaker.state(),
+        })
+    }
+
+    async fn revalidate_all(
+        &self,
+        cell_id: &CellId,
+        ribosome: WasmRibosome,
+    ) -> ConductorApiResult<Vec<RevalidationChange>> {
+        Ok(self
+            .conductor
+            .read()
+            .await
+            .revalidate_all(cell_id, ribosome)
+            .await?)
+    }
+
+    async fn force_integrate(
+        &self,
+        cell_id: &CellId,
+        op_hash: DhtOpHash,
+    ) -> ConductorApiResult<()> {
+        let held_op_types = self.held_op_types(cell_id.dna_hash());
+        Ok(self
+            .conductor
+            .read()
+            .await
+            .force_integrate(cell_id, op_hash, held_op_types)
+            .await?)
+    }
+
+    async fn resize_arc(
+        &self,
+        cell_id: &CellId,
+        arc: holochain_p2p::dht_arc::DhtArc,
+        min_redundancy: usize,
+    ) -> ConductorApiResult<()> {
+        Ok(self
+            .conductor
+            .read()
+            .await
+            .resize_arc(cell_id, arc, min_redundancy)?)
+    }
+
+    async fn validation_receipt_status(
+        &self,
+        cell_id: &CellId,
+        op_hash: &DhtOpHash,
+    ) -> ConductorApiResult<Option<ValidationReceiptStatus>> {
+        Ok(self
+            .conductor
+            .read()
+            .await
+            .validation_receipt_status(cell_id, op_hash)
+            .await?)
+    }
+
+    async fn subscribe_integration(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<IntegrationEventReceiver> {
+        Ok(self.conductor.read().await.subscribe_integration(cell_id)?)
+    }
+
+    async fn sys_validation_metrics(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<SysValidationMetrics> {
+        Ok(self
+            .conductor
+            .read()
+            .await
+            .sys_validation_metrics(cell_id)?)
+    }
+
+    async fn sys_validation_limbo_breakdown(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<LimboBreakdown> {
+        Ok(self
+            .conductor
+            .read()
+            .await
+            .sys_validation_limbo_breakdown(cell_id)?)
+    }
+
+    async fn pause_workflow(
+        &self,
+        cell_id: &CellId,
+        workflow: QueueWorkflow,
+    ) -> ConductorApiResult<()> {
+        self.conductor
+            .read()
+            .await
+            .workflow_pause(cell_id, workflow)?
+            .pause();
+        Ok(())
+    }
+
+    async fn resume_workflow(
+        &self,
+        cell_id: &CellId,
+        workflow: QueueWorkflow,
+    ) -> ConductorApiResult<()> {
+        self.conductor
+            .read()
+            .await
+            .workflow_pause(cell_id, workflow)?
+            .resume();
+        Ok(())
+    }
+
+    async fn is_workflow_paused(
+        &self,
+        cell_id: &CellId,
+        workflow: QueueWorkflow,
+    ) -> ConductorApiResult<bool> {
+        Ok(self
+            .conductor
+            .read()
+            .await
+            .workflow_pause(cell_id, workflow)?
+            .is_paused())
+    }
+
+    async fn validation_latency(&self, cell_id: &CellId) -> ConductorApiResult<ValidationLatency> {
+        Ok(self.conductor.read().await.validation_latency(cell_id)?)
+    }
+
+    async fn await_validation_receipts(
+        &self,
+        cell_id: &CellId,
+        header_hash: HeaderHash,
+        count: usize,
+        timeout: std::time::Duration,
+    ) -> ConductorApiResult<()> {
+        // Subscribe before checking so no receipt is missed in between.
+        // The conductor is only locked for each check so waiting
+        // doesn't hold up anything else.
+        let mut receipts = self
+            .conductor
+            .read()
+            .await
+            .subscribe_validation_receipts(cell_id)?;
+        let wait = async {
+            loop {
+                let valid = self
+                    .conductor
+                    .read()
+                    .await
+                    .header_valid_receipts(cell_id, &header_hash)
+                    .await?;
+                if valid >= count {
+                    return Ok(());
+                }
+                match receipts.recv().await {
+                    // Missed some receipts but the next check will count them
+                    Ok(_) | Err(tokio::sync::broadcast::RecvError::Lagged(_)) => (),
+                    Err(tokio::sync::broadcast::RecvError::Closed) => {
+                        return Err(ConductorApiError::CellMissing(cell_id.clone()))
+                    }
+                }
+            }
+        };
+        let waited = tokio::time::timeout(timeout, wait).await;
+        match waited {
+            Ok(result) => result,
+            Err(_) => Err(ConductorApiError::ValidationReceiptsTimeout(
+                header_hash,
+                count,
+            )),
+        }
+    }
+
     async fn get_app_info(&self, app_id: &AppId) -> ConductorResult<Option<InstalledApp>> {
         Ok(self
             .conductor
@@ -416,6 +1231,12 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .get_app_info(app_id))
     }
 
+    async fn get_cell_env_read(&self, cell_id: &CellId) -> ConductorApiResult<EnvironmentRead> {
+        let lock = self.conductor.read().await;
+        let cell = lock.cell_by_id(cell_id)?;
+        Ok(cell.env().clone().into())
+    }
+
     #[cfg(test)]
     async fn get_cell_env(&self, cell_id: &CellId) -> ConductorApiResult<EnvironmentWrite> {
         let lock = self.conductor.read().await;
@@ -433,9 +1254,431 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         Ok(cell.triggers().clone())
     }
 
+    #[cfg(test)]
+    async fn get_cell_signal_tx(&self, cell_id: &CellId) -> ConductorApiResult<SignalSender> {
+        let lock = self.conductor.read().await;
+        let cell = lock.cell_by_id(cell_id)?;
+        Ok(cell.signal_tx().clone())
+    }
+
     #[cfg(test)]
     async fn get_state_from_handle(&self) -> ConductorApiResult<ConductorState> {
         let lock = self.conductor.read().await;
         Ok(lock.get_state_from_handle().await?)
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+mod test {
+    use crate::conductor::interface::websocket::test::setup_app;
+    use crate::test_utils::{mock_dna_store, shutdown_conductor, test_dna_file};
+    use crate::{core::workflow::CallZomeWorkspace, fixt::ZomeCallHostAccessFixturator};
+    use ::fixt::prelude::*;
+    use hdk3::prelude::*;
+    use holochain_types::app::InstalledCell;
+    use holochain_types::cell::CellId;
+    use holochain_types::dna::{DnaDef, DnaFile};
+    use holochain_types::test_utils::fake_agent_pubkey_1;
+    use holochain_wasm_test_utils::TestWasm;
+
+    // simple replica of the internal type for the TestWasm::Crud entry
+    #[derive(Clone, Copy, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+    struct CounTree(u32);
+
+    #[tokio::test(threaded_scheduler)]
+    async fn call_zome_fn_matches_test_ribosome() {
+        // Call through the test ribosome
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+        let expected: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(1));
+
+        // Call through a conductor
+        let dna_file = test_dna_file(
+            "call_zome_fn_test",
+            "0c8e1a4e-7d5b-4f57-9f0e-3f1d2b6c9a41",
+            vec![TestWasm::Crud],
+        )
+        .await;
+        let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), fake_agent_pubkey_1());
+        let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+
+        let dna_store = mock_dna_store(vec![dna_file]);
+
+        let (_tmpdir, _app_api, handle) =
+            setup_app(vec![(alice_installed_cell, None)], dna_store).await;
+
+        let hash: EntryHash = handle
+            .call_zome_fn(
+                alice_cell_id.clone(),
+                TestWasm::Crud,
+                "entry_hash",
+                CounTree(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(hash, expected);
+
+        // Commits go through the Cell's workspace
+        let header_hash: HeaderHash = handle
+            .call_zome_fn(alice_cell_id.clone(), TestWasm::Crud, "new", ())
+            .await
+            .unwrap();
+        let details: GetDetailsOutput = handle
+            .call_zome_fn(alice_cell_id, TestWasm::Crud, "header_details", header_hash)
+            .await
+            .unwrap();
+        assert!(details.into_inner().is_some());
+
+        shutdown_conductor(handle).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn cancelled_zome_calls_release_blocked_host_fns() {
+        use crate::core::ribosome::{cancel::ZomeCallCancel, ZomeCallInvocation};
+        use holochain_zome_types::ExternInput;
+        use std::convert::TryInto;
+
+        let dna_file = test_dna_file(
+            "cancelled_zome_call_test",
+            "a4c6e8f0-2b4d-4f6a-8c0e-1d3f5b7a9c2e",
+            vec![TestWasm::Crud],
+        )
+        .await;
+        let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), fake_agent_pubkey_1());
+        let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+
+        let dna_store = mock_dna_store(vec![dna_file]);
+
+        let (_tmpdir, _app_api, handle) =
+            setup_app(vec![(alice_installed_cell, None)], dna_store).await;
+
+        let header_hash: HeaderHash = handle
+            .call_zome_fn(alice_cell_id.clone(), TestWasm::Crud, "new", ())
+            .await
+            .unwrap();
+
+        // The get blocks on the network so the cancelled call gives up on it
+        let cancel = ZomeCallCancel::default();
+        cancel.cancel();
+        let invocation = ZomeCallInvocation {
+            cell_id: alice_cell_id.clone(),
+            zome_name: TestWasm::Crud.into(),
+            cap: None,
+            fn_name: "header_details".into(),
+            payload: ExternInput::new(header_hash.try_into().unwrap()),
+            provenance: alice_cell_id.agent_pubkey().clone(),
+        };
+        let result = handle
+            .call_zome_cancellable(invocation, cancel)
+            .await
+            .unwrap();
+        assert!(format!("{:?}", result.unwrap_err()).contains("ZomeCallCancelled"));
+
+        shutdown_conductor(handle).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn workflows_pause_and_resume_one_at_a_time() {
+        use crate::core::queue_consumer::QueueWorkflow;
+
+        let dna_file = test_dna_file(
+            "pause_workflow_test",
+            "6e1c3a5f-8b2d-4f7a-9c0e-4d2b6f8a1c3e",
+            vec![TestWasm::Foo],
+        )
+        .await;
+        let cell_id = CellId::new(dna_file.dna_hash().to_owned(), fake_agent_pubkey_1());
+        let installed_cell = InstalledCell::new(cell_id.clone(), "alice_handle".into());
+        let dna_store = mock_dna_store(vec![dna_file]);
+        let (_tmpdir, _app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
+
+        let integration = QueueWorkflow::IntegrateDhtOps;
+        handle.pause_workflow(&cell_id, integration).await.unwrap();
+        // Pausing again leaves it paused
+        handle.pause_workflow(&cell_id, integration).await.unwrap();
+        assert!(handle
+            .is_workflow_paused(&cell_id, integration)
+            .await
+            .unwrap());
+        assert!(!handle
+            .is_workflow_paused(&cell_id, QueueWorkflow::PublishDhtOps)
+            .await
+            .unwrap());
+
+        handle.resume_workflow(&cell_id, integration).await.unwrap();
+        assert!(!handle
+            .is_workflow_paused(&cell_id, integration)
+            .await
+            .unwrap());
+
+        shutdown_conductor(handle).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn get_cell_env_read_can_build_a_read_workspace() {
+        use holochain_state::env::{EnvironmentRead, EnvironmentReadRef};
+
+        let dna_file = test_dna_file(
+            "get_cell_env_read_test",
+            "3f7a9c1e-5b2d-4e8f-a6c0-9d1b3e5f7a2c",
+            vec![TestWasm::Crud],
+        )
+        .await;
+        let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), fake_agent_pubkey_1());
+        let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+
+        let dna_store = mock_dna_store(vec![dna_file]);
+
+        let (_tmpdir, _app_api, handle) =
+            setup_app(vec![(alice_installed_cell, None)], dna_store).await;
+
+        let header_hash: HeaderHash = handle
+            .call_zome_fn(alice_cell_id.clone(), TestWasm::Crud, "new", ())
+            .await
+            .unwrap();
+
+        let env: EnvironmentRead = handle.get_cell_env_read(&alice_cell_id).await.unwrap();
+
+        // The read handle only hands out read guards
+        let _guard: EnvironmentReadRef = env.guard();
+
+        // It can still build a workspace to inspect the Cell's state
+        let workspace = CallZomeWorkspace::new(env).unwrap();
+        assert_eq!(workspace.source_chain.chain_head().unwrap(), &header_hash);
+        assert!(workspace
+            .source_chain
+            .get_element(&header_hash)
+            .unwrap()
+            .is_some());
+
+        shutdown_conductor(handle).await;
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn await_validation_receipts_resolves_once_enough_arrive() {
+        use crate::conductor::api::error::ConductorApiError;
+        use crate::core::state::{
+            dht_op_integration::AuthoredDhtOpsStore,
+            validation_receipts_db::{ValidationReceipt, ValidationResult},
+        };
+        use fallible_iterator::FallibleIterator;
+        use holo_hash::DhtOpHash;
+        use holochain_keystore::KeystoreSenderExt;
+        use holochain_p2p::{actor::HolochainP2pRefToCell, HolochainP2pCellT};
+        use holochain_state::{
+            buffer::KvBufFresh,
+            db::{GetDb, AUTHORED_DHT_OPS},
+            fresh_reader_test,
+        };
+        use std::{convert::TryInto, time::Duration};
+
+        let dna_file = test_dna_file(
+            "await_validation_receipts_test",
+            "8d2f4b6a-1c3e-4a5f-9b7d-2e4c6a8f0b1d",
+            vec![TestWasm::Create],
+        )
+        .await;
+        let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), fake_agent_pubkey_1());
+        let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+
+        let dna_store = mock_dna_store(vec![dna_file]);
+
+        let (_tmpdir, _app_api, handle) =
+            setup_app(vec![(alice_installed_cell, None)], dna_store).await;
+
+        let header_hash: HeaderHash = handle
+            .call_zome_fn(alice_cell_id.clone(), TestWasm::Create, "create_entry", ())
+            .await
+            .unwrap();
+
+        // Alice is the only agent so nobody sends her a receipt
+        let err = handle
+            .await_validation_receipts(
+                &alice_cell_id,
+                header_hash.clone(),
+                2,
+                Duration::from_millis(100),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ConductorApiError::ValidationReceiptsTimeout(_, 2)
+        ));
+
+        // Wait for the ops of the header to be produced
+        let env = handle.get_cell_env(&alice_cell_id).await.unwrap();
+        let authored: AuthoredDhtOpsStore =
+            KvBufFresh::new(env.clone().into(), env.get_db(&*AUTHORED_DHT_OPS).unwrap());
+        let mut op_hashes = Vec::new();
+        for _ in 0..50 {
+            op_hashes = fresh_reader_test!(env, |r| authored
+                .iter(&r)
+                .unwrap()
+                .filter(|(_, v)| Ok(v.op.header_hash() == &header_hash))
+                .map(|(k, _)| Ok(DhtOpHash::with_pre_hashed(k.to_vec())))
+                .collect::<Vec<_>>()
+                .unwrap());
+            if !op_hashes.is_empty() {
+                break;
+            }
+            tokio::time::delay_for(Duration::from_millis(100)).await;
+        }
+        assert!(!op_hashes.is_empty());
+
+        let waiting = {
+            let handle = handle.clone();
+            let alice_cell_id = alice_cell_id.clone();
+            let header_hash = header_hash.clone();
+            tokio::task::spawn(async move {
+                handle
+                    .await_validation_receipts(
+                        &alice_cell_id,
+                        header_hash,
+                        2,
+                        Duration::from_secs(10),
+                    )
+                    .await
+            })
+        };
+
+        // Send alice receipts from two mock validators
+        let mut network = handle.holochain_p2p().to_cell(
+            alice_cell_id.dna_hash().clone(),
+            alice_cell_id.agent_pubkey().clone(),
+        );
+        for _ in 0..2 {
+            let validator = handle
+                .keystore()
+                .clone()
+                .generate_sign_keypair_from_pure_entropy()
+                .await
+                .unwrap();
+            for op_hash in &op_hashes {
+                let receipt = ValidationReceipt {
+                    dht_op_hash: op_hash.clone(),
+                    validation_result: ValidationResult::Valid,
+                    validator: validator.clone(),
+                }
+                .sign(handle.keystore())
+                .await
+                .unwrap();
+                network
+                    .send_validation_receipt(
+                        alice_cell_id.agent_pubkey().clone(),
+                        receipt.try_into().unwrap(),
+                    )
+                    .await
+                    .unwrap();
+            }
+        }
+
+        waiting.await.unwrap().unwrap();
+
+        shutdown_conductor(handle).await;
+    }
+
+    /// Records every field of every span and event
+    #[derive(Clone, Default)]
+    struct LogRecorder(std::sync::Arc<std::sync::Mutex<String>>);
+
+    impl tracing::field::Visit for LogRecorder {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            let mut logs = self.0.lock().unwrap();
+            logs.push_str(&format!("{}={:?}\n", field.name(), value));
+        }
+    }
+
+    impl tracing::Subscriber for LogRecorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            span.record(&mut self.clone());
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            event.record(&mut self.clone());
+        }
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+    struct Secret(String);
+
+    // The conductor runs on this thread so it logs to the recorder
+    #[tokio::test]
+    async fn remote_calls_to_sensitive_fns_are_redacted_in_logs() {
+        use holochain_p2p::{actor::HolochainP2pRefToCell, HolochainP2pCellT};
+        use holochain_zome_types::capability::{SignedCapNonce, CAP_NONCE_BYTES};
+        use holochain_zome_types::signature::Signature;
+        use holochain_zome_types::timestamp::Timestamp;
+
+        let logs = LogRecorder::default();
+        let _guard = tracing::subscriber::set_default(logs.clone());
+
+        let dna_def = DnaDef::builder()
+            .name("sensitive_call_remote_test")
+            .uuid("5b8e2d4f-9a1c-4e6b-8d3f-7c2a0e4b6d81")
+            .add_zome(TestWasm::WhoAmI)
+            .sensitive_fn(TestWasm::WhoAmI, "whoami")
+            .build()
+            .unwrap();
+        let dna_file = DnaFile::new(dna_def, vec![TestWasm::WhoAmI.into()])
+            .await
+            .unwrap();
+        let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), fake_agent_pubkey_1());
+        let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+
+        let dna_store = mock_dna_store(vec![dna_file]);
+
+        let (_tmpdir, _app_api, handle) =
+            setup_app(vec![(alice_installed_cell, None)], dna_store).await;
+
+        let request = SerializedBytes::try_from(Secret("only alice may know this".into())).unwrap();
+        let mut network = handle.holochain_p2p().to_cell(
+            alice_cell_id.dna_hash().clone(),
+            alice_cell_id.agent_pubkey().clone(),
+        );
+        // The payload doesn't match the fn's input, only the logs matter here
+        let _ = network
+            .call_remote(
+                alice_cell_id.agent_pubkey().clone(),
+                TestWasm::WhoAmI.into(),
+                "whoami".into(),
+                None,
+                SignedCapNonce {
+                    nonce: [0; CAP_NONCE_BYTES].into(),
+                    expires_at: Timestamp(0, 0),
+                    signature: Signature(vec![0; 64]),
+                },
+                request.clone(),
+            )
+            .await;
+
+        let logs = logs.0.lock().unwrap().clone();
+        assert!(logs.contains("event=<redacted>"));
+        assert!(!logs.contains(&format!("{:?}", request)));
+
+        shutdown_conductor(handle).await;
+    }
+}
@@ -4,7 +4,8 @@ use crate::conductor::{
     interface::*,
     manager::{ManagedTaskHandle, ManagedTaskResult},
 };
-use crate::core::signal::Signal;
+use crate::core::ribosome::cancel::ZomeCallCancel;
+use crate::core::signal::{SignalFilter, SignalMessage, SignalSubscription};
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_websocket::{
     websocket_bind, WebsocketConfig, WebsocketListener, WebsocketMessage, WebsocketReceiver,
@@ -98,7 +99,7 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 pub async fn spawn_app_interface_task<A: InterfaceApi>(
     port: u16,
     api: A,
-    signal_broadcaster: broadcast::Sender<Signal>,
+    signal_broadcaster: broadcast::Sender<SignalMessage>,
     mut stop_rx: StopReceiver,
 ) -> InterfaceResult<(u16, ManagedTaskHandle)> {
     trace!("Initializing App interface");
@@ -117,7 +118,8 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
 
         let mut handle_connection =
             |send_socket: WebsocketSender, recv_socket: WebsocketReceiver| {
-                let signal_rx = signal_broadcaster.subscribe();
+                let signal_rx =
+                    SignalSubscription::new(&signal_broadcaster, SignalFilter::default());
                 listener_handles.push(tokio::task::spawn(recv_incoming_msgs_and_outgoing_signals(
                     api.clone(),
                     recv_socket,
@@ -178,10 +180,30 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(api: A, mut recv_socket: Webs
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
 /// App interface.
+/// Zome calls still running when the client goes away are cancelled.
 async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
+    api: A,
+    recv_socket: WebsocketReceiver,
+    signal_rx: SignalSubscription,
+    signal_tx: WebsocketSender,
+) -> InterfaceResult<()> {
+    let cancel = ZomeCallCancel::default();
+    let result = recv_connection(
+        api.for_connection(cancel.clone(), signal_rx.shared_filter()),
+        recv_socket,
+        signal_rx,
+        signal_tx,
+    )
+    .await;
+    cancel.cancel();
+    result
+}
+
+/// Serve one app interface connection until it closes
+async fn recv_connection<A: InterfaceApi>(
     api: A,
     mut recv_socket: WebsocketReceiver,
-    mut signal_rx: broadcast::Receiver<Signal>,
+    mut signal_rx: SignalSubscription,
     mut signal_tx: WebsocketSender,
 ) -> InterfaceResult<()> {
     trace!("CONNECTION: {}", recv_socket.remote_addr());
@@ -190,22 +212,41 @@ async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         tokio::select! {
             // If we receive a Signal broadcasted from a Cell, push it out
             // across the interface
-            signal = signal_rx.next() => {
-                if let Some(signal) = signal {
-                    let bytes = SerializedBytes::try_from(
-                        signal.map_err(InterfaceError::SignalReceive)?,
-                    )?;
-                    signal_tx.signal(bytes).await?;
-                } else {
-                    debug!("Closing interface: signal stream empty");
-                    break;
+            signal = signal_rx.recv() => {
+                match signal {
+                    Ok(signal) => {
+                        let bytes = SerializedBytes::try_from(signal)?;
+                        signal_tx.signal(bytes).await?;
+                    }
+                    Err(broadcast::RecvError::Closed) => {
+                        debug!("Closing interface: signal stream empty");
+                        break;
+                    }
+                    // Every Cell shares the channel so a slow client
+                    // misses some signals rather than losing its connection
+                    Err(broadcast::RecvError::Lagged(missed)) => {
+                        warn!(missed, "App interface client is missing signals");
+                    }
+                    Err(e) => return Err(InterfaceError::SignalReceive(e)),
                 }
             },
 
             // If we receive a message from outside, handle it
             msg = recv_socket.next() => {
                 if let Some(msg) = msg {
-                    handle_incoming_message(msg, api.clone()).await?
+                    match msg {
+                        // Requests are handled alongside the socket
+                        // so a closed connection is noticed during a call
+                        msg @ WebsocketMessage::Request(_, _) => {
+                            let api = api.clone();
+                            tokio::task::spawn(async move {
+                                if let Err(e) = handle_incoming_message(msg, api).await {
+                                    warn!(error = ?e, "Failed to handle app request");
+                                }
+                            });
+                        }
+                        msg => handle_incoming_message(msg, api.clone()).await?,
+                    }
                 } else {
                     debug!("Closing interface: message stream empty");
                     break;
@@ -244,6 +285,7 @@ pub mod test {
         state::ConductorState,
         Conductor, ConductorHandle,
     };
+    use crate::core::signal::{Signal, UserSignal};
     use crate::core::state::source_chain::SourceChainBuf;
     use crate::fixt::WasmRibosomeFixturator;
     use futures::future::FutureExt;
@@ -253,10 +295,10 @@ pub mod test {
         app::{InstallAppDnaPayload, InstallAppPayload, InstalledCell},
         cell::CellId,
         observability,
-        test_utils::{fake_agent_pubkey_1, fake_dna_file, fake_dna_zomes},
+        test_utils::{fake_agent_pubkey_1, fake_agent_pubkey_2, fake_dna_file, fake_dna_zomes},
     };
     use holochain_wasm_test_utils::TestWasm;
-    use holochain_websocket::WebsocketMessage;
+    use holochain_websocket::{websocket_connect, WebsocketMessage};
     use holochain_zome_types::ExternInput;
     use matches::assert_matches;
     use mockall::predicate;
@@ -483,6 +525,88 @@ pub mod test {
         shutdown.await.unwrap();
     }
 
+    /// The next signal a client receives, if one comes soon
+    async fn next_signal(rx: &mut WebsocketReceiver) -> Option<Signal> {
+        match tokio::time::timeout(std::time::Duration::from_millis(500), rx.next()).await {
+            Ok(Some(WebsocketMessage::Signal(bytes))) => Some(Signal::try_from(bytes).unwrap()),
+            Ok(other) => panic!("Expected a signal, got {:?}", other),
+            Err(_) => None,
+        }
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn app_interface_clients_only_receive_signals_they_subscribed_to() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let alice_cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        let bob_cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_2()));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .with(predicate::eq(dna_hash))
+            .returning(move |_| Some(dna.clone()));
+        dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+        dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+
+        let (_tmpdir, _, handle) = setup_app(
+            vec![
+                (
+                    InstalledCell::new(alice_cell_id.clone(), "alice".into()),
+                    None,
+                ),
+                (InstalledCell::new(bob_cell_id.clone(), "bob".into()), None),
+            ],
+            dna_store,
+        )
+        .await;
+        let port = handle.clone().add_app_interface(0).await.unwrap();
+
+        // Each client only subscribes to one Cell's signals
+        let mut clients = Vec::new();
+        for cell_id in &[alice_cell_id.clone(), bob_cell_id.clone()] {
+            let (mut client, rx) = websocket_connect(
+                url2!("ws://127.0.0.1:{}", port),
+                Arc::new(WebsocketConfig::default()),
+            )
+            .await
+            .unwrap();
+            let request =
+                AppRequest::SubscribeSignals(SignalFilter::default().with_cell_id(cell_id.clone()));
+            let response: AppResponse = client.request(request).await.unwrap();
+            assert_matches!(response, AppResponse::SignalsSubscribed);
+            clients.push((client, rx));
+        }
+
+        // Alice's Cell emits a trace and Bob's emits a user signal
+        handle
+            .get_cell_signal_tx(&alice_cell_id)
+            .await
+            .unwrap()
+            .send(Signal::Trace);
+        handle
+            .get_cell_signal_tx(&bob_cell_id)
+            .await
+            .unwrap()
+            .send(Signal::User(UserSignal));
+
+        let (_, alice_rx) = &mut clients[0];
+        assert_matches!(next_signal(alice_rx).await, Some(Signal::Trace));
+        assert_matches!(next_signal(alice_rx).await, None);
+        let (_, bob_rx) = &mut clients[1];
+        assert_matches!(next_signal(bob_rx).await, Some(Signal::User(_)));
+        assert_matches!(next_signal(bob_rx).await, None);
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn activate_app() {
         observability::test_run().ok();
@@ -19,6 +19,8 @@
 //! |                 **integration, common to both paths**                 |
 //! | DhtOpIntegr.   | IntegrationLimbo | IntegratedDhtOps | Publish        |
 //! | Publish        | AuthoredDhtOps   | *n/a*            | *n/a*          |
+//! |                         **resizing the arc**                          |
+//! | ArcResize      | IntegratedDhtOps | ValidationQueue  | SysValidation  |
 //!
 //! († Auth'd + IntQ is short for: AuthoredDhtOps + IntegrationLimbo)
 //!
@@ -26,13 +28,11 @@
 //! remove the item it has just processed.
 
 use std::sync::{Arc, Once};
+use std::time::Duration;
 
 use derive_more::{Constructor, Display, From};
 use futures::future::Either;
-use holochain_state::{
-    env::{EnvironmentWrite, WriteManager},
-    prelude::Writer,
-};
+use holochain_state::{env::EnvironmentWrite, prelude::Writer};
 use tokio::sync::{self, mpsc};
 
 // TODO: move these to workflow mod
@@ -44,45 +44,113 @@ mod app_validation_consumer;
 use app_validation_consumer::*;
 mod produce_dht_ops_consumer;
 use produce_dht_ops_consumer::*;
+mod arc_resize_consumer;
 mod publish_dht_ops_consumer;
-use super::state::workspace::WorkspaceError;
-use crate::conductor::{api::CellConductorApiT, manager::ManagedTaskAdd};
+pub use arc_resize_consumer::ArcResizeTarget;
+use arc_resize_consumer::*;
+pub mod trigger_log;
+mod workflow_runtimes;
+use super::state::{cascade::breaker::NetworkBreaker, workspace::WorkspaceError};
+use super::workflow::incoming_dht_ops_workflow::OpIngressFilterRef;
+use super::workflow::integrate_dht_ops_workflow::{
+    latency::LatencyHistogram, IntegrationEventSender,
+};
+use super::workflow::publish_dht_ops_workflow::OpEgressFilterRef;
+use super::workflow::sys_validation_workflow::metrics::DependencyCounters;
+use crate::conductor::{
+    api::CellConductorApiT,
+    config::{DepCacheConfig, GossipRequestPolicy, ValidationProfile},
+    manager::ManagedTaskAdd,
+};
 use holochain_p2p::HolochainP2pCell;
+use holochain_types::dht_op::HeldOpTypes;
 use publish_dht_ops_consumer::*;
+use workflow_runtimes::spawn_on;
+pub use workflow_runtimes::WorkflowRuntimes;
 
 /// Spawns several long-running tasks which are responsible for processing work
 /// which shows up on various databases.
 ///
 /// Waits for the initial loop to complete before returning, to prevent causing
 /// a race condition by trying to run a workflow too soon after cell creation.
+///
+/// A read-only Cell never authors so the authoring workflows,
+/// ProduceDhtOps and Publish, aren't spawned for it.
+///
+/// Alongside the triggers this returns a pause for each workflow,
+/// see [QueueConsumerPauses].
 pub async fn spawn_queue_consumer_tasks(
     env: &EnvironmentWrite,
     cell_network: HolochainP2pCell,
+    network_breaker: NetworkBreaker,
+    request_policy: GossipRequestPolicy,
+    validation_profile: ValidationProfile,
+    held_op_types: HeldOpTypes,
+    author_is_authority: bool,
+    max_pending_dependency_depth: usize,
+    dep_cache: Option<DepCacheConfig>,
+    dependency_counters: DependencyCounters,
+    read_only: bool,
+    egress_filter: OpEgressFilterRef,
+    ingress_filter: OpIngressFilterRef,
+    integration_events: IntegrationEventSender,
+    validation_latency: LatencyHistogram,
+    runtimes: WorkflowRuntimes,
     conductor_api: impl CellConductorApiT + 'static,
     mut task_sender: sync::mpsc::Sender<ManagedTaskAdd>,
     stop: sync::broadcast::Sender<()>,
-) -> InitialQueueTriggers {
+) -> (InitialQueueTriggers, QueueConsumerPauses) {
+    let pauses = QueueConsumerPauses::default();
+
     // Publish
-    let (tx_publish, handle) =
-        spawn_publish_dht_ops_consumer(env.clone(), stop.subscribe(), cell_network.clone());
-    task_sender
-        .send(ManagedTaskAdd::dont_handle(handle))
-        .await
-        .expect("Failed to manage workflow handle");
+    let tx_publish = if read_only {
+        TriggerSender::disconnected()
+    } else {
+        let (tx_publish, handle) = spawn_publish_dht_ops_consumer(
+            env.clone(),
+            stop.subscribe(),
+            pauses.publish_dht_ops.subscribe(),
+            cell_network.clone(),
+            egress_filter,
+            runtimes.authoring(),
+        );
+        task_sender
+            .send(ManagedTaskAdd::dont_handle(handle))
+            .await
+            .expect("Failed to manage workflow handle");
+        tx_publish
+    };
 
     let (create_tx_sys, get_tx_sys) = tokio::sync::oneshot::channel();
 
     // Integration
-    let (tx_integration, handle) =
-        spawn_integrate_dht_ops_consumer(env.clone(), stop.subscribe(), get_tx_sys);
+    let (tx_integration, handle) = spawn_integrate_dht_ops_consumer(
+        env.clone(),
+        stop.subscribe(),
+        pauses.integrate_dht_ops.subscribe(),
+        get_tx_sys,
+        held_op_types,
+        integration_events,
+        validation_latency,
+        runtimes.validation(),
+        runtimes.validation_flush_window(),
+    );
     task_sender
         .send(ManagedTaskAdd::dont_handle(handle))
         .await
         .expect("Failed to manage workflow handle");
 
     // App validation
-    let (tx_app, handle) =
-        spawn_app_validation_consumer(env.clone(), stop.subscribe(), tx_integration.clone());
+    let (tx_app, handle) = spawn_app_validation_consumer(
+        env.clone(),
+        stop.subscribe(),
+        pauses.app_validation.subscribe(),
+        tx_integration.clone(),
+        conductor_api.clone(),
+        cell_network.clone(),
+        runtimes.validation(),
+        runtimes.validation_flush_window(),
+    );
     task_sender
         .send(ManagedTaskAdd::dont_handle(handle))
         .await
@@ -92,9 +160,19 @@ pub async fn spawn_queue_consumer_tasks(
     let (tx_sys, handle) = spawn_sys_validation_consumer(
         env.clone(),
         stop.subscribe(),
+        pauses.sys_validation.subscribe(),
         tx_app.clone(),
-        cell_network,
+        cell_network.clone(),
+        network_breaker,
+        request_policy,
+        validation_profile,
+        author_is_authority,
+        max_pending_dependency_depth,
+        dep_cache,
+        dependency_counters,
         conductor_api,
+        runtimes.validation(),
+        runtimes.validation_flush_window(),
     );
     task_sender
         .send(ManagedTaskAdd::dont_handle(handle))
@@ -104,15 +182,137 @@ pub async fn spawn_queue_consumer_tasks(
         panic!("Failed to send tx_sys");
     }
 
-    // Produce
-    let (tx_produce, handle) =
-        spawn_produce_dht_ops_consumer(env.clone(), stop.subscribe(), tx_publish.clone());
+    // Arc resize
+    let (tx_arc_resize, arc_resize_target, handle) = spawn_arc_resize_consumer(
+        env.clone(),
+        stop.subscribe(),
+        pauses.arc_resize.subscribe(),
+        cell_network,
+        tx_sys.clone(),
+        ingress_filter,
+        runtimes.validation(),
+    );
     task_sender
         .send(ManagedTaskAdd::dont_handle(handle))
         .await
         .expect("Failed to manage workflow handle");
 
-    InitialQueueTriggers::new(tx_sys, tx_produce, tx_publish, tx_app, tx_integration)
+    // Produce
+    let tx_produce = if read_only {
+        TriggerSender::disconnected()
+    } else {
+        let (tx_produce, handle) = spawn_produce_dht_ops_consumer(
+            env.clone(),
+            stop.subscribe(),
+            pauses.produce_dht_ops.subscribe(),
+            tx_publish.clone(),
+            runtimes.authoring(),
+        );
+        task_sender
+            .send(ManagedTaskAdd::dont_handle(handle))
+            .await
+            .expect("Failed to manage workflow handle");
+        tx_produce
+    };
+
+    (
+        InitialQueueTriggers::new(
+            tx_sys,
+            tx_produce,
+            tx_arc_resize,
+            arc_resize_target,
+            tx_publish,
+            tx_app,
+            tx_integration,
+        ),
+        pauses,
+    )
+}
+
+/// Pauses and resumes one queue consumer.
+/// Clones share the same state, so pausing any clone pauses the consumer.
+#[derive(Clone, Debug)]
+pub struct WorkflowPause(Arc<(sync::watch::Sender<bool>, sync::watch::Receiver<bool>)>);
+
+impl Default for WorkflowPause {
+    fn default() -> Self {
+        Self(Arc::new(sync::watch::channel(false)))
+    }
+}
+
+impl WorkflowPause {
+    /// Pause the consumer once it has finished any run in progress.
+    /// Pausing a paused consumer does nothing.
+    pub fn pause(&self) {
+        // Can't fail because we hold a receiver
+        let _ = (self.0).0.broadcast(true);
+    }
+
+    /// Resume a paused consumer. It runs once to catch up on anything
+    /// triggered while it was paused.
+    /// Resuming a consumer that isn't paused does nothing.
+    pub fn resume(&self) {
+        let _ = (self.0).0.broadcast(false);
+    }
+
+    /// Is the consumer paused
+    pub fn is_paused(&self) -> bool {
+        *(self.0).1.borrow()
+    }
+
+    fn subscribe(&self) -> sync::watch::Receiver<bool> {
+        (self.0).1.clone()
+    }
+}
+
+/// The queue consumers a Cell runs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueueWorkflow {
+    /// The SysValidation workflow
+    SysValidation,
+    /// The AppValidation workflow
+    AppValidation,
+    /// The DhtOpIntegration workflow
+    IntegrateDhtOps,
+    /// The ProduceDhtOps workflow
+    ProduceDhtOps,
+    /// The Publish workflow
+    PublishDhtOps,
+    /// The ArcResize workflow
+    ArcResize,
+}
+
+/// A pause for each queue consumer, so one workflow can be paused while the
+/// rest of the Cell keeps running, e.g. when debugging a stuck integration loop.
+/// A paused consumer still shuts down with the rest of the Cell.
+#[derive(Clone, Debug, Default)]
+pub struct QueueConsumerPauses {
+    /// Pauses the SysValidation workflow
+    pub sys_validation: WorkflowPause,
+    /// Pauses the AppValidation workflow
+    pub app_validation: WorkflowPause,
+    /// Pauses the DhtOpIntegration workflow
+    pub integrate_dht_ops: WorkflowPause,
+    /// Pauses the ProduceDhtOps workflow
+    pub produce_dht_ops: WorkflowPause,
+    /// Pauses the Publish workflow
+    pub publish_dht_ops: WorkflowPause,
+    /// Pauses the ArcResize workflow
+    pub arc_resize: WorkflowPause,
+}
+
+impl QueueConsumerPauses {
+    /// The pause of one workflow
+    pub fn get(&self, workflow: QueueWorkflow) -> &WorkflowPause {
+        match workflow {
+            QueueWorkflow::SysValidation => &self.sys_validation,
+            QueueWorkflow::AppValidation => &self.app_validation,
+            QueueWorkflow::IntegrateDhtOps => &self.integrate_dht_ops,
+            QueueWorkflow::ProduceDhtOps => &self.produce_dht_ops,
+            QueueWorkflow::PublishDhtOps => &self.publish_dht_ops,
+            QueueWorkflow::ArcResize => &self.arc_resize,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -122,6 +322,10 @@ pub struct InitialQueueTriggers {
     pub sys_validation: TriggerSender,
     /// Notify the ProduceDhtOps workflow to run, i.e. after InvokeCallZome
     pub produce_dht_ops: TriggerSender,
+    /// Notify the ArcResize workflow to run, i.e. after setting a new target
+    pub arc_resize: TriggerSender,
+    /// The arc the ArcResize workflow resizes to
+    pub arc_resize_target: ArcResizeTarget,
 
     /// These triggers can only be run once
     /// so they are private
@@ -135,6 +339,8 @@ impl InitialQueueTriggers {
     fn new(
         sys_validation: TriggerSender,
         produce_dht_ops: TriggerSender,
+        arc_resize: TriggerSender,
+        arc_resize_target: ArcResizeTarget,
         publish_dht_ops: TriggerSender,
         app_validation: TriggerSender,
         integrate_dht_ops: TriggerSender,
@@ -142,6 +348,8 @@ impl InitialQueueTriggers {
         Self {
             sys_validation,
             produce_dht_ops,
+            arc_resize,
+            arc_resize_target,
             publish_dht_ops,
             app_validation,
             integrate_dht_ops,
@@ -164,12 +372,37 @@ impl InitialQueueTriggers {
         }
     }
 }
+/// How urgently a consumer should look for more work
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Priority {
+    /// Drained before any normal signals
+    High,
+    /// The priority of a plain [TriggerSender::trigger]
+    Normal,
+}
+
+/// How a trigger's workflow appears in the [trigger_log] if it wasn't named
+const UNNAMED_WORKFLOW: &str = "unnamed";
+
 /// The means of nudging a queue consumer to tell it to look for more work
 #[derive(Clone)]
-pub struct TriggerSender(mpsc::Sender<()>);
+pub struct TriggerSender {
+    normal: Option<mpsc::Sender<()>>,
+    /// Only set if the channel was created with priorities
+    high: Option<mpsc::Sender<()>>,
+    /// The workflow this triggers, for the [trigger_log]
+    workflow: &'static str,
+}
 
 /// The receiving end of a queue trigger channel
-pub struct TriggerReceiver(mpsc::Receiver<()>);
+pub struct TriggerReceiver {
+    normal: mpsc::Receiver<()>,
+    high: Option<mpsc::Receiver<()>>,
+    /// How long to wait for more normal triggers before running
+    flush_window: Option<Duration>,
+    /// Set when the consumer re-triggered itself so the next listen doesn't wait
+    skip_next_window: bool,
+}
 
 impl TriggerSender {
     /// Create a new channel for waking a consumer
@@ -178,13 +411,79 @@ impl TriggerSender {
     /// inconsistency from the perspective of any particular CPU thread
     pub fn new() -> (TriggerSender, TriggerReceiver) {
         let (tx, rx) = mpsc::channel(num_cpus::get());
-        (TriggerSender(tx), TriggerReceiver(rx))
+        (
+            TriggerSender {
+                normal: Some(tx),
+                high: None,
+                workflow: UNNAMED_WORKFLOW,
+            },
+            TriggerReceiver {
+                normal: rx,
+                high: None,
+                flush_window: None,
+                skip_next_window: false,
+            },
+        )
+    }
+
+    /// Create a new channel for waking a consumer where high priority
+    /// signals are received before normal ones, so a busy stream of
+    /// normal triggers can't hold them up.
+    pub fn new_prioritized() -> (TriggerSender, TriggerReceiver) {
+        let (tx, rx) = mpsc::channel(num_cpus::get());
+        let (high_tx, high_rx) = mpsc::channel(num_cpus::get());
+        (
+            TriggerSender {
+                normal: Some(tx),
+                high: Some(high_tx),
+                workflow: UNNAMED_WORKFLOW,
+            },
+            TriggerReceiver {
+                normal: rx,
+                high: Some(high_rx),
+                flush_window: None,
+                skip_next_window: false,
+            },
+        )
+    }
+
+    /// A trigger for a consumer that was never spawned,
+    /// e.g. the authoring workflows of a read-only Cell.
+    /// Triggering it does nothing.
+    pub fn disconnected() -> TriggerSender {
+        TriggerSender {
+            normal: None,
+            high: None,
+            workflow: UNNAMED_WORKFLOW,
+        }
+    }
+
+    /// Name the workflow this triggers in the [trigger_log]
+    pub fn for_workflow(mut self, workflow: &'static str) -> Self {
+        self.workflow = workflow;
+        self
     }
 
     /// Lazily nudge the consumer task, ignoring the case where the consumer
     /// already has a pending trigger signal
+    #[track_caller]
     pub fn trigger(&mut self) {
-        match self.0.try_send(()) {
+        self.trigger_priority(Priority::Normal)
+    }
+
+    /// Lazily nudge the consumer task at a priority.
+    /// Without priorities every trigger is normal.
+    #[track_caller]
+    pub fn trigger_priority(&mut self, priority: Priority) {
+        trigger_log::record(std::panic::Location::caller(), self.workflow, priority);
+        let tx = match (priority, &mut self.high) {
+            (Priority::High, Some(tx)) => tx,
+            _ => match &mut self.normal {
+                Some(tx) => tx,
+                None => return,
+            },
+        };
+        match tx.try_send(()) {
             Err(mpsc::error::TrySendError::Closed(_)) => {
                 tracing::warn!(
                     "Queue consumer trigger was sent while Cell is shutting down: ignoring."
@@ -197,23 +496,91 @@ impl TriggerSender {
 }
 
 impl TriggerReceiver {
+    /// Wait up to `window` after a normal trigger for more to arrive,
+    /// so the consumer runs, and commits, once for the whole burst.
+    /// High priority triggers never wait.
+    pub fn with_flush_window(mut self, window: Option<Duration>) -> Self {
+        self.flush_window = window;
+        self
+    }
+
+    /// Don't wait for the flush window on the next listen.
+    /// A consumer that triggers itself because it left work behind calls this
+    /// so each pass over a backlog doesn't wait an extra window.
+    pub fn skip_next_flush_window(&mut self) {
+        self.skip_next_window = true;
+    }
+
     /// Listen for one or more items to come through, draining the channel
-    /// each time. Bubble up errors on empty channel.
-    pub async fn listen(&mut self) -> Result<(), QueueTriggerClosedError> {
+    /// each time, high priority signals first. Returns the highest priority
+    /// drained. Bubble up errors on empty channel.
+    pub async fn listen(&mut self) -> Result<Priority, QueueTriggerClosedError> {
         use tokio::sync::mpsc::error::TryRecvError;
 
-        // wait for next item
-        if self.0.recv().await.is_some() {
-            // drain the channel
-            loop {
-                match self.0.try_recv() {
-                    Err(TryRecvError::Closed) => return Err(QueueTriggerClosedError),
-                    Err(TryRecvError::Empty) => return Ok(()),
-                    Ok(()) => (),
+        // wait for next item, preferring a high priority one already waiting
+        let mut priority = match &mut self.high {
+            None => match self.normal.recv().await {
+                Some(()) => Priority::Normal,
+                None => return Err(QueueTriggerClosedError),
+            },
+            Some(high) => match high.try_recv() {
+                Ok(()) => Priority::High,
+                Err(TryRecvError::Closed) => return Err(QueueTriggerClosedError),
+                Err(TryRecvError::Empty) => {
+                    let next_high = high.recv();
+                    let next_normal = self.normal.recv();
+                    tokio::pin!(next_high);
+                    tokio::pin!(next_normal);
+                    match futures::future::select(next_high, next_normal).await {
+                        Either::Left((Some(()), _)) => Priority::High,
+                        Either::Right((Some(()), _)) => Priority::Normal,
+                        _ => return Err(QueueTriggerClosedError),
+                    }
+                }
+            },
+        };
+
+        // hold a normal trigger open for more, unless a high one arrives
+        // or the consumer is re-running for work it left behind
+        let skip_window = std::mem::replace(&mut self.skip_next_window, false);
+        if let (Priority::Normal, Some(window), false) = (priority, self.flush_window, skip_window)
+        {
+            let window = tokio::time::delay_for(window);
+            tokio::pin!(window);
+            match &mut self.high {
+                None => window.await,
+                Some(high) => {
+                    let next_high = high.recv();
+                    tokio::pin!(next_high);
+                    match futures::future::select(window, next_high).await {
+                        Either::Left(_) => (),
+                        Either::Right((Some(()), _)) => priority = Priority::High,
+                        Either::Right((None, _)) => return Err(QueueTriggerClosedError),
+                    }
                 }
             }
-        } else {
-            Err(QueueTriggerClosedError)
+        }
+
+        // drain the channels
+        if let Some(high) = &mut self.high {
+            if drain(high)? {
+                priority = Priority::High;
+            }
+        }
+        drain(&mut self.normal)?;
+        Ok(priority)
+    }
+}
+
+/// Take every pending signal, returning whether there were any
+fn drain(rx: &mut mpsc::Receiver<()>) -> Result<bool, QueueTriggerClosedError> {
+    use tokio::sync::mpsc::error::TryRecvError;
+    let mut any = false;
+    loop {
+        match rx.try_recv() {
+            Err(TryRecvError::Closed) => return Err(QueueTriggerClosedError),
+            Err(TryRecvError::Empty) => return Ok(any),
+            Ok(()) => any = true,
         }
     }
 }
@@ -227,16 +594,16 @@ pub struct OneshotWriter(EnvironmentWrite);
 
 impl OneshotWriter {
     /// Create the writer and pass it into a closure.
+    ///
+    /// If the write fails because the environment's memory map is full
+    /// the map is grown and the closure run again with a fresh writer,
+    /// so it must be safe to run more than once.
+    /// See [EnvironmentWrite::with_commit_growing].
     pub fn with_writer<F>(self, f: F) -> Result<(), WorkspaceError>
     where
-        F: FnOnce(&mut Writer) -> Result<(), WorkspaceError> + Send,
+        F: FnMut(&mut Writer) -> Result<(), WorkspaceError> + Send,
     {
-        let env_ref = self.0.guard();
-        env_ref.with_commit::<WorkspaceError, (), _>(|w| {
-            f(w)?;
-            Ok(())
-        })?;
-        Ok(())
+        self.0.with_commit_growing(f)
     }
 }
 
@@ -259,10 +626,74 @@ enum Job {
     Shutdown,
 }
 
-/// Wait for the next job or exit command
+/// Wait for the next job or exit command.
+///
+/// While the workflow's pause is on the consumer waits without running,
+/// leaving the other workflows alone. It runs once on resuming to catch up
+/// on anything triggered while it was paused.
 async fn next_job_or_exit(
     rx: &mut TriggerReceiver,
     stop: &mut sync::broadcast::Receiver<()>,
+    pause: &mut sync::watch::Receiver<bool>,
+) -> Job {
+    if *pause.borrow() {
+        return wait_for_resume(stop, pause).await;
+    }
+    let paused = {
+        let next_job = next_job_or_stop(rx, stop);
+        let paused = wait_for_pause(pause);
+        tokio::pin!(next_job);
+        tokio::pin!(paused);
+        match futures::future::select(next_job, paused).await {
+            Either::Left((job, _)) => return job,
+            Either::Right((paused, _)) => paused,
+        }
+    };
+    if paused {
+        wait_for_resume(stop, pause).await
+    } else {
+        // Nothing can pause this consumer anymore
+        next_job_or_stop(rx, stop).await
+    }
+}
+
+/// Resolves with true once the pause is turned on,
+/// or with false if it can't be anymore
+async fn wait_for_pause(pause: &mut sync::watch::Receiver<bool>) -> bool {
+    while let Some(paused) = pause.recv().await {
+        if paused {
+            return true;
+        }
+    }
+    false
+}
+
+/// Wait while the workflow is paused or until the whole Cell stops
+async fn wait_for_resume(
+    stop: &mut sync::broadcast::Receiver<()>,
+    pause: &mut sync::watch::Receiver<bool>,
+) -> Job {
+    tracing::warn!("Queue consumer paused.");
+    loop {
+        let kill = stop.recv();
+        let pause_changed = pause.recv();
+        tokio::pin!(kill);
+        tokio::pin!(pause_changed);
+        match futures::future::select(kill, pause_changed).await {
+            Either::Left(_) => return Job::Shutdown,
+            Either::Right((Some(true), _)) => (),
+            Either::Right(_) => {
+                tracing::warn!("Queue consumer resumed.");
+                return Job::Run;
+            }
+        }
+    }
+}
+
+/// Wait for the next job or the stop shared by every workflow
+async fn next_job_or_stop(
+    rx: &mut TriggerReceiver,
+    stop: &mut sync::broadcast::Receiver<()>,
 ) -> Job {
     // Check for shutdown or next job
     let next_job = rx.listen();
@@ -278,3 +709,258 @@ async fn next_job_or_exit(
         Job::Run
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn plain_triggers_are_normal_priority() {
+        let (mut tx, mut rx) = TriggerSender::new();
+        tx.trigger();
+        assert_eq!(rx.listen().await.unwrap(), Priority::Normal);
+
+        // Without priorities a high trigger still wakes the consumer
+        tx.trigger_priority(Priority::High);
+        assert_eq!(rx.listen().await.unwrap(), Priority::Normal);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn high_priority_triggers_are_received_first() {
+        let (mut tx, mut rx) = TriggerSender::new_prioritized();
+        tx.trigger();
+        tx.trigger();
+        tx.trigger_priority(Priority::High);
+
+        // Everything pending is drained at once, reported as high
+        assert_eq!(rx.listen().await.unwrap(), Priority::High);
+
+        tx.trigger();
+        assert_eq!(rx.listen().await.unwrap(), Priority::Normal);
+
+        tx.trigger_priority(Priority::High);
+        assert_eq!(rx.listen().await.unwrap(), Priority::High);
+
+        drop(tx);
+        assert!(rx.listen().await.is_err());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn pausing_a_workflow_leaves_the_others_running() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        async fn wait_for_runs(runs: &AtomicUsize, expected: usize) {
+            let started = std::time::Instant::now();
+            while runs.load(Ordering::SeqCst) < expected {
+                assert!(
+                    started.elapsed() < Duration::from_secs(5),
+                    "consumer never ran"
+                );
+                tokio::time::delay_for(Duration::from_millis(10)).await;
+            }
+        }
+
+        let (stop, _) = sync::broadcast::channel(1);
+        let pauses = QueueConsumerPauses::default();
+        let spawn = |mut rx: TriggerReceiver, pause: &WorkflowPause| {
+            let runs = Arc::new(AtomicUsize::new(0));
+            let mut stop = stop.subscribe();
+            let mut pause = pause.subscribe();
+            let handle = {
+                let runs = runs.clone();
+                tokio::spawn(async move {
+                    while let Job::Run = next_job_or_exit(&mut rx, &mut stop, &mut pause).await {
+                        runs.fetch_add(1, Ordering::SeqCst);
+                    }
+                })
+            };
+            (runs, handle)
+        };
+        let (mut tx_integration, rx) = TriggerSender::new();
+        let (integration_runs, integration) = spawn(rx, &pauses.integrate_dht_ops);
+        let (mut tx_publish, rx) = TriggerSender::new();
+        let (publish_runs, publish) = spawn(rx, &pauses.publish_dht_ops);
+
+        tx_integration.trigger();
+        tx_publish.trigger();
+        wait_for_runs(&integration_runs, 1).await;
+        wait_for_runs(&publish_runs, 1).await;
+
+        // Pausing integration leaves publish running
+        pauses.integrate_dht_ops.pause();
+        // Pausing twice doesn't undo it
+        pauses.integrate_dht_ops.pause();
+        assert!(pauses.integrate_dht_ops.is_paused());
+        tokio::time::delay_for(Duration::from_millis(50)).await;
+        tx_integration.trigger();
+        tx_publish.trigger();
+        wait_for_runs(&publish_runs, 2).await;
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+        assert_eq!(integration_runs.load(Ordering::SeqCst), 1);
+
+        // Resuming runs it and the missed trigger isn't lost
+        pauses.integrate_dht_ops.resume();
+        assert!(!pauses.integrate_dht_ops.is_paused());
+        wait_for_runs(&integration_runs, 2).await;
+        tx_integration.trigger();
+        let resumed = integration_runs.load(Ordering::SeqCst);
+        wait_for_runs(&integration_runs, resumed + 1).await;
+
+        // The shared stop still shuts down a paused consumer
+        pauses.integrate_dht_ops.pause();
+        tokio::time::delay_for(Duration::from_millis(50)).await;
+        stop.send(()).unwrap();
+        let stopped = futures::future::join(integration, publish);
+        let (integration, publish) = tokio::time::timeout(Duration::from_secs(5), stopped)
+            .await
+            .expect("consumers didn't stop");
+        integration.unwrap();
+        publish.unwrap();
+    }
+
+    #[cfg(feature = "trigger_log")]
+    #[tokio::test(threaded_scheduler)]
+    async fn trigger_log_records_how_workflows_interleaved() {
+        // Names no real workflow uses so other tests' triggers can be ignored
+        const FIRST: &str = "trigger_log_test_first";
+        const SECOND: &str = "trigger_log_test_second";
+        const LAST: &str = "trigger_log_test_last";
+
+        let (stop, _) = sync::broadcast::channel(1);
+        let pause = WorkflowPause::default();
+        let (tx_last, mut rx_last) = TriggerSender::new();
+        let tx_last = tx_last.for_workflow(LAST);
+        let (tx_second, mut rx_second) = TriggerSender::new_prioritized();
+        let tx_second = tx_second.for_workflow(SECOND);
+        let (tx_first, mut rx_first) = TriggerSender::new();
+        let mut tx_first = tx_first.for_workflow(FIRST);
+
+        // Each consumer triggers the next, like sys validation triggering app validation
+        let first = {
+            let mut stop = stop.subscribe();
+            let mut pause = pause.subscribe();
+            let mut tx_second = tx_second.clone();
+            tokio::spawn(async move {
+                while let Job::Run = next_job_or_exit(&mut rx_first, &mut stop, &mut pause).await {
+                    tx_second.trigger_priority(Priority::High);
+                }
+            })
+        };
+        let second = {
+            let mut stop = stop.subscribe();
+            let mut pause = pause.subscribe();
+            let mut tx_last = tx_last.clone();
+            tokio::spawn(async move {
+                while let Job::Run = next_job_or_exit(&mut rx_second, &mut stop, &mut pause).await {
+                    tx_last.trigger();
+                }
+            })
+        };
+
+        tx_first.trigger();
+        assert_eq!(rx_last.listen().await.unwrap(), Priority::Normal);
+        stop.send(()).ok();
+        first.await.unwrap();
+        second.await.unwrap();
+
+        let log: Vec<_> = trigger_log::dump()
+            .into_iter()
+            .filter(|event| [FIRST, SECOND, LAST].contains(&event.target))
+            .collect();
+        let sequence: Vec<_> = log.iter().map(|e| (e.target, e.priority)).collect();
+        assert_eq!(
+            sequence,
+            vec![
+                (FIRST, Priority::Normal),
+                (SECOND, Priority::High),
+                (LAST, Priority::Normal)
+            ]
+        );
+        // Sources point at the callers, not at the trigger itself
+        assert!(log.iter().all(|e| e.source.file() == file!()));
+        let lines: Vec<_> = log.iter().map(|e| e.source.line()).collect();
+        assert!(lines[1] < lines[2] && lines[2] < lines[0]);
+        assert!(log.windows(2).all(|w| w[0].at <= w[1].at));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn self_triggered_reruns_skip_the_flush_window() {
+        let (mut tx, rx) = TriggerSender::new();
+        let mut rx = rx.with_flush_window(Some(Duration::from_secs(60)));
+
+        // A consumer that left work behind runs again straight away
+        tx.trigger();
+        rx.skip_next_flush_window();
+        tokio::time::timeout(Duration::from_secs(5), rx.listen())
+            .await
+            .expect("the re-run waited for the flush window")
+            .unwrap();
+
+        // Only for the one listen
+        tx.trigger();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), rx.listen())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn flush_window_coalesces_rapid_passes_into_fewer_commits() {
+        use holochain_state::test_utils::test_cell_env;
+        use parking_lot::Mutex;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        const PASSES: usize = 10;
+
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let (stop, _) = sync::broadcast::channel(1);
+        let pause = WorkflowPause::default();
+        let (mut tx, rx) = TriggerSender::new();
+        let mut rx = rx.with_flush_window(Some(Duration::from_millis(200)));
+
+        // A consumer that commits everything queued so far in one transaction
+        let queued = Arc::new(Mutex::new(Vec::new()));
+        let landed = Arc::new(Mutex::new(Vec::new()));
+        let commits = Arc::new(AtomicUsize::new(0));
+        let consumer = {
+            let mut stop = stop.subscribe();
+            let mut pause = pause.subscribe();
+            let (queued, landed, commits) = (queued.clone(), landed.clone(), commits.clone());
+            tokio::spawn(async move {
+                while let Job::Run = next_job_or_exit(&mut rx, &mut stop, &mut pause).await {
+                    let batch: Vec<usize> = queued.lock().drain(..).collect();
+                    OneshotWriter::new(env.clone())
+                        .with_writer(|_| {
+                            landed.lock().extend(batch.iter().copied());
+                            Ok(())
+                        })
+                        .unwrap();
+                    commits.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        };
+
+        for i in 0..PASSES {
+            queued.lock().push(i);
+            tx.trigger();
+            tokio::time::delay_for(Duration::from_millis(5)).await;
+        }
+
+        // Everything lands once the window closes
+        let started = std::time::Instant::now();
+        while landed.lock().len() < PASSES {
+            assert!(
+                started.elapsed() < Duration::from_secs(5),
+                "queued data never landed"
+            );
+            tokio::time::delay_for(Duration::from_millis(10)).await;
+        }
+        assert_eq!(*landed.lock(), (0..PASSES).collect::<Vec<_>>());
+        assert!(commits.load(Ordering::SeqCst) < PASSES);
+
+        stop.send(()).ok();
+        consumer.await.unwrap();
+    }
+}
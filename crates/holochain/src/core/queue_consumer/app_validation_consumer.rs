@@ -11,18 +11,25 @@ use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for AppValidation workflow
-#[instrument(skip(env, stop, trigger_integration))]
+#[instrument(skip(env, stop, pause, trigger_integration, conductor_api, network, runtime))]
 pub fn spawn_app_validation_consumer(
     env: EnvironmentWrite,
     mut stop: sync::broadcast::Receiver<()>,
+    mut pause: sync::watch::Receiver<bool>,
     mut trigger_integration: TriggerSender,
+    conductor_api: impl CellConductorApiT + 'static,
+    network: HolochainP2pCell,
+    runtime: Option<tokio::runtime::Handle>,
+    flush_window: Option<std::time::Duration>,
 ) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
-    let (tx, mut rx) = TriggerSender::new();
+    let (tx, rx) = TriggerSender::new();
+    let tx = tx.for_workflow("app_validation");
+    let mut rx = rx.with_flush_window(flush_window);
     let mut trigger_self = tx.clone();
-    let handle = tokio::spawn(async move {
+    let handle = spawn_on(runtime, async move {
         loop {
             // Wait for next job
-            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
+            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop, &mut pause).await {
                 tracing::warn!(
                     "Cell is shutting down: stopping app_validation_workflow queue consumer."
                 );
@@ -32,12 +39,18 @@ pub fn spawn_app_validation_consumer(
             // Run the workflow
             let workspace = AppValidationWorkspace::new(env.clone().into())
                 .expect("Could not create Workspace");
-            if let WorkComplete::Incomplete =
-                app_validation_workflow(workspace, env.clone().into(), &mut trigger_integration)
-                    .await
-                    .expect("Error running Workflow")
+            if let WorkComplete::Incomplete = app_validation_workflow(
+                workspace,
+                env.clone().into(),
+                &mut trigger_integration,
+                conductor_api.clone(),
+                network.clone(),
+            )
+            .await
+            .expect("Error running Workflow")
             {
-                trigger_self.trigger()
+                trigger_self.trigger();
+                rx.skip_next_flush_window();
             };
         }
         Ok(())
@@ -0,0 +1,124 @@
+//! The workflow and queue consumer for resizing the arc a Cell holds
+
+use super::*;
+
+use crate::{
+    conductor::manager::ManagedTaskResult,
+    core::workflow::{
+        arc_resize_workflow::{arc_resize_workflow, ArcResize, ArcResizeWorkspace},
+        incoming_dht_ops_workflow::OpIngressFilterRef,
+    },
+};
+use holochain_p2p::{
+    dht_arc::{DhtArc, MAX_HALF_LENGTH},
+    HolochainP2pCellT,
+};
+use holochain_state::env::EnvironmentWrite;
+use parking_lot::Mutex;
+
+use tokio::task::JoinHandle;
+use tracing::*;
+
+/// How long to wait before looking again for the redundancy a release needs
+const ARC_RESIZE_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// The arc a Cell should hold and how many other agents must hold an op
+/// before it is released from that arc.
+///
+/// Every Cell starts out holding the whole DHT.
+#[derive(Clone)]
+pub struct ArcResizeTarget(Arc<Mutex<(DhtArc, usize)>>);
+
+impl ArcResizeTarget {
+    fn new() -> Self {
+        Self(Arc::new(Mutex::new((DhtArc::new(0, MAX_HALF_LENGTH), 0))))
+    }
+
+    /// Set the arc to resize to, the consumer picks it up when triggered
+    pub fn set(&self, arc: DhtArc, min_redundancy: usize) {
+        *self.0.lock() = (arc, min_redundancy);
+    }
+
+    /// The arc to resize to
+    pub fn get(&self) -> (DhtArc, usize) {
+        *self.0.lock()
+    }
+}
+
+/// Spawn the QueueConsumer for the ArcResize workflow
+#[instrument(skip(env, stop, pause, cell_network, trigger_sys, ingress_filter, runtime))]
+pub fn spawn_arc_resize_consumer(
+    env: EnvironmentWrite,
+    mut stop: sync::broadcast::Receiver<()>,
+    mut pause: sync::watch::Receiver<bool>,
+    mut cell_network: HolochainP2pCell,
+    trigger_sys: TriggerSender,
+    ingress_filter: OpIngressFilterRef,
+    runtime: Option<tokio::runtime::Handle>,
+) -> (
+    TriggerSender,
+    ArcResizeTarget,
+    JoinHandle<ManagedTaskResult>,
+) {
+    let (tx, mut rx) = TriggerSender::new();
+    let tx = tx.for_workflow("arc_resize");
+    let target = ArcResizeTarget::new();
+    let trigger_self = tx.clone();
+    let current_target = target.clone();
+    let handle = spawn_on(runtime, async move {
+        let mut held = current_target.get().0;
+        loop {
+            // Wait for next job
+            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop, &mut pause).await {
+                tracing::warn!(
+                    "Cell is shutting down: stopping arc_resize_workflow queue consumer."
+                );
+                break;
+            }
+
+            let (new_arc, min_redundancy) = current_target.get();
+            if new_arc == held {
+                continue;
+            }
+
+            // Run the workflow
+            let workspace =
+                ArcResizeWorkspace::new(env.clone().into()).expect("Could not create Workspace");
+            let resize = ArcResize {
+                agent: cell_network.from_agent(),
+                old_arc: held,
+                new_arc,
+                min_redundancy,
+            };
+            match arc_resize_workflow(
+                workspace,
+                env.clone(),
+                &mut cell_network,
+                resize,
+                trigger_sys.clone(),
+                ingress_filter.clone(),
+            )
+            .await
+            .expect("Error running Workflow")
+            {
+                WorkComplete::Complete => {
+                    held = new_arc;
+                    // Let our peers know which ops we are an authority for now
+                    if let Err(e) = cell_network.set_arc(held).await {
+                        warn!(failed_to_announce_arc = ?e);
+                    }
+                }
+                // Other agents need time to pick up the ops we want to release
+                WorkComplete::Incomplete => {
+                    let mut trigger_self = trigger_self.clone();
+                    tokio::task::spawn(async move {
+                        tokio::time::delay_for(ARC_RESIZE_RETRY_DELAY).await;
+                        trigger_self.trigger();
+                    });
+                }
+            }
+        }
+        Ok(())
+    });
+    (tx, target, handle)
+}
@@ -5,28 +5,47 @@ use super::*;
 use crate::{
     conductor::manager::ManagedTaskResult,
     core::workflow::integrate_dht_ops_workflow::{
-        integrate_dht_ops_workflow, IntegrateDhtOpsWorkspace,
+        integrate_dht_ops_workflow, latency::LatencyHistogram, IntegrateDhtOpsWorkspace,
+        IntegrationEventSender,
     },
 };
 use holochain_state::env::EnvironmentWrite;
+use holochain_types::dht_op::HeldOpTypes;
 
 use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for DhtOpIntegration workflow
-#[instrument(skip(env, stop, trigger_sys))]
+#[instrument(skip(
+    env,
+    stop,
+    pause,
+    trigger_sys,
+    held_op_types,
+    integration_events,
+    latency,
+    runtime
+))]
 pub fn spawn_integrate_dht_ops_consumer(
     env: EnvironmentWrite,
     mut stop: sync::broadcast::Receiver<()>,
+    mut pause: sync::watch::Receiver<bool>,
     trigger_sys: sync::oneshot::Receiver<TriggerSender>,
+    held_op_types: HeldOpTypes,
+    integration_events: IntegrationEventSender,
+    latency: LatencyHistogram,
+    runtime: Option<tokio::runtime::Handle>,
+    flush_window: Option<std::time::Duration>,
 ) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
-    let (tx, mut rx) = TriggerSender::new();
+    let (tx, rx) = TriggerSender::new();
+    let tx = tx.for_workflow("integrate_dht_ops");
+    let mut rx = rx.with_flush_window(flush_window);
     let mut trigger_self = tx.clone();
-    let handle = tokio::spawn(async move {
+    let handle = spawn_on(runtime, async move {
         let mut trigger_sys = trigger_sys.await.expect("failed to get tx sys");
         loop {
             // Wait for next job
-            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
+            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop, &mut pause).await {
                 tracing::warn!(
                     "Cell is shutting down: stopping integrate_dht_ops_workflow queue consumer."
                 );
@@ -34,14 +53,21 @@ pub fn spawn_integrate_dht_ops_consumer(
             }
 
             // Run the workflow
-            let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into())
+            let mut workspace = IntegrateDhtOpsWorkspace::new(env.clone().into())
                 .expect("Could not create Workspace");
-            if let WorkComplete::Incomplete =
-                integrate_dht_ops_workflow(workspace, env.clone().into(), &mut trigger_sys)
-                    .await
-                    .expect("Error running Workflow")
+            workspace.held_op_types = held_op_types.clone();
+            workspace.latency = latency.clone();
+            if let WorkComplete::Incomplete = integrate_dht_ops_workflow(
+                workspace,
+                env.clone().into(),
+                &mut trigger_sys,
+                &integration_events,
+            )
+            .await
+            .expect("Error running Workflow")
             {
-                trigger_self.trigger()
+                trigger_self.trigger();
+                rx.skip_next_flush_window();
             };
         }
         Ok(())
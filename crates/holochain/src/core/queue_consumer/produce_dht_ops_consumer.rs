@@ -11,17 +11,20 @@ use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for Produce_dht_ops workflow
-#[instrument(skip(env, stop, trigger_publish))]
+#[instrument(skip(env, stop, pause, trigger_publish, runtime))]
 pub fn spawn_produce_dht_ops_consumer(
     env: EnvironmentWrite,
     mut stop: sync::broadcast::Receiver<()>,
+    mut pause: sync::watch::Receiver<bool>,
     mut trigger_publish: TriggerSender,
+    runtime: Option<tokio::runtime::Handle>,
 ) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
     let (tx, mut rx) = TriggerSender::new();
+    let tx = tx.for_workflow("produce_dht_ops");
     let mut trigger_self = tx.clone();
-    let handle = tokio::spawn(async move {
+    let handle = spawn_on(runtime, async move {
         loop {
-            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
+            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop, &mut pause).await {
                 tracing::warn!(
                     "Cell is shutting down: stopping produce_dht_ops_workflow queue consumer."
                 );
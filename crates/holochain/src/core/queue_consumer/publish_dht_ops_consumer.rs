@@ -4,7 +4,9 @@ use super::*;
 
 use crate::{
     conductor::manager::ManagedTaskResult,
-    core::workflow::publish_dht_ops_workflow::{publish_dht_ops_workflow, PublishDhtOpsWorkspace},
+    core::workflow::publish_dht_ops_workflow::{
+        publish_dht_ops_workflow, OpEgressFilterRef, PublishDhtOpsWorkspace,
+    },
 };
 use holochain_state::env::EnvironmentWrite;
 
@@ -12,18 +14,22 @@ use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for Publish workflow
-#[instrument(skip(env, stop, cell_network))]
+#[instrument(skip(env, stop, pause, cell_network, egress_filter, runtime))]
 pub fn spawn_publish_dht_ops_consumer(
     env: EnvironmentWrite,
     mut stop: sync::broadcast::Receiver<()>,
+    mut pause: sync::watch::Receiver<bool>,
     mut cell_network: HolochainP2pCell,
+    egress_filter: OpEgressFilterRef,
+    runtime: Option<tokio::runtime::Handle>,
 ) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
     let (tx, mut rx) = TriggerSender::new();
+    let tx = tx.for_workflow("publish_dht_ops");
     let mut trigger_self = tx.clone();
-    let handle = tokio::spawn(async move {
+    let handle = spawn_on(runtime, async move {
         loop {
             // Wait for next job
-            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
+            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop, &mut pause).await {
                 tracing::warn!(
                     "Cell is shutting down: stopping publish_dht_ops_workflow queue consumer."
                 );
@@ -31,8 +37,9 @@ pub fn spawn_publish_dht_ops_consumer(
             }
 
             // Run the workflow
-            let workspace = PublishDhtOpsWorkspace::new(env.clone().into())
+            let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into())
                 .expect("Could not create Workspace");
+            workspace.egress_filter = egress_filter.clone();
             if let WorkComplete::Incomplete =
                 publish_dht_ops_workflow(workspace, env.clone().into(), &mut cell_network)
                     .await
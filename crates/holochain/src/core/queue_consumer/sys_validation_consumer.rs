@@ -2,28 +2,65 @@
 
 use super::*;
 use crate::{
-    conductor::manager::ManagedTaskResult,
-    core::workflow::sys_validation_workflow::{sys_validation_workflow, SysValidationWorkspace},
+    conductor::{
+        config::{DepCacheConfig, GossipRequestPolicy, ValidationProfile},
+        manager::ManagedTaskResult,
+    },
+    core::{
+        state::cascade::{breaker::NetworkBreaker, retry::RetrySchedule},
+        workflow::sys_validation_workflow::{
+            metrics::DependencyCounters, sys_validation_workflow, types::CheckLevel,
+            SysValidationWorkspace,
+        },
+    },
 };
 use holochain_state::env::EnvironmentWrite;
 use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for SysValidation workflow
-#[instrument(skip(env, stop, trigger_app_validation, network, conductor_api))]
+#[instrument(skip(
+    env,
+    stop,
+    pause,
+    trigger_app_validation,
+    network,
+    network_breaker,
+    request_policy,
+    validation_profile,
+    dep_cache,
+    dependency_counters,
+    conductor_api,
+    runtime
+))]
 pub fn spawn_sys_validation_consumer(
     env: EnvironmentWrite,
     mut stop: sync::broadcast::Receiver<()>,
+    mut pause: sync::watch::Receiver<bool>,
     mut trigger_app_validation: TriggerSender,
     network: HolochainP2pCell,
+    network_breaker: NetworkBreaker,
+    request_policy: GossipRequestPolicy,
+    validation_profile: ValidationProfile,
+    author_is_authority: bool,
+    max_pending_dependency_depth: usize,
+    dep_cache: Option<DepCacheConfig>,
+    dependency_counters: DependencyCounters,
     conductor_api: impl CellConductorApiT + 'static,
+    runtime: Option<tokio::runtime::Handle>,
+    flush_window: Option<std::time::Duration>,
 ) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
-    let (tx, mut rx) = TriggerSender::new();
+    // Ops arriving from peers are triggered at a high priority so they
+    // skip the flush window the integration and retry triggers wait in
+    let (tx, rx) = TriggerSender::new_prioritized();
+    let tx = tx.for_workflow("sys_validation");
+    let mut rx = rx.with_flush_window(flush_window);
     let mut trigger_self = tx.clone();
-    let handle = tokio::spawn(async move {
+    let retry_schedule = RetrySchedule::new(request_policy);
+    let handle = spawn_on(runtime, async move {
         loop {
             // Wait for next job
-            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
+            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop, &mut pause).await {
                 tracing::warn!(
                     "Cell is shutting down: stopping sys_validation_workflow queue consumer."
                 );
@@ -31,8 +68,25 @@ pub fn spawn_sys_validation_consumer(
             }
 
             // Run the workflow
-            let workspace = SysValidationWorkspace::new(env.clone().into())
-                .expect("Could not create Workspace");
+            let mut workspace = match &dep_cache {
+                Some(config) => SysValidationWorkspace::with_dep_cache(
+                    env.clone().into(),
+                    config.capacity,
+                    std::time::Duration::from_secs(config.ttl_secs),
+                )
+                .map(|mut workspace| {
+                    workspace.check_level = CheckLevel::Cached;
+                    workspace
+                }),
+                None => SysValidationWorkspace::new(env.clone().into()),
+            }
+            .expect("Could not create Workspace");
+            workspace.network_breaker = Some(network_breaker.clone());
+            workspace.retry_schedule = Some(retry_schedule.clone());
+            workspace.validation_profile = validation_profile.clone();
+            workspace.author_is_authority = author_is_authority;
+            workspace.max_pending_dependency_depth = max_pending_dependency_depth;
+            workspace.dependency_counters = dependency_counters.clone();
             if let WorkComplete::Incomplete = sys_validation_workflow(
                 workspace,
                 env.clone().into(),
@@ -43,8 +97,19 @@ pub fn spawn_sys_validation_consumer(
             .await
             .expect("Error running Workflow")
             {
-                trigger_self.trigger()
+                trigger_self.trigger();
+                rx.skip_next_flush_window();
             };
+
+            // Come back for the dependencies that are backing off
+            // once the earliest retry is due
+            if let Some(due) = retry_schedule.next_due() {
+                let mut trigger_self = trigger_self.clone();
+                tokio::task::spawn(async move {
+                    tokio::time::delay_until(tokio::time::Instant::from_std(due)).await;
+                    trigger_self.trigger();
+                });
+            }
         }
         Ok(())
     });
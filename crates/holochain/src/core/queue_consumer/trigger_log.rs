@@ -0,0 +1,56 @@
+//! A log of every queue trigger, for reconstructing how the workflows
+//! interleaved when chasing an ordering bug.
+//!
+//! Triggers are only recorded with the `trigger_log` feature.
+//! Without it recording does nothing and the log is always empty.
+
+use super::Priority;
+use holochain_types::Timestamp;
+use std::panic::Location;
+
+#[cfg(feature = "trigger_log")]
+lazy_static::lazy_static! {
+    static ref TRIGGER_LOG: parking_lot::Mutex<Vec<TriggerEvent>> =
+        parking_lot::Mutex::new(Vec::new());
+}
+
+/// A single call to [TriggerSender::trigger](super::TriggerSender::trigger)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggerEvent {
+    /// When the trigger was sent
+    pub at: Timestamp,
+    /// Where in the code the trigger was sent from
+    pub source: &'static Location<'static>,
+    /// The workflow whose consumer was triggered
+    pub target: &'static str,
+    /// The priority the trigger was sent at
+    pub priority: Priority,
+}
+
+/// Record a trigger sent to the target workflow
+#[allow(unused_variables)]
+pub(super) fn record(source: &'static Location<'static>, target: &'static str, priority: Priority) {
+    #[cfg(feature = "trigger_log")]
+    TRIGGER_LOG.lock().push(TriggerEvent {
+        at: Timestamp::now(),
+        source,
+        target,
+        priority,
+    });
+}
+
+/// Every trigger recorded so far, oldest first.
+/// The log is shared by all Cells so filter by target when
+/// other Cells may be running.
+pub fn dump() -> Vec<TriggerEvent> {
+    #[cfg(feature = "trigger_log")]
+    return TRIGGER_LOG.lock().clone();
+    #[cfg(not(feature = "trigger_log"))]
+    Vec::new()
+}
+
+/// Forget every trigger recorded so far
+pub fn clear() {
+    #[cfg(feature = "trigger_log")]
+    TRIGGER_LOG.lock().clear();
+}
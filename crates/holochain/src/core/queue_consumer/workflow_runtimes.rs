@@ -0,0 +1,215 @@
+//! Dedicated runtimes that classes of queue consumer can be pinned to,
+//! so they keep running while the conductor's runtime is busy with zome calls.
+
+use crate::conductor::config::WorkflowRuntimeConfig;
+use futures::Future;
+use std::{sync::Arc, time::Duration};
+use tokio::{runtime::Handle, task::JoinHandle};
+
+/// The runtimes each class of workflow is spawned on.
+/// A class without a runtime is spawned on the current runtime.
+#[derive(Clone, Default)]
+pub struct WorkflowRuntimes {
+    validation: Option<Arc<DedicatedRuntime>>,
+    authoring: Option<Arc<DedicatedRuntime>>,
+    validation_flush_window: Option<Duration>,
+}
+
+impl WorkflowRuntimes {
+    /// Start a runtime for each class of workflow the config gives threads to
+    pub fn new(config: &WorkflowRuntimeConfig) -> std::io::Result<Self> {
+        let start = |name: &str, threads: Option<usize>| {
+            threads
+                .map(|threads| DedicatedRuntime::new(name, threads).map(Arc::new))
+                .transpose()
+        };
+        Ok(Self {
+            validation: start("holochain-validation", config.validation_threads)?,
+            authoring: start("holochain-authoring", config.authoring_threads)?,
+            validation_flush_window: config.validation_flush_window_ms.map(Duration::from_millis),
+        })
+    }
+
+    /// The runtime for sys validation, app validation and integration, if any
+    pub fn validation(&self) -> Option<Handle> {
+        self.validation.as_ref().map(|rt| rt.handle.clone())
+    }
+
+    /// How long sys validation, app validation and integration
+    /// coalesce triggers before running, if at all
+    pub fn validation_flush_window(&self) -> Option<Duration> {
+        self.validation_flush_window
+    }
+
+    /// The runtime for producing and publishing authored ops, if any
+    pub fn authoring(&self) -> Option<Handle> {
+        self.authoring.as_ref().map(|rt| rt.handle.clone())
+    }
+}
+
+/// Spawn a queue consumer on its dedicated runtime,
+/// or on the current runtime if it doesn't have one
+pub(super) fn spawn_on<F>(runtime: Option<Handle>, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match runtime {
+        Some(runtime) => runtime.spawn(future),
+        None => tokio::spawn(future),
+    }
+}
+
+/// A runtime driven by its own thread until it is dropped.
+/// The runtime is shut down on that thread because
+/// a runtime can't be dropped from inside an async context.
+struct DedicatedRuntime {
+    handle: Handle,
+    shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl DedicatedRuntime {
+    fn new(name: &str, threads: usize) -> std::io::Result<Self> {
+        let mut runtime = tokio::runtime::Builder::new()
+            .threaded_scheduler()
+            .core_threads(threads.max(1))
+            .thread_name(name)
+            .enable_all()
+            .build()?;
+        let handle = runtime.handle().clone();
+        let (shutdown, stopped) = tokio::sync::oneshot::channel::<()>();
+        std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                runtime.block_on(stopped).ok();
+            })?;
+        Ok(Self {
+            handle,
+            shutdown: Some(shutdown),
+        })
+    }
+}
+
+impl Drop for DedicatedRuntime {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        queue_consumer::{spawn_integrate_dht_ops_consumer, TriggerSender, WorkflowPause},
+        state::{dht_op_integration::IntegrationLimboValue, workspace::Workspace},
+        workflow::integrate_dht_ops_workflow::{IntegrateDhtOpsWorkspace, IntegrationEvent},
+    };
+    use ::fixt::prelude::*;
+    use holo_hash::DhtOpHash;
+    use holochain_state::{env::WriteManager, test_utils::test_cell_env};
+    use holochain_types::{
+        dht_op::{DhtOp, HeldOpTypes},
+        fixt::*,
+        validate::ValidationStatus,
+        HeaderHashed,
+    };
+    use holochain_zome_types::element::SignedHeaderHashed;
+    use std::time::{Duration, Instant};
+
+    /// Ops are still integrated while every thread zome calls run on is blocked
+    #[test]
+    fn validation_progresses_while_zome_call_pool_is_saturated() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut zome_call_pool = tokio::runtime::Builder::new()
+            .threaded_scheduler()
+            .core_threads(1)
+            .enable_all()
+            .build()
+            .unwrap();
+        let runtimes = WorkflowRuntimes::new(&WorkflowRuntimeConfig {
+            validation_threads: Some(1),
+            authoring_threads: None,
+            validation_flush_window_ms: None,
+        })
+        .unwrap();
+
+        // A zome call that never yields holds the only thread
+        let (busy_tx, busy_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        zome_call_pool.spawn(async move {
+            busy_tx.send(()).unwrap();
+            release_rx.recv().ok();
+        });
+        busy_rx.recv().unwrap();
+
+        zome_call_pool.block_on(async move {
+            let sig = fixt!(Signature);
+            let header = fixt!(Header);
+            let op = DhtOp::RegisterAgentActivity(sig.clone(), header.clone());
+            let hash = DhtOpHash::with_data_sync(&op);
+            {
+                let mut workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+                let iv = IntegrationLimboValue {
+                    validation_status: ValidationStatus::Valid,
+                    op: op.to_light().await,
+                    time_added: None,
+                };
+                workspace.integration_limbo.put(hash.clone(), iv).unwrap();
+                let signed_header = SignedHeaderHashed::with_presigned(
+                    HeaderHashed::from_content_sync(header),
+                    sig,
+                );
+                workspace.element_judged.put(signed_header, None).unwrap();
+                env.guard()
+                    .with_commit(|writer| workspace.flush_to_txn(writer))
+                    .unwrap();
+            }
+
+            let (stop, _) = tokio::sync::broadcast::channel(1);
+            let pause = WorkflowPause::default();
+            let (integration_events, mut events) = tokio::sync::broadcast::channel(1);
+            let (create_tx_sys, get_tx_sys) = tokio::sync::oneshot::channel();
+            let (mut trigger_integration, _handle) = spawn_integrate_dht_ops_consumer(
+                env.clone(),
+                stop.subscribe(),
+                pause.subscribe(),
+                get_tx_sys,
+                HeldOpTypes::default(),
+                integration_events,
+                Default::default(),
+                runtimes.validation(),
+                runtimes.validation_flush_window(),
+            );
+            let (tx_sys, _rx) = TriggerSender::new();
+            if create_tx_sys.send(tx_sys).is_err() {
+                panic!("Failed to send tx_sys");
+            }
+            trigger_integration.trigger();
+
+            // Timers can't fire on the blocked runtime so poll against the clock
+            let started = Instant::now();
+            let event = loop {
+                match events.try_recv() {
+                    Ok(event) => break event,
+                    Err(_) if started.elapsed() < Duration::from_secs(5) => {
+                        std::thread::sleep(Duration::from_millis(10))
+                    }
+                    Err(e) => panic!("op was not integrated: {:?}", e),
+                }
+            };
+            assert_eq!(
+                event,
+                IntegrationEvent {
+                    op_hash: hash,
+                    status: ValidationStatus::Valid,
+                }
+            );
+            stop.send(()).ok();
+        });
+
+        release_tx.send(()).unwrap();
+    }
+}
@@ -7,11 +7,16 @@
 
 // This allow is here because #[automock] automaticaly creates a struct without
 // documentation, and there seems to be no way to add docs to it after the fact
+pub mod cancel;
 pub mod error;
 pub mod guest_callback;
 pub mod host_fn;
+pub mod host_fn_executor;
+pub mod module_cache;
 pub mod wasm_ribosome;
 
+use crate::conductor::config::HostFnBudgetConfig;
+use crate::core::ribosome::cancel::ZomeCallCancel;
 use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
@@ -21,6 +26,8 @@ use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitResult;
+use crate::core::ribosome::guest_callback::resolve_conflict::ResolveConflictInvocation;
+use crate::core::ribosome::guest_callback::resolve_conflict::ResolveConflictResult;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::validate_link_add::ValidateCreateLinkHostAccess;
@@ -29,17 +36,18 @@ use crate::core::ribosome::guest_callback::validate_link_add::ValidateCreateLink
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
 use crate::core::ribosome::guest_callback::CallIterator;
+use crate::core::ribosome::host_fn_executor::HostFnExecutor;
+use crate::core::signal::SignalSender;
 use crate::core::workflow::CallZomeWorkspaceLock;
 use crate::fixt::ExternInputFixturator;
 use crate::fixt::FunctionNameFixturator;
 use crate::fixt::ZomeNameFixturator;
 use ::fixt::prelude::*;
-use derive_more::Constructor;
 use error::RibosomeResult;
 use guest_callback::{
     entry_defs::EntryDefsHostAccess, init::InitHostAccess, migrate_agent::MigrateAgentHostAccess,
-    post_commit::PostCommitHostAccess, validate::ValidateHostAccess,
-    validation_package::ValidationPackageHostAccess,
+    post_commit::PostCommitHostAccess, resolve_conflict::ResolveConflictHostAccess,
+    validate::ValidateHostAccess, validation_package::ValidationPackageHostAccess,
 };
 use holo_hash::fixt::AgentPubKeyFixturator;
 use holo_hash::AgentPubKey;
@@ -59,12 +67,17 @@ use holochain_zome_types::ExternOutput;
 use holochain_zome_types::ZomeCallResponse;
 use holochain_zome_types::{capability::CapSecret, header::ZomeId, ExternInput};
 use mockall::automock;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
 use std::iter::Iterator;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct CallContext {
     pub zome_name: ZomeName,
     pub host_access: HostAccess,
+    /// Bounds the host functions blocked at once across the conductor
+    pub host_fn_executor: HostFnExecutor,
 }
 
 impl CallContext {
@@ -72,6 +85,7 @@ impl CallContext {
         Self {
             zome_name,
             host_access,
+            host_fn_executor: HostFnExecutor::default(),
         }
     }
 
@@ -93,6 +107,7 @@ pub enum HostAccess {
     MigrateAgent(MigrateAgentHostAccess),
     ValidationPackage(ValidationPackageHostAccess),
     PostCommit(PostCommitHostAccess),
+    ResolveConflict(ResolveConflictHostAccess),
 }
 
 impl From<&HostAccess> for HostFnAccess {
@@ -110,6 +125,9 @@ impl From<&HostAccess> for HostFnAccess {
                 validation_package_host_access.into()
             }
             HostAccess::PostCommit(post_commit_host_access) => post_commit_host_access.into(),
+            HostAccess::ResolveConflict(resolve_conflict_host_access) => {
+                resolve_conflict_host_access.into()
+            }
         }
     }
 }
@@ -122,7 +140,8 @@ impl HostAccess {
             Self::Init(InitHostAccess{workspace, .. }) |
             Self::MigrateAgent(MigrateAgentHostAccess{workspace, .. }) |
             Self::ValidationPackage(ValidationPackageHostAccess{workspace, .. }) |
-            Self::PostCommit(PostCommitHostAccess{workspace, .. }) => {
+            Self::PostCommit(PostCommitHostAccess{workspace, .. }) |
+            Self::Validate(ValidateHostAccess{workspace, .. }) => {
                 workspace
             }
             _ => panic!("Gave access to a host function that uses the workspace without providing a workspace"),
@@ -141,12 +160,74 @@ impl HostAccess {
         }
     }
 
-    /// Get the network, panics if none was provided
+    /// Get the host function call counts if this access is for a zome call
+    pub fn host_fn_calls(&self) -> Option<&HostFnCallCounts> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess { host_fn_calls, .. }) => Some(host_fn_calls),
+            _ => None,
+        }
+    }
+
+    /// Get the cancellation token if this access is for a zome call
+    pub fn cancel(&self) -> Option<&ZomeCallCancel> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess { cancel, .. }) => Some(cancel),
+            _ => None,
+        }
+    }
+
+    /// Get where emitted signals go if this access is for a zome call of a Cell
+    pub fn signal_tx(&self) -> Option<&SignalSender> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess { signal_tx, .. }) => signal_tx.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Fail with [RibosomeError::CellReadOnly] if this access is for a zome call
+    /// on a read-only Cell. Host functions that commit check this first.
+    pub fn check_writable(&self) -> RibosomeResult<()> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess {
+                read_only: true, ..
+            }) => Err(RibosomeError::CellReadOnly),
+            _ => Ok(()),
+        }
+    }
+
+    /// Charge `calls` to a category of this zome call's budget.
+    /// Fail with [RibosomeError::HostCallBudgetExceeded] if that takes the call
+    /// past its budget. Only zome calls are charged.
+    pub fn charge_host_fn_budget(
+        &self,
+        category: HostFnCategory,
+        calls: u32,
+    ) -> RibosomeResult<()> {
+        if let Self::ZomeCall(ZomeCallHostAccess {
+            host_fn_calls,
+            host_fn_budget,
+            ..
+        }) = self
+        {
+            let charged = host_fn_calls.charge(category, calls);
+            if let Some(budget) = host_fn_budget.budget(category) {
+                if charged > budget {
+                    return Err(RibosomeError::HostCallBudgetExceeded(category, budget));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the network, panics if none was provided.
+    /// Cloning the returned handle is cheap so host functions can
+    /// clone it freely.
     pub fn network(&self) -> &HolochainP2pCell {
         match self {
             Self::ZomeCall(ZomeCallHostAccess { network, .. })
             | Self::Init(InitHostAccess { network, .. })
-            | Self::PostCommit(PostCommitHostAccess { network, .. }) => network,
+            | Self::PostCommit(PostCommitHostAccess { network, .. })
+            | Self::Validate(ValidateHostAccess { network, .. }) => network,
             _ => panic!(
                 "Gave access to a host function that uses the network without providing a network"
             ),
@@ -154,6 +235,41 @@ impl HostAccess {
     }
 }
 
+/// How the input or output of a zome function call appears in the logs.
+/// The payloads of functions marked sensitive in the DnaDef are replaced
+/// by `<redacted>` so private data never reaches the logs.
+pub struct LoggedPayload<'a, P: std::fmt::Debug> {
+    payload: &'a P,
+    redacted: bool,
+}
+
+impl<'a, P: std::fmt::Debug> LoggedPayload<'a, P> {
+    /// Log the payload of a call to this zome function in this dna
+    pub fn new(
+        dna_file: &DnaFile,
+        zome_name: &ZomeName,
+        fn_name: &FunctionName,
+        payload: &'a P,
+    ) -> Self {
+        Self::redacted_if(payload, dna_file.dna().is_sensitive(zome_name, fn_name))
+    }
+
+    /// Log a payload that carries a call to a sensitive function when `redacted` is true
+    pub fn redacted_if(payload: &'a P, redacted: bool) -> Self {
+        Self { payload, redacted }
+    }
+}
+
+impl<'a, P: std::fmt::Debug> std::fmt::Debug for LoggedPayload<'a, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.redacted {
+            write!(f, "<redacted>")
+        } else {
+            self.payload.fmt(f)
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FnComponents(pub Vec<String>);
 
@@ -218,6 +334,13 @@ pub trait Invocation: Clone {
     /// this is intentionally NOT a reference to self because ExternInput may be huge we want to be
     /// careful about cloning invocations
     fn host_input(self) -> Result<ExternInput, SerializedBytesError>;
+    /// Whether each zome's callbacks can run without seeing the results of
+    /// the other zomes, so the zomes can be called in parallel.
+    /// Callbacks with side effects that must happen in order, like init, are
+    /// always called one zome at a time.
+    fn order_independent(&self) -> bool {
+        false
+    }
 }
 
 impl ZomeCallInvocation {
@@ -351,11 +474,110 @@ impl Invocation for ZomeCallInvocation {
     }
 }
 
-#[derive(Clone, Constructor)]
+#[derive(Clone)]
 pub struct ZomeCallHostAccess {
     pub workspace: CallZomeWorkspaceLock,
     pub keystore: KeystoreSender,
     pub network: HolochainP2pCell,
+    /// How many times the zome called each host function
+    pub host_fn_calls: HostFnCallCounts,
+    /// Cancels the call, releasing any host function blocked on async work
+    pub cancel: ZomeCallCancel,
+    /// The Cell only observes the DHT so commits fail
+    pub read_only: bool,
+    /// The most host function calls of each category the zome can make
+    pub host_fn_budget: HostFnBudgetConfig,
+    /// Where the signals the zome emits go, if anywhere
+    pub signal_tx: Option<SignalSender>,
+}
+
+impl ZomeCallHostAccess {
+    pub fn new(
+        workspace: CallZomeWorkspaceLock,
+        keystore: KeystoreSender,
+        network: HolochainP2pCell,
+    ) -> Self {
+        Self {
+            workspace,
+            keystore,
+            network,
+            host_fn_calls: HostFnCallCounts::default(),
+            cancel: ZomeCallCancel::default(),
+            read_only: false,
+            host_fn_budget: HostFnBudgetConfig::default(),
+            signal_tx: None,
+        }
+    }
+}
+
+/// Counts of the host functions a zome has called, by name,
+/// and of what they were charged to each [HostFnCategory].
+/// Clones share the same counts.
+#[derive(Clone, Debug, Default)]
+pub struct HostFnCallCounts(Arc<Mutex<CallCounts>>);
+
+#[derive(Debug, Default)]
+struct CallCounts {
+    by_host_fn: BTreeMap<String, u32>,
+    by_category: BTreeMap<HostFnCategory, u32>,
+}
+
+impl HostFnCallCounts {
+    /// Count one call to a host function
+    pub fn count(&self, host_fn: &str) {
+        *self
+            .0
+            .lock()
+            .by_host_fn
+            .entry(host_fn.to_string())
+            .or_default() += 1;
+    }
+
+    /// Charge `calls` to a category and return what it has been charged in total
+    pub fn charge(&self, category: HostFnCategory, calls: u32) -> u32 {
+        let mut counts = self.0.lock();
+        let charged = counts.by_category.entry(category).or_default();
+        *charged = charged.saturating_add(calls);
+        *charged
+    }
+
+    /// How many times a host function has been called
+    pub fn get(&self, host_fn: &str) -> u32 {
+        self.0
+            .lock()
+            .by_host_fn
+            .get(host_fn)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The counts of every host function that has been called
+    pub fn snapshot(&self) -> BTreeMap<String, u32> {
+        self.0.lock().by_host_fn.clone()
+    }
+
+    /// How many calls have been charged to a category
+    pub fn in_category(&self, category: HostFnCategory) -> u32 {
+        self.0
+            .lock()
+            .by_category
+            .get(&category)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// The kinds of host function that reach the network,
+/// which a [HostFnBudgetConfig] limits separately.
+/// Each host function's category is given where the ribosome imports it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HostFnCategory {
+    /// Gets from the DHT, like `get` and `get_links`
+    NetworkGet,
+    /// Commits to the source chain, which are then published
+    Commit,
+    /// Calls to other agents
+    RemoteCall,
 }
 
 impl From<ZomeCallHostAccess> for HostAccess {
@@ -388,12 +610,9 @@ pub trait RibosomeT: Sized + std::fmt::Debug {
         to_call: &FunctionName,
     ) -> Result<Option<ExternOutput>, RibosomeError>;
 
-    /// @todo list out all the available callbacks and maybe cache them somewhere
-    fn list_callbacks(&self) {
-        unimplemented!()
-        // pseudocode
-        // self.instance().exports().filter(|e| e.is_callback())
-    }
+    /// The callbacks a zome implements, found from its wasm exports without
+    /// calling anything. Sorted by name.
+    fn list_callbacks(&self, zome_name: &ZomeName) -> RibosomeResult<Vec<FunctionName>>;
 
     /// @todo list out all the available zome functions and maybe cache them somewhere
     fn list_zome_fns(&self) {
@@ -432,6 +651,12 @@ pub trait RibosomeT: Sized + std::fmt::Debug {
         invocation: PostCommitInvocation,
     ) -> RibosomeResult<PostCommitResult>;
 
+    fn run_resolve_conflict(
+        &self,
+        access: ResolveConflictHostAccess,
+        invocation: ResolveConflictInvocation,
+    ) -> RibosomeResult<ResolveConflictResult>;
+
     /// Helper function for running a validation callback. Just calls
     /// [`run_callback`][] under the hood.
     /// [`run_callback`]: #method.run_callback
@@ -553,6 +778,33 @@ pub mod wasm_test {
 
         assert_eq!(fn_components.into_iter().collect::<Vec<String>>(), expected,);
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn sensitive_payloads_are_redacted_in_logs() {
+        use crate::core::ribosome::LoggedPayload;
+        use holochain_serialized_bytes::prelude::*;
+        use holochain_types::dna::{DnaDef, DnaFile};
+        use holochain_wasm_test_utils::TestWasm;
+        use holochain_zome_types::{zome::ZomeName, ExternInput};
+
+        let dna_def = DnaDef::builder()
+            .add_zome(TestWasm::Foo)
+            .sensitive_fn(TestWasm::Foo, "secret")
+            .build()
+            .unwrap();
+        let dna_file = DnaFile::new(dna_def, vec![]).await.unwrap();
+        let zome_name: ZomeName = TestWasm::Foo.into();
+        let payload = ExternInput::new(SerializedBytes::try_from(()).unwrap());
+
+        let logged = |fn_name: &str| {
+            format!(
+                "{:?}",
+                LoggedPayload::new(&dna_file, &zome_name, &fn_name.into(), &payload)
+            )
+        };
+        assert_eq!(logged("secret"), "<redacted>");
+        assert_eq!(logged("foo"), format!("{:?}", payload));
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,80 @@
+//! Cancelling a zome call while a host function is blocked on async work.
+//!
+//! Host functions are synchronous but most of what they do (network, database)
+//! is async, so they block the wasm thread until the work is done.
+//! [block_on_cancellable] blocks in the same way but also returns as soon as
+//! the call's [ZomeCallCancel] is triggered, so cancelled calls don't leave
+//! threads blocked on a network request nobody is waiting for.
+
+use crate::core::ribosome::error::{RibosomeError, RibosomeResult};
+use crate::core::ribosome::host_fn_executor::HostFnExecutor;
+use futures::future::{self, Either, Future};
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// A token to cancel a zome call.
+/// Clones share the same state, so cancelling any clone cancels them all.
+#[derive(Clone, Debug)]
+pub struct ZomeCallCancel(Arc<(watch::Sender<bool>, watch::Receiver<bool>)>);
+
+impl Default for ZomeCallCancel {
+    fn default() -> Self {
+        Self(Arc::new(watch::channel(false)))
+    }
+}
+
+impl ZomeCallCancel {
+    /// Cancel the call. Anything blocked in [block_on_cancellable] with this
+    /// token returns [RibosomeError::ZomeCallCancelled].
+    pub fn cancel(&self) {
+        // Can't fail because we hold a receiver
+        let _ = (self.0).0.broadcast(true);
+    }
+
+    /// Has the call been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        *(self.0).1.borrow()
+    }
+
+    /// Resolves once the call is cancelled
+    pub async fn cancelled(&self) {
+        let mut rx = (self.0).1.clone();
+        while let Some(cancelled) = rx.recv().await {
+            if cancelled {
+                return;
+            }
+        }
+    }
+}
+
+/// Block on a future from inside a host function.
+/// Takes a slot in the conductor's [HostFnExecutor] before blocking, so a
+/// burst of blocked host functions waits outside the blocking pool instead of
+/// exhausting it.
+/// Returns early with [RibosomeError::ZomeCallCancelled] if the token is
+/// cancelled, whether it is waiting for a slot or blocked. Without a token this behaves like `tokio_safe_block_forever_on`.
+pub fn block_on_cancellable<T, F>(
+    executor: &HostFnExecutor,
+    cancel: Option<ZomeCallCancel>,
+    f: F,
+) -> RibosomeResult<T>
+where
+    F: Future<Output = RibosomeResult<T>>,
+{
+    if cancel.as_ref().map_or(false, |c| c.is_cancelled()) {
+        return Err(RibosomeError::ZomeCallCancelled);
+    }
+    let _permit = executor.acquire(cancel.as_ref())?;
+    let cancel = match cancel {
+        Some(cancel) => cancel,
+        None => return tokio_safe_block_on::tokio_safe_block_forever_on(f),
+    };
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let cancelled = cancel.cancelled();
+        futures::pin_mut!(f, cancelled);
+        match future::select(f, cancelled).await {
+            Either::Left((r, _)) => r,
+            Either::Right(((), _)) => Err(RibosomeError::ZomeCallCancelled),
+        }
+    })
+}
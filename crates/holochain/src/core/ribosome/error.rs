@@ -1,8 +1,10 @@
 #![deny(missing_docs)]
 //! Errors occurring during a [Ribosome] call
 
+use crate::core::ribosome::HostFnCategory;
 use crate::core::state::{cascade::error::CascadeError, source_chain::SourceChainError};
-use holo_hash::AnyDhtHash;
+use crate::core::sys_validate::ValidationOutcome;
+use holo_hash::{AnyDhtHash, EntryHash, HeaderHash};
 use holochain_crypto::CryptoError;
 use holochain_serialized_bytes::prelude::SerializedBytesError;
 use holochain_types::dna::error::DnaError;
@@ -40,6 +42,14 @@ pub enum RibosomeError {
     #[error("An error with entry defs: {0}")]
     EntryDefs(ZomeName, String),
 
+    /// An entry was committed that is over the Dna's max entry size
+    #[error("The entry size {0} was bigger then the max entry size {1}")]
+    EntryTooLarge(usize, usize),
+
+    /// The resolve_conflict callback failed or chose a header that wasn't competing
+    #[error("The resolve_conflict callback in zome {0} failed: {1}")]
+    ConflictResolution(ZomeName, String),
+
     /// a mandatory dependency for an element doesn't exist
     /// for example a remove link ribosome call needs to find the add link in order to infer the
     /// correct base and this dependent relationship exists before even subconscious validation
@@ -47,6 +57,26 @@ pub enum RibosomeError {
     #[error("A mandatory element is missing, dht hash: {0}")]
     ElementDeps(AnyDhtHash),
 
+    /// The element can't be deleted because the delete would fail sys validation
+    #[error("The element {0} can't be deleted: {1}")]
+    InvalidDelete(HeaderHash, ValidationOutcome),
+
+    /// The zome call was cancelled while a host function was waiting on it
+    #[error("The zome call was cancelled")]
+    ZomeCallCancelled,
+
+    /// A commit was attempted on a Cell that only observes the DHT
+    #[error("The cell is read-only and can't commit")]
+    CellReadOnly,
+
+    /// A link base or target is not a whole hash so the link would fail sys validation
+    #[error("The link address {0:?} is not a valid entry hash or agent key")]
+    MalformedLinkAddress(EntryHash),
+
+    /// The zome call made more host function calls of a category than its budget allows
+    #[error("The zome call went over its budget of {1} {0:?} host function calls")]
+    HostCallBudgetExceeded(HostFnCategory, u32),
+
     /// ident
     #[error(transparent)]
     CryptoError(#[from] CryptoError),
@@ -74,6 +104,10 @@ pub enum RibosomeError {
     /// ident
     #[error(transparent)]
     P2pError(#[from] holochain_p2p::HolochainP2pError),
+
+    /// ident
+    #[error(transparent)]
+    KeystoreError(#[from] holochain_keystore::KeystoreError),
 }
 
 /// Type alias
@@ -2,6 +2,7 @@ pub mod entry_defs;
 pub mod init;
 pub mod migrate_agent;
 pub mod post_commit;
+pub mod resolve_conflict;
 pub mod validate;
 pub mod validate_link_add;
 pub mod validation_package;
@@ -11,8 +12,29 @@ use crate::core::ribosome::FnComponents;
 use crate::core::ribosome::Invocation;
 use crate::core::ribosome::RibosomeT;
 use fallible_iterator::FallibleIterator;
-use holochain_zome_types::zome::ZomeName;
+use holochain_zome_types::zome::{FunctionName, ZomeName};
 use holochain_zome_types::ExternOutput;
+use std::sync::Arc;
+
+/// The first component of every callback name.
+/// Callbacks are called by these names alone or followed by more
+/// specific components, e.g. `validate` and `validate_entry`.
+pub const CALLBACK_ROOTS: [&str; 7] = [
+    "entry_defs",
+    "init",
+    "migrate_agent",
+    "post_commit",
+    "resolve_conflict",
+    "validate",
+    "validation_package",
+];
+
+/// Whether a function with this name would be called as a callback
+pub fn is_callback(fn_name: &str) -> bool {
+    CALLBACK_ROOTS.iter().any(|root| {
+        fn_name == *root || (fn_name.starts_with(root) && fn_name[root.len()..].starts_with('_'))
+    })
+}
 
 pub struct CallIterator<R: RibosomeT, I: Invocation> {
     host_access: HostAccess,
@@ -20,6 +42,8 @@ pub struct CallIterator<R: RibosomeT, I: Invocation> {
     invocation: I,
     remaining_zomes: Vec<ZomeName>,
     remaining_components: FnComponents,
+    /// The callbacks the current zome exports, listed when we reach the zome
+    exported_callbacks: Option<Vec<FunctionName>>,
 }
 
 impl<R: RibosomeT, I: Invocation> CallIterator<R, I> {
@@ -30,8 +54,17 @@ impl<R: RibosomeT, I: Invocation> CallIterator<R, I> {
             ribosome,
             remaining_components: invocation.fn_components(),
             invocation,
+            exported_callbacks: None,
         }
     }
+
+    /// Only call the zomes the filter keeps.
+    /// The filter runs once for each zome here, not as the zomes are reached,
+    /// so nothing is called if it keeps none of them.
+    pub fn with_zome_filter<F: FnMut(&ZomeName) -> bool>(mut self, mut filter: F) -> Self {
+        self.remaining_zomes.retain(|zome_name| filter(zome_name));
+        self
+    }
 }
 
 impl<R: RibosomeT, I: Invocation + 'static> FallibleIterator for CallIterator<R, I> {
@@ -40,13 +73,26 @@ impl<R: RibosomeT, I: Invocation + 'static> FallibleIterator for CallIterator<R,
     fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
         Ok(match self.remaining_zomes.first() {
             Some(zome_name) => {
+                if self.exported_callbacks.is_none() {
+                    self.exported_callbacks = Some(self.ribosome.list_callbacks(zome_name)?);
+                }
                 match self.remaining_components.next() {
                     Some(to_call) => {
+                        let to_call: FunctionName = to_call.into();
+                        // don't pay for instantiating the zome to find a callback isn't there
+                        let exported = self
+                            .exported_callbacks
+                            .as_ref()
+                            .map(|exported| exported.contains(&to_call))
+                            .unwrap_or_default();
+                        if !exported {
+                            return self.next();
+                        }
                         match self.ribosome.maybe_call(
                             self.host_access.clone(),
                             &self.invocation,
                             zome_name,
-                            &to_call.into(),
+                            &to_call,
                         )? {
                             Some(result) => Some((zome_name.clone(), result)),
                             None => self.next()?,
@@ -57,6 +103,7 @@ impl<R: RibosomeT, I: Invocation + 'static> FallibleIterator for CallIterator<R,
                     None => {
                         self.remaining_components = self.invocation.fn_components();
                         self.remaining_zomes.remove(0);
+                        self.exported_callbacks = None;
                         self.next()?
                     }
                 }
@@ -66,26 +113,169 @@ impl<R: RibosomeT, I: Invocation + 'static> FallibleIterator for CallIterator<R,
     }
 }
 
+/// Calls the zomes of invocations whose zomes don't depend on each other on
+/// several threads at once, see [Invocation::order_independent].
+/// Every zome is called before anything is returned, so unlike [CallIterator]
+/// a definitive result in one zome doesn't stop the others from being called.
+/// Results come back in zome order.
+pub struct ParallelCallIterator<R: RibosomeT, I: Invocation> {
+    host_access: HostAccess,
+    ribosome: Arc<R>,
+    invocation: Arc<I>,
+    zomes: Vec<ZomeName>,
+    /// The most zomes called at once
+    max_parallel: usize,
+    results: Option<std::vec::IntoIter<(ZomeName, ExternOutput)>>,
+}
+
+impl<R: RibosomeT, I: Invocation> ParallelCallIterator<R, I> {
+    pub fn new(host_access: HostAccess, ribosome: R, invocation: I) -> Self {
+        Self {
+            host_access,
+            zomes: ribosome.zomes_to_invoke(invocation.zomes()),
+            ribosome: Arc::new(ribosome),
+            invocation: Arc::new(invocation),
+            max_parallel: num_cpus::get(),
+            results: None,
+        }
+    }
+
+    /// Call at most this many zomes at once
+    pub fn with_max_parallel(mut self, max_parallel: usize) -> Self {
+        self.max_parallel = max_parallel.max(1);
+        self
+    }
+
+    /// Only call the zomes the filter keeps, see [CallIterator::with_zome_filter]
+    pub fn with_zome_filter<F: FnMut(&ZomeName) -> bool>(mut self, mut filter: F) -> Self {
+        self.zomes.retain(|zome_name| filter(zome_name));
+        self
+    }
+}
+
+/// Call every callback a zome exports for an invocation
+fn call_zome<R: RibosomeT, I: Invocation>(
+    host_access: HostAccess,
+    ribosome: &R,
+    invocation: &I,
+    zome_name: ZomeName,
+) -> Result<Vec<(ZomeName, ExternOutput)>, RibosomeError> {
+    let exported = ribosome.list_callbacks(&zome_name)?;
+    let mut outputs = Vec::new();
+    for to_call in invocation.fn_components() {
+        let to_call: FunctionName = to_call.into();
+        if !exported.contains(&to_call) {
+            continue;
+        }
+        if let Some(output) =
+            ribosome.maybe_call(host_access.clone(), invocation, &zome_name, &to_call)?
+        {
+            outputs.push((zome_name.clone(), output));
+        }
+    }
+    Ok(outputs)
+}
+
+impl<R, I> ParallelCallIterator<R, I>
+where
+    R: RibosomeT + Send + Sync + 'static,
+    I: Invocation + Send + Sync + 'static,
+{
+    fn call_all(&mut self) -> Result<Vec<(ZomeName, ExternOutput)>, RibosomeError> {
+        let zomes = std::mem::take(&mut self.zomes);
+        // Nothing to gain from another thread
+        if zomes.len() < 2 || self.max_parallel < 2 {
+            let mut results = Vec::new();
+            for zome_name in zomes {
+                results.extend(call_zome(
+                    self.host_access.clone(),
+                    &*self.ribosome,
+                    &*self.invocation,
+                    zome_name,
+                )?);
+            }
+            return Ok(results);
+        }
+        let slots = Arc::new(tokio::sync::Semaphore::new(self.max_parallel));
+        let calls = zomes.into_iter().map(|zome_name| {
+            let host_access = self.host_access.clone();
+            let ribosome = self.ribosome.clone();
+            let invocation = self.invocation.clone();
+            let slots = slots.clone();
+            async move {
+                let _slot = slots.acquire().await;
+                // The blocking pool is where host functions expect to block
+                tokio::task::spawn_blocking(move || {
+                    call_zome(host_access, &*ribosome, &*invocation, zome_name)
+                })
+                .await?
+            }
+        });
+        let called =
+            tokio_safe_block_on::tokio_safe_block_forever_on(futures::future::join_all(calls));
+        let mut results = Vec::new();
+        for outputs in called {
+            results.extend(outputs?);
+        }
+        Ok(results)
+    }
+}
+
+impl<R, I> FallibleIterator for ParallelCallIterator<R, I>
+where
+    R: RibosomeT + Send + Sync + 'static,
+    I: Invocation + Send + Sync + 'static,
+{
+    type Item = (ZomeName, ExternOutput);
+    type Error = RibosomeError;
+    fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
+        if self.results.is_none() {
+            self.results = Some(self.call_all()?.into_iter());
+        }
+        Ok(self.results.as_mut().and_then(|results| results.next()))
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "slow_tests")]
 mod tests {
 
     use super::CallIterator;
+    use super::ParallelCallIterator;
+    use crate::core::ribosome::guest_callback::validate::{ValidateInvocation, ValidateResult};
     use crate::core::ribosome::FnComponents;
+    use crate::core::ribosome::HostAccess;
+    use crate::core::ribosome::Invocation;
     use crate::core::ribosome::MockInvocation;
     use crate::core::ribosome::MockRibosomeT;
     use crate::core::ribosome::ZomesToInvoke;
+    use crate::fixt::curve::Zomes;
     use crate::fixt::FnComponentsFixturator;
+    use crate::fixt::ValidateHostAccessFixturator;
+    use crate::fixt::ValidateInvocationFixturator;
+    use crate::fixt::WasmRibosomeFixturator;
     use crate::fixt::ZomeCallHostAccessFixturator;
     use crate::fixt::ZomeNameFixturator;
+    use ::fixt::prelude::*;
     use fallible_iterator::FallibleIterator;
+    use holo_hash::fixt::AgentPubKeyFixturator;
+    use holochain_serialized_bytes::SerializedBytesError;
+    use holochain_wasm_test_utils::TestWasm;
     use holochain_zome_types::init::InitCallbackResult;
+    use holochain_zome_types::validate::ValidateCallbackResult;
     use holochain_zome_types::zome::FunctionName;
     use holochain_zome_types::zome::ZomeName;
+    use holochain_zome_types::Entry;
+    use holochain_zome_types::ExternInput;
     use holochain_zome_types::ExternOutput;
     use mockall::predicate::*;
     use mockall::Sequence;
     use std::convert::TryInto;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use std::time::Duration;
 
     #[tokio::test(threaded_scheduler)]
     async fn call_iterator_iterates() {
@@ -127,6 +317,16 @@ mod tests {
         // zomes are the outer loop as we process all callbacks in a single zome before moving to
         // the next one
         for zome_name in zome_names.clone() {
+            // the exported callbacks are listed once per zome
+            let exported: Vec<FunctionName> =
+                fn_components.clone().map(FunctionName::from).collect();
+            ribosome
+                .expect_list_callbacks()
+                .with(eq(zome_name.clone()))
+                .times(1)
+                .in_sequence(&mut sequence)
+                .return_once(move |_| Ok(exported));
+
             for fn_component in fn_components.clone() {
                 // the invocation zome name and component will be called by the ribosome
                 ribosome
@@ -159,4 +359,393 @@ mod tests {
         let output: Vec<(_, ExternOutput)> = call_iterator.collect().unwrap();
         assert_eq!(output.len(), zome_names.len() * fn_components.0.len());
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn call_iterator_skips_callbacks_not_exported() {
+        let mut ribosome = MockRibosomeT::new();
+        let mut invocation = MockInvocation::new();
+
+        let host_access = ZomeCallHostAccessFixturator::new(fixt::Empty)
+            .next()
+            .unwrap();
+        let zome_name = ZomeNameFixturator::new(fixt::Unpredictable).next().unwrap();
+
+        invocation
+            .expect_zomes()
+            .return_const(ZomesToInvoke::One(zome_name.clone()));
+        invocation
+            .expect_fn_components()
+            .return_const(FnComponents(vec!["validate".into(), "entry".into()]));
+        ribosome
+            .expect_zomes_to_invoke()
+            .return_const(vec![zome_name.clone()]);
+        // the zome only has the general callback
+        ribosome
+            .expect_list_callbacks()
+            .with(eq(zome_name.clone()))
+            .times(1)
+            .returning(|_| Ok(vec!["validate".into()]));
+
+        ribosome
+            .expect_maybe_call::<MockInvocation>()
+            .with(
+                always(),
+                always(),
+                eq(zome_name.clone()),
+                eq(FunctionName::from("validate_entry")),
+            )
+            .never();
+        ribosome
+            .expect_maybe_call::<MockInvocation>()
+            .with(
+                always(),
+                always(),
+                eq(zome_name.clone()),
+                eq(FunctionName::from("validate")),
+            )
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok(Some(ExternOutput::new(
+                    ValidateCallbackResult::Valid.try_into().unwrap(),
+                )))
+            });
+
+        let call_iterator = CallIterator::new(host_access.into(), ribosome, invocation);
+        let output: Vec<(ZomeName, ExternOutput)> = call_iterator.collect().unwrap();
+        assert_eq!(output.len(), 1);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn call_iterator_only_calls_filtered_zomes() {
+        let mut ribosome = MockRibosomeT::new();
+        let mut invocation = MockInvocation::new();
+
+        let host_access = ZomeCallHostAccessFixturator::new(fixt::Empty)
+            .next()
+            .unwrap();
+        let zome_names: Vec<ZomeName> = ZomeNameFixturator::new(fixt::Unpredictable)
+            .take(3)
+            .collect();
+        let kept_zome = zome_names[1].clone();
+
+        invocation.expect_zomes().return_const(ZomesToInvoke::All);
+        invocation
+            .expect_fn_components()
+            .return_const(FnComponents(vec!["validate".into()]));
+        ribosome
+            .expect_zomes_to_invoke()
+            .return_const(zome_names.clone());
+        ribosome
+            .expect_list_callbacks()
+            .with(eq(kept_zome.clone()))
+            .times(1)
+            .returning(|_| Ok(vec!["validate".into()]));
+        ribosome
+            .expect_maybe_call::<MockInvocation>()
+            .with(always(), always(), eq(kept_zome.clone()), always())
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok(Some(ExternOutput::new(
+                    ValidateCallbackResult::Valid.try_into().unwrap(),
+                )))
+            });
+
+        let mut filtered = 0;
+        let call_iterator = CallIterator::new(host_access.into(), ribosome, invocation)
+            .with_zome_filter(|zome_name| {
+                filtered += 1;
+                *zome_name == kept_zome
+            });
+        let output: Vec<(ZomeName, ExternOutput)> = call_iterator.collect().unwrap();
+
+        // The filter ran once per zome, not once per call
+        assert_eq!(filtered, zome_names.len());
+        assert_eq!(
+            output.into_iter().map(|(z, _)| z).collect::<Vec<_>>(),
+            vec![kept_zome]
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn call_iterator_with_no_filtered_zomes_calls_nothing() {
+        let mut ribosome = MockRibosomeT::new();
+        let mut invocation = MockInvocation::new();
+
+        let host_access = ZomeCallHostAccessFixturator::new(fixt::Empty)
+            .next()
+            .unwrap();
+        let zome_names: Vec<ZomeName> = ZomeNameFixturator::new(fixt::Unpredictable)
+            .take(3)
+            .collect();
+
+        invocation.expect_zomes().return_const(ZomesToInvoke::All);
+        invocation
+            .expect_fn_components()
+            .return_const(FnComponents(vec!["validate".into()]));
+        ribosome.expect_zomes_to_invoke().return_const(zome_names);
+        ribosome.expect_list_callbacks().never();
+        ribosome.expect_maybe_call::<MockInvocation>().never();
+
+        let mut call_iterator =
+            CallIterator::new(host_access.into(), ribosome, invocation).with_zome_filter(|_| false);
+        assert!(call_iterator.next().unwrap().is_none());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn parallel_call_iterator_calls_zomes_concurrently() {
+        let mut ribosome = MockRibosomeT::new();
+        let mut invocation = MockInvocation::new();
+
+        let host_access = ZomeCallHostAccessFixturator::new(fixt::Empty)
+            .next()
+            .unwrap();
+        let zome_names: Vec<ZomeName> = ZomeNameFixturator::new(fixt::Unpredictable)
+            .take(3)
+            .collect();
+        let invalid_zome = zome_names[1].clone();
+
+        invocation.expect_zomes().return_const(ZomesToInvoke::All);
+        invocation
+            .expect_fn_components()
+            .return_const(FnComponents(vec!["validate".into()]));
+        ribosome
+            .expect_zomes_to_invoke()
+            .return_const(zome_names.clone());
+        ribosome
+            .expect_list_callbacks()
+            .returning(|_| Ok(vec!["validate".into()]));
+
+        // Track how many zomes are being called at once
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        ribosome
+            .expect_maybe_call::<MockInvocation>()
+            .times(zome_names.len())
+            .returning({
+                let running = running.clone();
+                let max_running = max_running.clone();
+                move |_, _, zome_name, _| {
+                    let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now_running, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(200));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    let result = if *zome_name == invalid_zome {
+                        ValidateCallbackResult::Invalid("bad".into())
+                    } else {
+                        ValidateCallbackResult::Valid
+                    };
+                    Ok(Some(ExternOutput::new(result.try_into().unwrap())))
+                }
+            });
+
+        let call_iterator = ParallelCallIterator::new(host_access.into(), ribosome, invocation)
+            .with_max_parallel(zome_names.len());
+        let output: Vec<(ZomeName, ExternOutput)> = call_iterator.collect().unwrap();
+
+        assert_eq!(max_running.load(Ordering::SeqCst), zome_names.len());
+        // Results come back in zome order
+        assert_eq!(
+            output.iter().map(|(z, _)| z.clone()).collect::<Vec<_>>(),
+            zome_names
+        );
+        // Any invalid zome makes the whole validation invalid
+        let results: Vec<(ZomeName, ValidateCallbackResult)> = output
+            .into_iter()
+            .map(|(zome_name, output)| (zome_name, output.into()))
+            .collect();
+        assert_eq!(
+            ValidateResult::from(results),
+            ValidateResult::Invalid("bad".into())
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn parallel_call_iterator_is_bounded() {
+        let mut ribosome = MockRibosomeT::new();
+        let mut invocation = MockInvocation::new();
+
+        let host_access = ZomeCallHostAccessFixturator::new(fixt::Empty)
+            .next()
+            .unwrap();
+        let zome_names: Vec<ZomeName> = ZomeNameFixturator::new(fixt::Unpredictable)
+            .take(4)
+            .collect();
+
+        invocation.expect_zomes().return_const(ZomesToInvoke::All);
+        invocation
+            .expect_fn_components()
+            .return_const(FnComponents(vec!["validate".into()]));
+        ribosome
+            .expect_zomes_to_invoke()
+            .return_const(zome_names.clone());
+        ribosome
+            .expect_list_callbacks()
+            .returning(|_| Ok(vec!["validate".into()]));
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        ribosome
+            .expect_maybe_call::<MockInvocation>()
+            .times(zome_names.len())
+            .returning({
+                let running = running.clone();
+                let max_running = max_running.clone();
+                move |_, _, _, _| {
+                    let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now_running, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(100));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(Some(ExternOutput::new(
+                        ValidateCallbackResult::Valid.try_into().unwrap(),
+                    )))
+                }
+            });
+
+        let call_iterator = ParallelCallIterator::new(host_access.into(), ribosome, invocation)
+            .with_max_parallel(2);
+        let output: Vec<(ZomeName, ExternOutput)> = call_iterator.collect().unwrap();
+
+        // Every zome is still called but never more than two at once
+        assert_eq!(output.len(), zome_names.len());
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn parallel_call_iterator_only_calls_filtered_zomes() {
+        let mut ribosome = MockRibosomeT::new();
+        let mut invocation = MockInvocation::new();
+
+        let host_access = ZomeCallHostAccessFixturator::new(fixt::Empty)
+            .next()
+            .unwrap();
+        let zome_names: Vec<ZomeName> = ZomeNameFixturator::new(fixt::Unpredictable)
+            .take(3)
+            .collect();
+        let kept_zome = zome_names[1].clone();
+
+        invocation.expect_zomes().return_const(ZomesToInvoke::All);
+        invocation
+            .expect_fn_components()
+            .return_const(FnComponents(vec!["validate".into()]));
+        ribosome
+            .expect_zomes_to_invoke()
+            .return_const(zome_names.clone());
+        ribosome
+            .expect_list_callbacks()
+            .with(eq(kept_zome.clone()))
+            .times(1)
+            .returning(|_| Ok(vec!["validate".into()]));
+        ribosome
+            .expect_maybe_call::<MockInvocation>()
+            .with(always(), always(), eq(kept_zome.clone()), always())
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok(Some(ExternOutput::new(
+                    ValidateCallbackResult::Valid.try_into().unwrap(),
+                )))
+            });
+
+        let mut filtered = 0;
+        let call_iterator = ParallelCallIterator::new(host_access.into(), ribosome, invocation)
+            .with_max_parallel(zome_names.len())
+            .with_zome_filter(|zome_name| {
+                filtered += 1;
+                *zome_name == kept_zome
+            });
+        let output: Vec<(ZomeName, ExternOutput)> = call_iterator.collect().unwrap();
+
+        // The filter ran once per zome, not once per call
+        assert_eq!(filtered, zome_names.len());
+        assert_eq!(
+            output.into_iter().map(|(z, _)| z).collect::<Vec<_>>(),
+            vec![kept_zome]
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn parallel_call_iterator_with_no_filtered_zomes_calls_nothing() {
+        let mut ribosome = MockRibosomeT::new();
+        let mut invocation = MockInvocation::new();
+
+        let host_access = ZomeCallHostAccessFixturator::new(fixt::Empty)
+            .next()
+            .unwrap();
+        let zome_names: Vec<ZomeName> = ZomeNameFixturator::new(fixt::Unpredictable)
+            .take(3)
+            .collect();
+
+        invocation.expect_zomes().return_const(ZomesToInvoke::All);
+        invocation
+            .expect_fn_components()
+            .return_const(FnComponents(vec!["validate".into()]));
+        ribosome.expect_zomes_to_invoke().return_const(zome_names);
+        ribosome.expect_list_callbacks().never();
+        ribosome.expect_maybe_call::<MockInvocation>().never();
+
+        let mut call_iterator = ParallelCallIterator::new(host_access.into(), ribosome, invocation)
+            .with_zome_filter(|_| false);
+        assert!(call_iterator.next().unwrap().is_none());
+    }
+
+    /// Validates in every zome rather than only the zome defining the entry,
+    /// so a real ribosome has several zomes to call at once
+    #[derive(Clone)]
+    struct ValidateAllZomes(ValidateInvocation);
+
+    impl Invocation for ValidateAllZomes {
+        fn zomes(&self) -> ZomesToInvoke {
+            ZomesToInvoke::All
+        }
+        fn fn_components(&self) -> FnComponents {
+            self.0.fn_components()
+        }
+        fn host_input(self) -> Result<ExternInput, SerializedBytesError> {
+            self.0.host_input()
+        }
+        fn order_independent(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn parallel_call_iterator_calls_wasm_zomes_like_call_iterator() {
+        let ribosome = WasmRibosomeFixturator::new(Zomes(vec![
+            TestWasm::ValidateValid,
+            TestWasm::ValidateInvalid,
+            TestWasm::Foo,
+        ]))
+        .next()
+        .unwrap();
+        let mut invocation = ValidateInvocationFixturator::new(fixt::Empty)
+            .next()
+            .unwrap();
+        invocation.entry = Arc::new(Entry::Agent(fixt!(AgentPubKey).into()));
+        let invocation = ValidateAllZomes(invocation);
+        let host_access: HostAccess = fixt!(ValidateHostAccess).into();
+
+        let results = |output: Vec<(ZomeName, ExternOutput)>| {
+            output
+                .into_iter()
+                .map(|(zome_name, output)| (zome_name, output.into()))
+                .collect::<Vec<(ZomeName, ValidateCallbackResult)>>()
+        };
+        let sequential = results(
+            CallIterator::new(host_access.clone(), ribosome.clone(), invocation.clone())
+                .collect()
+                .unwrap(),
+        );
+        let parallel = results(
+            ParallelCallIterator::new(host_access, ribosome, invocation)
+                .with_max_parallel(2)
+                .collect()
+                .unwrap(),
+        );
+
+        // The same callbacks are called and come back in the same order
+        assert_eq!(sequential, parallel);
+        assert_eq!(
+            ValidateResult::from(parallel),
+            ValidateResult::Invalid("esoteric edge case".into())
+        );
+    }
 }
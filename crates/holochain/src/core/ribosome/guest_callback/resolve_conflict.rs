@@ -0,0 +1,185 @@
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostAccess;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holo_hash::HeaderHash;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::dna::zome::HostFnAccess;
+use holochain_zome_types::resolve_conflict::ResolveConflict;
+use holochain_zome_types::resolve_conflict::ResolveConflictCallbackResult;
+use holochain_zome_types::zome::ZomeName;
+use holochain_zome_types::ExternInput;
+
+#[derive(Clone)]
+pub struct ResolveConflictInvocation {
+    zome_name: ZomeName,
+    conflict: ResolveConflict,
+}
+
+impl ResolveConflictInvocation {
+    pub fn new(zome_name: ZomeName, conflict: ResolveConflict) -> Self {
+        Self {
+            zome_name,
+            conflict,
+        }
+    }
+}
+
+/// The callback is run while the caller holds the workspace
+/// so it only gets the competing heads to choose from.
+#[derive(Clone, Constructor)]
+pub struct ResolveConflictHostAccess;
+
+impl From<ResolveConflictHostAccess> for HostAccess {
+    fn from(resolve_conflict_host_access: ResolveConflictHostAccess) -> Self {
+        Self::ResolveConflict(resolve_conflict_host_access)
+    }
+}
+
+impl From<&ResolveConflictHostAccess> for HostFnAccess {
+    fn from(_: &ResolveConflictHostAccess) -> Self {
+        Self::none()
+    }
+}
+
+impl Invocation for ResolveConflictInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::One(self.zome_name.to_owned())
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["resolve_conflict".into()].into()
+    }
+    fn host_input(self) -> Result<ExternInput, SerializedBytesError> {
+        Ok(ExternInput::new((&self.conflict).try_into()?))
+    }
+}
+
+impl TryFrom<ResolveConflictInvocation> for ExternInput {
+    type Error = SerializedBytesError;
+    fn try_from(
+        resolve_conflict_invocation: ResolveConflictInvocation,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self::new(
+            (&resolve_conflict_invocation.conflict).try_into()?,
+        ))
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ResolveConflictResult {
+    /// The callback chose this header as the winner
+    Winner(HeaderHash),
+    /// There is no callback or it left the choice to the default policy
+    Pass,
+    Fail(ZomeName, String),
+}
+
+impl From<Vec<(ZomeName, ResolveConflictCallbackResult)>> for ResolveConflictResult {
+    fn from(callback_results: Vec<(ZomeName, ResolveConflictCallbackResult)>) -> Self {
+        // this is an optional callback so defaults to pass
+        callback_results
+            .into_iter()
+            .fold(Self::Pass, |acc, (zome_name, x)| match (acc, x) {
+                // fail overrides everything
+                (Self::Fail(zome_name, fail_string), _) => Self::Fail(zome_name, fail_string),
+                (_, ResolveConflictCallbackResult::Fail(fail_string)) => {
+                    Self::Fail(zome_name, fail_string)
+                }
+                // the first winner stands
+                (Self::Winner(winner), _) => Self::Winner(winner),
+                (_, ResolveConflictCallbackResult::Winner(winner)) => Self::Winner(winner),
+                (Self::Pass, ResolveConflictCallbackResult::Pass) => Self::Pass,
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::ResolveConflictHostAccess;
+    use super::ResolveConflictInvocation;
+    use super::ResolveConflictResult;
+    use crate::core::ribosome::Invocation;
+    use crate::core::ribosome::ZomesToInvoke;
+    use crate::fixt::ZomeNameFixturator;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::HeaderHashFixturator;
+    use holochain_serialized_bytes::prelude::*;
+    use holochain_types::dna::zome::HostFnAccess;
+    use holochain_zome_types::resolve_conflict::ResolveConflict;
+    use holochain_zome_types::resolve_conflict::ResolveConflictCallbackResult;
+    use holochain_zome_types::ExternInput;
+
+    #[test]
+    fn resolve_conflict_callback_result_fold() {
+        let zome_name = ZomeNameFixturator::new(fixt::Empty).next().unwrap();
+        let winner = HeaderHashFixturator::new(fixt::Unpredictable)
+            .next()
+            .unwrap();
+
+        let cb = |result| (zome_name.clone(), result);
+        let cb_pass = || cb(ResolveConflictCallbackResult::Pass);
+        let cb_winner = || cb(ResolveConflictCallbackResult::Winner(winner.clone()));
+        let cb_fail = || cb(ResolveConflictCallbackResult::Fail("".into()));
+
+        let result_winner = || ResolveConflictResult::Winner(winner.clone());
+        let result_fail = || ResolveConflictResult::Fail(zome_name.clone(), "".into());
+
+        for (results, expected) in vec![
+            (vec![], ResolveConflictResult::Pass),
+            (vec![cb_pass()], ResolveConflictResult::Pass),
+            (vec![cb_winner()], result_winner()),
+            (vec![cb_pass(), cb_winner()], result_winner()),
+            (vec![cb_winner(), cb_pass()], result_winner()),
+            (vec![cb_fail()], result_fail()),
+            (vec![cb_winner(), cb_fail()], result_fail()),
+            (vec![cb_fail(), cb_winner()], result_fail()),
+        ] {
+            assert_eq!(expected, results.into());
+        }
+    }
+
+    #[test]
+    fn resolve_conflict_invocation_access() {
+        assert_eq!(
+            HostFnAccess::from(&ResolveConflictHostAccess),
+            HostFnAccess::none()
+        );
+    }
+
+    #[test]
+    fn resolve_conflict_invocation_zomes() {
+        let zome_name = ZomeNameFixturator::new(fixt::Unpredictable).next().unwrap();
+        let invocation = ResolveConflictInvocation::new(zome_name.clone(), ResolveConflict(vec![]));
+        assert_eq!(ZomesToInvoke::One(zome_name), invocation.zomes());
+    }
+
+    #[test]
+    fn resolve_conflict_invocation_fn_components() {
+        let invocation = ResolveConflictInvocation::new(
+            ZomeNameFixturator::new(fixt::Empty).next().unwrap(),
+            ResolveConflict(vec![]),
+        );
+
+        let mut expected = vec!["resolve_conflict"];
+        for fn_component in invocation.fn_components() {
+            assert_eq!(fn_component, expected.pop().unwrap());
+        }
+    }
+
+    #[test]
+    fn resolve_conflict_invocation_host_input() {
+        let invocation = ResolveConflictInvocation::new(
+            ZomeNameFixturator::new(fixt::Empty).next().unwrap(),
+            ResolveConflict(vec![]),
+        );
+
+        let host_input = invocation.host_input().unwrap();
+
+        assert_eq!(
+            host_input,
+            ExternInput::new(SerializedBytes::try_from(ResolveConflict(vec![])).unwrap()),
+        );
+    }
+}
@@ -2,10 +2,12 @@ use crate::core::ribosome::FnComponents;
 use crate::core::ribosome::HostAccess;
 use crate::core::ribosome::Invocation;
 use crate::core::ribosome::ZomesToInvoke;
+use crate::core::workflow::CallZomeWorkspaceLock;
 use derive_more::Constructor;
-use holo_hash::EntryHash;
+use holo_hash::AnyDhtHash;
+use holochain_p2p::HolochainP2pCell;
 use holochain_serialized_bytes::prelude::*;
-use holochain_types::dna::zome::HostFnAccess;
+use holochain_types::dna::zome::{HostFnAccess, Permission};
 use holochain_zome_types::entry::Entry;
 use holochain_zome_types::validate::ValidateCallbackResult;
 use holochain_zome_types::zome::ZomeName;
@@ -32,7 +34,10 @@ impl ValidateInvocation {
 }
 
 #[derive(Clone, Constructor)]
-pub struct ValidateHostAccess;
+pub struct ValidateHostAccess {
+    pub workspace: CallZomeWorkspaceLock,
+    pub network: HolochainP2pCell,
+}
 
 impl From<ValidateHostAccess> for HostAccess {
     fn from(validate_host_access: ValidateHostAccess) -> Self {
@@ -42,7 +47,9 @@ impl From<ValidateHostAccess> for HostAccess {
 
 impl From<&ValidateHostAccess> for HostFnAccess {
     fn from(_: &ValidateHostAccess) -> Self {
-        Self::none()
+        let mut access = Self::none();
+        access.read_workspace = Permission::Allow;
+        access
     }
 }
 
@@ -69,6 +76,10 @@ impl Invocation for ValidateInvocation {
     fn host_input(self) -> Result<ExternInput, SerializedBytesError> {
         Ok(ExternInput::new((&*self.entry).try_into()?))
     }
+    fn order_independent(&self) -> bool {
+        // any zome finding the entry invalid makes it invalid
+        true
+    }
 }
 
 impl TryFrom<ValidateInvocation> for ExternInput {
@@ -84,7 +95,7 @@ pub enum ValidateResult {
     Invalid(String),
     /// subconscious needs to map this to either pending or abandoned based on context that the
     /// wasm can't possibly have
-    UnresolvedDependencies(Vec<EntryHash>),
+    UnresolvedDependencies(Vec<AnyDhtHash>),
 }
 
 impl From<Vec<(ZomeName, ValidateCallbackResult)>> for ValidateResult {
@@ -171,12 +182,21 @@ mod test {
 
     #[tokio::test(threaded_scheduler)]
     async fn validate_invocation_allow_side_effects() {
+        use holochain_types::dna::zome::Permission::*;
         let validate_host_access = ValidateHostAccessFixturator::new(fixt::Unpredictable)
             .next()
             .unwrap();
         assert_eq!(
             HostFnAccess::from(&validate_host_access),
-            HostFnAccess::none(),
+            HostFnAccess {
+                agent_info: Deny,
+                read_workspace: Allow,
+                write_workspace: Deny,
+                write_network: Deny,
+                dna_bindings: Deny,
+                non_determinism: Deny,
+                keystore: Deny,
+            },
         );
     }
 
@@ -263,12 +283,12 @@ mod test {
 #[cfg(feature = "slow_tests")]
 mod slow_tests {
 
-    use super::ValidateHostAccess;
     use super::ValidateResult;
     use crate::core::ribosome::RibosomeT;
     use crate::core::state::source_chain::SourceChainResult;
     use crate::core::workflow::call_zome_workflow::CallZomeWorkspace;
     use crate::fixt::curve::Zomes;
+    use crate::fixt::ValidateHostAccessFixturator;
     use crate::fixt::ValidateInvocationFixturator;
     use crate::fixt::WasmRibosomeFixturator;
     use crate::fixt::ZomeCallHostAccessFixturator;
@@ -290,7 +310,7 @@ mod slow_tests {
         validate_invocation.zome_name = TestWasm::Foo.into();
 
         let result = ribosome
-            .run_validate(ValidateHostAccess, validate_invocation)
+            .run_validate(fixt!(ValidateHostAccess), validate_invocation)
             .unwrap();
         assert_eq!(result, ValidateResult::Valid,);
     }
@@ -306,7 +326,7 @@ mod slow_tests {
         validate_invocation.zome_name = TestWasm::ValidateValid.into();
 
         let result = ribosome
-            .run_validate(ValidateHostAccess, validate_invocation)
+            .run_validate(fixt!(ValidateHostAccess), validate_invocation)
             .unwrap();
         assert_eq!(result, ValidateResult::Valid,);
     }
@@ -322,7 +342,7 @@ mod slow_tests {
         validate_invocation.zome_name = TestWasm::ValidateInvalid.into();
 
         let result = ribosome
-            .run_validate(ValidateHostAccess, validate_invocation)
+            .run_validate(fixt!(ValidateHostAccess), validate_invocation)
             .unwrap();
         assert_eq!(result, ValidateResult::Invalid("esoteric edge case".into()),);
     }
@@ -346,7 +366,7 @@ mod slow_tests {
         validate_invocation.entry = Arc::new(entry);
 
         let result = ribosome
-            .run_validate(ValidateHostAccess, validate_invocation)
+            .run_validate(fixt!(ValidateHostAccess), validate_invocation)
             .unwrap();
         assert_eq!(result, ValidateResult::Invalid("esoteric edge case".into()));
     }
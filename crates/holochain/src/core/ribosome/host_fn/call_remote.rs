@@ -1,7 +1,15 @@
+use crate::core::ribosome::cancel::block_on_cancellable;
 use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::host_fn::random_bytes::random_bytes_async;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
+use holochain_keystore::AgentPubKeyExt;
 use holochain_p2p::HolochainP2pCellT;
+use holochain_zome_types::call_remote::CallRemoteSignedData;
+use holochain_zome_types::capability::{
+    CapNonce, SignedCapNonce, CAP_NONCE_BYTES, CAP_NONCE_EXPIRY_SECS,
+};
+use holochain_zome_types::timestamp::Timestamp;
 use holochain_zome_types::CallRemoteInput;
 use holochain_zome_types::CallRemoteOutput;
 use holochain_zome_types::ZomeCallResponse;
@@ -13,19 +21,54 @@ pub fn call_remote(
     call_context: Arc<CallContext>,
     input: CallRemoteInput,
 ) -> RibosomeResult<CallRemoteOutput> {
+    let cancel = call_context.host_access().cancel().cloned();
+    let executor = call_context.host_fn_executor.clone();
     // it is the network's responsibility to handle timeouts and return an Err result in that case
-    let result: ZomeCallResponse = tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+    let result: ZomeCallResponse = block_on_cancellable(&executor, cancel, async move {
         let mut network = call_context.host_access().network().clone();
         let call_remote = input.into_inner();
-        network
+        // Every call carries a nonce so the callee can reject replays
+        let nonce = match call_remote.nonce() {
+            Some(nonce) => nonce,
+            None => {
+                let mut nonce = [0; CAP_NONCE_BYTES];
+                nonce.copy_from_slice(&random_bytes_async(CAP_NONCE_BYTES as u32).await?);
+                CapNonce::from(nonce)
+            }
+        };
+        // and is only accepted for a short while
+        let now: Timestamp = holochain_types::Timestamp::now().into();
+        let expires_at = Timestamp(now.0 + CAP_NONCE_EXPIRY_SECS, now.1);
+        let keystore = call_context.host_access().keystore().clone();
+        let signature = network
+            .from_agent()
+            .sign(
+                &keystore,
+                CallRemoteSignedData {
+                    to_agent: call_remote.to_agent(),
+                    zome_name: call_remote.zome_name(),
+                    fn_name: call_remote.fn_name(),
+                    cap: call_remote.cap(),
+                    nonce,
+                    expires_at,
+                    payload: call_remote.request(),
+                },
+            )
+            .await?;
+        Ok(network
             .call_remote(
                 call_remote.to_agent(),
                 call_remote.zome_name(),
                 call_remote.fn_name(),
                 call_remote.cap(),
+                SignedCapNonce {
+                    nonce,
+                    expires_at,
+                    signature,
+                },
                 call_remote.request(),
             )
-            .await
+            .await?)
     })?
     .try_into()?;
 
@@ -63,6 +106,10 @@ pub mod wasm_test {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::WhoAmI.into()].into(),
+            reject_self_links: false,
+            max_entry_size: None,
+            update_conflict_policy: Default::default(),
+            sensitive_fns: Default::default(),
         };
         let dna_file = DnaFile::new(dna_def, vec![TestWasm::WhoAmI.into()])
             .await
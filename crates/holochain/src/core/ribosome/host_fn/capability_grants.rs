@@ -105,6 +105,10 @@ pub mod wasm_test {
                 uuid: "c2f5ccfb-42b4-4927-a32c-60a642265c5a".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::Capability.into()].into(),
+                reject_self_links: false,
+                max_entry_size: None,
+                update_conflict_policy: Default::default(),
+                sensitive_fns: Default::default(),
             },
             vec![TestWasm::Capability.into()],
         )
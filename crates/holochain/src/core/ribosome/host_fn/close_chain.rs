@@ -0,0 +1,116 @@
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::{
+    ribosome::{CallContext, RibosomeT},
+    workflow::CallZomeWorkspace,
+    SourceChainResult,
+};
+use holochain_zome_types::header::builder;
+use holochain_zome_types::CloseChainInput;
+use holochain_zome_types::CloseChainOutput;
+use std::sync::Arc;
+
+/// Close the source chain in favour of a chain on a new DNA.
+/// Nothing else can be committed to the chain after this.
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn close_chain<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: CloseChainInput,
+) -> RibosomeResult<CloseChainOutput> {
+    call_context.host_access.check_writable()?;
+
+    let header_builder = builder::CloseChain::new(input.into_inner());
+
+    let header_hash =
+        tokio_safe_block_on::tokio_safe_block_forever_on(tokio::task::spawn(async move {
+            let mut guard = call_context.host_access.workspace().write().await;
+            let workspace: &mut CallZomeWorkspace = &mut guard;
+            SourceChainResult::Ok(
+                workspace
+                    .source_chain
+                    .put(header_builder, None, None)
+                    .await?,
+            )
+        }))??;
+
+    Ok(CloseChainOutput::new(header_hash))
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use super::close_chain;
+    use crate::core::ribosome::error::RibosomeError;
+    use crate::core::ribosome::host_fn::create::create;
+    use crate::core::state::source_chain::SourceChainError;
+    use crate::core::workflow::call_zome_workflow::CallZomeWorkspace;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::EntryFixturator;
+    use crate::fixt::WasmRibosomeFixturator;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use holochain_types::{fixt::AppEntry, test_utils::fake_dna_hash};
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::entry_def::EntryDefId;
+    use holochain_zome_types::CloseChainInput;
+    use holochain_zome_types::CreateInput;
+    use holochain_zome_types::Header;
+    use matches::assert_matches;
+    use std::sync::Arc;
+
+    #[tokio::test(threaded_scheduler)]
+    /// nothing can be committed after the chain is closed
+    async fn close_chain_test() {
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let ribosome = Arc::new(
+            WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![TestWasm::Create]))
+                .next()
+                .unwrap(),
+        );
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.zome_name = TestWasm::Create.into();
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+        call_context.host_access = host_access.into();
+        let call_context = Arc::new(call_context);
+
+        let new_dna_hash = fake_dna_hash(2);
+        let output = close_chain(
+            ribosome.clone(),
+            call_context.clone(),
+            CloseChainInput::new(new_dna_hash.clone()),
+        )
+        .unwrap();
+
+        let close_header = tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+            workspace_lock
+                .read()
+                .await
+                .source_chain
+                .get_header(&output.into_inner())
+                .unwrap()
+                .unwrap()
+        });
+        assert_matches!(
+            close_header.header(),
+            Header::CloseChain(close) if close.new_dna_hash == new_dna_hash
+        );
+
+        let app_entry = EntryFixturator::new(AppEntry).next().unwrap();
+        let input = CreateInput::new((EntryDefId::App("post".into()), app_entry));
+        assert_matches!(
+            create(ribosome, call_context, input),
+            Err(RibosomeError::SourceChainError(
+                SourceChainError::ChainClosed
+            ))
+        );
+    }
+}
@@ -5,6 +5,7 @@ use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
 use crate::core::{
+    sys_validate::{self, SysValidationError, ValidationOutcome},
     workflow::{
         call_zome_workflow::CallZomeWorkspace, integrate_dht_ops_workflow::integrate_to_cache,
     },
@@ -17,6 +18,7 @@ use holochain_zome_types::header::AppEntryType;
 use holochain_zome_types::header::EntryType;
 use holochain_zome_types::CreateInput;
 use holochain_zome_types::CreateOutput;
+use holochain_zome_types::Entry;
 use std::sync::Arc;
 
 /// create element
@@ -26,9 +28,14 @@ pub fn create<'a>(
     call_context: Arc<CallContext>,
     input: CreateInput,
 ) -> RibosomeResult<CreateOutput> {
+    call_context.host_access.check_writable()?;
+
     // destructure the args out into an app type def id and entry
     let (entry_def_id, entry) = input.into_inner();
 
+    // reject oversized entries before they reach the source chain
+    check_entry_size(ribosome.as_ref(), &entry)?;
+
     // build the entry hash
     let async_entry = entry.clone();
     let entry_hash =
@@ -66,7 +73,10 @@ pub fn create<'a>(
         let workspace: &mut CallZomeWorkspace = &mut guard;
         let source_chain = &mut workspace.source_chain;
         // push the header and the entry into the source chain
-        let header_hash = source_chain.put(header_builder, Some(entry)).await?;
+        let chain_head = source_chain.chain_head()?.clone();
+        let header_hash = source_chain
+            .put(header_builder, Some(entry), Some(chain_head))
+            .await?;
         // fetch the element we just added so we can integrate its DhtOps
         let element = source_chain
             .get_element(&header_hash)?
@@ -83,6 +93,17 @@ pub fn create<'a>(
     })
 }
 
+/// Check the entry is under the Dna's max entry size
+pub fn check_entry_size(ribosome: &impl RibosomeT, entry: &Entry) -> RibosomeResult<()> {
+    let max_entry_size = sys_validate::max_entry_size(ribosome.dna_file().dna());
+    match sys_validate::check_entry_size(entry, max_entry_size) {
+        Err(SysValidationError::ValidationOutcome(ValidationOutcome::EntryTooLarge(size, max))) => {
+            Err(RibosomeError::EntryTooLarge(size, max))
+        }
+        _ => Ok(()),
+    }
+}
+
 pub fn extract_entry_def(
     ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
@@ -124,7 +145,9 @@ pub mod wasm_test {
     use super::create;
     use crate::conductor::dna_store::MockDnaStore;
     use crate::core::ribosome::error::RibosomeError;
+    use crate::core::ribosome::host_fn::get::get;
     use crate::core::ribosome::ZomeCallInvocation;
+    use crate::core::state::element_buf::{ElementBuf, EntryChunk};
     use crate::core::state::source_chain::ChainInvalidReason;
     use crate::core::state::source_chain::SourceChainError;
     use crate::core::state::source_chain::SourceChainResult;
@@ -137,6 +160,8 @@ pub mod wasm_test {
     use ::fixt::prelude::*;
     use hdk3::prelude::*;
     use holo_hash::{AnyDhtHash, EntryHash};
+    use holochain_serialized_bytes::UnsafeBytes;
+    use holochain_state::{env::DEFAULT_ENTRY_CHUNK_THRESHOLD, prelude::PrefixHashKey};
     use holochain_types::{
         app::InstalledCell, cell::CellId, dna::DnaDef, dna::DnaFile, fixt::AppEntry, observability,
         test_utils::fake_agent_pubkey_1, test_utils::fake_agent_pubkey_2,
@@ -146,8 +171,10 @@ pub mod wasm_test {
     use holochain_zome_types::CreateInput;
     use holochain_zome_types::CreateOutput;
     use holochain_zome_types::Entry;
+    use holochain_zome_types::GetInput;
     use holochain_zome_types::GetOutput;
     use holochain_zome_types::{entry::EntryError, ExternInput};
+    use matches::assert_matches;
     use std::sync::Arc;
     use test_wasm_common::TestBytes;
     use test_wasm_common::TestInt;
@@ -188,6 +215,130 @@ pub mod wasm_test {
         );
     }
 
+    #[tokio::test(threaded_scheduler)]
+    /// a commit flushed from the scratch can be read back through a fresh workspace
+    async fn create_flushed_entry_persists_test() {
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        let output: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Create, "create_entry", ());
+
+        // nothing is persisted until the scratch is flushed
+        let fresh = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        assert!(fresh.source_chain.get_element(&output).unwrap().is_none());
+
+        workspace_lock.write().await.flush_to(&env).unwrap();
+
+        let fresh = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        let element = fresh.source_chain.get_element(&output).unwrap().unwrap();
+        assert_eq!(element.header_address(), &output);
+        assert_eq!(fresh.source_chain.len(), 4);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    /// we cannot commit an entry over the dna's max entry size
+    async fn create_entry_too_large_test() {
+        // test workspace boilerplate
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut ribosome =
+            WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![TestWasm::Create]))
+                .next()
+                .unwrap();
+        ribosome.dna_file.dna.max_entry_size = Some(10);
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.zome_name = TestWasm::Create.into();
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+        call_context.host_access = host_access.into();
+        let app_entry = Entry::app(SerializedBytes::from(UnsafeBytes::from(vec![0; 20]))).unwrap();
+        let entry_def_id = EntryDefId::App("post".into());
+        let input = CreateInput::new((entry_def_id, app_entry));
+
+        let output = create(Arc::new(ribosome), Arc::new(call_context), input);
+
+        assert_matches!(output, Err(RibosomeError::EntryTooLarge(20, 10)));
+
+        // nothing was committed after genesis
+        let chain_len = tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+            workspace_lock.read().await.source_chain.len()
+        });
+        assert_eq!(chain_len, 3);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    /// an entry over the chunk threshold is stored in chunks and got back whole
+    async fn create_large_entry_is_chunked_test() {
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut ribosome =
+            WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![TestWasm::Create]))
+                .next()
+                .unwrap();
+        ribosome.dna_file.dna.max_entry_size = None;
+        let ribosome = Arc::new(ribosome);
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.zome_name = TestWasm::Create.into();
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+        call_context.host_access = host_access.into();
+        let call_context = Arc::new(call_context);
+
+        let bytes: Vec<u8> = (0..DEFAULT_ENTRY_CHUNK_THRESHOLD * 2)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let app_entry = Entry::app(SerializedBytes::from(UnsafeBytes::from(bytes))).unwrap();
+        let entry_hash = EntryHash::with_data_sync(&app_entry);
+        let input = CreateInput::new((EntryDefId::App("post".into()), app_entry.clone()));
+        let header_hash: HeaderHash = create(ribosome.clone(), call_context.clone(), input)
+            .unwrap()
+            .into_inner();
+
+        workspace_lock.write().await.flush_to(&env).unwrap();
+
+        // the entry is only stored as chunks
+        let vault = ElementBuf::vault(env.clone().into(), true).unwrap();
+        assert!(vault.is_entry_chunked(&entry_hash).unwrap());
+        assert!(!vault.public_entries().contains(&entry_hash).unwrap());
+        let manifest = vault
+            .entry_chunks()
+            .unwrap()
+            .get(&PrefixHashKey::new(&entry_hash))
+            .unwrap();
+        assert_matches!(manifest, Some(EntryChunk::Manifest { chunks, .. }) if chunks.len() > 1);
+
+        let element = get(
+            ribosome,
+            call_context,
+            GetInput::new((header_hash.into(), GetOptions::default())),
+        )
+        .unwrap()
+        .into_inner()
+        .unwrap();
+        assert_eq!(element.entry().as_option(), Some(&app_entry));
+    }
+
     #[tokio::test(threaded_scheduler)]
     /// we can get an entry hash out of the fn directly
     async fn create_entry_test<'a>() {
@@ -290,6 +441,10 @@ pub mod wasm_test {
                 uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::MultipleCalls.into()].into(),
+                reject_self_links: false,
+                max_entry_size: None,
+                update_conflict_policy: Default::default(),
+                sensitive_fns: Default::default(),
             },
             vec![TestWasm::MultipleCalls.into()],
         )
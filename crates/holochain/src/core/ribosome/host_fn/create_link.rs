@@ -1,10 +1,11 @@
-use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::error::{RibosomeError, RibosomeResult};
 use crate::core::workflow::integrate_dht_ops_workflow::integrate_to_cache;
 use crate::core::{
     ribosome::{CallContext, RibosomeT},
     workflow::CallZomeWorkspace,
     SourceChainResult,
 };
+use holo_hash::EntryHash;
 use holochain_zome_types::header::builder;
 use holochain_zome_types::CreateLinkInput;
 use holochain_zome_types::CreateLinkOutput;
@@ -16,7 +17,11 @@ pub fn create_link<'a>(
     call_context: Arc<CallContext>,
     input: CreateLinkInput,
 ) -> RibosomeResult<CreateLinkOutput> {
+    call_context.host_access.check_writable()?;
+
     let (base_address, target_address, tag) = input.into_inner();
+    check_link_address(&base_address)?;
+    check_link_address(&target_address)?;
 
     // extract the zome position
     let zome_id = ribosome.zome_name_to_id(&call_context.zome_name)?;
@@ -29,7 +34,11 @@ pub fn create_link<'a>(
             let mut guard = call_context.host_access.workspace().write().await;
             let workspace: &mut CallZomeWorkspace = &mut guard;
             // push the header into the source chain
-            let header_hash = workspace.source_chain.put(header_builder, None).await?;
+            let chain_head = workspace.source_chain.chain_head()?.clone();
+            let header_hash = workspace
+                .source_chain
+                .put(header_builder, None, Some(chain_head))
+                .await?;
             let element = workspace
                 .source_chain
                 .get_element(&header_hash)?
@@ -51,4 +60,86 @@ pub fn create_link<'a>(
     Ok(CreateLinkOutput::new(header_hash))
 }
 
+/// Check a link base or target is a whole hash.
+/// A hash of another type never deserializes into the [EntryHash]
+/// but a short one would be committed and then rejected by sys validation.
+pub fn check_link_address(address: &EntryHash) -> RibosomeResult<()> {
+    if address.has_valid_length() {
+        Ok(())
+    } else {
+        Err(RibosomeError::MalformedLinkAddress(address.clone()))
+    }
+}
+
 // we rely on the tests for get_links and get_link_details
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::core::ribosome::{NamedInvocation, RibosomeT, ZomeCallInvocationFixturator};
+    use crate::core::workflow::{CallZomeWorkspace, CallZomeWorkspaceLock};
+    use crate::fixt::{WasmRibosomeFixturator, ZomeCallHostAccessFixturator};
+    use ::fixt::prelude::*;
+    use fallible_iterator::FallibleIterator;
+    use holochain_p2p::HolochainP2pCellT;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::ExternInput;
+    use std::convert::TryInto;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn create_link_with_a_dna_hash_base_errors() {
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = CallZomeWorkspaceLock::new(workspace);
+
+        let ribosome =
+            WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![TestWasm::Link.into()]))
+                .next()
+                .unwrap();
+        let (_network, _recv, cell_network) =
+            crate::test_utils::test_network(Some(ribosome.dna_file().dna_hash().clone()), None)
+                .await;
+        let cell_id =
+            holochain_types::cell::CellId::new(cell_network.dna_hash(), cell_network.from_agent());
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+        host_access.network = cell_network;
+
+        let invocation = ZomeCallInvocationFixturator::new(NamedInvocation(
+            cell_id,
+            TestWasm::Link.into(),
+            "create_link_from_dna".into(),
+            ExternInput::new(().try_into().unwrap()),
+        ))
+        .next()
+        .unwrap();
+
+        let result =
+            tokio::task::spawn(async move { ribosome.call_zome_function(host_access, invocation) })
+                .await
+                .unwrap();
+        result.expect_err("a link can't have a dna hash for its base");
+
+        // The path entry was committed but no link was
+        let chain_has_link = workspace_lock
+            .read()
+            .await
+            .source_chain
+            .iter_back()
+            .any(|element| {
+                Ok(matches!(
+                    element.header(),
+                    holochain_zome_types::Header::CreateLink(_)
+                ))
+            })
+            .unwrap();
+        assert!(!chain_has_link);
+    }
+}
@@ -2,11 +2,13 @@ use crate::core::ribosome::error::{RibosomeError, RibosomeResult};
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
 use crate::core::state::cascade::error::CascadeError;
+use crate::core::sys_validate::ValidationOutcome;
 use crate::core::workflow::call_zome_workflow::CallZomeWorkspace;
 use crate::core::{workflow::integrate_dht_ops_workflow::integrate_to_cache, SourceChainError};
 use holo_hash::{EntryHash, HeaderHash};
 use holochain_p2p::actor::GetOptions;
 use holochain_zome_types::header::builder;
+use holochain_zome_types::metadata::Details;
 use holochain_zome_types::DeleteInput;
 use holochain_zome_types::{element::SignedHeaderHashed, DeleteOutput};
 use std::sync::Arc;
@@ -17,10 +19,11 @@ pub fn delete<'a>(
     call_context: Arc<CallContext>,
     input: DeleteInput,
 ) -> RibosomeResult<DeleteOutput> {
+    call_context.host_access.check_writable()?;
+
     let deletes_address = input.into_inner();
 
-    let deletes_entry_address =
-        get_original_address(call_context.clone(), deletes_address.clone())?;
+    let deletes_entry_address = get_delete_target(call_context.clone(), deletes_address.clone())?;
 
     let host_access = call_context.host_access();
 
@@ -33,7 +36,10 @@ pub fn delete<'a>(
             deletes_address,
             deletes_entry_address,
         };
-        let header_hash = source_chain.put(header_builder, None).await?;
+        let chain_head = source_chain.chain_head()?.clone();
+        let header_hash = source_chain
+            .put(header_builder, None, Some(chain_head))
+            .await?;
         let element = source_chain
             .get_element(&header_hash)?
             .expect("Element we just put in SourceChain must be gettable");
@@ -50,6 +56,52 @@ pub fn delete<'a>(
     })
 }
 
+/// Find the entry that deleting this header removes.
+/// The same checks sys validation runs on the delete are made here
+/// so an invalid delete is refused instead of being authored:
+/// the header must create an entry, be live and be authored by this agent.
+#[allow(clippy::extra_unused_lifetimes)]
+fn get_delete_target<'a>(
+    call_context: Arc<CallContext>,
+    address: HeaderHash,
+) -> RibosomeResult<EntryHash> {
+    let network = call_context.host_access.network().clone();
+    let workspace_lock = call_context.host_access.workspace();
+
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let mut workspace = workspace_lock.write().await;
+        let author = workspace.source_chain.agent_pubkey()?;
+        let mut cascade = workspace.cascade(network);
+        let details = match cascade
+            .get_details(address.clone().into(), GetOptions::default())
+            .await?
+        {
+            Some(Details::Element(details)) => details,
+            // Should not be trying to delete via EntryHash
+            Some(Details::Entry(_)) => {
+                return Err(CascadeError::InvalidResponse(address.into()).into())
+            }
+            None => return Err(RibosomeError::ElementDeps(address.into())),
+        };
+        let header = details.element.header();
+        let invalid = |outcome| Err(RibosomeError::InvalidDelete(address.clone(), outcome));
+        let entry_hash = match header.entry_data() {
+            Some((entry_hash, _)) => entry_hash.clone(),
+            None => return invalid(ValidationOutcome::NotNewEntry(header.clone())),
+        };
+        if !details.deletes.is_empty() {
+            return invalid(ValidationOutcome::AlreadyDeleted(address.clone()));
+        }
+        if *header.author() != author {
+            return invalid(ValidationOutcome::DeleteNotByAuthor(
+                author,
+                header.author().clone(),
+            ));
+        }
+        Ok(entry_hash)
+    })
+}
+
 #[allow(clippy::extra_unused_lifetimes)]
 pub(crate) fn get_original_address<'a>(
     call_context: Arc<CallContext>,
@@ -93,10 +145,27 @@ pub(crate) fn get_original_address<'a>(
 #[cfg(test)]
 #[cfg(feature = "slow_tests")]
 pub mod wasm_test {
-    use crate::{core::workflow::CallZomeWorkspace, fixt::ZomeCallHostAccessFixturator};
+    use super::delete;
+    use crate::core::ribosome::error::RibosomeError;
+    use crate::core::sys_validate::ValidationOutcome;
+    use crate::core::workflow::integrate_dht_ops_workflow::integrate_to_cache;
+    use crate::fixt::{
+        AgentPubKeyFixturator, CallContextFixturator, WasmRibosomeFixturator,
+        ZomeCallHostAccessFixturator,
+    };
+    use crate::{core::workflow::CallZomeWorkspace, test_utils::test_network};
     use ::fixt::prelude::*;
     use hdk3::prelude::*;
+    use holochain_types::{
+        element::SignedHeaderHashedExt,
+        entry::option_entry_hashed,
+        fixt::{AppEntry, CreateFixturator, EntryFixturator, SignatureFixturator},
+        EntryHashed, HeaderHashed,
+    };
     use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::element::SignedHeaderHashed;
+    use matches::assert_matches;
+    use std::sync::Arc;
 
     #[tokio::test(threaded_scheduler)]
     async fn ribosome_delete_entry_test<'a>() {
@@ -137,4 +206,114 @@ pub mod wasm_test {
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test(threaded_scheduler)]
+    /// only a live entry this agent created can be deleted
+    async fn ribosome_delete_invalid_target_test() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        let thing_a: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crd, "create", ());
+        let delete_a: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crd, "delete", thing_a);
+
+        // Call the host fn directly so the error can be inspected
+        let ribosome = WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![TestWasm::Crd]))
+            .next()
+            .unwrap();
+        let author = AgentPubKeyFixturator::new(Predictable).next().unwrap();
+        let (_network, _r, cell_network) =
+            test_network(Some(ribosome.dna_file().dna_hash().clone()), Some(author)).await;
+        host_access.network = cell_network;
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.zome_name = TestWasm::Crd.into();
+        call_context.host_access = host_access.into();
+        let ribosome = Arc::new(ribosome);
+        let call_context = Arc::new(call_context);
+
+        // The entry is already deleted
+        let output = delete(
+            ribosome.clone(),
+            call_context.clone(),
+            DeleteInput::new(thing_a.clone()),
+        );
+        assert_matches!(
+            output,
+            Err(RibosomeError::InvalidDelete(hash, ValidationOutcome::AlreadyDeleted(_)))
+                if hash == thing_a
+        );
+
+        // A delete doesn't create an entry so there is nothing to delete
+        let output = delete(
+            ribosome.clone(),
+            call_context.clone(),
+            DeleteInput::new(delete_a),
+        );
+        assert_matches!(
+            output,
+            Err(RibosomeError::InvalidDelete(
+                _,
+                ValidationOutcome::NotNewEntry(_)
+            ))
+        );
+
+        // Another agent's entry, held in the cache
+        let someone_else = AgentPubKeyFixturator::new(Unpredictable).next().unwrap();
+        let entry = EntryFixturator::new(AppEntry).next().unwrap();
+        let mut create = fixt!(Create);
+        create.author = someone_else.clone();
+        create.entry_hash = EntryHashed::from_content_sync(entry.clone()).into_hash();
+        let header = HeaderHashed::from_content_sync(Header::Create(create));
+        let foreign = header.as_hash().clone();
+        let element = Element::new(
+            SignedHeaderHashed::with_presigned(header, fixt!(Signature)),
+            Some(entry),
+        );
+        tokio_safe_block_on::tokio_safe_block_forever_on({
+            let workspace_lock = workspace_lock.clone();
+            async move {
+                let mut workspace = workspace_lock.write().await;
+                let (shh, entry) = element.clone().into_inner();
+                workspace
+                    .cache_cas
+                    .put(shh, option_entry_hashed(entry).await)
+                    .unwrap();
+                let CallZomeWorkspace {
+                    cache_cas,
+                    cache_meta,
+                    ..
+                } = &mut *workspace;
+                integrate_to_cache(&element, cache_cas, cache_meta)
+                    .await
+                    .unwrap();
+            }
+        });
+
+        // Only the author can delete it
+        let output = delete(ribosome, call_context, DeleteInput::new(foreign.clone()));
+        assert_matches!(
+            output,
+            Err(RibosomeError::InvalidDelete(
+                hash,
+                ValidationOutcome::DeleteNotByAuthor(_, author)
+            )) if hash == foreign && author == someone_else
+        );
+
+        // Nothing was committed by the refused deletes
+        let chain_len = tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+            workspace_lock.read().await.source_chain.len()
+        });
+        assert_eq!(chain_len, 5);
+    }
 }
@@ -19,6 +19,8 @@ pub fn delete_link<'a>(
     call_context: Arc<CallContext>,
     input: DeleteLinkInput,
 ) -> RibosomeResult<DeleteLinkOutput> {
+    call_context.host_access.check_writable()?;
+
     let link_add_address = input.into_inner();
 
     // get the base address from the add link header
@@ -77,7 +79,10 @@ pub fn delete_link<'a>(
             link_add_address,
             base_address,
         };
-        let header_hash = source_chain.put(header_builder, None).await?;
+        let chain_head = source_chain.chain_head()?.clone();
+        let header_hash = source_chain
+            .put(header_builder, None, Some(chain_head))
+            .await?;
         let element = source_chain
             .get_element(&header_hash)?
             .expect("Element we just put in SourceChain must be gettable");
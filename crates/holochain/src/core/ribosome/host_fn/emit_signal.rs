@@ -1,14 +1,60 @@
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
+use crate::core::signal::{Signal, UserSignal};
 use holochain_zome_types::EmitSignalInput;
 use holochain_zome_types::EmitSignalOutput;
 use std::sync::Arc;
 
+/// Send a signal from the zome's Cell to the app clients subscribed to it.
+/// Outside of a zome call on a Cell the signal goes nowhere.
 pub fn emit_signal(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     _input: EmitSignalInput,
 ) -> RibosomeResult<EmitSignalOutput> {
-    unimplemented!();
+    if let Some(signal_tx) = call_context.host_access.signal_tx() {
+        signal_tx.send(Signal::User(UserSignal));
+    }
+    Ok(EmitSignalOutput::new(()))
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use super::emit_signal;
+    use crate::core::ribosome::{CallContext, HostAccess};
+    use crate::core::signal::{Signal, SignalSender};
+    use crate::fixt::{WasmRibosomeFixturator, ZomeCallHostAccessFixturator, ZomeNameFixturator};
+    use ::fixt::prelude::*;
+    use holochain_types::fixt::CellIdFixturator;
+    use holochain_zome_types::EmitSignalInput;
+    use matches::assert_matches;
+    use std::sync::Arc;
+    use tokio::sync::broadcast;
+
+    #[tokio::test(threaded_scheduler)]
+    /// signals are marked with the cell and app they came from
+    async fn emit_signal_test() {
+        let ribosome = WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![]))
+            .next()
+            .unwrap();
+        let (tx, mut rx) = broadcast::channel(1);
+        let cell_id = fixt!(CellId);
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.signal_tx = Some(SignalSender::new(tx, cell_id.clone(), "app".into()));
+        let call_context = CallContext::new(fixt!(ZomeName), HostAccess::ZomeCall(host_access));
+
+        emit_signal(
+            Arc::new(ribosome),
+            Arc::new(call_context),
+            EmitSignalInput::new(()),
+        )
+        .unwrap();
+
+        let message = rx.recv().await.unwrap();
+        assert_eq!(message.cell_id, Some(cell_id));
+        assert_eq!(message.app_id, Some("app".to_string()));
+        assert_matches!(message.signal, Signal::User(_));
+    }
 }
@@ -0,0 +1,158 @@
+use crate::core::ribosome::error::{RibosomeError, RibosomeResult};
+use crate::core::ribosome::guest_callback::entry_defs::{EntryDefsInvocation, EntryDefsResult};
+use crate::core::ribosome::{CallContext, RibosomeT};
+use holochain_zome_types::header::EntryType;
+use holochain_zome_types::EntryDefForInput;
+use holochain_zome_types::EntryDefForOutput;
+use std::sync::Arc;
+
+/// Get the entry def the dna declares for an app entry type
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn entry_def_for<'a>(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: EntryDefForInput,
+) -> RibosomeResult<EntryDefForOutput> {
+    // system entries have no entry def
+    let app_entry_type = match input.into_inner() {
+        EntryType::App(app_entry_type) => app_entry_type,
+        _ => return Ok(EntryDefForOutput::new(None)),
+    };
+
+    // the zome id is the position of the zome in the dna
+    let zome_name = match ribosome
+        .dna_file()
+        .dna()
+        .zomes
+        .get(u8::from(app_entry_type.zome_id()) as usize)
+    {
+        Some((zome_name, _)) => zome_name.clone(),
+        None => return Ok(EntryDefForOutput::new(None)),
+    };
+
+    // the entry def id is the position of the def in that zome's entry defs
+    let entry_def =
+        match ribosome.run_entry_defs((&call_context.host_access).into(), EntryDefsInvocation)? {
+            EntryDefsResult::Defs(defs) => defs
+                .get(&zome_name)
+                .and_then(|entry_defs| entry_defs.get(u8::from(app_entry_type.id()) as usize))
+                .cloned(),
+            EntryDefsResult::Err(zome_name, msg) => {
+                return Err(RibosomeError::EntryDefs(zome_name, msg))
+            }
+        };
+
+    Ok(EntryDefForOutput::new(entry_def))
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use super::entry_def_for;
+    use crate::core::ribosome::host_fn::create::create;
+    use crate::core::ribosome::host_fn::get::get;
+    use crate::core::workflow::call_zome_workflow::CallZomeWorkspace;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::EntryFixturator;
+    use crate::fixt::WasmRibosomeFixturator;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use holo_hash::HeaderHash;
+    use holochain_types::fixt::AppEntry;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::entry::GetOptions;
+    use holochain_zome_types::entry_def::{EntryDefId, EntryVisibility, RequiredValidations};
+    use holochain_zome_types::header::EntryType;
+    use holochain_zome_types::CreateInput;
+    use holochain_zome_types::EntryDefForInput;
+    use holochain_zome_types::EntryDefForOutput;
+    use holochain_zome_types::GetInput;
+    use std::sync::Arc;
+
+    #[tokio::test(threaded_scheduler)]
+    /// the def for a committed entry is the one the zome declared
+    async fn entry_def_for_test() {
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let ribosome = Arc::new(
+            WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![TestWasm::Create]))
+                .next()
+                .unwrap(),
+        );
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.zome_name = TestWasm::Create.into();
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+        call_context.host_access = host_access.into();
+        let call_context = Arc::new(call_context);
+
+        // commit a "msg" which is the second entry def in the zome
+        let app_entry = EntryFixturator::new(AppEntry).next().unwrap();
+        let input = CreateInput::new((EntryDefId::App("msg".into()), app_entry));
+        let header_hash: HeaderHash = create(ribosome.clone(), call_context.clone(), input)
+            .unwrap()
+            .into_inner();
+        let element = get(
+            ribosome.clone(),
+            call_context.clone(),
+            GetInput::new((header_hash.into(), GetOptions::default())),
+        )
+        .unwrap()
+        .into_inner()
+        .unwrap();
+        let entry_type = element.header().entry_type().unwrap().clone();
+
+        let entry_def = entry_def_for(
+            ribosome.clone(),
+            call_context.clone(),
+            EntryDefForInput::new(entry_type),
+        )
+        .unwrap()
+        .into_inner()
+        .unwrap();
+
+        assert_eq!(entry_def.id, EntryDefId::App("msg".into()));
+        assert_eq!(entry_def.visibility, EntryVisibility::Public);
+        assert_eq!(entry_def.required_validations, RequiredValidations::from(5));
+
+        // system entries have no def
+        let output = entry_def_for(
+            ribosome,
+            call_context,
+            EntryDefForInput::new(EntryType::AgentPubKey),
+        )
+        .unwrap()
+        .into_inner();
+        assert_eq!(output, None);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    /// a zome gets the def of an entry it committed through the hdk
+    async fn entry_def_for_macro_test() {
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock;
+
+        let output: EntryDefForOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Create, "entry_def_for_post", ());
+        let entry_def = output.into_inner().unwrap();
+
+        assert_eq!(entry_def.id, EntryDefId::App("post".into()));
+        assert_eq!(entry_def.visibility, EntryVisibility::Public);
+        assert_eq!(entry_def.required_validations, RequiredValidations::from(5));
+    }
+}
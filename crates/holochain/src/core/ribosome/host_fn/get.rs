@@ -1,12 +1,21 @@
+use crate::core::ribosome::cancel::block_on_cancellable;
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::guest_callback::resolve_conflict::ResolveConflictHostAccess;
+use crate::core::ribosome::guest_callback::resolve_conflict::ResolveConflictInvocation;
+use crate::core::ribosome::guest_callback::resolve_conflict::ResolveConflictResult;
 use crate::core::ribosome::{CallContext, RibosomeT};
+use holochain_types::dna::UpdateConflictPolicy;
+use holochain_zome_types::element::Element;
+use holochain_zome_types::resolve_conflict::ResolveConflict;
+use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::GetInput;
 use holochain_zome_types::GetOutput;
 use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn get<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: GetInput,
 ) -> RibosomeResult<GetOutput> {
@@ -14,21 +23,293 @@ pub fn get<'a>(
 
     // Get the network from the context
     let network = call_context.host_access.network().clone();
+    let cancel = call_context.host_access.cancel().cloned();
+    let executor = call_context.host_fn_executor.clone();
 
     // timeouts must be handled by the network
-    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
-        let maybe_element = call_context
-            .host_access
-            .workspace()
-            .write()
-            .await
-            .cascade(network)
-            .dht_get(hash, options.into())
-            .await?;
+    block_on_cancellable(&executor, cancel, async move {
+        let follow_updates = options.follow_updates;
+        let mut workspace = call_context.host_access.workspace().write().await;
+        let mut cascade = workspace.cascade(network);
+        let maybe_element = if follow_updates {
+            let zome_name = call_context.zome_name();
+            cascade
+                .dht_get_latest(hash, options.into(), |heads| {
+                    resolve_update_conflict(ribosome.as_ref(), &zome_name, heads)
+                })
+                .await?
+                .map(|(element, _branch)| element)
+        } else {
+            cascade.dht_get(hash, options.into()).await?
+        };
 
         Ok(GetOutput::new(maybe_element))
     })
 }
 
+/// Choose the winner of diverging updates with the dna's [UpdateConflictPolicy].
+/// Every agent sees the same heads so every agent chooses the same winner.
+pub fn resolve_update_conflict(
+    ribosome: &impl RibosomeT,
+    zome_name: &ZomeName,
+    heads: Vec<Element>,
+) -> RibosomeResult<Element> {
+    let last_write = |e: &Element| (e.header().timestamp(), e.header_address().clone());
+    let last_write_wins = |heads: Vec<Element>| {
+        heads
+            .into_iter()
+            .max_by_key(last_write)
+            .expect("There are always competing heads")
+    };
+    match ribosome.dna_file().dna().update_conflict_policy {
+        UpdateConflictPolicy::LastWriteWins => Ok(last_write_wins(heads)),
+        UpdateConflictPolicy::LowestAuthorWins => Ok(heads
+            .into_iter()
+            .max_by(|a, b| {
+                b.header()
+                    .author()
+                    .cmp(a.header().author())
+                    .then_with(|| last_write(a).cmp(&last_write(b)))
+            })
+            .expect("There are always competing heads")),
+        UpdateConflictPolicy::Custom => {
+            let invocation =
+                ResolveConflictInvocation::new(zome_name.clone(), ResolveConflict(heads.clone()));
+            match ribosome.run_resolve_conflict(ResolveConflictHostAccess, invocation)? {
+                ResolveConflictResult::Winner(winner) => heads
+                    .into_iter()
+                    .find(|e| e.header_address() == &winner)
+                    .ok_or_else(|| {
+                        RibosomeError::ConflictResolution(
+                            zome_name.clone(),
+                            format!("{} is not a competing head", winner),
+                        )
+                    }),
+                ResolveConflictResult::Pass => Ok(last_write_wins(heads)),
+                ResolveConflictResult::Fail(zome_name, fail_string) => {
+                    Err(RibosomeError::ConflictResolution(zome_name, fail_string))
+                }
+            }
+        }
+    }
+}
+
 // we are relying on the create tests to show the commit/get round trip
 // @see commit_entry.rs
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::{core::workflow::CallZomeWorkspace, fixt::ZomeCallHostAccessFixturator};
+    use ::fixt::prelude::*;
+    use hdk3::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_get_matches_get_details_test() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        // simple replica of the internal type for the TestWasm::Crud entry
+        #[derive(Clone, Copy, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+        struct CounTree(u32);
+
+        let element = |output: GetOutput| output.into_inner().expect("no element");
+        let element_details = |details: GetDetailsOutput| match details.into_inner() {
+            Some(Details::Element(element_details)) => element_details,
+            _ => panic!("no element"),
+        };
+        let entry_details = |details: GetDetailsOutput| match details.into_inner() {
+            Some(Details::Entry(entry_details)) => entry_details,
+            _ => panic!("no entry"),
+        };
+
+        let zero_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(0));
+
+        let zero_a: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "new", ());
+        let one_a: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", zero_a.clone());
+
+        // By header the element is the same one get_details returns
+        let got = element(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "header_element",
+            zero_a.clone()
+        ));
+        let details = element_details(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "header_details",
+            zero_a.clone()
+        ));
+        assert_eq!(got, details.element);
+
+        // By entry the element is the entry get_details returns with one of its headers
+        let got = element(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_element",
+            zero_hash.clone()
+        ));
+        let details = entry_details(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details",
+            zero_hash.clone()
+        ));
+        assert_eq!(got.entry().as_option(), Some(&details.entry));
+        assert!(details.headers.contains(got.header()));
+        assert_eq!(got.header_address(), &zero_a);
+        assert_eq!(details.updates.len(), 1);
+
+        // Following updates returns the update instead
+        let latest = element(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "latest_element",
+            zero_hash.clone()
+        ));
+        assert_eq!(latest.header_address(), &one_a);
+        assert_eq!(
+            latest.entry().to_app_option::<CounTree>().unwrap(),
+            Some(CounTree(1))
+        );
+
+        // Updates of updates are followed
+        let two: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", one_a.clone());
+        let latest = element(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "latest_element",
+            zero_hash.clone()
+        ));
+        assert_eq!(latest.header_address(), &two);
+
+        // Deleted updates are not live so the previous update is returned
+        let _: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "dec", two);
+        let latest = element(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "latest_element",
+            zero_hash
+        ));
+        assert_eq!(latest.header_address(), &one_a);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_get_follow_updates_diverging_test() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        // simple replica of the internal type for the TestWasm::Crud entry
+        #[derive(Clone, Copy, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+        struct CounTree(u32);
+
+        let element = |output: GetOutput| output.into_inner().expect("no element");
+        // The order diverging updates are resolved in
+        let key = |element: Element| {
+            (
+                element.header().timestamp(),
+                element.header_address().clone(),
+            )
+        };
+
+        let zero_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(0));
+
+        // Diverge the zero entry with a few competing updates
+        let zero_a: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "new", ());
+        let mut heads = Vec::new();
+        for _ in 0..3 {
+            let head: HeaderHash =
+                crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", zero_a.clone());
+            heads.push(element(crate::call_test_ribosome!(
+                host_access,
+                TestWasm::Crud,
+                "header_element",
+                head
+            )));
+        }
+
+        // Not following updates returns the original
+        let original = element(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_element",
+            zero_hash.clone()
+        ));
+        assert_eq!(original.header_address(), &zero_a);
+
+        // The latest head by timestamp then hash is followed
+        let expected = heads
+            .iter()
+            .cloned()
+            .max_by_key(|head| key(head.clone()))
+            .unwrap();
+        let latest = element(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "latest_element",
+            zero_hash.clone()
+        ));
+        assert_eq!(latest, expected);
+
+        // Extend one head into a chain 1 -> 2 -> 3 and the newest is returned
+        let two: HeaderHash = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "inc",
+            heads[0].header_address().clone()
+        );
+        let three: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", two.clone());
+        let latest = element(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "latest_element",
+            zero_hash.clone()
+        ));
+        assert_eq!(latest.header_address(), &three);
+        assert_eq!(
+            latest.entry().to_app_option::<CounTree>().unwrap(),
+            Some(CounTree(3))
+        );
+
+        // The branch that was chosen is reported, starting at the winning head
+        let reported: GetLatestOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "latest_branch", zero_hash);
+        let reported = reported.into_inner().expect("no element");
+        assert_eq!(reported.element, latest);
+        assert_eq!(
+            reported.branch,
+            vec![expected.header_address().clone(), two, three]
+        );
+    }
+}
@@ -1,5 +1,7 @@
+use crate::core::ribosome::cancel::block_on_cancellable;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::{CallContext, RibosomeT};
+use holochain_zome_types::metadata::Details;
 use holochain_zome_types::{GetDetailsInput, GetDetailsOutput};
 use std::sync::Arc;
 
@@ -9,21 +11,24 @@ pub fn get_details<'a>(
     call_context: Arc<CallContext>,
     input: GetDetailsInput,
 ) -> RibosomeResult<GetDetailsOutput> {
-    let (hash, options) = input.into_inner();
+    let (hash, options, include_signatures) = input.into_inner();
 
     // Get the network from the context
     let network = call_context.host_access.network().clone();
+    let cancel = call_context.host_access.cancel().cloned();
+    let executor = call_context.host_fn_executor.clone();
 
     // timeouts must be handled by the network
-    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
-        let maybe_details = call_context
-            .host_access
-            .workspace()
-            .write()
-            .await
-            .cascade(network)
-            .get_details(hash, options.into())
-            .await?;
+    block_on_cancellable(&executor, cancel, async move {
+        let mut workspace = call_context.host_access.workspace().write().await;
+        let mut cascade = workspace.cascade(network);
+        let mut maybe_details = cascade.get_details(hash, options.into()).await?;
+        // Element details carry their signature either way
+        if !include_signatures {
+            if let Some(Details::Entry(details)) = maybe_details.as_mut() {
+                details.signed_headers.clear();
+            }
+        }
         Ok(GetDetailsOutput::new(maybe_details))
     })
 }
@@ -31,10 +36,20 @@ pub fn get_details<'a>(
 #[cfg(test)]
 #[cfg(feature = "slow_tests")]
 pub mod wasm_test {
+    use crate::core::ribosome::error::RibosomeError;
+    use crate::core::ribosome::host_fn::get_details::get_details;
+    use crate::core::ribosome::CallContext;
+    use crate::core::ribosome::HostFnCategory;
+    use crate::fixt::EntryHashFixturator;
+    use crate::fixt::WasmRibosomeFixturator;
     use crate::{core::workflow::CallZomeWorkspace, fixt::ZomeCallHostAccessFixturator};
     use ::fixt::prelude::*;
+    use futures::StreamExt;
     use hdk3::prelude::*;
+    use holochain_keystore::AgentPubKeyExt;
     use holochain_wasm_test_utils::TestWasm;
+    use std::sync::Arc;
+    use test_wasm_common::TestInt;
 
     #[tokio::test(threaded_scheduler)]
     async fn ribosome_get_details_test<'a>() {
@@ -232,4 +247,378 @@ pub mod wasm_test {
             _ => panic!("no element"),
         }
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_get_details_history_order_test() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        // simple replica of the internal type for the TestWasm::Crud entry
+        #[derive(Clone, Copy, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+        struct CounTree(u32);
+
+        let entry_details = |details: GetDetailsOutput| match details.into_inner() {
+            Some(Details::Entry(entry_details)) => entry_details,
+            _ => panic!("no entry"),
+        };
+        // The order the history should be in
+        let key = |header: Header| (header.timestamp(), HeaderHash::with_data_sync(&header));
+
+        let zero_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(0));
+        let one_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(1));
+
+        // Update the zero entry a few times then delete each update
+        let zero_a: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "new", ());
+        let mut ones = Vec::new();
+        for _ in 0..3 {
+            let one: HeaderHash =
+                crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", zero_a.clone());
+            ones.push(one);
+        }
+        for one in ones {
+            let _: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "dec", one);
+        }
+
+        // Updates are the same on every call and in timestamp order
+        let first = entry_details(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details",
+            zero_hash.clone()
+        ));
+        let second = entry_details(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details",
+            zero_hash
+        ));
+        assert_eq!(first.updates.len(), 3);
+        assert_eq!(first.updates, second.updates);
+        let keys: Vec<_> = first
+            .updates
+            .into_iter()
+            .map(|u| key(Header::Update(u)))
+            .collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+
+        // So are deletes
+        let first = entry_details(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details",
+            one_hash.clone()
+        ));
+        let second = entry_details(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details",
+            one_hash
+        ));
+        assert_eq!(first.deletes.len(), 3);
+        assert_eq!(first.deletes, second.deletes);
+        let keys: Vec<_> = first
+            .deletes
+            .into_iter()
+            .map(|d| key(Header::Delete(d)))
+            .collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_get_details_conflicts_test() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        // simple replica of the internal type for the TestWasm::Crud entry
+        #[derive(Clone, Copy, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+        struct CounTree(u32);
+
+        let conflicts = |details: GetDetailsOutput| match details.into_inner() {
+            Some(Details::Entry(entry_details)) => entry_details.conflicts,
+            _ => panic!("no entry"),
+        };
+
+        let zero_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(0));
+        let one_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(1));
+
+        // A single update is not a conflict
+        let zero_a: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "new", ());
+        let one_a: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", zero_a.clone());
+        assert!(conflicts(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details",
+            zero_hash.clone()
+        ))
+        .is_empty());
+
+        // A second update of the same header diverges
+        let one_b: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", zero_a);
+        let found = conflicts(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details",
+            zero_hash.clone()
+        ));
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&one_a));
+        assert!(found.contains(&one_b));
+
+        // Updating one of the heads doesn't conflict on the updated entry
+        let _: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", one_a);
+        assert!(conflicts(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details",
+            one_hash
+        ))
+        .is_empty());
+
+        // Deleting a head leaves no competition
+        let _: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "dec", one_b);
+        assert!(conflicts(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details",
+            zero_hash
+        ))
+        .is_empty());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_get_details_includes_signatures_test() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        // simple replica of the internal type for the TestWasm::Crud entry
+        #[derive(Clone, Copy, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+        struct CounTree(u32);
+
+        let entry_details = |details: GetDetailsOutput| match details.into_inner() {
+            Some(Details::Entry(entry_details)) => entry_details,
+            _ => panic!("no entry"),
+        };
+
+        let one_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(1));
+
+        // Two updates of the same header give the entry two headers
+        let zero_a: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "new", ());
+        let _: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", zero_a.clone());
+        let _: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", zero_a);
+
+        // Signatures are only included when asked for
+        let unsigned = entry_details(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details",
+            one_hash.clone()
+        ));
+        assert_eq!(unsigned.headers.len(), 2);
+        assert!(unsigned.signed_headers.is_empty());
+
+        let signed = entry_details(crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "signed_entry_details",
+            one_hash
+        ));
+        assert_eq!(signed.headers, unsigned.headers);
+        assert_eq!(signed.signed_headers.len(), signed.headers.len());
+        for (header, signed_header) in signed.headers.iter().zip(signed.signed_headers.iter()) {
+            assert_eq!(signed_header.header(), header);
+            assert!(header
+                .author()
+                .verify_signature(signed_header.signature(), header)
+                .await
+                .unwrap());
+        }
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_counts_get_details_calls() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock;
+        let host_fn_calls = host_access.host_fn_calls.clone();
+
+        let _: () = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "entry_details_times",
+            TestInt(5)
+        );
+        assert_eq!(host_fn_calls.get("get_details"), 5);
+        assert_eq!(host_fn_calls.get("hash_entry"), 1);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn network_gets_over_the_budget_fail_the_call() {
+        use crate::conductor::config::HostFnBudgetConfig;
+        use crate::core::ribosome::{NamedInvocation, RibosomeT, ZomeCallInvocationFixturator};
+        use holochain_p2p::HolochainP2pCellT;
+        use std::convert::TryInto;
+
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let ribosome =
+            WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![TestWasm::Crud.into()]))
+                .next()
+                .unwrap();
+        let (_network, _recv, cell_network) =
+            crate::test_utils::test_network(Some(ribosome.dna_file().dna_hash().clone()), None)
+                .await;
+        let cell_id =
+            holochain_types::cell::CellId::new(cell_network.dna_hash(), cell_network.from_agent());
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock;
+        host_access.network = cell_network;
+        host_access.host_fn_budget = HostFnBudgetConfig {
+            network_gets: Some(3),
+            ..Default::default()
+        };
+        let host_fn_calls = host_access.host_fn_calls.clone();
+
+        let invocation = ZomeCallInvocationFixturator::new(NamedInvocation(
+            cell_id,
+            TestWasm::Crud.into(),
+            "entry_details_times".into(),
+            holochain_zome_types::ExternInput::new(TestInt(5).try_into().unwrap()),
+        ))
+        .next()
+        .unwrap();
+
+        let result =
+            tokio::task::spawn(async move { ribosome.call_zome_function(host_access, invocation) })
+                .await
+                .unwrap();
+
+        let err = result.expect_err("the call made more network gets than its budget");
+        assert!(format!("{:?}", err).contains("HostCallBudgetExceeded(NetworkGet, 3)"));
+        // The get over the budget was refused before it reached the network
+        assert_eq!(host_fn_calls.get("get_details"), 4);
+        assert_eq!(host_fn_calls.in_category(HostFnCategory::NetworkGet), 4);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn cancelling_a_call_releases_a_blocked_get_details() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let ribosome =
+            WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![TestWasm::Crud.into()]))
+                .next()
+                .unwrap();
+
+        // Hold on to every request without ever answering it
+        // so the get blocks until the network times out
+        let (_network, mut recv, cell_network) =
+            crate::test_utils::test_network(Some(ribosome.dna_file().dna_hash().clone()), None)
+                .await;
+        tokio::task::spawn(async move {
+            let mut unanswered = Vec::new();
+            while let Some(evt) = recv.next().await {
+                unanswered.push(evt);
+            }
+        });
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock;
+        host_access.network = cell_network;
+        let cancel = host_access.cancel.clone();
+
+        let zome_name = ribosome.dna_file().dna().zomes[0].0.clone();
+        let call_context = CallContext::new(zome_name, host_access.into());
+        let input = GetDetailsInput::new((fixt!(EntryHash).into(), GetOptions::default(), false));
+        let blocked = tokio::task::spawn(async move {
+            get_details(Arc::new(ribosome), Arc::new(call_context), input)
+        });
+
+        tokio::time::delay_for(std::time::Duration::from_millis(100)).await;
+        cancel.cancel();
+
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), blocked)
+            .await
+            .expect("get_details is still blocked after the call was cancelled")
+            .unwrap();
+        assert!(matches!(result, Err(RibosomeError::ZomeCallCancelled)));
+    }
 }
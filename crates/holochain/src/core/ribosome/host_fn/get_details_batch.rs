@@ -0,0 +1,119 @@
+use crate::core::ribosome::cancel::block_on_cancellable;
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::{CallContext, HostFnCategory, RibosomeT};
+use holochain_zome_types::metadata::Details;
+use holochain_zome_types::{GetDetailsBatchInput, GetDetailsBatchOutput, GetDetailsOutput};
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn get_details_batch<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetDetailsBatchInput,
+) -> RibosomeResult<GetDetailsBatchOutput> {
+    let hashes = input.into_inner();
+    // Every hash is a get as far as the budget is concerned
+    call_context
+        .host_access
+        .charge_host_fn_budget(HostFnCategory::NetworkGet, hashes.len() as u32)?;
+
+    // Get the network from the context
+    let network = call_context.host_access.network().clone();
+    let cancel = call_context.host_access.cancel().cloned();
+    let executor = call_context.host_fn_executor.clone();
+
+    // timeouts must be handled by the network
+    block_on_cancellable(&executor, cancel, async move {
+        let found = call_context
+            .host_access
+            .workspace()
+            .write()
+            .await
+            .cascade(network)
+            .get_details_many(hashes)
+            .await?;
+        Ok(GetDetailsBatchOutput::new(
+            found
+                .into_iter()
+                .map(|mut details| {
+                    // Signatures are only returned when get_details asks for them
+                    if let Some(Details::Entry(details)) = details.as_mut() {
+                        details.signed_headers.clear();
+                    }
+                    GetDetailsOutput::new(details)
+                })
+                .collect(),
+        ))
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::{core::workflow::CallZomeWorkspace, fixt::ZomeCallHostAccessFixturator};
+    use ::fixt::prelude::*;
+    use hdk3::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+    use test_wasm_common::AnyDhtHashes;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_get_details_batch_test() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        // simple replica of the internal type for the TestWasm::Crud entry
+        #[derive(Clone, Copy, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+        struct CounTree(u32);
+
+        let zero_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(0));
+        let two_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(2));
+
+        let zero_a: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "new", ());
+        let one_a: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", zero_a.clone());
+
+        let hashes = AnyDhtHashes(vec![
+            one_a.clone().into(),
+            zero_hash.clone().into(),
+            two_hash.into(),
+            zero_a.clone().into(),
+        ]);
+        let found: GetDetailsBatchOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "details_batch", hashes);
+        let found: Vec<_> = found
+            .into_inner()
+            .into_iter()
+            .map(GetDetailsOutput::into_inner)
+            .collect();
+        assert_eq!(found.len(), 4);
+
+        // Every hash gets exactly what get_details returns for it, in order
+        let one_a_details: GetDetailsOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "header_details", one_a);
+        let zero_hash_details: GetDetailsOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_details", zero_hash);
+        let zero_a_details: GetDetailsOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "header_details", zero_a);
+        assert!(matches!(found[0], Some(Details::Element(_))));
+        assert_eq!(found[0], one_a_details.into_inner());
+        assert!(matches!(found[1], Some(Details::Entry(_))));
+        assert_eq!(found[1], zero_hash_details.into_inner());
+        assert_eq!(found[2], None);
+        assert!(matches!(found[3], Some(Details::Element(_))));
+        assert_eq!(found[3], zero_a_details.into_inner());
+    }
+}
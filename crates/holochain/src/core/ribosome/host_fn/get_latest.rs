@@ -0,0 +1,41 @@
+use crate::core::ribosome::cancel::block_on_cancellable;
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::host_fn::get::resolve_update_conflict;
+use crate::core::ribosome::{CallContext, RibosomeT};
+use holochain_zome_types::element::LatestElement;
+use holochain_zome_types::GetLatestInput;
+use holochain_zome_types::GetLatestOutput;
+use std::sync::Arc;
+
+/// Follow the updates of an element to its latest live update,
+/// reporting the branch of updates that was followed
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn get_latest<'a>(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetLatestInput,
+) -> RibosomeResult<GetLatestOutput> {
+    let (hash, options) = input.into_inner();
+
+    // Get the network from the context
+    let network = call_context.host_access.network().clone();
+    let cancel = call_context.host_access.cancel().cloned();
+    let executor = call_context.host_fn_executor.clone();
+
+    // timeouts must be handled by the network
+    block_on_cancellable(&executor, cancel, async move {
+        let zome_name = call_context.zome_name();
+        let mut workspace = call_context.host_access.workspace().write().await;
+        let latest = workspace
+            .cascade(network)
+            .dht_get_latest(hash, options.into(), |heads| {
+                resolve_update_conflict(ribosome.as_ref(), &zome_name, heads)
+            })
+            .await?
+            .map(|(element, branch)| LatestElement { element, branch });
+
+        Ok(GetLatestOutput::new(latest))
+    })
+}
+
+// @see get.rs for the wasm test of following updates
@@ -1,3 +1,4 @@
+use crate::core::ribosome::cancel::block_on_cancellable;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::{
     ribosome::{CallContext, RibosomeT},
@@ -21,8 +22,10 @@ pub fn get_links<'a>(
 
     // Get the network from the context
     let network = call_context.host_access.network().clone();
+    let cancel = call_context.host_access.cancel().cloned();
+    let executor = call_context.host_fn_executor.clone();
 
-    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+    block_on_cancellable(&executor, cancel, async move {
         // Create the key
         let key = match tag.as_ref() {
             Some(tag) => LinkMetaKey::BaseZomeTag(&base_address, zome_id, tag),
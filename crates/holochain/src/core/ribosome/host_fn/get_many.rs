@@ -0,0 +1,197 @@
+use crate::core::ribosome::cancel::block_on_cancellable;
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::host_fn::get::resolve_update_conflict;
+use crate::core::ribosome::{CallContext, HostFnCategory, RibosomeT};
+use holo_hash::AnyDhtHash;
+use holochain_zome_types::element::{Element, IndexedElements};
+use holochain_zome_types::GetManyInput;
+use holochain_zome_types::GetManyOutput;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn get_many<'a>(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetManyInput,
+) -> RibosomeResult<GetManyOutput> {
+    let (hashes, options) = input.into_inner();
+    // Every hash is a get as far as the budget is concerned
+    call_context
+        .host_access
+        .charge_host_fn_budget(HostFnCategory::NetworkGet, hashes.len() as u32)?;
+
+    // Get the network from the context
+    let network = call_context.host_access.network().clone();
+    let cancel = call_context.host_access.cancel().cloned();
+    let executor = call_context.host_fn_executor.clone();
+
+    // timeouts must be handled by the network
+    block_on_cancellable(&executor, cancel, async move {
+        let follow_updates = options.follow_updates;
+        let zome_name = call_context.zome_name();
+        let mut workspace = call_context.host_access.workspace().write().await;
+        let mut cascade = workspace.cascade(network);
+
+        // The same hash is only looked up once
+        let distinct = hashes.iter().cloned().collect::<HashSet<_>>();
+        let mut resolved: HashMap<AnyDhtHash, Element> = cascade
+            .dht_get_many(distinct.into_iter().collect(), options.clone().into())
+            .await?;
+        if follow_updates {
+            // Each branch of updates is walked a step at a time
+            for element in resolved.values_mut() {
+                let (latest, _branch) = cascade
+                    .follow_updates(element.clone(), options.clone().into(), |heads| {
+                        resolve_update_conflict(ribosome.as_ref(), &zome_name, heads)
+                    })
+                    .await?;
+                *element = latest;
+            }
+        }
+
+        let mut found = IndexedElements::default();
+        for hash in hashes {
+            found.push(resolved.get(&hash).cloned());
+        }
+
+        Ok(GetManyOutput::new(found))
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::{core::workflow::CallZomeWorkspace, fixt::ZomeCallHostAccessFixturator};
+    use ::fixt::prelude::*;
+    use hdk3::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+    use test_wasm_common::AnyDhtHashes;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_get_many_test() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+
+        // simple replica of the internal type for the TestWasm::Crud entry
+        #[derive(Clone, Copy, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+        struct CounTree(u32);
+
+        let zero_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(0));
+        let one_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(1));
+        let two_hash: EntryHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "entry_hash", CounTree(2));
+
+        let zero_a: HeaderHash = crate::call_test_ribosome!(host_access, TestWasm::Crud, "new", ());
+        let one_a: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", zero_a.clone());
+
+        let hashes = AnyDhtHashes(vec![
+            zero_hash.clone().into(),
+            one_hash.clone().into(),
+            zero_a.clone().into(),
+            two_hash.clone().into(),
+            zero_hash.clone().into(),
+        ]);
+
+        // Without following updates each hash finds its own element
+        let found: GetManyOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "elements", hashes.clone());
+        let found = found.into_inner();
+        assert_eq!(found.elements.len(), 2);
+        assert_eq!(
+            found.indices,
+            vec![Some(0), Some(1), Some(0), None, Some(0)]
+        );
+        assert_eq!(found.get(0).unwrap().header_address(), &zero_a);
+        assert_eq!(found.get(1).unwrap().header_address(), &one_a);
+        assert_eq!(found.get(2).unwrap().header_address(), &zero_a);
+        assert!(found.get(3).is_none());
+        assert!(found.get(5).is_none());
+
+        // Following updates resolves everything to the one update
+        let found: GetManyOutput = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Crud,
+            "latest_elements",
+            hashes.clone()
+        );
+        let found = found.into_inner();
+        assert_eq!(found.elements.len(), 1);
+        assert_eq!(
+            found.indices,
+            vec![Some(0), Some(0), Some(0), None, Some(0)]
+        );
+        assert_eq!(found.elements[0].header_address(), &one_a);
+
+        // Each element is the same one a single get returns
+        for (i, hash) in vec![zero_hash, one_hash].into_iter().enumerate() {
+            let single: GetOutput =
+                crate::call_test_ribosome!(host_access, TestWasm::Crud, "latest_element", hash);
+            assert_eq!(single.into_inner().as_ref(), found.get(i));
+        }
+
+        // The chain grows and the same hashes resolve to the newest update
+        let two: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "inc", one_a.clone());
+        let found: GetManyOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Crud, "latest_elements", hashes);
+        let found = found.into_inner();
+        assert_eq!(found.elements.len(), 1);
+        assert_eq!(
+            found.indices,
+            vec![Some(0), Some(0), Some(0), Some(0), Some(0)]
+        );
+        assert_eq!(found.elements[0].header_address(), &two);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn get_many_is_charged_for_every_hash() {
+        use super::get_many;
+        use crate::{
+            conductor::config::HostFnBudgetConfig,
+            core::ribosome::{CallContext, HostFnCategory, RibosomeT},
+            fixt::{curve::Zomes, EntryHashFixturator, WasmRibosomeFixturator},
+        };
+        use std::sync::Arc;
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.host_fn_budget = HostFnBudgetConfig {
+            network_gets: Some(4),
+            ..Default::default()
+        };
+        let host_fn_calls = host_access.host_fn_calls.clone();
+        let ribosome = WasmRibosomeFixturator::new(Zomes(vec![TestWasm::Crud.into()]))
+            .next()
+            .unwrap();
+        let zome_name = ribosome.dna_file().dna().zomes[0].0.clone();
+        let call_context = CallContext::new(zome_name, host_access.into());
+
+        // Five hashes in one call are five gets against a budget of four
+        let hashes = std::iter::repeat_with(|| fixt!(EntryHash).into())
+            .take(5)
+            .collect();
+        let err = get_many(
+            Arc::new(ribosome),
+            Arc::new(call_context),
+            GetManyInput::new((hashes, GetOptions::default())),
+        )
+        .expect_err("the call asked for more gets than its budget");
+        assert!(format!("{:?}", err).contains("HostCallBudgetExceeded(NetworkGet, 4)"));
+        assert_eq!(host_fn_calls.in_category(HostFnCategory::NetworkGet), 5);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::core::ribosome::cancel::block_on_cancellable;
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::RibosomeT;
+use holochain_p2p::HolochainP2pCellT;
+use holochain_zome_types::agent_info::PeerInfoVec;
+use holochain_zome_types::GetPeersInput;
+use holochain_zome_types::GetPeersOutput;
+use std::sync::Arc;
+
+pub fn get_peers(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    _input: GetPeersInput,
+) -> RibosomeResult<GetPeersOutput> {
+    let cancel = call_context.host_access().cancel().cloned();
+    let executor = call_context.host_fn_executor.clone();
+    let peers = block_on_cancellable(&executor, cancel, async move {
+        let mut network = call_context.host_access().network().clone();
+        Ok(network.get_peers().await?)
+    })?;
+    Ok(GetPeersOutput::new(PeerInfoVec(peers)))
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::conductor::dna_store::MockDnaStore;
+    use crate::conductor::interface::websocket::test::setup_app;
+    use crate::conductor::ConductorHandle;
+    use crate::core::ribosome::ZomeCallInvocation;
+    use crate::core::ribosome::ZomeCallResponse;
+    use hdk3::prelude::*;
+    use holochain_types::app::InstalledCell;
+    use holochain_types::cell::CellId;
+    use holochain_types::dna::DnaDef;
+    use holochain_types::dna::DnaFile;
+    use holochain_types::test_utils::fake_agent_pubkey_1;
+    use holochain_types::test_utils::fake_agent_pubkey_2;
+    use holochain_types::Timestamp;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::ExternInput;
+
+    async fn call(
+        handle: &ConductorHandle,
+        cell_id: &CellId,
+        fn_name: &str,
+        payload: SerializedBytes,
+    ) -> SerializedBytes {
+        match handle
+            .call_zome(ZomeCallInvocation {
+                cell_id: cell_id.clone(),
+                zome_name: TestWasm::WhoAmI.into(),
+                cap: None,
+                fn_name: fn_name.into(),
+                payload: ExternInput::new(payload),
+                provenance: cell_id.agent_pubkey().clone(),
+            })
+            .await
+            .unwrap()
+            .unwrap()
+        {
+            ZomeCallResponse::Ok(guest_output) => guest_output.into_inner(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    /// discoverable agents see each other once they have gossiped
+    async fn get_peers_test() {
+        let dna_def = DnaDef::builder()
+            .name("get_peers_test")
+            .add_zome(TestWasm::WhoAmI)
+            .build()
+            .unwrap();
+        let dna_file = DnaFile::new(dna_def, vec![TestWasm::WhoAmI.into()])
+            .await
+            .unwrap();
+
+        let alice_agent_id = fake_agent_pubkey_1();
+        let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), alice_agent_id.clone());
+        let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+
+        let bob_agent_id = fake_agent_pubkey_2();
+        let bob_cell_id = CellId::new(dna_file.dna_hash().to_owned(), bob_agent_id.clone());
+        let bob_installed_cell = InstalledCell::new(bob_cell_id.clone(), "bob_handle".into());
+
+        let mut dna_store = MockDnaStore::new();
+
+        dna_store.expect_get().return_const(Some(dna_file.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(2)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(2)
+            .return_const(());
+
+        let (_tmpdir, _app_api, handle) = setup_app(
+            vec![(alice_installed_cell, None), (bob_installed_cell, None)],
+            dna_store,
+        )
+        .await;
+
+        // nobody is discoverable until they opt in
+        let PeerInfoVec(peers) = call(&handle, &alice_cell_id, "peers", ().try_into().unwrap())
+            .await
+            .try_into()
+            .unwrap();
+        assert!(peers.is_empty());
+
+        call(
+            &handle,
+            &alice_cell_id,
+            "be_discoverable",
+            ().try_into().unwrap(),
+        )
+        .await;
+        call(
+            &handle,
+            &bob_cell_id,
+            "be_discoverable",
+            ().try_into().unwrap(),
+        )
+        .await;
+
+        // both agents joined before this so anything seen after it was seen gossiping
+        let opted_in: holochain_zome_types::timestamp::Timestamp = Timestamp::now().into();
+
+        let mut seen = (false, false);
+        for _ in 0..50 {
+            let PeerInfoVec(alice_peers) =
+                call(&handle, &alice_cell_id, "peers", ().try_into().unwrap())
+                    .await
+                    .try_into()
+                    .unwrap();
+            let PeerInfoVec(bob_peers) =
+                call(&handle, &bob_cell_id, "peers", ().try_into().unwrap())
+                    .await
+                    .try_into()
+                    .unwrap();
+
+            // we never see ourselves
+            assert!(alice_peers
+                .iter()
+                .all(|p| p.agent_pub_key != alice_agent_id));
+            assert!(bob_peers.iter().all(|p| p.agent_pub_key != bob_agent_id));
+
+            seen = (
+                alice_peers
+                    .iter()
+                    .any(|p| p.agent_pub_key == bob_agent_id && p.last_seen >= opted_in),
+                bob_peers
+                    .iter()
+                    .any(|p| p.agent_pub_key == alice_agent_id && p.last_seen >= opted_in),
+            );
+            if seen == (true, true) {
+                break;
+            }
+            tokio::time::delay_for(std::time::Duration::from_millis(100)).await;
+        }
+        assert_eq!((true, true), seen);
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+}
@@ -0,0 +1,129 @@
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::{CallContext, RibosomeT};
+use holochain_zome_types::entry::RawEntry;
+use holochain_zome_types::GetRawInput;
+use holochain_zome_types::GetRawOutput;
+use std::sync::Arc;
+
+/// Get the stored bytes and type of an entry without deserializing it
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn get_raw<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetRawInput,
+) -> RibosomeResult<GetRawOutput> {
+    let (hash, options) = input.into_inner();
+
+    // Get the network from the context
+    let network = call_context.host_access.network().clone();
+
+    // timeouts must be handled by the network
+    let maybe_element = tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        call_context
+            .host_access
+            .workspace()
+            .write()
+            .await
+            .cascade(network)
+            .dht_get(hash, options.into())
+            .await
+    })?;
+
+    let raw_entry = match maybe_element {
+        Some(element) => match (element.header().entry_type(), element.entry().as_option()) {
+            (Some(entry_type), Some(entry)) => {
+                Some(RawEntry::new(entry_type.clone(), entry.clone())?)
+            }
+            _ => None,
+        },
+        None => None,
+    };
+
+    Ok(GetRawOutput::new(raw_entry))
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use super::get_raw;
+    use crate::core::ribosome::host_fn::create::create;
+    use crate::core::ribosome::host_fn::get::get;
+    use crate::core::workflow::call_zome_workflow::CallZomeWorkspace;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::EntryFixturator;
+    use crate::fixt::WasmRibosomeFixturator;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use holo_hash::HeaderHash;
+    use holochain_types::fixt::AppEntry;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::entry::GetOptions;
+    use holochain_zome_types::entry_def::EntryDefId;
+    use holochain_zome_types::header::EntryType;
+    use holochain_zome_types::CreateInput;
+    use holochain_zome_types::Entry;
+    use holochain_zome_types::GetInput;
+    use holochain_zome_types::GetRawInput;
+    use matches::assert_matches;
+    use std::sync::Arc;
+
+    #[tokio::test(threaded_scheduler)]
+    /// the raw bytes are the same app entry a typed get returns
+    async fn get_raw_test() {
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let ribosome = Arc::new(
+            WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![TestWasm::Create]))
+                .next()
+                .unwrap(),
+        );
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.zome_name = TestWasm::Create.into();
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+        call_context.host_access = host_access.into();
+        let call_context = Arc::new(call_context);
+
+        let app_entry = EntryFixturator::new(AppEntry).next().unwrap();
+        let input = CreateInput::new((EntryDefId::App("post".into()), app_entry.clone()));
+        let header_hash: HeaderHash = create(ribosome.clone(), call_context.clone(), input)
+            .unwrap()
+            .into_inner();
+
+        let element = get(
+            ribosome.clone(),
+            call_context.clone(),
+            GetInput::new((header_hash.clone().into(), GetOptions::default())),
+        )
+        .unwrap()
+        .into_inner()
+        .unwrap();
+        let raw_entry = get_raw(
+            ribosome,
+            call_context,
+            GetRawInput::new((header_hash.into(), GetOptions::default())),
+        )
+        .unwrap()
+        .into_inner()
+        .unwrap();
+
+        assert_matches!(raw_entry.entry_type, EntryType::App(_));
+        assert_eq!(Some(&raw_entry.entry_type), element.header().entry_type());
+        assert_eq!(
+            element.entry().as_option(),
+            Some(&Entry::app(raw_entry.bytes.clone()).unwrap())
+        );
+        let entry_bytes: holochain_serialized_bytes::SerializedBytes = match app_entry {
+            Entry::App(bytes) => bytes.into_sb(),
+            _ => unreachable!(),
+        };
+        assert_eq!(entry_bytes, raw_entry.bytes);
+    }
+}
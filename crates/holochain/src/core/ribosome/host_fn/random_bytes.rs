@@ -1,3 +1,4 @@
+use crate::core::ribosome::cancel::block_on_cancellable;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
@@ -13,25 +14,32 @@ use std::sync::Arc;
 /// return n crypto secure random bytes from the standard holochain crypto lib
 pub fn random_bytes(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     input: RandomBytesInput,
 ) -> RibosomeResult<RandomBytesOutput> {
+    let cancel = call_context.host_access.cancel().cloned();
+    let executor = call_context.host_fn_executor.clone();
+    let random_bytes =
+        block_on_cancellable(&executor, cancel, random_bytes_async(input.into_inner()))?;
+    Ok(RandomBytesOutput::new(random_bytes))
+}
+
+/// return n crypto secure random bytes without blocking the thread,
+/// for callers that are already in an async context
+pub async fn random_bytes_async(len: u32) -> RibosomeResult<Bytes> {
     let _ = crypto_init_sodium();
-    let mut buf: DynCryptoBytes = crypto_secure_buffer(input.into_inner() as _)?;
+    let mut buf: DynCryptoBytes = crypto_secure_buffer(len as _)?;
 
-    tokio_safe_block_on::tokio_safe_block_forever_on(async {
-        crypto_randombytes_buf(&mut buf).await
-    })?;
+    crypto_randombytes_buf(&mut buf).await?;
 
     let random_bytes = buf.read();
-
-    Ok(RandomBytesOutput::new(Bytes::from(random_bytes.to_vec())))
+    Ok(Bytes::from(random_bytes.to_vec()))
 }
 
 #[cfg(test)]
 #[cfg(feature = "slow_tests")]
 pub mod wasm_test {
-    use crate::core::ribosome::host_fn::random_bytes::random_bytes;
+    use crate::core::ribosome::host_fn::random_bytes::{random_bytes, random_bytes_async};
 
     use crate::fixt::CallContextFixturator;
     use crate::fixt::WasmRibosomeFixturator;
@@ -63,6 +71,15 @@ pub mod wasm_test {
         assert_ne!(&[0; LEN], output.into_inner().as_ref(),);
     }
 
+    #[tokio::test(threaded_scheduler)]
+    /// we can get some random data from an async context without blocking
+    async fn random_bytes_async_test() {
+        const LEN: usize = 10;
+        let output = random_bytes_async(LEN as _).await.unwrap();
+        assert_eq!(LEN, output.len());
+        assert_ne!(&[0; LEN], output.as_ref());
+    }
+
     #[tokio::test(threaded_scheduler)]
     /// we can get some random data out of the fn via. a wasm call
     async fn ribosome_random_bytes_test() {
@@ -0,0 +1,19 @@
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::RibosomeT;
+use holochain_p2p::HolochainP2pCellT;
+use holochain_zome_types::SetDiscoverableInput;
+use holochain_zome_types::SetDiscoverableOutput;
+use std::sync::Arc;
+
+pub fn set_discoverable(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: SetDiscoverableInput,
+) -> RibosomeResult<SetDiscoverableOutput> {
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let mut network = call_context.host_access().network().clone();
+        network.set_discoverable(input.into_inner()).await
+    })?;
+    Ok(SetDiscoverableOutput::new(()))
+}
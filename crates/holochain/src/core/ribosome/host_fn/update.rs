@@ -1,4 +1,7 @@
-use super::{create::extract_entry_def, delete::get_original_address};
+use super::{
+    create::{check_entry_size, extract_entry_def},
+    delete::get_original_address,
+};
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::{
@@ -21,9 +24,14 @@ pub fn update<'a>(
     call_context: Arc<CallContext>,
     input: UpdateInput,
 ) -> RibosomeResult<UpdateOutput> {
+    call_context.host_access.check_writable()?;
+
     // destructure the args out into an app type def id and entry
     let (entry_def_id, entry, original_header_address) = input.into_inner();
 
+    // reject oversized entries before they reach the source chain
+    check_entry_size(ribosome.as_ref(), &entry)?;
+
     // build the entry hash
     let async_entry = entry.clone();
     let entry_hash =
@@ -67,7 +75,10 @@ pub fn update<'a>(
         let workspace: &mut CallZomeWorkspace = &mut guard;
         let source_chain = &mut workspace.source_chain;
         // push the header and the entry into the source chain
-        let header_hash = source_chain.put(header_builder, Some(entry)).await?;
+        let chain_head = source_chain.chain_head()?.clone();
+        let header_hash = source_chain
+            .put(header_builder, Some(entry), Some(chain_head))
+            .await?;
         // fetch the element we just added so we can integrate its DhtOps
         let element = source_chain
             .get_element(&header_hash)?
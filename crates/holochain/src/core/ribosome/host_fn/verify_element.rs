@@ -0,0 +1,176 @@
+use crate::core::ribosome::cancel::block_on_cancellable;
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::RibosomeT;
+use holo_hash::{EntryHash, HeaderHash};
+use holochain_keystore::AgentPubKeyExt;
+use holochain_zome_types::element::ElementVerification;
+use holochain_zome_types::VerifyElementInput;
+use holochain_zome_types::VerifyElementOutput;
+use std::sync::Arc;
+
+/// Check the hashes and signature of an element agree with its content
+pub fn verify_element(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: VerifyElementInput,
+) -> RibosomeResult<VerifyElementOutput> {
+    let element = input.into_inner();
+    let header = element.header();
+
+    // the hash is carried alongside the header so it can be stale or forged
+    let header_hash = HeaderHash::with_data_sync(header) == *element.header_address();
+
+    let cancel = call_context.host_access.cancel().cloned();
+    let executor = call_context.host_fn_executor.clone();
+    let signature = block_on_cancellable(&executor, cancel, async {
+        // a signature that can't be checked at all, e.g. the wrong length, hasn't passed
+        Ok(header
+            .author()
+            .verify_signature(element.signature(), header)
+            .await
+            .unwrap_or(false))
+    })?;
+
+    let entry_hash = match (header.entry_data(), element.entry().as_option()) {
+        (Some((entry_hash, _)), Some(entry)) => {
+            Some(EntryHash::with_data_sync(entry) == *entry_hash)
+        }
+        _ => None,
+    };
+
+    Ok(VerifyElementOutput::new(ElementVerification {
+        header_hash,
+        signature,
+        entry_hash,
+    }))
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::core::workflow::CallZomeWorkspace;
+    use crate::fixt::AgentPubKeyFixturator;
+    use crate::fixt::HeaderHashFixturator;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use hdk3::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::element::SignedHeaderHashed;
+    use holochain_zome_types::signature::Signature;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn ribosome_verify_element_test() {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+
+        crate::core::workflow::fake_genesis(&mut workspace.source_chain)
+            .await
+            .unwrap();
+
+        let workspace_lock = crate::core::workflow::CallZomeWorkspaceLock::new(workspace);
+
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock;
+
+        let _: HeaderHash =
+            crate::call_test_ribosome!(host_access, TestWasm::Create, "create_entry", ());
+        let output: GetOutput =
+            crate::call_test_ribosome!(host_access, TestWasm::Create, "get_entry", ());
+        let element = output.into_inner().unwrap();
+
+        let verified: ElementVerification =
+            crate::call_test_ribosome!(host_access, TestWasm::Create, "verify_element", element);
+        assert_eq!(
+            verified,
+            ElementVerification {
+                header_hash: true,
+                signature: true,
+                entry_hash: Some(true),
+            }
+        );
+        assert!(verified.is_valid());
+
+        let (signed_header, entry) = element.into_inner();
+        let (header_hashed, signature) = signed_header.into_header_and_signature();
+        let (header, header_hash) = header_hashed.into_inner();
+        let tampered =
+            |header: Header, header_hash: HeaderHash, signature, entry: Option<Entry>| {
+                Element::new(
+                    SignedHeaderHashed::with_presigned(
+                        HeaderHashed::with_pre_hashed(header, header_hash),
+                        signature,
+                    ),
+                    entry,
+                )
+            };
+
+        // A different entry under the genuine header
+        let other_entry = Entry::Agent(fixt!(AgentPubKey));
+        let verified: ElementVerification = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Create,
+            "verify_element",
+            tampered(
+                header.clone(),
+                header_hash.clone(),
+                signature.clone(),
+                Some(other_entry)
+            )
+        );
+        assert_eq!(
+            verified,
+            ElementVerification {
+                header_hash: true,
+                signature: true,
+                entry_hash: Some(false),
+            }
+        );
+        assert!(!verified.is_valid());
+
+        // A signature that wasn't made by the author
+        let verified: ElementVerification = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Create,
+            "verify_element",
+            tampered(
+                header.clone(),
+                header_hash.clone(),
+                Signature(vec![0; 64]),
+                entry.as_option().cloned()
+            )
+        );
+        assert_eq!(
+            verified,
+            ElementVerification {
+                header_hash: true,
+                signature: false,
+                entry_hash: Some(true),
+            }
+        );
+
+        // A header hash that isn't the hash of the header
+        let verified: ElementVerification = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Create,
+            "verify_element",
+            tampered(
+                header,
+                fixt!(HeaderHash),
+                signature,
+                entry.as_option().cloned()
+            )
+        );
+        assert_eq!(
+            verified,
+            ElementVerification {
+                header_hash: false,
+                signature: true,
+                entry_hash: Some(true),
+            }
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! A bound on the host functions blocked on async work at once,
+//! shared by every ribosome of a conductor.
+//!
+//! Host functions like `get_details` and `random_bytes` block their thread
+//! until the network or database answers, which moves that thread onto
+//! tokio's blocking pool. Enough concurrent zome calls doing this exhaust the
+//! pool and every other call stalls behind them. Past the bound, host
+//! functions wait for a slot before they block instead and a warning is logged
+//! so operators can see the executor is saturated and tune it for their workload.
+
+use crate::core::ribosome::cancel::ZomeCallCancel;
+use crate::core::ribosome::error::{RibosomeError, RibosomeResult};
+use parking_lot::{Condvar, Mutex};
+use std::{sync::Arc, time::Duration};
+use tracing::*;
+
+/// How many host functions can block at once if the conductor doesn't
+/// configure a bound. The conductor's runtime sizes its blocking pool to fit the bound.
+pub const DEFAULT_MAX_BLOCKING_HOST_FNS: usize = 512;
+
+/// How often a host function waiting for a slot checks if its call was cancelled
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Counts of how the host fn executor has been used
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HostFnExecutorMetrics {
+    /// A host function had to wait because the executor was full
+    pub saturations: u64,
+}
+
+/// Limits how many host functions block at once.
+/// Clones share the same slots.
+#[derive(Clone, Debug)]
+pub struct HostFnExecutor {
+    state: Arc<Mutex<ExecutorState>>,
+    released: Arc<Condvar>,
+}
+
+#[derive(Debug)]
+struct ExecutorState {
+    max: usize,
+    running: usize,
+    metrics: HostFnExecutorMetrics,
+}
+
+impl Default for HostFnExecutor {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BLOCKING_HOST_FNS)
+    }
+}
+
+impl HostFnExecutor {
+    /// Let up to `max` host functions block at once
+    pub fn new(max: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ExecutorState {
+                max: max.max(1),
+                running: 0,
+                metrics: HostFnExecutorMetrics::default(),
+            })),
+            released: Arc::new(Condvar::new()),
+        }
+    }
+
+    /// The metrics of this executor since it was created
+    pub fn metrics(&self) -> HostFnExecutorMetrics {
+        self.state.lock().metrics
+    }
+
+    /// Wait for a slot to block a host function in.
+    /// This parks the calling thread without handing it over to tokio,
+    /// so host functions waiting here don't take threads from the blocking pool.
+    /// Gives up with [RibosomeError::ZomeCallCancelled] if the call is
+    /// cancelled while waiting.
+    pub(crate) fn acquire(&self, cancel: Option<&ZomeCallCancel>) -> RibosomeResult<HostFnPermit> {
+        let is_cancelled = || cancel.map_or(false, |c| c.is_cancelled());
+        let mut state = self.state.lock();
+        if state.running >= state.max {
+            state.metrics.saturations += 1;
+            warn!(
+                max_blocking_host_fns = state.max,
+                "host fn executor is saturated, host function is waiting for a slot"
+            );
+            while state.running >= state.max {
+                if is_cancelled() {
+                    return Err(RibosomeError::ZomeCallCancelled);
+                }
+                self.released.wait_for(&mut state, CANCEL_CHECK_INTERVAL);
+            }
+        }
+        state.running += 1;
+        Ok(HostFnPermit(self.clone()))
+    }
+}
+
+/// A slot in the executor, released on drop
+pub(crate) struct HostFnPermit(HostFnExecutor);
+
+impl Drop for HostFnPermit {
+    fn drop(&mut self) {
+        self.0.state.lock().running -= 1;
+        self.0.released.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tracing::{span, Event, Metadata, Subscriber};
+
+    /// Counts warnings
+    #[derive(Clone, Default)]
+    struct WarnCounter(Arc<AtomicUsize>);
+
+    impl Subscriber for WarnCounter {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            if *event.metadata().level() == Level::WARN {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        fn enter(&self, _: &span::Id) {}
+
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    #[test]
+    fn saturating_the_executor_warns() {
+        let warnings = WarnCounter::default();
+        let executor = HostFnExecutor::new(2);
+
+        let first = executor.acquire(None).unwrap();
+        let _second = executor.acquire(None).unwrap();
+        assert_eq!(executor.metrics().saturations, 0);
+
+        // The third host function waits on its own thread
+        // and the saturation is logged once
+        let (tx, rx) = std::sync::mpsc::channel();
+        let third = {
+            let executor = executor.clone();
+            let warnings = warnings.clone();
+            std::thread::spawn(move || {
+                let _guard = tracing::subscriber::set_default(warnings);
+                let _permit = executor.acquire(None).unwrap();
+                tx.send(()).unwrap();
+            })
+        };
+        let timeout = std::time::Duration::from_millis(100);
+        assert!(rx.recv_timeout(timeout).is_err());
+        assert_eq!(warnings.0.load(Ordering::SeqCst), 1);
+        assert_eq!(executor.metrics().saturations, 1);
+
+        // It runs once a slot is released
+        drop(first);
+        rx.recv_timeout(std::time::Duration::from_secs(5))
+            .expect("waiting host function was not given the released slot");
+        third.join().unwrap();
+        assert_eq!(executor.state.lock().running, 1);
+    }
+
+    #[test]
+    fn cancelling_a_waiting_host_fn_gives_up_its_wait() {
+        let executor = HostFnExecutor::new(1);
+        let _running = executor.acquire(None).unwrap();
+
+        let cancel = ZomeCallCancel::default();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let waiting = {
+            let executor = executor.clone();
+            let cancel = cancel.clone();
+            std::thread::spawn(move || {
+                tx.send(executor.acquire(Some(&cancel)).map(|_| ()))
+                    .unwrap();
+            })
+        };
+        assert!(rx
+            .recv_timeout(std::time::Duration::from_millis(100))
+            .is_err());
+
+        // No slot is released but the call is cancelled
+        cancel.cancel();
+        let result = rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("cancelled host function kept waiting for a slot");
+        assert!(matches!(result, Err(RibosomeError::ZomeCallCancelled)));
+        waiting.join().unwrap();
+        assert_eq!(executor.state.lock().running, 1);
+    }
+}
@@ -0,0 +1,156 @@
+//! A bounded cache of compiled wasm modules shared by the ribosomes
+//! of a conductor.
+//!
+//! Compiling a module is slow so each one is kept after its first use.
+//! Once the cache holds more than its bound the least recently used module
+//! is evicted, which only means it is compiled again the next time it is needed.
+
+use super::error::RibosomeResult;
+use holochain_wasmer_host::prelude::Module;
+use parking_lot::Mutex;
+use std::{collections::HashMap, sync::Arc};
+use tracing::*;
+
+/// How many modules are kept if the conductor doesn't configure a bound
+pub const DEFAULT_MAX_CACHED_MODULES: usize = 64;
+
+/// Counts of how the module cache has been used
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ModuleCacheMetrics {
+    /// A compiled module was found in the cache
+    pub hits: u64,
+    /// A module had to be compiled
+    pub misses: u64,
+    /// A module was dropped to stay within the bound
+    pub evictions: u64,
+}
+
+/// A handle to a bounded cache of compiled modules.
+/// Clones share the same cache.
+#[derive(Clone)]
+pub struct ModuleCache(Arc<Mutex<CachedModules<Module>>>);
+
+impl ModuleCache {
+    /// A cache that holds at most `max` modules
+    pub fn new(max: usize) -> Self {
+        Self(Arc::new(Mutex::new(CachedModules::new(max))))
+    }
+
+    /// The metrics of this cache since it was created
+    pub fn metrics(&self) -> ModuleCacheMetrics {
+        self.0.lock().metrics
+    }
+
+    /// Get the module cached for this key or compile and cache it
+    pub(crate) fn get_or_compile<F>(&self, key: &[u8], compile: F) -> RibosomeResult<Module>
+    where
+        F: FnOnce() -> RibosomeResult<Module>,
+    {
+        if let Some(module) = self.0.lock().get(key) {
+            return Ok(module);
+        }
+        // Don't hold the lock while compiling so other zomes can still be called
+        let module = compile()?;
+        self.0.lock().put(key.to_vec(), module.clone());
+        Ok(module)
+    }
+}
+
+impl Default for ModuleCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CACHED_MODULES)
+    }
+}
+
+impl std::fmt::Debug for ModuleCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cache = self.0.lock();
+        f.debug_struct("ModuleCache")
+            .field("max", &cache.max)
+            .field("len", &cache.modules.len())
+            .finish()
+    }
+}
+
+/// Least recently used cache of modules by their wasm hash
+struct CachedModules<M> {
+    max: usize,
+    /// Each module along with when it was last used
+    modules: HashMap<Vec<u8>, (M, u64)>,
+    /// Increases on every use so the oldest use is the smallest
+    clock: u64,
+    metrics: ModuleCacheMetrics,
+}
+
+impl<M: Clone> CachedModules<M> {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            modules: HashMap::new(),
+            clock: 0,
+            metrics: ModuleCacheMetrics::default(),
+        }
+    }
+
+    fn get(&mut self, key: &[u8]) -> Option<M> {
+        self.clock += 1;
+        match self.modules.get_mut(key) {
+            Some((module, last_used)) => {
+                *last_used = self.clock;
+                self.metrics.hits += 1;
+                Some(module.clone())
+            }
+            None => {
+                self.metrics.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn put(&mut self, key: Vec<u8>, module: M) {
+        self.clock += 1;
+        self.modules.insert(key, (module, self.clock));
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.modules.len() > self.max {
+            let oldest = self
+                .modules
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(key) = oldest {
+                debug!(?key, "evicting compiled module");
+                self.modules.remove(&key);
+                self.metrics.evictions += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = CachedModules::new(2);
+        cache.put(vec![1], "one");
+        cache.put(vec![2], "two");
+        // Using one makes two the oldest
+        assert_eq!(cache.get(&[1]), Some("one"));
+        cache.put(vec![3], "three");
+        assert_eq!(cache.get(&[2]), None);
+        assert_eq!(cache.get(&[1]), Some("one"));
+        assert_eq!(cache.get(&[3]), Some("three"));
+        assert_eq!(
+            cache.metrics,
+            ModuleCacheMetrics {
+                hits: 3,
+                misses: 1,
+                evictions: 1,
+            }
+        );
+    }
+}
@@ -2,7 +2,8 @@ use super::{
     guest_callback::{
         entry_defs::EntryDefsHostAccess, init::InitHostAccess,
         migrate_agent::MigrateAgentHostAccess, post_commit::PostCommitHostAccess,
-        validate::ValidateHostAccess, validation_package::ValidationPackageHostAccess,
+        resolve_conflict::ResolveConflictHostAccess, validate::ValidateHostAccess,
+        validation_package::ValidationPackageHostAccess,
     },
     HostAccess, ZomeCallHostAccess,
 };
@@ -12,10 +13,13 @@ use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
 use crate::core::ribosome::guest_callback::init::InitInvocation;
 use crate::core::ribosome::guest_callback::init::InitResult;
+use crate::core::ribosome::guest_callback::is_callback;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitResult;
+use crate::core::ribosome::guest_callback::resolve_conflict::ResolveConflictInvocation;
+use crate::core::ribosome::guest_callback::resolve_conflict::ResolveConflictResult;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::validate_link_add::ValidateCreateLinkHostAccess;
@@ -24,12 +28,14 @@ use crate::core::ribosome::guest_callback::validate_link_add::ValidateCreateLink
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
 use crate::core::ribosome::guest_callback::CallIterator;
+use crate::core::ribosome::guest_callback::ParallelCallIterator;
 use crate::core::ribosome::host_fn::agent_info::agent_info;
 use crate::core::ribosome::host_fn::call::call;
 use crate::core::ribosome::host_fn::call_remote::call_remote;
 use crate::core::ribosome::host_fn::capability_claims::capability_claims;
 use crate::core::ribosome::host_fn::capability_grants::capability_grants;
 use crate::core::ribosome::host_fn::capability_info::capability_info;
+use crate::core::ribosome::host_fn::close_chain::close_chain;
 use crate::core::ribosome::host_fn::create::create;
 use crate::core::ribosome::host_fn::create_link::create_link;
 use crate::core::ribosome::host_fn::debug::debug;
@@ -38,23 +44,34 @@ use crate::core::ribosome::host_fn::delete::delete;
 use crate::core::ribosome::host_fn::delete_link::delete_link;
 use crate::core::ribosome::host_fn::emit_signal::emit_signal;
 use crate::core::ribosome::host_fn::encrypt::encrypt;
+use crate::core::ribosome::host_fn::entry_def_for::entry_def_for;
 use crate::core::ribosome::host_fn::get::get;
 use crate::core::ribosome::host_fn::get_details::get_details;
+use crate::core::ribosome::host_fn::get_details_batch::get_details_batch;
+use crate::core::ribosome::host_fn::get_latest::get_latest;
 use crate::core::ribosome::host_fn::get_link_details::get_link_details;
 use crate::core::ribosome::host_fn::get_links::get_links;
+use crate::core::ribosome::host_fn::get_many::get_many;
+use crate::core::ribosome::host_fn::get_peers::get_peers;
+use crate::core::ribosome::host_fn::get_raw::get_raw;
 use crate::core::ribosome::host_fn::hash_entry::hash_entry;
 use crate::core::ribosome::host_fn::keystore::keystore;
 use crate::core::ribosome::host_fn::property::property;
 use crate::core::ribosome::host_fn::query::query;
 use crate::core::ribosome::host_fn::random_bytes::random_bytes;
 use crate::core::ribosome::host_fn::schedule::schedule;
+use crate::core::ribosome::host_fn::set_discoverable::set_discoverable;
 use crate::core::ribosome::host_fn::show_env::show_env;
 use crate::core::ribosome::host_fn::sign::sign;
 use crate::core::ribosome::host_fn::sys_time::sys_time;
 use crate::core::ribosome::host_fn::unreachable::unreachable;
 use crate::core::ribosome::host_fn::update::update;
+use crate::core::ribosome::host_fn::verify_element::verify_element;
 use crate::core::ribosome::host_fn::zome_info::zome_info;
+use crate::core::ribosome::host_fn_executor::HostFnExecutor;
+use crate::core::ribosome::module_cache::ModuleCache;
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnCategory;
 use crate::core::ribosome::Invocation;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomeCallInvocation;
@@ -70,6 +87,7 @@ use holochain_zome_types::entry_def::EntryDefsCallbackResult;
 use holochain_zome_types::init::InitCallbackResult;
 use holochain_zome_types::migrate_agent::MigrateAgentCallbackResult;
 use holochain_zome_types::post_commit::PostCommitCallbackResult;
+use holochain_zome_types::resolve_conflict::ResolveConflictCallbackResult;
 use holochain_zome_types::validate::ValidateCallbackResult;
 use holochain_zome_types::validate::ValidationPackageCallbackResult;
 use holochain_zome_types::validate_link_add::ValidateCreateLinkCallbackResult;
@@ -78,6 +96,7 @@ use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::CallbackResult;
 use holochain_zome_types::ZomeCallResponse;
 use holochain_zome_types::{header::ZomeId, ExternOutput};
+use parking_lot::Mutex;
 use std::sync::Arc;
 
 /// Path to the wasm cache path
@@ -92,22 +111,64 @@ pub struct WasmRibosome {
     //      - is already in the wasm cache, and only include the DnaDef portion
     //      - here in the ribosome.
     pub dna_file: DnaFile,
+    /// Compiled modules, shared with the other ribosomes of the conductor
+    module_cache: ModuleCache,
+    /// Bounds the host functions blocked at once, shared with the other
+    /// ribosomes of the conductor
+    host_fn_executor: HostFnExecutor,
+    /// The most zomes an order-independent callback is called on at once,
+    /// None calls them one at a time
+    parallel_callbacks: Option<usize>,
+    /// The entry defs of every zome, shared by the clones of this ribosome
+    /// so the entry_defs callbacks are only called once
+    entry_defs: Arc<Mutex<Option<EntryDefsResult>>>,
 }
 
 impl WasmRibosome {
-    /// Create a new instance
+    /// Create a new instance with a cache of its own
     pub fn new(dna_file: DnaFile) -> Self {
-        Self { dna_file }
+        Self {
+            dna_file,
+            module_cache: ModuleCache::default(),
+            host_fn_executor: HostFnExecutor::default(),
+            parallel_callbacks: None,
+            entry_defs: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Compile modules into the cache shared by the conductor instead
+    pub fn with_module_cache(mut self, module_cache: ModuleCache) -> Self {
+        self.module_cache = module_cache;
+        self
+    }
+
+    /// Block host functions in the executor shared by the conductor instead
+    pub fn with_host_fn_executor(mut self, host_fn_executor: HostFnExecutor) -> Self {
+        self.host_fn_executor = host_fn_executor;
+        self
+    }
+
+    /// Call the zomes of order-independent callbacks like validate on up to
+    /// this many threads at once instead of one after another
+    pub fn with_parallel_callbacks(mut self, parallel_callbacks: Option<usize>) -> Self {
+        self.parallel_callbacks = parallel_callbacks;
+        self
     }
 
     pub fn module(&self, call_context: CallContext) -> RibosomeResult<Module> {
-        let zome_name: ZomeName = call_context.zome_name();
-        let wasm: Arc<Vec<u8>> = self.dna_file.get_wasm_for_zome(&zome_name)?.code();
-        Ok(holochain_wasmer_host::instantiate::module(
-            &self.wasm_cache_key(&zome_name)?,
-            &wasm,
-            std::env::var_os(WASM_CACHE_PATH_ENV),
-        )?)
+        self.zome_module(&call_context.zome_name())
+    }
+
+    fn zome_module(&self, zome_name: &ZomeName) -> RibosomeResult<Module> {
+        let key = self.wasm_cache_key(zome_name)?;
+        self.module_cache.get_or_compile(key, || {
+            let wasm: Arc<Vec<u8>> = self.dna_file.get_wasm_for_zome(zome_name)?.code();
+            Ok(holochain_wasmer_host::instantiate::module(
+                key,
+                &wasm,
+                std::env::var_os(WASM_CACHE_PATH_ENV),
+            )?)
+        })
     }
 
     pub fn wasm_cache_key(&self, zome_name: &ZomeName) -> Result<&[u8], DnaError> {
@@ -123,15 +184,11 @@ impl WasmRibosome {
     }
 
     pub fn instance(&self, call_context: CallContext) -> RibosomeResult<Instance> {
-        let zome_name: ZomeName = call_context.zome_name();
-        let wasm: Arc<Vec<u8>> = self.dna_file.get_wasm_for_zome(&zome_name)?.code();
+        let module = self.module(call_context.clone())?;
         let imports: ImportObject = Self::imports(self, call_context);
-        Ok(holochain_wasmer_host::instantiate::instantiate(
-            self.wasm_cache_key(&zome_name)?,
-            &wasm,
-            &imports,
-            std::env::var_os(WASM_CACHE_PATH_ENV),
-        )?)
+        Ok(module
+            .instantiate(&imports)
+            .map_err(|e| WasmError::Compile(e.to_string()))?)
     }
 
     fn imports(&self, call_context: CallContext) -> ImportObject {
@@ -141,11 +198,25 @@ impl WasmRibosome {
         let self_arc = std::sync::Arc::new((*self).clone());
         let call_context_arc = std::sync::Arc::new(call_context);
 
+        // A host function that reaches the network is imported with its
+        // category and each call is charged to that part of the zome call's budget.
+        // Batched gets charge for every hash they ask for themselves.
         macro_rules! invoke_host_function {
-            ( $host_function:ident ) => {{
+            ( $host_function:ident $(, $category:ident)? ) => {{
                 let closure_self_arc = std::sync::Arc::clone(&self_arc);
                 let closure_call_context_arc = std::sync::Arc::clone(&call_context_arc);
                 move |ctx: &mut Ctx, guest_allocation_ptr: GuestPtr| -> Result<Len, WasmError> {
+                    if let Some(host_fn_calls) =
+                        closure_call_context_arc.host_access.host_fn_calls()
+                    {
+                        host_fn_calls.count(stringify!($host_function));
+                    }
+                    $(
+                        closure_call_context_arc
+                            .host_access
+                            .charge_host_fn_budget(HostFnCategory::$category, 1)
+                            .map_err(|e| WasmError::Zome(format!("{:?}", e)))?;
+                    )?
                     let input = $crate::holochain_wasmer_host::guest::from_guest_ptr(
                         ctx,
                         guest_allocation_ptr,
@@ -180,6 +251,10 @@ impl WasmRibosome {
         ns.insert("__debug", func!(invoke_host_function!(debug)));
         ns.insert("__hash_entry", func!(invoke_host_function!(hash_entry)));
         ns.insert("__unreachable", func!(invoke_host_function!(unreachable)));
+        ns.insert(
+            "__verify_element",
+            func!(invoke_host_function!(verify_element)),
+        );
 
         if let HostFnAccess {
             keystore: Permission::Allow,
@@ -204,9 +279,14 @@ impl WasmRibosome {
         {
             ns.insert("__zome_info", func!(invoke_host_function!(zome_info)));
             ns.insert("__property", func!(invoke_host_function!(property)));
+            ns.insert(
+                "__entry_def_for",
+                func!(invoke_host_function!(entry_def_for)),
+            );
         } else {
             ns.insert("__zome_info", func!(invoke_host_function!(unreachable)));
             ns.insert("__property", func!(invoke_host_function!(unreachable)));
+            ns.insert("__entry_def_for", func!(invoke_host_function!(unreachable)));
         }
 
         if let HostFnAccess {
@@ -262,18 +342,44 @@ impl WasmRibosome {
             ..
         } = host_fn_access
         {
-            ns.insert("__get", func!(invoke_host_function!(get)));
-            ns.insert("__get_details", func!(invoke_host_function!(get_details)));
-            ns.insert("__get_links", func!(invoke_host_function!(get_links)));
+            ns.insert("__get", func!(invoke_host_function!(get, NetworkGet)));
+            ns.insert(
+                "__get_details",
+                func!(invoke_host_function!(get_details, NetworkGet)),
+            );
+            ns.insert(
+                "__get_details_batch",
+                func!(invoke_host_function!(get_details_batch)),
+            );
+            ns.insert(
+                "__get_latest",
+                func!(invoke_host_function!(get_latest, NetworkGet)),
+            );
+            ns.insert(
+                "__get_links",
+                func!(invoke_host_function!(get_links, NetworkGet)),
+            );
+            ns.insert("__get_many", func!(invoke_host_function!(get_many)));
+            ns.insert(
+                "__get_raw",
+                func!(invoke_host_function!(get_raw, NetworkGet)),
+            );
             ns.insert(
                 "__get_link_details",
-                func!(invoke_host_function!(get_link_details)),
+                func!(invoke_host_function!(get_link_details, NetworkGet)),
             );
             ns.insert("__query", func!(invoke_host_function!(query)));
         } else {
             ns.insert("__get", func!(invoke_host_function!(unreachable)));
             ns.insert("__get_details", func!(invoke_host_function!(unreachable)));
+            ns.insert(
+                "__get_details_batch",
+                func!(invoke_host_function!(unreachable)),
+            );
+            ns.insert("__get_latest", func!(invoke_host_function!(unreachable)));
             ns.insert("__get_links", func!(invoke_host_function!(unreachable)));
+            ns.insert("__get_many", func!(invoke_host_function!(unreachable)));
+            ns.insert("__get_raw", func!(invoke_host_function!(unreachable)));
             ns.insert(
                 "__get_link_details",
                 func!(invoke_host_function!(unreachable)),
@@ -286,9 +392,25 @@ impl WasmRibosome {
             ..
         } = host_fn_access
         {
-            ns.insert("__call_remote", func!(invoke_host_function!(call_remote)));
+            ns.insert(
+                "__call_remote",
+                func!(invoke_host_function!(call_remote, RemoteCall)),
+            );
+            ns.insert(
+                "__get_peers",
+                func!(invoke_host_function!(get_peers, NetworkGet)),
+            );
+            ns.insert(
+                "__set_discoverable",
+                func!(invoke_host_function!(set_discoverable)),
+            );
         } else {
             ns.insert("__call_remote", func!(invoke_host_function!(unreachable)));
+            ns.insert("__get_peers", func!(invoke_host_function!(unreachable)));
+            ns.insert(
+                "__set_discoverable",
+                func!(invoke_host_function!(unreachable)),
+            );
         }
 
         if let HostFnAccess {
@@ -297,13 +419,20 @@ impl WasmRibosome {
         } = host_fn_access
         {
             ns.insert("__call", func!(invoke_host_function!(call)));
-            ns.insert("__create", func!(invoke_host_function!(create)));
+            ns.insert("__create", func!(invoke_host_function!(create, Commit)));
             ns.insert("__emit_signal", func!(invoke_host_function!(emit_signal)));
-            ns.insert("__create_link", func!(invoke_host_function!(create_link)));
-            ns.insert("__delete_link", func!(invoke_host_function!(delete_link)));
-            ns.insert("__update", func!(invoke_host_function!(update)));
-            ns.insert("__delete", func!(invoke_host_function!(delete)));
+            ns.insert(
+                "__create_link",
+                func!(invoke_host_function!(create_link, Commit)),
+            );
+            ns.insert(
+                "__delete_link",
+                func!(invoke_host_function!(delete_link, Commit)),
+            );
+            ns.insert("__update", func!(invoke_host_function!(update, Commit)));
+            ns.insert("__delete", func!(invoke_host_function!(delete, Commit)));
             ns.insert("__schedule", func!(invoke_host_function!(schedule)));
+            ns.insert("__close_chain", func!(invoke_host_function!(close_chain)));
         } else {
             ns.insert("__call", func!(invoke_host_function!(unreachable)));
             ns.insert("__create", func!(invoke_host_function!(unreachable)));
@@ -313,6 +442,7 @@ impl WasmRibosome {
             ns.insert("__update", func!(invoke_host_function!(unreachable)));
             ns.insert("__delete", func!(invoke_host_function!(unreachable)));
             ns.insert("__schedule", func!(invoke_host_function!(unreachable)));
+            ns.insert("__close_chain", func!(invoke_host_function!(unreachable)));
         }
         imports.register("env", ns);
 
@@ -324,7 +454,32 @@ macro_rules! do_callback {
     ( $self:ident, $access:ident, $invocation:ident, $callback_result:ty ) => {{
         let mut results: Vec<(ZomeName, $callback_result)> = Vec::new();
         // fallible iterator syntax instead of for loop
-        let mut call_iterator = $self.call_iterator($access.into(), $self.clone(), $invocation);
+        let mut call_iterator: Box<
+            dyn FallibleIterator<Item = (ZomeName, ExternOutput), Error = RibosomeError>,
+        > = match $self
+            .parallel_callbacks
+            .filter(|_| $invocation.order_independent())
+        {
+            Some(max_parallel) => {
+                let callbacks: Vec<FunctionName> = $invocation
+                    .fn_components()
+                    .map(FunctionName::from)
+                    .collect();
+                Box::new(
+                    ParallelCallIterator::new($access.into(), $self.clone(), $invocation)
+                        .with_max_parallel(max_parallel)
+                        // a zome exporting none of the callbacks isn't worth a thread,
+                        // and one that fails to list them is kept so the call reports it
+                        .with_zome_filter(|zome_name| {
+                            $self
+                                .list_callbacks(zome_name)
+                                .map(|exported| callbacks.iter().any(|c| exported.contains(c)))
+                                .unwrap_or(true)
+                        }),
+                )
+            }
+            None => Box::new($self.call_iterator($access.into(), $self.clone(), $invocation)),
+        };
         while let Some(output) = call_iterator.next()? {
             let (zome_name, callback_result) = output;
             let callback_result: $callback_result = callback_result.into();
@@ -340,6 +495,16 @@ macro_rules! do_callback {
     }};
 }
 
+impl WasmRibosome {
+    fn call_entry_defs(
+        &self,
+        access: EntryDefsHostAccess,
+        invocation: EntryDefsInvocation,
+    ) -> RibosomeResult<EntryDefsResult> {
+        do_callback!(self, access, invocation, EntryDefsCallbackResult)
+    }
+}
+
 impl RibosomeT for WasmRibosome {
     fn dna_file(&self) -> &DnaFile {
         &self.dna_file
@@ -383,6 +548,7 @@ impl RibosomeT for WasmRibosome {
         let call_context = CallContext {
             zome_name: zome_name.clone(),
             host_access,
+            host_fn_executor: self.host_fn_executor.clone(),
         };
         let module = self.module(call_context.clone())?;
 
@@ -409,6 +575,19 @@ impl RibosomeT for WasmRibosome {
         }
     }
 
+    fn list_callbacks(&self, zome_name: &ZomeName) -> RibosomeResult<Vec<FunctionName>> {
+        let mut callbacks: Vec<FunctionName> = self
+            .zome_module(zome_name)?
+            .info()
+            .exports
+            .keys()
+            .filter(|name| is_callback(name))
+            .map(|name| FunctionName::from(name.as_str()))
+            .collect();
+        callbacks.sort();
+        Ok(callbacks)
+    }
+
     fn call_iterator<R: RibosomeT, I: crate::core::ribosome::Invocation>(
         &self,
         access: HostAccess,
@@ -473,7 +652,15 @@ impl RibosomeT for WasmRibosome {
         access: EntryDefsHostAccess,
         invocation: EntryDefsInvocation,
     ) -> RibosomeResult<EntryDefsResult> {
-        do_callback!(self, access, invocation, EntryDefsCallbackResult)
+        if let Some(entry_defs) = self.entry_defs.lock().clone() {
+            return Ok(entry_defs);
+        }
+        let result = self.call_entry_defs(access, invocation)?;
+        // a zome failing to declare its defs is tried again next time
+        if let EntryDefsResult::Defs(_) = result {
+            *self.entry_defs.lock() = Some(result.clone());
+        }
+        Ok(result)
     }
 
     fn run_migrate_agent(
@@ -499,4 +686,41 @@ impl RibosomeT for WasmRibosome {
     ) -> RibosomeResult<PostCommitResult> {
         do_callback!(self, access, invocation, PostCommitCallbackResult)
     }
+
+    fn run_resolve_conflict(
+        &self,
+        access: ResolveConflictHostAccess,
+        invocation: ResolveConflictInvocation,
+    ) -> RibosomeResult<ResolveConflictResult> {
+        do_callback!(self, access, invocation, ResolveConflictCallbackResult)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+mod slow_tests {
+    use crate::core::ribosome::RibosomeT;
+    use crate::fixt::curve::Zomes;
+    use crate::fixt::WasmRibosomeFixturator;
+    use ::fixt::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::zome::FunctionName;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn list_callbacks_finds_exported_callbacks() {
+        let ribosome =
+            WasmRibosomeFixturator::new(Zomes(vec![TestWasm::Validate, TestWasm::InitPass]))
+                .next()
+                .unwrap();
+
+        // zome functions like always_validates are not callbacks
+        assert_eq!(
+            ribosome.list_callbacks(&TestWasm::Validate.into()).unwrap(),
+            vec![FunctionName::from("entry_defs"), "validate".into()]
+        );
+        assert_eq!(
+            ribosome.list_callbacks(&TestWasm::InitPass.into()).unwrap(),
+            vec![FunctionName::from("init")]
+        );
+    }
 }
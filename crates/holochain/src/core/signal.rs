@@ -1,5 +1,9 @@
 use holochain_serialized_bytes::prelude::*;
+use holochain_types::{app::AppId, cell::CellId};
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, sync::Arc};
+use tokio::sync::broadcast;
 
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
 pub enum Signal {
@@ -10,3 +14,241 @@ pub enum Signal {
 
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
 pub struct UserSignal;
+
+/// The type of a [Signal] without its content, used for filtering
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignalType {
+    Trace,
+    User,
+}
+
+impl Signal {
+    /// The type of this signal
+    pub fn signal_type(&self) -> SignalType {
+        match self {
+            Signal::Trace => SignalType::Trace,
+            Signal::User(_) => SignalType::User,
+        }
+    }
+}
+
+/// A [Signal] along with where it was emitted from.
+/// This is what is sent on the signal broadcast channel.
+#[derive(Clone, Debug)]
+pub struct SignalMessage {
+    /// The cell that emitted the signal, if any
+    pub cell_id: Option<CellId>,
+    /// The app the emitting cell belongs to, if any
+    pub app_id: Option<AppId>,
+    /// The signal itself
+    pub signal: Signal,
+}
+
+impl From<Signal> for SignalMessage {
+    fn from(signal: Signal) -> Self {
+        Self {
+            cell_id: None,
+            app_id: None,
+            signal,
+        }
+    }
+}
+
+/// Sends the signals one Cell emits, marked with the Cell and its app
+#[derive(Clone, Debug)]
+pub struct SignalSender {
+    tx: broadcast::Sender<SignalMessage>,
+    cell_id: CellId,
+    app_id: AppId,
+}
+
+impl SignalSender {
+    /// Send the signals of `cell_id` in `app_id` on a broadcast channel
+    pub fn new(tx: broadcast::Sender<SignalMessage>, cell_id: CellId, app_id: AppId) -> Self {
+        Self {
+            tx,
+            cell_id,
+            app_id,
+        }
+    }
+
+    /// Send a signal to every subscriber whose filter matches it.
+    /// Having no subscribers is not an error, the signal is dropped.
+    pub fn send(&self, signal: Signal) {
+        self.tx
+            .send(SignalMessage {
+                cell_id: Some(self.cell_id.clone()),
+                app_id: Some(self.app_id.clone()),
+                signal,
+            })
+            .ok();
+    }
+}
+
+/// Which signals a subscriber wants to receive.
+/// Each field that is set must match for a signal to be received.
+/// The default filter receives every signal.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignalFilter {
+    /// Only receive signals from these cells
+    pub cell_ids: Option<HashSet<CellId>>,
+    /// Only receive these types of signals
+    pub signal_types: Option<HashSet<SignalType>>,
+    /// Only receive signals from cells in these apps
+    pub app_ids: Option<HashSet<AppId>>,
+}
+
+impl SignalFilter {
+    /// Also receive signals from this cell
+    pub fn with_cell_id(mut self, cell_id: CellId) -> Self {
+        self.cell_ids
+            .get_or_insert_with(HashSet::new)
+            .insert(cell_id);
+        self
+    }
+
+    /// Also receive signals of this type
+    pub fn with_signal_type(mut self, signal_type: SignalType) -> Self {
+        self.signal_types
+            .get_or_insert_with(HashSet::new)
+            .insert(signal_type);
+        self
+    }
+
+    /// Also receive signals from cells in this app
+    pub fn with_app_id(mut self, app_id: AppId) -> Self {
+        self.app_ids.get_or_insert_with(HashSet::new).insert(app_id);
+        self
+    }
+
+    /// Check if a signal passes this filter
+    pub fn matches(&self, message: &SignalMessage) -> bool {
+        fn check<T: Eq + std::hash::Hash>(set: &Option<HashSet<T>>, value: Option<&T>) -> bool {
+            match set {
+                None => true,
+                Some(set) => value.map(|v| set.contains(v)).unwrap_or(false),
+            }
+        }
+        check(&self.cell_ids, message.cell_id.as_ref())
+            && check(&self.signal_types, Some(&message.signal.signal_type()))
+            && check(&self.app_ids, message.app_id.as_ref())
+    }
+}
+
+/// A [SignalFilter] that can be replaced while a subscription is using it.
+/// Clones share the same filter.
+#[derive(Clone, Debug, Default)]
+pub struct SharedSignalFilter(Arc<RwLock<SignalFilter>>);
+
+impl SharedSignalFilter {
+    /// The current filter
+    pub fn get(&self) -> SignalFilter {
+        self.0.read().clone()
+    }
+
+    /// Replace the filter for the signals received from now on
+    pub fn set(&self, filter: SignalFilter) {
+        *self.0.write() = filter;
+    }
+
+    /// Check if a signal passes the current filter
+    pub fn matches(&self, message: &SignalMessage) -> bool {
+        self.0.read().matches(message)
+    }
+}
+
+impl From<SignalFilter> for SharedSignalFilter {
+    fn from(filter: SignalFilter) -> Self {
+        Self(Arc::new(RwLock::new(filter)))
+    }
+}
+
+/// A subscription to the signal broadcast channel which
+/// only yields the signals matching its [SignalFilter]
+pub struct SignalSubscription {
+    rx: broadcast::Receiver<SignalMessage>,
+    filter: SharedSignalFilter,
+}
+
+impl SignalSubscription {
+    /// Subscribe to a signal broadcaster with a filter
+    pub fn new(broadcaster: &broadcast::Sender<SignalMessage>, filter: SignalFilter) -> Self {
+        Self {
+            rx: broadcaster.subscribe(),
+            filter: filter.into(),
+        }
+    }
+
+    /// The filter for this subscription
+    pub fn filter(&self) -> SignalFilter {
+        self.filter.get()
+    }
+
+    /// A handle to replace the filter of this subscription from elsewhere
+    pub fn shared_filter(&self) -> SharedSignalFilter {
+        self.filter.clone()
+    }
+
+    /// Replace the filter for the signals received from now on
+    pub fn set_filter(&mut self, filter: SignalFilter) {
+        self.filter.set(filter);
+    }
+
+    /// Receive the next signal that matches the filter.
+    /// Signals that don't match are skipped.
+    pub async fn recv(&mut self) -> Result<Signal, broadcast::RecvError> {
+        loop {
+            let message = self.rx.recv().await?;
+            if self.filter.matches(&message) {
+                return Ok(message.signal);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holochain_types::fixt::CellIdFixturator;
+    use matches::assert_matches;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn subscribers_only_receive_matching_signals() {
+        let (tx, _rx) = broadcast::channel(10);
+        let cell_a = fixt!(CellId);
+        let cell_b = fixt!(CellId);
+
+        let mut sub_a =
+            SignalSubscription::new(&tx, SignalFilter::default().with_cell_id(cell_a.clone()));
+        let mut sub_trace = SignalSubscription::new(
+            &tx,
+            SignalFilter::default()
+                .with_signal_type(SignalType::Trace)
+                .with_app_id("app".to_string()),
+        );
+
+        let send = |cell_id: &CellId, app_id: &str, signal: Signal| {
+            tx.send(SignalMessage {
+                cell_id: Some(cell_id.clone()),
+                app_id: Some(app_id.to_string()),
+                signal,
+            })
+            .unwrap();
+        };
+        send(&cell_b, "app", Signal::User(UserSignal));
+        send(&cell_a, "other", Signal::Trace);
+        send(&cell_b, "app", Signal::Trace);
+        send(&cell_a, "app", Signal::User(UserSignal));
+        drop(tx);
+
+        // Only signals from cell a
+        assert_matches!(sub_a.recv().await, Ok(Signal::Trace));
+        assert_matches!(sub_a.recv().await, Ok(Signal::User(_)));
+        assert_matches!(sub_a.recv().await, Err(broadcast::RecvError::Closed));
+
+        // Only trace signals from the app
+        assert_matches!(sub_trace.recv().await, Ok(Signal::Trace));
+        assert_matches!(sub_trace.recv().await, Err(broadcast::RecvError::Closed));
+    }
+}
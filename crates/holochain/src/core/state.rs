@@ -39,6 +39,7 @@ pub mod chain_sequence;
 pub mod dht_op_integration;
 #[allow(missing_docs)]
 pub mod element_buf;
+pub mod entry_ops_index;
 pub mod metadata;
 #[allow(missing_docs)]
 pub mod source_chain;
@@ -12,7 +12,8 @@ use crate::core::workflow::{
     integrate_dht_ops_workflow::integrate_single_metadata,
     produce_dht_ops_workflow::dht_op_light::error::DhtOpConvertResult,
 };
-use error::CascadeResult;
+use breaker::NetworkBreaker;
+use error::{CascadeError, CascadeResult};
 use fallible_iterator::FallibleIterator;
 use holo_hash::{
     hash_type::{self, AnyDht},
@@ -38,14 +39,16 @@ use holochain_types::{
 use holochain_zome_types::header::{CreateLink, DeleteLink};
 use holochain_zome_types::{
     element::SignedHeader,
-    header::{Delete, Update},
-    link::Link,
+    header::{Delete, Update, ZomeId},
+    link::{Link, LinkTag},
     metadata::{Details, ElementDetails, EntryDetails},
     Header,
 };
+use metrics::{CascadeCounters, CascadeMetrics, CascadeSource};
+use retry::RetrySchedule;
 use std::convert::TryFrom;
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     convert::TryInto,
 };
 use tracing::*;
@@ -56,7 +59,10 @@ mod network_tests;
 #[cfg(all(test, outdated_tests))]
 mod test;
 
+pub mod breaker;
 pub mod error;
+pub mod metrics;
+pub mod retry;
 
 pub struct Cascade<'a, Network = HolochainP2pCell, MetaVault = MetadataBuf, MetaCache = MetadataBuf>
 where
@@ -72,6 +78,9 @@ where
 
     env: EnvironmentRead,
     network: Network,
+    counters: CascadeCounters,
+    breaker: Option<NetworkBreaker>,
+    retries: Option<RetrySchedule>,
 }
 
 #[derive(Debug)]
@@ -113,9 +122,54 @@ where
             element_cache,
             meta_cache,
             network,
+            counters: CascadeCounters::new(),
+            breaker: None,
+            retries: None,
         }
     }
 
+    /// Short-circuit network retrievals while this breaker is open.
+    /// Retrievals will only return what is held locally.
+    pub fn with_breaker(mut self, breaker: NetworkBreaker) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+
+    /// Schedule retries of failed element retrievals here.
+    /// Until a retry is due, retrievals of that hash only return what is held locally.
+    pub fn with_retry_schedule(mut self, retries: RetrySchedule) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    /// Check the breaker before going to the network
+    fn network_allowed(&self) -> bool {
+        match &self.breaker {
+            Some(breaker) if !breaker.allow_request() => {
+                debug!("Network breaker is open, skipping network");
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Let the breaker know how the network call went
+    fn record_network_outcome<T, E>(&self, result: &Result<T, E>) {
+        record_network_outcome(self.breaker.as_ref(), result)
+    }
+
+    /// Record retrieval hits into a shared set of counters
+    /// so they accumulate across many cascades
+    pub fn with_counters(mut self, counters: CascadeCounters) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    /// A snapshot of where this cascade's retrievals have been satisfied
+    pub fn metrics(&self) -> CascadeMetrics {
+        self.counters.snapshot()
+    }
+
     async fn update_stores(&mut self, element: Element) -> CascadeResult<()> {
         let op_lights = produce_op_lights_from_elements(vec![&element]).await?;
         let (shh, e) = element.into_inner();
@@ -139,12 +193,41 @@ where
         Ok(())
     }
 
+    /// Get from the network unless a retry of this hash isn't due yet.
+    /// A get that doesn't return the data schedules the next retry.
+    /// With peer rotation each attempt asks the next authority on its own.
+    async fn network_get(
+        &mut self,
+        hash: AnyDhtHash,
+        options: GetOptions,
+    ) -> CascadeResult<Vec<GetElementResponse>> {
+        network_get(
+            &mut self.network,
+            self.breaker.as_ref(),
+            self.retries.as_ref(),
+            hash,
+            options,
+        )
+        .await
+    }
+
     async fn fetch_element_via_header(
         &mut self,
         hash: HeaderHash,
         options: GetOptions,
     ) -> CascadeResult<()> {
-        let results = self.network.get(hash.into(), options).await?;
+        if !self.network_allowed() {
+            return Ok(());
+        }
+        let results = self.network_get(hash.into(), options).await?;
+        self.store_header_responses(results).await
+    }
+
+    /// Put the elements authorities returned for a header in the cache
+    async fn store_header_responses(
+        &mut self,
+        results: Vec<GetElementResponse>,
+    ) -> CascadeResult<()> {
         // Search through the returns for the first delete
         for response in results.into_iter() {
             match response {
@@ -176,12 +259,21 @@ where
         hash: EntryHash,
         options: GetOptions,
     ) -> CascadeResult<()> {
+        if !self.network_allowed() {
+            return Ok(());
+        }
         let results = self
-            .network
-            .get(hash.clone().into(), options.clone())
+            .network_get(hash.clone().into(), options.clone())
             .instrument(debug_span!("fetch_element_via_entry::network_get"))
             .await?;
+        self.store_entry_responses(results).await
+    }
 
+    /// Put the elements authorities returned for an entry in the cache
+    async fn store_entry_responses(
+        &mut self,
+        results: Vec<GetElementResponse>,
+    ) -> CascadeResult<()> {
         for response in results {
             match response {
                 GetElementResponse::GetEntryFull(Some(raw)) => {
@@ -263,7 +355,12 @@ where
         options: GetLinksOptions,
     ) -> CascadeResult<()> {
         debug!("in get links");
-        let results = self.network.get_links(link_key, options).await?;
+        if !self.network_allowed() {
+            return Ok(());
+        }
+        let results = self.network.get_links(link_key, options).await;
+        self.record_network_outcome(&results);
+        let results = results?;
         for links in results {
             let GetLinksResponse {
                 link_adds,
@@ -294,19 +391,31 @@ where
     }
 
     fn get_element_local_raw(&self, hash: &HeaderHash) -> CascadeResult<Option<Element>> {
+        Ok(self
+            .get_element_local_raw_with_source(hash)?
+            .map(|(el, _)| el))
+    }
+
+    fn get_element_local_raw_with_source(
+        &self,
+        hash: &HeaderHash,
+    ) -> CascadeResult<Option<(Element, CascadeSource)>> {
         let r = match self.element_vault.get_element(hash)? {
-            None => self.element_cache.get_element(hash)?,
-            r => r,
+            None => self
+                .element_cache
+                .get_element(hash)?
+                .map(|el| (el, CascadeSource::Cache)),
+            r => r.map(|el| (el, CascadeSource::Vault)),
         };
         // Check we have a valid reason to return this element
         match r {
-            Some(el)
+            Some((el, source))
                 if self.valid_element(
                     el.header_address(),
                     el.header().entry_data().map(|(h, _)| h),
                 )? =>
             {
-                Ok(Some(el))
+                Ok(Some((el, source)))
             }
             _ => Ok(None),
         }
@@ -314,6 +423,15 @@ where
 
     /// Gets the first element we can find for this entry locally
     fn get_element_local_raw_via_entry(&self, hash: &EntryHash) -> CascadeResult<Option<Element>> {
+        Ok(self
+            .get_element_local_raw_via_entry_with_source(hash)?
+            .map(|(el, _)| el))
+    }
+
+    fn get_element_local_raw_via_entry_with_source(
+        &self,
+        hash: &EntryHash,
+    ) -> CascadeResult<Option<(Element, CascadeSource)>> {
         // Get all the headers we know about.
         let mut headers: BTreeSet<TimedHeaderHash> =
             fresh_reader!(self.meta_cache.env(), |r| self
@@ -331,8 +449,8 @@ where
         // so iterate in reverse
         for header in headers.into_iter().rev() {
             // Return the first element we are actually holding
-            if let Some(el) = self.get_element_local_raw(&header.header_hash)? {
-                return Ok(Some(el));
+            if let Some(found) = self.get_element_local_raw_with_source(&header.header_hash)? {
+                return Ok(Some(found));
             }
         }
         // Not holding any
@@ -340,13 +458,23 @@ where
     }
 
     fn get_entry_local_raw(&self, hash: &EntryHash) -> CascadeResult<Option<EntryHashed>> {
+        Ok(self.get_entry_local_raw_with_source(hash)?.map(|(e, _)| e))
+    }
+
+    fn get_entry_local_raw_with_source(
+        &self,
+        hash: &EntryHash,
+    ) -> CascadeResult<Option<(EntryHashed, CascadeSource)>> {
         let r = match self.element_vault.get_entry(hash)? {
-            None => self.element_cache.get_entry(hash)?,
-            r => r,
+            None => self
+                .element_cache
+                .get_entry(hash)?
+                .map(|e| (e, CascadeSource::Cache)),
+            r => r.map(|e| (e, CascadeSource::Vault)),
         };
         // Check we have a valid reason to return this element
         match r {
-            Some(e) if self.valid_entry(e.as_hash())? => Ok(Some(e)),
+            Some((e, source)) if self.valid_entry(e.as_hash())? => Ok(Some((e, source))),
             _ => Ok(None),
         }
     }
@@ -361,28 +489,52 @@ where
         &self,
         hash: &HeaderHash,
     ) -> CascadeResult<Option<SignedHeaderHashed>> {
+        Ok(self
+            .get_header_local_raw_with_sig_and_source(hash)?
+            .map(|(h, _)| h))
+    }
+
+    fn get_header_local_raw_with_sig_and_source(
+        &self,
+        hash: &HeaderHash,
+    ) -> CascadeResult<Option<(SignedHeaderHashed, CascadeSource)>> {
         let r = match self.element_vault.get_header(hash)? {
-            None => self.element_cache.get_header(hash)?,
-            r => r,
+            None => self
+                .element_cache
+                .get_header(hash)?
+                .map(|h| (h, CascadeSource::Cache)),
+            r => r.map(|h| (h, CascadeSource::Vault)),
         };
         // Check we have a valid reason to return this element
         match r {
-            Some(h)
+            Some((h, source))
                 if self.valid_element(
                     h.header_address(),
                     h.header().entry_data().map(|(h, _)| h),
                 )? =>
             {
-                Ok(Some(h))
+                Ok(Some((h, source)))
             }
             _ => Ok(None),
         }
     }
 
-    fn render_headers<T, F>(&self, headers: Vec<TimedHeaderHash>, f: F) -> CascadeResult<Vec<T>>
+    /// Record the outcome of a retrieval that had to go to the network
+    fn record_network(&self, found: bool) {
+        self.counters.record(if found {
+            Some(CascadeSource::Network)
+        } else {
+            None
+        });
+    }
+
+    /// Headers are rendered in timestamp then hash order so every
+    /// node and every call returns the same history
+    fn render_headers<T, F>(&self, mut headers: Vec<TimedHeaderHash>, f: F) -> CascadeResult<Vec<T>>
     where
         F: Fn(Header) -> DhtOpConvertResult<T>,
     {
+        headers.sort();
         let mut result = Vec::with_capacity(headers.len());
         for h in headers {
             let hash = h.header_hash;
@@ -395,6 +547,56 @@ where
         Ok(result)
     }
 
+    /// Like [Cascade::render_headers] but keeps the signatures
+    fn render_signed_headers(
+        &self,
+        mut headers: Vec<TimedHeaderHash>,
+    ) -> CascadeResult<Vec<SignedHeaderHashed>> {
+        headers.sort();
+        let mut result = Vec::with_capacity(headers.len());
+        for h in headers {
+            if let Some(h) = self.get_header_local_raw_with_sig(&h.header_hash)? {
+                result.push(h);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Live updates that replace the same header compete with each other.
+    /// The competing heads are returned in timestamp then hash order
+    /// grouped by the header they replace.
+    fn render_conflicts<R: Readable>(
+        &self,
+        r: &R,
+        mut updates: Vec<TimedHeaderHash>,
+    ) -> CascadeResult<Vec<HeaderHash>> {
+        updates.sort();
+        let mut replaces: BTreeMap<HeaderHash, Vec<HeaderHash>> = BTreeMap::new();
+        for update in updates {
+            let hash = update.header_hash;
+            if self
+                .meta_cache
+                .get_deletes_on_header(r, hash.clone())?
+                .next()?
+                .is_some()
+            {
+                continue;
+            }
+            if let Some(h) = self.get_header_local_raw(&hash)? {
+                let update = Update::try_from(HeaderHashed::into_content(h))?;
+                replaces
+                    .entry(update.original_header_address)
+                    .or_default()
+                    .push(hash);
+            }
+        }
+        Ok(replaces
+            .into_iter()
+            .filter(|(_, heads)| heads.len() > 1)
+            .flat_map(|(_, heads)| heads)
+            .collect())
+    }
+
     async fn create_entry_details(&self, hash: EntryHash) -> CascadeResult<Option<EntryDetails>> {
         match self.get_entry_local_raw(&hash)? {
             Some(entry) => fresh_reader!(self.env, |r| {
@@ -403,7 +605,12 @@ where
                     .meta_cache
                     .get_headers(&r, hash.clone())?
                     .collect::<Vec<_>>()?;
-                let headers = self.render_headers(headers, Ok)?;
+                // Both lists come from the same read so they stay in step
+                let signed_headers = self.render_signed_headers(headers)?;
+                let headers = signed_headers
+                    .iter()
+                    .map(|shh| shh.header().clone())
+                    .collect();
                 let deletes = self
                     .meta_cache
                     .get_deletes_on_entry(&r, hash.clone())?
@@ -413,12 +620,15 @@ where
                     .meta_cache
                     .get_updates(&r, hash.into())?
                     .collect::<Vec<_>>()?;
+                let conflicts = self.render_conflicts(&r, updates.clone())?;
                 let updates = self.render_headers(updates, |h| Ok(Update::try_from(h)?))?;
                 Ok(Some(EntryDetails {
                     entry: entry.into_content(),
                     headers,
+                    signed_headers,
                     deletes,
                     updates,
+                    conflicts,
                     entry_dht_status,
                 }))
             }),
@@ -514,8 +724,20 @@ where
         self.fetch_element_via_entry(entry_hash.clone(), options.clone())
             .await?;
 
-        // Meta Cache
-        let oldest_live_element = fresh_reader!(self.env, |r| {
+        // Network
+        match self.oldest_live_element_local(entry_hash)? {
+            Search::Found(element) => Ok(Some(element)),
+            Search::Continue(oldest_live_header) => {
+                self.dht_get_header(oldest_live_header, options).await
+            }
+            Search::NotInCascade => Ok(None),
+        }
+    }
+
+    /// The oldest live element of an entry by the metadata in the cache,
+    /// or the header to get it by if the element isn't held
+    fn oldest_live_element_local(&self, entry_hash: EntryHash) -> CascadeResult<Search> {
+        fresh_reader!(self.env, |r| {
             match self.meta_cache.get_dht_status(&r, &entry_hash)? {
                 EntryDhtStatus::Live => {
                     let oldest_live_header = self
@@ -552,16 +774,7 @@ where
                 | EntryDhtStatus::Withdrawn
                 | EntryDhtStatus::Purged => CascadeResult::Ok(Search::NotInCascade),
             }
-        })?;
-
-        // Network
-        match oldest_live_element {
-            Search::Found(element) => Ok(Some(element)),
-            Search::Continue(oldest_live_header) => {
-                self.dht_get_header(oldest_live_header, options).await
-            }
-            Search::NotInCascade => Ok(None),
-        }
+        })
     }
 
     #[instrument(skip(self, options))]
@@ -590,7 +803,19 @@ where
         options: GetOptions,
     ) -> CascadeResult<Option<Element>> {
         debug!("in get header");
-        let found_local_delete = fresh_reader!(self.env, |r| {
+        if self.header_deleted_locally(&header_hash)? {
+            return Ok(None);
+        }
+        // Network
+        self.fetch_element_via_header(header_hash.clone(), options)
+            .await?;
+
+        self.live_header_local(header_hash)
+    }
+
+    /// Whether a delete of the header is already held in the cache or vault
+    fn header_deleted_locally(&self, header_hash: &HeaderHash) -> CascadeResult<bool> {
+        Ok(fresh_reader!(self.env, |r| {
             let in_cache = || {
                 DatabaseResult::Ok({
                     self.meta_cache
@@ -608,14 +833,11 @@ where
                 })
             };
             DatabaseResult::Ok(in_cache()? || in_vault()?)
-        })?;
-        if found_local_delete {
-            return Ok(None);
-        }
-        // Network
-        self.fetch_element_via_header(header_hash.clone(), options)
-            .await?;
+        })?)
+    }
 
+    /// The header's element from the cache unless the cache holds a delete of it
+    fn live_header_local(&self, header_hash: HeaderHash) -> CascadeResult<Option<Element>> {
         fresh_reader!(self.env, |r| {
             // Check if header is alive after fetch
             let is_live = self
@@ -642,11 +864,16 @@ where
         hash: EntryHash,
         options: GetOptions,
     ) -> CascadeResult<Option<EntryHashed>> {
-        match self.get_entry_local_raw(&hash)? {
-            Some(e) => Ok(Some(e)),
+        match self.get_entry_local_raw_with_source(&hash)? {
+            Some((e, source)) => {
+                self.counters.record(Some(source));
+                Ok(Some(e))
+            }
             None => {
                 self.fetch_element_via_entry(hash.clone(), options).await?;
-                self.get_entry_local_raw(&hash)
+                let r = self.get_entry_local_raw(&hash)?;
+                self.record_network(r.is_some());
+                Ok(r)
             }
         }
     }
@@ -662,11 +889,16 @@ where
         hash: HeaderHash,
         options: GetOptions,
     ) -> CascadeResult<Option<SignedHeaderHashed>> {
-        match self.get_header_local_raw_with_sig(&hash)? {
-            Some(h) => Ok(Some(h)),
+        match self.get_header_local_raw_with_sig_and_source(&hash)? {
+            Some((h, source)) => {
+                self.counters.record(Some(source));
+                Ok(Some(h))
+            }
             None => {
                 self.fetch_element_via_header(hash.clone(), options).await?;
-                self.get_header_local_raw_with_sig(&hash)
+                let r = self.get_header_local_raw_with_sig(&hash)?;
+                self.record_network(r.is_some());
+                Ok(r)
             }
         }
     }
@@ -687,21 +919,31 @@ where
         match *hash.hash_type() {
             AnyDht::Entry => {
                 let hash = hash.into();
-                match self.get_element_local_raw_via_entry(&hash)? {
-                    Some(e) => Ok(Some(e)),
+                match self.get_element_local_raw_via_entry_with_source(&hash)? {
+                    Some((e, source)) => {
+                        self.counters.record(Some(source));
+                        Ok(Some(e))
+                    }
                     None => {
                         self.fetch_element_via_entry(hash.clone(), options).await?;
-                        self.get_element_local_raw_via_entry(&hash)
+                        let r = self.get_element_local_raw_via_entry(&hash)?;
+                        self.record_network(r.is_some());
+                        Ok(r)
                     }
                 }
             }
             AnyDht::Header => {
                 let hash = hash.into();
-                match self.get_element_local_raw(&hash)? {
-                    Some(e) => Ok(Some(e)),
+                match self.get_element_local_raw_with_source(&hash)? {
+                    Some((e, source)) => {
+                        self.counters.record(Some(source));
+                        Ok(Some(e))
+                    }
                     None => {
                         self.fetch_element_via_header(hash.clone(), options).await?;
-                        self.get_element_local_raw(&hash)
+                        let r = self.get_element_local_raw(&hash)?;
+                        self.record_network(r.is_some());
+                        Ok(r)
                     }
                 }
             }
@@ -724,6 +966,90 @@ where
         }
     }
 
+    /// The updates on an entry that haven't been deleted,
+    /// in timestamp then header hash order
+    async fn live_updates(
+        &mut self,
+        entry_hash: EntryHash,
+        options: GetOptions,
+    ) -> CascadeResult<Vec<Element>> {
+        // Update the cache with the updates on this entry
+        self.fetch_element_via_entry(entry_hash.clone(), options.clone())
+            .await?;
+        let updates = fresh_reader!(self.env, |r| self
+            .meta_cache
+            .get_updates(&r, entry_hash.into())?
+            .collect::<BTreeSet<_>>())?;
+        let mut live = Vec::new();
+        for update in updates {
+            if let Some(element) = self
+                .dht_get_header(update.header_hash, options.clone())
+                .await?
+            {
+                live.push(element);
+            }
+        }
+        Ok(live)
+    }
+
+    #[instrument(skip(self, options, resolve))]
+    /// Like [Cascade::dht_get] but if the entry of the element has been updated
+    /// the latest live update is returned instead, following updates of updates.
+    /// When an entry has diverging updates `resolve` is given the competing
+    /// heads, in timestamp then header hash order, and chooses which to follow.
+    /// Entry hashes can go round in a circle of updates so each entry is
+    /// only followed once.
+    /// Returns the element with the branch of update headers followed to
+    /// reach it, oldest first, which is empty if there were no updates.
+    pub async fn dht_get_latest<F, E>(
+        &mut self,
+        hash: AnyDhtHash,
+        options: GetOptions,
+        resolve: F,
+    ) -> Result<Option<(Element, Vec<HeaderHash>)>, E>
+    where
+        F: FnMut(Vec<Element>) -> Result<Element, E>,
+        E: From<CascadeError>,
+    {
+        match self.dht_get(hash, options.clone()).await? {
+            Some(element) => Ok(Some(self.follow_updates(element, options, resolve).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Follow the updates of an element already got, see [Cascade::dht_get_latest]
+    pub async fn follow_updates<F, E>(
+        &mut self,
+        mut element: Element,
+        options: GetOptions,
+        mut resolve: F,
+    ) -> Result<(Element, Vec<HeaderHash>), E>
+    where
+        F: FnMut(Vec<Element>) -> Result<Element, E>,
+        E: From<CascadeError>,
+    {
+        let mut followed = HashSet::new();
+        let mut branch = Vec::new();
+        while let Some(entry_hash) = element.header().entry_hash().cloned() {
+            if !followed.insert(entry_hash.clone()) {
+                break;
+            }
+            let mut live = self.live_updates(entry_hash, options.clone()).await?;
+            let latest = match live.len() {
+                0 => break,
+                1 => live.remove(0),
+                _ => {
+                    let competing: Vec<_> = live.iter().map(|e| e.header_address()).collect();
+                    debug!(?competing, "diverging updates");
+                    resolve(live)?
+                }
+            };
+            branch.push(latest.header_address().clone());
+            element = latest;
+        }
+        Ok((element, branch))
+    }
+
     #[instrument(skip(self))]
     pub async fn get_details(
         &mut self,
@@ -766,6 +1092,46 @@ where
         })
     }
 
+    #[instrument(skip(self, tag, options))]
+    /// Follow the links on a base to their targets and then the links
+    /// on those targets, up to `max_depth` links away from the base.
+    /// Each entry's links are only fetched once so cycles in the link
+    /// graph, including self links, can't make the traversal loop.
+    /// Returns every link found, nearest to the base first.
+    pub async fn traverse_links(
+        &mut self,
+        base: EntryHash,
+        zome_id: ZomeId,
+        tag: Option<LinkTag>,
+        max_depth: usize,
+        options: GetLinksOptions,
+    ) -> CascadeResult<Vec<Link>> {
+        let mut visited = HashSet::new();
+        visited.insert(base.clone());
+        let mut bases = vec![base];
+        let mut found = Vec::new();
+        for _ in 0..max_depth {
+            let mut next_bases = Vec::new();
+            for base in bases {
+                let key = match &tag {
+                    Some(tag) => LinkMetaKey::BaseZomeTag(&base, zome_id, tag),
+                    None => LinkMetaKey::BaseZome(&base, zome_id),
+                };
+                for link in self.dht_get_links(&key, options.clone()).await? {
+                    if visited.insert(link.target.clone()) {
+                        next_bases.push(link.target.clone());
+                    }
+                    found.push(link);
+                }
+            }
+            if next_bases.is_empty() {
+                break;
+            }
+            bases = next_bases;
+        }
+        Ok(found)
+    }
+
     #[instrument(skip(self, key, options))]
     /// Return all CreateLink headers
     /// and DeleteLink headers ordered by time.
@@ -816,6 +1182,256 @@ where
     }
 }
 
+impl<'a, Network, MetaVault, MetaCache> Cascade<'a, Network, MetaVault, MetaCache>
+where
+    MetaCache: MetadataBufT,
+    MetaVault: MetadataBufT,
+    Network: HolochainP2pCellT + Clone,
+{
+    #[instrument(skip(self, hashes))]
+    /// Get the details of many hashes like [Cascade::get_details].
+    /// The network gets for every hash are in flight at once,
+    /// then the details are read from the stores in the order asked for.
+    pub async fn get_details_many(
+        &mut self,
+        hashes: Vec<(AnyDhtHash, GetOptions)>,
+    ) -> CascadeResult<Vec<Option<Details>>> {
+        let hashes: Vec<_> = hashes
+            .into_iter()
+            .map(|(hash, mut options)| {
+                options.all_live_headers_with_metadata = true;
+                (hash, options)
+            })
+            .collect();
+
+        // Update the cache from the network
+        if self.network_allowed() {
+            let breaker = self.breaker.as_ref();
+            let retries = self.retries.as_ref();
+            let network = &self.network;
+            let responses =
+                futures::future::join_all(hashes.iter().cloned().map(|(hash, options)| {
+                    let mut network = network.clone();
+                    async move { network_get(&mut network, breaker, retries, hash, options).await }
+                }))
+                .await;
+            for ((hash, _), results) in hashes.iter().zip(responses) {
+                match *hash.hash_type() {
+                    AnyDht::Entry => self.store_entry_responses(results?).await?,
+                    AnyDht::Header => self.store_header_responses(results?).await?,
+                }
+            }
+        }
+
+        // Get the details and the metadata
+        let mut found = Vec::with_capacity(hashes.len());
+        for (hash, _) in hashes {
+            let details = match *hash.hash_type() {
+                AnyDht::Entry => self
+                    .create_entry_details(hash.into())
+                    .await?
+                    .map(Details::Entry),
+                AnyDht::Header => self
+                    .create_element_details(hash.into())?
+                    .map(Details::Element),
+            };
+            found.push(details);
+        }
+        Ok(found)
+    }
+
+    #[instrument(skip(self, hashes, options))]
+    /// Retrieve many elements like [Cascade::retrieve].
+    /// The network gets for every hash that isn't held locally are in flight at once.
+    /// Only the elements that were found are returned.
+    pub async fn retrieve_many(
+        &mut self,
+        hashes: Vec<AnyDhtHash>,
+        options: GetOptions,
+    ) -> CascadeResult<HashMap<AnyDhtHash, Element>> {
+        let mut found = HashMap::with_capacity(hashes.len());
+        let mut missing = Vec::new();
+        for hash in hashes {
+            let local = match *hash.hash_type() {
+                AnyDht::Entry => {
+                    self.get_element_local_raw_via_entry_with_source(&hash.clone().into())?
+                }
+                AnyDht::Header => self.get_element_local_raw_with_source(&hash.clone().into())?,
+            };
+            match local {
+                Some((el, source)) => {
+                    self.counters.record(Some(source));
+                    found.insert(hash, el);
+                }
+                None => missing.push(hash),
+            }
+        }
+        if missing.is_empty() {
+            return Ok(found);
+        }
+
+        // Update the cache from the network
+        self.fetch_many(missing.clone(), options).await?;
+
+        for hash in missing {
+            let el = match *hash.hash_type() {
+                AnyDht::Entry => self.get_element_local_raw_via_entry(&hash.clone().into())?,
+                AnyDht::Header => self.get_element_local_raw(&hash.clone().into())?,
+            };
+            self.record_network(el.is_some());
+            if let Some(el) = el {
+                found.insert(hash, el);
+            }
+        }
+        Ok(found)
+    }
+
+    #[instrument(skip(self, hashes, options))]
+    /// Get many elements like [Cascade::dht_get], with the network gets
+    /// for every hash in flight at once.
+    /// Only the live elements that were found are returned.
+    pub async fn dht_get_many(
+        &mut self,
+        hashes: Vec<AnyDhtHash>,
+        options: GetOptions,
+    ) -> CascadeResult<HashMap<AnyDhtHash, Element>> {
+        let mut found = HashMap::with_capacity(hashes.len());
+        // A header with a delete already held is not live so isn't asked for
+        let mut to_fetch = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let deleted = match *hash.hash_type() {
+                AnyDht::Entry => false,
+                AnyDht::Header => self.header_deleted_locally(&hash.clone().into())?,
+            };
+            if !deleted {
+                to_fetch.push(hash);
+            }
+        }
+        self.fetch_many(to_fetch.clone(), options.clone()).await?;
+
+        // Entries whose oldest live element didn't come back with the entry
+        let mut oldest_live_headers = Vec::new();
+        for hash in to_fetch {
+            match *hash.hash_type() {
+                AnyDht::Entry => match self.oldest_live_element_local(hash.clone().into())? {
+                    Search::Found(element) => {
+                        found.insert(hash, element);
+                    }
+                    Search::Continue(header_hash) => {
+                        if !self.header_deleted_locally(&header_hash)? {
+                            oldest_live_headers.push((hash, header_hash));
+                        }
+                    }
+                    Search::NotInCascade => (),
+                },
+                AnyDht::Header => {
+                    if let Some(element) = self.live_header_local(hash.clone().into())? {
+                        found.insert(hash, element);
+                    }
+                }
+            }
+        }
+        let header_hashes = oldest_live_headers
+            .iter()
+            .map(|(_, header_hash)| header_hash.clone().into())
+            .collect();
+        self.fetch_many(header_hashes, options).await?;
+        for (hash, header_hash) in oldest_live_headers {
+            if let Some(element) = self.live_header_local(header_hash)? {
+                found.insert(hash, element);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Update the cache from the network for many hashes,
+    /// with every get in flight at once
+    async fn fetch_many(
+        &mut self,
+        hashes: Vec<AnyDhtHash>,
+        options: GetOptions,
+    ) -> CascadeResult<()> {
+        if hashes.is_empty() || !self.network_allowed() {
+            return Ok(());
+        }
+        let breaker = self.breaker.as_ref();
+        let retries = self.retries.as_ref();
+        let network = &self.network;
+        let responses = futures::future::join_all(hashes.iter().cloned().map(|hash| {
+            let mut network = network.clone();
+            let options = options.clone();
+            async move { network_get(&mut network, breaker, retries, hash, options).await }
+        }))
+        .await;
+        for (hash, results) in hashes.iter().zip(responses) {
+            match *hash.hash_type() {
+                AnyDht::Entry => self.store_entry_responses(results?).await?,
+                AnyDht::Header => self.store_header_responses(results?).await?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Get from the network on a handle of its own, so gets for many
+/// hashes can be in flight at once. See [Cascade::network_get].
+async fn network_get<Network: HolochainP2pCellT>(
+    network: &mut Network,
+    breaker: Option<&NetworkBreaker>,
+    retries: Option<&RetrySchedule>,
+    hash: AnyDhtHash,
+    mut options: GetOptions,
+) -> CascadeResult<Vec<GetElementResponse>> {
+    let retries = match retries {
+        Some(retries) => retries,
+        None => {
+            let results = network.get(hash, options).await;
+            record_network_outcome(breaker, &results);
+            return Ok(results?);
+        }
+    };
+    let attempt = match retries.due(&hash) {
+        Some(attempt) => attempt,
+        None => {
+            debug!(msg = "Retry not due yet, skipping network", ?hash);
+            return Ok(Vec::new());
+        }
+    };
+    if retries.policy().peer_rotation {
+        let authorities = network.get_authorities(hash.clone()).await?;
+        if !authorities.is_empty() {
+            options.target_agent = Some(authorities[attempt as usize % authorities.len()].clone());
+        }
+    }
+    let results = network.get(hash.clone(), options).await;
+    record_network_outcome(breaker, &results);
+    let found = match &results {
+        Ok(responses) => responses.iter().any(|response| {
+            matches!(
+                response,
+                GetElementResponse::GetHeader(Some(_)) | GetElementResponse::GetEntryFull(Some(_))
+            )
+        }),
+        Err(e) => {
+            debug!(msg = "Network get failed", ?hash, ?e, attempt);
+            false
+        }
+    };
+    if found {
+        retries.succeeded(&hash);
+    } else {
+        retries.failed(hash);
+    }
+    Ok(results?)
+}
+
+/// Let the breaker know how the network call went
+fn record_network_outcome<T, E>(breaker: Option<&NetworkBreaker>, result: &Result<T, E>) {
+    if let Some(breaker) = breaker {
+        breaker.record(result.is_ok());
+    }
+}
+
 #[cfg(test)]
 /// Helper function for easily setting up cascades during tests
 pub fn test_dbs_and_mocks(
@@ -0,0 +1,162 @@
+//! A circuit breaker for network retrievals.
+//!
+//! When the network is unreachable every cascade retrieve times out.
+//! After [NetworkBreaker] sees enough consecutive failures it opens
+//! and the cascade stops going to the network, returning only what is
+//! held locally. After a cool down period it half-opens and lets a single
+//! probe through. If the probe succeeds the breaker closes again,
+//! otherwise it goes back to open.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Consecutive network failures before the breaker opens
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+/// How long the breaker stays open before probing the network again
+pub const DEFAULT_COOL_DOWN: Duration = Duration::from_secs(30);
+
+/// The state of a [NetworkBreaker]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Network requests are allowed
+    Closed,
+    /// Network requests are short-circuited
+    Open,
+    /// A single probe request is allowed through to test recovery
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+/// A conductor wide circuit breaker for network retrievals.
+/// Clones share the same state.
+#[derive(Debug, Clone)]
+pub struct NetworkBreaker {
+    inner: Arc<Mutex<Inner>>,
+    failure_threshold: u32,
+    cool_down: Duration,
+}
+
+impl Default for NetworkBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOL_DOWN)
+    }
+}
+
+impl NetworkBreaker {
+    /// Create a breaker that opens after `failure_threshold` consecutive
+    /// failures and probes again after `cool_down`
+    pub fn new(failure_threshold: u32, cool_down: Duration) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                probing: false,
+            })),
+            failure_threshold: failure_threshold.max(1),
+            cool_down,
+        }
+    }
+
+    /// The current state of the breaker
+    pub fn state(&self) -> BreakerState {
+        let mut inner = self.inner.lock().expect("NetworkBreaker lock poisoned");
+        self.check_cool_down(&mut inner);
+        inner.state
+    }
+
+    /// Check if a network request should be made.
+    /// In the half-open state only one probe is allowed
+    /// until its outcome is recorded.
+    pub fn allow_request(&self) -> bool {
+        let mut inner = self.inner.lock().expect("NetworkBreaker lock poisoned");
+        self.check_cool_down(&mut inner);
+        match inner.state {
+            BreakerState::Closed => true,
+            BreakerState::Open => false,
+            BreakerState::HalfOpen if inner.probing => false,
+            BreakerState::HalfOpen => {
+                inner.probing = true;
+                true
+            }
+        }
+    }
+
+    /// Record the outcome of a network request
+    pub fn record(&self, success: bool) {
+        let mut inner = self.inner.lock().expect("NetworkBreaker lock poisoned");
+        inner.probing = false;
+        if success {
+            inner.state = BreakerState::Closed;
+            inner.consecutive_failures = 0;
+            inner.opened_at = None;
+            return;
+        }
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        let should_open = match inner.state {
+            BreakerState::Closed => inner.consecutive_failures >= self.failure_threshold,
+            // The probe failed so go straight back to open
+            BreakerState::HalfOpen | BreakerState::Open => true,
+        };
+        if should_open {
+            tracing::warn!(
+                msg = "Network breaker open",
+                consecutive_failures = inner.consecutive_failures
+            );
+            inner.state = BreakerState::Open;
+            inner.opened_at = Some(Instant::now());
+        }
+    }
+
+    /// Move from open to half-open once the cool down has passed
+    fn check_cool_down(&self, inner: &mut Inner) {
+        if let (BreakerState::Open, Some(opened_at)) = (inner.state, inner.opened_at) {
+            if opened_at.elapsed() >= self.cool_down {
+                inner.state = BreakerState::HalfOpen;
+                inner.probing = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_threshold_and_half_opens_after_cool_down() {
+        let breaker = NetworkBreaker::new(2, Duration::from_millis(10));
+        assert!(breaker.allow_request());
+        breaker.record(false);
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        breaker.record(false);
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(!breaker.allow_request());
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        // Only a single probe is let through
+        assert!(breaker.allow_request());
+        assert!(!breaker.allow_request());
+
+        // Failed probe goes back to open
+        breaker.record(false);
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(breaker.allow_request());
+        // Successful probe closes the breaker
+        breaker.record(true);
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert!(breaker.allow_request());
+    }
+}
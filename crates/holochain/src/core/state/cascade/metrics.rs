@@ -0,0 +1,76 @@
+//! Counters for where cascade retrievals are satisfied.
+//!
+//! Cascades are short lived so the counters are reference counted.
+//! Passing the same [CascadeCounters] to each new cascade accumulates
+//! hits across all of them.
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Where a retrieval from the cascade was satisfied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CascadeSource {
+    /// Found in the vault (the authored source chain or integrated data)
+    Vault,
+    /// Found in the local cache of previously fetched data
+    Cache,
+    /// Had to be fetched from the network
+    Network,
+}
+
+/// A snapshot of the cascade hit counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CascadeMetrics {
+    /// Retrievals served from the vault
+    pub vault: u64,
+    /// Retrievals served from the cache
+    pub cache: u64,
+    /// Retrievals served by fetching from the network
+    pub network: u64,
+    /// Retrievals that could not be satisfied from any source
+    pub miss: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    vault: AtomicU64,
+    cache: AtomicU64,
+    network: AtomicU64,
+    miss: AtomicU64,
+}
+
+/// Shared hit counters for the cascade.
+/// Clones share the same underlying counters.
+#[derive(Debug, Clone, Default)]
+pub struct CascadeCounters(Arc<Counters>);
+
+impl CascadeCounters {
+    /// Create a fresh set of counters starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the outcome of a retrieval.
+    /// `None` means the data was not found anywhere.
+    pub fn record(&self, source: Option<CascadeSource>) {
+        let counter = match source {
+            Some(CascadeSource::Vault) => &self.0.vault,
+            Some(CascadeSource::Cache) => &self.0.cache,
+            Some(CascadeSource::Network) => &self.0.network,
+            None => &self.0.miss,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Take a snapshot of the current counts
+    pub fn snapshot(&self) -> CascadeMetrics {
+        CascadeMetrics {
+            vault: self.0.vault.load(Ordering::Relaxed),
+            cache: self.0.cache.load(Ordering::Relaxed),
+            network: self.0.network.load(Ordering::Relaxed),
+            miss: self.0.miss.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -1,11 +1,20 @@
 use crate::{
-    conductor::{dna_store::MockDnaStore, interface::websocket::test::setup_app},
+    conductor::{
+        config::GossipRequestPolicy, dna_store::MockDnaStore, interface::websocket::test::setup_app,
+    },
     core::{
         state::{
+            cascade::{
+                breaker::{BreakerState, NetworkBreaker},
+                retry::RetrySchedule,
+            },
             element_buf::ElementBuf,
             metadata::{MetadataBuf, MetadataBufT},
         },
-        workflow::{integrate_dht_ops_workflow::integrate_to_cache, CallZomeWorkspace},
+        workflow::{
+            integrate_dht_ops_workflow::integrate_to_cache,
+            sys_validation_workflow::SysValidationWorkspace, CallZomeWorkspace,
+        },
     },
     test_utils::test_network,
 };
@@ -15,14 +24,14 @@ use futures::future::{Either, FutureExt};
 use ghost_actor::GhostControlSender;
 use hdk3::prelude::EntryVisibility;
 use holo_hash::{
+    fixt::{AgentPubKeyFixturator, DnaHashFixturator},
     hash_type::{self, AnyDht},
-    AnyDhtHash, EntryHash, HasHash, HeaderHash,
+    AgentPubKey, AnyDhtHash, EntryHash, HasHash, HeaderHash,
 };
 use holochain_p2p::{
     actor::{GetLinksOptions, GetMetaOptions, GetOptions},
-    HolochainP2pCell, HolochainP2pRef,
+    HolochainP2pCell, HolochainP2pError, HolochainP2pRef, MockHolochainP2pCellT,
 };
-use holochain_serialized_bytes::SerializedBytes;
 use holochain_state::{
     env::{EnvironmentWrite, ReadManager},
     prelude::{BufferedStore, WriteManager},
@@ -163,17 +172,444 @@ async fn get_meta_updates_meta_cache() {
     shutdown.clean().await;
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn retrieve_records_hit_source() {
+    observability::test_run().ok();
+    // Database setup
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    let (element_fixt_store, _) = generate_fixt_store().await;
+    let (header_hash, element) = element_fixt_store.into_iter().next().unwrap();
+
+    // The network should only be hit once
+    let mut network = MockHolochainP2pCellT::new();
+    network.expect_get().times(1).returning(move |_, _| {
+        Ok(vec![GetElementResponse::GetHeader(Some(Box::new(
+            WireElement::from_element(element.clone(), None),
+        )))])
+    });
+
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    let mut cascade = workspace.cascade(network);
+
+    // Not held locally so this is served by the network
+    cascade
+        .retrieve_header(header_hash.clone(), Default::default())
+        .await
+        .unwrap()
+        .expect("Network should return the header");
+    let metrics = cascade.metrics();
+    assert_eq!(metrics.network, 1);
+    assert_eq!(metrics.cache, 0);
+
+    // Now it's in the cache so this is served locally
+    cascade
+        .retrieve_header(header_hash, Default::default())
+        .await
+        .unwrap()
+        .expect("Cache should return the header");
+    let metrics = cascade.metrics();
+    assert_eq!(metrics.network, 1);
+    assert_eq!(metrics.cache, 1);
+    assert_eq!(metrics.miss, 0);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn cached_check_level_reuses_retrieved_dependencies() {
+    use crate::core::{
+        sys_validate::check_holding_header_all,
+        workflow::sys_validation_workflow::types::{CheckLevel, Dependency},
+    };
+    observability::test_run().ok();
+    // Database setup
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    let (element_fixt_store, _) = generate_fixt_store().await;
+    let (header_hash, element) = element_fixt_store.into_iter().next().unwrap();
+    let network = |times| {
+        let element = element.clone();
+        let mut network = MockHolochainP2pCellT::new();
+        network.expect_get().times(times).returning(move |_, _| {
+            Ok(vec![GetElementResponse::GetHeader(Some(Box::new(
+                WireElement::from_element(element.clone(), None),
+            )))])
+        });
+        network
+    };
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    // Empty the cascade's own cache so only the dependency cache can answer
+    let clear_cascade_cache = |workspace: &mut SysValidationWorkspace| {
+        workspace.element_cache = ElementBuf::cache(env.clone().into()).unwrap();
+        workspace.meta_cache = MetadataBuf::cache(env.clone().into()).unwrap();
+    };
+
+    // The first check retrieves the header
+    let dep =
+        check_holding_header_all(&header_hash, &mut workspace, network(1), CheckLevel::Cached)
+            .await
+            .unwrap();
+    assert!(matches!(dep, Dependency::Claim(_)));
+    assert_eq!(workspace.dep_cache.len(), 1);
+
+    // Later checks reuse it
+    for _ in 0..3 {
+        clear_cascade_cache(&mut workspace);
+        let dep =
+            check_holding_header_all(&header_hash, &mut workspace, network(0), CheckLevel::Cached)
+                .await
+                .unwrap();
+        assert_eq!(dep.into_inner().as_hash(), &header_hash);
+    }
+
+    // A claim always retrieves
+    clear_cascade_cache(&mut workspace);
+    check_holding_header_all(&header_hash, &mut workspace, network(1), CheckLevel::Claim)
+        .await
+        .unwrap();
+
+    // Once expired it's retrieved again
+    let mut workspace = SysValidationWorkspace::with_dep_cache(
+        env.clone().into(),
+        1,
+        std::time::Duration::from_millis(10),
+    )
+    .unwrap();
+    check_holding_header_all(&header_hash, &mut workspace, network(1), CheckLevel::Cached)
+        .await
+        .unwrap();
+    tokio::time::delay_for(std::time::Duration::from_millis(20)).await;
+    clear_cascade_cache(&mut workspace);
+    check_holding_header_all(&header_hash, &mut workspace, network(1), CheckLevel::Cached)
+        .await
+        .unwrap();
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn retrieve_many_has_every_get_in_flight_at_once() {
+    use crate::core::{
+        sys_validate::{check_deps_exist, SysValidationError, ValidationOutcome},
+        workflow::sys_validation_workflow::types::Dependency,
+    };
+    use holochain_p2p::event::HolochainP2pEvent;
+    use matches::assert_matches;
+    use std::sync::Arc;
+
+    observability::test_run().ok();
+    // Database setup
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    let mut elements = BTreeMap::new();
+    for _ in 0..3 {
+        elements.extend(generate_fixt_store().await.0);
+    }
+    let missing: AnyDhtHash = fixt!(HeaderHash).into();
+    let mut hashes: Vec<AnyDhtHash> = elements.keys().cloned().map(Into::into).collect();
+    hashes.push(missing.clone());
+
+    // Authorities only answer once every get has arrived,
+    // so gets sent one after another never complete
+    let (_network, mut recv, cell_network) = test_network(None, None).await;
+    let all_arrived = Arc::new(tokio::sync::Barrier::new(hashes.len()));
+    tokio::task::spawn({
+        let elements = elements.clone();
+        async move {
+            use tokio::stream::StreamExt;
+            while let Some(evt) = recv.next().await {
+                if let HolochainP2pEvent::Get {
+                    dht_hash, respond, ..
+                } = evt
+                {
+                    let all_arrived = all_arrived.clone();
+                    let element = elements.get(&HeaderHash::from(dht_hash)).cloned();
+                    tokio::task::spawn(async move {
+                        all_arrived.wait().await;
+                        let response = GetElementResponse::GetHeader(
+                            element.map(|el| Box::new(WireElement::from_element(el, None))),
+                        )
+                        .try_into()
+                        .unwrap();
+                        respond.respond(Ok(async move { Ok(response) }.boxed().into()));
+                    });
+                }
+            }
+        }
+    });
+
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    let found = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        workspace
+            .cascade(cell_network.clone())
+            .retrieve_many(hashes.clone(), Default::default()),
+    )
+    .await
+    .expect("gets were not sent at once")
+    .unwrap();
+    assert_eq!(found.len(), elements.len());
+    for (header_hash, element) in &elements {
+        let retrieved = &found[&AnyDhtHash::from(header_hash.clone())];
+        assert_eq!(retrieved.header_address(), header_hash);
+        assert_eq!(retrieved.entry(), element.entry());
+    }
+
+    // Getting the live elements sends every get at once too
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    let live = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        workspace
+            .cascade(cell_network.clone())
+            .dht_get_many(hashes.clone(), Default::default()),
+    )
+    .await
+    .expect("gets were not sent at once")
+    .unwrap();
+    assert_eq!(live.len(), elements.len());
+    assert!(!live.contains_key(&missing));
+    for header_hash in elements.keys() {
+        let got = &live[&AnyDhtHash::from(header_hash.clone())];
+        assert_eq!(got.header_address(), header_hash);
+    }
+
+    // Checking dependencies uses the same batch path.
+    // Every dependency must be found.
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    let err = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        check_deps_exist(hashes.clone(), &mut workspace, cell_network.clone()),
+    )
+    .await
+    .expect("gets were not sent at once")
+    .unwrap_err();
+    assert_matches!(
+        err,
+        SysValidationError::ValidationOutcome(ValidationOutcome::DepMissingFromDht(hash))
+            if hash == missing
+    );
+
+    // Found dependencies come back in the order asked for
+    let mut in_order = hashes[..elements.len()].to_vec();
+    in_order.reverse();
+    in_order.push(in_order[0].clone());
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    let deps = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        check_deps_exist(in_order.clone(), &mut workspace, cell_network),
+    )
+    .await
+    .expect("gets were not sent at once")
+    .unwrap();
+    let dep_hashes: Vec<AnyDhtHash> = deps
+        .into_iter()
+        .map(|dep| match dep {
+            Dependency::Claim(el) => el.header_address().clone().into(),
+            _ => panic!("dependencies retrieved from the network are claims"),
+        })
+        .collect();
+    assert_eq!(dep_hashes, in_order);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn open_breaker_skips_network() {
+    observability::test_run().ok();
+    // Database setup
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    let (element_fixt_store, _) = generate_fixt_store().await;
+    let (header_hash, _) = element_fixt_store.into_iter().next().unwrap();
+
+    // The network is down so every get fails.
+    // Only the gets before the breaker opens should reach the network.
+    let mut network = MockHolochainP2pCellT::new();
+    network
+        .expect_get()
+        .times(2)
+        .returning(|_, _| Err(HolochainP2pError::other("Network is down")));
+
+    let breaker = NetworkBreaker::new(2, std::time::Duration::from_secs(60));
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    workspace.network_breaker = Some(breaker.clone());
+    let mut cascade = workspace.cascade(network);
+
+    for _ in 0..2 {
+        assert!(cascade
+            .retrieve_header(header_hash.clone(), Default::default())
+            .await
+            .is_err());
+    }
+    assert_eq!(breaker.state(), BreakerState::Open);
+
+    // While open only local results are returned
+    for _ in 0..5 {
+        let r = cascade
+            .retrieve_header(header_hash.clone(), Default::default())
+            .await
+            .unwrap();
+        assert!(r.is_none());
+    }
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn retries_rotate_through_the_authorities_without_waiting() {
+    observability::test_run().ok();
+    // Database setup
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    let (element_fixt_store, _) = generate_fixt_store().await;
+    let (header_hash, element) = element_fixt_store.into_iter().next().unwrap();
+
+    // Two other agents on the network hold the full arc,
+    // so both are authorities for the header
+    let dna = fixt!(DnaHash);
+    let me = fake_agent_pubkey_1();
+    let (network, mut recv, cell_network) = test_network(Some(dna.clone()), Some(me.clone())).await;
+    network
+        .join(dna.clone(), fake_agent_pubkey_2())
+        .await
+        .unwrap();
+    network.join(dna.clone(), fixt!(AgentPubKey)).await.unwrap();
+
+    // The first authority asked doesn't have the header and the next one does
+    let asked = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let handle = tokio::task::spawn({
+        let asked = asked.clone();
+        async move {
+            use tokio::stream::StreamExt;
+            while let Some(evt) = recv.next().await {
+                if let holochain_p2p::event::HolochainP2pEvent::Get {
+                    to_agent, respond, ..
+                } = evt
+                {
+                    let mut asked = asked.lock().unwrap();
+                    let response = if asked.is_empty() {
+                        GetElementResponse::GetHeader(None)
+                    } else {
+                        GetElementResponse::GetHeader(Some(Box::new(WireElement::from_element(
+                            element.clone(),
+                            None,
+                        ))))
+                    };
+                    asked.push(to_agent);
+                    respond.respond(Ok(async move { Ok(response) }.boxed().into()));
+                }
+            }
+        }
+    });
+
+    let retries = RetrySchedule::new(GossipRequestPolicy {
+        max_retries: 2,
+        backoff_ms: 500,
+        peer_rotation: true,
+    });
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    workspace.retry_schedule = Some(retries.clone());
+    let mut cascade = workspace.cascade(cell_network);
+
+    // The miss schedules a retry instead of waiting for it
+    assert!(cascade
+        .retrieve_header(header_hash.clone(), Default::default())
+        .await
+        .unwrap()
+        .is_none());
+    assert!(retries.next_due().is_some());
+    // Until the retry is due the network isn't asked again
+    assert!(cascade
+        .retrieve_header(header_hash.clone(), Default::default())
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(asked.lock().unwrap().len(), 1);
+
+    tokio::time::delay_until(tokio::time::Instant::from_std(retries.next_due().unwrap())).await;
+    cascade
+        .retrieve_header(header_hash, Default::default())
+        .await
+        .unwrap()
+        .expect("The next authority should return the header");
+    assert_eq!(retries.next_due(), None);
+
+    // Each attempt asked a different authority and never itself
+    let asked = asked.lock().unwrap().clone();
+    assert_eq!(asked.len(), 2);
+    assert_ne!(asked[0], asked[1]);
+    assert!(!asked.contains(&me));
+
+    network.ghost_actor_shutdown().await.ok();
+    tokio::time::timeout(std::time::Duration::from_secs(2), handle)
+        .await
+        .ok();
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn traverse_links_terminates_on_cycles() {
+    observability::test_run().ok();
+    // Database setup
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    // a -> b -> c -> a, and a links to itself
+    let zome_id = fixt!(ZomeId);
+    let tag = fixt!(LinkTag);
+    let (a, b, c) = (fixt!(EntryHash), fixt!(EntryHash), fixt!(EntryHash));
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    for (base, target) in vec![(&a, &b), (&b, &c), (&c, &a), (&a, &a)] {
+        let mut link_add = fixt!(CreateLink);
+        link_add.base_address = base.clone();
+        link_add.target_address = target.clone();
+        link_add.zome_id = zome_id;
+        link_add.tag = tag.clone();
+        workspace.meta_cache.add_link(link_add).unwrap();
+    }
+
+    // Everything is already cached so the network has nothing to add
+    let mut network = MockHolochainP2pCellT::new();
+    network.expect_get_links().returning(|_, _| Ok(vec![]));
+    let mut cascade = workspace.cascade(network);
+
+    // Each link is found once even though the links go round forever
+    let links = cascade
+        .traverse_links(
+            a.clone(),
+            zome_id,
+            Some(tag.clone()),
+            100,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    let mut targets: Vec<_> = links.into_iter().map(|l| l.target).collect();
+    targets.sort();
+    let mut expected = vec![a.clone(), a.clone(), b.clone(), c.clone()];
+    expected.sort();
+    assert_eq!(targets, expected);
+
+    // The depth bound stops the traversal early
+    let links = cascade
+        .traverse_links(a.clone(), zome_id, None, 1, Default::default())
+        .await
+        .unwrap();
+    let mut targets: Vec<_> = links.into_iter().map(|l| l.target).collect();
+    targets.sort();
+    let mut expected = vec![a, b];
+    expected.sort();
+    assert_eq!(targets, expected);
+}
+
 #[tokio::test(threaded_scheduler)]
 #[ignore]
 async fn get_from_another_agent() {
     observability::test_run().ok();
     let dna_file = DnaFile::new(
-        DnaDef {
-            name: "dht_get_test".to_string(),
-            uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
-            properties: SerializedBytes::try_from(()).unwrap(),
-            zomes: vec![TestWasm::Create.into()].into(),
-        },
+        DnaDef::builder()
+            .name("dht_get_test")
+            .uuid("ba1d046d-ce29-4778-914b-47e6010d2faf")
+            .add_zome(TestWasm::Create)
+            .build()
+            .unwrap(),
         vec![TestWasm::Create.into()],
     )
     .await
@@ -211,6 +647,7 @@ async fn get_from_another_agent() {
         timeout_ms: None,
         as_race: false,
         race_timeout_ms: None,
+        target_agent: None,
         follow_redirects: false,
         all_live_headers_with_metadata: false,
     };
@@ -343,12 +780,12 @@ async fn get_from_another_agent() {
 async fn get_links_from_another_agent() {
     observability::test_run().ok();
     let dna_file = DnaFile::new(
-        DnaDef {
-            name: "dht_get_test".to_string(),
-            uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
-            properties: SerializedBytes::try_from(()).unwrap(),
-            zomes: vec![TestWasm::Create.into()].into(),
-        },
+        DnaDef::builder()
+            .name("dht_get_test")
+            .uuid("ba1d046d-ce29-4778-914b-47e6010d2faf")
+            .add_zome(TestWasm::Create)
+            .build()
+            .unwrap(),
         vec![TestWasm::Create.into()],
     )
     .await
@@ -0,0 +1,146 @@
+//! When to ask the network again for data it didn't return.
+//!
+//! A failed get isn't retried in place, because waiting out the backoff
+//! there would hold up everything else the caller has queued.
+//! Instead [RetrySchedule] remembers when each hash may be asked for again,
+//! following a [GossipRequestPolicy], and gets for that hash skip the network
+//! until then. The caller comes back for the hash once [RetrySchedule::next_due]
+//! has passed.
+
+use crate::conductor::config::GossipRequestPolicy;
+use holo_hash::AnyDhtHash;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+#[derive(Debug)]
+struct Retry {
+    /// The failed attempts so far
+    failures: u32,
+    /// The hash isn't asked for again before this
+    not_before: Instant,
+}
+
+/// The retries scheduled for the gets that failed.
+/// Clones share the same schedule.
+#[derive(Debug, Clone)]
+pub struct RetrySchedule {
+    retries: Arc<Mutex<HashMap<AnyDhtHash, Retry>>>,
+    policy: GossipRequestPolicy,
+}
+
+impl RetrySchedule {
+    /// Schedule retries following this policy
+    pub fn new(policy: GossipRequestPolicy) -> Self {
+        Self {
+            retries: Default::default(),
+            policy,
+        }
+    }
+
+    /// The policy retries follow
+    pub fn policy(&self) -> &GossipRequestPolicy {
+        &self.policy
+    }
+
+    /// Which attempt a get for this hash would be, counting from 0,
+    /// or `None` if it is still backing off from the last failure
+    pub fn due(&self, hash: &AnyDhtHash) -> Option<u32> {
+        let retries = self.retries.lock().expect("RetrySchedule lock poisoned");
+        match retries.get(hash) {
+            None => Some(0),
+            Some(retry) if retry.not_before <= Instant::now() => Some(retry.failures),
+            Some(_) => None,
+        }
+    }
+
+    /// The data was found so there is nothing left to retry
+    pub fn succeeded(&self, hash: &AnyDhtHash) {
+        self.retries
+            .lock()
+            .expect("RetrySchedule lock poisoned")
+            .remove(hash);
+    }
+
+    /// Schedule the next retry after a failed attempt.
+    /// Once the retries run out the hash is dropped from the schedule,
+    /// so it is only asked for again when the caller next needs it.
+    pub fn failed(&self, hash: AnyDhtHash) {
+        let mut retries = self.retries.lock().expect("RetrySchedule lock poisoned");
+        let failures = retries.get(&hash).map(|r| r.failures).unwrap_or(0) + 1;
+        if failures > self.policy.max_retries {
+            retries.remove(&hash);
+            return;
+        }
+        retries.insert(
+            hash,
+            Retry {
+                failures,
+                not_before: Instant::now() + self.policy.backoff(failures),
+            },
+        );
+    }
+
+    /// When the earliest scheduled retry is due
+    pub fn next_due(&self) -> Option<Instant> {
+        self.retries
+            .lock()
+            .expect("RetrySchedule lock poisoned")
+            .values()
+            .map(|r| r.not_before)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::HeaderHashFixturator;
+    use std::time::Duration;
+
+    #[test]
+    fn backs_off_until_the_retries_run_out() {
+        let schedule = RetrySchedule::new(GossipRequestPolicy {
+            max_retries: 2,
+            backoff_ms: 60_000,
+            peer_rotation: false,
+        });
+        let hash: AnyDhtHash = fixt!(HeaderHash).into();
+
+        assert_eq!(schedule.due(&hash), Some(0));
+        schedule.failed(hash.clone());
+        // Backing off from the first failure
+        assert_eq!(schedule.due(&hash), None);
+        let due = schedule.next_due().unwrap();
+        assert!(due > Instant::now() + Duration::from_secs(59));
+
+        schedule.failed(hash.clone());
+        assert_eq!(schedule.due(&hash), None);
+        // The backoff doubles
+        assert!(schedule.next_due().unwrap() > due + Duration::from_secs(59));
+
+        // Out of retries
+        schedule.failed(hash.clone());
+        assert_eq!(schedule.due(&hash), Some(0));
+        assert_eq!(schedule.next_due(), None);
+    }
+
+    #[test]
+    fn success_clears_the_retry() {
+        let schedule = RetrySchedule::new(GossipRequestPolicy {
+            max_retries: 2,
+            backoff_ms: 0,
+            peer_rotation: false,
+        });
+        let hash: AnyDhtHash = fixt!(HeaderHash).into();
+
+        schedule.failed(hash.clone());
+        assert_eq!(schedule.due(&hash), Some(1));
+        schedule.succeeded(&hash);
+        assert_eq!(schedule.due(&hash), Some(0));
+        assert_eq!(schedule.next_due(), None);
+    }
+}
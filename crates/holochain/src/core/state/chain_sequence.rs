@@ -37,6 +37,7 @@ pub struct ChainSequenceBuf {
     tx_seq: u32,
     current_head: Option<HeaderHash>,
     persisted_head: Option<HeaderHash>,
+    expected_head: Option<HeaderHash>,
 }
 
 impl ChainSequenceBuf {
@@ -53,6 +54,7 @@ impl ChainSequenceBuf {
             tx_seq,
             current_head,
             persisted_head,
+            expected_head: None,
         })
     }
 
@@ -117,6 +119,22 @@ impl ChainSequenceBuf {
         Ok(())
     }
 
+    /// Fail with [SourceChainError::HeadMoved] unless the chain head is
+    /// `expected_head`. If nothing has been added yet the committed head
+    /// is checked against `expected_head` again when flushing.
+    pub fn expect_head(&mut self, expected_head: HeaderHash) -> SourceChainResult<()> {
+        if self.current_head.as_ref() != Some(&expected_head) {
+            return Err(SourceChainError::HeadMoved(
+                Some(expected_head),
+                self.current_head.clone(),
+            ));
+        }
+        if !self.chain_moved_in_this_transaction() {
+            self.expected_head = Some(expected_head);
+        }
+        Ok(())
+    }
+
     pub fn get_items_with_incomplete_dht_ops<'txn, R: Readable>(
         &self,
         r: &'txn R,
@@ -178,6 +196,14 @@ impl BufferedStore for ChainSequenceBuf {
         let env = self.buf.env().clone();
         let db = env.get_db(&*CHAIN_SEQUENCE)?;
         let (_, _, persisted_head) = ChainSequenceBuf::head_info(&KvIntStore::new(db), writer)?;
+        if let Some(expected_head) = &self.expected_head {
+            if persisted_head.as_ref() != Some(expected_head) {
+                return Err(SourceChainError::HeadMoved(
+                    Some(expected_head.clone()),
+                    persisted_head,
+                ));
+            }
+        }
         let persisted_head_moved = self.persisted_head != persisted_head;
         if persisted_head_moved && self.chain_moved_in_this_transaction() {
             Err(SourceChainError::HeadMoved(
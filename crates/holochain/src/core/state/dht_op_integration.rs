@@ -1,16 +1,18 @@
 //! Various types for the databases involved in the DhtOp integration workflow
 
-use fallible_iterator::FallibleIterator;
+use fallible_iterator::{DoubleEndedFallibleIterator, FallibleIterator};
 use holo_hash::*;
 use holochain_p2p::dht_arc::DhtArc;
 use holochain_serialized_bytes::prelude::*;
 use holochain_state::{
-    buffer::KvBufFresh,
-    db::INTEGRATED_DHT_OPS,
+    buffer::{KvBufFresh, KvIntBufFresh},
+    db::{INTEGRATED_DHT_OPS, INTEGRATED_DHT_OPS_SEQUENCE},
     error::{DatabaseError, DatabaseResult},
-    prelude::{BufferedStore, EnvironmentRead, GetDb, Readable},
+    prelude::*,
 };
 use holochain_types::{dht_op::DhtOpLight, validate::ValidationStatus, Timestamp};
+use holochain_zome_types::entry_def::RequiredValidations;
+use std::collections::HashSet;
 
 /// Database type for AuthoredDhtOps
 /// Buffer for accessing [DhtOp]s that you authored and finding the amount of validation receipts
@@ -26,6 +28,10 @@ pub struct AuthoredDhtOpsValue {
     pub op: DhtOpLight,
     /// Validation receipts received
     pub receipt_count: u32,
+    /// Validation receipts needed before the op stops being published.
+    /// The entry def's required validations once a receipt has come in,
+    /// the default until then.
+    pub required_receipts: u32,
     /// Time last published, None if never published
     pub last_publish_time: Option<Timestamp>,
 }
@@ -36,6 +42,7 @@ impl AuthoredDhtOpsValue {
         Self {
             op,
             receipt_count: 0,
+            required_receipts: u8::from(RequiredValidations::default()) as u32,
             last_publish_time: None,
         }
     }
@@ -48,9 +55,20 @@ pub type IntegrationLimboStore = KvBufFresh<IntegrationLimboKey, IntegrationLimb
 /// [DhtOp]s that have already been integrated
 pub type IntegratedDhtOpsStore = KvBufFresh<DhtOpHash, IntegratedDhtOpsValue>;
 
+/// Database type for IntegratedDhtOpsSequence:
+/// the hashes of integrated [DhtOp]s in the order they were integrated
+pub type IntegratedDhtOpsSequenceStore = KvIntBufFresh<DhtOpHash>;
+
 /// Buffer that adds query logic to the IntegratedDhtOpsStore
+/// and records the order ops are integrated in
 pub struct IntegratedDhtOpsBuf {
     store: IntegratedDhtOpsStore,
+    sequence: IntegratedDhtOpsSequenceStore,
+    /// Ops put since the last flush that weren't integrated yet,
+    /// in the order they were put
+    integrated: Vec<DhtOpHash>,
+    /// Ops deleted since the last flush
+    removed: HashSet<DhtOpHash>,
 }
 
 impl std::ops::Deref for IntegratedDhtOpsBuf {
@@ -60,18 +78,65 @@ impl std::ops::Deref for IntegratedDhtOpsBuf {
     }
 }
 
-impl std::ops::DerefMut for IntegratedDhtOpsBuf {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.store
-    }
-}
-
 impl BufferedStore for IntegratedDhtOpsBuf {
     type Error = DatabaseError;
+    fn is_clean(&self) -> bool {
+        self.store.is_clean() && self.sequence.is_clean()
+    }
+
+    /// Indices are handed out here inside the write txn, so buffers
+    /// that were opened at the same time never hand out the same index
+    /// or add an op that another buffer has already integrated
     fn flush_to_txn_ref(
         &mut self,
         writer: &mut holochain_state::prelude::Writer,
     ) -> Result<(), Self::Error> {
+        let last = self
+            .sequence
+            .store()
+            .iter(&*writer)?
+            .next_back()?
+            .map(|(key, op_hash)| (IntKey::from_key_bytes_or_friendly_panic(key), op_hash));
+        let first_index = last.as_ref().map_or(0, |(key, _)| u32::from(*key) + 1);
+        let mut next_index = first_index;
+
+        // The last entry is kept for a removed op until there is a later one
+        // so its index isn't handed out again. Paging skips it while the op isn't held.
+        if !self.removed.is_empty() {
+            let removed = &self.removed;
+            let stale = self
+                .sequence
+                .store()
+                .iter(&*writer)?
+                .map(|(key, op_hash)| Ok((IntKey::from_key_bytes_or_friendly_panic(key), op_hash)))
+                .filter(|(key, op_hash)| {
+                    Ok(removed.contains(op_hash) && Some(key) != last.as_ref().map(|(k, _)| k))
+                })
+                .map(|(key, _)| Ok(key))
+                .collect::<Vec<_>>()?;
+            for key in stale {
+                self.sequence.delete(key)?;
+            }
+        }
+
+        for op_hash in self.integrated.drain(..) {
+            if self.store.store().get(&*writer, &op_hash)?.is_some() {
+                continue;
+            }
+            self.sequence.put(next_index.into(), op_hash)?;
+            next_index += 1;
+        }
+        if let Some((key, op_hash)) = last {
+            if next_index > first_index
+                && (self.removed.contains(&op_hash)
+                    || self.store.store().get(&*writer, &op_hash)?.is_none())
+            {
+                self.sequence.delete(key)?;
+            }
+        }
+        self.removed.clear();
+
+        self.sequence.flush_to_txn_ref(writer)?;
         self.store.flush_to_txn_ref(writer)
     }
 }
@@ -97,14 +162,24 @@ pub struct IntegrationLimboValue {
     pub validation_status: ValidationStatus,
     /// The op
     pub op: DhtOpLight,
+    /// When the op entered the validation limbo,
+    /// for measuring how long it took to validate and integrate
+    #[serde(default)]
+    pub time_added: Option<Timestamp>,
 }
 
 impl IntegratedDhtOpsBuf {
     /// Create a new buffer for the IntegratedDhtOpsStore
     pub fn new(env: EnvironmentRead) -> DatabaseResult<Self> {
         let db = env.get_db(&*INTEGRATED_DHT_OPS).unwrap();
+        let store = IntegratedDhtOpsStore::new(env.clone(), db);
+        let sequence: IntegratedDhtOpsSequenceStore =
+            KvIntBufFresh::new(env.clone(), env.get_db(&*INTEGRATED_DHT_OPS_SEQUENCE)?);
         Ok(Self {
-            store: IntegratedDhtOpsStore::new(env, db),
+            store,
+            sequence,
+            integrated: Vec::new(),
+            removed: HashSet::new(),
         })
     }
 
@@ -113,6 +188,31 @@ impl IntegratedDhtOpsBuf {
         self.store.get(op_hash)
     }
 
+    /// Integrate an op, adding it to the end of the integration order
+    /// on flush unless it was already integrated
+    pub fn put(&mut self, op_hash: DhtOpHash, value: IntegratedDhtOpsValue) -> DatabaseResult<()> {
+        self.removed.remove(&op_hash);
+        if !self.store.contains(&op_hash)? {
+            self.integrated.push(op_hash.clone());
+        }
+        self.store.put(op_hash, value)
+    }
+
+    /// Stop holding an op, removing it from the integration order on flush
+    pub fn delete(&mut self, op_hash: DhtOpHash) -> DatabaseResult<()> {
+        self.integrated.retain(|h| *h != op_hash);
+        self.removed.insert(op_hash.clone());
+        self.store.delete(op_hash)
+    }
+
+    /// Clear the integrated ops and their integration order
+    pub fn clear_all(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
+        self.integrated.clear();
+        self.removed.clear();
+        self.sequence.clear_all(writer)?;
+        self.store.clear_all(writer)
+    }
+
     /// Get ops that match optional queries:
     /// - from a time (Inclusive)
     /// - to a time (Exclusive)
@@ -152,7 +252,39 @@ impl IntegratedDhtOpsBuf {
                 }),
         ))
     }
+
+    /// Get up to `limit` ops in integration order, starting after the `after` cursor.
+    /// Integration order is the order ops were put in this buffer
+    /// so it doesn't depend on the clock.
+    /// Ops that are no longer held are skipped.
+    pub fn page<R: Readable>(
+        &self,
+        r: &R,
+        after: Option<&OpCursor>,
+        limit: usize,
+    ) -> DatabaseResult<Vec<(OpCursor, IntegratedDhtOpsValue)>> {
+        let from = after.map_or(0, |after| after.0 + 1);
+        self.sequence
+            .store()
+            .iter_from(r, from.into())?
+            .filter_map(|(key, op_hash)| {
+                let cursor = OpCursor(IntKey::from_key_bytes_or_friendly_panic(key).into());
+                Ok(self
+                    .store
+                    .store()
+                    .get(r, &op_hash)?
+                    .map(|value| (cursor, value)))
+            })
+            .take(limit)
+            .collect()
+    }
 }
+
+/// A position in a Cell's integrated ops, in integration order.
+/// Hand it back to [IntegratedDhtOpsBuf::page] to carry on after it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OpCursor(pub u32);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,4 +418,139 @@ mod tests {
             assert_eq!(r.len(), 3);
         }
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn page_follows_integration_order_not_the_clock() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let env_ref = env.guard();
+
+        // The clock goes backwards between each integration
+        let now = Utc::now();
+        let mut dht_hash = DhtOpHashFixturator::new(Predictable);
+        let ops = (0..6)
+            .map(|i| {
+                let value = IntegratedDhtOpsValue {
+                    validation_status: ValidationStatus::Valid,
+                    op: DhtOpLight::RegisterAgentActivity(fixt!(HeaderHash), fixt!(AnyDhtHash)),
+                    when_integrated: (now - Duration::hours(i)).into(),
+                };
+                (dht_hash.next().unwrap(), value)
+            })
+            .collect::<Vec<_>>();
+        {
+            let mut buf = IntegratedDhtOpsBuf::new(env.clone().into()).unwrap();
+            for (hash, value) in ops[..3].iter().cloned() {
+                buf.put(hash, value).unwrap();
+            }
+            // Putting an op again keeps its place
+            buf.put(ops[0].0.clone(), ops[0].1.clone()).unwrap();
+            env_ref
+                .with_commit(|writer| buf.flush_to_txn(writer))
+                .unwrap();
+        }
+        // A new buffer carries on the sequence
+        {
+            let mut buf = IntegratedDhtOpsBuf::new(env.clone().into()).unwrap();
+            for (hash, value) in ops[3..].iter().cloned() {
+                buf.put(hash, value).unwrap();
+            }
+            // An op that is no longer held
+            buf.delete(ops[4].0.clone()).unwrap();
+            env_ref
+                .with_commit(|writer| buf.flush_to_txn(writer))
+                .unwrap();
+        }
+
+        let reader = env_ref.reader().unwrap();
+        let buf = IntegratedDhtOpsBuf::new(env.clone().into()).unwrap();
+        let first = buf.page(&reader, None, 2).unwrap();
+        let rest = buf.page(&reader, first.last().map(|(c, _)| c), 10).unwrap();
+        let paged = first
+            .into_iter()
+            .chain(rest.into_iter())
+            .map(|(_, v)| v)
+            .collect::<Vec<_>>();
+        let expected = [0, 1, 2, 3, 5]
+            .iter()
+            .map(|i| ops[*i].1.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(paged, expected);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn interleaved_buffers_keep_one_entry_per_op() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let env_ref = env.guard();
+
+        let mut dht_hash = DhtOpHashFixturator::new(Predictable);
+        let ops = (0..4)
+            .map(|_| {
+                let value = IntegratedDhtOpsValue {
+                    validation_status: ValidationStatus::Valid,
+                    op: DhtOpLight::RegisterAgentActivity(fixt!(HeaderHash), fixt!(AnyDhtHash)),
+                    when_integrated: Utc::now().into(),
+                };
+                (dht_hash.next().unwrap(), value)
+            })
+            .collect::<Vec<_>>();
+        let page_all = || {
+            let reader = env_ref.reader().unwrap();
+            let buf = IntegratedDhtOpsBuf::new(env.clone().into()).unwrap();
+            buf.page(&reader, None, 10)
+                .unwrap()
+                .into_iter()
+                .map(|(_, v)| v)
+                .collect::<Vec<_>>()
+        };
+
+        // Two workspaces opened before either is flushed
+        // both integrate op 1
+        let mut first = IntegratedDhtOpsBuf::new(env.clone().into()).unwrap();
+        let mut second = IntegratedDhtOpsBuf::new(env.clone().into()).unwrap();
+        for (hash, value) in vec![ops[0].clone(), ops[1].clone()] {
+            first.put(hash, value).unwrap();
+        }
+        for (hash, value) in vec![ops[2].clone(), ops[1].clone()] {
+            second.put(hash, value).unwrap();
+        }
+        env_ref
+            .with_commit(|writer| second.flush_to_txn(writer))
+            .unwrap();
+        env_ref
+            .with_commit(|writer| first.flush_to_txn(writer))
+            .unwrap();
+        assert_eq!(
+            page_all(),
+            vec![ops[2].1.clone(), ops[1].1.clone(), ops[0].1.clone()]
+        );
+
+        // Removing op 1 and integrating it again puts it at the end once
+        let mut buf = IntegratedDhtOpsBuf::new(env.clone().into()).unwrap();
+        buf.delete(ops[1].0.clone()).unwrap();
+        env_ref
+            .with_commit(|writer| buf.flush_to_txn(writer))
+            .unwrap();
+        assert_eq!(page_all(), vec![ops[2].1.clone(), ops[0].1.clone()]);
+        let mut buf = IntegratedDhtOpsBuf::new(env.clone().into()).unwrap();
+        buf.put(ops[1].0.clone(), ops[1].1.clone()).unwrap();
+        buf.put(ops[3].0.clone(), ops[3].1.clone()).unwrap();
+        env_ref
+            .with_commit(|writer| buf.flush_to_txn(writer))
+            .unwrap();
+        assert_eq!(
+            page_all(),
+            vec![
+                ops[2].1.clone(),
+                ops[0].1.clone(),
+                ops[1].1.clone(),
+                ops[3].1.clone()
+            ]
+        );
+        let reader = env_ref.reader().unwrap();
+        let buf = IntegratedDhtOpsBuf::new(env.clone().into()).unwrap();
+        let sequence = buf.sequence.store().iter(&reader).unwrap().count().unwrap();
+        assert_eq!(sequence, 4);
+    }
 }
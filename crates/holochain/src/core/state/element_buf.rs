@@ -7,13 +7,16 @@
 /// using the ElementBuf for caching non-authored data, or for situations where
 /// it is known that private entries should be protected, such as when handling
 /// a get_entry request from the network.
+///
+/// The vault also stores large public entries in chunks, see the chunk module.
 use crate::core::state::source_chain::SourceChainResult;
 use holo_hash::{EntryHash, HasHash, HeaderHash};
+use holochain_serialized_bytes::{SerializedBytes, UnsafeBytes};
 use holochain_state::{
     buffer::CasBufFreshSync,
     db::{
-        GetDb, ELEMENT_CACHE_ENTRIES, ELEMENT_CACHE_HEADERS, ELEMENT_VAULT_HEADERS,
-        ELEMENT_VAULT_PRIVATE_ENTRIES, ELEMENT_VAULT_PUBLIC_ENTRIES,
+        GetDb, ELEMENT_CACHE_ENTRIES, ELEMENT_CACHE_HEADERS, ELEMENT_VAULT_ENTRY_CHUNKS,
+        ELEMENT_VAULT_HEADERS, ELEMENT_VAULT_PRIVATE_ENTRIES, ELEMENT_VAULT_PUBLIC_ENTRIES,
     },
     error::{DatabaseError, DatabaseResult},
     exports::SingleStore,
@@ -25,15 +28,21 @@ use holochain_types::{
 };
 use holochain_zome_types::entry_def::EntryVisibility;
 use holochain_zome_types::{Entry, Header};
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use tracing::*;
 
+pub use chunk::{ChunkStore, EntryChunk, CHUNK_SIZE};
+
+pub mod chunk;
+
 /// A CasBufFresh with Entries for values
 pub type EntryCas<P> = CasBufFreshSync<Entry, P>;
 /// A CasBufFresh with SignedHeaders for values
 pub type HeaderCas<P> = CasBufFreshSync<SignedHeader, P>;
 
 /// The representation of an ElementCache / ElementVault,
-/// using two or three DB references
+/// using two to four DB references
 pub struct ElementBuf<P = IntegratedPrefix>
 where
     P: PrefixType,
@@ -41,6 +50,19 @@ where
     public_entries: EntryCas<P>,
     private_entries: Option<EntryCas<P>>,
     headers: HeaderCas<P>,
+    entry_chunks: Option<ChunkStore<P>>,
+    /// The serialized bytes of entries put in chunks in this buffer
+    chunked_entries: HashMap<EntryHash, Vec<u8>>,
+    /// References from ops to chunked entries made or dropped in this buffer,
+    /// in order. They are applied in the write txn the buffer is flushed in.
+    chunk_refs: Vec<ChunkRef>,
+}
+
+/// An op referencing a chunked entry, or no longer referencing it
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ChunkRef {
+    Reference(EntryHash, HeaderHash),
+    Release(EntryHash, HeaderHash),
 }
 
 impl ElementBuf<IntegratedPrefix> {
@@ -57,7 +79,7 @@ impl ElementBuf<IntegratedPrefix> {
     pub fn cache(env: EnvironmentRead) -> DatabaseResult<Self> {
         let entries = env.get_db(&*ELEMENT_CACHE_ENTRIES)?;
         let headers = env.get_db(&*ELEMENT_CACHE_HEADERS)?;
-        ElementBuf::new(env, entries, None, headers)
+        ElementBuf::new(env, entries, None, headers, None)
     }
 }
 
@@ -96,16 +118,21 @@ where
         public_entries_store: SingleStore,
         private_entries_store: Option<SingleStore>,
         headers_store: SingleStore,
+        entry_chunks_store: Option<SingleStore>,
     ) -> DatabaseResult<Self> {
         let private_entries = if let Some(store) = private_entries_store {
             Some(CasBufFreshSync::new(env.clone(), store))
         } else {
             None
         };
+        let entry_chunks = entry_chunks_store.map(|store| ChunkStore::new(env.clone(), store));
         Ok(Self {
             public_entries: CasBufFreshSync::new(env.clone(), public_entries_store),
             private_entries,
             headers: CasBufFreshSync::new(env, headers_store),
+            entry_chunks,
+            chunked_entries: HashMap::new(),
+            chunk_refs: Vec::new(),
         })
     }
 
@@ -118,7 +145,84 @@ where
         } else {
             None
         };
-        Self::new(env, entries, private_entries, headers)
+        let entry_chunks = env.get_db(&*ELEMENT_VAULT_ENTRY_CHUNKS)?;
+        Self::new(env, entries, private_entries, headers, Some(entry_chunks))
+    }
+
+    /// Whether this public entry is stored in chunks
+    pub fn is_entry_chunked(&self, entry_hash: &EntryHash) -> DatabaseResult<bool> {
+        match self.pending_chunked_entry(entry_hash) {
+            Some(pending) => Ok(pending.is_some()),
+            None => match &self.entry_chunks {
+                Some(entry_chunks) => chunk::contains(entry_chunks, entry_hash),
+                None => Ok(false),
+            },
+        }
+    }
+
+    /// A chunked entry referenced or released in this buffer.
+    /// Some(None) if the last op referencing it in this buffer dropped it,
+    /// None if this buffer hasn't touched it.
+    fn pending_chunked_entry(&self, entry_hash: &EntryHash) -> Option<Option<&Vec<u8>>> {
+        self.chunk_refs.iter().rev().find_map(|r| match r {
+            ChunkRef::Reference(e, _) if e == entry_hash => {
+                Some(self.chunked_entries.get(entry_hash))
+            }
+            ChunkRef::Release(e, _) if e == entry_hash => Some(None),
+            _ => None,
+        })
+    }
+
+    /// Get a public entry, putting it back together if it was chunked
+    fn get_public_entry(&self, entry_hash: &EntryHash) -> DatabaseResult<Option<EntryHashed>> {
+        if let Some(entry) = self.public_entries.get(entry_hash)? {
+            return Ok(Some(entry));
+        }
+        match self.pending_chunked_entry(entry_hash) {
+            Some(Some(bytes)) => {
+                let entry =
+                    Entry::try_from(SerializedBytes::from(UnsafeBytes::from(bytes.clone())))?;
+                Ok(Some(EntryHashed::with_pre_hashed(
+                    entry,
+                    entry_hash.clone(),
+                )))
+            }
+            Some(None) => Ok(None),
+            None => match &self.entry_chunks {
+                Some(entry_chunks) => chunk::get(entry_chunks, entry_hash),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Put a public entry held by the ops with these headers, in chunks if it
+    /// is an app entry over the environment's [EnvironmentRead::entry_chunk_threshold].
+    /// Only the vault can chunk entries, the cache always stores them whole.
+    fn put_public_entry(
+        &mut self,
+        entry: EntryHashed,
+        headers: impl IntoIterator<Item = HeaderHash>,
+    ) -> DatabaseResult<()> {
+        if let Some(entry_chunks) = self.entry_chunks.as_ref() {
+            // Other entry types are small
+            if let Entry::App(bytes) = entry.as_content() {
+                if bytes.bytes().len() > entry_chunks.env().entry_chunk_threshold() {
+                    if !self.chunked_entries.contains_key(entry.as_hash()) {
+                        let bytes =
+                            UnsafeBytes::from(SerializedBytes::try_from(entry.as_content())?);
+                        self.chunked_entries
+                            .insert(entry.as_hash().clone(), bytes.into());
+                    }
+                    for header_hash in headers {
+                        self.chunk_refs
+                            .push(ChunkRef::Reference(entry.as_hash().clone(), header_hash));
+                    }
+                    return Ok(());
+                }
+            }
+        }
+        self.public_entries.put(entry);
+        Ok(())
     }
 
     /// Get an entry by its address
@@ -126,7 +230,7 @@ where
     /// First attempt to get from the public entry DB. If not present, and
     /// private DB access is specified, attempt to get as a private entry.
     pub fn get_entry(&self, entry_hash: &EntryHash) -> DatabaseResult<Option<EntryHashed>> {
-        match self.public_entries.get(entry_hash)? {
+        match self.get_public_entry(entry_hash)? {
             Some(entry) => Ok(Some(entry)),
             None => {
                 if let Some(ref db) = (self).private_entries {
@@ -139,7 +243,9 @@ where
     }
 
     pub fn contains_entry(&self, entry_hash: &EntryHash) -> DatabaseResult<bool> {
-        Ok(if self.public_entries.contains(entry_hash)? {
+        let public =
+            self.public_entries.contains(entry_hash)? || self.is_entry_chunked(entry_hash)?;
+        Ok(if public {
             true
         } else {
             // Potentially avoid this let Some if the above branch is hit first
@@ -176,7 +282,7 @@ where
                 match entry_type.visibility() {
                     // if the header references an entry and the database is
                     // available, it better have been stored!
-                    EntryVisibility::Public => self.public_entries.get(entry_hash)?,
+                    EntryVisibility::Public => self.get_public_entry(entry_hash)?,
                     EntryVisibility::Private => {
                         if let Some(ref db) = self.private_entries {
                             db.get(entry_hash)?
@@ -211,7 +317,10 @@ where
         if let Some(entry) = maybe_entry {
             if let Some((_, entry_type)) = signed_header.header().entry_data() {
                 match entry_type.visibility() {
-                    EntryVisibility::Public => self.public_entries.put(entry),
+                    EntryVisibility::Public => self.put_public_entry(
+                        entry,
+                        std::iter::once(signed_header.header_address().clone()),
+                    )?,
                     EntryVisibility::Private => {
                         if let Some(db) = self.private_entries.as_mut() {
                             db.put(entry);
@@ -233,12 +342,14 @@ where
     }
 
     pub fn put_element_group(&mut self, element_group: ElementGroup) -> DatabaseResult<()> {
+        let mut header_hashes = Vec::new();
         for shh in element_group.owned_signed_headers() {
+            header_hashes.push(shh.header_address().clone());
             self.headers.put(shh.into());
         }
         let entry = element_group.entry_hashed();
         match element_group.visibility()? {
-            EntryVisibility::Public => self.public_entries.put(entry),
+            EntryVisibility::Public => self.put_public_entry(entry, header_hashes)?,
             EntryVisibility::Private => {
                 if let Some(db) = self.private_entries.as_mut() {
                     db.put(entry);
@@ -251,22 +362,32 @@ where
     }
 
     pub fn delete(&mut self, header_hash: HeaderHash, entry_hash: Option<EntryHash>) {
-        self.headers.delete(header_hash);
+        self.headers.delete(header_hash.clone());
         if let Some(entry_hash) = entry_hash {
             if let Some(db) = self.private_entries.as_mut() {
                 db.delete(entry_hash.clone())
             }
+            // Other ops can still reference the entry's chunks
+            // so they are only released when the buffer is flushed
+            if self.entry_chunks.is_some() {
+                self.chunk_refs
+                    .push(ChunkRef::Release(entry_hash.clone(), header_hash.clone()));
+            }
             self.public_entries.delete(entry_hash);
         }
     }
 
     /// Removes a delete if there was one previously added
     pub fn cancel_delete(&mut self, header_hash: HeaderHash, entry_hash: Option<EntryHash>) {
-        self.headers.cancel_delete(header_hash);
+        self.headers.cancel_delete(header_hash.clone());
         if let Some(entry_hash) = entry_hash {
             if let Some(db) = self.private_entries.as_mut() {
                 db.cancel_delete(entry_hash.clone())
             }
+            let release = ChunkRef::Release(entry_hash.clone(), header_hash);
+            if let Some(i) = self.chunk_refs.iter().rposition(|r| *r == release) {
+                self.chunk_refs.remove(i);
+            }
             self.public_entries.cancel_delete(entry_hash);
         }
     }
@@ -283,6 +404,10 @@ where
         self.private_entries.as_ref()
     }
 
+    pub fn entry_chunks(&self) -> Option<&ChunkStore<P>> {
+        self.entry_chunks.as_ref()
+    }
+
     #[cfg(test)]
     /// Clear all scratch and db, useful for tests
     pub fn clear_all(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
@@ -290,6 +415,11 @@ where
         if let Some(private) = &mut self.private_entries {
             private.clear_all(writer)?
         }
+        if let Some(entry_chunks) = &mut self.entry_chunks {
+            entry_chunks.clear_all(writer)?
+        }
+        self.chunked_entries.clear();
+        self.chunk_refs.clear();
         self.headers.clear_all(writer)
     }
 }
@@ -305,6 +435,7 @@ impl<P: PrefixType> BufferedStore for ElementBuf<P> {
                 .as_ref()
                 .map(|db| db.is_clean())
                 .unwrap_or(true)
+            && self.chunk_refs.is_empty()
     }
 
     fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
@@ -315,6 +446,21 @@ impl<P: PrefixType> BufferedStore for ElementBuf<P> {
         if let Some(ref mut db) = self.private_entries {
             db.flush_to_txn_ref(writer)?
         };
+        if let Some(ref db) = self.entry_chunks {
+            // Not drained, so a flush retried after growing the map applies them again
+            for chunk_ref in self.chunk_refs.iter() {
+                match chunk_ref {
+                    ChunkRef::Reference(entry_hash, header_hash) => {
+                        // Only entries put in this buffer are referenced in it
+                        let bytes = &self.chunked_entries[entry_hash];
+                        chunk::reference(db, writer, entry_hash, header_hash, bytes)?
+                    }
+                    ChunkRef::Release(entry_hash, header_hash) => {
+                        chunk::release(db, writer, entry_hash, header_hash)?
+                    }
+                }
+            }
+        };
         self.headers.flush_to_txn_ref(writer)?;
         Ok(())
     }
@@ -328,8 +474,13 @@ mod tests {
     use holo_hash::*;
     use holochain_keystore::test_keystore::spawn_test_keystore;
     use holochain_keystore::AgentPubKeyExt;
-    use holochain_state::{prelude::*, test_utils::test_cell_env};
+    use holochain_serialized_bytes::{SerializedBytes, UnsafeBytes};
+    use holochain_state::{
+        db::DbName, env::DEFAULT_ENTRY_CHUNK_THRESHOLD, prelude::*, test_utils::test_cell_env,
+    };
+    use holochain_types::entry::EntryHashed;
     use holochain_zome_types::entry_def::EntryVisibility;
+    use holochain_zome_types::Entry;
 
     #[tokio::test(threaded_scheduler)]
     async fn can_write_private_entry_when_enabled() -> anyhow::Result<()> {
@@ -418,4 +569,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn deleting_chunked_entries_removes_chunks_no_manifest_lists() -> anyhow::Result<()> {
+        let keystore = spawn_test_keystore().await?;
+        let test_env = test_cell_env();
+        let arc = test_env.env();
+        let env = arc.guard();
+        let chunks_len = || arc.db_len(&DbName::ElementVaultEntryChunks);
+
+        let agent_key = AgentPubKey::new_from_pure_entropy(&keystore).await?;
+        let mut header_hashes = Vec::new();
+        for _ in 0..3 {
+            let (header, _) =
+                fake_unique_element(&keystore, agent_key.clone(), EntryVisibility::Public).await?;
+            header_hashes.push(header.as_hash().clone());
+        }
+        let (header_hash, other_header_hash, second_header_hash) = (
+            header_hashes[0].clone(),
+            header_hashes[1].clone(),
+            header_hashes[2].clone(),
+        );
+
+        // Two entries over the threshold which only differ in their last chunk
+        let large_entry = |last: u8| {
+            let mut bytes = vec![7; DEFAULT_ENTRY_CHUNK_THRESHOLD * 2];
+            *bytes.last_mut().unwrap() = last;
+            let bytes = SerializedBytes::from(UnsafeBytes::from(bytes));
+            EntryHashed::from_content_sync(Entry::app(bytes).unwrap())
+        };
+        let first = large_entry(1);
+        let second = large_entry(2);
+
+        let mut store = ElementBuf::vault(arc.clone().into(), true)?;
+        store.put_public_entry(
+            first.clone(),
+            vec![header_hash.clone(), other_header_hash.clone()],
+        )?;
+        store.put_public_entry(second.clone(), vec![second_header_hash.clone()])?;
+        assert_eq!(store.get_entry(first.as_hash()), Ok(Some(first.clone())));
+        env.with_commit(|txn| store.flush_to_txn(txn))?;
+        let stored = chunks_len()?;
+
+        // A delete that is cancelled keeps every chunk
+        let mut store = ElementBuf::vault(arc.clone().into(), true)?;
+        store.delete(header_hash.clone(), Some(first.as_hash().clone()));
+        store.cancel_delete(header_hash.clone(), Some(first.as_hash().clone()));
+        env.with_commit(|txn| store.flush_to_txn(txn))?;
+        assert_eq!(chunks_len()?, stored);
+
+        // Another op still references the first entry
+        let mut store = ElementBuf::vault(arc.clone().into(), true)?;
+        store.delete(header_hash.clone(), Some(first.as_hash().clone()));
+        env.with_commit(|txn| store.flush_to_txn(txn))?;
+        assert_eq!(chunks_len()?, stored);
+        let store = ElementBuf::vault(arc.clone().into(), true)?;
+        assert_eq!(store.get_entry(first.as_hash()), Ok(Some(first.clone())));
+
+        // Only the manifest and the last chunk of the first entry go
+        // once no op references it
+        let mut store = ElementBuf::vault(arc.clone().into(), true)?;
+        store.delete(other_header_hash, Some(first.as_hash().clone()));
+        assert_eq!(store.get_entry(first.as_hash()), Ok(None));
+        env.with_commit(|txn| store.flush_to_txn(txn))?;
+        assert_eq!(chunks_len()?, stored - 2);
+        let store = ElementBuf::vault(arc.clone().into(), true)?;
+        assert_eq!(store.get_entry(first.as_hash()), Ok(None));
+        assert_eq!(store.get_entry(second.as_hash()), Ok(Some(second.clone())));
+
+        // Nothing is left once both are deleted
+        let mut store = ElementBuf::vault(arc.clone().into(), true)?;
+        store.delete(second_header_hash, Some(second.as_hash().clone()));
+        env.with_commit(|txn| store.flush_to_txn(txn))?;
+        assert_eq!(chunks_len()?, 0);
+
+        Ok(())
+    }
 }
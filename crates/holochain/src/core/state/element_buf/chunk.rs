@@ -0,0 +1,209 @@
+//! Content addressed chunks of large public entries.
+//!
+//! A public app entry bigger than the environment's entry chunk threshold,
+//! which the conductor config can set, is not stored whole. Its serialized
+//! bytes are split into chunks stored under their own hash, and the entry
+//! hash points to a manifest of those chunk hashes in order. Chunks are
+//! hashed with a context of their own so a chunk can never be keyed the same
+//! as a manifest, even when it holds a whole serialized entry.
+//!
+//! The ops holding an entry reference its manifest by their header hash,
+//! and the manifest is removed once no op references it. Identical chunks of
+//! different entries are only stored once, counting the manifests that list
+//! them, and a chunk is removed once no manifest lists it. Both counts are
+//! read and written in the write txn the element buffer is flushed in,
+//! so buffers flushed one after another never lose a reference.
+//! Reading the entry puts the chunks back together and checks the result
+//! still hashes to the entry hash.
+
+use holo_hash::{encode::blake2b_256, EntryHash, HasHash, HeaderHash};
+use holochain_serialized_bytes::prelude::*;
+use holochain_state::{
+    buffer::{KvBufFresh, KvStoreT},
+    error::{DatabaseError, DatabaseResult},
+    fatal_db_hash_integrity_check,
+    prelude::*,
+};
+use holochain_types::entry::EntryHashed;
+use holochain_zome_types::Entry;
+use serde::{Deserialize, Serialize};
+
+/// The most bytes of an entry held in one chunk
+pub const CHUNK_SIZE: usize = 256 * 1024;
+
+/// Keeps the keys of chunks apart from the entry hashes manifests are keyed by
+const CHUNK_HASH_CONTEXT: &[u8] = b"holochain_entry_chunk";
+
+/// A value in the entry chunk database
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum EntryChunk {
+    /// The hashes of the chunks an entry was split into, in order,
+    /// and the headers of the ops holding the entry
+    Manifest {
+        chunks: Vec<EntryHash>,
+        headers: Vec<HeaderHash>,
+    },
+    /// Some of the serialized bytes of an entry,
+    /// and how many times manifests list them
+    Bytes { bytes: SerializedBytes, refs: u32 },
+}
+
+/// A KvBufFresh of entry manifests and chunks, keyed by their hash.
+/// Writes go straight to the write txn, see [reference] and [release].
+pub type ChunkStore<P> = KvBufFresh<PrefixHashKey<P>, EntryChunk>;
+
+/// Reference the entry's manifest from the op with this header,
+/// splitting the serialized entry into chunks and storing them
+/// if this is the first reference.
+/// Reads through the writer as this runs while the store is flushed.
+pub(super) fn reference<P: PrefixType>(
+    store: &ChunkStore<P>,
+    writer: &mut Writer,
+    entry_hash: &EntryHash,
+    header_hash: &HeaderHash,
+    bytes: &[u8],
+) -> DatabaseResult<()> {
+    let store = store.store();
+    let manifest_key = PrefixHashKey::new(entry_hash);
+    match store.get(&*writer, &manifest_key)? {
+        Some(EntryChunk::Manifest {
+            chunks,
+            mut headers,
+        }) => {
+            if !headers.contains(header_hash) {
+                headers.push(header_hash.clone());
+                store.put(
+                    writer,
+                    &manifest_key,
+                    &EntryChunk::Manifest { chunks, headers },
+                )?;
+            }
+            return Ok(());
+        }
+        Some(EntryChunk::Bytes { .. }) => return Err(DatabaseError::InvalidValue),
+        None => (),
+    }
+    let mut chunks = Vec::with_capacity(bytes.len() / CHUNK_SIZE + 1);
+    for chunk in bytes.chunks(CHUNK_SIZE) {
+        let chunk_hash = chunk_hash(chunk);
+        let key = PrefixHashKey::new(&chunk_hash);
+        let chunk = match store.get(&*writer, &key)? {
+            Some(EntryChunk::Bytes { bytes, refs }) => EntryChunk::Bytes {
+                bytes,
+                refs: refs + 1,
+            },
+            Some(EntryChunk::Manifest { .. }) => return Err(DatabaseError::InvalidValue),
+            None => EntryChunk::Bytes {
+                bytes: SerializedBytes::from(UnsafeBytes::from(chunk.to_vec())),
+                refs: 1,
+            },
+        };
+        store.put(writer, &key, &chunk)?;
+        chunks.push(chunk_hash);
+    }
+    store.put(
+        writer,
+        &manifest_key,
+        &EntryChunk::Manifest {
+            chunks,
+            headers: vec![header_hash.clone()],
+        },
+    )
+}
+
+/// Stop referencing the entry's manifest from the op with this header.
+/// Once no op references it the manifest is removed and its chunks
+/// are released, removing those no other manifest lists.
+/// Reads through the writer as this runs while the store is flushed.
+pub(super) fn release<P: PrefixType>(
+    store: &ChunkStore<P>,
+    writer: &mut Writer,
+    entry_hash: &EntryHash,
+    header_hash: &HeaderHash,
+) -> DatabaseResult<()> {
+    let store = store.store();
+    let manifest_key = PrefixHashKey::new(entry_hash);
+    let (chunks, mut headers) = match store.get(&*writer, &manifest_key)? {
+        Some(EntryChunk::Manifest { chunks, headers }) => (chunks, headers),
+        Some(EntryChunk::Bytes { .. }) => return Err(DatabaseError::InvalidValue),
+        None => return Ok(()),
+    };
+    // Already released
+    if !headers.contains(header_hash) {
+        return Ok(());
+    }
+    headers.retain(|h| h != header_hash);
+    if !headers.is_empty() {
+        return store.put(
+            writer,
+            &manifest_key,
+            &EntryChunk::Manifest { chunks, headers },
+        );
+    }
+    store.delete(writer, &manifest_key)?;
+    for chunk_hash in chunks {
+        let key = PrefixHashKey::new(&chunk_hash);
+        match store.get(&*writer, &key)? {
+            Some(EntryChunk::Bytes { bytes, refs }) if refs > 1 => store.put(
+                writer,
+                &key,
+                &EntryChunk::Bytes {
+                    bytes,
+                    refs: refs - 1,
+                },
+            )?,
+            Some(EntryChunk::Bytes { .. }) => store.delete(writer, &key)?,
+            Some(EntryChunk::Manifest { .. }) => return Err(DatabaseError::InvalidValue),
+            None => (),
+        }
+    }
+    Ok(())
+}
+
+/// The key a chunk is stored under: the hash of the chunk context
+/// followed by the chunk's bytes
+fn chunk_hash(chunk: &[u8]) -> EntryHash {
+    EntryHash::with_pre_hashed(blake2b_256(&[CHUNK_HASH_CONTEXT, chunk].concat()))
+}
+
+/// Put a chunked entry back together.
+/// Returns None if the entry was not stored in chunks.
+pub(super) fn get<P: PrefixType>(
+    store: &ChunkStore<P>,
+    entry_hash: &EntryHash,
+) -> DatabaseResult<Option<EntryHashed>> {
+    let manifest = match store.get(&PrefixHashKey::new(entry_hash))? {
+        Some(EntryChunk::Manifest { chunks, .. }) => chunks,
+        Some(EntryChunk::Bytes { .. }) => return Err(DatabaseError::InvalidValue),
+        None => return Ok(None),
+    };
+    let mut bytes = Vec::with_capacity(manifest.len() * CHUNK_SIZE);
+    for chunk_hash in manifest {
+        match store.get(&PrefixHashKey::new(&chunk_hash))? {
+            Some(EntryChunk::Bytes { bytes: chunk, .. }) => bytes.extend_from_slice(chunk.bytes()),
+            _ => {
+                return Err(DatabaseError::MissingEntryChunk(
+                    entry_hash.clone(),
+                    chunk_hash,
+                ))
+            }
+        }
+    }
+    let entry = Entry::try_from(SerializedBytes::from(UnsafeBytes::from(bytes)))?;
+    let entry = EntryHashed::from_content_sync(entry);
+    fatal_db_hash_integrity_check!(
+        "ElementBuf::get_entry",
+        entry_hash.get_full_bytes(),
+        entry.as_hash().get_full_bytes(),
+        entry.as_content(),
+    );
+    Ok(Some(entry))
+}
+
+/// Whether the entry was stored in chunks
+pub(super) fn contains<P: PrefixType>(
+    store: &ChunkStore<P>,
+    entry_hash: &EntryHash,
+) -> DatabaseResult<bool> {
+    store.contains(&PrefixHashKey::new(entry_hash))
+}
@@ -0,0 +1,77 @@
+//! An index from entries to the integrated ops that store or reference them
+
+use holo_hash::{hash_type::AnyDht, DhtOpHash, EntryHash};
+use holochain_state::{
+    buffer::{BufferedStore, KvvBufUsed},
+    db::{GetDb, ENTRY_OPS_INDEX},
+    error::{DatabaseError, DatabaseResult},
+    prelude::{Readable, Writer},
+};
+use holochain_types::dht_op::DhtOpLight;
+
+/// The database/buffer mapping an [EntryHash] to the hashes of
+/// every integrated op that stores or references that entry.
+pub struct EntryOpsIndexBuf(KvvBufUsed<EntryHash, DhtOpHash>);
+
+impl EntryOpsIndexBuf {
+    /// Constructor given read-only transaction and db ref.
+    pub fn new(dbs: &impl GetDb) -> DatabaseResult<Self> {
+        Ok(Self(KvvBufUsed::new_opts(
+            dbs.get_db(&*ENTRY_OPS_INDEX)?,
+            true, // set to no_dup_data mode
+        )))
+    }
+
+    /// List the hashes of all ops that touch an entry.
+    pub fn ops_for_entry<'r, R: Readable>(
+        &'r self,
+        r: &'r R,
+        entry_hash: &EntryHash,
+    ) -> DatabaseResult<
+        impl fallible_iterator::FallibleIterator<Item = DhtOpHash, Error = DatabaseError> + '_,
+    > {
+        Ok(fallible_iterator::convert(self.0.get(r, entry_hash)?))
+    }
+
+    /// Index an op under every entry it stores or references.
+    pub fn add_op(&mut self, op: &DhtOpLight, op_hash: DhtOpHash) {
+        for entry_hash in entries_for_op(op) {
+            self.0.insert(entry_hash, op_hash.clone());
+        }
+    }
+
+    /// Remove an op from under every entry it stores or references.
+    pub fn remove_op(&mut self, op: &DhtOpLight, op_hash: &DhtOpHash) {
+        for entry_hash in entries_for_op(op) {
+            self.0.delete(entry_hash, op_hash.clone());
+        }
+    }
+}
+
+/// The entries an op stores or references.
+/// This is the entry data of the header along with the
+/// basis of the op when the basis is an entry.
+fn entries_for_op(op: &DhtOpLight) -> Vec<EntryHash> {
+    let entry_data = match op {
+        DhtOpLight::StoreElement(_, e, _) => e.clone(),
+        DhtOpLight::StoreEntry(_, e, _) | DhtOpLight::RegisterUpdatedBy(_, e, _) => Some(e.clone()),
+        _ => None,
+    };
+    let basis = op.dht_basis();
+    let basis = match basis.hash_type() {
+        AnyDht::Entry => Some(basis.clone().into()),
+        AnyDht::Header => None,
+    };
+    let mut entries: Vec<EntryHash> = entry_data.into_iter().chain(basis).collect();
+    entries.dedup();
+    entries
+}
+
+impl BufferedStore for EntryOpsIndexBuf {
+    type Error = DatabaseError;
+
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
+        self.0.flush_to_txn_ref(writer)?;
+        Ok(())
+    }
+}
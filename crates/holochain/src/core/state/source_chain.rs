@@ -15,11 +15,13 @@ use holochain_zome_types::{
     header::{builder, EntryType, Header, HeaderBuilder, HeaderBuilderCommon, HeaderInner},
     query::ChainQueryFilter,
 };
+pub use read_cache::*;
 use shrinkwraprs::Shrinkwrap;
 pub use source_chain_buffer::*;
 use std::collections::HashSet;
 
 mod error;
+mod read_cache;
 mod source_chain_buffer;
 
 /// A wrapper around [SourceChainBuf] with the assumption that the source chain has been initialized,
@@ -49,16 +51,32 @@ impl SourceChain {
         Ok(SourceChainBuf::public_only(env)?.into())
     }
 
+    /// Cache up to `capacity` headers and elements read from this chain.
+    /// See [SourceChainBuf::with_read_cache]
+    pub fn with_read_cache(self, capacity: usize) -> Self {
+        self.0.with_read_cache(capacity).into()
+    }
+
     pub fn into_inner(self) -> SourceChainBuf {
         self.0
     }
 
-    /// Add a Element to the source chain, using a HeaderBuilder
+    /// Add a Element to the source chain, using a HeaderBuilder.
+    ///
+    /// If `expected_head` is given and the chain head has moved on,
+    /// nothing is added and [SourceChainError::HeadMoved] is returned
+    /// so the caller can retry against the new head.
+    /// The committed head is checked again under the write transaction
+    /// when flushing, so an append from another workspace also fails it.
     pub async fn put<H: HeaderInner, B: HeaderBuilder<H>>(
         &mut self,
         header_builder: B,
         maybe_entry: Option<Entry>,
+        expected_head: Option<HeaderHash>,
     ) -> SourceChainResult<HeaderHash> {
+        if let Some(expected_head) = expected_head {
+            self.0.expect_head(expected_head)?;
+        }
         let common = HeaderBuilderCommon {
             author: self.agent_pubkey()?,
             timestamp: Timestamp::now().into(),
@@ -69,6 +87,34 @@ impl SourceChain {
         self.put_raw(header, maybe_entry).await
     }
 
+    /// Add several Elements to the source chain in one all or nothing step,
+    /// using a HeaderBuilder for each.
+    /// If any Element fails then neither the chain nor the ChainSequence is changed.
+    pub async fn put_batch<H, B, I>(&mut self, batch: I) -> SourceChainResult<Vec<HeaderHash>>
+    where
+        H: HeaderInner,
+        B: HeaderBuilder<H>,
+        I: IntoIterator<Item = (B, Option<Entry>)>,
+    {
+        let author = self.agent_pubkey()?;
+        let mut prev_header = self.chain_head()?.to_owned();
+        let mut header_seq = self.len() as u32;
+        let mut headers = Vec::new();
+        for (header_builder, maybe_entry) in batch {
+            let common = HeaderBuilderCommon {
+                author: author.clone(),
+                timestamp: Timestamp::now().into(),
+                header_seq,
+                prev_header,
+            };
+            let header: Header = header_builder.build(common).into();
+            prev_header = HeaderHash::with_data_sync(&header);
+            header_seq += 1;
+            headers.push((header, maybe_entry));
+        }
+        self.put_raw_batch(headers).await
+    }
+
     /// Add a CapClaimEntry to the source chain
     pub async fn put_cap_claim(
         &mut self,
@@ -80,7 +126,7 @@ impl SourceChain {
             entry_type: EntryType::CapClaim,
             entry_hash,
         };
-        self.put(header_builder, Some(entry)).await
+        self.put(header_builder, Some(entry), None).await
     }
 
     /// Fetch a relevant CapGrant from the private entries.
@@ -287,12 +333,31 @@ impl SourceChain {
     //     }
     // }
 
+    /// Every element on the chain, from the head back to the Dna header,
+    /// following each header's `prev_header`.
+    pub fn iter_back(&self) -> SourceChainElementBackwardIterator {
+        SourceChainElementBackwardIterator {
+            store: &self.0,
+            headers: self.0.iter_back(),
+        }
+    }
+
+    /// Every element on the chain, from the Dna header forward to the head,
+    /// following the chain sequence.
+    pub fn iter_forward(&self) -> SourceChainElementForwardIterator {
+        SourceChainElementForwardIterator {
+            store: &self.0,
+            index: 0,
+        }
+    }
+
     /// Query Headers in the source chain.
     /// This returns a Vec rather than an iterator because it is intended to be
     /// used by the `query` host function, which crosses the wasm boundary
     pub fn query(&self, query: &ChainQueryFilter) -> SourceChainResult<Vec<Element>> {
         let include_entries = query.include_entries;
-        self.iter_back()
+        self.0
+            .iter_back()
             .filter(|shh| Ok(query.check(shh.header())))
             .map(|shh| {
                 let entry = match shh.header().entry_hash() {
@@ -305,6 +370,55 @@ impl SourceChain {
     }
 }
 
+/// FallibleIterator returning the elements of a chain
+/// starting with the head, moving back to the origin (Dna) header.
+pub struct SourceChainElementBackwardIterator<'a> {
+    store: &'a SourceChainBuf,
+    headers: SourceChainBackwardIterator<'a>,
+}
+
+impl<'a> FallibleIterator for SourceChainElementBackwardIterator<'a> {
+    type Item = Element;
+    type Error = SourceChainError;
+
+    fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
+        match self.headers.next()? {
+            None => Ok(None),
+            Some(header) => {
+                let hash = header.header_address();
+                self.store
+                    .get_element(hash)?
+                    .ok_or_else(|| SourceChainError::ElementMissing(hash.to_string()))
+                    .map(Some)
+            }
+        }
+    }
+}
+
+/// FallibleIterator returning the elements of a chain
+/// starting with the origin (Dna) header, moving forward to the head.
+pub struct SourceChainElementForwardIterator<'a> {
+    store: &'a SourceChainBuf,
+    index: u32,
+}
+
+impl<'a> FallibleIterator for SourceChainElementForwardIterator<'a> {
+    type Item = Element;
+    type Error = SourceChainError;
+
+    fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
+        if self.index as usize >= self.store.len() {
+            return Ok(None);
+        }
+        let element = self
+            .store
+            .get_at_index(self.index)?
+            .ok_or_else(|| SourceChainError::ElementMissing(format!("at index {}", self.index)))?;
+        self.index += 1;
+        Ok(Some(element))
+    }
+}
+
 impl From<SourceChainBuf> for SourceChain {
     fn from(buffer: SourceChainBuf) -> Self {
         Self(buffer)
@@ -377,7 +491,7 @@ pub mod tests {
                 entry_type: EntryType::CapGrant,
                 entry_hash: entry_hash.clone(),
             };
-            let header = chain.put(header_builder, Some(entry)).await?;
+            let header = chain.put(header_builder, Some(entry), None).await?;
 
             env.guard()
                 .with_commit(|writer| chain.flush_to_txn(writer))?;
@@ -419,7 +533,7 @@ pub mod tests {
                 original_header_address,
                 original_entry_address,
             };
-            let header = chain.put(header_builder, Some(entry)).await?;
+            let header = chain.put(header_builder, Some(entry), None).await?;
 
             env.guard()
                 .with_commit(|writer| chain.flush_to_txn(writer))?;
@@ -457,7 +571,7 @@ pub mod tests {
                 deletes_address: updated_header_hash,
                 deletes_entry_address: updated_entry_hash,
             };
-            chain.put(header_builder, None).await?;
+            chain.put(header_builder, None, None).await?;
 
             env.guard()
                 .with_commit(|writer| chain.flush_to_txn(writer))?;
@@ -489,6 +603,272 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn put_batch_is_all_or_nothing() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let alice = fixt!(AgentPubKey);
+        {
+            let mut store = SourceChainBuf::new(env.clone().into())?;
+            store.genesis(fake_dna_hash(1), alice, None).await?;
+            env.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+
+        let entry_type = EntryType::App(fixt!(AppEntryType));
+        let mut batch: Vec<_> = EntryFixturator::new(AppEntry)
+            .take(3)
+            .map(|entry| {
+                let entry_hash = EntryHash::with_data_sync(&entry);
+                let header_builder = builder::Create {
+                    entry_type: entry_type.clone(),
+                    entry_hash,
+                };
+                (header_builder, Some(entry))
+            })
+            .collect();
+
+        let mut chain = SourceChain::new(env.clone().into())?;
+        let head = chain.chain_head()?.clone();
+        let len = chain.len();
+
+        // Simulate a failure in the middle of the batch by giving
+        // the second header an entry that doesn't match it
+        let good_entry = batch[1].1.replace(fixt!(Entry, AppEntry));
+        let result = chain.put_batch(batch.clone()).await;
+        assert!(matches!(
+            result,
+            Err(SourceChainError::InvalidStructure(
+                ChainInvalidReason::HeaderAndEntryMismatch(_)
+            ))
+        ));
+        assert_eq!(chain.chain_head()?, &head);
+        assert_eq!(chain.len(), len);
+        assert!(chain.get_at_index(len as u32)?.is_none());
+
+        // The whole batch goes in once it is valid
+        batch[1].1 = good_entry;
+        let hashes = chain.put_batch(batch).await?;
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(chain.len(), len + 3);
+        assert_eq!(chain.chain_head()?, &hashes[2]);
+        env.guard()
+            .with_commit(|writer| chain.flush_to_txn(writer))?;
+
+        let chain = SourceChain::new(env.clone().into())?;
+        assert_eq!(chain.len(), len + 3);
+        assert_eq!(chain.chain_head()?, &hashes[2]);
+        for (i, hash) in hashes.iter().enumerate() {
+            let element = chain.get_at_index((len + i) as u32)?.unwrap();
+            assert_eq!(element.header_address(), hash);
+            assert!(element.entry().as_option().is_some());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn iterates_the_chain_in_both_directions() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let alice = fixt!(AgentPubKey);
+        {
+            let mut store = SourceChainBuf::new(env.clone().into())?;
+            store.genesis(fake_dna_hash(1), alice, None).await?;
+            env.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+
+        let entry_type = EntryType::App(fixt!(AppEntryType));
+        let batch: Vec<_> = EntryFixturator::new(AppEntry)
+            .take(3)
+            .map(|entry| {
+                let entry_hash = EntryHash::with_data_sync(&entry);
+                let header_builder = builder::Create {
+                    entry_type: entry_type.clone(),
+                    entry_hash,
+                };
+                (header_builder, Some(entry))
+            })
+            .collect();
+        let mut chain = SourceChain::new(env.clone().into())?;
+        chain.put_batch(batch).await?;
+        env.guard()
+            .with_commit(|writer| chain.flush_to_txn(writer))?;
+
+        let chain = SourceChain::new(env.clone().into())?;
+        let forward: Vec<_> = chain.iter_forward().collect()?;
+        let back: Vec<_> = chain.iter_back().collect()?;
+
+        // Genesis plus the three entries
+        assert_eq!(forward.len(), 6);
+        assert!(matches!(forward[0].header(), Header::Dna(_)));
+        assert_eq!(back[0].header_address(), chain.chain_head()?);
+        assert!(matches!(back[5].header(), Header::Dna(_)));
+
+        // Both directions walk the same chain
+        let forward_hashes: Vec<_> = forward.iter().map(|e| e.header_address()).collect();
+        let back_hashes: Vec<_> = back.iter().rev().map(|e| e.header_address()).collect();
+        assert_eq!(forward_hashes, back_hashes);
+        for (i, element) in forward.iter().enumerate() {
+            assert_eq!(element.header().header_seq(), i as u32);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn put_fails_if_head_moved() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        {
+            let mut store = SourceChainBuf::new(env.clone().into())?;
+            store
+                .genesis(fake_dna_hash(1), fixt!(AgentPubKey), None)
+                .await?;
+            env.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+
+        let mut chain = SourceChain::new(env.clone().into())?;
+        let expected_head = chain.chain_head()?.clone();
+        let entry_type = EntryType::App(fixt!(AppEntryType));
+        let mut entries = EntryFixturator::new(AppEntry);
+        let mut put = |entry: Entry| {
+            let header_builder = builder::Create {
+                entry_type: entry_type.clone(),
+                entry_hash: EntryHash::with_data_sync(&entry),
+            };
+            (header_builder, Some(entry))
+        };
+
+        // First append off the expected head succeeds
+        let (header_builder, entry) = put(entries.next().unwrap());
+        let first = chain
+            .put(header_builder, entry, Some(expected_head.clone()))
+            .await?;
+
+        // Second append off the same head fails cleanly
+        let len = chain.len();
+        let (header_builder, entry) = put(entries.next().unwrap());
+        let result = chain
+            .put(
+                header_builder.clone(),
+                entry.clone(),
+                Some(expected_head.clone()),
+            )
+            .await;
+        match result {
+            Err(SourceChainError::HeadMoved(expected, current)) => {
+                assert_eq!(expected, Some(expected_head));
+                assert_eq!(current, Some(first.clone()));
+            }
+            _ => panic!("Expected HeadMoved, got {:?}", result),
+        }
+        assert_eq!(chain.chain_head()?, &first);
+        assert_eq!(chain.len(), len);
+
+        // Retrying against the new head succeeds
+        chain.put(header_builder, entry, Some(first)).await?;
+        assert_eq!(chain.len(), len + 1);
+
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn put_fails_on_flush_if_committed_head_moved() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        {
+            let mut store = SourceChainBuf::new(env.clone().into())?;
+            store
+                .genesis(fake_dna_hash(1), fixt!(AgentPubKey), None)
+                .await?;
+            env.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+
+        // Two workspaces start from the same committed head
+        let mut chain1 = SourceChain::new(env.clone().into())?;
+        let mut chain2 = SourceChain::new(env.clone().into())?;
+        let expected_head = chain1.chain_head()?.clone();
+        let entry_type = EntryType::App(fixt!(AppEntryType));
+        let mut entries = EntryFixturator::new(AppEntry);
+        let mut put = |entry: Entry| {
+            let header_builder = builder::Create {
+                entry_type: entry_type.clone(),
+                entry_hash: EntryHash::with_data_sync(&entry),
+            };
+            (header_builder, Some(entry))
+        };
+
+        let (header_builder, entry) = put(entries.next().unwrap());
+        let first = chain1
+            .put(header_builder, entry, Some(expected_head.clone()))
+            .await?;
+        env.guard()
+            .with_commit(|writer| chain1.flush_to_txn(writer))?;
+
+        // The second workspace still sees the old head so the put is staged
+        // but the committed head no longer matches when it is flushed
+        let (header_builder, entry) = put(entries.next().unwrap());
+        chain2
+            .put(header_builder, entry, Some(expected_head.clone()))
+            .await?;
+        let result = env
+            .guard()
+            .with_commit(|writer| chain2.flush_to_txn(writer));
+        match result {
+            Err(SourceChainError::HeadMoved(expected, current)) => {
+                assert_eq!(expected, Some(expected_head));
+                assert_eq!(current, Some(first.clone()));
+            }
+            _ => panic!("Expected HeadMoved, got {:?}", result),
+        }
+
+        let chain = SourceChain::new(env.clone().into())?;
+        assert_eq!(chain.chain_head()?, &first);
+
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn closed_chain_rejects_commits() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        {
+            let mut store = SourceChainBuf::new(env.clone().into())?;
+            store
+                .genesis(fake_dna_hash(1), fixt!(AgentPubKey), None)
+                .await?;
+            env.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+
+        let mut chain = SourceChain::new(env.clone().into())?;
+        let closed = chain
+            .put(builder::CloseChain::new(fake_dna_hash(2)), None, None)
+            .await?;
+        env.guard()
+            .with_commit(|writer| chain.flush_to_txn(writer))?;
+
+        let mut chain = SourceChain::new(env.clone().into())?;
+        let entry = fixt!(Entry, AppEntry);
+        let header_builder = builder::Create {
+            entry_type: EntryType::App(fixt!(AppEntryType)),
+            entry_hash: EntryHash::with_data_sync(&entry),
+        };
+        let result = chain
+            .put(header_builder.clone(), Some(entry.clone()), None)
+            .await;
+        assert!(matches!(result, Err(SourceChainError::ChainClosed)));
+        let result = chain.put_batch(vec![(header_builder, Some(entry))]).await;
+        assert!(matches!(result, Err(SourceChainError::ChainClosed)));
+        assert_eq!(chain.chain_head()?, &closed);
+
+        Ok(())
+    }
+
     // @todo bring all this back when we want to administer cap claims better
     // #[tokio::test(threaded_scheduler)]
     // async fn test_get_cap_claim() -> SourceChainResult<()> {
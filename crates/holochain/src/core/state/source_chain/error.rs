@@ -19,6 +19,9 @@ pub enum SourceChainError {
     )]
     InvalidStructure(ChainInvalidReason),
 
+    #[error("The source chain has been closed and can't be committed to")]
+    ChainClosed,
+
     #[error("The source chain's head is pointing to an address which has no content.")]
     MissingHead,
 
@@ -88,6 +91,12 @@ pub enum ChainInvalidReason {
 
     #[error("Content was expected to definitely exist at this address, but didn't: {0}")]
     MissingData(EntryHash),
+
+    #[error("The chain sequence refers to a header which is not in the element vault: {0}")]
+    SequencedHeaderMissing(HeaderHash),
+
+    #[error("A header in the element vault is missing from the chain sequence and doesn't follow the chain head: {0}")]
+    UnsequencedHeader(HeaderHash),
 }
 
 pub type SourceChainResult<T> = Result<T, SourceChainError>;
@@ -0,0 +1,132 @@
+//! A small read cache for the source chain.
+//!
+//! A zome call will often read the same headers several times,
+//! for example the chain head. This cache is meant to live only as long
+//! as the workspace it's in, so it's never shared between calls.
+//! Headers are content addressed so a cached value can't go stale, and
+//! writes to the scratch space are added as they happen.
+
+use holo_hash::HeaderHash;
+use holochain_types::element::SignedHeaderHashed;
+use holochain_zome_types::element::Element;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+/// The default number of headers held by a [SourceChainReadCache]
+pub const DEFAULT_READ_CACHE_CAPACITY: usize = 64;
+
+#[derive(Debug, Default)]
+struct Inner {
+    headers: HashMap<HeaderHash, SignedHeaderHashed>,
+    elements: HashMap<HeaderHash, Element>,
+    order: VecDeque<HeaderHash>,
+    store_reads: u64,
+}
+
+impl Inner {
+    fn touch(&mut self, hash: &HeaderHash, capacity: usize) {
+        if self.headers.contains_key(hash) || self.elements.contains_key(hash) {
+            return;
+        }
+        self.order.push_back(hash.clone());
+        while self.order.len() > capacity {
+            if let Some(old) = self.order.pop_front() {
+                self.headers.remove(&old);
+                self.elements.remove(&old);
+            }
+        }
+    }
+}
+
+/// A bounded cache of headers and elements read from the source chain.
+/// When full the oldest entries are evicted first.
+#[derive(Debug)]
+pub struct SourceChainReadCache {
+    inner: Mutex<Inner>,
+    capacity: usize,
+}
+
+impl Default for SourceChainReadCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_READ_CACHE_CAPACITY)
+    }
+}
+
+impl SourceChainReadCache {
+    /// Create a cache holding up to `capacity` headers
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner::default()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Get a header from the cache or fall back to `fetch`.
+    /// Misses are not cached.
+    pub fn get_header<E, F>(
+        &self,
+        hash: &HeaderHash,
+        fetch: F,
+    ) -> Result<Option<SignedHeaderHashed>, E>
+    where
+        F: FnOnce() -> Result<Option<SignedHeaderHashed>, E>,
+    {
+        {
+            let inner = self.lock();
+            if let Some(header) = inner.headers.get(hash) {
+                return Ok(Some(header.clone()));
+            }
+            if let Some(element) = inner.elements.get(hash) {
+                return Ok(Some(element.signed_header().clone()));
+            }
+        }
+        let header = fetch()?;
+        let mut inner = self.lock();
+        inner.store_reads += 1;
+        if let Some(header) = &header {
+            inner.touch(hash, self.capacity);
+            inner.headers.insert(hash.clone(), header.clone());
+        }
+        Ok(header)
+    }
+
+    /// Get an element from the cache or fall back to `fetch`.
+    /// Misses are not cached.
+    pub fn get_element<E, F>(&self, hash: &HeaderHash, fetch: F) -> Result<Option<Element>, E>
+    where
+        F: FnOnce() -> Result<Option<Element>, E>,
+    {
+        if let Some(element) = self.lock().elements.get(hash) {
+            return Ok(Some(element.clone()));
+        }
+        let element = fetch()?;
+        let mut inner = self.lock();
+        inner.store_reads += 1;
+        if let Some(element) = &element {
+            inner.touch(hash, self.capacity);
+            inner.elements.insert(hash.clone(), element.clone());
+        }
+        Ok(element)
+    }
+
+    /// Add a header that was just written to the scratch space
+    pub fn put_header(&self, header: SignedHeaderHashed) {
+        let hash = header.header_address().clone();
+        let mut inner = self.lock();
+        inner.touch(&hash, self.capacity);
+        inner.headers.insert(hash, header);
+    }
+
+    /// The number of reads that missed the cache and went to the store
+    pub fn store_reads(&self) -> u64 {
+        self.lock().store_reads
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .expect("SourceChainReadCache lock poisoned")
+    }
+}
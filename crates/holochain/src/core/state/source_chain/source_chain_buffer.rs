@@ -1,4 +1,4 @@
-use super::ChainInvalidReason;
+use super::{ChainInvalidReason, SourceChainReadCache};
 use crate::core::state::{
     chain_sequence::ChainSequenceBuf,
     element_buf::{ElementBuf, HeaderCas},
@@ -14,12 +14,14 @@ use holochain_types::{
     HeaderHashed,
 };
 use holochain_zome_types::{header, Entry, Header};
+use std::collections::HashSet;
 use tracing::*;
 
 pub struct SourceChainBuf {
     elements: ElementBuf,
     sequence: ChainSequenceBuf,
     keystore: KeystoreSender,
+    read_cache: Option<SourceChainReadCache>,
 
     env: EnvironmentRead,
 }
@@ -30,6 +32,7 @@ impl SourceChainBuf {
             elements: ElementBuf::vault(env.clone(), true)?,
             sequence: ChainSequenceBuf::new(env.clone())?,
             keystore: env.keystore().clone(),
+            read_cache: None,
             env,
         })
     }
@@ -39,6 +42,7 @@ impl SourceChainBuf {
             elements: ElementBuf::vault(env.clone(), false)?,
             sequence: ChainSequenceBuf::new(env.clone())?,
             keystore: env.keystore().clone(),
+            read_cache: None,
             env,
         })
     }
@@ -47,6 +51,17 @@ impl SourceChainBuf {
         &self.env
     }
 
+    /// Cache up to `capacity` headers and elements read from this chain.
+    /// Only use this for short lived buffers like a zome call's workspace.
+    pub fn with_read_cache(mut self, capacity: usize) -> Self {
+        self.read_cache = Some(SourceChainReadCache::new(capacity));
+        self
+    }
+
+    pub fn read_cache(&self) -> Option<&SourceChainReadCache> {
+        self.read_cache.as_ref()
+    }
+
     // add a cache test only method that allows this to
     // be used with the cache database for testing
     // FIXME This should only be cfg(test) but that doesn't work with integration tests
@@ -55,6 +70,7 @@ impl SourceChainBuf {
             elements: ElementBuf::cache(env.clone())?,
             sequence: ChainSequenceBuf::new(env.clone())?,
             keystore: env.keystore().clone(),
+            read_cache: None,
             env,
         })
     }
@@ -88,11 +104,17 @@ impl SourceChainBuf {
 
     pub fn get_element(&self, k: &HeaderHash) -> SourceChainResult<Option<Element>> {
         debug!("GET {:?}", k);
-        self.elements.get_element(k)
+        match &self.read_cache {
+            Some(cache) => cache.get_element(k, || self.elements.get_element(k)),
+            None => self.elements.get_element(k),
+        }
     }
 
     pub fn get_header(&self, k: &HeaderHash) -> DatabaseResult<Option<SignedHeaderHashed>> {
-        self.elements.get_header(k)
+        match &self.read_cache {
+            Some(cache) => cache.get_header(k, || self.elements.get_header(k)),
+            None => self.elements.get_header(k),
+        }
     }
 
     pub fn get_entry(&self, k: &EntryHash) -> DatabaseResult<Option<EntryHashed>> {
@@ -132,30 +154,104 @@ impl SourceChainBuf {
         &self.sequence
     }
 
+    /// See [ChainSequenceBuf::expect_head]
+    pub fn expect_head(&mut self, expected_head: HeaderHash) -> SourceChainResult<()> {
+        self.sequence.expect_head(expected_head)
+    }
+
     /// Add a Element to the source chain, using a fully-formed Header
     pub async fn put_raw(
         &mut self,
         header: Header,
         maybe_entry: Option<Entry>,
     ) -> SourceChainResult<HeaderHash> {
-        let header = HeaderHashed::from_content_sync(header);
-        let header_address = header.as_hash().to_owned();
-        let signed_header = SignedHeaderHashed::new(&self.keystore, header).await?;
-        let maybe_entry = match maybe_entry {
-            None => None,
-            Some(entry) => Some(EntryHashed::from_content_sync(entry)),
-        };
+        let mut header_addresses = self
+            .put_elements(vec![(header, maybe_entry)], false)
+            .await?;
+        Ok(header_addresses
+            .pop()
+            .expect("A header address for every element put"))
+    }
+
+    /// Add several Elements to the source chain, all or nothing.
+    ///
+    /// Every header is hashed, checked against the chain head and its entry,
+    /// and signed before anything is staged. If any of that fails the
+    /// ChainSequence and Element stores are left untouched.
+    pub async fn put_raw_batch(
+        &mut self,
+        batch: Vec<(Header, Option<Entry>)>,
+    ) -> SourceChainResult<Vec<HeaderHash>> {
+        self.put_elements(batch, true).await
+    }
 
-        /*
-        FIXME: this needs to happen here.
-        if !header.validate_entry(maybe_entry) {
-            return Err(SourceChainError(ChainInvalidReason::HeaderAndEntryMismatch));
+    /// Check, sign and stage Elements, staging nothing if any of them fails.
+    /// With `check_prev_header` each header must follow the one before it,
+    /// starting from the current chain head.
+    async fn put_elements(
+        &mut self,
+        batch: Vec<(Header, Option<Entry>)>,
+        check_prev_header: bool,
+    ) -> SourceChainResult<Vec<HeaderHash>> {
+        self.check_open()?;
+        let mut head = self.chain_head().cloned();
+        let mut staged = Vec::with_capacity(batch.len());
+        for (header, maybe_entry) in batch {
+            if check_prev_header && header.prev_header() != head.as_ref() {
+                return Err(SourceChainError::InvalidPreviousHeader(format!(
+                    "Batch header {:?} does not follow chain head {:?}",
+                    header.prev_header(),
+                    head
+                )));
+            }
+            let maybe_entry = match (maybe_entry, header.entry_data()) {
+                (None, _) => None,
+                (Some(entry), Some((entry_hash, _))) => {
+                    let entry = EntryHashed::from_content_sync(entry);
+                    if entry.as_hash() != entry_hash {
+                        return Err(SourceChainError::InvalidStructure(
+                            ChainInvalidReason::HeaderAndEntryMismatch(entry.into_inner().1),
+                        ));
+                    }
+                    Some(entry)
+                }
+                (Some(entry), None) => {
+                    return Err(SourceChainError::InvalidStructure(
+                        ChainInvalidReason::HeaderAndEntryMismatch(
+                            EntryHashed::from_content_sync(entry).into_inner().1,
+                        ),
+                    ))
+                }
+            };
+            let header = HeaderHashed::from_content_sync(header);
+            head = Some(header.as_hash().clone());
+            let signed_header = SignedHeaderHashed::new(&self.keystore, header).await?;
+            staged.push((signed_header, maybe_entry));
+        }
+
+        let mut header_addresses = Vec::with_capacity(staged.len());
+        for (signed_header, maybe_entry) in staged {
+            let header_address = signed_header.header_address().clone();
+            if let Some(cache) = &self.read_cache {
+                cache.put_header(signed_header.clone());
+            }
+            self.sequence.put_header(header_address.clone())?;
+            self.elements.put(signed_header, maybe_entry)?;
+            header_addresses.push(header_address);
         }
-        */
+        Ok(header_addresses)
+    }
 
-        self.sequence.put_header(header_address.clone())?;
-        self.elements.put(signed_header, maybe_entry)?;
-        Ok(header_address)
+    /// Fail with [SourceChainError::ChainClosed] if the chain head is a `CloseChain`
+    fn check_open(&self) -> SourceChainResult<()> {
+        if let Some(head) = self.chain_head() {
+            if let Some(head) = self.get_header(head)? {
+                if let Header::CloseChain(_) = head.header() {
+                    return Err(SourceChainError::ChainClosed);
+                }
+            }
+        }
+        Ok(())
     }
 
     pub fn headers(&self) -> &HeaderCas<IntegratedPrefix> {
@@ -165,7 +261,15 @@ impl SourceChainBuf {
     // TODO: TK-01747: Make this check more robust maybe?
     // PERF: This call must be fast
     pub fn has_initialized(&self) -> bool {
-        self.len() > 3
+        match self.len() {
+            0..=3 => false,
+            // A migrated chain has an OpenChain header straight after genesis
+            4 => match self.get_at_index(3) {
+                Ok(Some(element)) => !matches!(element.header(), Header::OpenChain(_)),
+                _ => true,
+            },
+            _ => true,
+        }
     }
 
     /// Get the AgentPubKey from the entry committed to the chain.
@@ -272,6 +376,119 @@ impl SourceChainBuf {
 
         Ok(())
     }
+
+    /// Commit an `OpenChain` header declaring that this chain continues
+    /// the agent's closed chain on `prev_dna_hash`.
+    /// This must directly follow [SourceChainBuf::genesis].
+    pub async fn open_chain(&mut self, prev_dna_hash: DnaHash) -> SourceChainResult<HeaderHash> {
+        let author = self
+            .agent_pubkey()?
+            .ok_or(SourceChainError::InvalidStructure(
+                ChainInvalidReason::GenesisDataMissing,
+            ))?;
+        let prev_header = self
+            .chain_head()
+            .ok_or(SourceChainError::ChainEmpty)?
+            .clone();
+        let open_header = Header::OpenChain(header::OpenChain {
+            author,
+            timestamp: Timestamp::now().into(),
+            header_seq: self.len() as u32,
+            prev_header,
+            prev_dna_hash,
+        });
+        self.put_raw(open_header, None).await
+    }
+
+    /// Check that the ChainSequence and the element vault agree on this
+    /// chain's headers, repairing what an interrupted append left behind.
+    ///
+    /// A header of ours that is in the vault but not in the sequence and
+    /// follows the chain head is appended to the sequence if its entry made
+    /// it to the vault too, otherwise it is removed from the vault.
+    /// Anything else is returned as an unrecoverable
+    /// [SourceChainError::InvalidStructure].
+    ///
+    /// Repairs are staged in this buffer and need to be flushed.
+    pub fn heal(&mut self) -> SourceChainResult<Vec<ChainRepair>> {
+        let mut repairs = Vec::new();
+        let author = match self.sequence.get(0)? {
+            Some(dna_header) => match self.get_header(&dna_header)? {
+                Some(dna_header) => dna_header.header().author().clone(),
+                None => {
+                    return Err(SourceChainError::InvalidStructure(
+                        ChainInvalidReason::SequencedHeaderMissing(dna_header),
+                    ))
+                }
+            },
+            // Nothing has been appended yet
+            None => return Ok(repairs),
+        };
+
+        let mut sequenced = HashSet::with_capacity(self.len());
+        for i in 0..self.len() as u32 {
+            if let Some(header_hash) = self.sequence.get(i)? {
+                if !self.elements.headers().contains(&header_hash)? {
+                    return Err(SourceChainError::InvalidStructure(
+                        ChainInvalidReason::SequencedHeaderMissing(header_hash),
+                    ));
+                }
+                sequenced.insert(header_hash);
+            }
+        }
+
+        // The vault also holds headers integrated from other agents
+        let mut unsequenced: Vec<(HeaderHash, Header)> = fresh_reader!(self.env(), |r| {
+            DatabaseResult::Ok(
+                self.headers()
+                    .iter_fail(&r)?
+                    .filter(|h| {
+                        Ok(h.as_content().header().author() == &author
+                            && !sequenced.contains(h.as_hash()))
+                    })
+                    .map(|h| Ok((h.as_hash().clone(), h.as_content().header().clone())))
+                    .collect::<Vec<_>>()?,
+            )
+        })?;
+
+        while !unsequenced.is_empty() {
+            let head = self.chain_head().cloned();
+            let next = unsequenced
+                .iter()
+                .position(|(_, header)| header.prev_header() == head.as_ref());
+            let (header_hash, header) = match next {
+                Some(i) => unsequenced.remove(i),
+                None => {
+                    return Err(SourceChainError::InvalidStructure(
+                        ChainInvalidReason::UnsequencedHeader(unsequenced.remove(0).0),
+                    ))
+                }
+            };
+            let entry_missing = match header.entry_data() {
+                Some((entry_hash, _)) => self.get_entry(entry_hash)?.is_none(),
+                None => false,
+            };
+            if entry_missing {
+                warn!(?header_hash, "Rolling back partially appended header");
+                self.elements.delete(header_hash.clone(), None);
+                repairs.push(ChainRepair::RolledBack(header_hash));
+            } else {
+                warn!(?header_hash, "Completing partially appended header");
+                self.sequence.put_header(header_hash.clone())?;
+                repairs.push(ChainRepair::Completed(header_hash));
+            }
+        }
+        Ok(repairs)
+    }
+}
+
+/// A repair made by [SourceChainBuf::heal]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChainRepair {
+    /// The header was added to the ChainSequence
+    Completed(HeaderHash),
+    /// The header was removed from the element vault
+    RolledBack(HeaderHash),
 }
 
 impl BufferedStore for SourceChainBuf {
@@ -323,11 +540,17 @@ impl<'a> FallibleIterator for SourceChainBackwardIterator<'a> {
 #[cfg(test)]
 pub mod tests {
 
-    use super::SourceChainBuf;
-    use crate::core::state::source_chain::SourceChainResult;
+    use super::{ChainRepair, SourceChainBuf};
+    use crate::core::state::source_chain::{
+        ChainInvalidReason, SourceChainError, SourceChainResult,
+    };
+    use crate::fixt::*;
+    use ::fixt::prelude::*;
     use fallible_iterator::FallibleIterator;
-    use holochain_state::{prelude::*, test_utils::test_cell_env};
+    use holochain_state::{env::EnvironmentWrite, prelude::*, test_utils::test_cell_env};
     use holochain_types::{
+        element::{SignedHeaderHashed, SignedHeaderHashedExt},
+        entry::EntryHashed,
         prelude::*,
         test_utils::{fake_agent_pubkey_1, fake_dna_file},
         HeaderHashed,
@@ -506,4 +729,107 @@ pub mod tests {
         assert_eq!(signed_header.as_hash(), hashed.as_hash());
         assert_eq!(signed_header.as_hash(), signed_header.header_address());
     }
+
+    /// Write a header to the element vault without adding it to the
+    /// ChainSequence, as if the append was interrupted
+    async fn put_unsequenced(
+        env: &EnvironmentWrite,
+        header: Header,
+        entry: Option<Entry>,
+    ) -> SourceChainResult<HeaderHash> {
+        let mut store = SourceChainBuf::new(env.clone().into())?;
+        let header = HeaderHashed::from_content_sync(header);
+        let header_hash = header.as_hash().clone();
+        let signed_header = SignedHeaderHashed::new(env.keystore(), header).await?;
+        store
+            .elements
+            .put(signed_header, entry.map(EntryHashed::from_content_sync))?;
+        env.guard()
+            .with_commit(|writer| store.flush_to_txn(writer))?;
+        Ok(header_hash)
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn heal_repairs_partial_appends() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let arc = test_env.env();
+        let agent_pubkey = fake_agent_pubkey_1();
+        let dna_header = {
+            let mut store = SourceChainBuf::new(arc.clone().into())?;
+            store
+                .genesis(
+                    fake_dna_file("a").dna_hash().clone(),
+                    agent_pubkey.clone(),
+                    None,
+                )
+                .await?;
+            arc.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+            store.sequence.get(0)?.unwrap()
+        };
+        let create = |header_seq, prev_header, entry_author: AgentPubKey| {
+            Header::Create(header::Create {
+                author: agent_pubkey.clone(),
+                timestamp: Timestamp::now().into(),
+                header_seq,
+                prev_header,
+                entry_type: header::EntryType::AgentPubKey,
+                entry_hash: entry_author.into(),
+            })
+        };
+        let other_agent = fixt!(AgentPubKey);
+        let head = SourceChainBuf::new(arc.clone().into())?
+            .chain_head()
+            .unwrap()
+            .clone();
+
+        // The header and entry made it to the vault so the append is completed
+        let completed = put_unsequenced(
+            &arc,
+            create(3, head, other_agent.clone()),
+            Some(Entry::Agent(other_agent)),
+        )
+        .await?;
+        {
+            let mut store = SourceChainBuf::new(arc.clone().into())?;
+            assert_eq!(
+                store.heal()?,
+                vec![ChainRepair::Completed(completed.clone())]
+            );
+            arc.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+        let mut store = SourceChainBuf::new(arc.clone().into())?;
+        assert_eq!(store.chain_head(), Some(&completed));
+        assert_eq!(store.len(), 4);
+        assert_eq!(store.heal()?, vec![]);
+
+        // Only the header made it so the append is rolled back
+        let rolled_back =
+            put_unsequenced(&arc, create(4, completed.clone(), fixt!(AgentPubKey)), None).await?;
+        {
+            let mut store = SourceChainBuf::new(arc.clone().into())?;
+            assert_eq!(
+                store.heal()?,
+                vec![ChainRepair::RolledBack(rolled_back.clone())]
+            );
+            arc.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+        let store = SourceChainBuf::new(arc.clone().into())?;
+        assert_eq!(store.chain_head(), Some(&completed));
+        assert!(store.get_header(&rolled_back)?.is_none());
+
+        // A header that forks the chain can't be repaired
+        let fork = put_unsequenced(&arc, create(1, dna_header, fixt!(AgentPubKey)), None).await?;
+        let result = SourceChainBuf::new(arc.clone().into())?.heal();
+        match result {
+            Err(SourceChainError::InvalidStructure(ChainInvalidReason::UnsequencedHeader(h))) => {
+                assert_eq!(h, fork)
+            }
+            _ => panic!("Expected UnsequencedHeader, got {:?}", result),
+        }
+
+        Ok(())
+    }
 }
@@ -40,6 +40,9 @@ pub struct ValidationLimboValue {
     pub last_try: Option<Timestamp>,
     /// Number of times we have tried to validate the op
     pub num_tries: u32,
+    /// Number of times the op has been run through the zome's validate callback
+    #[serde(default)]
+    pub num_app_validations: u32,
 }
 
 /// The status of a [DhtOp] in limbo
@@ -47,12 +50,14 @@ pub struct ValidationLimboValue {
 pub enum ValidationLimboStatus {
     /// Is awaiting to be system validated
     Pending,
-    /// Is waiting for dependencies so the op can proceed to system validation
-    AwaitingSysDeps(AnyDhtHash),
+    /// Is waiting for dependencies so the op can proceed to system validation.
+    /// These are the headers and entries sys validation could not find.
+    AwaitingSysDeps(Vec<AnyDhtHash>),
     /// Is awaiting to be app validated
     SysValidated,
-    /// Is waiting for dependencies so the op can proceed to app validation
-    AwaitingAppDeps(AnyDhtHash),
+    /// Is waiting for dependencies so the op can proceed to app validation.
+    /// These are the dependencies the zome asked for.
+    AwaitingAppDeps(Vec<AnyDhtHash>),
     /// Has finished all validation but is still awaiting
     /// on a dependency to pass validation.
     PendingValidation,
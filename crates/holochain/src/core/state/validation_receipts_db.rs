@@ -10,6 +10,8 @@ use holochain_state::{
     error::{DatabaseError, DatabaseResult},
     prelude::{Readable, Writer},
 };
+use holochain_zome_types::entry_def::RequiredValidations;
+use std::collections::HashSet;
 
 /// The result of a DhtOp Validation.
 #[derive(
@@ -80,6 +82,31 @@ pub struct SignedValidationReceipt {
     pub validator_signature: Signature,
 }
 
+/// How many valid receipts an op has collected against how many
+/// the entry def of its entry requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ValidationReceiptStatus {
+    /// Valid receipts received from authorities
+    pub valid_receipts: usize,
+    /// The `required_validations` of the entry def
+    pub required: usize,
+}
+
+impl ValidationReceiptStatus {
+    /// Create a status from a receipt count and the required validations
+    pub fn new(valid_receipts: usize, required: RequiredValidations) -> Self {
+        Self {
+            valid_receipts,
+            required: u8::from(required) as usize,
+        }
+    }
+
+    /// The op has fewer valid receipts than its entry def requires
+    pub fn is_under_validated(&self) -> bool {
+        self.valid_receipts < self.required
+    }
+}
+
 /// The database/buffer for aggregating validation_receipts sent by remote
 /// nodes in charge of storage thereof.
 pub struct ValidationReceiptsBuf(KvvBufUsed<DhtOpHash, SignedValidationReceipt>);
@@ -108,20 +135,34 @@ impl ValidationReceiptsBuf {
     }
 
     /// Get the current valid receipt count for a given hash.
+    /// Each validator is counted once, however many receipts it sent.
     pub fn count_valid<'r, R: Readable>(
         &'r self,
         r: &'r R,
         dht_op_hash: &DhtOpHash,
     ) -> DatabaseResult<usize> {
-        let mut count = 0;
+        let mut validators = HashSet::new();
 
         let mut iter = self.list_receipts(r, dht_op_hash)?;
         while let Some(v) = iter.next()? {
             if v.receipt.validation_result == ValidationResult::Valid {
-                count += 1;
+                validators.insert(v.receipt.validator);
             }
         }
-        Ok(count)
+        Ok(validators.len())
+    }
+
+    /// Get the receipt status for a given hash.
+    pub fn status<'r, R: Readable>(
+        &'r self,
+        r: &'r R,
+        dht_op_hash: &DhtOpHash,
+        required: RequiredValidations,
+    ) -> DatabaseResult<ValidationReceiptStatus> {
+        Ok(ValidationReceiptStatus::new(
+            self.count_valid(r, dht_op_hash)?,
+            required,
+        ))
     }
 
     /// Add this receipt if it isn't already in the database.
@@ -225,4 +266,65 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_validation_receipts_count_each_validator_once() -> DatabaseResult<()> {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let keystore = holochain_state::test_utils::test_keystore();
+        let test_op_hash = fake_dht_op_hash(1);
+
+        // Two receipts from the same validator that differ in their bytes
+        let vr = fake_vr(&test_op_hash, &keystore).await;
+        let mut again = vr.clone();
+        again.validator_signature = Signature(vec![0; 64]);
+
+        let env_ref = env.guard();
+        let mut vr_buf = ValidationReceiptsBuf::new(&env)?;
+        vr_buf.add_if_unique(vr)?;
+        vr_buf.add_if_unique(again)?;
+        env_ref.with_commit(|writer| vr_buf.flush_to_txn(writer))?;
+
+        let reader = env_ref.reader()?;
+        let vr_buf = ValidationReceiptsBuf::new(&env)?;
+        assert_eq!(2, vr_buf.list_receipts(&reader, &test_op_hash)?.count()?);
+        assert_eq!(1, vr_buf.count_valid(&reader, &test_op_hash)?);
+
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_validation_receipt_status_reaches_required() -> DatabaseResult<()> {
+        holochain_types::observability::test_run().ok();
+
+        let test_env = holochain_state::test_utils::test_cell_env();
+        let env = test_env.env();
+        let keystore = holochain_state::test_utils::test_keystore();
+        let test_op_hash = fake_dht_op_hash(1);
+        let required = RequiredValidations::from(3);
+
+        let env_ref = env.guard();
+        let mut statuses = Vec::new();
+        for _ in 0..3 {
+            let mut vr_buf = ValidationReceiptsBuf::new(&env)?;
+            vr_buf.add_if_unique(fake_vr(&test_op_hash, &keystore).await)?;
+            env_ref.with_commit(|writer| vr_buf.flush_to_txn(writer))?;
+
+            let reader = env_ref.reader()?;
+            let vr_buf = ValidationReceiptsBuf::new(&env)?;
+            statuses.push(vr_buf.status(&reader, &test_op_hash, required)?);
+        }
+
+        assert_eq!(
+            statuses
+                .iter()
+                .map(|s| (s.valid_receipts, s.is_under_validated()))
+                .collect::<Vec<_>>(),
+            vec![(1, true), (2, true), (3, false)]
+        );
+
+        Ok(())
+    }
 }
@@ -4,7 +4,7 @@
 //! Every Workflow has an associated Workspace type.
 
 use super::source_chain::SourceChainError;
-use holochain_state::{error::DatabaseError, prelude::Writer};
+use holochain_state::{env::MapFullError, error::DatabaseError, prelude::Writer};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -17,6 +17,15 @@ pub enum WorkspaceError {
     SourceChainError(#[from] SourceChainError),
 }
 
+impl MapFullError for WorkspaceError {
+    fn is_map_full(&self) -> bool {
+        match self {
+            WorkspaceError::DatabaseError(e) => e.is_map_full(),
+            _ => false,
+        }
+    }
+}
+
 #[allow(missing_docs)]
 pub type WorkspaceResult<T> = Result<T, WorkspaceError>;
 
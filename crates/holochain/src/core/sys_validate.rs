@@ -15,11 +15,11 @@ use crate::conductor::{
 use fallible_iterator::FallibleIterator;
 use holochain_keystore::{AgentPubKeyExt, Signature};
 use holochain_state::{fresh_reader, prelude::PrefixType};
-use holochain_types::{header::NewEntryHeaderRef, Entry};
+use holochain_types::{dht_op::DhtOpLight, dna::DnaDef, header::NewEntryHeaderRef, Entry};
 use holochain_zome_types::{
     element::SignedHeaderHashed,
     entry_def::{EntryDef, EntryVisibility},
-    header::{AppEntryType, CreateLink, EntryType, Update},
+    header::{AppEntryType, CreateLink, Delete, EntryType, Update},
     link::LinkTag,
     Header,
 };
@@ -51,6 +51,9 @@ pub const MAX_ENTRY_SIZE: usize = 16_000_000;
 /// fast lookup so they need to be small.
 pub const MAX_TAG_SIZE: usize = 400;
 
+/// Ed25519 signatures are 64 bytes.
+pub const SIGNATURE_BYTES: usize = 64;
+
 /////////////
 // TODO: These checks are old and should probably be removed when
 // we implement the direct sys validation call
@@ -168,6 +171,60 @@ pub fn sys_validate_header(
 // Sys validation starts here
 //////////////////////////////
 
+/// Check the signature and every hash the header refers to are the right length.
+/// Ops arrive from the network without this being checked and
+/// the checks after this one can error or panic on a truncated hash or signature.
+pub fn check_well_formed(sig: &Signature, header: &Header) -> SysValidationResult<()> {
+    if sig.0.len() != SIGNATURE_BYTES {
+        return Err(ValidationOutcome::VerifySignature(sig.clone(), header.clone()).into());
+    }
+    let author = header.author().has_valid_length();
+    let prev_header = header
+        .prev_header()
+        .map_or(true, |prev_header| prev_header.has_valid_length());
+    let references = match header {
+        Header::Dna(dna) => dna.hash.has_valid_length(),
+        Header::AgentValidationPkg(_) | Header::InitZomesComplete(_) => true,
+        Header::CreateLink(link_add) => {
+            link_add.base_address.has_valid_length() && link_add.target_address.has_valid_length()
+        }
+        Header::DeleteLink(link_remove) => {
+            link_remove.base_address.has_valid_length()
+                && link_remove.link_add_address.has_valid_length()
+        }
+        Header::OpenChain(open) => open.prev_dna_hash.has_valid_length(),
+        Header::CloseChain(close) => close.new_dna_hash.has_valid_length(),
+        Header::Create(create) => create.entry_hash.has_valid_length(),
+        Header::Update(update) => {
+            update.entry_hash.has_valid_length()
+                && update.original_header_address.has_valid_length()
+                && update.original_entry_address.has_valid_length()
+        }
+        Header::Delete(delete) => {
+            delete.deletes_address.has_valid_length()
+                && delete.deletes_entry_address.has_valid_length()
+        }
+    };
+    if author && prev_header && references {
+        Ok(())
+    } else {
+        Err(ValidationOutcome::MalformedHash(header.clone()).into())
+    }
+}
+
+/// Check the basis an op is stored under is the type of hash that op is stored at.
+/// An [AnyDhtHash] deserializes whatever its prefix is, so a header hash
+/// where an entry hash belongs would otherwise be looked up in the wrong store.
+pub fn check_hash_type(op: &DhtOpLight) -> SysValidationResult<()> {
+    let basis = op.dht_basis();
+    let expected = op.get_type().basis_type();
+    if *basis.hash_type() == expected {
+        Ok(())
+    } else {
+        Err(ValidationOutcome::HashTypeMismatch(basis.clone(), expected).into())
+    }
+}
+
 /// Verify the signature for this header
 pub async fn verify_header_signature(sig: &Signature, header: &Header) -> SysValidationResult<()> {
     if header.author().verify_signature(sig, header).await? {
@@ -348,15 +405,22 @@ pub fn check_new_entry_header(header: &Header) -> SysValidationResult<()> {
     }
 }
 
-/// Check the entry size is under the MAX_ENTRY_SIZE
-pub fn check_entry_size(entry: &Entry) -> SysValidationResult<()> {
+/// The size at which app entries are rejected for this Dna.
+/// A Dna can lower the limit but never raise it above [MAX_ENTRY_SIZE].
+pub fn max_entry_size(dna: &DnaDef) -> usize {
+    dna.max_entry_size
+        .map_or(MAX_ENTRY_SIZE, |size| (size as usize).min(MAX_ENTRY_SIZE))
+}
+
+/// Check the entry size is under the max entry size
+pub fn check_entry_size(entry: &Entry, max_entry_size: usize) -> SysValidationResult<()> {
     match entry {
         Entry::App(bytes) => {
             let size = std::mem::size_of_val(&bytes.bytes()[..]);
-            if size < MAX_ENTRY_SIZE {
+            if size < max_entry_size {
                 Ok(())
             } else {
-                Err(ValidationOutcome::EntryTooLarge(size, MAX_ENTRY_SIZE).into())
+                Err(ValidationOutcome::EntryTooLarge(size, max_entry_size).into())
             }
         }
         // Other entry types are small
@@ -374,6 +438,34 @@ pub fn check_tag_size(tag: &LinkTag) -> SysValidationResult<()> {
     }
 }
 
+/// Check the link doesn't point back at its own base
+/// if the Dna rejects self links
+pub async fn check_self_link(
+    link_add: &CreateLink,
+    conductor_api: &impl CellConductorApiT,
+) -> SysValidationResult<()> {
+    if link_add.base_address != link_add.target_address {
+        return Ok(());
+    }
+    let dna_file = { conductor_api.get_this_dna().await };
+    let dna_file =
+        dna_file.ok_or_else(|| SysValidationError::DnaMissing(conductor_api.cell_id().clone()))?;
+    if dna_file.dna().reject_self_links {
+        Err(ValidationOutcome::SelfLink(link_add.base_address.clone()).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check the header an Update replaces is one that created an entry
+pub fn check_update_target(original_header: &Header) -> SysValidationResult<NewEntryHeaderRef<'_>> {
+    original_header.try_into().map_err(|_| {
+        SysValidationError::from(ValidationOutcome::UpdateTargetNotEntry(
+            original_header.clone(),
+        ))
+    })
+}
+
 /// Check a Update's entry type is the same for
 /// original and new entry.
 pub fn check_update_reference(
@@ -390,3 +482,37 @@ pub fn check_update_reference(
         .into())
     }
 }
+
+/// Check a Delete points at the entry its original header created
+/// and is authored by the agent that created it.
+pub fn check_delete_reference(
+    delete: &Delete,
+    original_header: &Header,
+) -> SysValidationResult<()> {
+    check_new_entry_header(original_header)?;
+    let original_entry_header: NewEntryHeaderRef = original_header
+        .try_into()
+        .expect("This can't fail due to the above check_new_entry_header");
+    if delete.deletes_entry_address != *original_entry_header.entry_hash() {
+        return Err(ValidationOutcome::DeleteEntryMismatch(
+            delete.deletes_entry_address.clone(),
+            original_entry_header.entry_hash().clone(),
+        )
+        .into());
+    }
+    check_delete_author(delete, original_header)
+}
+
+/// Check a Delete is authored by the agent that created what it deletes.
+/// Deletes made through a capability grant are still authored
+/// by the grantor so they pass this check.
+pub fn check_delete_author(delete: &Delete, original_header: &Header) -> SysValidationResult<()> {
+    if delete.author != *original_header.author() {
+        return Err(ValidationOutcome::DeleteNotByAuthor(
+            delete.author.clone(),
+            original_header.author().clone(),
+        )
+        .into());
+    }
+    Ok(())
+}
@@ -1,9 +1,9 @@
 use super::SourceChainError;
 use crate::{
-    conductor::entry_def_store::error::EntryDefStoreError,
+    conductor::{config::ValidationCategory, entry_def_store::error::EntryDefStoreError},
     core::state::cascade::error::CascadeError,
 };
-use holo_hash::{AnyDhtHash, HeaderHash};
+use holo_hash::{hash_type::AnyDht, AgentPubKey, AnyDhtHash, EntryHash, HeaderHash};
 use holochain_keystore::{KeystoreError, Signature};
 use holochain_state::error::DatabaseError;
 use holochain_types::cell::CellId;
@@ -48,20 +48,32 @@ pub type SysValidationResult<T> = Result<T, SysValidationError>;
 /// failed validation.
 #[derive(Error, Debug)]
 pub enum ValidationOutcome {
+    #[error("The element {0:?} has already been deleted")]
+    AlreadyDeleted(HeaderHash),
+    #[error("Delete references entry {0:?} but the deleted header created entry {1:?}")]
+    DeleteEntryMismatch(EntryHash, EntryHash),
+    #[error("Delete was authored by {0:?} but the deleted header was authored by {1:?}")]
+    DeleteNotByAuthor(AgentPubKey, AgentPubKey),
     #[error("The dependency {0:?} was not found on the DHT")]
     DepMissingFromDht(AnyDhtHash),
     #[error("The app entry type {0:?} entry def id was out of range")]
     EntryDefId(AppEntryType),
     #[error("The entry has a different hash to the header's entry hash")]
     EntryHash,
-    #[error("The entry size {0} was bigger then the MAX_ENTRY_SIZE {1}")]
+    #[error("The entry size {0} was bigger then the max entry size {1}")]
     EntryTooLarge(usize, usize),
     #[error("The entry has a different type to the header's entry type")]
     EntryType,
     #[error("The app entry type {0:?} visibility didn't match the zome")]
     EntryVisibility(AppEntryType),
+    #[error("The op's basis {0:?} should be a {1:?} hash")]
+    HashTypeMismatch(AnyDhtHash, AnyDht),
+    #[error("The header {0:?} refers to a hash which is the wrong length")]
+    MalformedHash(Header),
     #[error("The link tag size {0} was bigger then the MAX_TAG_SIZE {1}")]
     TagTooLarge(usize, usize),
+    #[error("The link add {0:?} has already been removed")]
+    LinkAlreadyRemoved(HeaderHash),
     #[error("The header {0:?} was expected to be a link add header")]
     NotCreateLink(HeaderHash),
     #[error("The header was expected to be a new entry header but was a {0:?}")]
@@ -72,6 +84,10 @@ pub enum ValidationOutcome {
     PrevHeaderError(#[from] PrevHeaderError),
     #[error("StoreEntry should not be gossiped for private entries")]
     PrivateEntry,
+    #[error("The link's base and target are both {0:?} but this Dna rejects self links")]
+    SelfLink(EntryHash),
+    #[error("Update replaces {0:?} which is not an entry creating header")]
+    UpdateTargetNotEntry(Header),
     #[error("Update original EntryType: {0:?} doesn't match new EntryType {1:?}")]
     UpdateTypeMismatch(EntryType, EntryType),
     #[error("Signature {0:?} failed to verify for Header {1:?}")]
@@ -80,6 +96,42 @@ pub enum ValidationOutcome {
     ZomeId(AppEntryType),
 }
 
+impl ValidationOutcome {
+    /// The category a [ValidationProfile](crate::conductor::config::ValidationProfile)
+    /// chooses the response to this outcome by.
+    /// None for the security critical outcomes, including private entries
+    /// reaching the DHT, which always reject, and for missing dependencies,
+    /// which always wait.
+    pub fn category(&self) -> Option<ValidationCategory> {
+        use ValidationCategory::*;
+        match self {
+            ValidationOutcome::AlreadyDeleted(_) => Some(Reference),
+            ValidationOutcome::DeleteEntryMismatch(_, _) => Some(Reference),
+            ValidationOutcome::DeleteNotByAuthor(_, _) => None,
+            ValidationOutcome::DepMissingFromDht(_) => None,
+            ValidationOutcome::EntryDefId(_) => Some(EntryDef),
+            ValidationOutcome::EntryHash => None,
+            ValidationOutcome::EntryTooLarge(_, _) => Some(Size),
+            ValidationOutcome::EntryType => Some(EntryDef),
+            ValidationOutcome::EntryVisibility(_) => Some(EntryDef),
+            ValidationOutcome::HashTypeMismatch(_, _) => None,
+            ValidationOutcome::MalformedHash(_) => None,
+            ValidationOutcome::TagTooLarge(_, _) => Some(Size),
+            ValidationOutcome::LinkAlreadyRemoved(_) => Some(Reference),
+            ValidationOutcome::NotCreateLink(_) => Some(Reference),
+            ValidationOutcome::NotNewEntry(_) => Some(Reference),
+            ValidationOutcome::NotHoldingDep(_) => None,
+            ValidationOutcome::PrevHeaderError(_) => None,
+            ValidationOutcome::PrivateEntry => None,
+            ValidationOutcome::SelfLink(_) => Some(SelfLink),
+            ValidationOutcome::UpdateTargetNotEntry(_) => Some(Reference),
+            ValidationOutcome::UpdateTypeMismatch(_, _) => Some(Reference),
+            ValidationOutcome::VerifySignature(_, _) => None,
+            ValidationOutcome::ZomeId(_) => Some(EntryDef),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum PrevHeaderError {
     #[error("Root of source chain must be Dna")]
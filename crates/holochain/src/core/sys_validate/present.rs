@@ -2,7 +2,9 @@
 //! either being held locally or existing on the DHT
 use super::*;
 use crate::core::workflow::sys_validation_workflow::types::{CheckLevel, Dependency};
+use holo_hash::hash_type::AnyDht;
 use holochain_p2p::HolochainP2pCellT;
+use std::collections::HashMap;
 
 macro_rules! check_holding {
     ($f:ident, $($hash:expr),+ => $dep:ident, $($ws:expr),+ ) => {{
@@ -38,6 +40,22 @@ macro_rules! check_holding_meta {
     };
 }
 
+/// Count where a dependency was found,
+/// or that it wasn't held so the op has to wait for it
+fn record_dependency<T>(
+    workspace: &SysValidationWorkspace,
+    dep: SysValidationResult<Dependency<T>>,
+) -> SysValidationResult<Dependency<T>> {
+    match &dep {
+        Ok(dep) => workspace.dependency_counters.record(dep),
+        Err(SysValidationError::ValidationOutcome(ValidationOutcome::NotHoldingDep(_))) => {
+            workspace.dependency_counters.record_not_held()
+        }
+        Err(_) => (),
+    }
+    dep
+}
+
 /// Check validated and integrated stores for a dependant op
 pub async fn check_holding_entry_all(
     hash: &EntryHash,
@@ -45,10 +63,12 @@ pub async fn check_holding_entry_all(
     network: impl HolochainP2pCellT,
     check_level: CheckLevel,
 ) -> SysValidationResult<Dependency<Element>> {
-    match check_level {
+    let dep = match check_level {
         CheckLevel::Proof => check_holding_entry_inner(hash, workspace).await,
+        CheckLevel::Cached => entry_exists(hash.clone(), workspace, network, true).await,
         CheckLevel::Claim => check_entry_exists(hash.clone(), workspace, network).await,
-    }
+    };
+    record_dependency(workspace, dep)
 }
 
 async fn check_holding_entry_inner(
@@ -66,10 +86,12 @@ pub async fn check_holding_header_all(
     network: impl HolochainP2pCellT,
     check_level: CheckLevel,
 ) -> SysValidationResult<Dependency<SignedHeaderHashed>> {
-    match check_level {
+    let dep = match check_level {
         CheckLevel::Proof => check_holding_header_inner(hash, workspace).await,
+        CheckLevel::Cached => header_exists(hash.clone(), workspace, network, true).await,
         CheckLevel::Claim => check_header_exists(hash.clone(), workspace, network).await,
-    }
+    };
+    record_dependency(workspace, dep)
 }
 async fn check_holding_header_inner(
     hash: &HeaderHash,
@@ -86,10 +108,12 @@ pub async fn check_holding_element_all(
     network: impl HolochainP2pCellT,
     check_level: CheckLevel,
 ) -> SysValidationResult<Dependency<Element>> {
-    match check_level {
+    let dep = match check_level {
         CheckLevel::Proof => check_holding_element_inner(hash, workspace).await,
+        CheckLevel::Cached => element_exists(hash.clone(), workspace, network, true).await,
         CheckLevel::Claim => check_element_exists(hash.clone(), workspace, network).await,
-    }
+    };
+    record_dependency(workspace, dep)
 }
 async fn check_holding_element_inner(
     hash: &HeaderHash,
@@ -109,14 +133,18 @@ pub async fn check_holding_prev_header_all(
     network: impl HolochainP2pCellT,
     check_level: CheckLevel,
 ) -> SysValidationResult<Dependency<SignedHeaderHashed>> {
-    match check_level {
+    let dep = match check_level {
         CheckLevel::Proof => {
             check_holding_prev_header_inner(author, prev_header_hash, workspace).await
         }
+        CheckLevel::Cached => {
+            header_exists(prev_header_hash.clone(), workspace, network, true).await
+        }
         CheckLevel::Claim => {
             check_header_exists(prev_header_hash.clone(), workspace, network).await
         }
-    }
+    };
+    record_dependency(workspace, dep)
 }
 
 async fn check_holding_prev_header_inner(
@@ -140,12 +168,14 @@ pub async fn check_holding_store_entry_all(
     network: impl HolochainP2pCellT,
     check_level: CheckLevel,
 ) -> SysValidationResult<Dependency<Element>> {
-    match check_level {
+    let dep = match check_level {
         CheckLevel::Proof => {
             check_holding_store_entry_inner(entry_hash, header_hash, workspace).await
         }
+        CheckLevel::Cached => element_exists(header_hash.clone(), workspace, network, true).await,
         CheckLevel::Claim => check_element_exists(header_hash.clone(), workspace, network).await,
-    }
+    };
+    record_dependency(workspace, dep)
 }
 
 async fn check_holding_store_entry_inner(
@@ -168,10 +198,12 @@ pub async fn check_holding_link_add_all(
     network: impl HolochainP2pCellT,
     check_level: CheckLevel,
 ) -> SysValidationResult<Dependency<SignedHeaderHashed>> {
-    match check_level {
+    let dep = match check_level {
         CheckLevel::Proof => check_holding_link_add_inner(header_hash, workspace).await,
+        CheckLevel::Cached => header_exists(header_hash.clone(), workspace, network, true).await,
         CheckLevel::Claim => check_header_exists(header_hash.clone(), workspace, network).await,
-    }
+    };
+    record_dependency(workspace, dep)
 }
 
 async fn check_holding_link_add_inner(
@@ -247,6 +279,71 @@ pub(super) async fn check_link_in_metadata<P: PrefixType>(
     Ok(())
 }
 
+/// Check no link remove other than `link_remove_hash`
+/// has already been registered on this link add
+pub(super) async fn check_link_not_removed<P: PrefixType>(
+    link_add_hash: &HeaderHash,
+    link_remove_hash: &HeaderHash,
+    meta_vault: &impl MetadataBufT<P>,
+) -> SysValidationResult<()> {
+    fresh_reader!(meta_vault.env(), |r| {
+        let already_removed = meta_vault
+            .get_link_removes_on_link_add(&r, link_add_hash.clone())?
+            .any(|remove| Ok(remove.header_hash != *link_remove_hash))?;
+        if already_removed {
+            Err(ValidationOutcome::LinkAlreadyRemoved(link_add_hash.clone()).into())
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// Check the validated and integrated stores for an earlier
+/// remove of this link add.
+/// Pending removes are ignored so two removes racing through
+/// validation don't reject each other.
+pub async fn check_link_not_removed_all(
+    link_add_hash: &HeaderHash,
+    link_remove_hash: &HeaderHash,
+    workspace: &SysValidationWorkspace,
+) -> SysValidationResult<()> {
+    check_link_not_removed(link_add_hash, link_remove_hash, &workspace.meta_vault).await?;
+    check_link_not_removed(link_add_hash, link_remove_hash, &workspace.meta_judged).await?;
+    Ok(())
+}
+
+/// Check no delete other than `delete_hash`
+/// has already been registered on this header
+pub(super) async fn check_not_deleted<P: PrefixType>(
+    header_hash: &HeaderHash,
+    delete_hash: &HeaderHash,
+    meta_vault: &impl MetadataBufT<P>,
+) -> SysValidationResult<()> {
+    fresh_reader!(meta_vault.env(), |r| {
+        let already_deleted = meta_vault
+            .get_deletes_on_header(&r, header_hash.clone())?
+            .any(|delete| Ok(delete.header_hash != *delete_hash))?;
+        if already_deleted {
+            Err(ValidationOutcome::AlreadyDeleted(header_hash.clone()).into())
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// Check the validated and integrated stores for an earlier
+/// delete of this header.
+/// Like [check_link_not_removed_all] pending deletes are ignored.
+pub async fn check_not_deleted_all(
+    header_hash: &HeaderHash,
+    delete_hash: &HeaderHash,
+    workspace: &SysValidationWorkspace,
+) -> SysValidationResult<()> {
+    check_not_deleted(header_hash, delete_hash, &workspace.meta_vault).await?;
+    check_not_deleted(header_hash, delete_hash, &workspace.meta_judged).await?;
+    Ok(())
+}
+
 /// Check the prev header is in the metadata
 async fn check_prev_header_in_metadata_all(
     author: &AgentPubKey,
@@ -329,42 +426,151 @@ pub async fn check_entry_exists(
     entry_hash: EntryHash,
     workspace: &mut SysValidationWorkspace,
     network: impl HolochainP2pCellT,
+) -> SysValidationResult<Dependency<Element>> {
+    entry_exists(entry_hash, workspace, network, false).await
+}
+
+/// Check that the header exists on the dht
+pub async fn check_header_exists(
+    hash: HeaderHash,
+    workspace: &mut SysValidationWorkspace,
+    network: impl HolochainP2pCellT,
+) -> SysValidationResult<Dependency<SignedHeaderHashed>> {
+    header_exists(hash, workspace, network, false).await
+}
+
+/// Check that the element exists on the dht
+pub async fn check_element_exists(
+    hash: HeaderHash,
+    workspace: &mut SysValidationWorkspace,
+    network: impl HolochainP2pCellT,
+) -> SysValidationResult<Dependency<Element>> {
+    element_exists(hash, workspace, network, false).await
+}
+
+/// Check that all of an op's dependencies exist on the dht,
+/// like [check_entry_exists] or [check_element_exists] for each hash.
+/// The dependencies that aren't held are retrieved from the network at once,
+/// so an op that declares several up front doesn't wait on each in turn.
+/// The dependencies are returned in the order of `hashes`.
+pub async fn check_deps_exist(
+    hashes: Vec<AnyDhtHash>,
+    workspace: &mut SysValidationWorkspace,
+    network: impl HolochainP2pCellT + Clone,
+) -> SysValidationResult<Vec<Dependency<Element>>> {
+    let mut held = Vec::with_capacity(hashes.len());
+    let mut missing = Vec::new();
+    for hash in &hashes {
+        let dep = match *hash.hash_type() {
+            AnyDht::Entry => check_holding_entry_inner(&hash.clone().into(), workspace).await,
+            AnyDht::Header => check_holding_element_inner(&hash.clone().into(), workspace).await,
+        };
+        match dep {
+            Ok(dep) => held.push(Some(dep)),
+            Err(SysValidationError::ValidationOutcome(ValidationOutcome::NotHoldingDep(_))) => {
+                workspace.dependency_counters.record_network_retrieval();
+                missing.push(hash.clone());
+                held.push(None);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    let retrieved = if missing.is_empty() {
+        HashMap::new()
+    } else {
+        workspace
+            .cascade(network)
+            .retrieve_many(missing, Default::default())
+            .await?
+    };
+    hashes
+        .into_iter()
+        .zip(held)
+        .map(|(hash, dep)| match dep {
+            Some(dep) => Ok(dep),
+            None => match retrieved.get(&hash) {
+                Some(el) => Ok(Dependency::Claim(el.clone())),
+                None => Err(ValidationOutcome::DepMissingFromDht(hash).into()),
+            },
+        })
+        .collect()
+}
+
+/// Check the local stores then, if `cached`, the workspace's dependency cache
+/// before retrieving the entry from the dht.
+/// A retrieved entry is cached if `cached`.
+async fn entry_exists(
+    entry_hash: EntryHash,
+    workspace: &mut SysValidationWorkspace,
+    network: impl HolochainP2pCellT,
+    cached: bool,
 ) -> SysValidationResult<Dependency<Element>> {
     check_holding_entry!(workspace, check_holding_entry, &entry_hash);
+    let hash: AnyDhtHash = entry_hash.into();
+    if cached {
+        if let Some(el) = workspace.dep_cache.get_element(&hash) {
+            return Ok(Dependency::Claim(el));
+        }
+    }
+    workspace.dependency_counters.record_network_retrieval();
     let mut cascade = workspace.cascade(network);
     let el = cascade
-        .retrieve(entry_hash.clone().into(), Default::default())
+        .retrieve(hash.clone(), Default::default())
         .await?
-        .ok_or_else(|| ValidationOutcome::DepMissingFromDht(entry_hash.into()))?;
+        .ok_or_else(|| ValidationOutcome::DepMissingFromDht(hash.clone()))?;
+    if cached {
+        workspace.dep_cache.put_element(hash, el.clone());
+    }
     Ok(Dependency::Claim(el))
 }
 
-/// Check that the header exists on the dht
-pub async fn check_header_exists(
+/// Like [entry_exists] for a header
+async fn header_exists(
     hash: HeaderHash,
     workspace: &mut SysValidationWorkspace,
     network: impl HolochainP2pCellT,
+    cached: bool,
 ) -> SysValidationResult<Dependency<SignedHeaderHashed>> {
     check_holding_el!(workspace, check_holding_header, &hash);
+    if cached {
+        if let Some(h) = workspace.dep_cache.get_header(&hash) {
+            return Ok(Dependency::Claim(h));
+        }
+    }
+    workspace.dependency_counters.record_network_retrieval();
     let mut cascade = workspace.cascade(network);
     let h = cascade
         .retrieve_header(hash.clone(), Default::default())
         .await?
-        .ok_or_else(|| ValidationOutcome::DepMissingFromDht(hash.into()))?;
+        .ok_or_else(|| ValidationOutcome::DepMissingFromDht(hash.clone().into()))?;
+    if cached {
+        workspace.dep_cache.put_header(hash, h.clone());
+    }
     Ok(Dependency::Claim(h))
 }
 
-/// Check that the element exists on the dht
-pub async fn check_element_exists(
+/// Like [entry_exists] for an element
+async fn element_exists(
     hash: HeaderHash,
     workspace: &mut SysValidationWorkspace,
     network: impl HolochainP2pCellT,
+    cached: bool,
 ) -> SysValidationResult<Dependency<Element>> {
     check_holding_el!(workspace, check_holding_element, &hash);
+    let hash: AnyDhtHash = hash.into();
+    if cached {
+        if let Some(el) = workspace.dep_cache.get_element(&hash) {
+            return Ok(Dependency::Claim(el));
+        }
+    }
+    workspace.dependency_counters.record_network_retrieval();
     let mut cascade = workspace.cascade(network);
     let el = cascade
-        .retrieve(hash.clone().into(), Default::default())
+        .retrieve(hash.clone(), Default::default())
         .await?
-        .ok_or_else(|| ValidationOutcome::DepMissingFromDht(hash.into()))?;
+        .ok_or_else(|| ValidationOutcome::DepMissingFromDht(hash.clone()))?;
+    if cached {
+        workspace.dep_cache.put_element(hash, el.clone());
+    }
     Ok(Dependency::Claim(el))
 }
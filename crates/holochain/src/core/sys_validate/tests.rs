@@ -1,10 +1,16 @@
 use super::*;
-use crate::{conductor::api::MockCellConductorApi, meta_mock};
+use crate::core::workflow::sys_validation_workflow::types::CheckLevel;
+use crate::{
+    conductor::api::MockCellConductorApi,
+    core::state::metadata::{LinkMetaVal, MockMetadataBuf},
+    meta_mock,
+};
 use ::fixt::prelude::*;
 use error::SysValidationError;
 use holo_hash::fixt::*;
 use holochain_keystore::AgentPubKeyExt;
-use holochain_serialized_bytes::SerializedBytes;
+use holochain_serialized_bytes::{SerializedBytes, UnsafeBytes};
+use holochain_state::error::DatabaseError;
 use holochain_state::{env::EnvironmentRead, test_utils::test_cell_env};
 use holochain_types::{
     dna::{DnaDef, DnaFile},
@@ -129,7 +135,9 @@ async fn verify_header_signature_test() {
 
     assert_matches!(
         verify_header_signature(&wrong_signature, &header).await,
-        Err(SysValidationError::ValidationOutcome(ValidationOutcome::VerifySignature(_, _)))
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::VerifySignature(_, _)
+        ))
     );
 
     assert_matches!(
@@ -213,7 +221,9 @@ async fn check_prev_header_in_metadata_test() {
     // No previous header on this hash
     assert_matches!(
         check_prev_header_in_metadata(&author, &header_fixt.next().unwrap(), &metadata).await,
-        Err(SysValidationError::ValidationOutcome(ValidationOutcome::NotHoldingDep(_)))
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotHoldingDep(_)
+        ))
     );
 }
 
@@ -254,32 +264,26 @@ async fn check_previous_seq() {
     prev_header.header_seq = 2;
     assert_matches!(
         check_prev_seq(&header.clone().into(), &prev_header.clone().into()),
-        Err(
-            SysValidationError::ValidationOutcome(
-                ValidationOutcome::PrevHeaderError(PrevHeaderError::InvalidSeq(_, _)),
-            ),
-        )
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::PrevHeaderError(PrevHeaderError::InvalidSeq(_, _)),
+        ),)
     );
 
     prev_header.header_seq = 3;
     assert_matches!(
         check_prev_seq(&header.clone().into(), &prev_header.clone().into()),
-        Err(
-            SysValidationError::ValidationOutcome(
-                ValidationOutcome::PrevHeaderError(PrevHeaderError::InvalidSeq(_, _)),
-            ),
-        )
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::PrevHeaderError(PrevHeaderError::InvalidSeq(_, _)),
+        ),)
     );
 
     header.header_seq = 0;
     prev_header.header_seq = 0;
     assert_matches!(
         check_prev_seq(&header.clone().into(), &prev_header.clone().into()),
-        Err(
-            SysValidationError::ValidationOutcome(
-                ValidationOutcome::PrevHeaderError(PrevHeaderError::InvalidSeq(_, _)),
-            ),
-        )
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::PrevHeaderError(PrevHeaderError::InvalidSeq(_, _)),
+        ),)
     );
 }
 
@@ -332,21 +336,35 @@ async fn check_entry_hash_test() {
     assert_matches!(check_entry_hash(&eh, &entry).await, Ok(()));
     assert_matches!(
         check_new_entry_header(&fixt!(CreateLink).into()),
-        Err(SysValidationError::ValidationOutcome(ValidationOutcome::NotNewEntry(_)))
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotNewEntry(_)
+        ))
     );
 }
 
 #[tokio::test(threaded_scheduler)]
 async fn check_entry_size_test() {
-    // let tiny = Entry::App(SerializedBytes::from(UnsafeBytes::from(vec![0; 1])));
-    // let bytes = (0..16_000_000).map(|_| 0u8).into_iter().collect::<Vec<_>>();
-    // let huge = Entry::App(SerializedBytes::from(UnsafeBytes::from(bytes)));
-    // assert_matches!(check_entry_size(&tiny), Ok(()));
-
-    // assert_matches!(
-    //     check_entry_size(&huge),
-    //     Err(SysValidationError::ValidationOutcome(ValidationOutcome::EntryTooLarge(_, _)))
-    // );
+    let tiny = Entry::app(SerializedBytes::from(UnsafeBytes::from(vec![0; 1]))).unwrap();
+    let big = Entry::app(SerializedBytes::from(UnsafeBytes::from(vec![0; 100]))).unwrap();
+
+    // # The conductor-wide limit applies when the dna doesn't set one
+    let mut dna_def = fixt!(DnaDef);
+    assert_eq!(max_entry_size(&dna_def), MAX_ENTRY_SIZE);
+    assert_matches!(check_entry_size(&big, max_entry_size(&dna_def)), Ok(()));
+
+    // # The dna can lower the limit
+    dna_def.max_entry_size = Some(100);
+    assert_matches!(check_entry_size(&tiny, max_entry_size(&dna_def)), Ok(()));
+    assert_matches!(
+        check_entry_size(&big, max_entry_size(&dna_def)),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::EntryTooLarge(100, 100)
+        ))
+    );
+
+    // # But not raise it
+    dna_def.max_entry_size = Some(MAX_ENTRY_SIZE as u32 * 2);
+    assert_eq!(max_entry_size(&dna_def), MAX_ENTRY_SIZE);
 }
 
 #[tokio::test(threaded_scheduler)]
@@ -372,7 +390,9 @@ async fn check_update_reference_test() {
 
     assert_matches!(
         check_update_reference(&eu, &NewEntryHeaderRef::from(&ec)),
-        Err(SysValidationError::ValidationOutcome(ValidationOutcome::UpdateTypeMismatch(_, _)))
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::UpdateTypeMismatch(_, _)
+        ))
     );
 
     // Different entry type
@@ -380,7 +400,53 @@ async fn check_update_reference_test() {
 
     assert_matches!(
         check_update_reference(&eu, &NewEntryHeaderRef::from(&ec)),
-        Err(SysValidationError::ValidationOutcome(ValidationOutcome::UpdateTypeMismatch(_, _)))
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::UpdateTypeMismatch(_, _)
+        ))
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn check_update_target_test() {
+    let test_env = test_cell_env();
+
+    // # Updating an entry creating header
+    let create: Header = fixt!(Create).into();
+    assert_matches!(check_update_target(&create), Ok(_));
+    let update: Header = fixt!(Update).into();
+    assert_matches!(check_update_target(&update), Ok(_));
+
+    // # Updating a header without an entry
+    let link_add: Header = fixt!(CreateLink).into();
+    assert_matches!(
+        check_update_target(&link_add),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::UpdateTargetNotEntry(_)
+        ))
+    );
+    let delete: Header = fixt!(Delete).into();
+    assert_matches!(
+        check_update_target(&delete),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::UpdateTargetNotEntry(_)
+        ))
+    );
+
+    // # Updating a target we aren't holding waits for it
+    let mut workspace = SysValidationWorkspace::new(test_env.env.clone().into()).unwrap();
+    let eu = fixt!(Update);
+    assert_matches!(
+        check_holding_store_entry_all(
+            &eu.original_entry_address,
+            &eu.original_header_address,
+            &mut workspace,
+            holochain_p2p::MockHolochainP2pCellT::new(),
+            CheckLevel::Proof,
+        )
+        .await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotHoldingDep(_)
+        ))
     );
 }
 
@@ -393,7 +459,9 @@ async fn check_link_tag_size_test() {
 
     assert_matches!(
         check_tag_size(&huge),
-        Err(SysValidationError::ValidationOutcome(ValidationOutcome::TagTooLarge(_, _)))
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::TagTooLarge(_, _)
+        ))
     );
 }
 
@@ -407,6 +475,10 @@ async fn check_app_entry_type_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::EntryDefs.into()].into(),
+            reject_self_links: false,
+            max_entry_size: None,
+            update_conflict_policy: Default::default(),
+            sensitive_fns: Default::default(),
         },
         vec![TestWasm::EntryDefs.into()],
     )
@@ -439,14 +511,18 @@ async fn check_app_entry_type_test() {
     let aet = AppEntryType::new(0.into(), 1.into(), EntryVisibility::Public);
     assert_matches!(
         check_app_entry_type(&aet, &conductor_api).await,
-        Err(SysValidationError::ValidationOutcome(ValidationOutcome::ZomeId(_)))
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::ZomeId(_)
+        ))
     );
 
     // ## EntryId is out of range
     let aet = AppEntryType::new(10.into(), 0.into(), EntryVisibility::Public);
     assert_matches!(
         check_app_entry_type(&aet, &conductor_api).await,
-        Err(SysValidationError::ValidationOutcome(ValidationOutcome::EntryDefId(_)))
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::EntryDefId(_)
+        ))
     );
 
     // ## EntryId is in range for dna
@@ -455,7 +531,9 @@ async fn check_app_entry_type_test() {
     let aet = AppEntryType::new(0.into(), 0.into(), EntryVisibility::Private);
     assert_matches!(
         check_app_entry_type(&aet, &conductor_api).await,
-        Err(SysValidationError::ValidationOutcome(ValidationOutcome::EntryVisibility(_)))
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::EntryVisibility(_)
+        ))
     );
 
     // # Add an entry def to the buffer
@@ -481,4 +559,202 @@ async fn check_entry_not_private_test() {
             ValidationOutcome::PrivateEntry
         ))
     );
+    // No profile can let a private entry onto the DHT
+    assert!(ValidationOutcome::PrivateEntry.category().is_none());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn check_self_link_test() {
+    observability::test_run().ok();
+    let dna_def = |reject_self_links| {
+        DnaDef::builder()
+            .name("self_link_test")
+            .add_zome(TestWasm::Link)
+            .reject_self_links(reject_self_links)
+            .build()
+            .unwrap()
+    };
+    let allows = DnaFile::new(dna_def(false), vec![TestWasm::Link.into()])
+        .await
+        .unwrap();
+    let rejects = DnaFile::new(dna_def(true), vec![TestWasm::Link.into()])
+        .await
+        .unwrap();
+
+    let mut link_add = fixt!(CreateLink);
+    let mut conductor_api = MockCellConductorApi::new();
+    conductor_api.expect_cell_id().return_const(fixt!(CellId));
+
+    // # Links to another entry never need the dna
+    assert_matches!(check_self_link(&link_add, &conductor_api).await, Ok(()));
+
+    // # Self links are fine unless the dna rejects them
+    link_add.target_address = link_add.base_address.clone();
+    conductor_api
+        .expect_sync_get_this_dna()
+        .return_const(Some(allows));
+    assert_matches!(check_self_link(&link_add, &conductor_api).await, Ok(()));
+
+    conductor_api.checkpoint();
+    conductor_api.expect_cell_id().return_const(fixt!(CellId));
+    conductor_api
+        .expect_sync_get_this_dna()
+        .return_const(Some(rejects));
+    assert_matches!(
+        check_self_link(&link_add, &conductor_api).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::SelfLink(_)
+        ))
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn check_link_not_removed_test() {
+    let env: EnvironmentRead = test_cell_env().env.into();
+    let mut header_fixt = HeaderHashFixturator::new(Predictable);
+    let link_add_hash = header_fixt.next().unwrap();
+    let link_remove_hash = header_fixt.next().unwrap();
+    let other_remove_hash = header_fixt.next().unwrap();
+
+    // # Nothing has removed this link yet
+    let mut metadata = meta_mock!(expect_get_link_removes_on_link_add);
+    metadata.expect_env().return_const(env.clone());
+    assert_matches!(
+        check_link_not_removed(&link_add_hash, &link_remove_hash, &metadata).await,
+        Ok(())
+    );
+
+    // # Only this remove is registered so it's being revalidated
+    let mut metadata = meta_mock!(
+        expect_get_link_removes_on_link_add,
+        vec![link_remove_hash.clone()]
+    );
+    metadata.expect_env().return_const(env.clone());
+    assert_matches!(
+        check_link_not_removed(&link_add_hash, &link_remove_hash, &metadata).await,
+        Ok(())
+    );
+
+    // # Another remove got there first
+    let mut metadata = meta_mock!(
+        expect_get_link_removes_on_link_add,
+        vec![other_remove_hash.clone()]
+    );
+    metadata.expect_env().return_const(env);
+    assert_matches!(
+        check_link_not_removed(&link_add_hash, &link_remove_hash, &metadata).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::LinkAlreadyRemoved(hash)
+        )) if hash == link_add_hash
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn check_link_in_metadata_missing_test() {
+    let env: EnvironmentRead = test_cell_env().env.into();
+    let link_add = fixt!(CreateLink);
+    let link_add_hash = fixt!(HeaderHash);
+
+    // # Removing a link we don't hold waits for the link add
+    let mut metadata = MockMetadataBuf::new();
+    metadata.expect_get_links_all().returning(|_| {
+        Ok(Box::new(fallible_iterator::convert(std::iter::empty::<
+            Result<LinkMetaVal, DatabaseError>,
+        >())))
+    });
+    metadata.expect_env().return_const(env);
+    assert_matches!(
+        check_link_in_metadata(&link_add.into(), &link_add_hash, &metadata).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotHoldingDep(_)
+        ))
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn check_delete_reference_test() {
+    let create = fixt!(Create);
+    let create_header: Header = create.clone().into();
+    let mut delete = fixt!(Delete);
+    delete.author = create.author.clone();
+    delete.deletes_entry_address = create.entry_hash.clone();
+
+    // # Valid delete of the author's own entry
+    assert_matches!(check_delete_reference(&delete, &create_header), Ok(()));
+
+    // # Deleting a delete
+    let delete_header: Header = fixt!(Delete).into();
+    assert_matches!(
+        check_delete_reference(&delete, &delete_header),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotNewEntry(_)
+        ))
+    );
+
+    // # Entry address doesn't match the deleted header
+    let mut mismatched = delete.clone();
+    mismatched.deletes_entry_address = fixt!(EntryHash);
+    assert_matches!(
+        check_delete_reference(&mismatched, &create_header),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::DeleteEntryMismatch(_, _)
+        ))
+    );
+
+    // # Deleting someone else's entry
+    let mut not_author = delete;
+    not_author.author = fixt!(AgentPubKey);
+    assert_matches!(
+        check_delete_reference(&not_author, &create_header),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::DeleteNotByAuthor(_, _)
+        ))
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn check_not_deleted_test() {
+    let env: EnvironmentRead = test_cell_env().env.into();
+    let mut header_fixt = HeaderHashFixturator::new(Predictable);
+    let header_hash = header_fixt.next().unwrap();
+    let delete_hash = header_fixt.next().unwrap();
+    let other_delete_hash = header_fixt.next().unwrap();
+
+    // # Not deleted yet
+    let mut metadata = meta_mock!(expect_get_deletes_on_header);
+    metadata.expect_env().return_const(env.clone());
+    assert_matches!(
+        check_not_deleted(&header_hash, &delete_hash, &metadata).await,
+        Ok(())
+    );
+
+    // # Already deleted by another delete
+    let mut metadata = meta_mock!(expect_get_deletes_on_header, vec![other_delete_hash]);
+    metadata.expect_env().return_const(env);
+    assert_matches!(
+        check_not_deleted(&header_hash, &delete_hash, &metadata).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::AlreadyDeleted(hash)
+        )) if hash == header_hash
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn delete_of_missing_element_waits_test() {
+    let test_env = test_cell_env();
+    let mut workspace = SysValidationWorkspace::new(test_env.env.clone().into()).unwrap();
+    let missing = fixt!(HeaderHash);
+
+    assert_matches!(
+        check_holding_element_all(
+            &missing,
+            &mut workspace,
+            holochain_p2p::MockHolochainP2pCellT::new(),
+            CheckLevel::Proof,
+        )
+        .await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotHoldingDep(_)
+        ))
+    );
 }
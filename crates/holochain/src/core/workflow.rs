@@ -24,11 +24,13 @@
 pub mod error;
 
 pub mod app_validation_workflow;
+pub mod arc_resize_workflow;
 pub mod call_zome_workflow;
 pub mod genesis_workflow;
 pub mod incoming_dht_ops_workflow;
 pub mod initialize_zomes_workflow;
 pub mod integrate_dht_ops_workflow;
+pub mod op_trace;
 pub mod produce_dht_ops_workflow;
 pub mod publish_dht_ops_workflow;
 pub mod sys_validation_workflow;
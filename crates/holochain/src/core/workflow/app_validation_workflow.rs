@@ -7,21 +7,36 @@ use super::{
         disintegrate_single_data, disintegrate_single_metadata, integrate_single_data,
         integrate_single_metadata,
     },
+    op_trace::op_span,
     produce_dht_ops_workflow::dht_op_light::light_to_op,
     sys_validation_workflow::types::DepType,
+    CallZomeWorkspace, CallZomeWorkspaceLock,
 };
-use crate::core::{
-    queue_consumer::{OneshotWriter, TriggerSender, WorkComplete},
-    state::{
-        dht_op_integration::{IntegratedDhtOpsStore, IntegrationLimboStore, IntegrationLimboValue},
-        element_buf::ElementBuf,
-        metadata::MetadataBuf,
-        validation_db::{ValidationLimboStatus, ValidationLimboStore, ValidationLimboValue},
-        workspace::{Workspace, WorkspaceResult},
+use crate::{
+    conductor::{api::CellConductorApiT, CellError},
+    core::{
+        queue_consumer::{OneshotWriter, TriggerSender, WorkComplete},
+        ribosome::{
+            error::RibosomeError,
+            guest_callback::validate::{ValidateHostAccess, ValidateInvocation, ValidateResult},
+            wasm_ribosome::WasmRibosome,
+            RibosomeT,
+        },
+        state::{
+            dht_op_integration::{
+                IntegratedDhtOpsStore, IntegrationLimboStore, IntegrationLimboValue,
+            },
+            element_buf::ElementBuf,
+            metadata::MetadataBuf,
+            validation_db::{ValidationLimboStatus, ValidationLimboStore, ValidationLimboValue},
+            validation_receipts_db::{ValidationReceipt, ValidationResult},
+            workspace::{Workspace, WorkspaceResult},
+        },
     },
 };
 use fallible_iterator::FallibleIterator;
-use holo_hash::DhtOpHash;
+use holo_hash::{AgentPubKey, AnyDhtHash, DhtOpHash, HeaderHash};
+use holochain_p2p::{HolochainP2pCell, HolochainP2pCellT};
 use holochain_state::{
     buffer::{BufferedStore, KvBufFresh},
     db::{INTEGRATED_DHT_OPS, INTEGRATION_LIMBO},
@@ -29,30 +44,102 @@ use holochain_state::{
     fresh_reader,
     prelude::*,
 };
-use holochain_types::{dht_op::DhtOp, dht_op::DhtOpLight, validate::ValidationStatus, Timestamp};
+use holochain_types::{
+    dht_op::DhtOp, dht_op::DhtOpLight, header::NewEntryHeaderRef, validate::ValidationStatus,
+    Timestamp,
+};
+use holochain_zome_types::{entry::GetOptions, header::EntryType, Header};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    convert::TryInto,
+};
 use tracing::*;
 
-#[instrument(skip(workspace, writer, trigger_integration))]
+#[cfg(test)]
+mod tests;
+
+/// How many times an op can be run through the validate callback
+/// before it is abandoned because the zome keeps asking for more dependencies.
+pub const MAX_APP_VALIDATIONS: u32 = 10;
+
+#[instrument(skip(workspace, writer, trigger_integration, conductor_api, network))]
 pub async fn app_validation_workflow(
     mut workspace: AppValidationWorkspace,
     writer: OneshotWriter,
     trigger_integration: &mut TriggerSender,
+    conductor_api: impl CellConductorApiT,
+    network: HolochainP2pCell,
 ) -> WorkflowResult<WorkComplete> {
-    warn!("unimplemented passthrough");
-
-    let complete = app_validation_workflow_inner(&mut workspace).await?;
+    let ribosome = conductor_api
+        .get_this_dna()
+        .await
+        .map(|dna| {
+            WasmRibosome::new(dna)
+                .with_module_cache(conductor_api.module_cache())
+                .with_host_fn_executor(conductor_api.host_fn_executor())
+        })
+        .ok_or(CellError::DnaMissing)?;
+    let complete =
+        app_validation_workflow_inner(&mut workspace, &ribosome, network.clone()).await?;
+    let to_receipt = std::mem::take(&mut workspace.to_receipt);
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     // trigger other workflows
     trigger_integration.trigger();
 
+    // let the authors know their ops were validated
+    send_validation_receipts(to_receipt, &conductor_api, network).await;
+
     Ok(complete)
 }
+
+/// Sign a receipt for each op this node found valid and send it to the op's author.
+/// The ops are already committed by now, so failing to sign a receipt or
+/// reach an author is only logged and the rest of the receipts still go out.
+async fn send_validation_receipts(
+    to_receipt: Vec<(DhtOpHash, AgentPubKey)>,
+    conductor_api: &impl CellConductorApiT,
+    mut network: HolochainP2pCell,
+) {
+    let validator = conductor_api.cell_id().agent_pubkey().clone();
+    for (dht_op_hash, author) in to_receipt {
+        // No need to receipt our own ops
+        if author == validator {
+            continue;
+        }
+        let receipt = ValidationReceipt {
+            dht_op_hash,
+            validation_result: ValidationResult::Valid,
+            validator: validator.clone(),
+        }
+        .sign(conductor_api.keystore())
+        .await;
+        let receipt = match receipt {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                warn!(failed_to_sign_validation_receipt = ?e);
+                continue;
+            }
+        };
+        let receipt = match receipt.try_into() {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                warn!(failed_to_serialize_validation_receipt = ?e);
+                continue;
+            }
+        };
+        if let Err(e) = network.send_validation_receipt(author, receipt).await {
+            warn!(failed_to_send_validation_receipt = ?e);
+        }
+    }
+}
 async fn app_validation_workflow_inner(
     workspace: &mut AppValidationWorkspace,
+    ribosome: &impl RibosomeT,
+    network: HolochainP2pCell,
 ) -> WorkflowResult<WorkComplete> {
     let env = workspace.validation_limbo.env().clone();
     let (ops, mut awaiting_ops): (Vec<ValidationLimboValue>, Vec<ValidationLimboValue>) =
@@ -75,28 +162,84 @@ async fn app_validation_workflow_inner(
                 _ => Ok(true),
             }))?;
     debug!(?ops, ?awaiting_ops);
-    for mut vlv in ops {
-        match &vlv.status {
-            ValidationLimboStatus::AwaitingAppDeps(_) => {
-                let op = light_to_op(vlv.op.clone(), &workspace.element_pending).await?;
-                let hash = DhtOpHash::with_data_sync(&op);
-                workspace.put_val_limbo(hash, vlv)?;
+    if !ops.is_empty() {
+        // The validate callback can read from the dht through this workspace
+        let workspace_lock = CallZomeWorkspaceLock::new(CallZomeWorkspace::new(env.clone())?);
+        let mut waiting = WaitingOps::default();
+        let mut ready = VecDeque::new();
+        for vlv in ops {
+            match &vlv.status {
+                ValidationLimboStatus::AwaitingAppDeps(deps) => {
+                    let deps = deps.clone();
+                    waiting.wait_on(deps, vlv);
+                }
+                _ => ready.push_back(vlv),
             }
-            ValidationLimboStatus::SysValidated => {
-                if vlv.pending_dependencies.pending_dependencies() {
-                    vlv.status = ValidationLimboStatus::PendingValidation;
-                    awaiting_ops.push(vlv);
-                } else {
-                    let op = light_to_op(vlv.op.clone(), &workspace.element_pending).await?;
-                    let hash = DhtOpHash::with_data_sync(&op);
+        }
+        // Check each awaited dependency once
+        for dep in waiting.dependencies() {
+            if dependency_held(&dep, &workspace_lock, network.clone()).await? {
+                ready.extend(waiting.resolve(&dep));
+            }
+        }
+
+        while let Some(mut vlv) = ready.pop_front() {
+            let op = light_to_op(vlv.op.clone(), &workspace.element_pending).await?;
+            let hash = DhtOpHash::with_data_sync(&op);
+            vlv.num_app_validations += 1;
+            let span = op_span("app_validation", &hash);
+            match span.in_scope(|| validate_op(&op, ribosome, &workspace_lock, &network))? {
+                ValidateResult::Valid => {
+                    // Anything waiting on this op's data can now be validated
+                    for held in hold_for_waiting(&op, &vlv.op, &workspace_lock).await? {
+                        ready.extend(waiting.resolve(&held));
+                    }
+                    if vlv.pending_dependencies.pending_dependencies() {
+                        vlv.status = ValidationLimboStatus::PendingValidation;
+                        awaiting_ops.push(vlv);
+                    } else {
+                        let iv = IntegrationLimboValue {
+                            validation_status: ValidationStatus::Valid,
+                            op: vlv.op,
+                            time_added: Some(vlv.time_added),
+                        };
+                        workspace.put_int_limbo(hash, iv, op)?;
+                    }
+                }
+                ValidateResult::Invalid(reason) => {
+                    debug!(?reason, "op failed app validation");
+                    let iv = IntegrationLimboValue {
+                        validation_status: ValidationStatus::Rejected,
+                        op: vlv.op,
+                        time_added: Some(vlv.time_added),
+                    };
+                    workspace.put_int_limbo(hash, iv, op)?;
+                }
+                ValidateResult::UnresolvedDependencies(_)
+                    if vlv.num_app_validations >= MAX_APP_VALIDATIONS =>
+                {
+                    // The zome keeps asking for more so give up on this op
+                    warn!(?hash, "op abandoned after too many app validation attempts");
                     let iv = IntegrationLimboValue {
-                        validation_status: ValidationStatus::Valid,
+                        validation_status: ValidationStatus::Abandoned,
                         op: vlv.op,
+                        time_added: Some(vlv.time_added),
                     };
                     workspace.put_int_limbo(hash, iv, op)?;
                 }
+                ValidateResult::UnresolvedDependencies(deps) => {
+                    // Wait for the next run so the new dependencies have a chance to arrive
+                    vlv.status = ValidationLimboStatus::AwaitingAppDeps(deps);
+                    workspace.put_val_limbo(hash, vlv)?;
+                }
             }
-            _ => unreachable!("Should not contain any other status"),
+        }
+
+        // Everything still waiting goes back in limbo without being validated
+        for vlv in waiting.into_remaining() {
+            let op = light_to_op(vlv.op.clone(), &workspace.element_pending).await?;
+            let hash = DhtOpHash::with_data_sync(&op);
+            workspace.put_val_limbo(hash, vlv)?;
         }
     }
     fn check_dep_status(
@@ -136,6 +279,7 @@ async fn app_validation_workflow_inner(
                                     let iv = IntegrationLimboValue {
                                         validation_status: status,
                                         op: vlv.op,
+                                        time_added: Some(vlv.time_added),
                                     };
                                     workspace.put_int_limbo(hash, iv, op)?;
 
@@ -168,6 +312,7 @@ async fn app_validation_workflow_inner(
             let iv = IntegrationLimboValue {
                 validation_status: ValidationStatus::Valid,
                 op: vlv.op,
+                time_added: Some(vlv.time_added),
             };
             workspace.put_int_limbo(hash, iv, op)?;
         }
@@ -175,6 +320,189 @@ async fn app_validation_workflow_inner(
     Ok(WorkComplete::Complete)
 }
 
+/// An integrated op that the new validation rules would judge differently
+#[derive(Clone, Debug, PartialEq)]
+pub struct RevalidationChange {
+    /// The hash of the integrated op
+    pub op_hash: DhtOpHash,
+    /// The status the op was integrated with
+    pub integrated: ValidationStatus,
+    /// The status the new rules give the op
+    pub revalidated: ValidationStatus,
+}
+
+/// Run the validate callbacks of a new ribosome over every integrated op
+/// and report the ops whose outcome would change.
+///
+/// Only ops that store an app entry are validated by zomes so only they are
+/// checked, and abandoned ops are skipped. Ops rejected by sys validation
+/// will be reported if the new rules accept them.
+/// Nothing is written to the Cell so this is safe to run on a live Cell.
+pub async fn revalidate_integrated_ops(
+    env: EnvironmentRead,
+    ribosome: &impl RibosomeT,
+    network: HolochainP2pCell,
+) -> WorkflowResult<Vec<RevalidationChange>> {
+    let integrated_dht_ops: IntegratedDhtOpsStore =
+        KvBufFresh::new(env.clone(), env.get_db(&*INTEGRATED_DHT_OPS)?);
+    let element_vault = ElementBuf::vault(env.clone(), true)?;
+    let element_rejected = ElementBuf::rejected(env.clone())?;
+    let ops: Vec<_> = fresh_reader!(env, |r| integrated_dht_ops
+        .iter(&r)?
+        .filter(|(_, iv)| {
+            Ok(matches!(iv.op, DhtOpLight::StoreEntry(_, _, _))
+                && iv.validation_status != ValidationStatus::Abandoned)
+        })
+        .map(|(k, iv)| Ok((DhtOpHash::with_pre_hashed(k.to_vec()), iv)))
+        .collect())?;
+
+    // The validate callbacks can read and cache through this workspace
+    // but it is never flushed
+    let workspace_lock = CallZomeWorkspaceLock::new(CallZomeWorkspace::new(env)?);
+    let mut changes = Vec::new();
+    for (op_hash, iv) in ops {
+        let op = match iv.validation_status {
+            ValidationStatus::Rejected => light_to_op(iv.op, &element_rejected).await?,
+            _ => light_to_op(iv.op, &element_vault).await?,
+        };
+        let revalidated = match validate_op(&op, ribosome, &workspace_lock, &network)? {
+            ValidateResult::Valid => ValidationStatus::Valid,
+            ValidateResult::Invalid(_) => ValidationStatus::Rejected,
+            // Can't say either way until the dependencies are found
+            ValidateResult::UnresolvedDependencies(_) => continue,
+        };
+        if revalidated != iv.validation_status {
+            changes.push(RevalidationChange {
+                op_hash,
+                integrated: iv.validation_status,
+                revalidated,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+/// Run the validate callback of the zome that defines the entry an op stores.
+/// Ops that don't store an app entry have nothing for the zome to validate.
+fn validate_op(
+    op: &DhtOp,
+    ribosome: &impl RibosomeT,
+    workspace_lock: &CallZomeWorkspaceLock,
+    network: &HolochainP2pCell,
+) -> WorkflowResult<ValidateResult> {
+    let (header, entry): (NewEntryHeaderRef, _) = match op {
+        DhtOp::StoreEntry(_, header, entry) => (header.into(), entry),
+        _ => return Ok(ValidateResult::Valid),
+    };
+    let zome_name = match header.entry_type() {
+        EntryType::App(app_entry_type) => {
+            let zome_index = u8::from(app_entry_type.zome_id()) as usize;
+            match ribosome.dna_file().dna().zomes.get(zome_index) {
+                Some((zome_name, _)) => zome_name.clone(),
+                // Sys validation checks the zome exists
+                None => return Ok(ValidateResult::Valid),
+            }
+        }
+        _ => return Ok(ValidateResult::Valid),
+    };
+    Ok(ribosome.run_validate(
+        ValidateHostAccess::new(workspace_lock.clone(), network.clone()),
+        ValidateInvocation::new(zome_name, (**entry).clone()),
+    )?)
+}
+
+/// Check if a dependency a zome asked for can now be found.
+async fn dependency_held(
+    dep: &AnyDhtHash,
+    workspace_lock: &CallZomeWorkspaceLock,
+    network: HolochainP2pCell,
+) -> WorkflowResult<bool> {
+    let mut workspace = workspace_lock.write().await;
+    let mut cascade = workspace.cascade(network);
+    Ok(cascade
+        .dht_get(dep.clone(), GetOptions::default().into())
+        .await
+        .map_err(RibosomeError::from)?
+        .is_some())
+}
+
+/// Make the data of an op that just passed app validation visible to
+/// the validate callbacks of the ops waiting on it for the rest of this run.
+/// Returns the hashes that are now held.
+async fn hold_for_waiting(
+    op: &DhtOp,
+    light: &DhtOpLight,
+    workspace_lock: &CallZomeWorkspaceLock,
+) -> WorkflowResult<Vec<AnyDhtHash>> {
+    let held = match op {
+        DhtOp::StoreEntry(_, header, _) => vec![
+            HeaderHash::with_data_sync(&Header::from(header.clone())).into(),
+            header.entry().clone().into(),
+        ],
+        _ => return Ok(Vec::new()),
+    };
+    let mut guard = workspace_lock.write().await;
+    let workspace = &mut *guard;
+    integrate_single_data(op.clone(), &mut workspace.cache_cas)?;
+    integrate_single_metadata(
+        light.clone(),
+        &workspace.cache_cas,
+        &mut workspace.cache_meta,
+    )?;
+    Ok(held)
+}
+
+/// The ops waiting on app dependencies, indexed by the
+/// dependencies they are waiting on.
+#[derive(Default)]
+struct WaitingOps {
+    /// Each waiting op along with the dependencies it still needs
+    ops: Vec<Option<(ValidationLimboValue, HashSet<AnyDhtHash>)>>,
+    /// The reverse dependency-wait index from a dependency to the ops waiting on it
+    waiting_on: HashMap<AnyDhtHash, Vec<usize>>,
+}
+
+impl WaitingOps {
+    fn wait_on(&mut self, deps: Vec<AnyDhtHash>, vlv: ValidationLimboValue) {
+        let i = self.ops.len();
+        for dep in &deps {
+            self.waiting_on.entry(dep.clone()).or_default().push(i);
+        }
+        self.ops.push(Some((vlv, deps.into_iter().collect())));
+    }
+
+    /// All the dependencies that are being waited on
+    fn dependencies(&self) -> Vec<AnyDhtHash> {
+        self.waiting_on.keys().cloned().collect()
+    }
+
+    /// Mark a dependency as held and return the ops
+    /// that are no longer waiting on anything.
+    fn resolve(&mut self, dep: &AnyDhtHash) -> Vec<ValidationLimboValue> {
+        let mut ready = Vec::new();
+        for i in self.waiting_on.remove(dep).unwrap_or_default() {
+            let now_ready = match &mut self.ops[i] {
+                Some((_, deps)) => {
+                    deps.remove(dep);
+                    deps.is_empty()
+                }
+                None => false,
+            };
+            if now_ready {
+                if let Some((vlv, _)) = self.ops[i].take() {
+                    ready.push(vlv);
+                }
+            }
+        }
+        ready
+    }
+
+    /// The ops that are still waiting
+    fn into_remaining(self) -> impl Iterator<Item = ValidationLimboValue> {
+        self.ops.into_iter().filter_map(|op| op.map(|(vlv, _)| vlv))
+    }
+}
+
 pub struct AppValidationWorkspace {
     pub integrated_dht_ops: IntegratedDhtOpsStore,
     pub integration_limbo: IntegrationLimboStore,
@@ -193,6 +521,8 @@ pub struct AppValidationWorkspace {
     pub meta_cache: MetadataBuf,
     // Ops to disintegrate
     pub to_disintegrate_pending: Vec<DhtOpLight>,
+    // Valid ops to send a validation receipt for, along with their author
+    pub to_receipt: Vec<(DhtOpHash, AgentPubKey)>,
 }
 
 impl AppValidationWorkspace {
@@ -228,6 +558,7 @@ impl AppValidationWorkspace {
             element_cache,
             meta_cache,
             to_disintegrate_pending: Vec::new(),
+            to_receipt: Vec::new(),
         })
     }
 
@@ -249,10 +580,14 @@ impl AppValidationWorkspace {
         iv: IntegrationLimboValue,
         op: DhtOp,
     ) -> WorkflowResult<()> {
+        let op_author = op.header().author().clone();
         disintegrate_single_metadata(iv.op.clone(), &self.element_pending, &mut self.meta_pending)?;
         self.to_disintegrate_pending.push(iv.op.clone());
         integrate_single_data(op, &mut self.element_judged)?;
         integrate_single_metadata(iv.op.clone(), &self.element_judged, &mut self.meta_judged)?;
+        if iv.validation_status == ValidationStatus::Valid {
+            self.to_receipt.push((hash.clone(), op_author));
+        }
         self.integration_limbo.put(hash, iv)?;
         Ok(())
     }
@@ -0,0 +1,538 @@
+use super::{
+    app_validation_workflow_inner, revalidate_integrated_ops, AppValidationWorkspace,
+    RevalidationChange, MAX_APP_VALIDATIONS,
+};
+use crate::{
+    conductor::dna_store::MockDnaStore,
+    core::{
+        ribosome::{
+            guest_callback::validate::ValidateResult, wasm_ribosome::WasmRibosome, MockRibosomeT,
+            RibosomeT,
+        },
+        state::{
+            dht_op_integration::{AuthoredDhtOpsStore, IntegratedDhtOpsValue},
+            validation_db::{ValidationLimboStatus, ValidationLimboValue},
+            validation_receipts_db::{ValidationReceiptsBuf, ValidationResult},
+            workspace::{Workspace, WorkspaceError},
+        },
+        workflow::{
+            integrate_dht_ops_workflow::{integrate_single_data, IntegrateDhtOpsWorkspace},
+            sys_validation_workflow::types::PendingDependencies,
+        },
+    },
+    test_utils::{
+        host_fn_api::{commit_entry, CallData, POST_ID},
+        setup_app, test_network,
+    },
+};
+use ::fixt::prelude::*;
+use fallible_iterator::FallibleIterator;
+use futures::future::FutureExt;
+use holo_hash::{AnyDhtHash, DhtOpHash, EntryHash};
+use holochain_keystore::AgentPubKeyExt;
+use holochain_p2p::{
+    event::{HolochainP2pEvent, HolochainP2pEventReceiver},
+    HolochainP2pCell,
+};
+use holochain_serialized_bytes::prelude::*;
+use holochain_state::{
+    buffer::KvBufFresh,
+    db::{GetDb, AUTHORED_DHT_OPS},
+    env::EnvironmentWrite,
+    fresh_reader_test,
+    test_utils::test_cell_env,
+};
+use holochain_types::{
+    app::InstalledCell,
+    cell::CellId,
+    dht_op::{DhtOp, DhtOpLight},
+    dna::{DnaDef, DnaFile},
+    element::{Element, GetElementResponse, RawGetEntryResponse, SignedHeaderHashed},
+    fixt::*,
+    test_utils::{fake_agent_pubkey_1, fake_agent_pubkey_2},
+    validate::ValidationStatus,
+    Entry, HeaderHashed, Timestamp,
+};
+use holochain_wasm_test_utils::TestWasm;
+use holochain_zome_types::{
+    entry_def::EntryVisibility,
+    header::{AppEntryType, Create, EntryType},
+};
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[derive(Serialize, Deserialize, SerializedBytes)]
+struct Post(String);
+
+#[derive(Serialize, Deserialize, SerializedBytes)]
+struct Comment {
+    post: EntryHash,
+    body: String,
+}
+
+fn post(content: &str) -> Entry {
+    Entry::app(Post(content.into()).try_into().unwrap()).unwrap()
+}
+
+fn comment(post: &EntryHash, body: &str) -> Entry {
+    Entry::app(
+        Comment {
+            post: post.clone(),
+            body: body.into(),
+        }
+        .try_into()
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+/// Create an entry of the validate deps wasm
+fn create_header(entry: &Entry, entry_def_index: u8) -> Create {
+    let mut create = fixt!(Create);
+    create.entry_hash = EntryHash::with_data_sync(entry);
+    create.entry_type = EntryType::App(AppEntryType::new(
+        entry_def_index.into(),
+        0.into(),
+        EntryVisibility::Public,
+    ));
+    create
+}
+
+/// The element an authority returns for an entry
+fn entry_element(entry: Entry, entry_def_index: u8) -> Element {
+    Element::new(
+        SignedHeaderHashed::with_presigned(
+            HeaderHashed::from_content_sync(create_header(&entry, entry_def_index).into()),
+            fixt!(Signature),
+        ),
+        Some(entry),
+    )
+}
+
+async fn test_dna_file() -> DnaFile {
+    DnaFile::new(
+        DnaDef::builder()
+            .name("app_validation_workflow_test")
+            .add_zome(TestWasm::ValidateDeps)
+            .build()
+            .unwrap(),
+        vec![TestWasm::ValidateDeps.into()],
+    )
+    .await
+    .unwrap()
+}
+
+/// Put a store entry op in limbo with this status
+async fn put_in_limbo(
+    env: &EnvironmentWrite,
+    entry: Entry,
+    entry_def_index: u8,
+    status: ValidationLimboStatus,
+) -> DhtOpHash {
+    let op = DhtOp::StoreEntry(
+        fixt!(Signature),
+        create_header(&entry, entry_def_index).into(),
+        Box::new(entry),
+    );
+    let op_hash = DhtOpHash::with_data_sync(&op);
+    let mut workspace = AppValidationWorkspace::new(env.clone().into()).unwrap();
+    let vlv = ValidationLimboValue {
+        status,
+        pending_dependencies: PendingDependencies::new(),
+        basis: op.dht_basis().await,
+        op: op.to_light().await,
+        time_added: Timestamp::now(),
+        last_try: None,
+        num_tries: 0,
+        num_app_validations: 0,
+    };
+    integrate_single_data(op, &mut workspace.element_pending).unwrap();
+    workspace
+        .validation_limbo
+        .put(op_hash.clone(), vlv)
+        .unwrap();
+    env.guard()
+        .with_commit::<WorkspaceError, _, _>(|writer| workspace.flush_to_txn_ref(writer))
+        .unwrap();
+    op_hash
+}
+
+/// Integrate a store entry op as if it had passed validation
+async fn put_integrated(env: &EnvironmentWrite, entry: Entry, entry_def_index: u8) -> DhtOpHash {
+    let op = DhtOp::StoreEntry(
+        fixt!(Signature),
+        create_header(&entry, entry_def_index).into(),
+        Box::new(entry),
+    );
+    let op_hash = DhtOpHash::with_data_sync(&op);
+    let mut workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    let value = IntegratedDhtOpsValue {
+        validation_status: ValidationStatus::Valid,
+        op: op.to_light().await,
+        when_integrated: Timestamp::now(),
+    };
+    integrate_single_data(op, &mut workspace.elements).unwrap();
+    workspace
+        .integrated_dht_ops
+        .put(op_hash.clone(), value)
+        .unwrap();
+    env.guard()
+        .with_commit::<WorkspaceError, _, _>(|writer| workspace.flush_to_txn_ref(writer))
+        .unwrap();
+    op_hash
+}
+
+/// Answer entry gets with whatever the authorities currently hold
+fn spawn_authority(
+    mut recv: HolochainP2pEventReceiver,
+    held: Arc<Mutex<HashMap<AnyDhtHash, Element>>>,
+) {
+    tokio::task::spawn(async move {
+        use tokio::stream::StreamExt;
+        while let Some(evt) = recv.next().await {
+            if let HolochainP2pEvent::Get {
+                dht_hash, respond, ..
+            } = evt
+            {
+                let raw = held
+                    .lock()
+                    .unwrap()
+                    .get(&dht_hash)
+                    .cloned()
+                    .and_then(|element| {
+                        RawGetEntryResponse::from_elements(vec![element], vec![], vec![])
+                    })
+                    .map(Box::new);
+                let response = GetElementResponse::GetEntryFull(raw).try_into().unwrap();
+                respond.respond(Ok(async move { Ok(response) }.boxed().into()));
+            }
+        }
+    });
+}
+
+async fn run_workflow(
+    env: &EnvironmentWrite,
+    network: HolochainP2pCell,
+    ribosome: &impl RibosomeT,
+) {
+    let mut workspace = AppValidationWorkspace::new(env.clone().into()).unwrap();
+    app_validation_workflow_inner(&mut workspace, ribosome, network)
+        .await
+        .unwrap();
+    env.guard()
+        .with_commit::<WorkspaceError, _, _>(|writer| workspace.flush_to_txn_ref(writer))
+        .unwrap();
+}
+
+fn integrated_status(env: &EnvironmentWrite, op_hash: &DhtOpHash) -> Option<ValidationStatus> {
+    let workspace = AppValidationWorkspace::new(env.clone().into()).unwrap();
+    workspace
+        .integration_limbo
+        .get(op_hash)
+        .unwrap()
+        .map(|iv| iv.validation_status)
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn app_validation_waits_for_unresolved_dependencies() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let ribosome = WasmRibosome::new(test_dna_file().await);
+
+    let post = post("A post to comment on");
+    let post_hash = EntryHash::with_data_sync(&post);
+    let comment = comment(&post_hash, "Nice post");
+
+    // Put the comment in limbo as if it has passed sys validation
+    let op_hash = put_in_limbo(&env, comment, 1, ValidationLimboStatus::SysValidated).await;
+
+    // Only return the post from the network once it has arrived
+    let (_network, recv, cell_network) = test_network(None, None).await;
+    let held = Arc::new(Mutex::new(HashMap::new()));
+    spawn_authority(recv, held.clone());
+
+    // The zome can't find the post so the comment waits on it
+    let awaiting = ValidationLimboStatus::AwaitingAppDeps(vec![post_hash.clone().into()]);
+    for _ in 0..2 {
+        run_workflow(&env, cell_network.clone(), &ribosome).await;
+        let workspace = AppValidationWorkspace::new(env.clone().into()).unwrap();
+        assert_eq!(
+            workspace
+                .validation_limbo
+                .get(&op_hash)
+                .unwrap()
+                .unwrap()
+                .status,
+            awaiting
+        );
+        assert_eq!(integrated_status(&env, &op_hash), None);
+    }
+
+    // Once the post arrives the comment is validated again and passes
+    held.lock()
+        .unwrap()
+        .insert(post_hash.into(), entry_element(post, 0));
+    run_workflow(&env, cell_network.clone(), &ribosome).await;
+    let workspace = AppValidationWorkspace::new(env.clone().into()).unwrap();
+    assert!(workspace.validation_limbo.get(&op_hash).unwrap().is_none());
+    assert_eq!(
+        integrated_status(&env, &op_hash),
+        Some(ValidationStatus::Valid)
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn app_dependency_chain_validates_each_op_once() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    // Record the order the entries are validated in
+    let validated = Arc::new(Mutex::new(Vec::new()));
+    let mut ribosome = MockRibosomeT::new();
+    ribosome
+        .expect_dna_file()
+        .return_const(test_dna_file().await);
+    ribosome.expect_run_validate().returning({
+        let validated = validated.clone();
+        move |_, invocation| {
+            validated
+                .lock()
+                .unwrap()
+                .push(EntryHash::with_data_sync(&*invocation.entry));
+            Ok(ValidateResult::Valid)
+        }
+    });
+
+    // Only the post is on the network, each comment is on the one before it
+    let post = post("The start of a thread");
+    let post_hash = EntryHash::with_data_sync(&post);
+    let (_network, recv, cell_network) = test_network(None, None).await;
+    let held = Arc::new(Mutex::new(HashMap::new()));
+    held.lock()
+        .unwrap()
+        .insert(post_hash.clone().into(), entry_element(post, 0));
+    spawn_authority(recv, held);
+
+    let mut entry_hashes = Vec::new();
+    let mut op_hashes = Vec::new();
+    let mut on = post_hash;
+    for i in 0..3 {
+        let comment = comment(&on, &format!("Reply {}", i));
+        let comment_hash = EntryHash::with_data_sync(&comment);
+        let status = ValidationLimboStatus::AwaitingAppDeps(vec![on.into()]);
+        op_hashes.push(put_in_limbo(&env, comment, 1, status).await);
+        entry_hashes.push(comment_hash.clone());
+        on = comment_hash;
+    }
+
+    // The whole chain is validated in one run in dependency order
+    run_workflow(&env, cell_network, &ribosome).await;
+    assert_eq!(*validated.lock().unwrap(), entry_hashes);
+    for op_hash in op_hashes {
+        assert_eq!(
+            integrated_status(&env, &op_hash),
+            Some(ValidationStatus::Valid)
+        );
+    }
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn app_validation_abandons_endless_dependencies() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    let post = post("Always one more");
+    let post_hash = EntryHash::with_data_sync(&post);
+
+    // The zome asks for the post every time even though it's held
+    let mut ribosome = MockRibosomeT::new();
+    ribosome
+        .expect_dna_file()
+        .return_const(test_dna_file().await);
+    ribosome
+        .expect_run_validate()
+        .times(MAX_APP_VALIDATIONS as usize)
+        .returning({
+            let dep: AnyDhtHash = post_hash.clone().into();
+            move |_, _| Ok(ValidateResult::UnresolvedDependencies(vec![dep.clone()]))
+        });
+
+    let (_network, recv, cell_network) = test_network(None, None).await;
+    let held = Arc::new(Mutex::new(HashMap::new()));
+    held.lock()
+        .unwrap()
+        .insert(post_hash.clone().into(), entry_element(post, 0));
+    spawn_authority(recv, held);
+
+    let comment = comment(&post_hash, "Never valid");
+    let op_hash = put_in_limbo(&env, comment, 1, ValidationLimboStatus::SysValidated).await;
+
+    for _ in 0..MAX_APP_VALIDATIONS - 1 {
+        run_workflow(&env, cell_network.clone(), &ribosome).await;
+        assert_eq!(integrated_status(&env, &op_hash), None);
+    }
+    run_workflow(&env, cell_network.clone(), &ribosome).await;
+    assert_eq!(
+        integrated_status(&env, &op_hash),
+        Some(ValidationStatus::Abandoned)
+    );
+
+    // Abandoned ops are not validated again
+    run_workflow(&env, cell_network, &ribosome).await;
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn revalidation_reports_ops_stricter_rules_reject() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    let short = post("Hi");
+    let short_hash = EntryHash::with_data_sync(&short);
+    let short_op = put_integrated(&env, short, 0).await;
+    let long_op = put_integrated(&env, post("Long enough for the new rules"), 0).await;
+
+    // The new rules reject short posts
+    let mut ribosome = MockRibosomeT::new();
+    ribosome
+        .expect_dna_file()
+        .return_const(test_dna_file().await);
+    ribosome
+        .expect_run_validate()
+        .times(2)
+        .returning(move |_, invocation| {
+            Ok(
+                if EntryHash::with_data_sync(&*invocation.entry) == short_hash {
+                    ValidateResult::Invalid("Too short".to_string())
+                } else {
+                    ValidateResult::Valid
+                },
+            )
+        });
+
+    let (_network, _recv, cell_network) = test_network(None, None).await;
+    let changes = revalidate_integrated_ops(env.clone().into(), &ribosome, cell_network)
+        .await
+        .unwrap();
+    assert_eq!(
+        changes,
+        vec![RevalidationChange {
+            op_hash: short_op.clone(),
+            integrated: ValidationStatus::Valid,
+            revalidated: ValidationStatus::Rejected,
+        }]
+    );
+
+    // The report doesn't change what was integrated
+    let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    for op_hash in &[short_op, long_op] {
+        assert_eq!(
+            workspace
+                .integrated_dht_ops
+                .get(op_hash)
+                .unwrap()
+                .unwrap()
+                .validation_status,
+            ValidationStatus::Valid
+        );
+    }
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn validators_send_signed_receipts_to_the_author() {
+    observability::test_run().ok();
+
+    let dna_file = DnaFile::new(
+        DnaDef::builder()
+            .name("validation_receipt_test")
+            .add_zome(TestWasm::Create)
+            .build()
+            .unwrap(),
+        vec![TestWasm::Create.into()],
+    )
+    .await
+    .unwrap();
+
+    let alice_agent_id = fake_agent_pubkey_1();
+    let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), alice_agent_id.clone());
+    let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+
+    let bob_agent_id = fake_agent_pubkey_2();
+    let bob_cell_id = CellId::new(dna_file.dna_hash().to_owned(), bob_agent_id.clone());
+    let bob_installed_cell = InstalledCell::new(bob_cell_id.clone(), "bob_handle".into());
+
+    let mut dna_store = MockDnaStore::new();
+    dna_store.expect_get().return_const(Some(dna_file.clone()));
+    dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+    dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+    dna_store.expect_get_entry_def().return_const(None);
+
+    let (_tmpdir, _app_api, handle) = setup_app(
+        vec![(
+            "test_app",
+            vec![(alice_installed_cell, None), (bob_installed_cell, None)],
+        )],
+        dna_store,
+    )
+    .await;
+
+    // Alice commits a post and publishes it
+    let (alice_env, call_data) = CallData::create(&alice_cell_id, &handle, &dna_file).await;
+    let header_hash = commit_entry(&alice_env, call_data, post("Validate me"), POST_ID).await;
+    let mut triggers = handle.get_cell_triggers(&alice_cell_id).await.unwrap();
+    triggers.produce_dht_ops.trigger();
+
+    // Find the op that stores the post
+    let authored_dht_ops: AuthoredDhtOpsStore = KvBufFresh::new(
+        alice_env.clone().into(),
+        alice_env.get_db(&*AUTHORED_DHT_OPS).unwrap(),
+    );
+    let store_entry_hash = || -> Option<DhtOpHash> {
+        fresh_reader_test!(alice_env, |r| authored_dht_ops
+            .iter(&r)
+            .unwrap()
+            .find(|(_, v)| Ok(
+                matches!(&v.op, DhtOpLight::StoreEntry(h, _, _) if *h == header_hash)
+            ))
+            .unwrap()
+            .map(|(k, _)| DhtOpHash::with_pre_hashed(k.to_vec())))
+    };
+
+    // Wait for bob to validate the op and send alice a receipt
+    let mut receipts = Vec::new();
+    for _ in 0..30 {
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+        if let Some(op_hash) = store_entry_hash() {
+            let vr_buf = ValidationReceiptsBuf::new(&alice_env).unwrap();
+            receipts = fresh_reader_test!(alice_env, |r| vr_buf
+                .list_receipts(&r, &op_hash)
+                .unwrap()
+                .collect::<Vec<_>>()
+                .unwrap());
+            if !receipts.is_empty() {
+                break;
+            }
+        }
+    }
+
+    // Alice never receipts her own op so the only receipt is bob's
+    assert_eq!(receipts.len(), 1);
+    let receipt = receipts.pop().unwrap();
+    assert_eq!(receipt.receipt.validator, bob_agent_id);
+    assert_eq!(receipt.receipt.validation_result, ValidationResult::Valid);
+    assert!(bob_agent_id
+        .verify_signature(&receipt.validator_signature, receipt.receipt.clone())
+        .await
+        .unwrap());
+
+    let shutdown = handle.take_shutdown_handle().await.unwrap();
+    handle.shutdown().await;
+    shutdown.await.unwrap();
+}
@@ -0,0 +1,568 @@
+//! # Arc Resize Workflow
+//!
+//! When the arc of the DHT a cell is an authority for changes, ops that
+//! fall out of the arc are handed off rather than dropped on the floor.
+//!
+//! An op that is no longer covered is only released once at least
+//! `min_redundancy` other agents covering its basis report that they
+//! hold it, so shrinking an arc never leaves an op with fewer authorities
+//! than it had. Ops still waiting on redundancy stay integrated and the
+//! workflow reports itself as incomplete so it is run again later.
+//!
+//! Releasing an op removes it from the integrated ops, the entry ops index
+//! and the metadata, so it is no longer gossiped or served as an authority.
+//! The element data is left in place because other ops we still hold can
+//! share the same headers and entries.
+//!
+//! Ops that fall into a grown arc are fetched from the agents already
+//! holding them and handed to the incoming dht ops workflow, so they are
+//! validated and integrated like any other op we are sent.
+//!
+//! ## Open questions
+//! - [ ] Gossip still runs over the full arc so a released op can be
+//!   gossiped back to us until gossip uses the storage arc.
+
+use super::error::WorkflowResult;
+use super::incoming_dht_ops_workflow::{incoming_dht_ops_workflow, OpIngressFilterRef};
+use super::integrate_dht_ops_workflow::disintegrate_single_metadata;
+use crate::core::{
+    queue_consumer::{OneshotWriter, TriggerSender, WorkComplete},
+    state::{
+        dht_op_integration::{IntegratedDhtOpsBuf, IntegratedDhtOpsValue},
+        element_buf::ElementBuf,
+        entry_ops_index::EntryOpsIndexBuf,
+        metadata::MetadataBuf,
+        workspace::{Workspace, WorkspaceResult},
+    },
+};
+use fallible_iterator::FallibleIterator;
+use holo_hash::{AgentPubKey, AnyDhtHash, DhtOpHash};
+use holochain_p2p::{dht_arc::DhtArc, HolochainP2pCellT};
+use holochain_state::{buffer::BufferedStore, env::EnvironmentWrite, fresh_reader, prelude::*};
+use holochain_types::{
+    dht_op::{DhtOp, HeldOpTypes},
+    validate::ValidationStatus,
+};
+use std::collections::HashSet;
+use tracing::*;
+
+/// A change to the arc of the DHT a cell holds
+#[derive(Clone, Debug)]
+pub struct ArcResize {
+    /// The agent of the cell, we don't count ourselves as redundancy
+    pub agent: AgentPubKey,
+    /// The arc before the resize
+    pub old_arc: DhtArc,
+    /// The arc after the resize
+    pub new_arc: DhtArc,
+    /// How many other agents must hold an op before we release it
+    pub min_redundancy: usize,
+}
+
+/// What a run of the workflow did with the ops that changed coverage
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArcResizeOutcome {
+    /// Ops released to the rest of the network
+    pub released: Vec<DhtOpHash>,
+    /// Ops still held until enough other agents hold them
+    pub awaiting_redundancy: Vec<DhtOpHash>,
+    /// Ops that fell into the arc and were fetched from their holders
+    pub fetched: Vec<DhtOpHash>,
+    /// Some holders of newly covered ops couldn't be reached
+    pub fetch_incomplete: bool,
+}
+
+#[instrument(skip(workspace, env, network, trigger_sys, ingress_filter))]
+pub async fn arc_resize_workflow(
+    mut workspace: ArcResizeWorkspace,
+    env: EnvironmentWrite,
+    network: &mut impl HolochainP2pCellT,
+    resize: ArcResize,
+    trigger_sys: TriggerSender,
+    ingress_filter: OpIngressFilterRef,
+) -> WorkflowResult<WorkComplete> {
+    let (outcome, ops) = arc_resize_workflow_inner(&mut workspace, network, &resize).await?;
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    // commit the workspace
+    OneshotWriter::new(env.clone())
+        .with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+
+    // validate and integrate the ops we now cover
+    if !ops.is_empty() {
+        incoming_dht_ops_workflow(&env, trigger_sys, ops, ingress_filter).await?;
+    }
+
+    Ok(
+        if outcome.awaiting_redundancy.is_empty() && !outcome.fetch_incomplete {
+            WorkComplete::Complete
+        } else {
+            WorkComplete::Incomplete
+        },
+    )
+}
+
+/// Release every op that fell out of the arc and is held by enough other
+/// agents, and fetch every op that fell into the arc.
+///
+/// The fetched ops are returned rather than written so the caller can pass
+/// them through validation.
+pub async fn arc_resize_workflow_inner(
+    workspace: &mut ArcResizeWorkspace,
+    network: &mut impl HolochainP2pCellT,
+    resize: &ArcResize,
+) -> WorkflowResult<(ArcResizeOutcome, Vec<(DhtOpHash, DhtOp)>)> {
+    let env = workspace.integrated_dht_ops.env().clone();
+    let ArcResize {
+        agent,
+        old_arc,
+        new_arc,
+        min_redundancy,
+    } = resize;
+
+    let mut outcome = ArcResizeOutcome::default();
+    let uncovered: Vec<(DhtOpHash, IntegratedDhtOpsValue)> = fresh_reader!(env, |r| workspace
+        .integrated_dht_ops
+        .iter(&r)?
+        .map(|(k, v)| Ok((DhtOpHash::with_pre_hashed(k.to_vec()), v)))
+        .filter(|(_, v)| {
+            let loc = v.op.dht_basis().get_loc();
+            Ok(old_arc.contains(loc) && !new_arc.contains(loc))
+        })
+        .collect())?;
+
+    for (op_hash, value) in uncovered {
+        let basis = value.op.dht_basis().clone();
+        // Only the agents covering the basis are asked
+        let holders = match network
+            .fetch_op_hashes(basis.clone(), DhtArc::new(basis.get_loc(), 1), None)
+            .await
+        {
+            Ok(holders) => holders,
+            Err(e) => {
+                warn!(?op_hash, ?e, "Couldn't confirm redundancy");
+                outcome.awaiting_redundancy.push(op_hash);
+                continue;
+            }
+        };
+        let redundancy = holders
+            .iter()
+            .filter(|(holder, op_hashes)| holder != agent && op_hashes.contains(&op_hash))
+            .count();
+        if redundancy >= *min_redundancy {
+            debug!(?op_hash, "releasing");
+            workspace.release(op_hash.clone(), value)?;
+            outcome.released.push(op_hash);
+        } else {
+            outcome.awaiting_redundancy.push(op_hash);
+        }
+    }
+
+    let ops = if new_arc.range().is_empty() {
+        Vec::new()
+    } else {
+        fetch_newly_covered(workspace, network, resize, &mut outcome).await?
+    };
+    Ok((outcome, ops))
+}
+
+/// Ask the agents around our arc for the ops we don't hold yet
+async fn fetch_newly_covered(
+    workspace: &ArcResizeWorkspace,
+    network: &mut impl HolochainP2pCellT,
+    resize: &ArcResize,
+    outcome: &mut ArcResizeOutcome,
+) -> WorkflowResult<Vec<(DhtOpHash, DhtOp)>> {
+    let ArcResize {
+        agent,
+        old_arc,
+        new_arc,
+        ..
+    } = resize;
+    let holders = match network
+        .fetch_op_hashes(AnyDhtHash::from(agent.clone()), *new_arc, None)
+        .await
+    {
+        Ok(holders) => holders,
+        Err(e) => {
+            warn!(?e, "Couldn't ask for the ops in the new arc");
+            outcome.fetch_incomplete = true;
+            return Ok(Vec::new());
+        }
+    };
+
+    let mut requested = HashSet::new();
+    let mut ops = Vec::new();
+    for (holder, op_hashes) in holders {
+        if holder == *agent {
+            continue;
+        }
+        let mut wanted = Vec::new();
+        for op_hash in op_hashes {
+            if !requested.contains(&op_hash) && !workspace.integrated_dht_ops.contains(&op_hash)? {
+                wanted.push(op_hash);
+            }
+        }
+        if wanted.is_empty() {
+            continue;
+        }
+        match network.fetch_op_data(holder.clone(), wanted.clone()).await {
+            Ok(data) => {
+                requested.extend(wanted);
+                // The holder answers for its whole arc so only keep the ops
+                // that weren't already ours to hold
+                for (basis, op_hash, op) in data {
+                    let loc = basis.get_loc();
+                    if new_arc.contains(loc) && !old_arc.contains(loc) {
+                        outcome.fetched.push(op_hash.clone());
+                        ops.push((op_hash, op));
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(?holder, ?e, "Couldn't fetch ops in the new arc");
+                outcome.fetch_incomplete = true;
+            }
+        }
+    }
+    Ok(ops)
+}
+
+pub struct ArcResizeWorkspace {
+    // integrated ops
+    pub integrated_dht_ops: IntegratedDhtOpsBuf,
+    // integrated ops indexed by the entries they reference
+    pub entry_ops_index: EntryOpsIndexBuf,
+    // needed to look up headers when removing metadata
+    pub elements: ElementBuf,
+    pub meta: MetadataBuf,
+    pub element_rejected: ElementBuf<RejectedPrefix>,
+    pub meta_rejected: MetadataBuf<RejectedPrefix>,
+    // Which types of ops we are an authority for
+    pub held_op_types: HeldOpTypes,
+}
+
+impl Workspace for ArcResizeWorkspace {
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> WorkspaceResult<()> {
+        self.meta.flush_to_txn_ref(writer)?;
+        self.meta_rejected.flush_to_txn_ref(writer)?;
+        self.entry_ops_index.flush_to_txn_ref(writer)?;
+        self.integrated_dht_ops.flush_to_txn_ref(writer)?;
+        Ok(())
+    }
+}
+
+impl ArcResizeWorkspace {
+    /// Constructor
+    pub fn new(env: EnvironmentRead) -> WorkspaceResult<Self> {
+        let integrated_dht_ops = IntegratedDhtOpsBuf::new(env.clone())?;
+        let entry_ops_index = EntryOpsIndexBuf::new(&env)?;
+
+        let elements = ElementBuf::vault(env.clone(), true)?;
+        let meta = MetadataBuf::vault(env.clone())?;
+        let element_rejected = ElementBuf::rejected(env.clone())?;
+        let meta_rejected = MetadataBuf::rejected(env)?;
+
+        Ok(Self {
+            integrated_dht_ops,
+            entry_ops_index,
+            elements,
+            meta,
+            element_rejected,
+            meta_rejected,
+            held_op_types: HeldOpTypes::default(),
+        })
+    }
+
+    /// Stop being an authority for an op
+    fn release(&mut self, hash: DhtOpHash, v: IntegratedDhtOpsValue) -> WorkflowResult<()> {
+        // Metadata is only registered for ops we hold
+        if self.held_op_types.is_held(v.op.get_type()) {
+            match v.validation_status {
+                ValidationStatus::Valid => {
+                    disintegrate_single_metadata(v.op.clone(), &self.elements, &mut self.meta)?
+                }
+                ValidationStatus::Rejected => disintegrate_single_metadata(
+                    v.op.clone(),
+                    &self.element_rejected,
+                    &mut self.meta_rejected,
+                )?,
+                ValidationStatus::Abandoned => (),
+            }
+        }
+        self.entry_ops_index.remove_op(&v.op, &hash);
+        self.integrated_dht_ops.delete(hash)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::{fixt::AgentPubKeyFixturator, hash_type::AnyDht, HeaderHash};
+    use holochain_p2p::{dht_arc::MAX_HALF_LENGTH, MockHolochainP2pCellT};
+    use holochain_state::test_utils::test_cell_env;
+    use holochain_types::{
+        dht_op::{DhtOpLight, DhtOpType},
+        fixt::{HeaderFixturator, SignatureFixturator},
+        Timestamp,
+    };
+    use std::collections::HashMap;
+
+    fn basis_at(loc: u32) -> AnyDhtHash {
+        let mut bytes = vec![0; 32];
+        bytes.extend_from_slice(&loc.to_le_bytes());
+        AnyDhtHash::from_raw_bytes_and_type(bytes, AnyDht::Header)
+    }
+
+    fn op_at(n: u8, loc: u32) -> (DhtOpHash, IntegratedDhtOpsValue) {
+        let mut header_bytes = vec![n; 32];
+        header_bytes.extend_from_slice(&[0; 4]);
+        let mut op_bytes = vec![n; 32];
+        op_bytes.extend_from_slice(&loc.to_le_bytes());
+        (
+            DhtOpHash::from_raw_bytes(op_bytes),
+            IntegratedDhtOpsValue {
+                validation_status: ValidationStatus::Valid,
+                op: DhtOpLight::RegisterAgentActivity(
+                    HeaderHash::from_raw_bytes(header_bytes),
+                    basis_at(loc),
+                ),
+                when_integrated: Timestamp::now(),
+            },
+        )
+    }
+
+    fn test_workspace(env: &EnvironmentWrite) -> ArcResizeWorkspace {
+        let mut workspace = ArcResizeWorkspace::new(env.clone().into()).unwrap();
+        // The test ops have no element data behind them
+        workspace.held_op_types = HeldOpTypes::default().disable(DhtOpType::RegisterAgentActivity);
+        workspace
+    }
+
+    /// A network where each agent holds the listed ops at their basis
+    fn network_holding(
+        held: Vec<(AgentPubKey, Vec<(DhtOpHash, AnyDhtHash)>)>,
+    ) -> MockHolochainP2pCellT {
+        let mut network = MockHolochainP2pCellT::new();
+        network
+            .expect_fetch_op_hashes()
+            .returning(move |_, dht_arc, _| {
+                Ok(held
+                    .iter()
+                    .map(|(agent, ops)| {
+                        let in_arc = ops
+                            .iter()
+                            .filter(|(_, basis)| dht_arc.contains(basis.get_loc()))
+                            .map(|(op_hash, _)| op_hash.clone())
+                            .collect();
+                        (agent.clone(), in_arc)
+                    })
+                    .collect())
+            });
+        network
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn shrinking_arc_releases_only_redundant_ops() {
+        holochain_types::observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let env_ref = env.guard();
+        let agent = fixt!(AgentPubKey);
+        let bob = fixt!(AgentPubKey);
+        let carol = fixt!(AgentPubKey);
+
+        let (still_covered, still_covered_value) = op_at(1, 10);
+        let (redundant, redundant_value) = op_at(2, u32::MAX / 2);
+        let (not_redundant, not_redundant_value) = op_at(3, u32::MAX / 2 + 5);
+        let redundant_basis = redundant_value.op.dht_basis().clone();
+        let not_redundant_basis = not_redundant_value.op.dht_basis().clone();
+
+        // Integrate the ops
+        {
+            let mut workspace = test_workspace(&env);
+            for (hash, value) in vec![
+                (still_covered.clone(), still_covered_value),
+                (redundant.clone(), redundant_value),
+                (not_redundant.clone(), not_redundant_value),
+            ] {
+                workspace.entry_ops_index.add_op(&value.op, hash.clone());
+                workspace.integrated_dht_ops.put(hash, value).unwrap();
+            }
+            env_ref
+                .with_commit(|writer| workspace.flush_to_txn(writer))
+                .unwrap();
+        }
+
+        // Shrink from the whole dht to a small arc around 0
+        let resize = ArcResize {
+            agent: agent.clone(),
+            old_arc: DhtArc::new(0, MAX_HALF_LENGTH),
+            new_arc: DhtArc::new(0, 1000),
+            min_redundancy: 2,
+        };
+
+        let mut network = network_holding(vec![
+            (
+                bob.clone(),
+                vec![
+                    (redundant.clone(), redundant_basis.clone()),
+                    (not_redundant.clone(), not_redundant_basis.clone()),
+                ],
+            ),
+            (carol.clone(), vec![(redundant.clone(), redundant_basis)]),
+            // We don't count as redundancy
+            (
+                agent.clone(),
+                vec![(not_redundant.clone(), not_redundant_basis.clone())],
+            ),
+        ]);
+        let mut workspace = test_workspace(&env);
+        let (outcome, ops) = arc_resize_workflow_inner(&mut workspace, &mut network, &resize)
+            .await
+            .unwrap();
+        env_ref
+            .with_commit(|writer| workspace.flush_to_txn(writer))
+            .unwrap();
+        assert_eq!(
+            ArcResizeOutcome {
+                released: vec![redundant.clone()],
+                awaiting_redundancy: vec![not_redundant.clone()],
+                ..Default::default()
+            },
+            outcome
+        );
+        assert!(ops.is_empty());
+
+        let workspace = test_workspace(&env);
+        assert!(workspace
+            .integrated_dht_ops
+            .contains(&still_covered)
+            .unwrap());
+        assert!(!workspace.integrated_dht_ops.contains(&redundant).unwrap());
+        assert!(workspace
+            .integrated_dht_ops
+            .contains(&not_redundant)
+            .unwrap());
+
+        // Once another agent holds the op it is released too
+        let mut network = network_holding(vec![
+            (
+                bob,
+                vec![(not_redundant.clone(), not_redundant_basis.clone())],
+            ),
+            (carol, vec![(not_redundant.clone(), not_redundant_basis)]),
+        ]);
+        let mut workspace = test_workspace(&env);
+        let (outcome, _) = arc_resize_workflow_inner(&mut workspace, &mut network, &resize)
+            .await
+            .unwrap();
+        env_ref
+            .with_commit(|writer| workspace.flush_to_txn(writer))
+            .unwrap();
+        assert_eq!(
+            ArcResizeOutcome {
+                released: vec![not_redundant.clone()],
+                ..Default::default()
+            },
+            outcome
+        );
+
+        let workspace = test_workspace(&env);
+        assert!(workspace
+            .integrated_dht_ops
+            .contains(&still_covered)
+            .unwrap());
+        assert!(!workspace
+            .integrated_dht_ops
+            .contains(&not_redundant)
+            .unwrap());
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn growing_arc_fetches_newly_covered_ops() {
+        holochain_types::observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let env_ref = env.guard();
+        let agent = fixt!(AgentPubKey);
+        let bob = fixt!(AgentPubKey);
+
+        let op = |n| {
+            let op = DhtOp::RegisterAgentActivity(fixt!(Signature), fixt!(Header));
+            let mut bytes = vec![n; 32];
+            bytes.extend_from_slice(&[0; 4]);
+            (DhtOpHash::from_raw_bytes(bytes), op)
+        };
+        let (already_held, already_held_op) = op(1);
+        let (newly_covered, newly_covered_op) = op(2);
+        let already_held_basis = basis_at(10);
+        let newly_covered_basis = basis_at(u32::MAX / 2);
+
+        // We already integrated the op inside the old arc
+        {
+            let mut workspace = test_workspace(&env);
+            let (_, value) = op_at(1, 10);
+            workspace
+                .integrated_dht_ops
+                .put(already_held.clone(), value)
+                .unwrap();
+            env_ref
+                .with_commit(|writer| workspace.flush_to_txn(writer))
+                .unwrap();
+        }
+
+        let mut network = network_holding(vec![(
+            bob.clone(),
+            vec![
+                (already_held.clone(), already_held_basis.clone()),
+                (newly_covered.clone(), newly_covered_basis.clone()),
+            ],
+        )]);
+        let data: HashMap<_, _> = vec![
+            (already_held.clone(), (already_held_basis, already_held_op)),
+            (
+                newly_covered.clone(),
+                (newly_covered_basis, newly_covered_op.clone()),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        // Only the op we don't hold is asked for
+        network
+            .expect_fetch_op_data()
+            .times(1)
+            .returning(move |to_agent, op_hashes| {
+                assert_eq!(to_agent, bob);
+                Ok(op_hashes
+                    .into_iter()
+                    .map(|h| {
+                        let (basis, op) = data[&h].clone();
+                        (basis, h, op)
+                    })
+                    .collect())
+            });
+
+        let resize = ArcResize {
+            agent,
+            old_arc: DhtArc::new(0, 1000),
+            new_arc: DhtArc::new(0, MAX_HALF_LENGTH),
+            min_redundancy: 2,
+        };
+        let mut workspace = test_workspace(&env);
+        let (outcome, ops) = arc_resize_workflow_inner(&mut workspace, &mut network, &resize)
+            .await
+            .unwrap();
+        assert_eq!(
+            ArcResizeOutcome {
+                fetched: vec![newly_covered.clone()],
+                ..Default::default()
+            },
+            outcome
+        );
+        assert_eq!(ops, vec![(newly_covered, newly_covered_op)]);
+    }
+}
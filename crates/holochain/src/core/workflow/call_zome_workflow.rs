@@ -1,4 +1,6 @@
 use super::error::{WorkflowError, WorkflowResult};
+use crate::conductor::config::HostFnBudgetConfig;
+use crate::core::ribosome::cancel::ZomeCallCancel;
 use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::{ValidateHostAccess, ValidateResult};
@@ -7,13 +9,17 @@ use crate::core::ribosome::guest_callback::validate_link_add::ValidateCreateLink
 use crate::core::ribosome::guest_callback::validate_link_add::ValidateCreateLinkResult;
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::ribosome::{error::RibosomeResult, RibosomeT, ZomeCallHostAccess};
+use crate::core::signal::SignalSender;
 use crate::core::state::source_chain::SourceChainError;
 use crate::core::state::workspace::Workspace;
 use crate::core::{
     queue_consumer::{OneshotWriter, TriggerSender},
     state::{
-        cascade::Cascade, element_buf::ElementBuf, metadata::MetadataBuf,
-        source_chain::SourceChain, workspace::WorkspaceResult,
+        cascade::Cascade,
+        element_buf::ElementBuf,
+        metadata::MetadataBuf,
+        source_chain::{SourceChain, DEFAULT_READ_CACHE_CAPACITY},
+        workspace::WorkspaceResult,
     },
     sys_validate_element,
 };
@@ -22,6 +28,7 @@ use fallible_iterator::FallibleIterator;
 use holo_hash::AnyDhtHash;
 use holochain_keystore::KeystoreSender;
 use holochain_p2p::HolochainP2pCell;
+use holochain_state::env::EnvironmentWrite;
 use holochain_state::prelude::*;
 use holochain_types::element::Element;
 use holochain_zome_types::entry::GetOptions;
@@ -40,6 +47,14 @@ pub type ZomeCallInvocationResult = RibosomeResult<ZomeCallResponse>;
 pub struct CallZomeWorkflowArgs<Ribosome: RibosomeT> {
     pub ribosome: Ribosome,
     pub invocation: ZomeCallInvocation,
+    /// The Cell only observes the DHT so commits fail
+    pub read_only: bool,
+    /// The most host functions that reach the network the call can make
+    pub host_fn_budget: HostFnBudgetConfig,
+    /// Cancels the call, releasing any host function blocked on async work
+    pub cancel: ZomeCallCancel,
+    /// Where the signals the zome emits go, if anywhere
+    pub signal_tx: Option<SignalSender>,
 }
 
 #[instrument(skip(workspace, network, keystore, writer, args, trigger_produce_dht_ops))]
@@ -77,6 +92,10 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
     let CallZomeWorkflowArgs {
         ribosome,
         invocation,
+        read_only,
+        host_fn_budget,
+        cancel,
+        signal_tx,
     } = args;
 
     let zome_name = invocation.zome_name.clone();
@@ -94,9 +113,22 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
     tracing::trace!(line = line!());
     // Create the unsafe sourcechain for use with wasm closure
     let result = {
-        let host_access =
+        let mut host_access =
             ZomeCallHostAccess::new(workspace_lock.clone(), keystore, network.clone());
-        ribosome.call_zome_function(host_access, invocation)
+        host_access.read_only = read_only;
+        host_access.host_fn_budget = host_fn_budget;
+        host_access.cancel = cancel;
+        host_access.signal_tx = signal_tx;
+        #[cfg(feature = "profiling")]
+        let host_fn_calls = host_access.host_fn_calls.clone();
+        let result = ribosome.call_zome_function(host_access, invocation);
+        #[cfg(feature = "profiling")]
+        tracing::info!(
+            zome = %zome_name,
+            host_fn_calls = ?host_fn_calls.snapshot(),
+            "zome call profile"
+        );
+        result
     };
     tracing::trace!(line = line!());
 
@@ -111,7 +143,7 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
         // Has there been changes?
         if chain_head_start != *chain_head_end {
             // get the changes
-            let mut new_headers = workspace
+            let mut new_elements = workspace
                 .source_chain
                 .iter_back()
                 .scan(None, |current_header, element| {
@@ -125,97 +157,87 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
                 })
                 .map_err(WorkflowError::from);
 
-            while let Some(header) = new_headers.next()? {
-                let chain_element = workspace
-                    .source_chain
-                    .get_element(header.header_address())?;
-                let prev_chain_element = match chain_element {
-                    Some(ref c) => match c.header().prev_header() {
-                        Some(h) => workspace.source_chain.get_element(&h)?,
-                        None => None,
-                    },
+            while let Some(chain_element) = new_elements.next()? {
+                let prev_chain_element = match chain_element.header().prev_header() {
+                    Some(h) => workspace.source_chain.get_element(&h)?,
                     None => None,
                 };
-                if let Some(ref chain_element) = chain_element {
-                    sys_validate_element(&agent_key, chain_element, prev_chain_element.as_ref())
-                        .await?;
-                    to_app_validate.push(chain_element.to_owned());
-                }
+                sys_validate_element(&agent_key, &chain_element, prev_chain_element.as_ref())
+                    .await?;
+                to_app_validate.push(chain_element);
             }
         }
         to_app_validate
     };
 
-    {
-        let mut workspace = workspace_lock.write().await;
-        let mut cascade = workspace.cascade(network);
-        for chain_element in to_app_validate {
-            // @todo have app validate in its own workflow
-            if let Header::CreateLink(link_add) = chain_element.header() {
-                let validate: ValidateCreateLinkResult = ribosome.run_validate_link_add(
-                    ValidateCreateLinkHostAccess,
-                    ValidateCreateLinkInvocation {
-                        zome_name: zome_name.clone(),
-                        base: Arc::new({
-                            let base_address: AnyDhtHash = link_add.base_address.clone().into();
-                            #[allow(clippy::eval_order_dependence)]
-                            cascade
-                                .dht_get(base_address.clone(), GetOptions.into())
-                                .await
-                                .map_err(RibosomeError::from)?
-                                .ok_or_else(|| RibosomeError::ElementDeps(base_address.clone()))?
-                                .entry()
-                                .as_option()
-                                .ok_or_else(|| RibosomeError::ElementDeps(base_address.clone()))?
-                                .to_owned()
-                        }),
-                        target: Arc::new({
-                            let target_address: AnyDhtHash = link_add.target_address.clone().into();
-                            #[allow(clippy::eval_order_dependence)]
-                            cascade
-                                .dht_get(target_address.clone(), GetOptions.into())
-                                .await
-                                .map_err(RibosomeError::from)?
-                                .ok_or_else(|| RibosomeError::ElementDeps(target_address.clone()))?
-                                .entry()
-                                .as_option()
-                                .ok_or_else(|| RibosomeError::ElementDeps(target_address.clone()))?
-                                .to_owned()
-                        }),
-                        link_add: Arc::new(link_add.to_owned()),
-                    },
-                )?;
-                match validate {
-                    ValidateCreateLinkResult::Valid => {}
-                    ValidateCreateLinkResult::Invalid(reason) => {
-                        return Err(SourceChainError::InvalidCreateLink(reason).into());
-                    }
+    for chain_element in to_app_validate {
+        // @todo have app validate in its own workflow
+        if let Header::CreateLink(link_add) = chain_element.header() {
+            let mut workspace = workspace_lock.write().await;
+            let mut cascade = workspace.cascade(network.clone());
+            let validate: ValidateCreateLinkResult = ribosome.run_validate_link_add(
+                ValidateCreateLinkHostAccess,
+                ValidateCreateLinkInvocation {
+                    zome_name: zome_name.clone(),
+                    base: Arc::new({
+                        let base_address: AnyDhtHash = link_add.base_address.clone().into();
+                        #[allow(clippy::eval_order_dependence)]
+                        cascade
+                            .dht_get(base_address.clone(), GetOptions::default().into())
+                            .await
+                            .map_err(RibosomeError::from)?
+                            .ok_or_else(|| RibosomeError::ElementDeps(base_address.clone()))?
+                            .entry()
+                            .as_option()
+                            .ok_or_else(|| RibosomeError::ElementDeps(base_address.clone()))?
+                            .to_owned()
+                    }),
+                    target: Arc::new({
+                        let target_address: AnyDhtHash = link_add.target_address.clone().into();
+                        #[allow(clippy::eval_order_dependence)]
+                        cascade
+                            .dht_get(target_address.clone(), GetOptions::default().into())
+                            .await
+                            .map_err(RibosomeError::from)?
+                            .ok_or_else(|| RibosomeError::ElementDeps(target_address.clone()))?
+                            .entry()
+                            .as_option()
+                            .ok_or_else(|| RibosomeError::ElementDeps(target_address.clone()))?
+                            .to_owned()
+                    }),
+                    link_add: Arc::new(link_add.to_owned()),
+                },
+            )?;
+            match validate {
+                ValidateCreateLinkResult::Valid => {}
+                ValidateCreateLinkResult::Invalid(reason) => {
+                    return Err(SourceChainError::InvalidCreateLink(reason).into());
                 }
             }
+        }
 
-            if let holochain_types::element::ElementEntry::Present(entry) = chain_element.entry() {
-                let validate: ValidateResult = ribosome.run_validate(
-                    ValidateHostAccess,
-                    ValidateInvocation {
-                        zome_name: zome_name.clone(),
-                        entry: Arc::new(entry.clone()),
-                    },
-                )?;
-                match validate {
-                    ValidateResult::Valid => {}
-                    // when the wasm is being called directly in a zome invocation any
-                    // state other than valid is not allowed for new entries
-                    // e.g. we require that all dependencies are met when committing an
-                    // entry to a local source chain
-                    // this is different to the case where we are validating data coming in
-                    // from the network where unmet dependencies would need to be
-                    // rescheduled to attempt later due to partitions etc.
-                    ValidateResult::Invalid(reason) => {
-                        return Err(SourceChainError::InvalidCommit(reason).into());
-                    }
-                    ValidateResult::UnresolvedDependencies(hashes) => {
-                        return Err(SourceChainError::InvalidCommit(format!("{:?}", hashes)).into());
-                    }
+        if let holochain_types::element::ElementEntry::Present(entry) = chain_element.entry() {
+            let validate: ValidateResult = ribosome.run_validate(
+                ValidateHostAccess::new(workspace_lock.clone(), network.clone()),
+                ValidateInvocation {
+                    zome_name: zome_name.clone(),
+                    entry: Arc::new(entry.clone()),
+                },
+            )?;
+            match validate {
+                ValidateResult::Valid => {}
+                // when the wasm is being called directly in a zome invocation any
+                // state other than valid is not allowed for new entries
+                // e.g. we require that all dependencies are met when committing an
+                // entry to a local source chain
+                // this is different to the case where we are validating data coming in
+                // from the network where unmet dependencies would need to be
+                // rescheduled to attempt later due to partitions etc.
+                ValidateResult::Invalid(reason) => {
+                    return Err(SourceChainError::InvalidCommit(reason).into());
+                }
+                ValidateResult::UnresolvedDependencies(hashes) => {
+                    return Err(SourceChainError::InvalidCommit(format!("{:?}", hashes)).into());
                 }
             }
         }
@@ -233,7 +255,13 @@ pub struct CallZomeWorkspace {
 
 impl<'a> CallZomeWorkspace {
     pub fn new(env: EnvironmentRead) -> WorkspaceResult<Self> {
-        let source_chain = SourceChain::new(env.clone())?;
+        Self::with_read_cache(env, DEFAULT_READ_CACHE_CAPACITY)
+    }
+
+    /// Cache up to `capacity` headers and elements read from the source chain
+    /// for the life of the workspace
+    pub fn with_read_cache(env: EnvironmentRead, capacity: usize) -> WorkspaceResult<Self> {
+        let source_chain = SourceChain::new(env.clone())?.with_read_cache(capacity);
         let cache_cas = ElementBuf::cache(env.clone())?;
         let meta = MetadataBuf::vault(env.clone())?;
         let cache_meta = MetadataBuf::cache(env)?;
@@ -246,6 +274,14 @@ impl<'a> CallZomeWorkspace {
         })
     }
 
+    /// Commit everything in the scratch to this environment.
+    /// Lets tests persist what a call has done so far and
+    /// read it back through a fresh workspace.
+    pub fn flush_to(&mut self, env: &EnvironmentWrite) -> WorkspaceResult<()> {
+        env.guard()
+            .with_commit(|writer| self.flush_to_txn_ref(writer))
+    }
+
     pub fn cascade(&'a mut self, network: HolochainP2pCell) -> Cascade<'a> {
         Cascade::new(
             self.source_chain.env().clone(),
@@ -302,10 +338,46 @@ pub mod tests {
         let args = CallZomeWorkflowArgs {
             invocation,
             ribosome,
+            read_only: false,
+            host_fn_budget: Default::default(),
+            cancel: Default::default(),
+            signal_tx: None,
         };
         call_zome_workflow_inner(workspace.into(), network, keystore, args).await
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn source_chain_reads_are_cached_within_a_call() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        {
+            let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+            fake_genesis(&mut workspace.source_chain).await.unwrap();
+            env.guard()
+                .with_commit(|writer| workspace.flush_to_txn(writer))
+                .unwrap();
+        }
+
+        let workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        let source_chain = &workspace.source_chain;
+        let cache = source_chain.read_cache().unwrap();
+        let head = source_chain.chain_head().unwrap().clone();
+
+        let first = source_chain.get_header(&head).unwrap();
+        assert!(first.is_some());
+        assert_eq!(cache.store_reads(), 1);
+        // The second read is served from memory
+        let second = source_chain.get_header(&head).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(cache.store_reads(), 1);
+
+        let element = source_chain.get_element(&head).unwrap();
+        assert!(element.is_some());
+        assert_eq!(cache.store_reads(), 2);
+        assert_eq!(source_chain.get_element(&head).unwrap(), element);
+        assert_eq!(cache.store_reads(), 2);
+    }
+
     // 1.  Check if there is a Capability token secret in the parameters.
     // If there isn't and the function to be called isn't public,
     // we stop the process and return an error. MVT
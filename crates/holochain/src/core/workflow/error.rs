@@ -11,9 +11,14 @@ use crate::{
         SysValidationError,
     },
 };
+use holo_hash::DhtOpHash;
 use holochain_p2p::HolochainP2pError;
 use holochain_state::error::DatabaseError;
-use holochain_types::{dht_op::error::DhtOpError, prelude::*};
+use holochain_types::{
+    dht_op::{error::DhtOpError, DhtOpType},
+    prelude::*,
+    validate::ValidationStatus,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -59,6 +64,15 @@ pub enum WorkflowError {
 
     #[error(transparent)]
     SysValidationError(#[from] SysValidationError),
+
+    #[error("Op {0:?} is not waiting in the integration limbo so it has not been validated")]
+    OpNotValidated(DhtOpHash),
+
+    #[error("Op {0:?} was validated as {1:?} so it can't be forced through as valid")]
+    OpNotValid(DhtOpHash, ValidationStatus),
+
+    #[error("Op {0:?} is a {1:?} which this cell doesn't hold so it can't be integrated")]
+    OpNotHeld(DhtOpHash, DhtOpType),
 }
 
 /// Internal type to handle running workflows
@@ -2,6 +2,7 @@
 //! - Dna
 //! - AgentValidationPkg
 //! - AgentId
+//! - OpenChain, if the agent is migrating from a closed chain
 //!
 
 // FIXME: understand the details of actually getting the DNA
@@ -28,6 +29,8 @@ pub struct GenesisWorkflowArgs {
     dna_file: DnaFile,
     agent_pubkey: AgentPubKey,
     membrane_proof: Option<SerializedBytes>,
+    /// The DNA of the closed chain this one continues, if any
+    prev_dna_hash: Option<DnaHash>,
 }
 
 #[instrument(skip(workspace, writer, api))]
@@ -42,7 +45,7 @@ pub async fn genesis_workflow<'env, Api: CellConductorApiT>(
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     Ok(())
 }
@@ -56,6 +59,7 @@ async fn genesis_workflow_inner<Api: CellConductorApiT>(
         dna_file,
         agent_pubkey,
         membrane_proof,
+        prev_dna_hash,
     } = args;
 
     // TODO: this is a placeholder for a real DPKI request to show intent
@@ -78,6 +82,14 @@ async fn genesis_workflow_inner<Api: CellConductorApiT>(
         .await
         .map_err(WorkflowError::from)?;
 
+    if let Some(prev_dna_hash) = prev_dna_hash {
+        workspace
+            .source_chain
+            .open_chain(prev_dna_hash)
+            .await
+            .map_err(WorkflowError::from)?;
+    }
+
     Ok(())
 }
 
@@ -115,7 +127,7 @@ pub mod tests {
     use holochain_state::test_utils::test_cell_env;
     use holochain_types::{
         observability,
-        test_utils::{fake_agent_pubkey_1, fake_dna_file},
+        test_utils::{fake_agent_pubkey_1, fake_dna_file, fake_dna_hash},
     };
     use holochain_zome_types::Header;
     use matches::assert_matches;
@@ -145,6 +157,7 @@ pub mod tests {
                 dna_file: dna.clone(),
                 agent_pubkey: agent_pubkey.clone(),
                 membrane_proof: None,
+                prev_dna_hash: None,
             };
             let _: () = genesis_workflow(workspace, arc.clone().into(), api, args).await?;
         }
@@ -171,6 +184,42 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn genesis_opens_migrated_chain() -> Result<(), anyhow::Error> {
+        observability::test_run()?;
+        let test_env = test_cell_env();
+        let arc = test_env.env();
+        let dna = fake_dna_file("a");
+        let prev_dna_hash = fake_dna_hash(2);
+
+        {
+            let workspace = GenesisWorkspace::new(arc.clone().into()).await?;
+            let mut api = MockCellConductorApi::new();
+            api.expect_sync_dpki_request()
+                .returning(|_, _| Ok("mocked dpki request response".to_string()));
+            let args = GenesisWorkflowArgs {
+                dna_file: dna.clone(),
+                agent_pubkey: fake_agent_pubkey_1(),
+                membrane_proof: None,
+                prev_dna_hash: Some(prev_dna_hash.clone()),
+            };
+            let _: () = genesis_workflow(workspace, arc.clone().into(), api, args).await?;
+        }
+
+        let source_chain = SourceChain::new(arc.clone().into())?;
+        let head = source_chain
+            .get_header(source_chain.chain_head()?)?
+            .expect("chain head should be set");
+        assert_matches!(
+            head.header(),
+            Header::OpenChain(open) if open.prev_dna_hash == prev_dna_hash
+        );
+        // Init hasn't run just because the chain is one header longer
+        assert!(!source_chain.has_initialized());
+
+        Ok(())
+    }
 }
 
 /* TODO: make doc-able
@@ -3,11 +3,12 @@
 use super::{
     error::WorkflowResult,
     integrate_dht_ops_workflow::{integrate_single_data, integrate_single_metadata},
+    op_trace::op_span,
     produce_dht_ops_workflow::dht_op_light::error::DhtOpConvertResult,
     sys_validation_workflow::types::PendingDependencies,
 };
 use crate::core::{
-    queue_consumer::TriggerSender,
+    queue_consumer::{Priority, TriggerSender},
     state::{
         dht_op_integration::{IntegratedDhtOpsStore, IntegrationLimboStore},
         element_buf::ElementBuf,
@@ -26,35 +27,50 @@ use holochain_state::{
     prelude::{EnvironmentRead, GetDb, PendingPrefix, Writer},
 };
 use holochain_types::{dht_op::DhtOp, Timestamp};
+use std::sync::Arc;
 use tracing::instrument;
+use tracing_futures::Instrument;
+
+pub use ingress_filter::*;
+
+mod ingress_filter;
 
 #[cfg(test)]
 mod test;
 
-#[instrument(skip(state_env, sys_validation_trigger, ops))]
+#[instrument(skip(state_env, sys_validation_trigger, ops, ingress_filter))]
 pub async fn incoming_dht_ops_workflow(
     state_env: &EnvironmentWrite,
     mut sys_validation_trigger: TriggerSender,
     ops: Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
+    ingress_filter: OpIngressFilterRef,
 ) -> WorkflowResult<()> {
     // set up our workspace
     let mut workspace = IncomingDhtOpsWorkspace::new(state_env.clone().into())?;
+    workspace.ingress_filter = ingress_filter;
 
     // add incoming ops to the validation limbo
     for (hash, op) in ops {
         if !workspace.op_exists(&hash)? {
-            tracing::debug!(?op);
-            workspace.add_to_pending(hash, op).await?;
+            let span = op_span("incoming", &hash);
+            span.in_scope(|| tracing::debug!(?op));
+            if let IngressDecision::Reject(reason) = workspace.ingress_filter.filter(&op) {
+                span.in_scope(|| {
+                    tracing::warn!(?reason, "incoming op rejected by the ingress filter")
+                });
+                continue;
+            }
+            workspace.add_to_pending(hash, op).instrument(span).await?;
         }
     }
 
     // commit our transaction
     let writer: crate::core::queue_consumer::OneshotWriter = state_env.clone().into();
 
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
-    // trigger validation of queued ops
-    sys_validation_trigger.trigger();
+    // trigger validation of queued ops ahead of any other work waiting
+    sys_validation_trigger.trigger_priority(Priority::High);
 
     Ok(())
 }
@@ -66,6 +82,8 @@ pub struct IncomingDhtOpsWorkspace {
     pub validation_limbo: ValidationLimboStore,
     pub element_pending: ElementBuf<PendingPrefix>,
     pub meta_pending: MetadataBuf<PendingPrefix>,
+    /// Consulted before each op enters the validation limbo
+    pub ingress_filter: OpIngressFilterRef,
 }
 
 impl Workspace for IncomingDhtOpsWorkspace {
@@ -96,6 +114,7 @@ impl IncomingDhtOpsWorkspace {
             validation_limbo,
             element_pending,
             meta_pending,
+            ingress_filter: Arc::new(AcceptAllOps),
         })
     }
 
@@ -116,6 +135,7 @@ impl IncomingDhtOpsWorkspace {
             time_added: Timestamp::now(),
             last_try: None,
             num_tries: 0,
+            num_app_validations: 0,
             pending_dependencies: PendingDependencies::new(),
         };
         self.validation_limbo.put(hash, vlv)?;
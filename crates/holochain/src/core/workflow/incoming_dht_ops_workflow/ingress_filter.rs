@@ -0,0 +1,43 @@
+//! A hook for deciding which [DhtOp]s from other nodes are taken in for validation.
+
+use holochain_types::dht_op::DhtOp;
+use std::sync::Arc;
+
+/// What the incoming ops workflow should do with an op it has been sent
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IngressDecision {
+    /// Put the op in the validation limbo
+    Accept,
+    /// Drop the op without validating it or recording a status for it
+    Reject(String),
+}
+
+/// Consulted before each incoming op enters the validation limbo.
+/// Useful for content moderation or allowlists of op sizes and types.
+/// This is not validation, rejected ops are simply never held.
+pub trait OpIngressFilter: Send + Sync {
+    /// Decide what to do with this op
+    fn filter(&self, op: &DhtOp) -> IngressDecision;
+}
+
+/// A shared [OpIngressFilter]
+pub type OpIngressFilterRef = Arc<dyn OpIngressFilter>;
+
+/// The default filter, which accepts every op
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AcceptAllOps;
+
+impl OpIngressFilter for AcceptAllOps {
+    fn filter(&self, _op: &DhtOp) -> IngressDecision {
+        IngressDecision::Accept
+    }
+}
+
+impl<F> OpIngressFilter for F
+where
+    F: Fn(&DhtOp) -> IngressDecision + Send + Sync,
+{
+    fn filter(&self, op: &DhtOp) -> IngressDecision {
+        self(op)
+    }
+}
@@ -1,23 +1,70 @@
 use super::*;
 use ::fixt::prelude::*;
 use holochain_state::test_utils::TestEnvironment;
-use holochain_types::{dht_op::DhtOp, fixt::*};
+use holochain_types::{
+    dht_op::{DhtOp, DhtOpType},
+    fixt::*,
+};
 
 #[tokio::test(threaded_scheduler)]
 async fn incoming_ops_to_limbo() {
     let TestEnvironment { env, tmpdir: _t } = holochain_state::test_utils::test_cell_env();
-    let (sys_validation_trigger, mut rx) = TriggerSender::new();
+    let (sys_validation_trigger, mut rx) = TriggerSender::new_prioritized();
     let op = DhtOp::RegisterAgentActivity(fixt!(Signature), fixt!(Header));
     let op_light = op.to_light().await;
     let hash = DhtOpHash::with_data_sync(&op);
     let ops = vec![(hash.clone(), op.clone())];
 
-    incoming_dht_ops_workflow(&env, sys_validation_trigger.clone(), ops)
+    incoming_dht_ops_workflow(
+        &env,
+        sys_validation_trigger.clone(),
+        ops,
+        Arc::new(AcceptAllOps),
+    )
+    .await
+    .unwrap();
+    // Ops from peers are validated ahead of other work
+    assert_eq!(rx.listen().await.unwrap(), Priority::High);
+
+    let workspace = IncomingDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    let r = workspace.validation_limbo.get(&hash).unwrap().unwrap();
+    assert_eq!(r.op, op_light);
+}
+
+/// An ingress filter that rejects one type of op keeps it out of the limbo
+/// while the rest are taken in for validation
+#[tokio::test(threaded_scheduler)]
+async fn ingress_filter_drops_op_type() {
+    let TestEnvironment { env, tmpdir: _t } = holochain_state::test_utils::test_cell_env();
+    let (sys_validation_trigger, mut rx) = TriggerSender::new();
+    let rejected = DhtOp::RegisterAgentActivity(fixt!(Signature), fixt!(Header));
+    let rejected_hash = DhtOpHash::with_data_sync(&rejected);
+    let accepted = DhtOp::StoreElement(fixt!(Signature), fixt!(Dna).into(), None);
+    let accepted_hash = DhtOpHash::with_data_sync(&accepted);
+    let ops = vec![
+        (rejected_hash.clone(), rejected),
+        (accepted_hash.clone(), accepted),
+    ];
+
+    let filter = |op: &DhtOp| match op.get_type() {
+        DhtOpType::RegisterAgentActivity => IngressDecision::Reject("no activity".into()),
+        _ => IngressDecision::Accept,
+    };
+    incoming_dht_ops_workflow(&env, sys_validation_trigger, ops, Arc::new(filter))
         .await
         .unwrap();
     rx.listen().await.unwrap();
 
     let workspace = IncomingDhtOpsWorkspace::new(env.clone().into()).unwrap();
-    let r = workspace.validation_limbo.get(&hash).unwrap().unwrap();
-    assert_eq!(r.op, op_light);
+    assert!(!workspace.op_exists(&rejected_hash).unwrap());
+    assert!(workspace
+        .validation_limbo
+        .get(&rejected_hash)
+        .unwrap()
+        .is_none());
+    assert!(workspace
+        .validation_limbo
+        .get(&accepted_hash)
+        .unwrap()
+        .is_some());
 }
@@ -5,33 +5,36 @@ use crate::core::{
     queue_consumer::{OneshotWriter, TriggerSender, WorkComplete},
     state::{
         dht_op_integration::{
-            IntegratedDhtOpsStore, IntegratedDhtOpsValue, IntegrationLimboStore,
+            IntegratedDhtOpsBuf, IntegratedDhtOpsValue, IntegrationLimboStore,
             IntegrationLimboValue,
         },
         element_buf::ElementBuf,
+        entry_ops_index::EntryOpsIndexBuf,
         metadata::{MetadataBuf, MetadataBufT},
         workspace::{Workspace, WorkspaceResult},
     },
 };
-use error::WorkflowResult;
+use error::{WorkflowError, WorkflowResult};
 use fallible_iterator::FallibleIterator;
 use holo_hash::{DhtOpHash, HeaderHash};
 use holochain_keystore::Signature;
 use holochain_state::{
-    buffer::BufferedStore,
-    buffer::KvBufFresh,
-    db::{INTEGRATED_DHT_OPS, INTEGRATION_LIMBO},
+    buffer::{BufferedStore, KvBufFresh},
+    db::INTEGRATION_LIMBO,
+    env::EnvironmentWrite,
     error::DatabaseResult,
     fresh_reader,
     prelude::*,
 };
 use holochain_types::{
-    dht_op::{produce_op_lights_from_elements, DhtOp, DhtOpLight},
+    dht_op::{produce_op_lights_from_elements, DhtOp, DhtOpLight, HeldOpTypes},
     element::{Element, SignedHeaderHashed, SignedHeaderHashedExt},
     validate::ValidationStatus,
     Entry, EntryHashed, Timestamp,
 };
 use holochain_zome_types::{element::SignedHeader, Header};
+use latency::LatencyHistogram;
+use op_trace::op_span;
 use produce_dht_ops_workflow::dht_op_light::{
     error::{DhtOpConvertError, DhtOpConvertResult},
     light_to_op,
@@ -43,13 +46,33 @@ use tracing::*;
 pub use disintegrate::*;
 
 mod disintegrate;
+pub mod latency;
 mod tests;
 
-#[instrument(skip(workspace, writer, trigger_sys))]
+/// How many integration events are buffered before slow subscribers miss some
+pub const INTEGRATION_EVENT_BUFFER_SIZE: usize = 100;
+
+/// Sends an [IntegrationEvent] for each op a Cell finishes integrating
+pub type IntegrationEventSender = tokio::sync::broadcast::Sender<IntegrationEvent>;
+/// A stream of the [IntegrationEvent]s of a Cell
+pub type IntegrationEventReceiver = tokio::sync::broadcast::Receiver<IntegrationEvent>;
+
+/// An op has finished integration with this validation status
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrationEvent {
+    /// The hash of the integrated op
+    pub op_hash: DhtOpHash,
+    /// Whether the op was integrated as valid or rejected,
+    /// or thrown away because it was abandoned
+    pub status: ValidationStatus,
+}
+
+#[instrument(skip(workspace, writer, trigger_sys, integration_events))]
 pub async fn integrate_dht_ops_workflow(
     mut workspace: IntegrateDhtOpsWorkspace,
     writer: OneshotWriter,
     trigger_sys: &mut TriggerSender,
+    integration_events: &IntegrationEventSender,
 ) -> WorkflowResult<WorkComplete> {
     // one of many possible ways to access the env
     let env = workspace.elements.headers().env().clone();
@@ -77,6 +100,10 @@ pub async fn integrate_dht_ops_workflow(
     }
 
     let mut total_integrated: usize = 0;
+    // Only sent once the workspace is committed
+    let mut events = Vec::new();
+    // Only recorded once the workspace is committed
+    let mut latencies = Vec::new();
 
     // Try to process the queue over and over again, until we either exhaust
     // the queue, or we can no longer integrate anything in the queue.
@@ -93,6 +120,14 @@ pub async fn integrate_dht_ops_workflow(
                 value,
                 order,
             } = so.0;
+            let span = op_span("integration", &hash);
+            let _g = span.enter();
+            // Ops of a type we don't hold are released instead of integrated
+            // so we neither act as an authority for them nor gossip them
+            if !workspace.held_op_types.is_held(op.get_type()) {
+                debug!("releasing op of a type that isn't held");
+                continue;
+            }
             // Check validation status and put in correct dbs
             let outcome = match value.validation_status {
                 ValidationStatus::Valid => integrate_single_dht_op(
@@ -111,6 +146,10 @@ pub async fn integrate_dht_ops_workflow(
                     // Throwing away abandoned ops
                     // TODO: keep abandoned ops but remove the entries
                     // and put them in a AbandonedPrefix db
+                    events.push(IntegrationEvent {
+                        op_hash: hash,
+                        status: ValidationStatus::Abandoned,
+                    });
                     continue;
                 }
             };
@@ -120,6 +159,13 @@ pub async fn integrate_dht_ops_workflow(
                     // and separate rejected ops from valid ops.
                     // Currently you need to check the IntegratedDhtOpsValue for
                     // the status
+                    events.push(IntegrationEvent {
+                        op_hash: hash.clone(),
+                        status: integrated.validation_status.clone(),
+                    });
+                    if let Some(time_added) = value.time_added {
+                        latencies.push(latency_since(time_added, integrated.when_integrated));
+                    }
                     workspace.integrate(hash, integrated)?;
                     num_integrated += 1;
                     total_integrated += 1;
@@ -158,7 +204,16 @@ pub async fn integrate_dht_ops_workflow(
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    let histogram = workspace.latency.clone();
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+
+    // let subscribers know, it's fine if there are none
+    for event in events {
+        integration_events.send(event).ok();
+    }
+    for latency in latencies {
+        histogram.record(latency);
+    }
 
     // trigger other workflows
 
@@ -169,6 +224,60 @@ pub async fn integrate_dht_ops_workflow(
     Ok(result)
 }
 
+/// How long an op took from entering the validation limbo to being integrated
+fn latency_since(time_added: Timestamp, when_integrated: Timestamp) -> std::time::Duration {
+    let added: chrono::DateTime<chrono::Utc> = time_added.into();
+    let integrated: chrono::DateTime<chrono::Utc> = when_integrated.into();
+    // Clocks can move backwards so an op added "in the future" took no time
+    (integrated - added).to_std().unwrap_or_default()
+}
+
+/// Integrate one validated op that is stuck in the integration limbo
+/// without waiting for its dependencies to be integrated first.
+///
+/// This is an operator override for recovering a Cell.
+/// The op still goes through the normal data and metadata updates
+/// but ops that haven't finished validation, weren't found valid,
+/// or are of a type that isn't held are refused.
+pub async fn force_integrate_op(
+    env: EnvironmentWrite,
+    op_hash: DhtOpHash,
+    held_op_types: HeldOpTypes,
+) -> WorkflowResult<IntegrationEvent> {
+    let mut workspace = IntegrateDhtOpsWorkspace::new(env.clone().into())?;
+    workspace.held_op_types = held_op_types;
+
+    let iv = workspace
+        .integration_limbo
+        .get(&op_hash)?
+        .ok_or_else(|| WorkflowError::OpNotValidated(op_hash.clone()))?;
+    if iv.validation_status != ValidationStatus::Valid {
+        return Err(WorkflowError::OpNotValid(op_hash, iv.validation_status));
+    }
+
+    let op = light_to_op(iv.op.clone(), &workspace.element_judged).await?;
+    if !workspace.held_op_types.is_held(op.get_type()) {
+        return Err(WorkflowError::OpNotHeld(op_hash, op.get_type()));
+    }
+    integrate_single_data(op, &mut workspace.elements)?;
+    integrate_single_metadata(iv.op.clone(), &workspace.elements, &mut workspace.meta)?;
+    let integrated = IntegratedDhtOpsValue {
+        validation_status: iv.validation_status,
+        op: iv.op,
+        when_integrated: Timestamp::now(),
+    };
+    workspace.integration_limbo.delete(op_hash.clone())?;
+    workspace.integrate(op_hash.clone(), integrated)?;
+
+    env.guard()
+        .with_commit(|writer| workspace.flush_to_txn(writer))?;
+
+    Ok(IntegrationEvent {
+        op_hash,
+        status: ValidationStatus::Valid,
+    })
+}
+
 /// Integrate a single DhtOp to the specified stores.
 ///
 /// The two stores are intended to be either the pair of Vaults,
@@ -426,7 +535,9 @@ pub struct IntegrateDhtOpsWorkspace {
     // integration queue
     pub integration_limbo: IntegrationLimboStore,
     // integrated ops
-    pub integrated_dht_ops: IntegratedDhtOpsStore,
+    pub integrated_dht_ops: IntegratedDhtOpsBuf,
+    // integrated ops indexed by the entries they reference
+    pub entry_ops_index: EntryOpsIndexBuf,
     // Cas for storing
     pub elements: ElementBuf,
     // metadata store
@@ -438,6 +549,10 @@ pub struct IntegrateDhtOpsWorkspace {
     pub meta_rejected: MetadataBuf<RejectedPrefix>,
     // Ops to disintegrate
     pub to_disintegrate_judged: Vec<DhtOpLight>,
+    // Which types of ops we are an authority for
+    pub held_op_types: HeldOpTypes,
+    // Where to record how long integrated ops took to get here
+    pub latency: LatencyHistogram,
 }
 
 impl Workspace for IntegrateDhtOpsWorkspace {
@@ -449,6 +564,7 @@ impl Workspace for IntegrateDhtOpsWorkspace {
         self.meta.flush_to_txn_ref(writer)?;
         // flush integrated
         self.integrated_dht_ops.flush_to_txn_ref(writer)?;
+        self.entry_ops_index.flush_to_txn_ref(writer)?;
         // flush integration queue
         self.integration_limbo.flush_to_txn_ref(writer)?;
         self.element_judged.flush_to_txn_ref(writer)?;
@@ -462,8 +578,8 @@ impl Workspace for IntegrateDhtOpsWorkspace {
 impl IntegrateDhtOpsWorkspace {
     /// Constructor
     pub fn new(env: EnvironmentRead) -> WorkspaceResult<Self> {
-        let db = env.get_db(&*INTEGRATED_DHT_OPS)?;
-        let integrated_dht_ops = KvBufFresh::new(env.clone(), db);
+        let integrated_dht_ops = IntegratedDhtOpsBuf::new(env.clone())?;
+        let entry_ops_index = EntryOpsIndexBuf::new(&env)?;
 
         let db = env.get_db(&*INTEGRATION_LIMBO)?;
         let integration_limbo = KvBufFresh::new(env.clone(), db);
@@ -480,6 +596,7 @@ impl IntegrateDhtOpsWorkspace {
         Ok(Self {
             integration_limbo,
             integrated_dht_ops,
+            entry_ops_index,
             elements,
             meta,
             element_judged,
@@ -487,6 +604,8 @@ impl IntegrateDhtOpsWorkspace {
             element_rejected,
             meta_rejected,
             to_disintegrate_judged: Vec::new(),
+            held_op_types: HeldOpTypes::default(),
+            latency: LatencyHistogram::default(),
         })
     }
 
@@ -494,6 +613,7 @@ impl IntegrateDhtOpsWorkspace {
     fn integrate(&mut self, hash: DhtOpHash, v: IntegratedDhtOpsValue) -> DhtOpConvertResult<()> {
         disintegrate_single_metadata(v.op.clone(), &self.element_judged, &mut self.meta_judged)?;
         self.to_disintegrate_judged.push(v.op.clone());
+        self.entry_ops_index.add_op(&v.op, hash.clone());
         self.integrated_dht_ops.put(hash, v)?;
         Ok(())
     }
@@ -0,0 +1,153 @@
+//! How long ops take from entering the validation limbo to being integrated.
+//!
+//! Every op is stamped with the time it was added to the validation limbo
+//! and validation carries that time into the integration limbo.
+//! Integration records the difference once the op is committed, so
+//! [ValidationLatency] covers the whole trip through sys validation,
+//! app validation and integration.
+
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Exact buckets below this many microseconds and this many
+/// buckets for every doubling above it, so a reported
+/// percentile is within an eighth of the real latency
+const SUB_BUCKETS: u64 = 8;
+/// Enough doublings of [SUB_BUCKETS] to cover every u64 of microseconds
+const BUCKETS: usize = (SUB_BUCKETS + (64 - 3) * SUB_BUCKETS) as usize;
+
+/// Counts op latencies in log scaled buckets of microseconds.
+/// Clones share the same counts so a Cell can read what its
+/// queue consumer records.
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram(Arc<Mutex<Counts>>);
+
+#[derive(Debug)]
+struct Counts {
+    buckets: Vec<u64>,
+    samples: u64,
+    max: u64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(Counts {
+            buckets: vec![0; BUCKETS],
+            samples: 0,
+            max: 0,
+        })))
+    }
+}
+
+impl LatencyHistogram {
+    /// Count the latency of one op
+    pub fn record(&self, latency: Duration) {
+        let micros = latency.as_micros().min(u64::MAX as u128) as u64;
+        let mut counts = self.0.lock();
+        counts.buckets[bucket(micros)] += 1;
+        counts.samples += 1;
+        counts.max = counts.max.max(micros);
+    }
+
+    /// The percentiles of every latency counted so far
+    pub fn percentiles(&self) -> ValidationLatency {
+        let counts = self.0.lock();
+        if counts.samples == 0 {
+            return ValidationLatency::default();
+        }
+        let percentile = |q: f64| {
+            let rank = ((counts.samples as f64 * q).ceil() as u64).max(1);
+            let mut seen = 0;
+            for (i, n) in counts.buckets.iter().enumerate() {
+                seen += n;
+                if seen >= rank {
+                    return Duration::from_micros(upper_bound(i).min(counts.max));
+                }
+            }
+            Duration::from_micros(counts.max)
+        };
+        ValidationLatency {
+            samples: counts.samples,
+            p50: percentile(0.50),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+        }
+    }
+}
+
+/// The bucket counting this many microseconds
+fn bucket(micros: u64) -> usize {
+    if micros < SUB_BUCKETS {
+        return micros as usize;
+    }
+    let doublings = 63 - micros.leading_zeros() as u64 - 3;
+    let sub = (micros >> doublings) - SUB_BUCKETS;
+    (SUB_BUCKETS + doublings * SUB_BUCKETS + sub) as usize
+}
+
+/// The most microseconds counted in this bucket
+fn upper_bound(bucket: usize) -> u64 {
+    let bucket = bucket as u64;
+    if bucket < SUB_BUCKETS {
+        return bucket;
+    }
+    let doublings = (bucket - SUB_BUCKETS) / SUB_BUCKETS;
+    let sub = (bucket - SUB_BUCKETS) % SUB_BUCKETS;
+    // The last bucket ends past u64::MAX
+    let bound = ((SUB_BUCKETS + sub + 1) as u128) << doublings;
+    (bound - 1).min(u64::MAX as u128) as u64
+}
+
+/// Percentiles of how long ops took from entering the validation limbo to
+/// being integrated, from
+/// [ConductorHandle::validation_latency](crate::conductor::handle::ConductorHandleT::validation_latency).
+/// Every latency is zero until an op has been integrated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValidationLatency {
+    /// How many ops were measured
+    pub samples: u64,
+    /// Half of the ops were integrated within this long
+    pub p50: Duration,
+    /// 95% of the ops were integrated within this long
+    pub p95: Duration,
+    /// 99% of the ops were integrated within this long
+    pub p99: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_cover_their_bounds() {
+        for micros in (0..10_000).chain(vec![u64::MAX / 2, u64::MAX]) {
+            let b = bucket(micros);
+            assert!(b < BUCKETS);
+            assert!(micros <= upper_bound(b), "{} is above its bucket", micros);
+            // Within an eighth of the real value
+            assert!(upper_bound(b) - micros <= micros / SUB_BUCKETS);
+        }
+    }
+
+    #[test]
+    fn percentiles_of_recorded_latencies() {
+        let histogram = LatencyHistogram::default();
+        assert_eq!(histogram.percentiles(), ValidationLatency::default());
+
+        for ms in 1..=100 {
+            histogram.clone().record(Duration::from_millis(ms));
+        }
+        let latency = histogram.percentiles();
+        assert_eq!(latency.samples, 100);
+        let near = |d: Duration, ms: u64| {
+            d >= Duration::from_millis(ms) && d <= Duration::from_millis(ms + ms / 8)
+        };
+        assert!(near(latency.p50, 50), "{:?}", latency);
+        assert!(near(latency.p95, 95), "{:?}", latency);
+        assert!(near(latency.p99, 99), "{:?}", latency);
+        // Never more than the slowest op
+        histogram.record(Duration::from_millis(1));
+        assert!(histogram.percentiles().p99 <= Duration::from_millis(100));
+    }
+}
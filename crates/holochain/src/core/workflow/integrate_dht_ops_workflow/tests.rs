@@ -24,7 +24,7 @@ use holochain_state::{
     test_utils::test_cell_env,
 };
 use holochain_types::{
-    dht_op::{DhtOp, DhtOpHashed},
+    dht_op::{DhtOp, DhtOpHashed, DhtOpType},
     fixt::*,
     header::NewEntryHeader,
     metadata::TimedHeaderHash,
@@ -211,6 +211,7 @@ impl Db {
                     let value = IntegrationLimboValue {
                         validation_status: ValidationStatus::Valid,
                         op: op.to_light().await,
+                        time_added: None,
                     };
                     let res = workspace
                         .integration_limbo
@@ -467,6 +468,7 @@ impl Db {
                     let val = IntegrationLimboValue {
                         validation_status: ValidationStatus::Valid,
                         op: op.to_light().await,
+                        time_added: None,
                     };
                     workspace
                         .integration_limbo
@@ -532,7 +534,8 @@ impl Db {
 async fn call_workflow<'env>(env: EnvironmentWrite) {
     let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
     let (mut qt, _rx) = TriggerSender::new();
-    integrate_dht_ops_workflow(workspace, env.clone().into(), &mut qt)
+    let (integration_events, _) = tokio::sync::broadcast::channel(1);
+    integrate_dht_ops_workflow(workspace, env.clone().into(), &mut qt, &integration_events)
         .await
         .unwrap();
 }
@@ -820,6 +823,259 @@ async fn test_ops_state() {
     }
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn ops_not_held_are_released_instead_of_integrated() {
+    use holochain_p2p::dht_arc::{DhtArc, MAX_HALF_LENGTH};
+
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let env_ref = env.guard();
+
+    let td = TestData::new().await;
+    let op = DhtOp::RegisterAgentActivity(td.signature.clone(), td.any_header.clone());
+    let pre_state = add_op_to_judged(vec![Db::IntQueue(op.clone())], &op);
+    Db::set(pre_state, env.clone()).await;
+
+    // Run the workflow without holding agent activity
+    let mut workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    workspace.held_op_types = HeldOpTypes::default().disable(DhtOpType::RegisterAgentActivity);
+    let (mut qt, _rx) = TriggerSender::new();
+    let (integration_events, _) = tokio::sync::broadcast::channel(1);
+    integrate_dht_ops_workflow(workspace, env.clone().into(), &mut qt, &integration_events)
+        .await
+        .unwrap();
+
+    // The op left the limbo without being integrated
+    Db::check(
+        vec![Db::IntegratedEmpty, Db::IntQueueEmpty],
+        env.clone(),
+        here!("not held").to_string(),
+    )
+    .await;
+
+    // So it's not offered to gossip
+    let reader = env_ref.reader().unwrap();
+    let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    let gossiped = workspace
+        .integrated_dht_ops
+        .query(&reader, None, None, Some(DhtArc::new(0, MAX_HALF_LENGTH)))
+        .unwrap()
+        .count()
+        .unwrap();
+    assert_eq!(gossiped, 0);
+
+    // And the activity was never registered
+    let activity = workspace
+        .meta
+        .get_activity(&reader, td.any_header.author().clone())
+        .unwrap()
+        .collect::<Vec<_>>()
+        .unwrap();
+    assert!(activity.is_empty());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn validation_latency_is_recorded_for_integrated_ops() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    const OPS: i64 = 20;
+
+    // Ops that entered the validation limbo 10ms to 200ms ago
+    // and one from the authoring path without a time
+    let mut workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    for i in 0..=OPS {
+        let sig = fixt!(Signature);
+        let header = fixt!(Header);
+        let op = DhtOp::RegisterAgentActivity(sig.clone(), header.clone());
+        let time_added = if i < OPS {
+            Some((chrono::Utc::now() - chrono::Duration::milliseconds((i + 1) * 10)).into())
+        } else {
+            None
+        };
+        let iv = IntegrationLimboValue {
+            validation_status: ValidationStatus::Valid,
+            op: op.to_light().await,
+            time_added,
+        };
+        workspace
+            .integration_limbo
+            .put(DhtOpHash::with_data_sync(&op), iv)
+            .unwrap();
+        let signed_header =
+            SignedHeaderHashed::with_presigned(HeaderHashed::from_content_sync(header), sig);
+        workspace.element_judged.put(signed_header, None).unwrap();
+    }
+    env.guard()
+        .with_commit(|writer| workspace.flush_to_txn(writer))
+        .unwrap();
+
+    let histogram = latency::LatencyHistogram::default();
+    let mut workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    workspace.latency = histogram.clone();
+    let (mut qt, _rx) = TriggerSender::new();
+    let (integration_events, _) = tokio::sync::broadcast::channel(1);
+    integrate_dht_ops_workflow(workspace, env.clone().into(), &mut qt, &integration_events)
+        .await
+        .unwrap();
+
+    let latency = histogram.percentiles();
+    assert_eq!(latency.samples, OPS as u64);
+    let ms = std::time::Duration::from_millis;
+    assert!(latency.p50 >= ms(100), "{:?}", latency);
+    assert!(latency.p50 <= latency.p95 && latency.p95 <= latency.p99);
+    assert!(latency.p99 >= ms(200), "{:?}", latency);
+    assert!(latency.p99 < ms(5_000), "{:?}", latency);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn entry_ops_index_has_store_and_update_ops() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let env_ref = env.guard();
+
+    let td = TestData::new().await;
+    let store_entry = DhtOp::StoreEntry(
+        td.signature.clone(),
+        td.original_header.clone(),
+        td.original_entry.clone().into(),
+    );
+    let updated_by = DhtOp::RegisterUpdatedBy(td.signature.clone(), td.entry_update_entry.clone());
+    let pre_state = vec![
+        Db::IntQueue(store_entry.clone()),
+        Db::IntQueue(updated_by.clone()),
+    ];
+    let pre_state = add_op_to_judged(pre_state, &store_entry);
+    let pre_state = add_op_to_judged(pre_state, &updated_by);
+    Db::set(pre_state, env.clone()).await;
+    call_workflow(env.clone()).await;
+
+    let reader = env_ref.reader().unwrap();
+    let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    let mut ops = workspace
+        .entry_ops_index
+        .ops_for_entry(&reader, &td.original_entry_hash)
+        .unwrap()
+        .collect::<Vec<_>>()
+        .unwrap();
+    ops.sort();
+    let mut expected = vec![
+        DhtOpHashed::from_content_sync(store_entry).into_hash(),
+        DhtOpHashed::from_content_sync(updated_by.clone()).into_hash(),
+    ];
+    expected.sort();
+    assert_eq!(ops, expected);
+
+    // The update is also indexed under the new entry
+    let ops = workspace
+        .entry_ops_index
+        .ops_for_entry(&reader, &td.new_entry_hash)
+        .unwrap()
+        .collect::<Vec<_>>()
+        .unwrap();
+    assert_eq!(
+        ops,
+        vec![DhtOpHashed::from_content_sync(updated_by).into_hash()]
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn force_integrate_stuck_valid_op() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    // The update is valid but stuck because the original entry is never held
+    let td = TestData::new().await;
+    let op = DhtOp::RegisterUpdatedBy(td.signature.clone(), td.entry_update_entry.clone());
+    let op_hash = DhtOpHashed::from_content_sync(op.clone()).into_hash();
+    let pre_state = add_op_to_judged(vec![Db::IntQueue(op.clone())], &op);
+    Db::set(pre_state, env.clone()).await;
+
+    let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    let (mut qt, _rx) = TriggerSender::new();
+    let (integration_events, _) = tokio::sync::broadcast::channel(1);
+    integrate_dht_ops_workflow(workspace, env.clone().into(), &mut qt, &integration_events)
+        .await
+        .unwrap();
+    Db::check(
+        vec![Db::IntQueue(op.clone()), Db::IntegratedEmpty],
+        env.clone(),
+        here!("stuck").to_string(),
+    )
+    .await;
+
+    // Forcing it through integrates it with its metadata
+    let event = force_integrate_op(env.clone(), op_hash.clone(), HeldOpTypes::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        event,
+        IntegrationEvent {
+            op_hash: op_hash.clone(),
+            status: ValidationStatus::Valid,
+        }
+    );
+    Db::check(
+        vec![
+            Db::Integrated(op.clone()),
+            Db::IntQueueEmpty,
+            Db::MetaUpdate(
+                td.original_entry_hash.clone().into(),
+                td.entry_update_entry.clone().into(),
+            ),
+        ],
+        env.clone(),
+        here!("forced").to_string(),
+    )
+    .await;
+
+    // It's no longer waiting so it can't be forced again
+    matches::assert_matches!(
+        force_integrate_op(env.clone(), op_hash, HeldOpTypes::default()).await,
+        Err(WorkflowError::OpNotValidated(_))
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn force_integrate_refuses_rejected_op() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+
+    let td = TestData::new().await;
+    let op = DhtOp::RegisterUpdatedBy(td.signature.clone(), td.entry_update_entry.clone());
+    let op_hash = DhtOpHashed::from_content_sync(op.clone()).into_hash();
+    let mut workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+    workspace
+        .integration_limbo
+        .put(
+            op_hash.clone(),
+            IntegrationLimboValue {
+                validation_status: ValidationStatus::Rejected,
+                op: op.to_light().await,
+                time_added: None,
+            },
+        )
+        .unwrap();
+    env.guard()
+        .with_commit(|writer| workspace.flush_to_txn(writer))
+        .unwrap();
+
+    matches::assert_matches!(
+        force_integrate_op(env.clone(), op_hash, HeldOpTypes::default()).await,
+        Err(WorkflowError::OpNotValid(_, ValidationStatus::Rejected))
+    );
+    Db::check(
+        vec![Db::IntegratedEmpty],
+        env.clone(),
+        here!("rejected").to_string(),
+    )
+    .await;
+}
+
 /// Call the produce dht ops workflow
 async fn produce_dht_ops<'env>(env: EnvironmentWrite) {
     let (mut qt, _rx) = TriggerSender::new();
@@ -931,7 +1187,7 @@ async fn get_entry(env: EnvironmentWrite, entry_hash: EntryHash) -> Option<Entry
 
     let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
 
-    let input = GetInput::new((entry_hash.clone().into(), GetOptions));
+    let input = GetInput::new((entry_hash.clone().into(), GetOptions::default()));
 
     let output = {
         let mut host_access = fixt!(ZomeCallHostAccess);
@@ -1216,7 +1472,11 @@ async fn test_wasm_api_without_integration_delete() {
             deletes_address: entry_header.header_hash,
             deletes_entry_address: base_address.clone(),
         };
-        workspace.source_chain.put(delete, None).await.unwrap();
+        workspace
+            .source_chain
+            .put(delete, None, None)
+            .await
+            .unwrap();
         env_ref
             .with_commit(|writer| workspace.flush_to_txn(writer))
             .unwrap();
@@ -1381,7 +1641,7 @@ mod slow_tests {
             };
             workspace
                 .source_chain
-                .put(header_builder, Some(base_entry.clone()))
+                .put(header_builder, Some(base_entry.clone()), None)
                 .await
                 .unwrap();
 
@@ -1396,7 +1656,7 @@ mod slow_tests {
             };
             let hh = workspace
                 .source_chain
-                .put(header_builder, Some(target_entry.clone()))
+                .put(header_builder, Some(target_entry.clone()), None)
                 .await
                 .unwrap();
 
@@ -1421,7 +1681,7 @@ mod slow_tests {
             };
             let hh = workspace
                 .source_chain
-                .put(header_builder, Some(base_entry.clone()))
+                .put(header_builder, Some(base_entry.clone()), None)
                 .await
                 .unwrap();
 
@@ -1443,7 +1703,7 @@ mod slow_tests {
             };
             let hh = workspace
                 .source_chain
-                .put(header_builder, None)
+                .put(header_builder, None, None)
                 .await
                 .unwrap();
 
@@ -1487,8 +1747,7 @@ mod slow_tests {
             let env_ref = cell_env.guard();
 
             let reader = env_ref.reader().unwrap();
-            let db = cell_env.get_db(&*INTEGRATED_DHT_OPS).unwrap();
-            let ops_db = IntegratedDhtOpsStore::new(cell_env.clone().into(), db);
+            let ops_db = IntegratedDhtOpsBuf::new(cell_env.clone().into()).unwrap();
             let ops = ops_db.iter(&reader).unwrap().collect::<Vec<_>>().unwrap();
             debug!(?ops);
             assert!(!ops.is_empty());
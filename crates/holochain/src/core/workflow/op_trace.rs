@@ -0,0 +1,170 @@
+//! Tracing context that follows a single [DhtOp] through the workflows.
+//!
+//! An op received through gossip passes through incoming, sys validation,
+//! app validation and integration, usually across several runs of each.
+//! Every stage handles the op inside a span carrying a correlation id derived
+//! from the op's hash, so filtering the logs on that id shows the op's
+//! whole journey through this Cell.
+//!
+//! [DhtOp]: holochain_types::dht_op::DhtOp
+
+use holo_hash::DhtOpHash;
+use tracing::Span;
+
+/// How many bytes of the op hash make up the correlation id
+const CORRELATION_ID_BYTES: usize = 8;
+
+/// The id every span for this op carries.
+/// The same op always gets the same id on every node.
+pub fn op_correlation_id(hash: &DhtOpHash) -> String {
+    hash.get_core_bytes()
+        .iter()
+        .take(CORRELATION_ID_BYTES)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The span a workflow stage enters while it handles this op
+pub fn op_span(stage: &'static str, hash: &DhtOpHash) -> Span {
+    tracing::debug_span!("dht_op", stage, correlation_id = %op_correlation_id(hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        queue_consumer::TriggerSender,
+        state::{dht_op_integration::IntegrationLimboValue, workspace::Workspace},
+        workflow::{
+            incoming_dht_ops_workflow::{incoming_dht_ops_workflow, AcceptAllOps},
+            integrate_dht_ops_workflow::{integrate_dht_ops_workflow, IntegrateDhtOpsWorkspace},
+        },
+    };
+    use ::fixt::prelude::*;
+    use holochain_state::{env::WriteManager, test_utils::test_cell_env};
+    use holochain_types::{dht_op::DhtOp, fixt::*, validate::ValidationStatus, HeaderHashed};
+    use holochain_zome_types::element::SignedHeaderHashed;
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    };
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    /// Records the stage and correlation id of every op span
+    #[derive(Clone, Default)]
+    struct OpSpanRecorder {
+        spans: Arc<Mutex<Vec<(String, String)>>>,
+        next_id: Arc<AtomicU64>,
+    }
+
+    #[derive(Default)]
+    struct OpSpanFields {
+        stage: Option<String>,
+        correlation_id: Option<String>,
+    }
+
+    impl Visit for OpSpanFields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "stage" {
+                self.stage = Some(value.to_string());
+            }
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "correlation_id" {
+                self.correlation_id = Some(format!("{:?}", value));
+            }
+        }
+    }
+
+    impl Subscriber for OpSpanRecorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+            let mut fields = OpSpanFields::default();
+            span.record(&mut fields);
+            if let (Some(stage), Some(id)) = (fields.stage, fields.correlation_id) {
+                self.spans.lock().unwrap().push((stage, id));
+            }
+            span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &span::Id) {}
+
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    #[tokio::test]
+    async fn correlation_id_follows_op_across_stages() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let recorder = OpSpanRecorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let sig = fixt!(Signature);
+        let header = fixt!(Header);
+        let op = DhtOp::RegisterAgentActivity(sig.clone(), header.clone());
+        let op_light = op.to_light().await;
+        let hash = DhtOpHash::with_data_sync(&op);
+
+        // The op arrives through gossip
+        let (sys_validation_trigger, _rx) = TriggerSender::new();
+        incoming_dht_ops_workflow(
+            &env,
+            sys_validation_trigger,
+            vec![(hash.clone(), op)],
+            Arc::new(AcceptAllOps),
+        )
+        .await
+        .unwrap();
+
+        // Skip straight to integration as if the op was validated
+        {
+            let mut workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+            let iv = IntegrationLimboValue {
+                validation_status: ValidationStatus::Valid,
+                op: op_light,
+                time_added: None,
+            };
+            workspace.integration_limbo.put(hash.clone(), iv).unwrap();
+            let signed_header =
+                SignedHeaderHashed::with_presigned(HeaderHashed::from_content_sync(header), sig);
+            workspace.element_judged.put(signed_header, None).unwrap();
+            env.guard()
+                .with_commit(|writer| workspace.flush_to_txn(writer))
+                .unwrap();
+        }
+        let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        let (mut trigger_sys, _rx) = TriggerSender::new();
+        let (integration_events, _) = tokio::sync::broadcast::channel(1);
+        integrate_dht_ops_workflow(
+            workspace,
+            env.clone().into(),
+            &mut trigger_sys,
+            &integration_events,
+        )
+        .await
+        .unwrap();
+
+        let expected = op_correlation_id(&hash);
+        let spans = recorder.spans.lock().unwrap().clone();
+        assert_eq!(
+            spans,
+            vec![
+                ("incoming".to_string(), expected.clone()),
+                ("integration".to_string(), expected),
+            ]
+        );
+    }
+}
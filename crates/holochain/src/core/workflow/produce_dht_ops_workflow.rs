@@ -26,7 +26,7 @@ pub async fn produce_dht_ops_workflow(
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     // trigger other workflows
     trigger_publish.trigger();
@@ -44,11 +44,7 @@ async fn produce_dht_ops_workflow_inner(
         for op in ops {
             let (op, hash) = DhtOpHashed::from_content_sync(op).into_inner();
             debug!(?hash, ?op);
-            let value = AuthoredDhtOpsValue {
-                op: op.to_light().await,
-                receipt_count: 0,
-                last_publish_time: None,
-            };
+            let value = AuthoredDhtOpsValue::from_light(op.to_light().await);
             workspace.authored_dht_ops.put(hash, value)?;
         }
         // Mark the dht op as complete
@@ -135,6 +131,7 @@ mod tests {
                         entry_hash,
                     },
                     Some(app_entry),
+                    None,
                 )
                 .await
                 .unwrap();
@@ -160,17 +157,12 @@ mod tests {
 
             // Add genesis so we can use the source chain
             fake_genesis(&mut source_chain).await.unwrap();
-            let headers: Vec<_> = source_chain.iter_back().collect().unwrap();
             // The ops will be created from start to end of the chain
-            let headers: Vec<_> = headers.into_iter().rev().collect();
+            let elements: Vec<_> = source_chain.iter_forward().collect().unwrap();
             let mut all_ops = Vec::new();
             // Collect the ops from genesis
-            for h in headers {
-                let ops = produce_ops_from_element(
-                    &source_chain.get_element(h.as_hash()).unwrap().unwrap(),
-                )
-                .await
-                .unwrap();
+            for element in elements {
+                let ops = produce_ops_from_element(&element).await.unwrap();
                 all_ops.push(ops);
             }
 
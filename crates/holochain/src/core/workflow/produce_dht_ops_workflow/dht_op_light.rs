@@ -1,6 +1,6 @@
 use crate::core::state::element_buf::ElementBuf;
 use error::{DhtOpConvertError, DhtOpConvertResult};
-use holo_hash::{EntryHash, HeaderHash};
+use holo_hash::{DhtOpHash, EntryHash, HeaderHash};
 use holochain_keystore::Signature;
 use holochain_types::{
     dht_op::{DhtOp, DhtOpLight},
@@ -133,6 +133,26 @@ pub async fn light_to_op<P: PrefixType>(
     }
 }
 
+/// Convert a DhtOpLight into a DhtOp and check the
+/// rendered op hashes to the expected DhtOpHash.
+/// This catches a light op that has drifted from the element data
+/// it was created from.
+pub async fn light_to_op_checked<P: PrefixType>(
+    op: DhtOpLight,
+    expected_hash: &DhtOpHash,
+    cas: &ElementBuf<P>,
+) -> DhtOpConvertResult<DhtOp> {
+    let op = light_to_op(op, cas).await?;
+    let hash = DhtOpHash::with_data_sync(&op);
+    if &hash != expected_hash {
+        return Err(DhtOpConvertError::OpHashMismatch(
+            expected_hash.clone(),
+            hash,
+        ));
+    }
+    Ok(op)
+}
+
 fn get_element_delete<P: PrefixType>(
     header_hash: HeaderHash,
     op_name: String,
@@ -1,5 +1,5 @@
 use crate::core::SourceChainError;
-use holo_hash::{AnyDhtHash, HeaderHash};
+use holo_hash::{AnyDhtHash, DhtOpHash, HeaderHash};
 use holochain_serialized_bytes::SerializedBytesError;
 use holochain_state::error::DatabaseError;
 use holochain_types::dht_op::error::DhtOpError;
@@ -32,6 +32,8 @@ pub enum DhtOpConvertError {
     DhtOpError(#[from] DhtOpError),
     #[error("Tried to use the wrong header for this op: {0}")]
     WrongHeaderError(#[from] WrongHeaderError),
+    #[error("The op rendered from a DhtOpLight has hash {1} but was expected to have hash {0}")]
+    OpHashMismatch(DhtOpHash, DhtOpHash),
 }
 
 pub type DhtOpConvertResult<T> = Result<T, DhtOpConvertError>;
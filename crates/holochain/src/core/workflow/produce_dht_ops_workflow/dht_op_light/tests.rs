@@ -2,12 +2,16 @@ use crate::{
     core::state::element_buf::ElementBuf,
     fixt::{
         AgentValidationPkgFixturator, CloseChainFixturator, CreateFixturator, CreateLinkFixturator,
-        DeleteLinkFixturator, DnaFixturator, EntryFixturator, EntryHashFixturator,
-        EntryTypeFixturator, InitZomesCompleteFixturator, OpenChainFixturator, UpdateFixturator,
+        DeleteFixturator, DeleteLinkFixturator, DnaFixturator, EntryFixturator,
+        EntryHashFixturator, EntryTypeFixturator, InitZomesCompleteFixturator, OpenChainFixturator,
+        UpdateFixturator,
     },
 };
 use ::fixt::prelude::*;
-use holo_hash::{fixt::HeaderHashFixturator, *};
+use holo_hash::{
+    fixt::{DhtOpHashFixturator, HeaderHashFixturator},
+    *,
+};
 use holochain_keystore::Signature;
 use holochain_state::test_utils::test_cell_env;
 use holochain_types::{
@@ -25,6 +29,8 @@ use holochain_zome_types::header::{
 use pretty_assertions::assert_eq;
 use tracing::*;
 
+use super::{error::DhtOpConvertError, light_to_op_checked};
+
 struct ElementTest {
     entry_type: EntryType,
     entry_hash: EntryHash,
@@ -280,3 +286,56 @@ async fn test_dht_basis() {
         assert_eq!(expected_entry_hash, result);
     }
 }
+
+#[tokio::test(threaded_scheduler)]
+async fn test_light_round_trip() {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let mut cas = ElementBuf::vault(env.clone().into(), true).unwrap();
+
+    let entry = fixt!(Entry);
+    let entry_hashed = EntryHashed::from_content_sync(entry.clone());
+    let mut create = fixt!(Create);
+    create.entry_hash = entry_hashed.as_hash().clone();
+    let mut update = fixt!(Update);
+    update.entry_hash = entry_hashed.as_hash().clone();
+    let headers: Vec<(Header, Option<Entry>)> = vec![
+        (create.into(), Some(entry.clone())),
+        (update.into(), Some(entry)),
+        (fixt!(Delete).into(), None),
+        (fixt!(CreateLink).into(), None),
+        (fixt!(DeleteLink).into(), None),
+        (fixt!(Dna).into(), None),
+    ];
+
+    // Store the elements and produce every op variant from them
+    let mut ops = Vec::new();
+    for (header, entry) in headers {
+        let header = HeaderHashed::from_content_sync(header);
+        let signed_header = SignedHeaderHashed::with_presigned(header, fixt!(Signature));
+        let element = Element::new(signed_header.clone(), entry.clone());
+        ops.extend(produce_ops_from_element(&element).await.unwrap());
+        cas.put(signed_header, entry.map(EntryHashed::from_content_sync))
+            .unwrap();
+    }
+    assert_eq!(ops.len(), 19);
+
+    for op in ops {
+        let hash = DhtOpHash::with_data_sync(&op);
+        let light = op.to_light().await;
+
+        // The op rendered from the light op has the same hash
+        let result = light_to_op_checked(light.clone(), &hash, &cas)
+            .await
+            .unwrap();
+        assert_eq!(DhtOpHash::with_data_sync(&result), hash);
+        assert_eq!(result.to_light().await, light);
+
+        // A different hash is caught
+        let result = light_to_op_checked(light, &fixt!(DhtOpHash), &cas).await;
+        assert!(matches!(
+            result,
+            Err(DhtOpConvertError::OpHashMismatch(_, h)) if h == hash
+        ));
+    }
+}
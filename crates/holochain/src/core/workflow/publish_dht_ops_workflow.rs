@@ -12,7 +12,7 @@
 
 use super::{
     error::WorkflowResult,
-    produce_dht_ops_workflow::dht_op_light::{error::DhtOpConvertError, light_to_op},
+    produce_dht_ops_workflow::dht_op_light::{error::DhtOpConvertError, light_to_op_checked},
 };
 use crate::core::{
     queue_consumer::{OneshotWriter, WorkComplete},
@@ -27,7 +27,7 @@ use holo_hash::*;
 use holochain_p2p::HolochainP2pCell;
 use holochain_p2p::HolochainP2pCellT;
 use holochain_state::{
-    buffer::{BufferedStore, KvBufFresh},
+    buffer::{BufferedStore, KvBufFresh, KvStoreT},
     db::AUTHORED_DHT_OPS,
     fresh_reader,
     prelude::*,
@@ -35,14 +35,13 @@ use holochain_state::{
 };
 use holochain_types::{dht_op::DhtOp, Timestamp};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time;
 use tracing::*;
 
-/// Default redundancy factor for validation receipts
-// TODO: Pull this from the wasm entry def and only use this if it's missing
-// TODO: Put a default in the DnaBundle
-// TODO: build zome_types/entry_def map to get the (AppEntryType map to entry def)
-pub const DEFAULT_RECEIPT_BUNDLE_SIZE: u32 = 5;
+pub use egress_filter::*;
+
+mod egress_filter;
 
 /// Don't publish a DhtOp more than once during this interval.
 /// This allows us to trigger the publish workflow as often as we like, without
@@ -53,8 +52,14 @@ pub const MIN_PUBLISH_INTERVAL: time::Duration = time::Duration::from_secs(5);
 pub struct PublishDhtOpsWorkspace {
     /// Database of authored DhtOps, with data about prior publishing
     authored_dht_ops: AuthoredDhtOpsStore,
+    /// When each op being published counts as last published.
+    /// Only this is written back, onto the op's value as it is when flushed,
+    /// so receipts counted while the ops were being published aren't lost.
+    publish_times: HashMap<DhtOpHash, Timestamp>,
     /// Element store for looking up data to construct ops
     elements: ElementBuf,
+    /// Consulted before each op is published
+    pub egress_filter: OpEgressFilterRef,
 }
 
 #[instrument(skip(workspace, writer, network))]
@@ -72,7 +77,7 @@ pub async fn publish_dht_ops_workflow(
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     Ok(WorkComplete::Complete)
 }
@@ -96,8 +101,8 @@ pub async fn publish_dht_ops_workflow_inner(
     let values = fresh_reader!(env, |r| workspace
         .authored()
         .iter(&r)?
-        .filter_map(|(k, mut r)| {
-            Ok(if r.receipt_count < DEFAULT_RECEIPT_BUNDLE_SIZE {
+        .filter_map(|(k, r)| {
+            Ok(if r.receipt_count < r.required_receipts {
                 let needs_publish = r
                     .last_publish_time
                     .map(|last| {
@@ -106,7 +111,6 @@ pub async fn publish_dht_ops_workflow_inner(
                     })
                     .unwrap_or(true);
                 if needs_publish {
-                    r.last_publish_time = Some(now_ts);
                     Some((DhtOpHash::with_pre_hashed(k.to_vec()), r))
                 } else {
                     None
@@ -121,15 +125,28 @@ pub async fn publish_dht_ops_workflow_inner(
     let mut to_publish = HashMap::new();
 
     for (op_hash, value) in values {
-        // Insert updated values into database for items about to be published
-        let op = value.op.clone();
-        workspace.authored().put(op_hash.clone(), value)?;
+        // Record the publish time for items about to be published
+        workspace.publish_times.insert(op_hash.clone(), now_ts);
+        let op = value.op;
 
-        let op = match light_to_op(op, workspace.elements()).await {
+        let op = match light_to_op_checked(op, &op_hash, workspace.elements()).await {
             // Ignore StoreEntry ops on private
             Err(DhtOpConvertError::StoreEntryOnPrivate) => continue,
             r => r?,
         };
+        match workspace.egress_filter.filter(&op) {
+            EgressDecision::Send => (),
+            // Treated as published so it isn't offered again until the interval passes
+            EgressDecision::Drop => continue,
+            EgressDecision::Delay(delay) => {
+                // Record a publish time that makes the op due again once the delay has passed
+                let delay = chrono::Duration::from_std(delay).unwrap_or(interval);
+                workspace
+                    .publish_times
+                    .insert(op_hash, (now + delay - interval).into());
+                continue;
+            }
+        }
         // For every op publish a request
         // Collect and sort ops by basis
         to_publish
@@ -144,6 +161,14 @@ pub async fn publish_dht_ops_workflow_inner(
 impl Workspace for PublishDhtOpsWorkspace {
     fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> WorkspaceResult<()> {
         self.authored_dht_ops.flush_to_txn_ref(writer)?;
+        // Re-read each op in this txn so only its publish time changes
+        let store = self.authored_dht_ops.store();
+        for (op_hash, publish_time) in self.publish_times.iter() {
+            if let Some(mut value) = store.get(&*writer, op_hash)? {
+                value.last_publish_time = Some(*publish_time);
+                store.put(writer, op_hash, &value)?;
+            }
+        }
         Ok(())
     }
 }
@@ -156,7 +181,9 @@ impl PublishDhtOpsWorkspace {
         let elements = ElementBuf::vault(env, false)?;
         Ok(Self {
             authored_dht_ops,
+            publish_times: HashMap::new(),
             elements,
+            egress_filter: Arc::new(SendAllOps),
         })
     }
 
@@ -199,7 +226,7 @@ mod tests {
         test_utils::test_cell_env,
     };
     use holochain_types::{
-        dht_op::{DhtOp, DhtOpHashed, DhtOpLight},
+        dht_op::{DhtOp, DhtOpHashed, DhtOpLight, DhtOpType},
         fixt::{AppEntryTypeFixturator, SignatureFixturator},
         observability, HeaderHashed,
     };
@@ -426,7 +453,7 @@ mod tests {
                     .iter(&reader)
                     .unwrap()
                     .map(|(k, mut v)| {
-                        v.receipt_count = DEFAULT_RECEIPT_BUNDLE_SIZE;
+                        v.receipt_count = v.required_receipts;
                         Ok((DhtOpHash::with_pre_hashed(k.to_vec()), v))
                     })
                     .collect::<Vec<_>>()
@@ -545,6 +572,7 @@ mod tests {
                                 entry_hash: original_entry_hash.clone(),
                             },
                             Some(original_entry),
+                            None,
                         )
                         .await
                         .unwrap();
@@ -564,6 +592,7 @@ mod tests {
                                 original_entry_address: original_entry_hash,
                             },
                             Some(new_entry),
+                            None,
                         )
                         .await
                         .unwrap();
@@ -746,5 +775,69 @@ mod tests {
         );
     }
 
+    /// An egress filter that delays one type of op holds that op back
+    /// while the rest are published, and pushes its next publish past the delay
+    #[tokio::test(threaded_scheduler)]
+    async fn egress_filter_delays_op_type() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let env_ref = env.guard();
+
+        // A link element gives us two types of op
+        let sig = fixt!(Signature);
+        let link_add = fixt!(CreateLink);
+        let header_hash = HeaderHashed::from_content_sync(link_add.clone().into());
+        let add_link_op = DhtOp::RegisterAddLink(sig.clone(), link_add.clone());
+        let store_element_op = DhtOp::StoreElement(sig.clone(), link_add.into(), None);
+        let add_link_hash = DhtOpHashed::from_content_sync(add_link_op.clone()).into_hash();
+        let store_element_hash =
+            DhtOpHashed::from_content_sync(store_element_op.clone()).into_hash();
+
+        let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        for (op_hash, op) in vec![
+            (add_link_hash.clone(), &add_link_op),
+            (store_element_hash.clone(), &store_element_op),
+        ] {
+            let authored_value = AuthoredDhtOpsValue::from_light(op.to_light().await);
+            workspace
+                .authored_dht_ops
+                .put(op_hash, authored_value)
+                .unwrap();
+        }
+        let signed_header = SignedHeaderHashed::with_presigned(header_hash, sig);
+        workspace.elements.put(signed_header, None).unwrap();
+        env_ref
+            .with_commit::<DatabaseError, _, _>(|writer| {
+                workspace.authored_dht_ops.flush_to_txn(writer)?;
+                workspace.elements.flush_to_txn(writer)?;
+                Ok(())
+            })
+            .unwrap();
+
+        let delay = Duration::from_secs(60);
+        let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+        workspace.egress_filter = Arc::new(move |op: &DhtOp| match op.get_type() {
+            DhtOpType::RegisterAddLink => EgressDecision::Delay(delay),
+            _ => EgressDecision::Send,
+        });
+        let to_publish = publish_dht_ops_workflow_inner(&mut workspace)
+            .await
+            .unwrap();
+
+        // Only the store element op is published
+        let published = to_publish
+            .into_iter()
+            .flat_map(|(_, ops)| ops)
+            .map(|(op_hash, _)| op_hash)
+            .collect::<Vec<_>>();
+        assert_eq!(published, vec![store_element_hash]);
+
+        // The delayed op won't be due again until after the delay
+        let delayed = workspace.publish_times[&add_link_hash];
+        let next_due = chrono::DateTime::<chrono::Utc>::from(delayed)
+            + chrono::Duration::from_std(MIN_PUBLISH_INTERVAL).unwrap();
+        assert!(next_due > chrono::Utc::now() + chrono::Duration::from_std(delay / 2).unwrap());
+    }
+
     // TODO: COVERAGE: Test public ops do publish
 }
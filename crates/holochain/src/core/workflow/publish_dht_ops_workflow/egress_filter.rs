@@ -0,0 +1,46 @@
+//! A hook for deciding which authored [DhtOp]s leave this node and when.
+
+use holochain_types::dht_op::DhtOp;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What the publish workflow should do with an op it is about to publish
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EgressDecision {
+    /// Publish the op now
+    Send,
+    /// Don't publish the op this time.
+    /// It will be offered to the filter again after [MIN_PUBLISH_INTERVAL](super::MIN_PUBLISH_INTERVAL).
+    Drop,
+    /// Don't publish the op until at least this much time has passed
+    Delay(Duration),
+}
+
+/// Consulted by the publish workflow before each op is sent to the network.
+/// Useful for rate limiting, privacy policies or testing partitions.
+pub trait OpEgressFilter: Send + Sync {
+    /// Decide what to do with this op
+    fn filter(&self, op: &DhtOp) -> EgressDecision;
+}
+
+/// A shared [OpEgressFilter]
+pub type OpEgressFilterRef = Arc<dyn OpEgressFilter>;
+
+/// The default filter, which publishes every op
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SendAllOps;
+
+impl OpEgressFilter for SendAllOps {
+    fn filter(&self, _op: &DhtOp) -> EgressDecision {
+        EgressDecision::Send
+    }
+}
+
+impl<F> OpEgressFilter for F
+where
+    F: Fn(&DhtOp) -> EgressDecision + Send + Sync,
+{
+    fn filter(&self, op: &DhtOp) -> EgressDecision {
+        self(op)
+    }
+}
@@ -2,12 +2,17 @@
 
 use super::*;
 use crate::{
-    conductor::api::CellConductorApiT,
+    conductor::{
+        api::CellConductorApiT,
+        config::{ValidationProfile, ValidationResponse},
+    },
     core::{
         queue_consumer::{OneshotWriter, TriggerSender, WorkComplete},
         state::{
-            cascade::Cascade,
-            dht_op_integration::{IntegrationLimboStore, IntegrationLimboValue},
+            cascade::{breaker::NetworkBreaker, retry::RetrySchedule, Cascade},
+            dht_op_integration::{
+                AuthoredDhtOpsStore, IntegrationLimboStore, IntegrationLimboValue,
+            },
             element_buf::ElementBuf,
             metadata::MetadataBuf,
             validation_db::{ValidationLimboStatus, ValidationLimboStore, ValidationLimboValue},
@@ -22,8 +27,9 @@ use holo_hash::DhtOpHash;
 use holochain_keystore::Signature;
 use holochain_p2p::{HolochainP2pCell, HolochainP2pCellT};
 use holochain_state::{
-    buffer::{BufferedStore, KvBufFresh},
-    db::INTEGRATION_LIMBO,
+    buffer::{BufferedStore, KvBufFresh, KvBufUsed},
+    db::{DbName, AUTHORED_DHT_OPS, INTEGRATION_LIMBO},
+    env::ReaderPool,
     fresh_reader,
     prelude::*,
 };
@@ -35,16 +41,28 @@ use holochain_zome_types::{
     header::{CreateLink, Delete, DeleteLink, EntryType, Update},
     Header,
 };
-use std::{collections::BinaryHeap, convert::TryInto};
+use op_trace::op_span;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::convert::TryInto;
 use tracing::*;
+use tracing_futures::Instrument;
 
+use dep_cache::{DepCache, DEFAULT_DEP_CACHE_CAPACITY, DEFAULT_DEP_CACHE_TTL};
 use integrate_dht_ops_workflow::{
     disintegrate_single_data, disintegrate_single_metadata, integrate_single_data,
     integrate_single_metadata, reintegrate_single_data,
 };
+use metrics::{DependencyCounters, LimboBreakdown, SysValidationMetrics};
 use produce_dht_ops_workflow::dht_op_light::light_to_op;
 use types::{CheckLevel, DhtOpOrder, OrderedOp, Outcome, PendingDependencies};
 
+pub mod dep_cache;
+pub mod metrics;
+
+/// How long a chain of pending ops an op can wait on
+/// if the conductor doesn't configure a bound
+pub const DEFAULT_MAX_PENDING_DEPENDENCY_DEPTH: usize = 16;
 pub mod types;
 
 #[cfg(test)]
@@ -94,7 +112,7 @@ async fn sys_validation_workflow_inner(
         .collect())?;
 
     // Sort the ops
-    let mut sorted_ops = BinaryHeap::new();
+    let mut sorted_ops = Vec::with_capacity(ops.len());
     for vlv in ops {
         // let op = light_to_op(vlv.op.clone(), &workspace.element_pending).await?;
         let op = light_to_op(vlv.op.clone(), &workspace.element_pending).await?;
@@ -107,32 +125,71 @@ async fn sys_validation_workflow_inner(
             op,
             value: vlv,
         };
-        // We want a min-heap
-        sorted_ops.push(std::cmp::Reverse(v));
+        sorted_ops.push(v);
 
         // Since we are processing DhtOps in a loop, make sure we yield
         // between each one, since hashing could take a while
         tokio::task::yield_now().await;
     }
+    let sorted_ops = order_ops(sorted_ops);
+
+    // One pooled reader answers which ops we authored
+    // instead of a read transaction per op
+    let authored: HashSet<DhtOpHash> = if workspace.author_is_authority {
+        let g = env.guard();
+        let mut pool = ReaderPool::new(&g);
+        let mut authored = HashSet::new();
+        for so in &sorted_ops {
+            let store: &KvBufUsed<_, _> = &workspace.authored_dht_ops;
+            if store.contains(pool.reader()?, &so.hash)? {
+                authored.insert(so.hash.clone());
+            }
+        }
+        authored
+    } else {
+        HashSet::new()
+    };
 
     // Process each op
+    let check_level = workspace.check_level;
     for so in sorted_ops {
         let OrderedOp {
             hash: op_hash,
             op,
             value: mut vlv,
             ..
-        } = so.0;
+        } = so;
+        let authored = authored.contains(&op_hash);
         let outcome = validate_op(
             &op,
+            &vlv.op,
             workspace,
             network.clone(),
             &conductor_api,
             &mut vlv.pending_dependencies,
-            CheckLevel::Proof,
+            check_level,
+            authored,
         )
+        .instrument(op_span("sys_validation", &op_hash))
         .await?;
 
+        // Validating against pending ops is optimistic,
+        // so bound how long a chain of them an op can wait on
+        let mut deepest = None;
+        if let Outcome::Accepted | Outcome::SkipAppValidation = outcome {
+            let (depth, head) = workspace.pending_dependency_depth(&vlv.pending_dependencies)?;
+            vlv.pending_dependencies.depth = depth;
+            deepest = head;
+        }
+        let outcome = match outcome {
+            Outcome::Accepted | Outcome::SkipAppValidation
+                if vlv.pending_dependencies.depth > workspace.max_pending_dependency_depth =>
+            {
+                Outcome::DepthExceeded(deepest)
+            }
+            outcome => outcome,
+        };
+
         match outcome {
             Outcome::Accepted => {
                 vlv.status = ValidationLimboStatus::SysValidated;
@@ -146,6 +203,7 @@ async fn sys_validation_workflow_inner(
                     let iv = IntegrationLimboValue {
                         op: vlv.op,
                         validation_status: ValidationStatus::Valid,
+                        time_added: Some(vlv.time_added),
                     };
                     workspace.put_int_limbo(op_hash, iv, op)?;
                 }
@@ -160,17 +218,35 @@ async fn sys_validation_workflow_inner(
                 // We need to be holding the dependency because
                 // we were meant to get a StoreElement or StoreEntry or
                 // RegisterAgentActivity or RegisterAddLink.
-                vlv.status = ValidationLimboStatus::AwaitingSysDeps(missing_dep);
+                vlv.status = ValidationLimboStatus::AwaitingSysDeps(vec![missing_dep]);
                 workspace.put_val_limbo(op_hash, vlv)?;
             }
-            Outcome::MissingDhtDep => {
+            Outcome::MissingDhtDep(missing_dep) => {
+                vlv.status = ValidationLimboStatus::AwaitingSysDeps(vec![missing_dep]);
+                workspace.put_val_limbo(op_hash, vlv)?;
+            }
+            Outcome::Pending => {
                 vlv.status = ValidationLimboStatus::Pending;
                 workspace.put_val_limbo(op_hash, vlv)?;
             }
+            Outcome::DepthExceeded(deepest) => {
+                // The depth stays recorded as the reason this op is parked
+                warn!(
+                    ?op_hash,
+                    depth = vlv.pending_dependencies.depth,
+                    max = workspace.max_pending_dependency_depth,
+                    "pending dependency depth exceeded: parking op until its dependencies validate"
+                );
+                // It will be validated from scratch
+                vlv.pending_dependencies.pending.clear();
+                vlv.status = ValidationLimboStatus::AwaitingSysDeps(deepest.into_iter().collect());
+                workspace.put_val_limbo(op_hash, vlv)?;
+            }
             Outcome::Rejected => {
                 let iv = IntegrationLimboValue {
                     op: vlv.op,
                     validation_status: ValidationStatus::Rejected,
+                    time_added: Some(vlv.time_added),
                 };
                 workspace.put_int_limbo(op_hash, iv, op)?;
             }
@@ -179,31 +255,151 @@ async fn sys_validation_workflow_inner(
     Ok(WorkComplete::Complete)
 }
 
+/// Order a batch of ops so the ops that others depend on are validated first.
+/// An op comes after every op in the batch holding a header or entry it
+/// references, so an op whose dependency arrived in the same batch
+/// sees it already validated instead of waiting on it.
+/// Ops that don't depend on each other keep the [DhtOpOrder].
+fn order_ops<V>(mut ops: Vec<OrderedOp<V>>) -> Vec<OrderedOp<V>> {
+    ops.sort();
+
+    // The ops in this batch holding each header or entry
+    let mut providers: HashMap<AnyDhtHash, Vec<usize>> = HashMap::new();
+    for (i, so) in ops.iter().enumerate() {
+        for hash in op_provides(&so.op) {
+            providers.entry(hash).or_default().push(i);
+        }
+    }
+
+    let mut dependents = vec![Vec::new(); ops.len()];
+    let mut waiting_on = vec![0; ops.len()];
+    for (i, so) in ops.iter().enumerate() {
+        let deps: HashSet<usize> = op_depends_on(&so.op)
+            .iter()
+            .filter_map(|hash| providers.get(hash))
+            .flatten()
+            .copied()
+            .filter(|d| *d != i)
+            .collect();
+        waiting_on[i] = deps.len();
+        for d in deps {
+            dependents[d].push(i);
+        }
+    }
+
+    // Of the ops that are ready take the first in the sorted order
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..ops.len())
+        .filter(|i| waiting_on[*i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(ops.len());
+    while let Some(Reverse(i)) = ready.pop() {
+        order.push(i);
+        for d in &dependents[i] {
+            waiting_on[*d] -= 1;
+            if waiting_on[*d] == 0 {
+                ready.push(Reverse(*d));
+            }
+        }
+    }
+    // Ops can't depend on each other in a cycle without a hash collision
+    // but they are still validated if they do
+    order.extend((0..ops.len()).filter(|i| waiting_on[*i] > 0));
+
+    let mut ops: Vec<_> = ops.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| ops[i].take()).collect()
+}
+
+/// The headers and entries other ops can find in this op
+fn op_provides(op: &DhtOp) -> Vec<AnyDhtHash> {
+    let header_hash = || HeaderHash::with_data_sync(&op.header()).into();
+    match op {
+        DhtOp::StoreElement(_, _, _)
+        | DhtOp::RegisterAgentActivity(_, _)
+        | DhtOp::RegisterAddLink(_, _) => vec![header_hash()],
+        DhtOp::StoreEntry(_, header, _) => vec![header_hash(), header.entry().clone().into()],
+        DhtOp::RegisterUpdatedBy(_, _)
+        | DhtOp::RegisterDeletedBy(_, _)
+        | DhtOp::RegisterDeletedEntryHeader(_, _)
+        | DhtOp::RegisterRemoveLink(_, _) => Vec::new(),
+    }
+}
+
+/// The headers and entries sys validation looks up to validate this op
+fn op_depends_on(op: &DhtOp) -> Vec<AnyDhtHash> {
+    let header = op.header();
+    let mut deps: Vec<AnyDhtHash> = header
+        .prev_header()
+        .cloned()
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    match &header {
+        Header::Update(update) => {
+            deps.push(update.original_header_address.clone().into());
+            if let DhtOp::RegisterUpdatedBy(_, _) = op {
+                deps.push(update.original_entry_address.clone().into());
+            }
+        }
+        Header::Delete(delete) => deps.push(delete.deletes_address.clone().into()),
+        Header::CreateLink(link_add) => {
+            deps.push(link_add.base_address.clone().into());
+            deps.push(link_add.target_address.clone().into());
+        }
+        Header::DeleteLink(link_remove) => deps.push(link_remove.link_add_address.clone().into()),
+        _ => (),
+    }
+    deps
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn validate_op(
     op: &DhtOp,
+    light: &DhtOpLight,
     workspace: &mut SysValidationWorkspace,
     network: HolochainP2pCell,
     conductor_api: &impl CellConductorApiT,
     dependencies: &mut PendingDependencies,
     check_level: CheckLevel,
+    authored: bool,
 ) -> WorkflowResult<Outcome> {
-    match validate_op_inner(
-        op,
-        workspace,
-        network,
-        conductor_api,
-        dependencies,
-        check_level,
-    )
-    .await
-    {
-        Ok(_) => match op {
-            DhtOp::RegisterAgentActivity(_, _) |
-            // TODO: Check strict mode where store element
-            // is also run through app validation
-            DhtOp::StoreElement(_, _, _) => Ok(Outcome::SkipAppValidation),
-            _ => Ok(Outcome::Accepted)
-        },
+    let mut checks = ProfiledChecks::new(workspace.validation_profile.clone());
+    // A forged op is rejected before any of its dependencies are fetched
+    let header = op.header();
+    let well_formed =
+        match check_well_formed(op.signature(), &header).and_then(|_| check_hash_type(light)) {
+            Ok(()) => all_op_check(op.signature(), &header).await,
+            Err(e) => Err(e),
+        };
+    let result = if let Err(e) = well_formed {
+        Err(e)
+    } else if authored {
+        validate_authored_op_inner(op, conductor_api, &mut checks).await
+    } else {
+        validate_op_inner(
+            op,
+            workspace,
+            network,
+            conductor_api,
+            dependencies,
+            check_level,
+            &mut checks,
+        )
+        .await
+    };
+    match result {
+        Ok(_) => {
+            for e in checks.warned {
+                warn!(
+                    agent = %which_agent(conductor_api.cell_id().agent_pubkey()),
+                    msg = "Accepting DhtOp despite failed system validation",
+                    ?op,
+                    error = ?e,
+                    error_msg = %e
+                );
+            }
+            Ok(accepted(op))
+        }
         // Handle the errors that result in pending or awaiting deps
         Err(SysValidationError::ValidationOutcome(e)) => {
             warn!(
@@ -219,6 +415,50 @@ async fn validate_op(
     }
 }
 
+/// Applies the [ValidationProfile] to each check an op fails
+/// so a relaxed failure doesn't stop the checks after it from running.
+/// Failures the profile can't relax are returned as errors.
+struct ProfiledChecks {
+    profile: ValidationProfile,
+    /// The failures the profile accepts with a warning
+    warned: Vec<ValidationOutcome>,
+}
+
+impl ProfiledChecks {
+    fn new(profile: ValidationProfile) -> Self {
+        Self {
+            profile,
+            warned: Vec::new(),
+        }
+    }
+
+    /// The check's value, or `None` if it failed
+    /// and the profile says to carry on with the other checks
+    fn check<T>(&mut self, result: SysValidationResult<T>) -> SysValidationResult<Option<T>> {
+        let e = match result {
+            Ok(t) => return Ok(Some(t)),
+            Err(SysValidationError::ValidationOutcome(e)) => e,
+            Err(e) => return Err(e),
+        };
+        match e.category().map(|category| self.profile.response(category)) {
+            Some(ValidationResponse::Warn) => self.warned.push(e),
+            Some(ValidationResponse::Reject) | None => return Err(e.into()),
+        }
+        Ok(None)
+    }
+}
+
+/// Where an op goes once it has passed sys validation
+fn accepted(op: &DhtOp) -> Outcome {
+    match op {
+        DhtOp::RegisterAgentActivity(_, _) |
+        // TODO: Check strict mode where store element
+        // is also run through app validation
+        DhtOp::StoreElement(_, _, _) => Outcome::SkipAppValidation,
+        _ => Outcome::Accepted
+    }
+}
+
 /// For now errors result in an outcome but in the future
 /// we might find it useful to include the reason something
 /// was rejected etc.
@@ -226,13 +466,19 @@ async fn validate_op(
 fn handle_failed(error: ValidationOutcome) -> Outcome {
     use Outcome::*;
     match error {
-        ValidationOutcome::DepMissingFromDht(_) => MissingDhtDep,
+        ValidationOutcome::AlreadyDeleted(_) => Rejected,
+        ValidationOutcome::DeleteEntryMismatch(_, _) => Rejected,
+        ValidationOutcome::DeleteNotByAuthor(_, _) => Rejected,
+        ValidationOutcome::DepMissingFromDht(dep) => MissingDhtDep(dep),
         ValidationOutcome::EntryDefId(_) => Rejected,
         ValidationOutcome::EntryHash => Rejected,
         ValidationOutcome::EntryTooLarge(_, _) => Rejected,
         ValidationOutcome::EntryType => Rejected,
         ValidationOutcome::EntryVisibility(_) => Rejected,
+        ValidationOutcome::HashTypeMismatch(_, _) => Rejected,
+        ValidationOutcome::MalformedHash(_) => Rejected,
         ValidationOutcome::TagTooLarge(_, _) => Rejected,
+        ValidationOutcome::LinkAlreadyRemoved(_) => Rejected,
         ValidationOutcome::NotCreateLink(_) => Rejected,
         ValidationOutcome::NotNewEntry(_) => Rejected,
         ValidationOutcome::NotHoldingDep(dep) => AwaitingOpDep(dep),
@@ -241,6 +487,8 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
         }
         ValidationOutcome::PrevHeaderError(_) => Rejected,
         ValidationOutcome::PrivateEntry => Rejected,
+        ValidationOutcome::SelfLink(_) => Rejected,
+        ValidationOutcome::UpdateTargetNotEntry(_) => Rejected,
         ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected,
         ValidationOutcome::VerifySignature(_, _) => Rejected,
         ValidationOutcome::ZomeId(_) => Rejected,
@@ -254,28 +502,33 @@ async fn validate_op_inner(
     conductor_api: &impl CellConductorApiT,
     dependencies: &mut PendingDependencies,
     check_level: CheckLevel,
+    checks: &mut ProfiledChecks,
 ) -> SysValidationResult<()> {
     match op {
-        DhtOp::StoreElement(signature, header, entry) => {
+        DhtOp::StoreElement(_, header, entry) => {
             store_element(header, workspace, network.clone(), dependencies).await?;
             if let Some(entry) = entry {
-                store_entry(
-                    (header)
+                let entry_header = checks.check(
+                    header
                         .try_into()
-                        .map_err(|_| ValidationOutcome::NotNewEntry(header.clone()))?,
-                    entry.as_ref(),
-                    conductor_api,
-                    workspace,
-                    network,
-                    dependencies,
-                )
-                .await?;
+                        .map_err(|_| ValidationOutcome::NotNewEntry(header.clone()).into()),
+                )?;
+                if let Some(entry_header) = entry_header {
+                    store_entry(
+                        entry_header,
+                        entry.as_ref(),
+                        conductor_api,
+                        workspace,
+                        network,
+                        dependencies,
+                        checks,
+                    )
+                    .await?;
+                }
             }
-
-            all_op_check(signature, header).await?;
             Ok(())
         }
-        DhtOp::StoreEntry(signature, header, entry) => {
+        DhtOp::StoreEntry(_, header, entry) => {
             store_entry(
                 (header).into(),
                 entry.as_ref(),
@@ -283,15 +536,15 @@ async fn validate_op_inner(
                 workspace,
                 network.clone(),
                 dependencies,
+                checks,
             )
             .await?;
 
             let header = header.clone().into();
             store_element(&header, workspace, network, dependencies).await?;
-            all_op_check(signature, &header).await?;
             Ok(())
         }
-        DhtOp::RegisterAgentActivity(signature, header) => {
+        DhtOp::RegisterAgentActivity(_, header) => {
             register_agent_activity(
                 header,
                 workspace,
@@ -301,48 +554,105 @@ async fn validate_op_inner(
             )
             .await?;
             store_element(header, workspace, network, dependencies).await?;
-            all_op_check(signature, header).await?;
             Ok(())
         }
-        DhtOp::RegisterUpdatedBy(signature, header) => {
-            register_updated_by(header, workspace, network, dependencies, check_level).await?;
-
-            let header = header.clone().into();
-            all_op_check(signature, &header).await?;
+        DhtOp::RegisterUpdatedBy(_, header) => {
+            register_updated_by(
+                header,
+                workspace,
+                network,
+                dependencies,
+                check_level,
+                checks,
+            )
+            .await?;
             Ok(())
         }
-        DhtOp::RegisterDeletedBy(signature, header) => {
-            register_deleted_by(header, workspace, network, dependencies, check_level).await?;
-
-            let header = header.clone().into();
-            all_op_check(signature, &header).await?;
+        DhtOp::RegisterDeletedBy(_, header) => {
+            register_deleted_by(
+                header,
+                workspace,
+                network,
+                dependencies,
+                check_level,
+                checks,
+            )
+            .await?;
             Ok(())
         }
-        DhtOp::RegisterDeletedEntryHeader(signature, header) => {
-            register_deleted_entry_header(header, workspace, network, dependencies, check_level)
-                .await?;
-
-            let header = header.clone().into();
-            all_op_check(signature, &header).await?;
+        DhtOp::RegisterDeletedEntryHeader(_, header) => {
+            register_deleted_entry_header(
+                header,
+                workspace,
+                network,
+                dependencies,
+                check_level,
+                checks,
+            )
+            .await?;
             Ok(())
         }
-        DhtOp::RegisterAddLink(signature, header) => {
-            register_add_link(header, workspace, network, dependencies, check_level).await?;
-
-            let header = header.clone().into();
-            all_op_check(signature, &header).await?;
+        DhtOp::RegisterAddLink(_, header) => {
+            checks.check(check_self_link(header, conductor_api).await)?;
+            register_add_link(
+                header,
+                workspace,
+                network,
+                dependencies,
+                check_level,
+                checks,
+            )
+            .await?;
             Ok(())
         }
-        DhtOp::RegisterRemoveLink(signature, header) => {
-            register_delete_link(header, workspace, network, dependencies, check_level).await?;
-
-            let header = header.clone().into();
-            all_op_check(signature, &header).await?;
+        DhtOp::RegisterRemoveLink(_, header) => {
+            register_delete_link(
+                header,
+                workspace,
+                network,
+                dependencies,
+                check_level,
+                checks,
+            )
+            .await?;
             Ok(())
         }
     }
 }
 
+/// The lighter validation path for ops this node authored.
+/// The author already holds every dependency on its own chain so
+/// only the structural checks are run and nothing is fetched from the network.
+async fn validate_authored_op_inner(
+    op: &DhtOp,
+    conductor_api: &impl CellConductorApiT,
+    checks: &mut ProfiledChecks,
+) -> SysValidationResult<()> {
+    let header = op.header();
+    check_prev_header(&header)?;
+    match op {
+        DhtOp::StoreElement(_, header, Some(entry)) => {
+            let entry_header = checks.check(
+                header
+                    .try_into()
+                    .map_err(|_| ValidationOutcome::NotNewEntry(header.clone()).into()),
+            )?;
+            if let Some(entry_header) = entry_header {
+                store_entry_structure(entry_header, entry.as_ref(), conductor_api, checks).await?;
+            }
+        }
+        DhtOp::StoreEntry(_, header, entry) => {
+            store_entry_structure(header.into(), entry.as_ref(), conductor_api, checks).await?;
+        }
+        DhtOp::RegisterAddLink(_, link_add) => {
+            checks.check(check_tag_size(&link_add.tag))?;
+            checks.check(check_self_link(link_add, conductor_api).await)?;
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
 async fn all_op_check(signature: &Signature, header: &Header) -> SysValidationResult<()> {
     verify_header_signature(&signature, &header).await?;
     author_key_is_valid(header.author()).await?;
@@ -405,19 +715,9 @@ async fn store_entry(
     workspace: &mut SysValidationWorkspace,
     network: HolochainP2pCell,
     dependencies: &mut PendingDependencies,
+    checks: &mut ProfiledChecks,
 ) -> SysValidationResult<()> {
-    // Get data ready to validate
-    let entry_type = header.entry_type();
-    let entry_hash = header.entry_hash();
-
-    // Checks
-    check_entry_type(entry_type, entry)?;
-    if let EntryType::App(app_entry_type) = entry_type {
-        let entry_def = check_app_entry_type(app_entry_type, conductor_api).await?;
-        check_not_private(&entry_def)?;
-    }
-    check_entry_hash(entry_hash, entry).await?;
-    check_entry_size(entry)?;
+    store_entry_structure(header, entry, conductor_api, checks).await?;
 
     // Additional checks if this is an Update
     if let NewEntryHeaderRef::Update(entry_update) = header {
@@ -428,8 +728,35 @@ async fn store_entry(
         )
         .await?;
         let original_header = dependencies.store_element(dependency).await?;
-        update_check(entry_update, original_header.header())?;
+        update_check(entry_update, original_header.header(), checks)?;
+    }
+    Ok(())
+}
+
+/// The checks on a new entry that don't need any other data
+async fn store_entry_structure(
+    header: NewEntryHeaderRef<'_>,
+    entry: &Entry,
+    conductor_api: &impl CellConductorApiT,
+    checks: &mut ProfiledChecks,
+) -> SysValidationResult<()> {
+    // Get data ready to validate
+    let entry_type = header.entry_type();
+    let entry_hash = header.entry_hash();
+
+    // Checks
+    checks.check(check_entry_type(entry_type, entry))?;
+    if let EntryType::App(app_entry_type) = entry_type {
+        let entry_def = checks.check(check_app_entry_type(app_entry_type, conductor_api).await)?;
+        if let Some(entry_def) = entry_def {
+            checks.check(check_not_private(&entry_def))?;
+        }
     }
+    check_entry_hash(entry_hash, entry).await?;
+    let dna_file = { conductor_api.get_this_dna().await };
+    let dna_file =
+        dna_file.ok_or_else(|| SysValidationError::DnaMissing(conductor_api.cell_id().clone()))?;
+    checks.check(check_entry_size(entry, max_entry_size(dna_file.dna())))?;
     Ok(())
 }
 
@@ -439,6 +766,7 @@ async fn register_updated_by(
     network: HolochainP2pCell,
     dependencies: &mut PendingDependencies,
     check_level: CheckLevel,
+    checks: &mut ProfiledChecks,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
     let original_header_address = &entry_update.original_header_address;
@@ -453,7 +781,7 @@ async fn register_updated_by(
     )
     .await?;
     let original_element = dependencies.store_entry_fixed(dependency).await?;
-    update_check(entry_update, original_element.header())?;
+    update_check(entry_update, original_element.header(), checks)?;
     Ok(())
 }
 
@@ -463,15 +791,23 @@ async fn register_deleted_by(
     network: HolochainP2pCell,
     dependencies: &mut PendingDependencies,
     check_level: CheckLevel,
+    checks: &mut ProfiledChecks,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
     let removed_header_address = &element_delete.deletes_address;
+    let delete_address = HeaderHash::with_data_sync(&Header::from(element_delete.clone()));
 
     // Checks
     let dependency =
         check_holding_element_all(removed_header_address, workspace, network, check_level).await?;
     let removed_header = dependencies.store_entry_fixed(dependency).await?;
-    check_new_entry_header(removed_header.header())?;
+    check_delete_author(element_delete, removed_header.header())?;
+    checks.check(check_delete_reference(
+        element_delete,
+        removed_header.header(),
+    ))?;
+    checks
+        .check(check_not_deleted_all(removed_header_address, &delete_address, workspace).await)?;
     Ok(())
 }
 
@@ -481,15 +817,24 @@ async fn register_deleted_entry_header(
     network: HolochainP2pCell,
     dependencies: &mut PendingDependencies,
     check_level: CheckLevel,
+    checks: &mut ProfiledChecks,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
     let removed_header_address = &element_delete.deletes_address;
 
+    let delete_address = HeaderHash::with_data_sync(&Header::from(element_delete.clone()));
+
     // Checks
     let dependency =
         check_holding_header_all(removed_header_address, workspace, network, check_level).await?;
     let removed_header = dependencies.store_element(dependency).await?;
-    check_new_entry_header(removed_header.header())?;
+    check_delete_author(element_delete, removed_header.header())?;
+    checks.check(check_delete_reference(
+        element_delete,
+        removed_header.header(),
+    ))?;
+    checks
+        .check(check_not_deleted_all(removed_header_address, &delete_address, workspace).await)?;
     Ok(())
 }
 
@@ -499,19 +844,20 @@ async fn register_add_link(
     network: HolochainP2pCell,
     dependencies: &mut PendingDependencies,
     check_level: CheckLevel,
+    checks: &mut ProfiledChecks,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
     let base_entry_address = &link_add.base_address;
     let target_entry_address = &link_add.target_address;
 
     // Checks
+    checks.check(check_tag_size(&link_add.tag))?;
     let dependency =
         check_holding_entry_all(base_entry_address, workspace, network.clone(), check_level)
             .await?;
     dependencies.store_entry_any(dependency).await?;
     let dependency = check_entry_exists(target_entry_address.clone(), workspace, network).await?;
     dependencies.store_entry_any(dependency).await?;
-    check_tag_size(&link_add.tag)?;
     Ok(())
 }
 
@@ -521,23 +867,31 @@ async fn register_delete_link(
     network: HolochainP2pCell,
     dependencies: &mut PendingDependencies,
     check_level: CheckLevel,
+    checks: &mut ProfiledChecks,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
     let link_add_address = &link_remove.link_add_address;
 
+    let link_remove_address = HeaderHash::with_data_sync(&Header::from(link_remove.clone()));
+
     // Checks
     let dependency =
         check_holding_link_add_all(link_add_address, workspace, network, check_level).await?;
     dependencies.add_link(dependency).await?;
+    checks.check(
+        check_link_not_removed_all(link_add_address, &link_remove_address, workspace).await,
+    )?;
     Ok(())
 }
 
-fn update_check(entry_update: &Update, original_header: &Header) -> SysValidationResult<()> {
-    check_new_entry_header(original_header)?;
-    let original_header: NewEntryHeaderRef = original_header
-        .try_into()
-        .expect("This can't fail due to the above check_new_entry_header");
-    check_update_reference(entry_update, &original_header)?;
+fn update_check(
+    entry_update: &Update,
+    original_header: &Header,
+    checks: &mut ProfiledChecks,
+) -> SysValidationResult<()> {
+    if let Some(original_header) = checks.check(check_update_target(original_header))? {
+        checks.check(check_update_reference(entry_update, &original_header))?;
+    }
     Ok(())
 }
 
@@ -558,6 +912,24 @@ pub struct SysValidationWorkspace {
     pub meta_cache: MetadataBuf,
     // Ops to disintegrate
     pub to_disintegrate_pending: Vec<DhtOpLight>,
+    // Stops network retrievals when the network is unreachable
+    pub network_breaker: Option<NetworkBreaker>,
+    // Ops this node authored
+    pub authored_dht_ops: AuthoredDhtOpsStore,
+    // Authored ops skip the checks that fetch dependencies
+    pub author_is_authority: bool,
+    // When missing dependencies are asked for again
+    pub retry_schedule: Option<RetrySchedule>,
+    // How strictly failed checks are treated
+    pub validation_profile: ValidationProfile,
+    // How dependencies are checked, Cached reuses the ones in dep_cache
+    pub check_level: CheckLevel,
+    // Dependencies retrieved at CheckLevel::Cached
+    pub dep_cache: DepCache,
+    // Where checked dependencies were found, shared with the Cell
+    pub dependency_counters: DependencyCounters,
+    // Ops waiting on longer chains of pending ops are parked
+    pub max_pending_dependency_depth: usize,
 }
 
 impl<'a> SysValidationWorkspace {
@@ -565,24 +937,44 @@ impl<'a> SysValidationWorkspace {
         &'a mut self,
         network: Network,
     ) -> Cascade<'a, Network> {
-        Cascade::new(
+        let cascade = Cascade::new(
             self.validation_limbo.env().clone(),
             &self.element_vault,
             &self.meta_vault,
             &mut self.element_cache,
             &mut self.meta_cache,
             network,
-        )
+        );
+        let cascade = match &self.retry_schedule {
+            Some(retries) => cascade.with_retry_schedule(retries.clone()),
+            None => cascade,
+        };
+        match &self.network_breaker {
+            Some(breaker) => cascade.with_breaker(breaker.clone()),
+            None => cascade,
+        }
     }
 }
 
 impl SysValidationWorkspace {
     pub fn new(env: EnvironmentRead) -> WorkspaceResult<Self> {
+        Self::with_dep_cache(env, DEFAULT_DEP_CACHE_CAPACITY, DEFAULT_DEP_CACHE_TTL)
+    }
+
+    /// Cache up to `capacity` dependencies retrieved at [CheckLevel::Cached] for `ttl` each
+    pub fn with_dep_cache(
+        env: EnvironmentRead,
+        capacity: usize,
+        ttl: std::time::Duration,
+    ) -> WorkspaceResult<Self> {
         let db = env.get_db(&*INTEGRATION_LIMBO)?;
         let integration_limbo = KvBufFresh::new(env.clone(), db);
 
         let validation_limbo = ValidationLimboStore::new(env.clone())?;
 
+        let db = env.get_db(&*AUTHORED_DHT_OPS)?;
+        let authored_dht_ops = KvBufFresh::new(env.clone(), db);
+
         let element_vault = ElementBuf::vault(env.clone(), false)?;
         let meta_vault = MetadataBuf::vault(env.clone())?;
         let element_cache = ElementBuf::cache(env.clone())?;
@@ -606,9 +998,68 @@ impl SysValidationWorkspace {
             element_cache,
             meta_cache,
             to_disintegrate_pending: Vec::new(),
+            network_breaker: None,
+            authored_dht_ops,
+            author_is_authority: false,
+            retry_schedule: None,
+            validation_profile: ValidationProfile::default(),
+            check_level: CheckLevel::Proof,
+            dep_cache: DepCache::new(capacity, ttl),
+            dependency_counters: DependencyCounters::default(),
+            max_pending_dependency_depth: DEFAULT_MAX_PENDING_DEPENDENCY_DEPTH,
         })
     }
 
+    /// Count the ops waiting in each limbo and where the dependencies
+    /// sys validation checked were found.
+    /// The counts are the entries LMDB keeps for each database,
+    /// so polling this costs the same however many ops are waiting.
+    pub fn metrics(&self) -> WorkspaceResult<SysValidationMetrics> {
+        let env = self.validation_limbo.env();
+        Ok(SysValidationMetrics {
+            validation_limbo: env.db_len(&DbName::ValidationLimbo)?,
+            integration_limbo: env.db_len(&DbName::IntegrationLimbo)?,
+            integrated: env.db_len(&DbName::IntegratedDhtOps)?,
+            ..SysValidationMetrics::new(&self.dependency_counters)
+        })
+    }
+
+    /// Count the validation limbo by status and how long it has waited.
+    /// This reads every op in the limbo so it's for looking into a backlog
+    /// [metrics](Self::metrics) shows, not for polling.
+    pub fn limbo_breakdown(&self) -> WorkspaceResult<LimboBreakdown> {
+        let env = self.validation_limbo.env().clone();
+        let limbo: Vec<ValidationLimboValue> = fresh_reader!(env, |r| self
+            .validation_limbo
+            .iter(&r)?
+            .map(|(_, vlv)| Ok(vlv))
+            .collect())?;
+        Ok(LimboBreakdown::count(&limbo))
+    }
+
+    /// How long the chain of pending ops waited on through these dependencies is,
+    /// and the header the deepest chain starts at.
+    /// Dependencies that have left the validation limbo end a chain.
+    fn pending_dependency_depth(
+        &self,
+        dependencies: &PendingDependencies,
+    ) -> WorkflowResult<(usize, Option<AnyDhtHash>)> {
+        let mut deepest = (0, None);
+        for dep in &dependencies.pending {
+            let (depth, head) = match self.validation_limbo.get(dep.as_ref())? {
+                Some(vlv) => (
+                    vlv.pending_dependencies.depth + 1,
+                    Some(vlv.op.header_hash().clone().into()),
+                ),
+                None => (1, None),
+            };
+            if depth > deepest.0 {
+                deepest = (depth, head);
+            }
+        }
+        Ok(deepest)
+    }
+
     fn put_val_limbo(
         &mut self,
         hash: DhtOpHash,
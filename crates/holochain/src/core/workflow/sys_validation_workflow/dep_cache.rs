@@ -0,0 +1,191 @@
+//! A short lived cache of the dependencies sys validation retrieved from the network.
+//!
+//! Many ops in one validation pass can reference the same dependency,
+//! like every link on a popular base. At [CheckLevel::Cached](super::types::CheckLevel::Cached)
+//! a dependency retrieved for one op is reused by the others instead of being
+//! retrieved again. Entries expire after a short time so a pass that runs
+//! for a long time still notices changes on the DHT.
+
+use holo_hash::{AnyDhtHash, HeaderHash};
+use holochain_types::element::Element;
+use holochain_zome_types::element::SignedHeaderHashed;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How many dependencies are cached if the workspace doesn't set a capacity
+pub const DEFAULT_DEP_CACHE_CAPACITY: usize = 256;
+
+/// How long a dependency is cached if the workspace doesn't set a ttl
+pub const DEFAULT_DEP_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// A dependency as it was retrieved
+#[derive(Clone, Debug)]
+enum CachedDep {
+    Element(Element),
+    Header(SignedHeaderHashed),
+}
+
+struct Cached {
+    dep: CachedDep,
+    retrieved: Instant,
+    last_used: u64,
+}
+
+/// Least recently used cache of retrieved dependencies by their hash
+pub struct DepCache {
+    capacity: usize,
+    ttl: Duration,
+    deps: HashMap<AnyDhtHash, Cached>,
+    /// Increases on every use so the oldest use is the smallest
+    clock: u64,
+}
+
+impl Default for DepCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEP_CACHE_CAPACITY, DEFAULT_DEP_CACHE_TTL)
+    }
+}
+
+impl DepCache {
+    /// Cache up to `capacity` dependencies for `ttl` each.
+    /// A capacity of zero caches nothing.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            deps: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// The element cached for this hash
+    pub fn get_element(&mut self, hash: &AnyDhtHash) -> Option<Element> {
+        match self.get(hash)? {
+            CachedDep::Element(element) => Some(element.clone()),
+            CachedDep::Header(_) => None,
+        }
+    }
+
+    /// The header cached for this hash, either on its own or as part of an element
+    pub fn get_header(&mut self, hash: &HeaderHash) -> Option<SignedHeaderHashed> {
+        match self.get(&hash.clone().into())? {
+            CachedDep::Element(element) => Some(element.signed_header().clone()),
+            CachedDep::Header(header) => Some(header.clone()),
+        }
+    }
+
+    /// Cache an element retrieved by this hash
+    pub fn put_element(&mut self, hash: AnyDhtHash, element: Element) {
+        self.put(hash, CachedDep::Element(element));
+    }
+
+    /// Cache a header retrieved by its hash
+    pub fn put_header(&mut self, hash: HeaderHash, header: SignedHeaderHashed) {
+        self.put(hash.into(), CachedDep::Header(header));
+    }
+
+    /// How many dependencies are cached, including any that have expired
+    pub fn len(&self) -> usize {
+        self.deps.len()
+    }
+
+    /// Is nothing cached
+    pub fn is_empty(&self) -> bool {
+        self.deps.is_empty()
+    }
+
+    fn get(&mut self, hash: &AnyDhtHash) -> Option<&CachedDep> {
+        let expired = self.deps.get(hash)?.retrieved.elapsed() >= self.ttl;
+        if expired {
+            self.deps.remove(hash);
+            return None;
+        }
+        self.clock += 1;
+        let cached = self.deps.get_mut(hash)?;
+        cached.last_used = self.clock;
+        Some(&cached.dep)
+    }
+
+    fn put(&mut self, hash: AnyDhtHash, dep: CachedDep) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.clock += 1;
+        self.deps.insert(
+            hash,
+            Cached {
+                dep,
+                retrieved: Instant::now(),
+                last_used: self.clock,
+            },
+        );
+        while self.deps.len() > self.capacity {
+            let oldest = self
+                .deps
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(hash, _)| hash.clone());
+            match oldest {
+                Some(hash) => {
+                    self.deps.remove(&hash);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixt::{HeaderFixturator, SignatureFixturator};
+    use ::fixt::prelude::*;
+    use holochain_types::HeaderHashed;
+
+    fn header() -> (HeaderHash, SignedHeaderHashed) {
+        let header = SignedHeaderHashed::with_presigned(
+            HeaderHashed::from_content_sync(fixt!(Header)),
+            fixt!(Signature),
+        );
+        (header.as_hash().clone(), header)
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used() {
+        let mut cache = DepCache::new(2, Duration::from_secs(60));
+        let (a, a_header) = header();
+        let (b, b_header) = header();
+        let (c, c_header) = header();
+        cache.put_header(a.clone(), a_header);
+        cache.put_header(b.clone(), b_header);
+
+        // Using a makes b the oldest
+        assert!(cache.get_header(&a).is_some());
+        cache.put_header(c.clone(), c_header);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get_header(&a).is_some());
+        assert!(cache.get_header(&b).is_none());
+        assert!(cache.get_header(&c).is_some());
+    }
+
+    #[test]
+    fn expires_after_the_ttl() {
+        let mut cache = DepCache::new(2, Duration::from_millis(10));
+        let (a, a_header) = header();
+        cache.put_header(a.clone(), a_header);
+        assert!(cache.get_header(&a).is_some());
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(cache.get_header(&a).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn zero_capacity_caches_nothing() {
+        let mut cache = DepCache::new(0, Duration::from_secs(60));
+        let (a, a_header) = header();
+        cache.put_header(a.clone(), a_header);
+        assert!(cache.get_header(&a).is_none());
+    }
+}
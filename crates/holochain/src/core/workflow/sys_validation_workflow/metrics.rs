@@ -0,0 +1,201 @@
+//! Metrics for operators to see how sys validation is keeping up.
+//!
+//! Ops stuck waiting on dependencies are otherwise invisible until they
+//! are integrated. [SysValidationWorkspace::metrics](super::SysValidationWorkspace::metrics)
+//! reports how many ops are in each limbo and where the dependencies
+//! sys validation checked were found.
+//! [SysValidationWorkspace::limbo_breakdown](super::SysValidationWorkspace::limbo_breakdown)
+//! goes through the validation limbo to count it by status.
+
+use super::types::Dependency;
+use crate::core::state::validation_db::{ValidationLimboStatus, ValidationLimboValue};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+struct Counts {
+    proofs: AtomicU64,
+    claims: AtomicU64,
+    pending_validation: AtomicU64,
+    not_held: AtomicU64,
+    network_retrievals: AtomicU64,
+}
+
+/// Counts where the dependencies sys validation checked were found.
+/// Clones share the same counts so a Cell can read what its
+/// queue consumer records.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyCounters(Arc<Counts>);
+
+impl DependencyCounters {
+    /// Count a dependency that was found
+    pub fn record<T>(&self, dep: &Dependency<T>) {
+        let count = match dep {
+            Dependency::Proof(_) => &self.0.proofs,
+            Dependency::Claim(_) => &self.0.claims,
+            Dependency::PendingValidation(_) => &self.0.pending_validation,
+        };
+        count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a dependency that wasn't held so the op has to wait for it
+    pub fn record_not_held(&self) {
+        self.0.not_held.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a dependency that wasn't held so had to be retrieved from the network
+    pub fn record_network_retrieval(&self) {
+        self.0.network_retrievals.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// A snapshot of sys validation from
+/// [SysValidationWorkspace::metrics](super::SysValidationWorkspace::metrics)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SysValidationMetrics {
+    /// Ops waiting to be validated
+    pub validation_limbo: usize,
+    /// Validated ops waiting to be integrated
+    pub integration_limbo: usize,
+    /// Integrated ops
+    pub integrated: usize,
+    /// Dependencies found held and validated by this agent
+    pub proofs: u64,
+    /// Dependencies another agent claimed to have validated
+    pub claims: u64,
+    /// Dependencies found held but not yet validated by this agent
+    pub pending_validation_deps: u64,
+    /// Dependencies this agent wasn't holding so the op waited for them
+    pub not_held: u64,
+    /// Dependencies this agent wasn't holding so retrieved from the network
+    pub network_retrievals: u64,
+}
+
+impl SysValidationMetrics {
+    /// Start a snapshot from the current dependency counts
+    pub(super) fn new(counters: &DependencyCounters) -> Self {
+        Self {
+            proofs: counters.0.proofs.load(Ordering::Relaxed),
+            claims: counters.0.claims.load(Ordering::Relaxed),
+            pending_validation_deps: counters.0.pending_validation.load(Ordering::Relaxed),
+            not_held: counters.0.not_held.load(Ordering::Relaxed),
+            network_retrievals: counters.0.network_retrievals.load(Ordering::Relaxed),
+            ..Default::default()
+        }
+    }
+}
+
+/// The validation limbo counted by status from
+/// [SysValidationWorkspace::limbo_breakdown](super::SysValidationWorkspace::limbo_breakdown)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LimboBreakdown {
+    /// Ops waiting to be sys validated
+    pub pending: usize,
+    /// Ops waiting on dependencies before they can be sys validated
+    pub awaiting_sys_deps: usize,
+    /// Ops waiting to be app validated
+    pub sys_validated: usize,
+    /// Ops waiting on dependencies before they can be app validated
+    pub awaiting_app_deps: usize,
+    /// Validated ops waiting on a dependency to pass validation
+    pub pending_validation: usize,
+    /// How long the ops in the validation limbo have waited on average.
+    /// None if the limbo is empty.
+    pub average_time_in_limbo: Option<Duration>,
+}
+
+impl LimboBreakdown {
+    /// Count the validation limbo by status and how long it has waited
+    pub(super) fn count<'a>(limbo: impl IntoIterator<Item = &'a ValidationLimboValue>) -> Self {
+        let mut breakdown = Self::default();
+        let now = chrono::Utc::now();
+        let mut waited = Duration::default();
+        let mut ops: u32 = 0;
+        for vlv in limbo {
+            match vlv.status {
+                ValidationLimboStatus::Pending => breakdown.pending += 1,
+                ValidationLimboStatus::AwaitingSysDeps(_) => breakdown.awaiting_sys_deps += 1,
+                ValidationLimboStatus::SysValidated => breakdown.sys_validated += 1,
+                ValidationLimboStatus::AwaitingAppDeps(_) => breakdown.awaiting_app_deps += 1,
+                ValidationLimboStatus::PendingValidation => breakdown.pending_validation += 1,
+            }
+            let added: chrono::DateTime<chrono::Utc> = vlv.time_added.into();
+            // Clocks can move backwards so an op added "in the future" hasn't waited
+            waited += (now - added).to_std().unwrap_or_default();
+            ops += 1;
+        }
+        breakdown.average_time_in_limbo = if ops > 0 { Some(waited / ops) } else { None };
+        breakdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::workflow::sys_validation_workflow::types::PendingDependencies;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::HeaderHashFixturator;
+    use holochain_types::dht_op::DhtOpLight;
+
+    fn vlv(status: ValidationLimboStatus, waited: chrono::Duration) -> ValidationLimboValue {
+        let header_hash = fixt!(HeaderHash);
+        ValidationLimboValue {
+            status,
+            pending_dependencies: PendingDependencies::new(),
+            op: DhtOpLight::StoreElement(header_hash.clone(), None, header_hash.clone().into()),
+            basis: header_hash.into(),
+            time_added: (chrono::Utc::now() - waited).into(),
+            last_try: None,
+            num_tries: 0,
+            num_app_validations: 0,
+        }
+    }
+
+    #[test]
+    fn counts_dependencies_and_limbo_by_status() {
+        let counters = DependencyCounters::default();
+        counters.record(&Dependency::Proof(()));
+        counters.record(&Dependency::Claim(()));
+        counters.record(&Dependency::Claim(()));
+        counters.record_not_held();
+        counters.record_network_retrieval();
+        // Clones count into the same metrics
+        counters.clone().record(&Dependency::PendingValidation(()));
+
+        let limbo = vec![
+            vlv(
+                ValidationLimboStatus::Pending,
+                chrono::Duration::seconds(10),
+            ),
+            vlv(
+                ValidationLimboStatus::AwaitingSysDeps(vec![]),
+                chrono::Duration::seconds(30),
+            ),
+            vlv(
+                ValidationLimboStatus::AwaitingSysDeps(vec![]),
+                chrono::Duration::seconds(20),
+            ),
+        ];
+        let metrics = SysValidationMetrics::new(&counters);
+        assert_eq!(metrics.proofs, 1);
+        assert_eq!(metrics.claims, 2);
+        assert_eq!(metrics.pending_validation_deps, 1);
+        assert_eq!(metrics.not_held, 1);
+        assert_eq!(metrics.network_retrievals, 1);
+
+        let breakdown = LimboBreakdown::count(&limbo);
+        assert_eq!(breakdown.pending, 1);
+        assert_eq!(breakdown.awaiting_sys_deps, 2);
+        assert_eq!(breakdown.sys_validated, 0);
+        let average = breakdown.average_time_in_limbo.unwrap();
+        assert!(average >= Duration::from_secs(20) && average < Duration::from_secs(21));
+
+        assert_eq!(
+            LimboBreakdown::count(&Vec::new()).average_time_in_limbo,
+            None
+        );
+    }
+}
@@ -1,25 +1,54 @@
+use super::{sys_validation_workflow_inner, types::PendingDependencies, SysValidationWorkspace};
 use crate::{
-    conductor::{dna_store::MockDnaStore, ConductorHandle},
+    conductor::{api::MockCellConductorApi, dna_store::MockDnaStore, ConductorHandle},
     core::{
-        state::{element_buf::ElementBuf, validation_db::ValidationLimboStatus},
-        workflow::incoming_dht_ops_workflow::IncomingDhtOpsWorkspace,
+        state::{
+            dht_op_integration::AuthoredDhtOpsValue,
+            element_buf::ElementBuf,
+            validation_db::{ValidationLimboStatus, ValidationLimboValue},
+            workspace::{Workspace, WorkspaceError},
+        },
+        workflow::{
+            incoming_dht_ops_workflow::IncomingDhtOpsWorkspace,
+            integrate_dht_ops_workflow::integrate_single_data,
+        },
     },
-    test_utils::{host_fn_api::*, setup_app},
+    test_utils::{host_fn_api::*, setup_app, test_network},
 };
 use ::fixt::prelude::*;
 use fallible_iterator::FallibleIterator;
+use futures::future::FutureExt;
 use hdk3::prelude::LinkTag;
 use holo_hash::{AnyDhtHash, DhtOpHash, EntryHash, HeaderHash};
+use holochain_p2p::{event::HolochainP2pEvent, HolochainP2pCell};
 use holochain_serialized_bytes::SerializedBytes;
-use holochain_state::{fresh_reader_test, prelude::ReadManager};
+use holochain_state::{
+    buffer::BufferedStore, env::EnvironmentWrite, fresh_reader_test, prelude::ReadManager,
+    test_utils::test_cell_env,
+};
 use holochain_types::{
-    app::InstalledCell, cell::CellId, dht_op::DhtOpLight, dna::DnaDef, dna::DnaFile, fixt::*,
-    test_utils::fake_agent_pubkey_1, test_utils::fake_agent_pubkey_2, validate::ValidationStatus,
-    Entry,
+    app::InstalledCell,
+    cell::CellId,
+    dht_op::DhtOp,
+    dht_op::DhtOpLight,
+    dna::DnaDef,
+    dna::DnaFile,
+    element::{GetElementResponse, SignedHeaderHashed, SignedHeaderHashedExt},
+    fixt::*,
+    test_utils::fake_agent_pubkey_1,
+    test_utils::fake_agent_pubkey_2,
+    test_utils::fake_header_hash,
+    validate::ValidationStatus,
+    Entry, HeaderHashed, Timestamp,
 };
 use holochain_wasm_test_utils::TestWasm;
+use holochain_zome_types::header::InitZomesComplete;
 use std::{
     convert::{TryFrom, TryInto},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 use tracing::*;
@@ -35,6 +64,10 @@ async fn sys_validation_workflow_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            reject_self_links: false,
+            max_entry_size: None,
+            update_conflict_policy: Default::default(),
+            sensitive_fns: Default::default(),
         },
         vec![TestWasm::Create.into()],
     )
@@ -148,6 +181,13 @@ async fn run_test(
         }
 
         assert_eq!(res.len(), 9 + 14);
+
+        let summary = handle.integration_summary(&alice_cell_id).await.unwrap();
+        // Both agent keys plus bob's base and target
+        assert_eq!(summary.store_entry, 2 + 2);
+        assert_eq!(summary.register_add_link, 1);
+        assert_eq!(summary.rejected, 0);
+        assert_eq!(summary.total(), res.len());
     }
 
     let (bad_update_header, bad_update_entry_hash, link_add_hash) =
@@ -212,19 +252,39 @@ async fn run_test(
                 .unwrap()),
             12 + 23
         );
+
+        let summary = handle.integration_summary(&alice_cell_id).await.unwrap();
+        // Plus the new base, target and bad update
+        assert_eq!(summary.store_entry, 4 + 3);
+        // Plus the large link
+        assert_eq!(summary.register_add_link, 1 + 1);
+        // The bad update's element and entry and the large link
+        assert_eq!(summary.rejected, 3);
+        assert_eq!(summary.total(), 12 + 23);
     }
 
-    dodgy_bob(&bob_cell_id, &handle, &dna_file).await;
+    let target_entry_hash = dodgy_bob(&bob_cell_id, &handle, &dna_file).await;
 
     // Some time for ops to reach alice and run through validation
     tokio::time::delay_for(Duration::from_millis(1500)).await;
 
+    // Bob's link is stuck in alice's limbo
+    let health = handle.health_summary().await.unwrap();
+    assert_eq!(health.cells.len(), 2);
+    let alice_health = health
+        .cells
+        .iter()
+        .find(|cell| cell.cell_id == alice_cell_id)
+        .unwrap();
+    assert!(alice_health.validation_limbo > 0);
+
     {
         let alice_env = handle.get_cell_env(&alice_cell_id).await.unwrap();
         let env_ref = alice_env.guard();
 
         let workspace = IncomingDhtOpsWorkspace::new(alice_env.clone().into()).unwrap();
-        // Validation should still contain bobs link pending because the target was missing
+        // Validation should still contain bobs link awaiting the missing target
+        let missing_deps = vec![AnyDhtHash::from(target_entry_hash)];
         assert_eq!(
             {
                 let r = env_ref.reader().unwrap();
@@ -236,7 +296,10 @@ async fn run_test(
                         let s = debug_span!("inspect_ops");
                         let _g = s.enter();
                         debug!(?i.op);
-                        assert_eq!(i.status, ValidationLimboStatus::Pending);
+                        assert_eq!(
+                            i.status,
+                            ValidationLimboStatus::AwaitingSysDeps(missing_deps.clone())
+                        );
                         Ok(())
                     })
                     .count()
@@ -371,7 +434,11 @@ async fn bob_makes_a_large_link(
     (bad_update_header, bad_update_entry_hash, link_add_address)
 }
 
-async fn dodgy_bob(bob_cell_id: &CellId, handle: &ConductorHandle, dna_file: &DnaFile) {
+async fn dodgy_bob(
+    bob_cell_id: &CellId,
+    handle: &ConductorHandle,
+    dna_file: &DnaFile,
+) -> EntryHash {
     let base = Post("Bob is the best and I'll link to proof so you can check".into());
     let target = Post("Dodgy proof Bob is the best".into());
     let base_entry_hash = EntryHash::with_data_sync(&Entry::try_from(base.clone()).unwrap());
@@ -403,4 +470,1258 @@ async fn dodgy_bob(bob_cell_id: &CellId, handle: &ConductorHandle, dna_file: &Dn
     // Produce and publish these commits
     let mut triggers = handle.get_cell_triggers(&bob_cell_id).await.unwrap();
     triggers.produce_dht_ops.trigger();
+    target_entry_hash
+}
+
+#[test]
+fn ops_are_ordered_by_dependency() {
+    use super::{
+        order_ops,
+        types::{DhtOpOrder, OrderedOp},
+    };
+    use holochain_types::{dht_op::DhtOp, header::NewEntryHeader};
+    use holochain_zome_types::{timestamp::Timestamp, Header};
+
+    let base_entry = fixt!(Entry);
+    let mut base_header = fixt!(Create);
+    base_header.entry_hash = EntryHash::with_data_sync(&base_entry);
+    base_header.timestamp = Timestamp(20, 0);
+
+    // The link was authored before the base arrived
+    let mut link_header = fixt!(CreateLink);
+    link_header.base_address = base_header.entry_hash.clone();
+    link_header.timestamp = Timestamp(10, 0);
+
+    // An update to the base, which sorts before the base's store element
+    let mut update_header = fixt!(Update);
+    update_header.original_header_address =
+        HeaderHash::with_data_sync(&Header::Create(base_header.clone()));
+    update_header.timestamp = Timestamp(30, 0);
+
+    let store_entry = DhtOp::StoreEntry(
+        fixt!(Signature),
+        NewEntryHeader::Create(base_header.clone()),
+        Box::new(base_entry),
+    );
+    let store_element = DhtOp::StoreElement(fixt!(Signature), Header::Create(base_header), None);
+    let add_link = DhtOp::RegisterAddLink(fixt!(Signature), link_header);
+    let store_update = DhtOp::StoreEntry(
+        fixt!(Signature),
+        NewEntryHeader::Update(update_header),
+        Box::new(fixt!(Entry)),
+    );
+    let hash = |op: &DhtOp| DhtOpHash::with_data_sync(op);
+    let expected = vec![
+        hash(&store_entry),
+        hash(&store_element),
+        hash(&store_update),
+        hash(&add_link),
+    ];
+
+    // Gossip the ops that depend on the base before the base
+    let ops = vec![add_link, store_update, store_element, store_entry]
+        .into_iter()
+        .map(|op| OrderedOp {
+            order: DhtOpOrder::from(&op),
+            hash: hash(&op),
+            op,
+            value: (),
+        })
+        .collect();
+
+    let order: Vec<_> = order_ops(ops).into_iter().map(|o| o.hash).collect();
+    assert_eq!(order, expected);
+}
+
+/// Ops gossiped after the ops that depend on them are still validated first,
+/// so the dependent op sees how long its dependency waited
+#[tokio::test(threaded_scheduler)]
+async fn gossiped_ops_are_validated_in_dependency_order() {
+    use super::types::CheckLevel;
+    use crate::{
+        core::{
+            queue_consumer::TriggerSender,
+            workflow::incoming_dht_ops_workflow::{incoming_dht_ops_workflow, AcceptAllOps},
+        },
+        test_utils::test_network,
+    };
+    use holochain_state::test_utils::test_cell_env;
+    use holochain_types::{
+        element::SignedHeaderHashed, element::SignedHeaderHashedExt, HeaderHashed,
+    };
+    use holochain_zome_types::{
+        header::{Dna, InitZomesComplete},
+        Header,
+    };
+    use std::sync::Arc;
+
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let keystore = holochain_state::test_utils::test_keystore();
+
+    let base = Timestamp::now().0;
+    let dna: Header = Dna {
+        author: fake_agent_pubkey_1(),
+        timestamp: Timestamp(base, 0).into(),
+        hash: fixt!(DnaHash),
+    }
+    .into();
+    let first: Header = InitZomesComplete {
+        author: fake_agent_pubkey_1(),
+        timestamp: Timestamp(base + 1, 0).into(),
+        header_seq: 1,
+        prev_header: HeaderHash::with_data_sync(&dna),
+    }
+    .into();
+    let second: Header = InitZomesComplete {
+        author: fake_agent_pubkey_1(),
+        timestamp: Timestamp(base + 2, 0).into(),
+        header_seq: 2,
+        prev_header: HeaderHash::with_data_sync(&first),
+    }
+    .into();
+    let sign = |header: Header| {
+        let keystore = keystore.clone();
+        async move {
+            let header = HeaderHashed::from_content_sync(header);
+            SignedHeaderHashed::new(&keystore, header)
+                .await
+                .unwrap()
+                .into_header_and_signature()
+        }
+    };
+
+    // The start of the chain has already passed sys validation
+    let (header, signature) = sign(dna).await;
+    let dna_op = DhtOp::StoreElement(signature, header.into_content(), None);
+    let dna_op_hash = put_in_limbo(&env, dna_op, false).await;
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    let mut vlv = workspace
+        .validation_limbo
+        .get(&dna_op_hash)
+        .unwrap()
+        .unwrap();
+    vlv.status = ValidationLimboStatus::SysValidated;
+    workspace.validation_limbo.put(dna_op_hash, vlv).unwrap();
+    env.guard()
+        .with_commit::<WorkspaceError, _, _>(|writer| workspace.flush_to_txn_ref(writer))
+        .unwrap();
+
+    // The agent activity of the second header sorts before
+    // the store element of the first header it depends on
+    let (header, signature) = sign(first).await;
+    let first_op = DhtOp::StoreElement(signature, header.into_content(), None);
+    let (header, signature) = sign(second).await;
+    let second_op = DhtOp::RegisterAgentActivity(signature, header.into_content());
+    let first_op_hash = DhtOpHash::with_data_sync(&first_op);
+    let second_op_hash = DhtOpHash::with_data_sync(&second_op);
+
+    // Gossip the second op before the first
+    let (sys_validation_trigger, _rx) = TriggerSender::new();
+    incoming_dht_ops_workflow(
+        &env,
+        sys_validation_trigger,
+        vec![
+            (second_op_hash.clone(), second_op),
+            (first_op_hash.clone(), first_op),
+        ],
+        Arc::new(AcceptAllOps),
+    )
+    .await
+    .unwrap();
+
+    // Only the ops held pending validation are checked, not their metadata
+    let (_network, _recv, cell_network) = test_network(None, None).await;
+    run_workflow(&env, cell_network, |workspace, _| {
+        workspace.check_level = CheckLevel::Claim
+    })
+    .await;
+
+    let workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    let first = workspace
+        .validation_limbo
+        .get(&first_op_hash)
+        .unwrap()
+        .unwrap();
+    let second = workspace
+        .validation_limbo
+        .get(&second_op_hash)
+        .unwrap()
+        .unwrap();
+    assert_eq!(first.status, ValidationLimboStatus::SysValidated);
+    assert_eq!(second.status, ValidationLimboStatus::SysValidated);
+    // The first op was validated before the second,
+    // so the second counts the wait on the start of the chain
+    assert_eq!(first.pending_dependencies.depth, 1);
+    assert_eq!(second.pending_dependencies.depth, 2);
+}
+
+/// Put an op in the validation limbo as if it had just arrived,
+/// and in the authored ops if this node authored it
+async fn put_in_limbo(env: &EnvironmentWrite, op: DhtOp, authored: bool) -> DhtOpHash {
+    let hash = DhtOpHash::with_data_sync(&op);
+    let light = op.to_light().await;
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    if authored {
+        workspace
+            .authored_dht_ops
+            .put(hash.clone(), AuthoredDhtOpsValue::from_light(light.clone()))
+            .unwrap();
+    }
+    let vlv = ValidationLimboValue {
+        status: ValidationLimboStatus::Pending,
+        pending_dependencies: PendingDependencies::new(),
+        basis: op.dht_basis().await,
+        op: light,
+        time_added: Timestamp::now(),
+        last_try: None,
+        num_tries: 0,
+        num_app_validations: 0,
+    };
+    integrate_single_data(op, &mut workspace.element_pending).unwrap();
+    workspace.validation_limbo.put(hash.clone(), vlv).unwrap();
+    env.guard()
+        .with_commit::<WorkspaceError, _, _>(|writer| {
+            workspace.authored_dht_ops.flush_to_txn_ref(writer)?;
+            workspace.flush_to_txn_ref(writer)?;
+            Ok(())
+        })
+        .unwrap();
+    hash
+}
+
+/// Run sys validation over the limbo and commit the results.
+/// `configure` sets up the workspace and conductor api for the run.
+async fn run_workflow(
+    env: &EnvironmentWrite,
+    network: HolochainP2pCell,
+    configure: impl FnOnce(&mut SysValidationWorkspace, &mut MockCellConductorApi),
+) {
+    let mut conductor_api = MockCellConductorApi::new();
+    conductor_api.expect_cell_id().return_const(fixt!(CellId));
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    configure(&mut workspace, &mut conductor_api);
+    sys_validation_workflow_inner(&mut workspace, network, conductor_api)
+        .await
+        .unwrap();
+    env.guard()
+        .with_commit::<WorkspaceError, _, _>(|writer| workspace.flush_to_txn_ref(writer))
+        .unwrap();
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn authored_ops_skip_network_dependency_checks() {
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let keystore = holochain_state::test_utils::test_keystore();
+
+    // An op whose previous header isn't held by anyone
+    let make_op = |n| {
+        let keystore = keystore.clone();
+        async move {
+            let header = InitZomesComplete {
+                author: fake_agent_pubkey_1(),
+                timestamp: Timestamp::now().into(),
+                header_seq: 5,
+                prev_header: fake_header_hash(n),
+            };
+            let header = HeaderHashed::from_content_sync(header.into());
+            let (header, signature) = SignedHeaderHashed::new(&keystore, header)
+                .await
+                .unwrap()
+                .into_header_and_signature();
+            DhtOp::StoreElement(signature, header.into_content(), None)
+        }
+    };
+
+    // Count the gets that reach the network
+    let (_network, mut recv, cell_network) = test_network(None, None).await;
+    let gets = Arc::new(AtomicUsize::new(0));
+    tokio::task::spawn({
+        let gets = gets.clone();
+        async move {
+            use tokio::stream::StreamExt;
+            while let Some(evt) = recv.next().await {
+                if let HolochainP2pEvent::Get { respond, .. } = evt {
+                    gets.fetch_add(1, Ordering::SeqCst);
+                    let response = GetElementResponse::GetHeader(None).try_into().unwrap();
+                    respond.respond(Ok(async move { Ok(response) }.boxed().into()));
+                }
+            }
+        }
+    });
+
+    // The authored op goes straight through without touching the network
+    let authored = put_in_limbo(&env, make_op(1).await, true).await;
+    run_workflow(&env, cell_network.clone(), |workspace, _| {
+        workspace.author_is_authority = true
+    })
+    .await;
+    assert_eq!(gets.load(Ordering::SeqCst), 0);
+    let workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    assert!(workspace.integration_limbo.contains(&authored).unwrap());
+
+    // The received op still looks for its dependency on the network
+    // and waits on the missing previous header
+    let received = put_in_limbo(&env, make_op(2).await, false).await;
+    run_workflow(&env, cell_network.clone(), |workspace, _| {
+        workspace.author_is_authority = true
+    })
+    .await;
+    assert!(gets.load(Ordering::SeqCst) > 0);
+    let workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    assert!(!workspace.integration_limbo.contains(&received).unwrap());
+    assert_eq!(
+        workspace
+            .validation_limbo
+            .0
+            .get(&received)
+            .unwrap()
+            .unwrap()
+            .status,
+        ValidationLimboStatus::AwaitingSysDeps(vec![fake_header_hash(2).into()])
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn validation_profile_decides_the_fate_of_a_malformed_op() {
+    use crate::{
+        conductor::config::{ValidationCategory, ValidationProfile, ValidationResponse},
+        fixt::DnaFileFixturator,
+        test_utils::test_network,
+    };
+    use holochain_keystore::Signature;
+    use holochain_state::test_utils::test_cell_env;
+    use holochain_types::{
+        element::{SignedHeaderHashed, SignedHeaderHashedExt},
+        header::NewEntryHeader,
+        test_utils::fake_header_hash,
+        HeaderHashed,
+    };
+    use holochain_zome_types::{
+        header::{Create, EntryType},
+        Header,
+    };
+
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let keystore = holochain_state::test_utils::test_keystore();
+
+    // An app entry stored under the agent key entry type.
+    // The ops are authored so their missing previous headers aren't waited on.
+    let make_op = |n, forge_signature| {
+        let keystore = keystore.clone();
+        async move {
+            let entry = fixt!(Entry, AppEntry);
+            let header = Create {
+                author: fake_agent_pubkey_1(),
+                timestamp: Timestamp::now().into(),
+                header_seq: 5,
+                prev_header: fake_header_hash(n),
+                entry_type: EntryType::AgentPubKey,
+                entry_hash: EntryHash::with_data_sync(&entry),
+            };
+            let header = HeaderHashed::from_content_sync(header.into());
+            let (header, signature) = SignedHeaderHashed::new(&keystore, header)
+                .await
+                .unwrap()
+                .into_header_and_signature();
+            let signature = if forge_signature {
+                Signature(vec![0; 64])
+            } else {
+                signature
+            };
+            let header = match header.into_content() {
+                Header::Create(create) => NewEntryHeader::Create(create),
+                _ => unreachable!(),
+            };
+            DhtOp::StoreEntry(signature, header, Box::new(entry))
+        }
+    };
+
+    // The entry is also over this DNA's size limit
+    let mut dna_file = fixt!(DnaFile);
+    dna_file.dna.max_entry_size = Some(1);
+    let with_profile = |profile| {
+        let dna_file = dna_file.clone();
+        move |workspace: &mut SysValidationWorkspace, conductor_api: &mut MockCellConductorApi| {
+            workspace.author_is_authority = true;
+            workspace.validation_profile = profile;
+            conductor_api
+                .expect_sync_get_this_dna()
+                .return_const(Some(dna_file));
+        }
+    };
+
+    let (_network, _recv, cell_network) = test_network(None, None).await;
+
+    // Strict rejects the op
+    let strict = put_in_limbo(&env, make_op(1, false).await, true).await;
+    run_workflow(
+        &env,
+        cell_network.clone(),
+        with_profile(ValidationProfile::Strict),
+    )
+    .await;
+    let workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    assert_eq!(
+        workspace
+            .integration_limbo
+            .get(&strict)
+            .unwrap()
+            .unwrap()
+            .validation_status,
+        ValidationStatus::Rejected
+    );
+
+    // Permissive accepts the same op with a warning
+    let permissive = put_in_limbo(&env, make_op(2, false).await, true).await;
+    run_workflow(
+        &env,
+        cell_network.clone(),
+        with_profile(ValidationProfile::Permissive),
+    )
+    .await;
+    let workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    assert!(!workspace.integration_limbo.contains(&permissive).unwrap());
+    assert_eq!(
+        workspace
+            .validation_limbo
+            .0
+            .get(&permissive)
+            .unwrap()
+            .unwrap()
+            .status,
+        ValidationLimboStatus::SysValidated
+    );
+
+    // But not when the signature doesn't verify
+    let forged = put_in_limbo(&env, make_op(3, true).await, true).await;
+    run_workflow(
+        &env,
+        cell_network.clone(),
+        with_profile(ValidationProfile::Permissive),
+    )
+    .await;
+    let workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    assert_eq!(
+        workspace
+            .integration_limbo
+            .get(&forged)
+            .unwrap()
+            .unwrap()
+            .validation_status,
+        ValidationStatus::Rejected
+    );
+
+    // Warning on the entry type doesn't skip the size check that comes after it
+    let custom = put_in_limbo(&env, make_op(4, false).await, true).await;
+    let profile = ValidationProfile::Custom(
+        vec![
+            (ValidationCategory::EntryDef, ValidationResponse::Warn),
+            (ValidationCategory::Size, ValidationResponse::Reject),
+        ]
+        .into_iter()
+        .collect(),
+    );
+    run_workflow(&env, cell_network, with_profile(profile)).await;
+    let workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    assert_eq!(
+        workspace
+            .integration_limbo
+            .get(&custom)
+            .unwrap()
+            .unwrap()
+            .validation_status,
+        ValidationStatus::Rejected
+    );
+}
+
+/// An op whose basis has a header prefix where an entry hash is expected
+/// would be looked up in the wrong store, so no profile lets it through.
+#[tokio::test(threaded_scheduler)]
+async fn ops_with_the_wrong_basis_hash_type_are_rejected() {
+    use crate::{conductor::config::ValidationProfile, test_utils::test_network};
+    use holochain_state::test_utils::test_cell_env;
+    use holochain_types::{
+        element::{SignedHeaderHashed, SignedHeaderHashedExt},
+        header::NewEntryHeader,
+        test_utils::fake_header_hash,
+        HeaderHashed,
+    };
+    use holochain_zome_types::{
+        header::{Create, EntryType},
+        Header,
+    };
+
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let keystore = holochain_state::test_utils::test_keystore();
+    let (_network, _recv, cell_network) = test_network(None, None).await;
+
+    let mut hashes = Vec::new();
+    for (n, forge_basis) in vec![(1, false), (2, true)] {
+        let entry = Entry::Agent(fake_agent_pubkey_1());
+        let header = Create {
+            author: fake_agent_pubkey_1(),
+            timestamp: Timestamp::now().into(),
+            header_seq: 5,
+            prev_header: fake_header_hash(n),
+            entry_type: EntryType::AgentPubKey,
+            entry_hash: EntryHash::with_data_sync(&entry),
+        };
+        let header = HeaderHashed::from_content_sync(header.into());
+        let (header, signature) = SignedHeaderHashed::new(&keystore, header)
+            .await
+            .unwrap()
+            .into_header_and_signature();
+        let header = match header.into_content() {
+            Header::Create(create) => NewEntryHeader::Create(create),
+            _ => unreachable!(),
+        };
+        let op = DhtOp::StoreEntry(signature, header, Box::new(entry));
+        let hash = DhtOpHash::with_data_sync(&op);
+
+        let light = match op.to_light().await {
+            // Store the entry at its header instead
+            DhtOpLight::StoreEntry(h, e, _) if forge_basis => {
+                DhtOpLight::StoreEntry(h.clone(), e, h.into())
+            }
+            light => light,
+        };
+        let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+        let vlv = ValidationLimboValue {
+            status: ValidationLimboStatus::Pending,
+            pending_dependencies: PendingDependencies::new(),
+            basis: light.dht_basis().clone(),
+            op: light,
+            time_added: Timestamp::now(),
+            last_try: None,
+            num_tries: 0,
+            num_app_validations: 0,
+        };
+        integrate_single_data(op, &mut workspace.element_pending).unwrap();
+        workspace.validation_limbo.put(hash.clone(), vlv).unwrap();
+        env.guard()
+            .with_commit::<WorkspaceError, _, _>(|writer| workspace.flush_to_txn_ref(writer))
+            .unwrap();
+        hashes.push(hash);
+    }
+
+    run_workflow(&env, cell_network, |workspace, _| {
+        workspace.validation_profile = ValidationProfile::Permissive
+    })
+    .await;
+
+    let workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    // The genuine op passes
+    assert!(!workspace.integration_limbo.contains(&hashes[0]).unwrap());
+    // The one stored at a header hash is rejected
+    assert_eq!(
+        workspace
+            .integration_limbo
+            .get(&hashes[1])
+            .unwrap()
+            .unwrap()
+            .validation_status,
+        ValidationStatus::Rejected
+    );
+}
+
+/// Feed many malformed and edge case ops through sys validation, the way
+/// they could arrive over gossip or be produced by a broken author.
+/// None may panic or error and each must end with the outcome its case defines.
+#[tokio::test(threaded_scheduler)]
+async fn malformed_ops_yield_defined_outcomes() {
+    use super::{
+        types::{CheckLevel, Outcome, PendingDependencies},
+        validate_op, SysValidationWorkspace,
+    };
+    use crate::{
+        conductor::api::MockCellConductorApi,
+        core::sys_validate::{MAX_TAG_SIZE, SIGNATURE_BYTES},
+        fixt::DnaFileFixturator,
+        test_utils::test_network,
+    };
+    use futures::future::FutureExt;
+    use holo_hash::{hash_type::AnyDht, HashType, HoloHash};
+    use holochain_keystore::{KeystoreSender, Signature};
+    use holochain_p2p::event::HolochainP2pEvent;
+    use holochain_state::test_utils::test_cell_env;
+    use holochain_types::{
+        dht_op::DhtOp,
+        element::{GetElementResponse, SignedHeaderHashed, SignedHeaderHashedExt},
+        header::NewEntryHeader,
+        HeaderHashed, Timestamp,
+    };
+    use holochain_zome_types::{
+        header::{Create, CreateLink, Delete, EntryType, InitZomesComplete, Update, ZomeId},
+        Header,
+    };
+    use std::panic::AssertUnwindSafe;
+
+    /// The outcome a case must end with
+    #[derive(Debug)]
+    enum Expect {
+        /// Integrated as rejected, or dropped because it doesn't decode
+        Rejected,
+        /// Left in limbo waiting for a dependency
+        Held,
+        /// Passed sys validation
+        Passed,
+    }
+
+    /// Hashes are only length checked on construction
+    /// so a short one has to come from deserializing
+    fn truncate<T: HashType>(hash: HoloHash<T>) -> HoloHash<T> {
+        #[derive(serde::Serialize)]
+        struct RawHash<T> {
+            hash: Vec<u8>,
+            hash_type: T,
+        }
+        let raw = RawHash {
+            hash: hash.get_full_bytes()[..10].to_vec(),
+            hash_type: *hash.hash_type(),
+        };
+        holochain_serialized_bytes::decode(&holochain_serialized_bytes::encode(&raw).unwrap())
+            .unwrap()
+    }
+
+    async fn sign(keystore: &KeystoreSender, header: Header) -> (Signature, Header) {
+        let header = HeaderHashed::from_content_sync(header);
+        let (header, signature) = SignedHeaderHashed::new(keystore, header)
+            .await
+            .unwrap()
+            .into_header_and_signature();
+        (signature, header.into_content())
+    }
+
+    /// The op as it travels between peers
+    fn wire(op: DhtOp) -> Vec<u8> {
+        holochain_serialized_bytes::encode(&op).unwrap()
+    }
+
+    /// The op as a peer could send it,
+    /// with one of its hashes carrying another hash type's prefix
+    fn forge_prefix<T: HashType>(op: DhtOp, hash: &HoloHash<T>, prefix: &[u8]) -> Vec<u8> {
+        let mut bytes = wire(op);
+        let hash_bytes = holochain_serialized_bytes::encode(hash).unwrap();
+        let at = bytes
+            .windows(hash_bytes.len())
+            .position(|w| w == &hash_bytes[..])
+            .unwrap();
+        // The prefix is serialized after the hash's bytes
+        let from = hash.hash_type().get_prefix();
+        let at = at
+            + hash_bytes
+                .windows(from.len())
+                .rposition(|w| w == from)
+                .unwrap();
+        bytes[at..at + prefix.len()].copy_from_slice(prefix);
+        bytes
+    }
+
+    fn new_entry(header: Header) -> NewEntryHeader {
+        match header {
+            Header::Create(create) => NewEntryHeader::Create(create),
+            Header::Update(update) => NewEntryHeader::Update(update),
+            _ => unreachable!(),
+        }
+    }
+
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let keystore = holochain_state::test_utils::test_keystore();
+    let forged = || Signature(vec![0; SIGNATURE_BYTES]);
+
+    // Nobody holds any of the dependencies
+    let (_network, mut recv, cell_network) = test_network(None, None).await;
+    tokio::task::spawn(async move {
+        use tokio::stream::StreamExt;
+        while let Some(evt) = recv.next().await {
+            if let HolochainP2pEvent::Get { respond, .. } = evt {
+                let response = GetElementResponse::GetHeader(None).try_into().unwrap();
+                respond.respond(Ok(async move { Ok(response) }.boxed().into()));
+            }
+        }
+    });
+
+    let mut conductor_api = MockCellConductorApi::new();
+    conductor_api.expect_cell_id().return_const(fixt!(CellId));
+    conductor_api
+        .expect_sync_get_this_dna()
+        .return_const(Some(fixt!(DnaFile)));
+
+    const ROUNDS: usize = 20;
+    let mut header_hashes = HeaderHashFixturator::new(Unpredictable);
+    let mut entry_hashes = EntryHashFixturator::new(Unpredictable);
+    let mut app_entries = EntryFixturator::new(AppEntry);
+    let mut checked = 0;
+    for _ in 0..ROUNDS {
+        let author = fake_agent_pubkey_1();
+        let prev_header = header_hashes.next().unwrap();
+        let init = |header_seq, prev_header| InitZomesComplete {
+            author: author.clone(),
+            timestamp: Timestamp::now().into(),
+            header_seq,
+            prev_header,
+        };
+        let create = |entry_type, entry_hash| Create {
+            author: author.clone(),
+            timestamp: Timestamp::now().into(),
+            header_seq: 5,
+            prev_header: prev_header.clone(),
+            entry_type,
+            entry_hash,
+        };
+        let link = |base_address, target_address, tag| CreateLink {
+            author: author.clone(),
+            timestamp: Timestamp::now().into(),
+            header_seq: 5,
+            prev_header: prev_header.clone(),
+            base_address,
+            target_address,
+            zome_id: ZomeId::from(0),
+            tag,
+        };
+        let agent_entry = Entry::Agent(fake_agent_pubkey_2());
+
+        // Each case is validated as if it came from gossip
+        // and as if this node authored it
+        let mut cases: Vec<(&str, Vec<u8>, Expect, Expect)> = Vec::new();
+
+        // A well formed op for comparison.
+        // Nobody holds its previous header.
+        let (signature, header) = sign(&keystore, init(5, prev_header.clone()).into()).await;
+        cases.push((
+            "well formed",
+            wire(DhtOp::StoreElement(signature, header, None)),
+            Expect::Held,
+            Expect::Passed,
+        ));
+
+        // Signatures that don't belong to the header
+        let header: Header = init(5, prev_header.clone()).into();
+        cases.push((
+            "forged signature",
+            wire(DhtOp::StoreElement(forged(), header, None)),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+        let (signature, header) = sign(&keystore, init(5, prev_header.clone()).into()).await;
+        cases.push((
+            "truncated signature",
+            wire(DhtOp::StoreElement(
+                Signature(signature.0[..10].to_vec()),
+                header,
+                None,
+            )),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+
+        // Truncated hashes
+        let (signature, header) =
+            sign(&keystore, init(5, truncate(prev_header.clone())).into()).await;
+        cases.push((
+            "truncated previous header",
+            wire(DhtOp::RegisterAgentActivity(signature, header)),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+        let mut header = init(5, prev_header.clone());
+        header.author = truncate(author.clone());
+        cases.push((
+            "truncated author",
+            wire(DhtOp::StoreElement(forged(), header.into(), None)),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+        let (signature, header) = sign(
+            &keystore,
+            link(
+                truncate(entry_hashes.next().unwrap()),
+                entry_hashes.next().unwrap(),
+                LinkTag::new(""),
+            )
+            .into(),
+        )
+        .await;
+        cases.push((
+            "truncated link base",
+            wire(DhtOp::RegisterAddLink(
+                signature,
+                header.try_into().unwrap(),
+            )),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+
+        // Hashes of one type passed off as another.
+        // Every hash in a header is typed so these never decode.
+        let (signature, header) = sign(&keystore, init(5, prev_header.clone()).into()).await;
+        cases.push((
+            "entry prefix on previous header",
+            forge_prefix(
+                DhtOp::StoreElement(signature, header, None),
+                &prev_header,
+                AnyDht::Entry.get_prefix(),
+            ),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+        let base = entry_hashes.next().unwrap();
+        let (signature, header) = sign(
+            &keystore,
+            link(base.clone(), entry_hashes.next().unwrap(), LinkTag::new("")).into(),
+        )
+        .await;
+        cases.push((
+            "header prefix on link base",
+            forge_prefix(
+                DhtOp::RegisterAddLink(signature, header.try_into().unwrap()),
+                &base,
+                AnyDht::Header.get_prefix(),
+            ),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+        let deletes_address = header_hashes.next().unwrap();
+        let (signature, header) = sign(
+            &keystore,
+            Delete {
+                author: author.clone(),
+                timestamp: Timestamp::now().into(),
+                header_seq: 5,
+                prev_header: prev_header.clone(),
+                deletes_address: deletes_address.clone(),
+                deletes_entry_address: entry_hashes.next().unwrap(),
+            }
+            .into(),
+        )
+        .await;
+        cases.push((
+            "entry prefix on deleted header",
+            forge_prefix(
+                DhtOp::RegisterDeletedBy(signature, header.try_into().unwrap()),
+                &deletes_address,
+                AnyDht::Entry.get_prefix(),
+            ),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+
+        // Headers that don't match their entries
+        let (signature, header) = sign(
+            &keystore,
+            create(EntryType::AgentPubKey, entry_hashes.next().unwrap()).into(),
+        )
+        .await;
+        cases.push((
+            "entry hash mismatch",
+            wire(DhtOp::StoreEntry(
+                signature,
+                new_entry(header),
+                Box::new(agent_entry.clone()),
+            )),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+        let app_entry = app_entries.next().unwrap();
+        let (signature, header) = sign(
+            &keystore,
+            create(
+                EntryType::AgentPubKey,
+                EntryHash::with_data_sync(&app_entry),
+            )
+            .into(),
+        )
+        .await;
+        cases.push((
+            "entry type mismatch",
+            wire(DhtOp::StoreEntry(
+                signature,
+                new_entry(header),
+                Box::new(app_entry),
+            )),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+        let (signature, header) = sign(
+            &keystore,
+            Update {
+                author: author.clone(),
+                timestamp: Timestamp::now().into(),
+                header_seq: 5,
+                prev_header: prev_header.clone(),
+                original_header_address: header_hashes.next().unwrap(),
+                original_entry_address: entry_hashes.next().unwrap(),
+                entry_type: EntryType::AgentPubKey,
+                entry_hash: entry_hashes.next().unwrap(),
+            }
+            .into(),
+        )
+        .await;
+        cases.push((
+            "update entry hash mismatch",
+            wire(DhtOp::StoreEntry(
+                signature,
+                new_entry(header),
+                Box::new(agent_entry),
+            )),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+
+        // Broken chain structure and limits
+        let (signature, header) = sign(&keystore, init(0, prev_header.clone()).into()).await;
+        cases.push((
+            "root that isn't a dna",
+            wire(DhtOp::StoreElement(signature, header, None)),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+        let (signature, header) = sign(
+            &keystore,
+            link(
+                entry_hashes.next().unwrap(),
+                entry_hashes.next().unwrap(),
+                LinkTag::new(vec![0; MAX_TAG_SIZE + 1]),
+            )
+            .into(),
+        )
+        .await;
+        cases.push((
+            "oversized link tag",
+            wire(DhtOp::RegisterAddLink(
+                signature,
+                header.try_into().unwrap(),
+            )),
+            Expect::Rejected,
+            Expect::Rejected,
+        ));
+
+        for (case, bytes, from_gossip, from_author) in cases {
+            // Ops are validated as they decode off the wire
+            let op: DhtOp = match holochain_serialized_bytes::decode(&bytes) {
+                Ok(op) => op,
+                Err(_) => {
+                    for expect in vec![from_gossip, from_author] {
+                        assert!(
+                            matches!(expect, Expect::Rejected),
+                            "{} didn't decode but expected {:?}",
+                            case,
+                            expect
+                        );
+                        checked += 1;
+                    }
+                    continue;
+                }
+            };
+            let light = op.to_light().await;
+            for (authored, expect) in vec![(false, from_gossip), (true, from_author)] {
+                let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+                let result = AssertUnwindSafe(validate_op(
+                    &op,
+                    &light,
+                    &mut workspace,
+                    cell_network.clone(),
+                    &conductor_api,
+                    &mut PendingDependencies::new(),
+                    CheckLevel::Proof,
+                    authored,
+                ))
+                .catch_unwind()
+                .await;
+                let outcome = match result {
+                    Ok(Ok(outcome)) => outcome,
+                    Ok(Err(e)) => panic!("{} (authored: {}) errored: {:?}", case, authored, e),
+                    Err(_) => panic!("{} (authored: {}) panicked", case, authored),
+                };
+                match (&expect, &outcome) {
+                    (Expect::Rejected, Outcome::Rejected)
+                    | (Expect::Held, Outcome::AwaitingOpDep(_))
+                    | (Expect::Held, Outcome::MissingDhtDep(_))
+                    | (Expect::Passed, Outcome::Accepted)
+                    | (Expect::Passed, Outcome::SkipAppValidation) => (),
+                    _ => panic!(
+                        "{} (authored: {}) expected {:?} but got {:?}",
+                        case, authored, expect, outcome
+                    ),
+                }
+                checked += 1;
+            }
+        }
+    }
+    assert_eq!(checked, ROUNDS * 14 * 2);
+}
+
+/// A link base is typed as an entry hash so a peer can only send a
+/// [DnaHash] there by encoding its prefix. The op must fail to decode
+/// so it is dropped before it can reach the validation limbo.
+#[tokio::test(threaded_scheduler)]
+async fn incoming_link_with_a_dna_hash_base_never_decodes() {
+    use holochain_types::{
+        dht_op::DhtOp,
+        element::{SignedHeaderHashed, SignedHeaderHashedExt},
+        test_utils::fake_header_hash,
+        HeaderHashed, Timestamp,
+    };
+    use holochain_zome_types::{
+        header::{CreateLink, ZomeId},
+        Header,
+    };
+
+    let keystore = holochain_state::test_utils::test_keystore();
+    let link = CreateLink {
+        author: fake_agent_pubkey_1(),
+        timestamp: Timestamp::now().into(),
+        header_seq: 5,
+        prev_header: fake_header_hash(1),
+        base_address: EntryHash::from_raw_bytes(vec![0xdb; 36]),
+        target_address: EntryHash::from_raw_bytes(vec![0xdc; 36]),
+        zome_id: ZomeId::from(0),
+        tag: LinkTag::new(""),
+    };
+    let header = HeaderHashed::from_content_sync(Header::CreateLink(link.clone()));
+    let (_, signature) = SignedHeaderHashed::new(&keystore, header)
+        .await
+        .unwrap()
+        .into_header_and_signature();
+    let op = DhtOp::RegisterAddLink(signature, link);
+    let bytes = holochain_serialized_bytes::encode(&op).unwrap();
+    let decoded: DhtOp = holochain_serialized_bytes::decode(&bytes).unwrap();
+    assert_eq!(decoded, op);
+
+    // The base is the first entry hash in the header.
+    // Its prefix is encoded as 3 bytes of binary.
+    let entry_prefix = [0xc4, 0x03, 0x84, 0x21, 0x24];
+    let dna_prefix = [0xc4, 0x03, 0x84, 0x2d, 0x24];
+    let at = bytes
+        .windows(entry_prefix.len())
+        .position(|w| w == entry_prefix)
+        .unwrap();
+    let mut forged = bytes.clone();
+    forged[at..at + dna_prefix.len()].copy_from_slice(&dna_prefix);
+
+    let decoded: Result<DhtOp, _> = holochain_serialized_bytes::decode(&forged);
+    assert!(decoded.is_err());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn deep_pending_dependency_chains_are_parked() {
+    use crate::test_utils::test_network;
+    use holochain_state::test_utils::test_cell_env;
+    use holochain_types::{
+        element::SignedHeaderHashed, element::SignedHeaderHashedExt, HeaderHashed,
+    };
+    use holochain_zome_types::{
+        header::{Dna, InitZomesComplete},
+        Header,
+    };
+
+    const MAX_DEPTH: usize = 3;
+    const CHAIN_LEN: u32 = 6;
+
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let keystore = holochain_state::test_utils::test_keystore();
+
+    // A chain where every header is only held pending validation,
+    // so each op waits on the op before it.
+    // The chain starts at an op that has already passed sys validation.
+    let base = Timestamp::now().0;
+    let mut headers: Vec<Header> = vec![Dna {
+        author: fake_agent_pubkey_1(),
+        timestamp: Timestamp(base, 0).into(),
+        hash: fixt!(DnaHash),
+    }
+    .into()];
+    for seq in 1..=CHAIN_LEN {
+        let prev_header = HeaderHash::with_data_sync(headers.last().unwrap());
+        headers.push(
+            InitZomesComplete {
+                author: fake_agent_pubkey_1(),
+                timestamp: Timestamp(base + seq as i64, 0).into(),
+                header_seq: seq,
+                prev_header,
+            }
+            .into(),
+        );
+    }
+
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    let mut op_hashes = Vec::new();
+    for (seq, header) in headers.iter().cloned().enumerate() {
+        let header = HeaderHashed::from_content_sync(header);
+        let (header, signature) = SignedHeaderHashed::new(&keystore, header)
+            .await
+            .unwrap()
+            .into_header_and_signature();
+        let op = DhtOp::StoreElement(signature, header.into_content(), None);
+        let hash = DhtOpHash::with_data_sync(&op);
+        let status = if seq == 0 {
+            ValidationLimboStatus::SysValidated
+        } else {
+            ValidationLimboStatus::Pending
+        };
+        let vlv = ValidationLimboValue {
+            status,
+            pending_dependencies: PendingDependencies::new(),
+            basis: op.dht_basis().await,
+            op: op.to_light().await,
+            time_added: Timestamp::now(),
+            last_try: None,
+            num_tries: 0,
+            num_app_validations: 0,
+        };
+        integrate_single_data(op, &mut workspace.element_pending).unwrap();
+        workspace.validation_limbo.put(hash.clone(), vlv).unwrap();
+        op_hashes.push(hash);
+    }
+    env.guard()
+        .with_commit::<WorkspaceError, _, _>(|writer| workspace.flush_to_txn_ref(writer))
+        .unwrap();
+
+    let (_network, _recv, cell_network) = test_network(None, None).await;
+    run_workflow(&env, cell_network, |workspace, _| {
+        workspace.max_pending_dependency_depth = MAX_DEPTH
+    })
+    .await;
+
+    let workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    for (seq, hash) in op_hashes.iter().enumerate() {
+        let vlv = workspace.validation_limbo.get(hash).unwrap().unwrap();
+        // Each op is one deeper than the op it waits on
+        assert_eq!(vlv.pending_dependencies.depth, seq);
+        if seq <= MAX_DEPTH {
+            assert!(!matches!(
+                vlv.status,
+                ValidationLimboStatus::Pending | ValidationLimboStatus::AwaitingSysDeps(_)
+            ));
+        } else {
+            // Parked waiting on the previous header instead of
+            // being validated against an ever longer chain
+            let prev_header: AnyDhtHash = HeaderHash::with_data_sync(&headers[seq - 1]).into();
+            assert_eq!(
+                vlv.status,
+                ValidationLimboStatus::AwaitingSysDeps(vec![prev_header])
+            );
+            assert!(!vlv.pending_dependencies.pending_dependencies());
+        }
+    }
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn metrics_count_the_dependencies_a_run_checked() {
+    use super::metrics::DependencyCounters;
+    use crate::test_utils::test_network;
+    use futures::future::FutureExt;
+    use holochain_p2p::event::HolochainP2pEvent;
+    use holochain_state::test_utils::test_cell_env;
+    use holochain_types::{
+        element::{GetElementResponse, SignedHeaderHashed, SignedHeaderHashedExt},
+        test_utils::fake_header_hash,
+        HeaderHashed,
+    };
+    use holochain_zome_types::{
+        header::{Dna, InitZomesComplete},
+        Header,
+    };
+
+    observability::test_run().ok();
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let keystore = holochain_state::test_utils::test_keystore();
+    let sign = |header: Header| {
+        let keystore = keystore.clone();
+        async move {
+            let header = HeaderHashed::from_content_sync(header);
+            let (header, signature) = SignedHeaderHashed::new(&keystore, header)
+                .await
+                .unwrap()
+                .into_header_and_signature();
+            DhtOp::StoreElement(signature, header.into_content(), None)
+        }
+    };
+
+    // A chain start that has passed sys validation but is still
+    // only held pending validation
+    let dna: Header = Dna {
+        author: fake_agent_pubkey_1(),
+        timestamp: Timestamp::now().into(),
+        hash: fixt!(DnaHash),
+    }
+    .into();
+    let dna_op = sign(dna.clone()).await;
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    let vlv = ValidationLimboValue {
+        status: ValidationLimboStatus::SysValidated,
+        pending_dependencies: PendingDependencies::new(),
+        basis: dna_op.dht_basis().await,
+        op: dna_op.to_light().await,
+        time_added: Timestamp::now(),
+        last_try: None,
+        num_tries: 0,
+        num_app_validations: 0,
+    };
+    let dna_op_hash = DhtOpHash::with_data_sync(&dna_op);
+    integrate_single_data(dna_op, &mut workspace.element_pending).unwrap();
+    workspace.validation_limbo.put(dna_op_hash, vlv).unwrap();
+    env.guard()
+        .with_commit::<WorkspaceError, _, _>(|writer| workspace.flush_to_txn_ref(writer))
+        .unwrap();
+
+    // An op on top of the chain start and an op whose previous header
+    // isn't held by anyone
+    let on_dna = InitZomesComplete {
+        author: fake_agent_pubkey_1(),
+        timestamp: Timestamp::now().into(),
+        header_seq: 1,
+        prev_header: HeaderHash::with_data_sync(&dna),
+    };
+    let orphan = InitZomesComplete {
+        author: fake_agent_pubkey_1(),
+        timestamp: Timestamp::now().into(),
+        header_seq: 5,
+        prev_header: fake_header_hash(1),
+    };
+    put_in_limbo(&env, sign(on_dna.into()).await, false).await;
+    put_in_limbo(&env, sign(orphan.into()).await, false).await;
+
+    let (_network, mut recv, cell_network) = test_network(None, None).await;
+    tokio::task::spawn(async move {
+        use tokio::stream::StreamExt;
+        while let Some(evt) = recv.next().await {
+            if let HolochainP2pEvent::Get { respond, .. } = evt {
+                let response = GetElementResponse::GetHeader(None).try_into().unwrap();
+                respond.respond(Ok(async move { Ok(response) }.boxed().into()));
+            }
+        }
+    });
+
+    let counters = DependencyCounters::default();
+    run_workflow(&env, cell_network, |workspace, _| {
+        workspace.dependency_counters = counters.clone()
+    })
+    .await;
+
+    let mut workspace = SysValidationWorkspace::new(env.clone().into()).unwrap();
+    workspace.dependency_counters = counters;
+    let metrics = workspace.metrics().unwrap();
+    assert_eq!(metrics.validation_limbo + metrics.integration_limbo, 3);
+    assert_eq!(metrics.integrated, 0);
+    assert!(metrics.pending_validation_deps > 0);
+    assert!(metrics.not_held > 0);
+
+    let breakdown = workspace.limbo_breakdown().unwrap();
+    assert_eq!(breakdown.pending, 0);
+    assert_eq!(breakdown.awaiting_sys_deps, 1);
+    assert!(breakdown.average_time_in_limbo.is_some());
 }
@@ -17,7 +17,13 @@ pub(super) enum Outcome {
     /// Stays in limbo because a dependency could not
     /// be found currently on the DHT.
     /// Note this is not proof it doesn't exist.
-    MissingDhtDep,
+    MissingDhtDep(AnyDhtHash),
+    /// Stays in limbo to be validated again
+    /// because the validation profile pends this failure
+    Pending,
+    /// Stays in limbo because the chain of pending ops it depends on
+    /// is deeper than allowed. Holds the header the deepest chain starts at.
+    DepthExceeded(Option<AnyDhtHash>),
     /// Moves to integration with status rejected
     Rejected,
 }
@@ -81,6 +87,9 @@ pub enum DepType {
 pub enum CheckLevel {
     /// Selected dependencies must be validated by this agent
     Proof,
+    /// Like [CheckLevel::Claim] but a dependency already retrieved
+    /// in this validation pass is reused instead of retrieved again
+    Cached,
     /// Selected dependencies must be validated by another authority
     Claim,
 }
@@ -140,6 +149,10 @@ pub struct PendingDependencies {
     /// PendingDependencies that hadn't finished validation at the
     /// time we used them to validate this op.
     pub pending: Vec<DepType>,
+    /// How long the chain of pending ops this op waits on is.
+    /// Zero if it's not waiting on any.
+    #[serde(default)]
+    pub depth: usize,
 }
 
 impl PendingDependencies {
@@ -147,6 +160,7 @@ impl PendingDependencies {
     pub fn new() -> Self {
         Self {
             pending: Vec::new(),
+            depth: 0,
         }
     }
 
@@ -8,6 +8,7 @@ use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitHostAccess;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
+use crate::core::ribosome::guest_callback::resolve_conflict::ResolveConflictHostAccess;
 use crate::core::ribosome::guest_callback::validate::ValidateHostAccess;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate_link_add::ValidateCreateLinkHostAccess;
@@ -42,7 +43,6 @@ use holochain_zome_types::header::HeaderHashes;
 use holochain_zome_types::link::LinkTag;
 use holochain_zome_types::ExternInput;
 use rand::seq::IteratorRandom;
-use rand::thread_rng;
 use rand::Rng;
 use std::collections::BTreeMap;
 use std::sync::Arc;
@@ -91,7 +91,7 @@ fixturator!(
     DnaWasm;
     // note that an empty wasm will not compile
     curve Empty DnaWasm { code: Arc::new(vec![]) };
-    curve Unpredictable TestWasm::iter().choose(&mut thread_rng()).unwrap().into();
+    curve Unpredictable TestWasm::iter().choose(&mut rng()).unwrap().into();
     curve Predictable TestWasm::iter().cycle().nth(self.0.index).unwrap().into();
 );
 
@@ -99,7 +99,7 @@ fixturator!(
     Wasms;
     curve Empty BTreeMap::new();
     curve Unpredictable {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         let number_of_wasms = rng.gen_range(0, 5);
 
         let mut wasms: Wasms = BTreeMap::new();
@@ -215,7 +215,7 @@ fixturator!(
     HeaderHashes,
     vec![].into(),
     {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         let number_of_hashes = rng.gen_range(0, 5);
 
         let mut hashes: Vec<HeaderHash> = vec![];
@@ -323,6 +323,11 @@ fixturator!(
     constructor fn new(CallZomeWorkspaceLock, KeystoreSender, HolochainP2pCell);
 );
 
+fixturator!(
+    ResolveConflictHostAccess;
+    constructor fn new();
+);
+
 fixturator!(
     ValidateInvocation;
     constructor fn new(ZomeName, Entry);
@@ -340,7 +345,7 @@ fixturator!(
 
 fixturator!(
     ValidateHostAccess;
-    constructor fn new();
+    constructor fn new(CallZomeWorkspaceLock, HolochainP2pCell);
 );
 
 fixturator!(
@@ -363,6 +368,7 @@ fixturator!(
         MigrateAgent(MigrateAgentHostAccess)
         ValidationPackage(ValidationPackageHostAccess)
         PostCommit(PostCommitHostAccess)
+        ResolveConflict(ResolveConflictHostAccess)
     ];
 );
 
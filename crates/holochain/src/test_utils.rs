@@ -16,6 +16,7 @@ use holochain_serialized_bytes::{SerializedBytes, UnsafeBytes};
 use holochain_state::test_utils::{test_conductor_env, test_wasm_env, TestEnvironment};
 use holochain_types::{
     app::InstalledCell,
+    dna::{DnaDef, DnaFile},
     element::{SignedHeaderHashed, SignedHeaderHashedExt},
     test_utils::fake_header_hash,
     Entry, EntryHashed, HeaderHashed, Timestamp,
@@ -177,6 +178,44 @@ pub async fn setup_app(
     (tmpdir, RealAppInterfaceApi::new(conductor_handle), handle)
 }
 
+/// A DnaFile with one zome for each of these wasms
+pub async fn test_dna_file(name: &str, uuid: &str, wasms: Vec<TestWasm>) -> DnaFile {
+    DnaFile::new(
+        wasms
+            .iter()
+            .fold(DnaDef::builder().name(name).uuid(uuid), |dna, wasm| {
+                dna.add_zome(*wasm)
+            })
+            .build()
+            .unwrap(),
+        wasms.into_iter().map(Into::into),
+    )
+    .await
+    .unwrap()
+}
+
+/// A MockDnaStore that serves these DnaFiles by their hash
+pub fn mock_dna_store(dna_files: Vec<DnaFile>) -> MockDnaStore {
+    let mut dna_store = MockDnaStore::new();
+    dna_store.expect_get().returning(move |hash| {
+        dna_files
+            .iter()
+            .find(|dna_file| dna_file.dna_hash() == hash)
+            .cloned()
+    });
+    dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+    dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+    dna_store.expect_get_entry_def().return_const(None);
+    dna_store
+}
+
+/// Shut the conductor down and wait for its tasks to finish
+pub async fn shutdown_conductor(handle: ConductorHandle) {
+    let shutdown = handle.take_shutdown_handle().await.unwrap();
+    handle.shutdown().await;
+    shutdown.await.unwrap();
+}
+
 pub fn warm_wasm_tests() {
     // If HC_WASM_CACHE_PATH is set warm the cache
     if let Some(_path) = std::env::var_os("HC_WASM_CACHE_PATH") {
@@ -213,7 +213,7 @@ pub async fn get(
 
     let input = GetInput::new((
         entry_hash.clone().into(),
-        holochain_zome_types::entry::GetOptions,
+        holochain_zome_types::entry::GetOptions::default(),
     ));
 
     let output = {
@@ -244,7 +244,8 @@ pub async fn get_details<'env>(
 
     let input = GetDetailsInput::new((
         entry_hash.clone().into(),
-        holochain_zome_types::entry::GetOptions,
+        holochain_zome_types::entry::GetOptions::default(),
+        false,
     ));
 
     let output = {
@@ -57,6 +57,10 @@ async fn ser_regression_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::SerRegression.into()].into(),
+            reject_self_links: false,
+            max_entry_size: None,
+            update_conflict_policy: Default::default(),
+            sensitive_fns: Default::default(),
         },
         vec![TestWasm::SerRegression.into()],
     )
@@ -139,6 +139,10 @@ async fn speed_test(n: Option<usize>) -> TestEnvironment {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Anchor.into()].into(),
+            reject_self_links: false,
+            max_entry_size: None,
+            update_conflict_policy: Default::default(),
+            sensitive_fns: Default::default(),
         },
         vec![TestWasm::Anchor.into()],
     )
@@ -0,0 +1,127 @@
+use hdk3::prelude::*;
+use holochain::conductor::dna_store::MockDnaStore;
+use holochain::test_utils::setup_app;
+use holochain_types::app::InstalledCell;
+use holochain_types::cell::CellId;
+use holochain_types::dna::{DnaDef, DnaFile, UpdateConflictPolicy};
+use holochain_types::observability;
+use holochain_types::test_utils::{fake_agent_pubkey_1, fake_agent_pubkey_2};
+use holochain_wasm_test_utils::TestWasm;
+
+// simple replica of the internal type for the TestWasm::Crud entry
+#[derive(Clone, Copy, Serialize, Deserialize, SerializedBytes, Debug, PartialEq)]
+struct CounTree(u32);
+
+#[tokio::test(threaded_scheduler)]
+async fn last_write_wins_on_every_node() {
+    observability::test_run().ok();
+
+    let dna_file = DnaFile::new(
+        DnaDef::builder()
+            .name("update_conflicts_test")
+            .add_zome(TestWasm::Crud)
+            .update_conflict_policy(UpdateConflictPolicy::LastWriteWins)
+            .build()
+            .unwrap(),
+        vec![TestWasm::Crud.into()],
+    )
+    .await
+    .unwrap();
+
+    let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), fake_agent_pubkey_1());
+    let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+    let bob_cell_id = CellId::new(dna_file.dna_hash().to_owned(), fake_agent_pubkey_2());
+    let bob_installed_cell = InstalledCell::new(bob_cell_id.clone(), "bob_handle".into());
+
+    let mut dna_store = MockDnaStore::new();
+    dna_store.expect_get().return_const(Some(dna_file.clone()));
+    dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+    dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+    dna_store.expect_get_entry_def().return_const(None);
+
+    let (_tmpdir, _app_api, handle) = setup_app(
+        vec![(
+            "conflict app",
+            vec![(alice_installed_cell, None), (bob_installed_cell, None)],
+        )],
+        dna_store,
+    )
+    .await;
+
+    let zero_hash: EntryHash = handle
+        .call_zome_fn(
+            alice_cell_id.clone(),
+            TestWasm::Crud,
+            "entry_hash",
+            CounTree(0),
+        )
+        .await
+        .unwrap();
+    let zero: HeaderHash = handle
+        .call_zome_fn(alice_cell_id.clone(), TestWasm::Crud, "new", ())
+        .await
+        .unwrap();
+
+    // Give publish time to finish
+    tokio::time::delay_for(std::time::Duration::from_secs(1)).await;
+
+    // Alice and Bob both update the zero entry
+    let one_a: HeaderHash = handle
+        .call_zome_fn(alice_cell_id.clone(), TestWasm::Crud, "inc", zero.clone())
+        .await
+        .unwrap();
+    let one_b: HeaderHash = handle
+        .call_zome_fn(bob_cell_id.clone(), TestWasm::Crud, "inc", zero)
+        .await
+        .unwrap();
+
+    // Give publish time to finish
+    tokio::time::delay_for(std::time::Duration::from_secs(1)).await;
+
+    let latest = |output: GetOutput| output.into_inner().expect("no element");
+    let alice_latest = latest(
+        handle
+            .call_zome_fn(
+                alice_cell_id.clone(),
+                TestWasm::Crud,
+                "latest_element",
+                zero_hash.clone(),
+            )
+            .await
+            .unwrap(),
+    );
+    let bob_latest = latest(
+        handle
+            .call_zome_fn(bob_cell_id, TestWasm::Crud, "latest_element", zero_hash)
+            .await
+            .unwrap(),
+    );
+
+    // Both nodes choose the same winner
+    assert_eq!(alice_latest, bob_latest);
+
+    // Which is the last write
+    let mut heads = Vec::new();
+    for header_hash in vec![one_a, one_b] {
+        heads.push(latest(
+            handle
+                .call_zome_fn(
+                    alice_cell_id.clone(),
+                    TestWasm::Crud,
+                    "header_element",
+                    header_hash,
+                )
+                .await
+                .unwrap(),
+        ));
+    }
+    let last_write = heads
+        .into_iter()
+        .max_by_key(|head| (head.header().timestamp(), head.header_address().clone()))
+        .unwrap();
+    assert_eq!(alice_latest, last_write);
+
+    let shutdown = handle.take_shutdown_handle().await.unwrap();
+    handle.shutdown().await;
+    shutdown.await.unwrap();
+}
@@ -69,6 +69,7 @@ fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
         }]),
         environment_path: environment_path.into(),
         network: None,
+        network_breaker: None,
         signing_service_uri: None,
         encryption_service_uri: None,
         decryption_service_uri: None,
@@ -77,6 +78,24 @@ fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
             passphrase: "password".into(),
         }),
         use_dangerous_test_keystore: true,
+        author_is_authority: false,
+        max_pending_dependency_depth: None,
+        max_cached_modules: None,
+        source_chain_read_cache: None,
+        max_blocking_host_fns: None,
+        parallel_callbacks: None,
+        cell_map_size: None,
+        entry_chunk_threshold: None,
+        held_op_types: None,
+        zome_call_limit: None,
+        zome_call_timeout_secs: None,
+        host_fn_budget: None,
+        cap_nonces: None,
+        workflow_runtimes: None,
+        gossip_request_policy: None,
+        validation_profiles: None,
+        sys_validation_dep_cache: None,
+        read_only_cells: None,
     }
 }
 
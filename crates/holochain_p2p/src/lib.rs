@@ -4,8 +4,12 @@
 use holo_hash::*;
 use holochain_keystore::*;
 use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::agent_info::PeerInfo;
 use holochain_zome_types::zome::FunctionName;
-use holochain_zome_types::{capability::CapSecret, zome::ZomeName};
+use holochain_zome_types::{
+    capability::{CapSecret, SignedCapNonce},
+    zome::ZomeName,
+};
 use std::sync::Arc;
 
 mod types;
@@ -39,6 +43,29 @@ pub trait HolochainP2pCellT {
     /// If a cell is deactivated, we'll need to \"leave\" the network module as well.
     async fn leave(&mut self) -> actor::HolochainP2pResult<()>;
 
+    /// Opt this cell's agent in or out of showing up in other agents' `get_peers`.
+    async fn set_discoverable(&mut self, discoverable: bool) -> actor::HolochainP2pResult<()>;
+
+    /// Announce the arc this cell's agent holds.
+    async fn set_arc(&mut self, dht_arc: dht_arc::DhtArc) -> actor::HolochainP2pResult<()>;
+
+    /// Get the discoverable agents on this dna, most recently seen first.
+    async fn get_peers(&mut self) -> actor::HolochainP2pResult<Vec<PeerInfo>>;
+
+    /// Is `agent` known to hold an arc covering `basis` on this dna.
+    async fn is_authority(
+        &mut self,
+        agent: AgentPubKey,
+        basis: AnyDhtHash,
+    ) -> actor::HolochainP2pResult<bool>;
+
+    /// Get the other agents known to hold an arc covering `basis` on this dna,
+    /// most recently seen first.
+    async fn get_authorities(
+        &mut self,
+        basis: AnyDhtHash,
+    ) -> actor::HolochainP2pResult<Vec<AgentPubKey>>;
+
     /// Invoke a zome function on a remote node (if you have been granted the capability).
     async fn call_remote(
         &mut self,
@@ -46,6 +73,7 @@ pub trait HolochainP2pCellT {
         zome_name: ZomeName,
         fn_name: FunctionName,
         cap: Option<CapSecret>,
+        nonce: SignedCapNonce,
         request: SerializedBytes,
     ) -> actor::HolochainP2pResult<SerializedBytes>;
 
@@ -83,6 +111,27 @@ pub trait HolochainP2pCellT {
         options: actor::GetLinksOptions,
     ) -> actor::HolochainP2pResult<Vec<GetLinksResponse>>;
 
+    /// Ask the agents covering a basis which ops they hold in an arc.
+    async fn fetch_op_hashes(
+        &mut self,
+        basis: holo_hash::AnyDhtHash,
+        dht_arc: dht_arc::DhtArc,
+        timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<Vec<(AgentPubKey, Vec<holo_hash::DhtOpHash>)>>;
+
+    /// Fetch the content of ops from an agent holding them.
+    async fn fetch_op_data(
+        &mut self,
+        to_agent: AgentPubKey,
+        op_hashes: Vec<holo_hash::DhtOpHash>,
+    ) -> actor::HolochainP2pResult<
+        Vec<(
+            holo_hash::AnyDhtHash,
+            holo_hash::DhtOpHash,
+            holochain_types::dht_op::DhtOp,
+        )>,
+    >;
+
     /// Send a validation receipt to a remote node.
     async fn send_validation_receipt(
         &mut self,
@@ -93,6 +142,9 @@ pub trait HolochainP2pCellT {
 
 /// A wrapper around HolochainP2pSender that partially applies the dna_hash / agent_pub_key.
 /// I.e. a sender that is tied to a specific cell.
+///
+/// The sender and hashes are all refcounted so a clone never allocates.
+/// Host functions clone it on every call.
 #[derive(Clone)]
 pub struct HolochainP2pCell {
     sender: ghost_actor::GhostSender<actor::HolochainP2p>,
@@ -126,6 +178,57 @@ impl HolochainP2pCellT for HolochainP2pCell {
             .await
     }
 
+    /// Opt this cell's agent in or out of showing up in other agents' `get_peers`.
+    async fn set_discoverable(&mut self, discoverable: bool) -> actor::HolochainP2pResult<()> {
+        self.sender
+            .set_discoverable(
+                (*self.dna_hash).clone(),
+                (*self.from_agent).clone(),
+                discoverable,
+            )
+            .await
+    }
+
+    /// Announce the arc this cell's agent holds.
+    async fn set_arc(&mut self, dht_arc: dht_arc::DhtArc) -> actor::HolochainP2pResult<()> {
+        self.sender
+            .set_arc(
+                (*self.dna_hash).clone(),
+                (*self.from_agent).clone(),
+                dht_arc,
+            )
+            .await
+    }
+
+    /// Get the discoverable agents on this dna, most recently seen first.
+    async fn get_peers(&mut self) -> actor::HolochainP2pResult<Vec<PeerInfo>> {
+        self.sender
+            .get_peers((*self.dna_hash).clone(), (*self.from_agent).clone())
+            .await
+    }
+
+    /// Is `agent` known to hold an arc covering `basis` on this dna.
+    async fn is_authority(
+        &mut self,
+        agent: AgentPubKey,
+        basis: AnyDhtHash,
+    ) -> actor::HolochainP2pResult<bool> {
+        self.sender
+            .is_authority((*self.dna_hash).clone(), agent, basis)
+            .await
+    }
+
+    /// Get the other agents known to hold an arc covering `basis` on this dna,
+    /// most recently seen first.
+    async fn get_authorities(
+        &mut self,
+        basis: AnyDhtHash,
+    ) -> actor::HolochainP2pResult<Vec<AgentPubKey>> {
+        self.sender
+            .get_authorities((*self.dna_hash).clone(), (*self.from_agent).clone(), basis)
+            .await
+    }
+
     /// Invoke a zome function on a remote node (if you have been granted the capability).
     async fn call_remote(
         &mut self,
@@ -133,6 +236,7 @@ impl HolochainP2pCellT for HolochainP2pCell {
         zome_name: ZomeName,
         fn_name: FunctionName,
         cap: Option<CapSecret>,
+        nonce: SignedCapNonce,
         request: SerializedBytes,
     ) -> actor::HolochainP2pResult<SerializedBytes> {
         self.sender
@@ -143,6 +247,7 @@ impl HolochainP2pCellT for HolochainP2pCell {
                 zome_name,
                 fn_name,
                 cap,
+                nonce,
                 request,
             )
             .await
@@ -227,6 +332,46 @@ impl HolochainP2pCellT for HolochainP2pCell {
             .await
     }
 
+    /// Ask the agents covering a basis which ops they hold in an arc.
+    async fn fetch_op_hashes(
+        &mut self,
+        basis: holo_hash::AnyDhtHash,
+        dht_arc: dht_arc::DhtArc,
+        timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<Vec<(AgentPubKey, Vec<holo_hash::DhtOpHash>)>> {
+        self.sender
+            .fetch_op_hashes(
+                (*self.dna_hash).clone(),
+                (*self.from_agent).clone(),
+                basis,
+                dht_arc,
+                timeout_ms,
+            )
+            .await
+    }
+
+    /// Fetch the content of ops from an agent holding them.
+    async fn fetch_op_data(
+        &mut self,
+        to_agent: AgentPubKey,
+        op_hashes: Vec<holo_hash::DhtOpHash>,
+    ) -> actor::HolochainP2pResult<
+        Vec<(
+            holo_hash::AnyDhtHash,
+            holo_hash::DhtOpHash,
+            holochain_types::dht_op::DhtOp,
+        )>,
+    > {
+        self.sender
+            .fetch_op_data(
+                (*self.dna_hash).clone(),
+                (*self.from_agent).clone(),
+                to_agent,
+                op_hashes,
+            )
+            .await
+    }
+
     /// Send a validation receipt to a remote node.
     async fn send_validation_receipt(
         &mut self,
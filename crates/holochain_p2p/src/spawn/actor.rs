@@ -42,6 +42,7 @@ impl HolochainP2pActor {
         zome_name: ZomeName,
         fn_name: FunctionName,
         cap: Option<CapSecret>,
+        nonce: SignedCapNonce,
         data: Vec<u8>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
         let data: SerializedBytes = UnsafeBytes::from(data).into();
@@ -49,7 +50,7 @@ impl HolochainP2pActor {
         Ok(async move {
             let res = evt_sender
                 .call_remote(
-                    dna_hash, to_agent, from_agent, zome_name, fn_name, cap, data,
+                    dna_hash, to_agent, from_agent, zome_name, fn_name, cap, nonce, data,
                 )
                 .await;
             res.map_err(kitsune_p2p::KitsuneP2pError::from)
@@ -122,6 +123,53 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving an incoming request for the ops we hold in an arc
+    fn handle_incoming_fetch_op_hashes(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        dht_arc: dht_arc::DhtArc,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            // every op in the arc, however long ago it was integrated
+            let res = evt_sender
+                .fetch_op_hashes_for_constraints(
+                    dna_hash,
+                    to_agent,
+                    dht_arc,
+                    Timestamp(i64::MIN, 0),
+                    Timestamp(i64::MAX, 0),
+                )
+                .await;
+            res.and_then(|r| Ok(SerializedBytes::try_from(crate::wire::WireOpHashes(r))?))
+                .map_err(kitsune_p2p::KitsuneP2pError::from)
+                .map(|res| UnsafeBytes::from(res).into())
+        }
+        .boxed()
+        .into())
+    }
+
+    /// receiving an incoming request for the content of ops we hold
+    fn handle_incoming_fetch_op_data(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        op_hashes: Vec<holo_hash::DhtOpHash>,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            let res = evt_sender
+                .fetch_op_hash_data(dna_hash, to_agent, op_hashes)
+                .await;
+            res.and_then(|r| Ok(SerializedBytes::try_from(crate::wire::WireOpData(r))?))
+                .map_err(kitsune_p2p::KitsuneP2pError::from)
+                .map(|res| UnsafeBytes::from(res).into())
+        }
+        .boxed()
+        .into())
+    }
+
     /// receiving an incoming publish from a remote node
     fn handle_incoming_publish(
         &mut self,
@@ -195,9 +243,10 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 zome_name,
                 fn_name,
                 cap,
+                nonce,
                 data,
             } => self.handle_incoming_call_remote(
-                space, to_agent, from_agent, zome_name, fn_name, cap, data,
+                space, to_agent, from_agent, zome_name, fn_name, cap, nonce, data,
             ),
             crate::wire::WireMessage::Get { dht_hash, options } => {
                 self.handle_incoming_get(space, to_agent, dht_hash, options)
@@ -208,6 +257,17 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::GetLinks { link_key, options } => {
                 self.handle_incoming_get_links(space, to_agent, link_key, options)
             }
+            crate::wire::WireMessage::FetchOpHashes {
+                center_loc,
+                half_length,
+            } => self.handle_incoming_fetch_op_hashes(
+                space,
+                to_agent,
+                dht_arc::DhtArc::new(center_loc, half_length),
+            ),
+            crate::wire::WireMessage::FetchOpData { op_hashes } => {
+                self.handle_incoming_fetch_op_data(space, to_agent, op_hashes)
+            }
             // holochain_p2p never publishes via request
             // these only occur on broadcasts
             crate::wire::WireMessage::Publish { .. } => {
@@ -241,6 +301,8 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             | crate::wire::WireMessage::Get { .. }
             | crate::wire::WireMessage::GetMeta { .. }
             | crate::wire::WireMessage::GetLinks { .. }
+            | crate::wire::WireMessage::FetchOpHashes { .. }
+            | crate::wire::WireMessage::FetchOpData { .. }
             | crate::wire::WireMessage::ValidationReceipt { .. } => {
                 Err(HolochainP2pError::invalid_p2p_message(
                     "invalid call type message in a notify".to_string(),
@@ -405,14 +467,15 @@ impl HolochainP2pHandler for HolochainP2pActor {
         zome_name: ZomeName,
         fn_name: FunctionName,
         cap: Option<CapSecret>,
+        nonce: SignedCapNonce,
         request: SerializedBytes,
     ) -> HolochainP2pHandlerResult<SerializedBytes> {
         let space = dna_hash.into_kitsune();
         let to_agent = to_agent.into_kitsune();
         let from_agent = from_agent.into_kitsune();
 
-        let req =
-            crate::wire::WireMessage::call_remote(zome_name, fn_name, cap, request).encode()?;
+        let req = crate::wire::WireMessage::call_remote(zome_name, fn_name, cap, nonce, request)
+            .encode()?;
 
         let kitsune_p2p = self.kitsune_p2p.clone();
         Ok(async move {
@@ -483,6 +546,20 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let payload = crate::wire::WireMessage::get(dht_hash, r_options).encode()?;
 
         let kitsune_p2p = self.kitsune_p2p.clone();
+        if let Some(to_agent) = options.target_agent {
+            let to_agent = to_agent.into_kitsune();
+            return Ok(async move {
+                let response = kitsune_p2p
+                    .rpc_single(space, to_agent, from_agent, payload)
+                    .instrument(tracing::debug_span!("rpc_single"))
+                    .await?;
+                Ok(vec![
+                    SerializedBytes::from(UnsafeBytes::from(response)).try_into()?
+                ])
+            }
+            .boxed()
+            .into());
+        }
         Ok(async move {
             let result = kitsune_p2p
                 .rpc_multi(kitsune_p2p::actor::RpcMulti {
@@ -595,6 +672,187 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .into())
     }
 
+    fn handle_set_discoverable(
+        &mut self,
+        dna_hash: DnaHash,
+        agent_pub_key: AgentPubKey,
+        discoverable: bool,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.into_kitsune();
+        let agent = agent_pub_key.into_kitsune();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            Ok(kitsune_p2p
+                .set_discoverable(space, agent, discoverable)
+                .await?)
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_set_arc(
+        &mut self,
+        dna_hash: DnaHash,
+        agent_pub_key: AgentPubKey,
+        dht_arc: kitsune_p2p::dht_arc::DhtArc,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.into_kitsune();
+        let agent = agent_pub_key.into_kitsune();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(
+            async move { Ok(kitsune_p2p.set_arc(space, agent, dht_arc).await?) }
+                .boxed()
+                .into(),
+        )
+    }
+
+    fn handle_get_peers(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+    ) -> HolochainP2pHandlerResult<Vec<holochain_zome_types::agent_info::PeerInfo>> {
+        let space = dna_hash.into_kitsune();
+        let from_agent = from_agent.into_kitsune();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            let peers = kitsune_p2p.list_peers(space, from_agent).await?;
+            Ok(peers
+                .into_iter()
+                .map(|peer| {
+                    let ms = peer.last_seen_utc_epoch_ms;
+                    holochain_zome_types::agent_info::PeerInfo {
+                        agent_pub_key: AgentPubKey::from_kitsune(&peer.agent),
+                        last_seen: Timestamp(
+                            ms.div_euclid(1000),
+                            (ms.rem_euclid(1000) * 1_000_000) as u32,
+                        )
+                        .into(),
+                    }
+                })
+                .collect())
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_is_authority(
+        &mut self,
+        dna_hash: DnaHash,
+        agent: AgentPubKey,
+        basis: AnyDhtHash,
+    ) -> HolochainP2pHandlerResult<bool> {
+        let space = dna_hash.into_kitsune();
+        let agent = agent.into_kitsune();
+        let basis = basis.to_kitsune();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(
+            async move { Ok(kitsune_p2p.is_authority(space, agent, basis).await?) }
+                .boxed()
+                .into(),
+        )
+    }
+
+    fn handle_get_authorities(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        basis: AnyDhtHash,
+    ) -> HolochainP2pHandlerResult<Vec<AgentPubKey>> {
+        let space = dna_hash.into_kitsune();
+        let from_agent = from_agent.into_kitsune();
+        let basis = basis.to_kitsune();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            let authorities = kitsune_p2p
+                .list_authorities(space, from_agent, basis)
+                .await?;
+            Ok(authorities.iter().map(AgentPubKey::from_kitsune).collect())
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_fetch_op_hashes(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        basis: holo_hash::AnyDhtHash,
+        dht_arc: dht_arc::DhtArc,
+        timeout_ms: Option<u64>,
+    ) -> HolochainP2pHandlerResult<Vec<(AgentPubKey, Vec<holo_hash::DhtOpHash>)>> {
+        let space = dna_hash.into_kitsune();
+        let from_agent = from_agent.into_kitsune();
+        let basis = basis.to_kitsune();
+
+        let payload = crate::wire::WireMessage::fetch_op_hashes(dht_arc).encode()?;
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            let result = kitsune_p2p
+                .rpc_multi(kitsune_p2p::actor::RpcMulti {
+                    space,
+                    from_agent,
+                    basis,
+                    remote_agent_count: None,
+                    timeout_ms,
+                    // every holder counts, don't stop at the first
+                    as_race: false,
+                    race_timeout_ms: None,
+                    payload,
+                })
+                .await?;
+
+            let mut out = Vec::new();
+            for item in result {
+                let kitsune_p2p::actor::RpcMultiResponse { agent, response } = item;
+                let crate::wire::WireOpHashes(op_hashes) =
+                    SerializedBytes::from(UnsafeBytes::from(response)).try_into()?;
+                out.push((AgentPubKey::from_kitsune(&agent), op_hashes));
+            }
+
+            Ok(out)
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_fetch_op_data(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        to_agent: AgentPubKey,
+        op_hashes: Vec<holo_hash::DhtOpHash>,
+    ) -> HolochainP2pHandlerResult<
+        Vec<(
+            holo_hash::AnyDhtHash,
+            holo_hash::DhtOpHash,
+            holochain_types::dht_op::DhtOp,
+        )>,
+    > {
+        let space = dna_hash.into_kitsune();
+        let to_agent = to_agent.into_kitsune();
+        let from_agent = from_agent.into_kitsune();
+
+        let req = crate::wire::WireMessage::fetch_op_data(op_hashes).encode()?;
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            let response = kitsune_p2p
+                .rpc_single(space, to_agent, from_agent, req)
+                .await?;
+            let crate::wire::WireOpData(ops) =
+                SerializedBytes::from(UnsafeBytes::from(response)).try_into()?;
+            Ok(ops)
+        }
+        .boxed()
+        .into())
+    }
+
     fn handle_send_validation_receipt(
         &mut self,
         dna_hash: DnaHash,
@@ -98,6 +98,11 @@ mod tests {
                 "".into(),
                 "".into(),
                 None,
+                SignedCapNonce {
+                    nonce: [0; 32].into(),
+                    expires_at: holochain_zome_types::timestamp::Timestamp(0, 0),
+                    signature: fixt!(Signature),
+                },
                 UnsafeBytes::from(b"yippo".to_vec()).into(),
             )
             .await
@@ -44,6 +44,11 @@ pub struct GetOptions {
     /// Set to `None` for a default "best-effort" race.
     pub race_timeout_ms: Option<u64>,
 
+    /// [Network]
+    /// Ask only this agent instead of the neighborhood of the basis.
+    /// Set to `None` to let the network choose.
+    pub target_agent: Option<AgentPubKey>,
+
     /// [Remote]
     /// Whether the remote-end should follow redirects or just return the
     /// requested entry.
@@ -62,6 +67,7 @@ impl Default for GetOptions {
             timeout_ms: None,
             as_race: true,
             race_timeout_ms: None,
+            target_agent: None,
             follow_redirects: true,
             all_live_headers_with_metadata: false,
         }
@@ -150,6 +156,23 @@ ghost_actor::ghost_chan! {
         /// If a cell is deactivated, we'll need to \"leave\" the network module as well.
         fn leave(dna_hash: DnaHash, agent_pub_key: AgentPubKey) -> ();
 
+        /// Opt an agent in or out of showing up in other agents' `get_peers`.
+        fn set_discoverable(dna_hash: DnaHash, agent_pub_key: AgentPubKey, discoverable: bool) -> ();
+
+        /// Announce the arc an agent holds, it holds the full arc until it does.
+        fn set_arc(dna_hash: DnaHash, agent_pub_key: AgentPubKey, dht_arc: kitsune_p2p::dht_arc::DhtArc) -> ();
+
+        /// Get the discoverable agents on this dna other than `from_agent`,
+        /// most recently seen first.
+        fn get_peers(dna_hash: DnaHash, from_agent: AgentPubKey) -> Vec<holochain_zome_types::agent_info::PeerInfo>;
+
+        /// Is `agent` known to hold an arc covering `basis` on this dna.
+        fn is_authority(dna_hash: DnaHash, agent: AgentPubKey, basis: AnyDhtHash) -> bool;
+
+        /// Get the agents other than `from_agent` known to hold an arc
+        /// covering `basis` on this dna, most recently seen first.
+        fn get_authorities(dna_hash: DnaHash, from_agent: AgentPubKey, basis: AnyDhtHash) -> Vec<AgentPubKey>;
+
         /// Invoke a zome function on a remote node (if you have been granted the capability).
         fn call_remote(
             dna_hash: DnaHash,
@@ -158,6 +181,7 @@ ghost_actor::ghost_chan! {
             zome_name: ZomeName,
             fn_name: FunctionName,
             cap: Option<CapSecret>,
+            nonce: SignedCapNonce,
             request: SerializedBytes,
         ) -> SerializedBytes;
 
@@ -198,6 +222,23 @@ ghost_actor::ghost_chan! {
             options: GetLinksOptions,
         ) -> Vec<GetLinksResponse>;
 
+        /// Ask the agents covering a basis which ops they hold in an arc.
+        fn fetch_op_hashes(
+            dna_hash: DnaHash,
+            from_agent: AgentPubKey,
+            basis: holo_hash::AnyDhtHash,
+            dht_arc: kitsune_p2p::dht_arc::DhtArc,
+            timeout_ms: Option<u64>,
+        ) -> Vec<(AgentPubKey, Vec<holo_hash::DhtOpHash>)>;
+
+        /// Fetch the content of ops from an agent holding them.
+        fn fetch_op_data(
+            dna_hash: DnaHash,
+            from_agent: AgentPubKey,
+            to_agent: AgentPubKey,
+            op_hashes: Vec<holo_hash::DhtOpHash>,
+        ) -> Vec<(holo_hash::AnyDhtHash, holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>;
+
         /// Send a validation receipt to a remote node.
         fn send_validation_receipt(dna_hash: DnaHash, to_agent: AgentPubKey, from_agent: AgentPubKey, receipt: SerializedBytes) -> ();
     }
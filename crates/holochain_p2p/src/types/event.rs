@@ -55,6 +55,7 @@ ghost_actor::ghost_chan! {
             zome_name: ZomeName,
             fn_name: FunctionName,
             cap: Option<CapSecret>,
+            nonce: SignedCapNonce,
             request: SerializedBytes,
         ) -> SerializedBytes;
 
@@ -19,6 +19,20 @@ impl WireDhtOpData {
     }
 }
 
+/// The hashes of the ops an agent holds in an arc
+#[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub(crate) struct WireOpHashes(pub Vec<holo_hash::DhtOpHash>);
+
+/// The content of the ops an agent was asked for
+#[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub(crate) struct WireOpData(
+    pub  Vec<(
+        holo_hash::AnyDhtHash,
+        holo_hash::DhtOpHash,
+        holochain_types::dht_op::DhtOp,
+    )>,
+);
+
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[serde(tag = "type", content = "content")]
 pub(crate) enum WireMessage {
@@ -26,6 +40,7 @@ pub(crate) enum WireMessage {
         zome_name: ZomeName,
         fn_name: FunctionName,
         cap: Option<CapSecret>,
+        nonce: SignedCapNonce,
         #[serde(with = "serde_bytes")]
         data: Vec<u8>,
     },
@@ -50,6 +65,13 @@ pub(crate) enum WireMessage {
         link_key: WireLinkMetaKey,
         options: event::GetLinksOptions,
     },
+    FetchOpHashes {
+        center_loc: u32,
+        half_length: u32,
+    },
+    FetchOpData {
+        op_hashes: Vec<holo_hash::DhtOpHash>,
+    },
 }
 
 impl WireMessage {
@@ -66,12 +88,14 @@ impl WireMessage {
         zome_name: ZomeName,
         fn_name: FunctionName,
         cap: Option<CapSecret>,
+        nonce: SignedCapNonce,
         request: SerializedBytes,
     ) -> WireMessage {
         Self::CallRemote {
             zome_name,
             fn_name,
             cap,
+            nonce,
             data: UnsafeBytes::from(request).into(),
         }
     }
@@ -108,4 +132,15 @@ impl WireMessage {
     pub fn get_links(link_key: WireLinkMetaKey, options: event::GetLinksOptions) -> WireMessage {
         Self::GetLinks { link_key, options }
     }
+
+    pub fn fetch_op_hashes(dht_arc: dht_arc::DhtArc) -> WireMessage {
+        Self::FetchOpHashes {
+            center_loc: dht_arc.center_loc.into(),
+            half_length: dht_arc.half_length,
+        }
+    }
+
+    pub fn fetch_op_data(op_hashes: Vec<holo_hash::DhtOpHash>) -> WireMessage {
+        Self::FetchOpData { op_hashes }
+    }
 }
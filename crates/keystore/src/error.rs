@@ -23,6 +23,14 @@ pub enum KeystoreError {
     #[error("Invalid signature {0:?}, for {1}")]
     InvalidSignature(Signature, String),
 
+    /// A signature that is the wrong length to be an ed25519 signature
+    #[error("Signature is {0} bytes but ed25519 signatures are 64 bytes")]
+    SignatureLength(usize),
+
+    /// A seed that is the wrong length to derive keys from
+    #[error("Seed is {0} bytes but seeds are 32 bytes")]
+    SeedLength(usize),
+
     /// Unexpected Internal Error.
     #[error("Other: {0}")]
     Other(String),
@@ -3,6 +3,10 @@
 
 use crate::*;
 use ghost_actor::dependencies::futures::future::FutureExt;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
 
 /// GhostSender type for the KeystoreApi
 pub type KeystoreSender = ghost_actor::GhostSender<lair_keystore_api::actor::LairClientApi>;
@@ -14,13 +18,54 @@ pub type KeystoreApiResult<T> = Result<T, KeystoreError>;
 pub type KeystoreApiFuture<T> =
     ghost_actor::dependencies::must_future::MustBoxFuture<'static, KeystoreApiResult<T>>;
 
+/// The length of an ed25519 signature in bytes.
+pub const SIGNATURE_BYTES: usize = 64;
+
+/// The length of a [Seed] in bytes.
+pub const SEED_BYTES: usize = 32;
+
+type DerivedSignKeys = HashMap<holo_hash::AgentPubKey, holochain_crypto::DynCryptoBytes>;
+
+lazy_static::lazy_static! {
+    /// The secret keys of keypairs derived with [KeystoreSenderExt::derive_sign_keypair].
+    /// Lair can't import keys, so they are kept here in secure memory for the
+    /// life of the process and [KeystoreSenderExt::sign] signs with them itself.
+    static ref DERIVED_SIGN_KEYS: Mutex<DerivedSignKeys> = Mutex::new(HashMap::new());
+}
+
 /// Some legacy APIs to make refactor easier.
 pub trait KeystoreSenderExt {
     /// Generates a new pure entropy keypair in the keystore, returning the public key.
     fn generate_sign_keypair_from_pure_entropy(&self) -> KeystoreApiFuture<holo_hash::AgentPubKey>;
 
+    /// Derives the signing keypair at `index` from a seed, returning the public key.
+    /// The same seed and index always give the same key so every device
+    /// holding the seed can work out the keys of the others.
+    ///
+    /// The child seed is the 32 byte BLAKE2b hash, keyed with the seed,
+    /// of "holochain_derive_sign_ed25519" followed by the big endian index.
+    /// The keypair is the ed25519 keypair generated from that child seed.
+    ///
+    /// The secret key never leaves the keystore. Sign with the returned
+    /// public key like any other.
+    fn derive_sign_keypair(
+        &self,
+        seed: Seed,
+        index: u32,
+    ) -> KeystoreApiFuture<holo_hash::AgentPubKey>;
+
     /// Generate a signature for a given blob of binary data.
     fn sign(&self, input: SignInput) -> KeystoreApiFuture<Signature>;
+
+    /// Verify a signature of a blob of binary data by any agent.
+    /// Resolves to `false` if the signature doesn't match, and errors if
+    /// the signature is the wrong length to be a signature at all.
+    fn verify_sign(
+        &self,
+        key: holo_hash::AgentPubKey,
+        data: Vec<u8>,
+        signature: Signature,
+    ) -> KeystoreApiFuture<bool>;
 }
 
 impl KeystoreSenderExt for KeystoreSender {
@@ -35,8 +80,34 @@ impl KeystoreSenderExt for KeystoreSender {
         .into()
     }
 
+    fn derive_sign_keypair(
+        &self,
+        seed: Seed,
+        index: u32,
+    ) -> KeystoreApiFuture<holo_hash::AgentPubKey> {
+        async move {
+            let (agent_pubkey, secret_key) = seed.derive_sign_keypair(index).await?;
+            derived_sign_keys().insert(agent_pubkey.clone(), secret_key);
+            Ok(agent_pubkey)
+        }
+        .boxed()
+        .into()
+    }
+
     fn sign(&self, input: SignInput) -> KeystoreApiFuture<Signature> {
         use lair_keystore_api::actor::LairClientApiSender;
+        let derived = derived_sign_keys().get(&input.key).cloned();
+        if let Some(mut secret_key) = derived {
+            return async move {
+                let mut data =
+                    holochain_crypto::crypto_insecure_buffer_from_bytes(input.data.bytes())?;
+                let signature = holochain_crypto::crypto_sign(&mut data, &mut secret_key).await?;
+                let signature = signature.read().to_vec();
+                Ok(Signature(signature))
+            }
+            .boxed()
+            .into();
+        }
         let fut = self.sign_ed25519_sign_by_pub_key(
             input.key.as_ref()[..32].to_vec().into(),
             <Vec<u8>>::from(UnsafeBytes::from(input.data)).into(),
@@ -48,4 +119,29 @@ impl KeystoreSenderExt for KeystoreSender {
         .boxed()
         .into()
     }
+
+    fn verify_sign(
+        &self,
+        key: holo_hash::AgentPubKey,
+        data: Vec<u8>,
+        signature: Signature,
+    ) -> KeystoreApiFuture<bool> {
+        // Lair only holds our own keys so other agents' signatures
+        // are verified locally against their public key.
+        async move {
+            if signature.0.len() != SIGNATURE_BYTES {
+                return Err(KeystoreError::SignatureLength(signature.0.len()));
+            }
+            key.verify_signature_raw(&signature, &data).await
+        }
+        .boxed()
+        .into()
+    }
+}
+
+fn derived_sign_keys() -> MutexGuard<'static, DerivedSignKeys> {
+    // Nothing panics while holding the lock
+    DERIVED_SIGN_KEYS
+        .lock()
+        .expect("derived sign keys lock was poisoned")
 }
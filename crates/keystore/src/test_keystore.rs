@@ -113,4 +113,94 @@ mod tests {
         .await
         .unwrap();
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_verify_sign() {
+        tokio::task::spawn(async move {
+            let _ = holochain_crypto::crypto_init_sodium();
+
+            let keystore = spawn_test_keystore().await.unwrap();
+            let agent_pubkey = holo_hash::AgentPubKey::new_from_pure_entropy(&keystore)
+                .await
+                .unwrap();
+            let data = b"signature test data".to_vec();
+            let signature = agent_pubkey.sign_raw(&keystore, &data).await.unwrap();
+
+            assert!(keystore
+                .verify_sign(agent_pubkey.clone(), data.clone(), signature.clone())
+                .await
+                .unwrap());
+
+            // A signature of other data is not valid
+            assert!(!keystore
+                .verify_sign(
+                    agent_pubkey.clone(),
+                    b"other data".to_vec(),
+                    signature.clone()
+                )
+                .await
+                .unwrap());
+
+            // Nor is a signature by another agent
+            let other_pubkey = holo_hash::AgentPubKey::new_from_pure_entropy(&keystore)
+                .await
+                .unwrap();
+            assert!(!keystore
+                .verify_sign(other_pubkey, data.clone(), signature.clone())
+                .await
+                .unwrap());
+
+            // A truncated signature is malformed rather than invalid
+            let truncated = Signature(signature.0[..32].to_vec());
+            assert!(matches!(
+                keystore.verify_sign(agent_pubkey, data, truncated).await,
+                Err(KeystoreError::SignatureLength(32))
+            ));
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_derive_sign_keypair() {
+        tokio::task::spawn(async move {
+            let _ = holochain_crypto::crypto_init_sodium();
+
+            let keystore = spawn_test_keystore().await.unwrap();
+            let seed = Seed::from_bytes(&[7; SEED_BYTES]).unwrap();
+            let first = keystore.derive_sign_keypair(seed.clone(), 0).await.unwrap();
+
+            // The same seed and index always give the same key
+            let again = keystore
+                .derive_sign_keypair(Seed::from_bytes(&[7; SEED_BYTES]).unwrap(), 0)
+                .await
+                .unwrap();
+            assert_eq!(again, first);
+
+            // Another index or another seed gives another key
+            let second = keystore.derive_sign_keypair(seed, 1).await.unwrap();
+            assert_ne!(second, first);
+            let other_seed = Seed::new_from_entropy().await.unwrap();
+            assert_ne!(
+                keystore.derive_sign_keypair(other_seed, 0).await.unwrap(),
+                first
+            );
+
+            // A derived key signs through the keystore
+            // and its signatures verify against its public key only
+            #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
+            struct MyData(Vec<u8>);
+            let data = MyData(b"signed by a derived key".to_vec());
+            let signature = first.sign(&keystore, &data).await.unwrap();
+            assert!(first.verify_signature(&signature, &data).await.unwrap());
+            assert!(!second.verify_signature(&signature, &data).await.unwrap());
+
+            assert!(matches!(
+                Seed::from_bytes(&[7; 16]),
+                Err(KeystoreError::SeedLength(16))
+            ));
+        })
+        .await
+        .unwrap();
+    }
 }
@@ -1,6 +1,53 @@
 use crate::*;
 pub use holochain_zome_types::signature::Signature;
 
+/// Keeps keys derived for signing apart from anything else the same seed is used for
+const DERIVE_SIGN_CONTEXT: &[u8] = b"holochain_derive_sign_ed25519";
+
+/// A secret that signing keys are deterministically derived from,
+/// see [KeystoreSenderExt::derive_sign_keypair].
+/// The bytes are kept in secure memory.
+#[derive(Clone)]
+pub struct Seed(holochain_crypto::DynCryptoBytes);
+
+impl Seed {
+    /// Copy seed bytes into secure memory.
+    /// Errors unless there are exactly [SEED_BYTES] of them.
+    pub fn from_bytes(bytes: &[u8]) -> KeystoreApiResult<Self> {
+        if bytes.len() != SEED_BYTES {
+            return Err(KeystoreError::SeedLength(bytes.len()));
+        }
+        Ok(Self(
+            holochain_crypto::danger_crypto_secure_buffer_from_bytes(bytes)?,
+        ))
+    }
+
+    /// A new seed from pure entropy
+    pub async fn new_from_entropy() -> KeystoreApiResult<Self> {
+        let mut seed = holochain_crypto::crypto_secure_buffer(SEED_BYTES)?;
+        holochain_crypto::crypto_randombytes_buf(&mut seed).await?;
+        Ok(Self(seed))
+    }
+
+    /// Derives the signing keypair at `index` from this seed,
+    /// see [KeystoreSenderExt::derive_sign_keypair].
+    pub(crate) async fn derive_sign_keypair(
+        &self,
+        index: u32,
+    ) -> KeystoreApiResult<(holo_hash::AgentPubKey, holochain_crypto::DynCryptoBytes)> {
+        let mut seed = self.0.clone();
+        let mut context = holochain_crypto::crypto_insecure_buffer_from_bytes(
+            &[DERIVE_SIGN_CONTEXT, &index.to_be_bytes()].concat(),
+        )?;
+        let mut child_seed = holochain_crypto::crypto_secure_buffer(SEED_BYTES)?;
+        holochain_crypto::crypto_generic_hash_into(&mut child_seed, &mut context, Some(&mut seed))
+            .await?;
+        let (pk, sk) = holochain_crypto::crypto_sign_keypair(Some(&mut child_seed)).await?;
+        let pk = pk.read().to_vec();
+        Ok((holo_hash::AgentPubKey::with_pre_hashed(pk), sk))
+    }
+}
+
 /// Input structure for creating a signature.
 #[derive(Debug)]
 pub struct SignInput {
@@ -1,5 +1,6 @@
 use crate::actor::*;
 use crate::event::*;
+use crate::types::gossip_config::GossipConfig;
 
 mod actor;
 use actor::*;
@@ -8,6 +9,17 @@ use actor::*;
 pub async fn spawn_kitsune_p2p() -> KitsuneP2pResult<(
     ghost_actor::GhostSender<KitsuneP2p>,
     KitsuneP2pEventReceiver,
+)> {
+    spawn_kitsune_p2p_with_gossip_config(GossipConfig::default()).await
+}
+
+/// Spawn a new KitsuneP2p actor whose spaces gossip
+/// with the fanout described by `gossip_config`.
+pub async fn spawn_kitsune_p2p_with_gossip_config(
+    gossip_config: GossipConfig,
+) -> KitsuneP2pResult<(
+    ghost_actor::GhostSender<KitsuneP2p>,
+    KitsuneP2pEventReceiver,
 )> {
     let (evt_send, evt_recv) = futures::channel::mpsc::channel(10);
     let builder = ghost_actor::actor_builder::GhostActorBuilder::new();
@@ -22,6 +34,7 @@ pub async fn spawn_kitsune_p2p() -> KitsuneP2pResult<(
         channel_factory,
         internal_sender,
         evt_send,
+        gossip_config,
     )?));
 
     Ok((sender, evt_recv))
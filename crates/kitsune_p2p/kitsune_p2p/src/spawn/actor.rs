@@ -1,6 +1,6 @@
 // this is largely a passthrough that routes to a specific space handler
 
-use crate::{actor, actor::*, event::*, types::*};
+use crate::{actor, actor::*, event::*, types::gossip_config::GossipConfig, types::*};
 use futures::future::FutureExt;
 use kitsune_p2p_types::async_lazy::AsyncLazy;
 use std::{
@@ -27,6 +27,7 @@ pub(crate) struct KitsuneP2pActor {
     #[allow(dead_code)]
     evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     spaces: HashMap<Arc<KitsuneSpace>, AsyncLazy<ghost_actor::GhostSender<KitsuneP2p>>>,
+    gossip_config: GossipConfig,
 }
 
 impl KitsuneP2pActor {
@@ -34,12 +35,14 @@ impl KitsuneP2pActor {
         channel_factory: ghost_actor::actor_builder::GhostActorChannelFactory<Self>,
         internal_sender: ghost_actor::GhostSender<Internal>,
         evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+        gossip_config: GossipConfig,
     ) -> KitsuneP2pResult<Self> {
         Ok(Self {
             channel_factory,
             internal_sender,
             evt_sender,
             spaces: HashMap::new(),
+            gossip_config,
         })
     }
 }
@@ -131,10 +134,11 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
     ) -> KitsuneP2pHandlerResult<()> {
         let internal_sender = self.internal_sender.clone();
         let space2 = space.clone();
+        let gossip_config = self.gossip_config.clone();
         let space_sender = match self.spaces.entry(space.clone()) {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => entry.insert(AsyncLazy::new(async move {
-                let (send, evt_recv) = spawn_space(space2)
+                let (send, evt_recv) = spawn_space(space2, gossip_config)
                     .await
                     .expect("cannot fail to create space");
                 internal_sender
@@ -167,6 +171,96 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         .into())
     }
 
+    fn handle_set_discoverable(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+        discoverable: bool,
+    ) -> KitsuneP2pHandlerResult<()> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            space_sender
+                .await
+                .set_discoverable(space, agent, discoverable)
+                .await
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_list_peers(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        from_agent: Arc<KitsuneAgent>,
+    ) -> KitsuneP2pHandlerResult<Vec<actor::PeerInfo>> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(
+            async move { space_sender.await.list_peers(space, from_agent).await }
+                .boxed()
+                .into(),
+        )
+    }
+
+    fn handle_set_arc(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+        arc: dht_arc::DhtArc,
+    ) -> KitsuneP2pHandlerResult<()> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(
+            async move { space_sender.await.set_arc(space, agent, arc).await }
+                .boxed()
+                .into(),
+        )
+    }
+
+    fn handle_is_authority(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+        basis: Arc<KitsuneBasis>,
+    ) -> KitsuneP2pHandlerResult<bool> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(
+            async move { space_sender.await.is_authority(space, agent, basis).await }
+                .boxed()
+                .into(),
+        )
+    }
+
+    fn handle_list_authorities(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        from_agent: Arc<KitsuneAgent>,
+        basis: Arc<KitsuneBasis>,
+    ) -> KitsuneP2pHandlerResult<Vec<Arc<KitsuneAgent>>> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            space_sender
+                .await
+                .list_authorities(space, from_agent, basis)
+                .await
+        }
+        .boxed()
+        .into())
+    }
+
     fn handle_rpc_single(
         &mut self,
         space: Arc<KitsuneSpace>,
@@ -1,7 +1,10 @@
 //! This is a temporary quick-hack gossip module for use with the
 //! in-memory / full-sync / non-sharded networking module
 
-use crate::{types::actor::KitsuneP2pResult, *};
+use crate::{
+    types::{actor::KitsuneP2pResult, gossip_config::GossipConfig},
+    *,
+};
 use ghost_actor::dependencies::{tracing, tracing_futures};
 use kitsune_p2p_types::dht_arc::DhtArc;
 use std::{collections::HashSet, iter::FromIterator, sync::Arc};
@@ -40,21 +43,22 @@ ghost_actor::ghost_chan! {
 pub type GossipEventReceiver = futures::channel::mpsc::Receiver<GossipEvent>;
 
 /// spawn a gossip module to control gossip for a space
-pub fn spawn_gossip_module() -> GossipEventReceiver {
+pub fn spawn_gossip_module(config: GossipConfig) -> GossipEventReceiver {
     let (evt_send, evt_recv) = futures::channel::mpsc::channel(10);
 
-    tokio::task::spawn(gossip_loop(evt_send));
+    tokio::task::spawn(gossip_loop(evt_send, config));
 
     evt_recv
 }
 
-#[tracing::instrument(skip(evt_send))]
+#[tracing::instrument(skip(evt_send, config))]
 /// the gossip module is not an actor because we want to pause while
 /// awaiting requests - not process requests in parallel.
 async fn gossip_loop(
     evt_send: futures::channel::mpsc::Sender<GossipEvent>,
+    config: GossipConfig,
 ) -> KitsuneP2pResult<()> {
-    let mut gossip_data = GossipData::new(evt_send);
+    let mut gossip_data = GossipData::new(evt_send, config);
     loop {
         gossip_data.take_action().await?;
 
@@ -65,13 +69,22 @@ async fn gossip_loop(
 struct GossipData {
     evt_send: futures::channel::mpsc::Sender<GossipEvent>,
     pending_gossip_list: Vec<(Arc<KitsuneAgent>, Arc<KitsuneAgent>)>,
+    config: GossipConfig,
+    /// how many times we have fetched the gossip list,
+    /// rotates the peers picked when the fanout is partial
+    round: usize,
 }
 
 impl GossipData {
-    pub fn new(evt_send: futures::channel::mpsc::Sender<GossipEvent>) -> Self {
+    pub fn new(
+        evt_send: futures::channel::mpsc::Sender<GossipEvent>,
+        config: GossipConfig,
+    ) -> Self {
         Self {
             evt_send,
             pending_gossip_list: Vec::new(),
+            config,
+            round: 0,
         }
     }
 
@@ -86,16 +99,9 @@ impl GossipData {
 
     async fn fetch_pending_gossip_list(&mut self) -> KitsuneP2pResult<()> {
         let list = self.evt_send.list_neighbor_agents().await?;
-        // super naive gossip just processes all combinations
-        // also causes duplication because it runs pairs from both sides
-        for a1 in list.iter() {
-            for a2 in list.iter() {
-                // at the very least, avoid gossiping with ourselves
-                if a1 != a2 {
-                    self.pending_gossip_list.push((a1.clone(), a2.clone()));
-                }
-            }
-        }
+        let fanout = self.config.fanout(list.len());
+        self.pending_gossip_list = gossip_pairs(&list, fanout, self.round);
+        self.round = self.round.wrapping_add(1);
         Ok(())
     }
 
@@ -193,3 +199,76 @@ impl GossipData {
         Ok(())
     }
 }
+
+/// Pair every agent with `fanout` of the other agents.
+/// Which peers are picked rotates with `round` so over enough rounds
+/// every pair is gossiped even when the fanout is small.
+/// With a full fanout this is every combination, which
+/// causes duplication because it runs pairs from both sides.
+fn gossip_pairs<T: Clone>(list: &[T], fanout: usize, round: usize) -> Vec<(T, T)> {
+    let n = list.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    // at the very least, avoid gossiping with ourselves
+    let others = n - 1;
+    let fanout = fanout.min(others);
+    let mut out = Vec::with_capacity(n * fanout);
+    for (i, a1) in list.iter().enumerate() {
+        for k in 0..fanout {
+            let offset = 1 + (round.wrapping_add(k)) % others;
+            out.push((a1.clone(), list[(i + offset) % n].clone()));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::gossip_config::{FanoutScaling, NetworkSizeEstimate};
+
+    #[test]
+    fn full_fanout_is_every_pair() {
+        let list: Vec<u8> = (0..5).collect();
+        let fanout = GossipConfig::default().fanout(list.len());
+        let pairs: HashSet<_> = gossip_pairs(&list, fanout, 0).into_iter().collect();
+        assert_eq!(20, pairs.len());
+        assert!(pairs.iter().all(|(a, b)| a != b));
+    }
+
+    #[test]
+    fn fanout_scales_with_peer_store_size() {
+        let config = GossipConfig {
+            size_estimate: NetworkSizeEstimate::PeerStore,
+            scaling: FanoutScaling::Logarithmic { min: 2, max: 6 },
+        };
+        // pretend the peer store holds this many agents
+        for &size in &[1, 3, 10, 100, 1000] {
+            let list: Vec<usize> = (0..size).collect();
+            let fanout = config.fanout(size);
+            let pairs = gossip_pairs(&list, fanout, 7);
+            assert_eq!(size * fanout, pairs.len());
+            for agent in &list {
+                let peers: HashSet<_> = pairs
+                    .iter()
+                    .filter(|(a, _)| a == agent)
+                    .map(|(_, b)| *b)
+                    .collect();
+                assert_eq!(fanout, peers.len());
+                assert!(!peers.contains(agent));
+            }
+        }
+        assert_eq!(6, config.fanout(1000));
+    }
+
+    #[test]
+    fn partial_fanout_reaches_everyone_over_rounds() {
+        let list: Vec<u8> = (0..8).collect();
+        let mut seen = HashSet::new();
+        for round in 0..7 {
+            seen.extend(gossip_pairs(&list, 1, round));
+        }
+        assert_eq!(8 * 7, seen.len());
+    }
+}
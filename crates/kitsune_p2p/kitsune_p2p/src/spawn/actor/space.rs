@@ -39,6 +39,7 @@ ghost_actor::ghost_chan! {
 
 pub(crate) async fn spawn_space(
     space: Arc<KitsuneSpace>,
+    gossip_config: GossipConfig,
 ) -> KitsuneP2pResult<(
     ghost_actor::GhostSender<KitsuneP2p>,
     KitsuneP2pEventReceiver,
@@ -48,7 +49,7 @@ pub(crate) async fn spawn_space(
     let builder = ghost_actor::actor_builder::GhostActorBuilder::new();
 
     // initialize gossip module
-    let gossip_recv = gossip::spawn_gossip_module();
+    let gossip_recv = gossip::spawn_gossip_module(gossip_config);
     builder
         .channel_factory()
         .attach_receiver(gossip_recv)
@@ -82,12 +83,16 @@ impl gossip::GossipEventHandler for Space {
 
     fn handle_req_op_hashes(
         &mut self,
-        _from_agent: Arc<KitsuneAgent>,
+        from_agent: Arc<KitsuneAgent>,
         to_agent: Arc<KitsuneAgent>,
         dht_arc: kitsune_p2p_types::dht_arc::DhtArc,
         since_utc_epoch_s: i64,
         until_utc_epoch_s: i64,
     ) -> gossip::GossipEventHandlerResult<Vec<Arc<KitsuneOpHash>>> {
+        // both sides of a gossip round are alive
+        self.record_seen(&from_agent);
+        self.record_seen(&to_agent);
+
         // while full-sync just redirecting to self...
         // but eventually some of these will be outgoing remote requests
         let fut = self
@@ -170,6 +175,9 @@ impl SpaceInternalHandler for Space {
             return Err(KitsuneP2pError::RoutingAgentError(to_agent));
         }
 
+        // anyone reaching us is alive
+        self.record_seen(&from_agent);
+
         // to_agent *is* joined - let's forward the request
         let space = self.space.clone();
 
@@ -183,6 +191,21 @@ impl SpaceInternalHandler for Space {
         let data = wire::Wire::decode((*data).clone())?;
 
         match data {
+            wire::Wire::Presence(discoverable) => {
+                // presence is kitsune's own business, the implementor never sees it
+                self.peers
+                    .entry(from_agent)
+                    .or_insert_with(PeerRecord::new)
+                    .discoverable = discoverable;
+                Ok(async move { Ok(vec![]) }.boxed().into())
+            }
+            wire::Wire::Arc(arc) => {
+                self.peers
+                    .entry(from_agent)
+                    .or_insert_with(PeerRecord::new)
+                    .arc = Some(arc);
+                Ok(async move { Ok(vec![]) }.boxed().into())
+            }
             wire::Wire::Call(payload) => {
                 Ok(
                     async move { evt_sender.call(space, to_agent, from_agent, payload).await }
@@ -230,18 +253,116 @@ impl KitsuneP2pHandler for Space {
         match self.agents.entry(agent.clone()) {
             Entry::Occupied(_) => (),
             Entry::Vacant(entry) => {
-                entry.insert(AgentInfo { agent });
+                entry.insert(AgentInfo {
+                    agent: agent.clone(),
+                });
             }
         }
+        self.record_seen(&agent);
+        // An agent joined here holds everything until it sets its arc
+        self.peers
+            .get_mut(&agent)
+            .expect("just recorded")
+            .arc
+            .get_or_insert_with(|| dht_arc::DhtArc::new(0, dht_arc::MAX_HALF_LENGTH));
         Ok(async move { Ok(()) }.boxed().into())
     }
 
+    fn handle_set_discoverable(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+        discoverable: bool,
+    ) -> KitsuneP2pHandlerResult<()> {
+        if !self.agents.contains_key(&agent) {
+            return Err(KitsuneP2pError::RoutingAgentError(agent));
+        }
+        self.peers
+            .entry(agent.clone())
+            .or_insert_with(PeerRecord::new)
+            .discoverable = discoverable;
+
+        // announce it to everyone who might list us as a peer
+        let payload = Arc::new(wire::Wire::presence(discoverable).encode());
+        self.announce(agent, payload, "failed to announce presence")
+    }
+
+    fn handle_list_peers(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        from_agent: Arc<KitsuneAgent>,
+    ) -> KitsuneP2pHandlerResult<Vec<actor::PeerInfo>> {
+        let mut res = self
+            .peers
+            .iter()
+            .filter(|(agent, peer)| peer.discoverable && **agent != from_agent)
+            .map(|(agent, peer)| actor::PeerInfo {
+                agent: agent.clone(),
+                last_seen_utc_epoch_ms: peer.last_seen_utc_epoch_ms,
+            })
+            .collect::<Vec<_>>();
+        res.sort_by(|a, b| b.last_seen_utc_epoch_ms.cmp(&a.last_seen_utc_epoch_ms));
+        Ok(async move { Ok(res) }.boxed().into())
+    }
+
+    fn handle_set_arc(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+        arc: dht_arc::DhtArc,
+    ) -> KitsuneP2pHandlerResult<()> {
+        if !self.agents.contains_key(&agent) {
+            return Err(KitsuneP2pError::RoutingAgentError(agent));
+        }
+        self.peers
+            .entry(agent.clone())
+            .or_insert_with(PeerRecord::new)
+            .arc = Some(arc);
+        let payload = Arc::new(wire::Wire::arc(arc).encode());
+        self.announce(agent, payload, "failed to announce arc")
+    }
+
+    fn handle_is_authority(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+        basis: Arc<KitsuneBasis>,
+    ) -> KitsuneP2pHandlerResult<bool> {
+        let res = self
+            .peers
+            .get(&agent)
+            .map(|peer| peer.holds(&basis))
+            .unwrap_or(false);
+        Ok(async move { Ok(res) }.boxed().into())
+    }
+
+    fn handle_list_authorities(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        from_agent: Arc<KitsuneAgent>,
+        basis: Arc<KitsuneBasis>,
+    ) -> KitsuneP2pHandlerResult<Vec<Arc<KitsuneAgent>>> {
+        let mut authorities = self
+            .peers
+            .iter()
+            .filter(|(agent, peer)| **agent != from_agent && peer.holds(&basis))
+            .collect::<Vec<_>>();
+        authorities
+            .sort_by(|(_, a), (_, b)| b.last_seen_utc_epoch_ms.cmp(&a.last_seen_utc_epoch_ms));
+        let res = authorities
+            .into_iter()
+            .map(|(agent, _)| agent.clone())
+            .collect::<Vec<_>>();
+        Ok(async move { Ok(res) }.boxed().into())
+    }
+
     fn handle_leave(
         &mut self,
         _space: Arc<KitsuneSpace>,
         agent: Arc<KitsuneAgent>,
     ) -> KitsuneP2pHandlerResult<()> {
         self.agents.remove(&agent);
+        self.peers.remove(&agent);
         Ok(async move { Ok(()) }.boxed().into())
     }
 
@@ -373,6 +494,40 @@ struct AgentInfo {
     agent: Arc<KitsuneAgent>,
 }
 
+/// What the peer store knows of an agent, joined here or remote.
+struct PeerRecord {
+    /// has the agent announced it is willing to show up in `list_peers`
+    discoverable: bool,
+    /// the arc the agent last announced.
+    /// A remote agent we have only heard from holds nothing until it announces one.
+    arc: Option<dht_arc::DhtArc>,
+    last_seen_utc_epoch_ms: i64,
+}
+
+impl PeerRecord {
+    fn new() -> Self {
+        Self {
+            discoverable: false,
+            arc: None,
+            last_seen_utc_epoch_ms: now_utc_epoch_ms(),
+        }
+    }
+
+    /// does the agent's announced arc cover this basis
+    fn holds(&self, basis: &KitsuneBasis) -> bool {
+        self.arc
+            .map(|arc| arc.contains(basis.get_loc()))
+            .unwrap_or(false)
+    }
+}
+
+fn now_utc_epoch_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
 /// A Kitsune P2p Node can track multiple "spaces" -- Non-interacting namespaced
 /// areas that share common transport infrastructure for communication.
 pub(crate) struct Space {
@@ -380,6 +535,8 @@ pub(crate) struct Space {
     internal_sender: ghost_actor::GhostSender<SpaceInternal>,
     evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     agents: HashMap<Arc<KitsuneAgent>, AgentInfo>,
+    /// every agent we have heard from in this space
+    peers: HashMap<Arc<KitsuneAgent>, PeerRecord>,
 }
 
 impl Space {
@@ -394,9 +551,49 @@ impl Space {
             internal_sender,
             evt_sender,
             agents: HashMap::new(),
+            peers: HashMap::new(),
         }
     }
 
+    /// record that we have just seen this agent alive
+    fn record_seen(&mut self, agent: &Arc<KitsuneAgent>) {
+        self.peers
+            .entry(agent.clone())
+            .or_insert_with(PeerRecord::new)
+            .last_seen_utc_epoch_ms = now_utc_epoch_ms();
+    }
+
+    /// send a kitsune message from a joined agent to every other agent
+    /// joined in this space, logging the ones that can't be reached
+    fn announce(
+        &self,
+        from_agent: Arc<KitsuneAgent>,
+        payload: Arc<Vec<u8>>,
+        failed_msg: &'static str,
+    ) -> KitsuneP2pHandlerResult<()> {
+        let space = self.space.clone();
+        let internal_sender = self.internal_sender.clone();
+        let neighbors = self
+            .agents
+            .keys()
+            .filter(|a| **a != from_agent)
+            .cloned()
+            .collect::<Vec<_>>();
+        Ok(async move {
+            for to_agent in neighbors {
+                if let Err(e) = internal_sender
+                    .immediate_request(space.clone(), to_agent, from_agent.clone(), payload.clone())
+                    .await
+                {
+                    tracing::warn!(msg = failed_msg, ?e);
+                }
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     /// actual logic for handle_rpc_multi ...
     /// the top-level handler may or may not spawn a task for this
     #[allow(unused_variables, unused_assignments, unused_mut)]
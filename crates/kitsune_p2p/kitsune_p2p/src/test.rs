@@ -348,4 +348,156 @@ mod tests {
             panic!("failed to gossip both dht op hashes");
         }
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_list_peers_only_discoverable() {
+        let space1: Arc<KitsuneSpace> =
+            Arc::new(b"ssssssssssssssssssssssssssssssssssss".to_vec().into());
+        let a1: Arc<KitsuneAgent> =
+            Arc::new(b"111111111111111111111111111111111111".to_vec().into());
+        let a2: Arc<KitsuneAgent> =
+            Arc::new(b"222222222222222222222222222222222222".to_vec().into());
+        let a3: Arc<KitsuneAgent> =
+            Arc::new(b"333333333333333333333333333333333333".to_vec().into());
+
+        let (p2p, _evt) = spawn_kitsune_p2p().await.unwrap();
+
+        p2p.join(space1.clone(), a1.clone()).await.unwrap();
+        p2p.join(space1.clone(), a2.clone()).await.unwrap();
+        p2p.join(space1.clone(), a3.clone()).await.unwrap();
+
+        // nobody has opted in yet
+        let peers = p2p.list_peers(space1.clone(), a1.clone()).await.unwrap();
+        assert!(peers.is_empty());
+
+        p2p.set_discoverable(space1.clone(), a1.clone(), true)
+            .await
+            .unwrap();
+        p2p.set_discoverable(space1.clone(), a2.clone(), true)
+            .await
+            .unwrap();
+
+        // a3 never opted in and we never see ourselves
+        let peers = p2p.list_peers(space1.clone(), a1.clone()).await.unwrap();
+        assert_eq!(
+            vec![a2.clone()],
+            peers.into_iter().map(|p| p.agent).collect::<Vec<_>>()
+        );
+        let peers = p2p.list_peers(space1.clone(), a3.clone()).await.unwrap();
+        assert_eq!(2, peers.len());
+        assert!(peers.iter().all(|p| p.last_seen_utc_epoch_ms > 0));
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_is_authority_only_for_known_peers_covering_the_basis() {
+        let space1: Arc<KitsuneSpace> =
+            Arc::new(b"ssssssssssssssssssssssssssssssssssss".to_vec().into());
+        let a1: Arc<KitsuneAgent> =
+            Arc::new(b"111111111111111111111111111111111111".to_vec().into());
+        let a2: Arc<KitsuneAgent> =
+            Arc::new(b"222222222222222222222222222222222222".to_vec().into());
+        let a3: Arc<KitsuneAgent> =
+            Arc::new(b"333333333333333333333333333333333333".to_vec().into());
+        let basis: Arc<KitsuneBasis> =
+            Arc::new(b"bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb".to_vec().into());
+
+        let (p2p, mut evt) = spawn_kitsune_p2p().await.unwrap();
+
+        let r_task = tokio::task::spawn(async move {
+            use tokio::stream::StreamExt;
+            while let Some(evt) = evt.next().await {
+                if let KitsuneP2pEvent::Call { respond, .. } = evt {
+                    respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                }
+            }
+        });
+
+        p2p.join(space1.clone(), a1.clone()).await.unwrap();
+
+        assert!(p2p
+            .is_authority(space1.clone(), a1.clone(), basis.clone())
+            .await
+            .unwrap());
+        // we have never heard from a2
+        assert!(!p2p
+            .is_authority(space1.clone(), a2.clone(), basis.clone())
+            .await
+            .unwrap());
+        // nor does hearing from a3 make it an authority before it announces an arc
+        p2p.rpc_single(space1.clone(), a1.clone(), a3.clone(), b"hi".to_vec())
+            .await
+            .unwrap();
+        assert!(!p2p
+            .is_authority(space1.clone(), a3.clone(), basis.clone())
+            .await
+            .unwrap());
+        assert!(p2p
+            .list_authorities(space1.clone(), a1.clone(), basis.clone())
+            .await
+            .unwrap()
+            .is_empty());
+
+        // an arc that doesn't reach the basis
+        p2p.set_arc(space1.clone(), a1.clone(), dht_arc::DhtArc::new(0, 1))
+            .await
+            .unwrap();
+        assert!(!p2p
+            .is_authority(space1.clone(), a1.clone(), basis.clone())
+            .await
+            .unwrap());
+        p2p.set_arc(
+            space1.clone(),
+            a1.clone(),
+            dht_arc::DhtArc::new(basis.get_loc(), 1),
+        )
+        .await
+        .unwrap();
+        assert!(p2p
+            .is_authority(space1.clone(), a1.clone(), basis.clone())
+            .await
+            .unwrap());
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+        r_task.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_list_authorities_covering_the_basis() {
+        let space1: Arc<KitsuneSpace> =
+            Arc::new(b"ssssssssssssssssssssssssssssssssssss".to_vec().into());
+        let a1: Arc<KitsuneAgent> =
+            Arc::new(b"111111111111111111111111111111111111".to_vec().into());
+        let a2: Arc<KitsuneAgent> =
+            Arc::new(b"222222222222222222222222222222222222".to_vec().into());
+        let a3: Arc<KitsuneAgent> =
+            Arc::new(b"333333333333333333333333333333333333".to_vec().into());
+        let basis: Arc<KitsuneBasis> =
+            Arc::new(b"bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb".to_vec().into());
+
+        let (p2p, _evt) = spawn_kitsune_p2p().await.unwrap();
+
+        p2p.join(space1.clone(), a1.clone()).await.unwrap();
+        p2p.join(space1.clone(), a2.clone()).await.unwrap();
+        p2p.join(space1.clone(), a3.clone()).await.unwrap();
+
+        // a3 holds an arc that doesn't reach the basis
+        p2p.set_arc(
+            space1.clone(),
+            a3.clone(),
+            dht_arc::DhtArc::new(basis.get_loc().wrapping_add(u32::MAX / 2), 1),
+        )
+        .await
+        .unwrap();
+
+        // none of them are discoverable but authorities are still listed
+        let res = p2p
+            .list_authorities(space1.clone(), a1.clone(), basis.clone())
+            .await
+            .unwrap();
+        assert_eq!(res, vec![a2.clone()]);
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+    }
 }
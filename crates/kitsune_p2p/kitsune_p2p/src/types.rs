@@ -174,6 +174,7 @@ impl std::fmt::Debug for KitsuneSignature {
 
 pub mod actor;
 pub mod event;
+pub mod gossip_config;
 pub(crate) mod wire;
 
 pub use kitsune_p2p_types::dht_arc;
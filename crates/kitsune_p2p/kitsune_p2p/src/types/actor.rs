@@ -62,6 +62,15 @@ pub struct NotifyMulti {
     pub payload: Vec<u8>,
 }
 
+/// An agent in a space's peer store, as reported to discovery queries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeerInfo {
+    /// The agent.
+    pub agent: Arc<super::KitsuneAgent>,
+    /// When we last saw the agent join or take part in gossip.
+    pub last_seen_utc_epoch_ms: i64,
+}
+
 ghost_actor::ghost_chan! {
     /// The KitsuneP2pSender allows async remote-control of the KitsuneP2p actor.
     pub chan KitsuneP2p<super::KitsuneP2pError> {
@@ -71,6 +80,26 @@ ghost_actor::ghost_chan! {
         /// Withdraw this space/agent pair from this network.
         fn leave(space: Arc<super::KitsuneSpace>, agent: Arc<super::KitsuneAgent>) -> ();
 
+        /// Opt a joined agent in or out of showing up in other agents' `list_peers`.
+        /// Agents are not discoverable until they opt in.
+        fn set_discoverable(space: Arc<super::KitsuneSpace>, agent: Arc<super::KitsuneAgent>, discoverable: bool) -> ();
+
+        /// Announce the arc a joined agent holds.
+        /// Agents hold the full arc until they announce otherwise.
+        fn set_arc(space: Arc<super::KitsuneSpace>, agent: Arc<super::KitsuneAgent>, arc: super::dht_arc::DhtArc) -> ();
+
+        /// List the discoverable agents in a space's peer store,
+        /// other than `from_agent`, most recently seen first.
+        fn list_peers(space: Arc<super::KitsuneSpace>, from_agent: Arc<super::KitsuneAgent>) -> Vec<PeerInfo>;
+
+        /// Is `agent` in a space's peer store with an arc covering `basis`.
+        fn is_authority(space: Arc<super::KitsuneSpace>, agent: Arc<super::KitsuneAgent>, basis: Arc<super::KitsuneBasis>) -> bool;
+
+        /// List the agents in a space's peer store, other than `from_agent`,
+        /// with an arc covering `basis`, most recently seen first.
+        /// Unlike `list_peers` this doesn't wait for agents to opt in.
+        fn list_authorities(space: Arc<super::KitsuneSpace>, from_agent: Arc<super::KitsuneAgent>, basis: Arc<super::KitsuneBasis>) -> Vec<Arc<super::KitsuneAgent>>;
+
         /// Make a request of a single remote agent, expecting a response.
         /// The remote side will receive a "Call" event.
         fn rpc_single(space: Arc<super::KitsuneSpace>, to_agent: Arc<super::KitsuneAgent>, from_agent: Arc<super::KitsuneAgent>, payload: Vec<u8>) -> Vec<u8>;
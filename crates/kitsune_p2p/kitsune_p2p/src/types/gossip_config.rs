@@ -0,0 +1,141 @@
+//! Configuration for how widely each gossip round fans out.
+
+use std::sync::Arc;
+
+/// Where the gossip module gets its estimate of the network size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetworkSizeEstimate {
+    /// Count the agents in the peer store
+    PeerStore,
+    /// Assume the network is this size no matter what the peer store says
+    Fixed(usize),
+}
+
+/// Turns a network size estimate into how many peers each agent
+/// gossips with per round.
+#[derive(Clone)]
+pub enum FanoutScaling {
+    /// Gossip with every known peer
+    Full,
+    /// Gossip with this many peers whatever the size of the network
+    Fixed(usize),
+    /// Grow with the log2 of the network size, clamped to `min..=max`.
+    /// Small networks gossip with nearly every peer for fast convergence
+    /// while huge networks only reach a bounded number of peers.
+    Logarithmic {
+        /// Never gossip with fewer peers than this
+        min: usize,
+        /// Never gossip with more peers than this
+        max: usize,
+    },
+    /// Any other scaling
+    Custom(Arc<dyn Fn(usize) -> usize + Send + Sync>),
+}
+
+impl std::fmt::Debug for FanoutScaling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FanoutScaling::Full => write!(f, "Full"),
+            FanoutScaling::Fixed(n) => write!(f, "Fixed({})", n),
+            FanoutScaling::Logarithmic { min, max } => {
+                write!(f, "Logarithmic {{ min: {}, max: {} }}", min, max)
+            }
+            FanoutScaling::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Configures the fanout of the gossip module.
+/// The default gossips with every known peer.
+#[derive(Clone, Debug)]
+pub struct GossipConfig {
+    /// Where the network size estimate comes from
+    pub size_estimate: NetworkSizeEstimate,
+    /// How the estimate is turned into a fanout
+    pub scaling: FanoutScaling,
+}
+
+impl Default for GossipConfig {
+    fn default() -> Self {
+        Self {
+            size_estimate: NetworkSizeEstimate::PeerStore,
+            scaling: FanoutScaling::Full,
+        }
+    }
+}
+
+impl GossipConfig {
+    /// How many peers each agent gossips with when the peer store
+    /// holds `peer_store_size` agents.
+    /// Never more than the other agents in the peer store.
+    pub fn fanout(&self, peer_store_size: usize) -> usize {
+        let estimate = match self.size_estimate {
+            NetworkSizeEstimate::PeerStore => peer_store_size,
+            NetworkSizeEstimate::Fixed(n) => n,
+        };
+        let fanout = match &self.scaling {
+            FanoutScaling::Full => estimate,
+            FanoutScaling::Fixed(n) => *n,
+            FanoutScaling::Logarithmic { min, max } => {
+                // ceil(log2(estimate)) without going through floats
+                let log =
+                    (usize::MAX.count_ones() - estimate.saturating_sub(1).leading_zeros()) as usize;
+                log.max(*min).min(*max)
+            }
+            FanoutScaling::Custom(f) => f(estimate),
+        };
+        fanout.min(peer_store_size.saturating_sub(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_gossips_with_every_peer() {
+        let config = GossipConfig::default();
+        assert_eq!(0, config.fanout(0));
+        assert_eq!(0, config.fanout(1));
+        assert_eq!(9, config.fanout(10));
+    }
+
+    #[test]
+    fn logarithmic_fanout_is_clamped() {
+        let config = GossipConfig {
+            size_estimate: NetworkSizeEstimate::PeerStore,
+            scaling: FanoutScaling::Logarithmic { min: 3, max: 8 },
+        };
+        // small networks are clamped by the peers we actually know
+        assert_eq!(1, config.fanout(2));
+        assert_eq!(3, config.fanout(4));
+        assert_eq!(3, config.fanout(8));
+        assert_eq!(4, config.fanout(9));
+        assert_eq!(7, config.fanout(100));
+        assert_eq!(8, config.fanout(1_000_000));
+    }
+
+    #[test]
+    fn fixed_estimate_ignores_peer_store() {
+        let config = GossipConfig {
+            size_estimate: NetworkSizeEstimate::Fixed(1024),
+            scaling: FanoutScaling::Logarithmic { min: 1, max: 20 },
+        };
+        assert_eq!(10, config.fanout(50));
+        assert_eq!(5, config.fanout(6));
+    }
+
+    #[test]
+    fn custom_scaling_is_used() {
+        let config = GossipConfig {
+            size_estimate: NetworkSizeEstimate::PeerStore,
+            scaling: FanoutScaling::Custom(Arc::new(|n| n / 10)),
+        };
+        for size in &[0, 5, 10, 55, 1000] {
+            assert_eq!(
+                (size / 10).min(size.saturating_sub(1)),
+                config.fanout(*size)
+            );
+        }
+    }
+}
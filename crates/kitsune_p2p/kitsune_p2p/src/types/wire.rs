@@ -3,13 +3,17 @@
 // The kitsune wire protocol is designed to be very light,
 // both in terms of cpu overhead, and in terms of dependencies.
 
-use crate::types::KitsuneP2pError;
+use crate::types::{dht_arc::DhtArc, KitsuneP2pError};
 
 /// The main kitsune wire message enum
 #[derive(Debug)]
 pub enum Wire {
     Call(Vec<u8>),
     Notify(Vec<u8>),
+    /// An agent announcing whether it may be listed as a peer
+    Presence(bool),
+    /// An agent announcing the arc it holds
+    Arc(DhtArc),
 }
 
 impl Wire {
@@ -28,6 +32,14 @@ impl Wire {
     pub fn notify(payload: Vec<u8>) -> Self {
         Self::Notify(payload)
     }
+
+    pub fn presence(discoverable: bool) -> Self {
+        Self::Presence(discoverable)
+    }
+
+    pub fn arc(arc: DhtArc) -> Self {
+        Self::Arc(arc)
+    }
 }
 
 // -- private -- //
@@ -49,6 +61,12 @@ const WIRE_CALL: u8 = 0x10;
 /// a kitsune notify message
 const WIRE_NOTIFY: u8 = 0x20;
 
+/// a kitsune presence message
+const WIRE_PRESENCE: u8 = 0x30;
+
+/// a kitsune arc message
+const WIRE_ARC: u8 = 0x40;
+
 impl Wire {
     fn priv_encode_inner(msg_type: u8, mut msg: Vec<u8>) -> Vec<u8> {
         let mut out = Vec::with_capacity(msg.len() + 4);
@@ -64,6 +82,14 @@ impl Wire {
         match self {
             Wire::Call(payload) => Wire::priv_encode_inner(WIRE_CALL, payload),
             Wire::Notify(payload) => Wire::priv_encode_inner(WIRE_NOTIFY, payload),
+            Wire::Presence(discoverable) => {
+                Wire::priv_encode_inner(WIRE_PRESENCE, vec![discoverable as u8])
+            }
+            Wire::Arc(arc) => {
+                let mut msg = u32::from(arc.center_loc).to_le_bytes().to_vec();
+                msg.extend_from_slice(&arc.half_length.to_le_bytes());
+                Wire::priv_encode_inner(WIRE_ARC, msg)
+            }
         }
     }
 
@@ -77,6 +103,15 @@ impl Wire {
                 data.drain(..4);
                 Ok(Wire::Notify(data))
             }
+            [KITSUNE_MAGIC_1, KITSUNE_MAGIC_2, KITSUNE_PROTO_VER, WIRE_PRESENCE, discoverable] => {
+                Ok(Wire::Presence(*discoverable != 0))
+            }
+            [KITSUNE_MAGIC_1, KITSUNE_MAGIC_2, KITSUNE_PROTO_VER, WIRE_ARC, c0, c1, c2, c3, h0, h1, h2, h3] => {
+                Ok(Wire::Arc(DhtArc::new(
+                    u32::from_le_bytes([*c0, *c1, *c2, *c3]),
+                    u32::from_le_bytes([*h0, *h1, *h2, *h3]),
+                )))
+            }
             _ => Err(KitsuneP2pError::decoding_error(
                 "invalid or corrupt kitsune p2p message".to_string(),
             )),
@@ -100,6 +135,21 @@ mod tests {
         assert_matches!(res, Ok(Wire::Call(vec)) if vec.is_empty());
     }
 
+    #[test]
+    fn presence_round_trips() {
+        let res = Wire::decode(Wire::presence(true).encode());
+        assert_matches!(res, Ok(Wire::Presence(true)));
+        let res = Wire::decode(Wire::presence(false).encode());
+        assert_matches!(res, Ok(Wire::Presence(false)));
+    }
+
+    #[test]
+    fn arc_round_trips() {
+        let arc = DhtArc::new(3_860_645_936, 42);
+        let res = Wire::decode(Wire::arc(arc).encode());
+        assert_matches!(res, Ok(Wire::Arc(a)) if a == arc);
+    }
+
     #[test]
     fn bad_decode_size() {
         let res = Wire::decode(vec![KITSUNE_MAGIC_1, KITSUNE_MAGIC_2, KITSUNE_PROTO_VER]);
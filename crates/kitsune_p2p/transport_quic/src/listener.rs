@@ -5,7 +5,7 @@ use kitsune_p2p_types::{
     transport::transport_listener::*,
     transport::*,
 };
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Duration};
 
 ghost_actor::ghost_chan! {
     chan ListenerInner<TransportError> {
@@ -69,23 +69,54 @@ impl TransportListenerHandler for TransportListenerQuic {
     }
 }
 
+/// Connection level timeouts for the QUIC connections a listener
+/// accepts and opens.
+/// `None` for a field keeps quinn's default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuicConnectionConfig {
+    /// Close a connection once nothing has been received on it for this long
+    pub max_idle_timeout: Option<Duration>,
+    /// Send a keepalive when nothing has been sent on a connection for this long,
+    /// which holds open NAT mappings and stops the idle timeout closing it.
+    /// Should be shorter than the `max_idle_timeout` of both peers.
+    pub keep_alive_interval: Option<Duration>,
+}
+
+impl QuicConnectionConfig {
+    /// Set these timeouts on a quinn TransportConfig
+    fn apply(&self, transport_config: &mut quinn::TransportConfig) -> TransportResult<()> {
+        if let Some(max_idle_timeout) = self.max_idle_timeout {
+            transport_config
+                .max_idle_timeout(Some(max_idle_timeout))
+                .map_err(TransportError::other)?;
+        }
+        if let Some(keep_alive_interval) = self.keep_alive_interval {
+            transport_config.keep_alive_interval(Some(keep_alive_interval));
+        }
+        Ok(())
+    }
+}
+
 /// Spawn a new QUIC TransportListenerSender.
+/// Without a `config` connections use quinn's default timeouts.
 pub async fn spawn_transport_listener_quic(
     bind_to: Url2,
     cert: Option<(
         lair_keystore_api::actor::Cert,
         lair_keystore_api::actor::CertPrivKey,
     )>,
+    config: Option<QuicConnectionConfig>,
 ) -> TransportListenerResult<(
     ghost_actor::GhostSender<TransportListener>,
     TransportListenerEventReceiver,
 )> {
-    let server_config = danger::configure_server(cert)
+    let config = config.unwrap_or_default();
+    let server_config = danger::configure_server(cert, &config)
         .await
         .map_err(|e| TransportError::from(format!("cert error: {:?}", e)))?;
     let mut builder = quinn::Endpoint::builder();
     builder.listen(server_config);
-    builder.default_client_config(danger::configure_client());
+    builder.default_client_config(danger::configure_client(&config)?);
     let (quinn_endpoint, incoming) = builder
         .bind(&crate::url_to_addr(&bind_to, crate::SCHEME).await?)
         .map_err(TransportError::other)?;
@@ -129,6 +160,7 @@ pub async fn spawn_transport_listener_quic(
 }
 
 mod danger {
+    use super::QuicConnectionConfig;
     use kitsune_p2p_types::transport::{TransportError, TransportResult};
     use quinn::{
         Certificate, CertificateChain, ClientConfig, ClientConfigBuilder, PrivateKey, ServerConfig,
@@ -142,6 +174,7 @@ mod danger {
             lair_keystore_api::actor::Cert,
             lair_keystore_api::actor::CertPrivKey,
         )>,
+        config: &QuicConnectionConfig,
     ) -> TransportResult<ServerConfig> {
         let (cert, cert_priv) = match cert {
             Some(r) => r,
@@ -162,6 +195,7 @@ mod danger {
 
         let mut transport_config = TransportConfig::default();
         transport_config.stream_window_uni(0);
+        config.apply(&mut transport_config)?;
         let mut server_config = ServerConfig::default();
         server_config.transport = Arc::new(transport_config);
         let mut cfg_builder = ServerConfigBuilder::new(server_config);
@@ -194,13 +228,16 @@ mod danger {
         }
     }
 
-    pub(crate) fn configure_client() -> ClientConfig {
+    pub(crate) fn configure_client(config: &QuicConnectionConfig) -> TransportResult<ClientConfig> {
         let mut cfg = ClientConfigBuilder::default().build();
+        let mut transport_config = TransportConfig::default();
+        config.apply(&mut transport_config)?;
+        cfg.transport = Arc::new(transport_config);
         let tls_cfg: &mut rustls::ClientConfig = Arc::get_mut(&mut cfg.crypto).unwrap();
         // this is only available when compiled with "dangerous_configuration" feature
         tls_cfg
             .dangerous()
             .set_certificate_verifier(SkipServerVerification::new());
-        cfg
+        Ok(cfg)
     }
 }
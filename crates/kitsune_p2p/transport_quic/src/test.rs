@@ -2,12 +2,16 @@
 mod tests {
     use crate::*;
     use futures::{future::FutureExt, stream::StreamExt};
-    use kitsune_p2p_types::{transport::transport_connection::*, transport::transport_listener::*};
+    use kitsune_p2p_types::{
+        dependencies::ghost_actor, transport::transport_connection::*,
+        transport::transport_listener::*,
+    };
+    use std::time::Duration;
 
     #[tokio::test(threaded_scheduler)]
     async fn test_message() {
         let (listener1, _events1) =
-            spawn_transport_listener_quic(url2!("kitsune-quic://127.0.0.1:0"), None)
+            spawn_transport_listener_quic(url2!("kitsune-quic://127.0.0.1:0"), None, None)
                 .await
                 .unwrap();
 
@@ -15,7 +19,7 @@ mod tests {
         println!("listener1 bound to: {}", bound1);
 
         let (listener2, mut events2) =
-            spawn_transport_listener_quic(url2!("kitsune-quic://127.0.0.1:0"), None)
+            spawn_transport_listener_quic(url2!("kitsune-quic://127.0.0.1:0"), None, None)
                 .await
                 .unwrap();
 
@@ -73,4 +77,74 @@ mod tests {
 
         assert_eq!("echo: hello", &String::from_utf8_lossy(&resp));
     }
+
+    /// Spawn a listener that echoes every request it receives
+    async fn spawn_echo_listener(
+        config: Option<QuicConnectionConfig>,
+    ) -> ghost_actor::GhostSender<TransportListener> {
+        let (listener, mut events) =
+            spawn_transport_listener_quic(url2!("kitsune-quic://127.0.0.1:0"), None, config)
+                .await
+                .unwrap();
+        tokio::task::spawn(async move {
+            while let Some(evt) = events.next().await {
+                let TransportListenerEvent::IncomingConnection {
+                    respond,
+                    receiver: mut evt,
+                    ..
+                } = evt;
+                respond.respond(Ok(async move { Ok(()) }.boxed().into()));
+                tokio::task::spawn(async move {
+                    while let Some(evt) = evt.next().await {
+                        let TransportConnectionEvent::IncomingRequest { respond, data, .. } = evt;
+                        respond.respond(Ok(async move { Ok(data) }.boxed().into()));
+                    }
+                });
+            }
+        });
+        listener
+    }
+
+    fn timeouts(keep_alive_interval: Option<Duration>) -> QuicConnectionConfig {
+        QuicConnectionConfig {
+            max_idle_timeout: Some(Duration::from_millis(300)),
+            keep_alive_interval,
+        }
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn keepalives_hold_idle_connections_open() {
+        let config = timeouts(Some(Duration::from_millis(100)));
+        let listener1 = spawn_echo_listener(Some(config.clone())).await;
+        let listener2 = spawn_echo_listener(Some(config)).await;
+
+        let (con, _evt) = listener1
+            .connect(listener2.bound_url().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(con.request(b"hello".to_vec()).await.unwrap(), b"hello");
+
+        // Sit idle for well past the idle timeout
+        tokio::time::delay_for(Duration::from_millis(1000)).await;
+        assert_eq!(
+            con.request(b"still here".to_vec()).await.unwrap(),
+            b"still here"
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn idle_connections_without_keepalives_time_out() {
+        let config = timeouts(None);
+        let listener1 = spawn_echo_listener(Some(config.clone())).await;
+        let listener2 = spawn_echo_listener(Some(config)).await;
+
+        let (con, _evt) = listener1
+            .connect(listener2.bound_url().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(con.request(b"hello".to_vec()).await.unwrap(), b"hello");
+
+        tokio::time::delay_for(Duration::from_millis(1000)).await;
+        assert!(con.request(b"anyone there?".to_vec()).await.is_err());
+    }
 }
@@ -25,6 +25,9 @@ pub enum DbName {
     ElementVaultPrivateEntries,
     /// Vault database: KV store of chain headers, keyed by address
     ElementVaultHeaders,
+    /// Vault database: KV store of the chunks of large public entries,
+    /// and of the manifest listing them keyed by entry address
+    ElementVaultEntryChunks,
     /// Vault database: KVV store of chain metadata, storing relationships
     MetaVaultSys,
     /// Vault database: Kv store of links
@@ -57,12 +60,17 @@ pub enum DbName {
     AuthoredDhtOps,
     /// Integrated [DhtOp]s KV store
     IntegratedDhtOps,
+    /// int KV store of the [DhtOpHash]es of integrated [DhtOp]s
+    /// in the order they were integrated
+    IntegratedDhtOpsSequence,
     /// Integration Queue of [DhtOp]s KV store where key is [DhtOpHash]
     IntegrationLimbo,
     /// Place for [DhtOp]s waiting to be validated to hang out. KV store where key is a [DhtOpHash]
     ValidationLimbo,
     /// KVV store to accumulate validation receipts for a published EntryHash
     ValidationReceipts,
+    /// KVV store indexing integrated [DhtOp]s by the [EntryHash]es they reference
+    EntryOpsIndex,
 }
 
 impl DbName {
@@ -74,6 +82,7 @@ impl DbName {
             ElementVaultPublicEntries => Single,
             ElementVaultPrivateEntries => Single,
             ElementVaultHeaders => Single,
+            ElementVaultEntryChunks => Single,
             MetaVaultSys => Multi,
             MetaVaultLinks => Single,
             MetaVaultMisc => Single,
@@ -89,9 +98,11 @@ impl DbName {
             EntryDef => Single,
             AuthoredDhtOps => Single,
             IntegratedDhtOps => Single,
+            IntegratedDhtOpsSequence => SingleInt,
             IntegrationLimbo => Single,
             ValidationLimbo => Single,
             ValidationReceipts => Multi,
+            EntryOpsIndex => Multi,
         }
     }
 }
@@ -124,6 +135,9 @@ lazy_static! {
     /// The key to access the ChainHeaders database
     pub static ref ELEMENT_VAULT_HEADERS: DbKey<SingleStore> =
     DbKey::<SingleStore>::new(DbName::ElementVaultHeaders);
+    /// The key to access the EntryChunks database
+    pub static ref ELEMENT_VAULT_ENTRY_CHUNKS: DbKey<SingleStore> =
+    DbKey::<SingleStore>::new(DbName::ElementVaultEntryChunks);
     /// The key to access the Metadata database of the Vault
     pub static ref META_VAULT_SYS: DbKey<MultiStore> = DbKey::new(DbName::MetaVaultSys);
     /// The key to access the links database of the Vault
@@ -156,12 +170,17 @@ lazy_static! {
     pub static ref AUTHORED_DHT_OPS: DbKey<SingleStore> = DbKey::new(DbName::AuthoredDhtOps);
     /// The key to access the IntegratedDhtOps database
     pub static ref INTEGRATED_DHT_OPS: DbKey<SingleStore> = DbKey::new(DbName::IntegratedDhtOps);
+    /// The key to access the IntegratedDhtOpsSequence database
+    pub static ref INTEGRATED_DHT_OPS_SEQUENCE: DbKey<IntegerStore> =
+    DbKey::new(DbName::IntegratedDhtOpsSequence);
     /// The key to access the IntegrationLimbo database
     pub static ref INTEGRATION_LIMBO: DbKey<SingleStore> = DbKey::new(DbName::IntegrationLimbo);
     /// The key to access the IntegrationLimbo database
     pub static ref VALIDATION_LIMBO: DbKey<SingleStore> = DbKey::new(DbName::ValidationLimbo);
     /// The key to access the ValidationReceipts database
     pub static ref VALIDATION_RECEIPTS: DbKey<MultiStore> = DbKey::new(DbName::ValidationReceipts);
+    /// The key to access the EntryOpsIndex database
+    pub static ref ENTRY_OPS_INDEX: DbKey<MultiStore> = DbKey::new(DbName::EntryOpsIndex);
 }
 
 lazy_static! {
@@ -200,12 +219,51 @@ pub(super) fn get_db<V: 'static + Copy + Send + Sync>(
     Ok(db)
 }
 
+/// Create the databases of an environment without registering them,
+/// for a copy of an environment that isn't opened through [GetDb]
+pub(super) fn create_databases(rkv: &Rkv, kind: &EnvironmentKind) -> DatabaseResult<()> {
+    register_databases(rkv, kind, &mut UniversalMap::new())
+}
+
+/// The names of the databases in each kind of environment.
+/// This must list the same databases as [register_databases].
+pub(super) fn db_names(kind: &EnvironmentKind) -> Vec<DbName> {
+    use DbName::*;
+    match kind {
+        EnvironmentKind::Cell(_) => vec![
+            ElementVaultPublicEntries,
+            ElementVaultPrivateEntries,
+            ElementVaultHeaders,
+            ElementVaultEntryChunks,
+            MetaVaultSys,
+            MetaVaultLinks,
+            MetaVaultMisc,
+            ChainSequence,
+            ElementCacheEntries,
+            ElementCacheHeaders,
+            MetaCacheSys,
+            MetaCacheLinks,
+            MetaCacheStatus,
+            AuthoredDhtOps,
+            IntegratedDhtOps,
+            IntegratedDhtOpsSequence,
+            IntegrationLimbo,
+            ValidationLimbo,
+            ValidationReceipts,
+            EntryOpsIndex,
+        ],
+        EnvironmentKind::Conductor => vec![ConductorState],
+        EnvironmentKind::Wasm => vec![Wasm, DnaDef, EntryDef],
+    }
+}
+
 fn register_databases(env: &Rkv, kind: &EnvironmentKind, um: &mut DbMap) -> DatabaseResult<()> {
     match kind {
         EnvironmentKind::Cell(_) => {
             register_db(env, um, &*ELEMENT_VAULT_PUBLIC_ENTRIES)?;
             register_db(env, um, &*ELEMENT_VAULT_PRIVATE_ENTRIES)?;
             register_db(env, um, &*ELEMENT_VAULT_HEADERS)?;
+            register_db(env, um, &*ELEMENT_VAULT_ENTRY_CHUNKS)?;
             register_db(env, um, &*META_VAULT_SYS)?;
             register_db(env, um, &*META_VAULT_LINKS)?;
             register_db(env, um, &*META_VAULT_MISC)?;
@@ -217,9 +275,11 @@ fn register_databases(env: &Rkv, kind: &EnvironmentKind, um: &mut DbMap) -> Data
             register_db(env, um, &*CACHE_STATUS_META)?;
             register_db(env, um, &*AUTHORED_DHT_OPS)?;
             register_db(env, um, &*INTEGRATED_DHT_OPS)?;
+            register_db(env, um, &*INTEGRATED_DHT_OPS_SEQUENCE)?;
             register_db(env, um, &*INTEGRATION_LIMBO)?;
             register_db(env, um, &*VALIDATION_LIMBO)?;
             register_db(env, um, &*VALIDATION_RECEIPTS)?;
+            register_db(env, um, &*ENTRY_OPS_INDEX)?;
         }
         EnvironmentKind::Conductor => {
             register_db(env, um, &*CONDUCTOR_STATE)?;
@@ -1,7 +1,7 @@
 //! Functions dealing with obtaining and referencing singleton LMDB environments
 
 use crate::{
-    db::{get_db, initialize_databases, DbKey, GetDb},
+    db::{get_db, initialize_databases, DbKey, DbName, GetDb},
     error::{DatabaseError, DatabaseResult},
     transaction::{Reader, Writer},
 };
@@ -18,6 +18,16 @@ use std::{
     sync::Arc,
 };
 
+mod backup;
+mod config;
+mod map_size;
+mod reader_pool;
+mod stat;
+pub use config::{EnvironmentConfig, DEFAULT_ENTRY_CHUNK_THRESHOLD};
+pub use map_size::{MapFullError, MapSize, DEFAULT_MAX_MAP_SIZE};
+pub use reader_pool::{ReaderPool, DEFAULT_MAX_READER_AGE};
+pub use stat::DbStat;
+
 const DEFAULT_INITIAL_MAP_SIZE: usize = 100 * 1024 * 1024; // 100MB
 const MAX_DBS: u32 = 32;
 
@@ -90,6 +100,7 @@ pub struct EnvironmentRead {
     kind: EnvironmentKind,
     path: PathBuf,
     keystore: KeystoreSender,
+    config: EnvironmentConfig,
 }
 
 impl EnvironmentRead {
@@ -117,6 +128,11 @@ impl EnvironmentRead {
     pub fn path(&self) -> &PathBuf {
         &self.path
     }
+
+    /// Public app entries with more serialized bytes than this are stored in chunks
+    pub fn entry_chunk_threshold(&self) -> usize {
+        self.config.entry_chunk_threshold
+    }
 }
 
 impl GetDb for EnvironmentWrite {
@@ -151,6 +167,17 @@ impl EnvironmentWrite {
         path_prefix: &Path,
         kind: EnvironmentKind,
         keystore: KeystoreSender,
+    ) -> DatabaseResult<EnvironmentWrite> {
+        Self::new_with_config(path_prefix, kind, keystore, EnvironmentConfig::default())
+    }
+
+    /// Create an environment with the given settings.
+    /// If the environment is already open its settings are left as they are.
+    pub fn new_with_config(
+        path_prefix: &Path,
+        kind: EnvironmentKind,
+        keystore: KeystoreSender,
+        config: EnvironmentConfig,
     ) -> DatabaseResult<EnvironmentWrite> {
         let mut map = ENVIRONMENTS.write();
         let path = path_prefix.join(kind.path());
@@ -162,7 +189,7 @@ impl EnvironmentWrite {
             hash_map::Entry::Occupied(e) => e.get().clone(),
             hash_map::Entry::Vacant(e) => e
                 .insert({
-                    let rkv = rkv_builder(None, None)(&path)?;
+                    let rkv = rkv_builder(Some(config.map_size.initial), None)(&path)?;
                     tracing::debug!("Initializing databases for path {:?}", path);
                     initialize_databases(&rkv, &kind)?;
                     EnvironmentWrite(EnvironmentRead {
@@ -170,6 +197,7 @@ impl EnvironmentWrite {
                         kind,
                         keystore,
                         path,
+                        config,
                     })
                 })
                 .clone(),
@@ -227,13 +255,39 @@ impl EnvironmentKind {
 /// A reference to a read-only EnvironmentRead.
 /// This has the distinction of being unable to create a read-write transaction,
 /// because unlike [EnvironmentWriteRef], this does not implement WriteManager
+/// and doesn't hand out the wrapped Rkv outside this crate.
+///
+/// It can open a read transaction:
+/// ```
+/// use holochain_state::{env::{EnvironmentRead, ReadManager}, error::DatabaseResult};
+/// fn read(env: &EnvironmentRead) -> DatabaseResult<()> {
+///     env.guard().with_reader(|_reader| Ok(()))
+/// }
+/// ```
+///
+/// But not a read-write one:
+/// ```compile_fail
+/// use holochain_state::{env::{EnvironmentRead, WriteManager}, error::DatabaseResult};
+/// fn write(env: &EnvironmentRead) -> DatabaseResult<()> {
+///     env.guard().with_commit(|_writer| Ok(()))
+/// }
+/// ```
+///
+/// Not even through the Rkv:
+/// ```compile_fail
+/// use holochain_state::env::EnvironmentRead;
+/// fn write(env: &EnvironmentRead) {
+///     let guard = env.guard();
+///     guard.rkv().write().unwrap();
+/// }
+/// ```
 pub struct EnvironmentReadRef<'e> {
     rkv: RwLockReadGuard<'e, Rkv>,
 }
 
 impl<'e> EnvironmentReadRef<'e> {
     /// Access the wrapped Rkv
-    pub fn rkv(&self) -> &Rkv {
+    pub(crate) fn rkv(&self) -> &Rkv {
         &self.rkv
     }
 }
@@ -260,8 +314,25 @@ pub trait WriteManager<'e> {
     where
         E: From<DatabaseError>,
         F: FnOnce(&mut Writer) -> Result<R, E>;
+
+    /// Run several flushes in one read-write transaction and commit them together.
+    /// If any flush fails, none of them are committed.
+    fn with_commit_multi<E>(&self, flushes: Vec<FlushFn<'_, E>>) -> Result<(), E>
+    where
+        E: From<DatabaseError> + Send,
+    {
+        self.with_commit(|writer| {
+            for flush in flushes {
+                flush(writer)?;
+            }
+            Ok(())
+        })
+    }
 }
 
+/// One of the flushes committed together by [WriteManager::with_commit_multi]
+pub type FlushFn<'f, E> = Box<dyn FnOnce(&mut Writer) -> Result<(), E> + Send + 'f>;
+
 impl<'e> ReadManager<'e> for EnvironmentReadRef<'e> {
     fn reader(&'e self) -> DatabaseResult<Reader<'e>> {
         let reader = Reader::from(self.rkv.read()?);
@@ -323,3 +394,31 @@ impl<'e> ReadManager<'e> for EnvironmentWriteRef<'e> {
         self.0.with_reader(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::INTEGRATION_LIMBO, test_utils::test_cell_env};
+    use rkv::SingleStore;
+
+    fn put(db: SingleStore, key: &'static [u8]) -> FlushFn<'static, DatabaseError> {
+        Box::new(move |writer| Ok(db.put(writer, key, &rkv::Value::Blob(b"value"))?))
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn failed_flush_rolls_back_the_others() -> DatabaseResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let db = env.get_db(&*INTEGRATION_LIMBO)?;
+        let fail: FlushFn<'_, DatabaseError> = Box::new(|_| Err(DatabaseError::InvalidValue));
+
+        let result = env.guard().with_commit_multi(vec![put(db, b"first"), fail]);
+        assert_eq!(result, Err(DatabaseError::InvalidValue));
+        assert_eq!(env.db_len(&DbName::IntegrationLimbo)?, 0);
+
+        env.guard()
+            .with_commit_multi(vec![put(db, b"first"), put(db, b"second")])?;
+        assert_eq!(env.db_len(&DbName::IntegrationLimbo)?, 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+//! Hot backups of an environment.
+//!
+//! A backup copies every database from a single read transaction,
+//! so it is a consistent snapshot even while other tasks keep writing.
+
+use super::{rkv_builder, EnvironmentWrite};
+use crate::{
+    db::{create_databases, db_names},
+    error::DatabaseResult,
+};
+use rkv::StoreOptions;
+use std::path::Path;
+
+impl EnvironmentWrite {
+    /// Copy the current state of this environment into a new environment
+    /// under `path_prefix`, laid out so it can be opened with
+    /// [EnvironmentWrite::new] using the same prefix and [EnvironmentKind].
+    ///
+    /// Writes made while the backup runs are not included.
+    /// The backup must not already exist.
+    ///
+    /// [EnvironmentKind]: super::EnvironmentKind
+    pub fn backup_to(&self, path_prefix: &Path) -> DatabaseResult<()> {
+        let path = path_prefix.join(self.kind().path());
+        std::fs::create_dir_all(&path)?;
+
+        let g = self.guard();
+        let source = g.rkv();
+        let backup = rkv_builder(Some(source.info()?.map_size()), None)(&path)?;
+        create_databases(&backup, self.kind())?;
+
+        // One reader for every database so they all see the same state
+        let reader = source.read()?;
+        let mut writer = backup.write()?;
+        for name in db_names(self.kind()) {
+            let name = name.to_string();
+            // Multi and integer stores are opened as single stores
+            // so every item, including duplicates, is copied as is
+            let from = source.open_single(name.as_str(), StoreOptions::default())?;
+            let to = backup.open_single(name.as_str(), StoreOptions::default())?;
+            for item in from.iter_start(&reader)? {
+                if let (k, Some(v)) = item? {
+                    to.put(&mut writer, k, &v)?;
+                }
+            }
+        }
+        writer.commit()?;
+        tracing::debug!("Backed up {:?} to {:?}", self.path(), path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{DbName, ENTRY_OPS_INDEX, INTEGRATION_LIMBO},
+        env::EnvironmentKind,
+        prelude::*,
+        test_utils::{test_cell_env, test_keystore},
+    };
+    use tempdir::TempDir;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn backup_only_has_state_from_before_it_was_taken() -> DatabaseResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let limbo = env.get_db(&*INTEGRATION_LIMBO)?;
+        let index = env.get_db(&*ENTRY_OPS_INDEX)?;
+        let value = rkv::Value::Blob(b"value");
+
+        env.guard().with_commit(|writer| -> DatabaseResult<()> {
+            limbo.put(writer, b"before", &value)?;
+            index.put(writer, b"entry", &rkv::Value::Blob(b"op 1"))?;
+            index.put(writer, b"entry", &rkv::Value::Blob(b"op 2"))?;
+            Ok(())
+        })?;
+
+        let backup_dir = TempDir::new("holochain-test-backups").unwrap();
+        env.backup_to(backup_dir.path())?;
+
+        env.guard().with_commit(|writer| -> DatabaseResult<()> {
+            limbo.put(writer, b"after", &value)?;
+            index.put(writer, b"entry", &rkv::Value::Blob(b"op 3"))?;
+            Ok(())
+        })?;
+        assert_eq!(env.db_len(&DbName::IntegrationLimbo)?, 2);
+
+        let kind = EnvironmentKind::Cell(holochain_types::test_utils::fake_cell_id(1));
+        let backup: EnvironmentRead =
+            EnvironmentWrite::new(backup_dir.path(), kind, test_keystore())?.into();
+        assert_eq!(backup.db_len(&DbName::IntegrationLimbo)?, 1);
+        assert_eq!(backup.db_len(&DbName::EntryOpsIndex)?, 2);
+
+        let limbo = backup.get_db(&*INTEGRATION_LIMBO)?;
+        let g = backup.guard();
+        let r = g.reader()?;
+        assert!(limbo.get(&r, b"before")?.is_some());
+        assert!(limbo.get(&r, b"after")?.is_none());
+        Ok(())
+    }
+}
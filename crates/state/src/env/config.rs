@@ -0,0 +1,25 @@
+//! The settings an environment is opened with.
+
+use super::MapSize;
+
+/// The default size past which public app entries are stored in chunks, in bytes
+pub const DEFAULT_ENTRY_CHUNK_THRESHOLD: usize = 1_000_000;
+
+/// The settings an environment is opened with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnvironmentConfig {
+    /// How large the memory map starts and how large it may grow
+    pub map_size: MapSize,
+    /// Public app entries with more serialized bytes than this
+    /// are stored in chunks by the element vault
+    pub entry_chunk_threshold: usize,
+}
+
+impl Default for EnvironmentConfig {
+    fn default() -> Self {
+        Self {
+            map_size: MapSize::default(),
+            entry_chunk_threshold: DEFAULT_ENTRY_CHUNK_THRESHOLD,
+        }
+    }
+}
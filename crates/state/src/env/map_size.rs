@@ -0,0 +1,228 @@
+//! Growing an environment's memory map when it fills up.
+//!
+//! LMDB fails any write that would go past the end of the memory map.
+//! Rather than sizing every map for the worst case up front,
+//! an environment starts with a modest map and doubles it, up to a cap,
+//! when a write runs out of room.
+
+use super::{EnvironmentWrite, WriteManager, DEFAULT_INITIAL_MAP_SIZE};
+use crate::{
+    error::{DatabaseError, DatabaseResult},
+    transaction::Writer,
+};
+use std::time::Duration;
+
+/// The default cap on how large a map can grow
+pub const DEFAULT_MAX_MAP_SIZE: usize = 16 * DEFAULT_INITIAL_MAP_SIZE;
+
+/// How long growing the map waits for the environment's guards to be dropped
+/// before letting them be taken again
+const GROW_LOCK_WAIT: Duration = Duration::from_millis(100);
+
+/// How many times growing the map waits for the environment's guards
+const GROW_LOCK_ATTEMPTS: usize = 50;
+
+/// How large an environment's memory map starts and how large it may grow, in bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapSize {
+    /// The size of the map when the environment is opened
+    pub initial: usize,
+    /// The map is never grown past this size
+    pub max: usize,
+}
+
+impl Default for MapSize {
+    fn default() -> Self {
+        Self {
+            initial: DEFAULT_INITIAL_MAP_SIZE,
+            max: DEFAULT_MAX_MAP_SIZE,
+        }
+    }
+}
+
+impl EnvironmentWrite {
+    /// The current size of the memory map
+    pub fn map_size(&self) -> DatabaseResult<usize> {
+        Ok(self.guard().rkv().info()?.map_size())
+    }
+
+    /// Double the memory map, up to the configured max.
+    /// Returns false if the map is already as large as it can be,
+    /// or if the environment was never free to grow it.
+    ///
+    /// LMDB can only resize a map with no open transactions, so this waits
+    /// until every guard on the environment has been dropped. A thread holding
+    /// a guard while calling this would wait on itself, so each wait is bounded
+    /// and the lock given up in between, letting any other guards be taken
+    /// and dropped again.
+    pub fn grow_map_size(&self) -> DatabaseResult<bool> {
+        let rkv =
+            match (0..GROW_LOCK_ATTEMPTS).find_map(|_| self.0.arc.try_write_for(GROW_LOCK_WAIT)) {
+                Some(rkv) => rkv,
+                None => {
+                    tracing::warn!(
+                        "Couldn't grow the memory map of {:?} while its guards were held",
+                        self.0.path
+                    );
+                    return Ok(false);
+                }
+            };
+        let current = rkv.info()?.map_size();
+        if current >= self.0.config.map_size.max {
+            return Ok(false);
+        }
+        let new_size = current.saturating_mul(2).min(self.0.config.map_size.max);
+        rkv.set_map_size(new_size)?;
+        tracing::info!(
+            "Grew the memory map of {:?} from {} to {} bytes",
+            self.0.path,
+            current,
+            new_size
+        );
+        Ok(true)
+    }
+
+    /// Like [WriteManager::with_commit] but if the write fails because the
+    /// memory map is full, the map is grown and the closure run again.
+    ///
+    /// Nothing from the failed attempt is committed, so the closure must be
+    /// idempotent: running it again must write everything the first run did.
+    /// A closure that consumes what it writes, e.g. by draining a buffer,
+    /// would lose that data on the retry.
+    ///
+    /// The caller must not hold a guard on this environment, or the map
+    /// can't be grown.
+    pub fn with_commit_growing<E, R, F>(&self, mut f: F) -> Result<R, E>
+    where
+        E: MapFullError,
+        F: FnMut(&mut Writer) -> Result<R, E> + Send,
+    {
+        loop {
+            // The guard is dropped at the end of this statement
+            // so growing the map doesn't wait on ourselves
+            let result = self.guard().with_commit(|writer| f(writer));
+            match result {
+                Err(e) if e.is_map_full() => {
+                    if !self.grow_map_size()? {
+                        return Err(e);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// An error which may be LMDB running out of room in the memory map
+pub trait MapFullError: From<DatabaseError> {
+    /// Whether this error is LMDB running out of room in the memory map
+    fn is_map_full(&self) -> bool;
+}
+
+impl MapFullError for DatabaseError {
+    fn is_map_full(&self) -> bool {
+        match self {
+            DatabaseError::LmdbStoreError(e) => matches!(e.get_ref(), rkv::StoreError::MapFull),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{DbName, INTEGRATION_LIMBO},
+        env::{EnvironmentConfig, EnvironmentKind},
+        prelude::*,
+        test_utils::test_keystore,
+    };
+    use holochain_types::test_utils::fake_cell_id;
+    use tempdir::TempDir;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn write_past_the_map_grows_it() -> DatabaseResult<()> {
+        let tmpdir = TempDir::new("holochain-test-environments").unwrap();
+        let map_size = MapSize {
+            initial: 1024 * 1024,
+            max: 8 * 1024 * 1024,
+        };
+        let env = EnvironmentWrite::new_with_config(
+            tmpdir.path(),
+            EnvironmentKind::Cell(fake_cell_id(1)),
+            test_keystore(),
+            EnvironmentConfig {
+                map_size,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(env.map_size()?, map_size.initial);
+
+        let db = env.get_db(&*INTEGRATION_LIMBO)?;
+        let value = vec![7u8; 64 * 1024];
+        // About 2.5MB, more than the initial map can hold
+        let write = |writer: &mut Writer| -> DatabaseResult<()> {
+            for i in 0..40u32 {
+                db.put(writer, i.to_be_bytes(), &rkv::Value::Blob(&value))?;
+            }
+            Ok(())
+        };
+
+        let result = env.guard().with_commit(write);
+        assert!(result.unwrap_err().is_map_full());
+
+        env.with_commit_growing(write)?;
+        assert!(env.map_size()? > map_size.initial);
+        assert_eq!(env.db_len(&DbName::IntegrationLimbo)?, 40);
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn map_stops_growing_at_the_max() -> DatabaseResult<()> {
+        let tmpdir = TempDir::new("holochain-test-environments").unwrap();
+        let map_size = MapSize {
+            initial: 1024 * 1024,
+            max: 2 * 1024 * 1024,
+        };
+        let env = EnvironmentWrite::new_with_config(
+            tmpdir.path(),
+            EnvironmentKind::Cell(fake_cell_id(1)),
+            test_keystore(),
+            EnvironmentConfig {
+                map_size,
+                ..Default::default()
+            },
+        )?;
+        assert!(env.grow_map_size()?);
+        assert_eq!(env.map_size()?, map_size.max);
+        assert!(!env.grow_map_size()?);
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn map_only_grows_once_guards_are_dropped() -> DatabaseResult<()> {
+        let tmpdir = TempDir::new("holochain-test-environments").unwrap();
+        let map_size = MapSize {
+            initial: 1024 * 1024,
+            max: 2 * 1024 * 1024,
+        };
+        let env = EnvironmentWrite::new_with_config(
+            tmpdir.path(),
+            EnvironmentKind::Cell(fake_cell_id(1)),
+            test_keystore(),
+            EnvironmentConfig {
+                map_size,
+                ..Default::default()
+            },
+        )?;
+        {
+            // Growing with a guard held gives up instead of waiting forever
+            let _guard = env.guard();
+            assert!(!env.grow_map_size()?);
+        }
+        assert_eq!(env.map_size()?, map_size.initial);
+        assert!(env.grow_map_size()?);
+        assert_eq!(env.map_size()?, map_size.max);
+        Ok(())
+    }
+}
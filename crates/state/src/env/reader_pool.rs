@@ -0,0 +1,116 @@
+//! Reuse a read-only transaction across many short reads.
+//!
+//! Opening a read transaction for every lookup (as [fresh_reader!] does)
+//! adds up when a workflow does many small reads in a row.
+//! A [ReaderPool] hands out the same reader until it is older than
+//! `max_age`, at which point it is dropped and a new one is opened.
+//! Keeping the age short matters: LMDB can't reclaim pages freed by
+//! writers while an old reader is still open.
+
+use super::ReadManager;
+use crate::{
+    error::{DatabaseError, DatabaseResult},
+    transaction::Reader,
+};
+use std::time::{Duration, Instant};
+
+/// The default longest time a pooled reader is reused before being renewed
+pub const DEFAULT_MAX_READER_AGE: Duration = Duration::from_millis(50);
+
+/// Reuses a single read-only transaction for reads that don't need to see
+/// writes made after the reader was opened.
+///
+/// The pool borrows the environment guard so, like any reader,
+/// it must not be held across an `.await`.
+pub struct ReaderPool<'e, M: ReadManager<'e>> {
+    manager: &'e M,
+    reader: Option<(Reader<'e>, Instant)>,
+    max_age: Duration,
+    opened: usize,
+}
+
+impl<'e, M: ReadManager<'e>> ReaderPool<'e, M> {
+    /// Create a pool from an environment guard
+    pub fn new(manager: &'e M) -> Self {
+        Self {
+            manager,
+            reader: None,
+            max_age: DEFAULT_MAX_READER_AGE,
+            opened: 0,
+        }
+    }
+
+    /// Change how long a reader is reused before it is renewed
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Get the pooled reader, opening a new one if there
+    /// is none or the current one is too old.
+    pub fn reader(&mut self) -> DatabaseResult<&Reader<'e>> {
+        let expired = match &self.reader {
+            Some((_, opened_at)) => opened_at.elapsed() >= self.max_age,
+            None => true,
+        };
+        if expired {
+            // Drop the old reader before opening a new one
+            self.reader = None;
+            self.reader = Some((self.manager.reader()?, Instant::now()));
+            self.opened += 1;
+        }
+        Ok(&self
+            .reader
+            .as_ref()
+            .expect("Reader was set if it was missing")
+            .0)
+    }
+
+    /// Run a closure with the pooled reader
+    pub fn with_reader<E, R, F>(&mut self, f: F) -> Result<R, E>
+    where
+        E: From<DatabaseError>,
+        F: FnOnce(&Reader<'e>) -> Result<R, E>,
+    {
+        f(self.reader()?)
+    }
+
+    /// Drop the current reader so writers can reclaim space.
+    /// The next read will open a new one.
+    pub fn release(&mut self) {
+        self.reader = None;
+    }
+
+    /// How many read transactions this pool has opened
+    pub fn transactions_opened(&self) -> usize {
+        self.opened
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_cell_env;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn reuses_reader_until_too_old() -> DatabaseResult<()> {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let g = env.guard();
+        let mut pool = ReaderPool::new(&g).with_max_age(Duration::from_millis(20));
+
+        for _ in 0..10 {
+            pool.reader()?;
+        }
+        assert_eq!(pool.transactions_opened(), 1);
+
+        std::thread::sleep(Duration::from_millis(30));
+        pool.reader()?;
+        assert_eq!(pool.transactions_opened(), 2);
+
+        pool.release();
+        pool.reader()?;
+        assert_eq!(pool.transactions_opened(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+//! Sizes of an environment's databases, read from LMDB's own bookkeeping.
+//!
+//! LMDB keeps page and entry counts for every database up to date as it
+//! writes, so asking for them costs the same however large the database is.
+//! rkv doesn't expose them, so the stats are read through the cursor
+//! a store opens on a reader that stands in for the real one.
+
+use super::EnvironmentRead;
+use crate::{db::DbName, error::DatabaseResult};
+use lmdb::Cursor;
+use rkv::{Database, RoCursor, StoreError, StoreOptions, Value};
+use std::cell::Cell;
+
+/// The LMDB stats of one database
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DbStat {
+    /// The size of a page in bytes
+    pub page_size: usize,
+    /// The branch, leaf and overflow pages the database takes up
+    pub pages: usize,
+    /// The number of items, counting each duplicate value of a multi store
+    pub entries: usize,
+}
+
+impl EnvironmentRead {
+    /// The LMDB stats of one of this environment's databases
+    pub fn db_stat(&self, name: &DbName) -> DatabaseResult<DbStat> {
+        let g = self.guard();
+        // Multi stores are opened as single stores on the same LMDB database,
+        // which keeps the flags it was created with
+        let db = g
+            .rkv()
+            .open_single(name.to_string().as_str(), StoreOptions::default())?;
+        let reader = g.reader()?;
+        let stat_reader = StatReader {
+            reader: &reader,
+            stat: Cell::new(None),
+        };
+        // Any key will do, the stat reader never looks it up
+        db.get(&stat_reader, [0])?;
+        Ok(stat_reader
+            .stat
+            .get()
+            .expect("a single store reads through the reader it is given"))
+    }
+
+    /// The number of bytes in the pages of one of this environment's databases
+    pub fn db_size(&self, name: &DbName) -> DatabaseResult<usize> {
+        let stat = self.db_stat(name)?;
+        Ok(stat.pages * stat.page_size)
+    }
+
+    /// The number of items in one of this environment's databases.
+    /// A multi store counts each duplicate value.
+    pub fn db_len(&self, name: &DbName) -> DatabaseResult<usize> {
+        Ok(self.db_stat(name)?.entries)
+    }
+}
+
+/// Stands in for a reader so a store's get hands over the database it
+/// would read from. Rather than reading, it records the database's stats.
+struct StatReader<'r, R: rkv::Readable> {
+    reader: &'r R,
+    stat: Cell<Option<DbStat>>,
+}
+
+impl<'r, R: rkv::Readable> rkv::Readable for StatReader<'r, R> {
+    fn get<K: AsRef<[u8]>>(&self, db: Database, _k: &K) -> Result<Option<Value>, StoreError> {
+        let cursor = self.reader.open_ro_cursor(db)?;
+        let mut stat = lmdb_sys::MDB_stat {
+            ms_psize: 0,
+            ms_depth: 0,
+            ms_branch_pages: 0,
+            ms_leaf_pages: 0,
+            ms_overflow_pages: 0,
+            ms_entries: 0,
+        };
+        // SAFETY: the cursor belongs to the open read transaction,
+        // which outlives this call
+        let rc = unsafe {
+            let txn = lmdb_sys::mdb_cursor_txn(cursor.cursor());
+            lmdb_sys::mdb_stat(txn, db.dbi(), &mut stat)
+        };
+        if rc != 0 {
+            return Err(StoreError::LmdbError(lmdb::Error::from_err_code(rc)));
+        }
+        self.stat.set(Some(DbStat {
+            page_size: stat.ms_psize as usize,
+            pages: stat.ms_branch_pages + stat.ms_leaf_pages + stat.ms_overflow_pages,
+            entries: stat.ms_entries,
+        }));
+        Ok(None)
+    }
+
+    fn open_ro_cursor(&self, db: Database) -> Result<RoCursor, StoreError> {
+        self.reader.open_ro_cursor(db)
+    }
+}
@@ -74,6 +74,9 @@ pub enum DatabaseError {
     #[error(transparent)]
     ElementGroupError(#[from] ElementGroupError),
 
+    #[error("Chunk {1:?} of the chunked entry {0:?} is missing")]
+    MissingEntryChunk(holo_hash::EntryHash, holo_hash::EntryHash),
+
     #[error("Empty keys cannot be used with lmdb")]
     EmptyKey,
 
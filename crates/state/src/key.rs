@@ -1,6 +1,6 @@
 //! Traits for defining keys and values of databases
 
-use holo_hash::{HashType, HoloHash, PrimitiveHashType};
+use holo_hash::{AnyDhtHash, HoloHash, PrimitiveHashType};
 use holochain_serialized_bytes::prelude::*;
 pub use prefix::*;
 use serde::{de::DeserializeOwned, Serialize};
@@ -80,11 +80,29 @@ impl From<IntKey> for u32 {
     }
 }
 
-impl<T: HashType + Send + Sync> BufKey for HoloHash<T> {
-    fn from_key_bytes_or_friendly_panic(bytes: &[u8]) -> Self {
+macro_rules! primitive_hash_buf_key {
+    ($($hash_type:ident),*) => {
+        $(
+            impl BufKey for HoloHash<holo_hash::hash_type::$hash_type> {
+                fn from_key_bytes_or_friendly_panic(bytes: &[u8]) -> Self {
+                    Self::from_raw_bytes(bytes.to_vec())
+                }
+            }
+        )*
+    };
+}
+
+primitive_hash_buf_key!(Agent, Dna, DhtOp, Entry, Header, NetId, Wasm);
+
+impl BufKey for AnyDhtHash {
+    fn from_key_bytes_or_friendly_panic(_bytes: &[u8]) -> Self {
         // FIXME: change after [ B-02112 ]
-        tracing::error!("This is NOT correct for AnyDhtHash!");
-        Self::from_raw_bytes_and_type(bytes.to_vec(), T::default())
+        // Keys don't include the hash's prefix so whether it was an entry
+        // or a header hash is lost. Guessing would silently misroute it.
+        panic!(
+            "AnyDhtHash keys don't record whether they are an entry or a header hash \
+            so they can't be read back from the database"
+        )
     }
 }
 
@@ -35,6 +35,7 @@ pub enum TestWasm {
     SerRegression,
     SysTime,
     Validate,
+    ValidateDeps,
     ValidateLink,
     ValidateInvalid,
     ValidateCreateLinkInvalid,
@@ -75,6 +76,7 @@ impl From<TestWasm> for ZomeName {
             TestWasm::SerRegression => "ser_regression",
             TestWasm::SysTime => "sys_time",
             TestWasm::Validate => "validate",
+            TestWasm::ValidateDeps => "validate_deps",
             TestWasm::ValidateLink => "validate_link",
             TestWasm::ValidateInvalid => "validate_invalid",
             TestWasm::ValidateCreateLinkInvalid => "validate_link_add_invalid",
@@ -149,6 +151,9 @@ impl From<TestWasm> for DnaWasm {
             TestWasm::Validate => {
                 get_code("wasm32-unknown-unknown/release/test_wasm_validate.wasm")
             }
+            TestWasm::ValidateDeps => {
+                get_code("wasm32-unknown-unknown/release/test_wasm_validate_deps.wasm")
+            }
             TestWasm::ValidateLink => {
                 get_code("wasm32-unknown-unknown/release/test_wasm_validate_link.wasm")
             }
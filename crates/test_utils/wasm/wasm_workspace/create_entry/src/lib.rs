@@ -21,3 +21,18 @@ fn create_entry(_: ()) -> ExternResult<HeaderHash> {
 fn get_entry(_: ()) -> ExternResult<GetOutput> {
     Ok(GetOutput::new(get!(hash_entry!(post())?)?))
 }
+
+#[hdk_extern]
+fn verify_element(element: Element) -> ExternResult<ElementVerification> {
+    Ok(verify_element!(element)?)
+}
+
+#[hdk_extern]
+fn entry_def_for_post(_: ()) -> ExternResult<EntryDefForOutput> {
+    let element = get!(create_entry!(post())?)?;
+    let entry_def = match element.and_then(|element| element.header().entry_type().cloned()) {
+        Some(entry_type) => entry_def_for!(entry_type)?,
+        None => None,
+    };
+    Ok(EntryDefForOutput::new(entry_def))
+}
@@ -25,10 +25,40 @@ impl CounTree {
     pub fn ensure(countree: CounTree) -> ExternResult<HeaderHash> {
         match get!(hash_entry!(countree)?)? {
             Some(element) => Ok(element.header_address().to_owned()),
-            None => Ok(create_entry!(countree)?)
+            None => Ok(create_entry!(countree)?),
         }
     }
 
+    /// return the GetOutput for the header hash, without the crud metadata
+    pub fn header_element(header_hash: HeaderHash) -> ExternResult<GetOutput> {
+        Ok(GetOutput::new(get!(header_hash)?))
+    }
+
+    /// return the GetOutput for the entry hash, without the crud metadata
+    pub fn entry_element(entry_hash: EntryHash) -> ExternResult<GetOutput> {
+        Ok(GetOutput::new(get!(entry_hash)?))
+    }
+
+    /// return the GetOutput for the latest live update of the entry hash
+    pub fn latest_element(entry_hash: EntryHash) -> ExternResult<GetOutput> {
+        Ok(GetOutput::new(get!(entry_hash, GetOptions::latest())?))
+    }
+
+    /// return the latest live update of the entry hash and the updates followed to it
+    pub fn latest_branch(entry_hash: EntryHash) -> ExternResult<GetLatestOutput> {
+        Ok(GetLatestOutput::new(get_latest!(entry_hash)?))
+    }
+
+    /// return the elements for many hashes, without the crud metadata
+    pub fn elements(hashes: Vec<AnyDhtHash>) -> ExternResult<GetManyOutput> {
+        Ok(GetManyOutput::new(get_many!(hashes)?))
+    }
+
+    /// return the latest live updates of the elements for many hashes
+    pub fn latest_elements(hashes: Vec<AnyDhtHash>) -> ExternResult<GetManyOutput> {
+        Ok(GetManyOutput::new(get_many!(hashes, GetOptions::latest())?))
+    }
+
     pub fn header_details(header_hash: HeaderHash) -> ExternResult<GetDetailsOutput> {
         Ok(GetDetailsOutput::new(get_details!(header_hash)?))
     }
@@ -38,15 +68,36 @@ impl CounTree {
         Ok(GetDetailsOutput::new(get_details!(entry_hash)?))
     }
 
+    /// return the GetDetailsOutput for the entry hash with the signature of every header
+    pub fn signed_entry_details(entry_hash: EntryHash) -> ExternResult<GetDetailsOutput> {
+        Ok(GetDetailsOutput::new(get_details!(
+            entry_hash,
+            GetOptions::default(),
+            true
+        )?))
+    }
+
+    /// return the GetDetailsOutput for many header or entry hashes at once
+    pub fn details_batch(hashes: Vec<AnyDhtHash>) -> ExternResult<GetDetailsBatchOutput> {
+        Ok(GetDetailsBatchOutput::new(get_details_batch!(hashes)?))
+    }
+
+    /// gets the details of the default countree entry over and over
+    pub fn entry_details_times(times: u32) -> ExternResult<()> {
+        let entry_hash = hash_entry!(Self::default())?;
+        for _ in 0..times {
+            get_details!(entry_hash.clone())?;
+        }
+        Ok(())
+    }
+
     /// increments the given header hash by 1 or creates it if not found
     /// this is silly as being offline resets the counter >.<
     pub fn incsert(header_hash: HeaderHash) -> ExternResult<HeaderHash> {
         let current: CounTree = match get!(header_hash.clone())? {
-            Some(element) => {
-                match element.entry().to_app_option()? {
-                    Some(v) => v,
-                    None => return Self::new(),
-                }
+            Some(element) => match element.entry().to_app_option()? {
+                Some(v) => v,
+                None => return Self::new(),
             },
             None => return Self::new(),
         };
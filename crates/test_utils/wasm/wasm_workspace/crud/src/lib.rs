@@ -1,4 +1,6 @@
 use hdk3::prelude::*;
+use test_wasm_common::AnyDhtHashes;
+use test_wasm_common::TestInt;
 mod countree;
 
 entry_defs![countree::CounTree::entry_def()];
@@ -8,6 +10,36 @@ fn new(_: ()) -> ExternResult<HeaderHash> {
     countree::CounTree::new()
 }
 
+#[hdk_extern]
+fn header_element(header_hash: HeaderHash) -> ExternResult<GetOutput> {
+    countree::CounTree::header_element(header_hash)
+}
+
+#[hdk_extern]
+fn entry_element(entry_hash: EntryHash) -> ExternResult<GetOutput> {
+    countree::CounTree::entry_element(entry_hash)
+}
+
+#[hdk_extern]
+fn latest_element(entry_hash: EntryHash) -> ExternResult<GetOutput> {
+    countree::CounTree::latest_element(entry_hash)
+}
+
+#[hdk_extern]
+fn latest_branch(entry_hash: EntryHash) -> ExternResult<GetLatestOutput> {
+    countree::CounTree::latest_branch(entry_hash)
+}
+
+#[hdk_extern]
+fn elements(hashes: AnyDhtHashes) -> ExternResult<GetManyOutput> {
+    countree::CounTree::elements(hashes.0)
+}
+
+#[hdk_extern]
+fn latest_elements(hashes: AnyDhtHashes) -> ExternResult<GetManyOutput> {
+    countree::CounTree::latest_elements(hashes.0)
+}
+
 #[hdk_extern]
 fn header_details(header_hash: HeaderHash) -> ExternResult<GetDetailsOutput> {
     countree::CounTree::header_details(header_hash)
@@ -18,6 +50,21 @@ fn entry_details(entry_hash: EntryHash) -> ExternResult<GetDetailsOutput> {
     countree::CounTree::entry_details(entry_hash)
 }
 
+#[hdk_extern]
+fn signed_entry_details(entry_hash: EntryHash) -> ExternResult<GetDetailsOutput> {
+    countree::CounTree::signed_entry_details(entry_hash)
+}
+
+#[hdk_extern]
+fn details_batch(hashes: AnyDhtHashes) -> ExternResult<GetDetailsBatchOutput> {
+    countree::CounTree::details_batch(hashes.0)
+}
+
+#[hdk_extern]
+fn entry_details_times(times: TestInt) -> ExternResult<()> {
+    countree::CounTree::entry_details_times(times.0)
+}
+
 #[hdk_extern]
 fn entry_hash(countree: countree::CounTree) -> ExternResult<EntryHash> {
     Ok(hash_entry!(countree)?)
@@ -21,6 +21,17 @@ fn create_link(_: ()) -> ExternResult<HeaderHash> {
     Ok(create_link!(base()?, target()?)?)
 }
 
+/// Shaped like a [CreateLinkInput] but with a [DnaHash] for the base
+#[derive(Serialize, Deserialize, SerializedBytes)]
+struct CreateLinkFromDnaInput((DnaHash, EntryHash, LinkTag));
+
+#[hdk_extern]
+fn create_link_from_dna(_: ()) -> ExternResult<HeaderHash> {
+    let input = CreateLinkFromDnaInput((zome_info!()?.dna_hash, target()?, LinkTag::new("")));
+    host_externs!(__create_link);
+    Ok(hdk3::host_fn!(__create_link, input, CreateLinkOutput)?)
+}
+
 #[hdk_extern]
 fn delete_link(input: DeleteLinkInput) -> ExternResult<HeaderHash> {
     Ok(delete_link!(input.into_inner())?)
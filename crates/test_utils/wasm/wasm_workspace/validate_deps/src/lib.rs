@@ -0,0 +1,28 @@
+use hdk3::prelude::*;
+
+#[hdk_entry(id = "post", required_validations = 5)]
+struct Post(String);
+
+/// a comment can only be validated against the post it is on
+#[hdk_entry(id = "comment", required_validations = 5)]
+struct Comment {
+    post: EntryHash,
+    body: String,
+}
+
+entry_defs![Post::entry_def(), Comment::entry_def()];
+
+#[hdk_extern]
+fn validate(entry: Entry) -> ExternResult<ValidateCallbackResult> {
+    if let Ok(Comment { post, .. }) = Comment::try_from(&entry) {
+        // defer until the post can be found
+        return Ok(match get!(post.clone())? {
+            Some(_) => ValidateCallbackResult::Valid,
+            None => ValidateCallbackResult::UnresolvedDependencies(vec![post.into()]),
+        });
+    }
+    Ok(match Post::try_from(&entry) {
+        Ok(_) => ValidateCallbackResult::Valid,
+        Err(_) => ValidateCallbackResult::Invalid("Not a post or comment".to_string()),
+    })
+}
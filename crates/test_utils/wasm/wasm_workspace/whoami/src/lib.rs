@@ -43,3 +43,15 @@ fn whoarethey(agent_pubkey: AgentPubKey) -> ExternResult<AgentInfo> {
         ZomeCallResponse::Unauthorized => unreachable!(),
     }
 }
+
+// opt in to showing up in other agents' peers
+#[hdk_extern]
+fn be_discoverable(_: ()) -> ExternResult<()> {
+    Ok(set_discoverable!(true)?)
+}
+
+// the other discoverable agents on this dna
+#[hdk_extern]
+fn peers(_: ()) -> ExternResult<PeerInfoVec> {
+    Ok(get_peers!()?)
+}
@@ -52,3 +52,8 @@ pub struct LinkTags(pub Vec<LinkTag>);
 #[repr(transparent)]
 #[serde(transparent)]
 pub struct AnchorTags(pub Vec<String>);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+#[repr(transparent)]
+#[serde(transparent)]
+pub struct AnyDhtHashes(pub Vec<AnyDhtHash>);
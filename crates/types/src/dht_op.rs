@@ -10,9 +10,11 @@ use error::{DhtOpError, DhtOpResult};
 use holo_hash::{hash_type, HashableContentBytes};
 use holochain_zome_types::{header, Entry, Header};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[allow(missing_docs)]
 pub mod error;
+pub mod external;
 
 /// A unit of DHT gossip. Used to notify an authority of new (meta)data to hold
 /// as well as changes to the status of already held data.
@@ -83,6 +85,60 @@ pub enum DhtOp {
 /// Show that this type is used as the basis
 type DhtBasis = AnyDhtHash;
 
+/// The type of a [DhtOp] or [DhtOpLight] without any of its data
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
+pub enum DhtOpType {
+    StoreElement,
+    StoreEntry,
+    RegisterAgentActivity,
+    RegisterUpdatedBy,
+    RegisterDeletedBy,
+    RegisterDeletedEntryHeader,
+    RegisterAddLink,
+    RegisterRemoveLink,
+}
+
+impl DhtOpType {
+    /// The type of hash the basis of this type of op is.
+    /// Only a [DhtOp::StoreElement] or [DhtOp::RegisterDeletedBy] is stored at a header,
+    /// the rest are stored at an entry or agent.
+    pub fn basis_type(&self) -> hash_type::AnyDht {
+        match self {
+            DhtOpType::StoreElement | DhtOpType::RegisterDeletedBy => hash_type::AnyDht::Header,
+            DhtOpType::StoreEntry
+            | DhtOpType::RegisterAgentActivity
+            | DhtOpType::RegisterUpdatedBy
+            | DhtOpType::RegisterDeletedEntryHeader
+            | DhtOpType::RegisterAddLink
+            | DhtOpType::RegisterRemoveLink => hash_type::AnyDht::Entry,
+        }
+    }
+}
+
+/// Which types of [DhtOp] this node takes authority for.
+/// Ops of a type that isn't held are released once validated
+/// instead of being integrated, so they are never served or gossiped.
+/// All types are held by default.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct HeldOpTypes {
+    /// The op types that are not held
+    pub disabled: HashSet<DhtOpType>,
+}
+
+impl HeldOpTypes {
+    /// Stop holding this type of op
+    pub fn disable(mut self, op_type: DhtOpType) -> Self {
+        self.disabled.insert(op_type);
+        self
+    }
+
+    /// Check if this type of op is held
+    pub fn is_held(&self, op_type: DhtOpType) -> bool {
+        !self.disabled.contains(&op_type)
+    }
+}
+
 /// A type for storing in databases that don't need the actual
 /// data. Everything is a hash of the type except the signatures.
 #[allow(missing_docs)]
@@ -165,6 +221,20 @@ impl DhtOp {
         }
     }
 
+    /// Get the type of this op
+    pub fn get_type(&self) -> DhtOpType {
+        match self {
+            DhtOp::StoreElement(_, _, _) => DhtOpType::StoreElement,
+            DhtOp::StoreEntry(_, _, _) => DhtOpType::StoreEntry,
+            DhtOp::RegisterAgentActivity(_, _) => DhtOpType::RegisterAgentActivity,
+            DhtOp::RegisterUpdatedBy(_, _) => DhtOpType::RegisterUpdatedBy,
+            DhtOp::RegisterDeletedBy(_, _) => DhtOpType::RegisterDeletedBy,
+            DhtOp::RegisterDeletedEntryHeader(_, _) => DhtOpType::RegisterDeletedEntryHeader,
+            DhtOp::RegisterAddLink(_, _) => DhtOpType::RegisterAddLink,
+            DhtOp::RegisterRemoveLink(_, _) => DhtOpType::RegisterRemoveLink,
+        }
+    }
+
     /// Get the signature for this op
     pub fn signature(&self) -> &Signature {
         match self {
@@ -178,6 +248,20 @@ impl DhtOp {
             | DhtOp::RegisterRemoveLink(s, _) => s,
         }
     }
+
+    /// Get the header for this op
+    pub fn header(&self) -> Header {
+        match self {
+            DhtOp::StoreElement(_, h, _) | DhtOp::RegisterAgentActivity(_, h) => h.clone(),
+            DhtOp::StoreEntry(_, h, _) => h.clone().into(),
+            DhtOp::RegisterUpdatedBy(_, h) => h.clone().into(),
+            DhtOp::RegisterDeletedBy(_, h) | DhtOp::RegisterDeletedEntryHeader(_, h) => {
+                h.clone().into()
+            }
+            DhtOp::RegisterAddLink(_, h) => h.clone().into(),
+            DhtOp::RegisterRemoveLink(_, h) => h.clone().into(),
+        }
+    }
 }
 
 impl DhtOpLight {
@@ -194,6 +278,20 @@ impl DhtOpLight {
             | DhtOpLight::RegisterRemoveLink(_, b) => b,
         }
     }
+    /// Get the type of this op
+    pub fn get_type(&self) -> DhtOpType {
+        match self {
+            DhtOpLight::StoreElement(_, _, _) => DhtOpType::StoreElement,
+            DhtOpLight::StoreEntry(_, _, _) => DhtOpType::StoreEntry,
+            DhtOpLight::RegisterAgentActivity(_, _) => DhtOpType::RegisterAgentActivity,
+            DhtOpLight::RegisterUpdatedBy(_, _, _) => DhtOpType::RegisterUpdatedBy,
+            DhtOpLight::RegisterDeletedBy(_, _) => DhtOpType::RegisterDeletedBy,
+            DhtOpLight::RegisterDeletedEntryHeader(_, _) => DhtOpType::RegisterDeletedEntryHeader,
+            DhtOpLight::RegisterAddLink(_, _) => DhtOpType::RegisterAddLink,
+            DhtOpLight::RegisterRemoveLink(_, _) => DhtOpType::RegisterRemoveLink,
+        }
+    }
+
     /// Get the header hash from this op
     pub fn header_hash(&self) -> &HeaderHash {
         match self {
@@ -1,3 +1,4 @@
+use super::DhtOpType;
 use holochain_serialized_bytes::SerializedBytesError;
 use holochain_zome_types::{header::conversions::WrongHeaderError, Header};
 use thiserror::Error;
@@ -10,6 +11,14 @@ pub enum DhtOpError {
     SerializedBytesError(#[from] SerializedBytesError),
     #[error(transparent)]
     WrongHeaderError(#[from] WrongHeaderError),
+    #[error("External op representation version {0} is not supported")]
+    ExternalVersion(u32),
+    #[error("External op has a malformed hash {0}: {1}")]
+    ExternalHash(String, String),
+    #[error("External op of type {0:?} has an entry hash where it shouldn't or is missing one")]
+    ExternalEntryHash(DhtOpType),
+    #[error("External op of type {0:?} has a basis of the wrong hash type")]
+    ExternalBasis(DhtOpType),
 }
 
 pub type DhtOpResult<T> = Result<T, DhtOpError>;
@@ -0,0 +1,193 @@
+//! A stable, self-describing form of [DhtOpLight] for tools outside of
+//! Holochain, like indexers and explorers, to consume.
+//!
+//! The enum layout of [DhtOpLight] is internal and may change at any time.
+//! [ExternalOpRepr] is a plain struct of strings which serializes to JSON
+//! (or any other serde format) the same way regardless of that layout.
+//! Any breaking change to it bumps [EXTERNAL_OP_REPR_VERSION].
+
+use super::{DhtOpLight, DhtOpType};
+use crate::dht_op::error::{DhtOpError, DhtOpResult};
+use holo_hash::{error::HoloHashError, AnyDhtHash, EntryHash, HeaderHash};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The version of [ExternalOpRepr] this code produces and parses
+pub const EXTERNAL_OP_REPR_VERSION: u32 = 1;
+
+/// An op as external consumers see it.
+/// Hashes are in their display form, e.g. `uhCkk...`,
+/// so their prefix says what kind of hash they are.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExternalOpRepr {
+    /// The [EXTERNAL_OP_REPR_VERSION] this was produced with
+    pub version: u32,
+    /// Which type of op this is, e.g. `"StoreEntry"`
+    pub op_type: DhtOpType,
+    /// The header the op was produced from
+    pub header_hash: String,
+    /// The entry the op refers to.
+    /// Always set for `StoreEntry` and `RegisterUpdatedBy`,
+    /// set for `StoreElement` when the header has an entry
+    /// and never set otherwise.
+    pub entry_hash: Option<String>,
+    /// The address of the authorities that hold this op
+    pub basis: String,
+}
+
+impl DhtOpLight {
+    /// Convert to the representation for external consumers
+    pub fn to_external(&self) -> ExternalOpRepr {
+        let entry_hash = match self {
+            DhtOpLight::StoreElement(_, e, _) => e.as_ref(),
+            DhtOpLight::StoreEntry(_, e, _) | DhtOpLight::RegisterUpdatedBy(_, e, _) => Some(e),
+            _ => None,
+        };
+        ExternalOpRepr {
+            version: EXTERNAL_OP_REPR_VERSION,
+            op_type: self.get_type(),
+            header_hash: self.header_hash().to_string(),
+            entry_hash: entry_hash.map(ToString::to_string),
+            basis: self.dht_basis().to_string(),
+        }
+    }
+
+    /// Parse the representation for external consumers
+    pub fn from_external(repr: &ExternalOpRepr) -> DhtOpResult<Self> {
+        if repr.version != EXTERNAL_OP_REPR_VERSION {
+            return Err(DhtOpError::ExternalVersion(repr.version));
+        }
+        let h = parse_header_hash(&repr.header_hash)?;
+        let b = parse_basis(&repr.basis)?;
+        if *b.hash_type() != repr.op_type.basis_type() {
+            return Err(DhtOpError::ExternalBasis(repr.op_type));
+        }
+        let e = repr
+            .entry_hash
+            .as_ref()
+            .map(|e| parse_entry_hash(e))
+            .transpose()?;
+        let op = match (repr.op_type, e) {
+            (DhtOpType::StoreElement, e) => DhtOpLight::StoreElement(h, e, b),
+            (DhtOpType::StoreEntry, Some(e)) => DhtOpLight::StoreEntry(h, e, b),
+            (DhtOpType::RegisterUpdatedBy, Some(e)) => DhtOpLight::RegisterUpdatedBy(h, e, b),
+            (DhtOpType::RegisterAgentActivity, None) => DhtOpLight::RegisterAgentActivity(h, b),
+            (DhtOpType::RegisterDeletedBy, None) => DhtOpLight::RegisterDeletedBy(h, b),
+            (DhtOpType::RegisterDeletedEntryHeader, None) => {
+                DhtOpLight::RegisterDeletedEntryHeader(h, b)
+            }
+            (DhtOpType::RegisterAddLink, None) => DhtOpLight::RegisterAddLink(h, b),
+            (DhtOpType::RegisterRemoveLink, None) => DhtOpLight::RegisterRemoveLink(h, b),
+            (op_type, _) => return Err(DhtOpError::ExternalEntryHash(op_type)),
+        };
+        Ok(op)
+    }
+}
+
+impl TryFrom<&ExternalOpRepr> for DhtOpLight {
+    type Error = DhtOpError;
+    fn try_from(repr: &ExternalOpRepr) -> DhtOpResult<Self> {
+        Self::from_external(repr)
+    }
+}
+
+fn parse_header_hash(s: &str) -> DhtOpResult<HeaderHash> {
+    HeaderHash::try_from(s).map_err(|e| external_hash_error(s, e))
+}
+
+fn parse_entry_hash(s: &str) -> DhtOpResult<EntryHash> {
+    EntryHash::try_from(s).map_err(|e| external_hash_error(s, e))
+}
+
+fn external_hash_error(s: &str, e: HoloHashError) -> DhtOpError {
+    DhtOpError::ExternalHash(s.to_string(), format!("{:?}", e))
+}
+
+/// The basis is either an entry (which includes agent keys) or a header
+fn parse_basis(s: &str) -> DhtOpResult<AnyDhtHash> {
+    parse_entry_hash(s)
+        .map(AnyDhtHash::from)
+        .or_else(|_| parse_header_hash(s).map(AnyDhtHash::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixt::*;
+    use ::fixt::prelude::*;
+
+    #[test]
+    fn every_op_round_trips_through_external_json() {
+        let h = || fixt!(HeaderHash);
+        let e = || fixt!(EntryHash);
+        let agent_basis = || AnyDhtHash::from(fixt!(AgentPubKey));
+        let entry_basis = || AnyDhtHash::from(fixt!(EntryHash));
+        let header_basis = || AnyDhtHash::from(fixt!(HeaderHash));
+
+        let ops = vec![
+            DhtOpLight::StoreElement(h(), Some(e()), header_basis()),
+            DhtOpLight::StoreElement(h(), None, header_basis()),
+            DhtOpLight::StoreEntry(h(), e(), entry_basis()),
+            DhtOpLight::RegisterAgentActivity(h(), agent_basis()),
+            DhtOpLight::RegisterUpdatedBy(h(), e(), entry_basis()),
+            DhtOpLight::RegisterDeletedBy(h(), header_basis()),
+            DhtOpLight::RegisterDeletedEntryHeader(h(), entry_basis()),
+            DhtOpLight::RegisterAddLink(h(), entry_basis()),
+            DhtOpLight::RegisterRemoveLink(h(), entry_basis()),
+        ];
+        for op in ops {
+            let repr = op.to_external();
+            assert_eq!(repr.version, EXTERNAL_OP_REPR_VERSION);
+            assert_eq!(repr.op_type, op.get_type());
+            assert_eq!(repr.header_hash, op.header_hash().to_string());
+
+            let json = serde_json::to_string(&repr).unwrap();
+            let parsed: ExternalOpRepr = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, repr);
+            assert_eq!(DhtOpLight::from_external(&parsed).unwrap(), op);
+        }
+    }
+
+    #[test]
+    fn inconsistent_external_ops_are_refused() {
+        let op =
+            DhtOpLight::StoreEntry(fixt!(HeaderHash), fixt!(EntryHash), fixt!(EntryHash).into());
+
+        let mut repr = op.to_external();
+        repr.version += 1;
+        assert!(matches!(
+            DhtOpLight::from_external(&repr),
+            Err(DhtOpError::ExternalVersion(_))
+        ));
+
+        let mut repr = op.to_external();
+        repr.entry_hash = None;
+        assert!(matches!(
+            DhtOpLight::from_external(&repr),
+            Err(DhtOpError::ExternalEntryHash(DhtOpType::StoreEntry))
+        ));
+
+        let mut repr = op.to_external();
+        repr.header_hash = repr.entry_hash.clone().unwrap();
+        assert!(matches!(
+            DhtOpLight::from_external(&repr),
+            Err(DhtOpError::ExternalHash(_, _))
+        ));
+
+        // A StoreEntry is held by the entry's authorities, not a header's
+        let mut repr = op.to_external();
+        repr.basis = fixt!(HeaderHash).to_string();
+        assert!(matches!(
+            DhtOpLight::from_external(&repr),
+            Err(DhtOpError::ExternalBasis(DhtOpType::StoreEntry))
+        ));
+        // and a StoreElement by the header's
+        let mut repr = DhtOpLight::StoreElement(fixt!(HeaderHash), None, fixt!(HeaderHash).into())
+            .to_external();
+        repr.basis = fixt!(EntryHash).to_string();
+        assert!(matches!(
+            DhtOpLight::from_external(&repr),
+            Err(DhtOpError::ExternalBasis(DhtOpType::StoreElement))
+        ));
+    }
+}
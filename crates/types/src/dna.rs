@@ -11,8 +11,8 @@ use derive_more::From;
 pub use error::DnaError;
 use holo_hash::impl_hashable_content;
 pub use holo_hash::*;
-use holochain_zome_types::zome::ZomeName;
-use std::collections::BTreeMap;
+use holochain_zome_types::zome::{FunctionName, ZomeName};
+use std::collections::{BTreeMap, BTreeSet};
 
 /// Zomes need to be an ordered map from ZomeName to a Zome
 pub type Zomes = Vec<(ZomeName, zome::Zome)>;
@@ -43,14 +43,89 @@ pub struct DnaDef {
 
     /// An array of zomes associated with your holochain application.
     pub zomes: Zomes,
+
+    /// Reject links whose base and target are the same entry in sys validation.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reject_self_links: bool,
+
+    /// Reject app entries of this many bytes or more, both when committing
+    /// and in sys validation. Falls back to the conductor-wide limit when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_entry_size: Option<u32>,
+
+    /// How `get` chooses between diverging updates when following updates.
+    #[serde(default, skip_serializing_if = "UpdateConflictPolicy::is_default")]
+    pub update_conflict_policy: UpdateConflictPolicy,
+
+    /// Zome functions whose inputs and outputs may hold private data.
+    /// The ribosome logs a redacted placeholder in place of their payloads.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub sensitive_fns: BTreeSet<(ZomeName, FunctionName)>,
+}
+
+/// How diverging updates of an entry are resolved to a single winner.
+/// Every agent applies the same policy so they all choose the same winner.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UpdateConflictPolicy {
+    /// The update with the latest timestamp then header hash wins
+    LastWriteWins,
+    /// The update by the lowest author key wins.
+    /// Updates by the same author fall back to the last write.
+    LowestAuthorWins,
+    /// The `resolve_conflict` callback of the calling zome chooses the winner.
+    /// Falls back to the last write if the callback doesn't choose.
+    Custom,
+}
+
+impl Default for UpdateConflictPolicy {
+    fn default() -> Self {
+        Self::LastWriteWins
+    }
+}
+
+impl UpdateConflictPolicy {
+    /// Whether this is the policy a [DnaDef] gets when it doesn't choose one
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 impl DnaDef {
+    /// Start building a [DnaDef] with sensible defaults
+    pub fn builder() -> DnaDefBuilder {
+        DnaDefBuilder::default()
+    }
+
     /// Calculate DnaHash for DnaDef
     pub async fn dna_hash(&self) -> DnaHash {
         DnaHash::with_data(self).await
     }
 
+    /// Whether the payloads of this zome function must be kept out of the logs
+    pub fn is_sensitive(&self, zome_name: &ZomeName, fn_name: &FunctionName) -> bool {
+        self.sensitive_fns
+            .contains(&(zome_name.clone(), fn_name.clone()))
+    }
+
+    /// Check the zomes are structurally sound.
+    /// There must be at least one zome and
+    /// every zome must have a unique, non-empty name.
+    pub fn check_zomes(&self) -> Result<(), DnaError> {
+        if self.zomes.is_empty() {
+            return Err(DnaError::EmptyDna);
+        }
+        let mut names = std::collections::HashSet::new();
+        for (name, _) in &self.zomes {
+            if name.0.is_empty() {
+                return Err(DnaError::Invalid("Zome name is empty".to_string()));
+            }
+            if !names.insert(name) {
+                return Err(DnaError::DuplicateZomeName(name.to_string()));
+            }
+        }
+        Ok(())
+    }
+
     /// Return a Zome
     pub fn get_zome(&self, zome_name: &ZomeName) -> Result<&zome::Zome, DnaError> {
         self.zomes
@@ -61,6 +136,112 @@ impl DnaDef {
     }
 }
 
+/// A builder for [DnaDef].
+/// Any field that is not set will get a default when built.
+/// If no uuid is set a random one is generated so that
+/// DNAs built in different places don't collide.
+#[derive(Debug, Clone, Default)]
+pub struct DnaDefBuilder {
+    name: Option<String>,
+    uuid: Option<String>,
+    properties: Option<SerializedBytes>,
+    zomes: Zomes,
+    reject_self_links: bool,
+    max_entry_size: Option<u32>,
+    update_conflict_policy: UpdateConflictPolicy,
+    sensitive_fns: BTreeSet<(ZomeName, FunctionName)>,
+}
+
+impl DnaDefBuilder {
+    /// Set the name of the dna
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the uuid of the dna
+    pub fn uuid<S: Into<String>>(mut self, uuid: S) -> Self {
+        self.uuid = Some(uuid.into());
+        self
+    }
+
+    /// Set a random uuid for the dna
+    pub fn random_uuid(mut self) -> Self {
+        self.uuid = Some(nanoid::nanoid!());
+        self
+    }
+
+    /// Set the properties of the dna
+    pub fn properties(mut self, properties: SerializedBytes) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+
+    /// Reject links from an entry to itself
+    pub fn reject_self_links(mut self, reject: bool) -> Self {
+        self.reject_self_links = reject;
+        self
+    }
+
+    /// Set the size in bytes at which app entries are rejected
+    pub fn max_entry_size(mut self, size: u32) -> Self {
+        self.max_entry_size = Some(size);
+        self
+    }
+
+    /// Set how diverging updates are resolved
+    pub fn update_conflict_policy(mut self, policy: UpdateConflictPolicy) -> Self {
+        self.update_conflict_policy = policy;
+        self
+    }
+
+    /// Keep the payloads of a zome function out of the logs
+    pub fn sensitive_fn<Z: Into<ZomeName>, F: Into<FunctionName>>(
+        mut self,
+        zome_name: Z,
+        fn_name: F,
+    ) -> Self {
+        self.sensitive_fns
+            .insert((zome_name.into(), fn_name.into()));
+        self
+    }
+
+    /// Add a zome to the dna.
+    /// Zomes are kept in the order they are added.
+    pub fn add_zome<Z: Into<(ZomeName, zome::Zome)>>(mut self, zome: Z) -> Self {
+        self.zomes.push(zome.into());
+        self
+    }
+
+    /// Build the [DnaDef].
+    /// Errors if the zomes are not valid. See [DnaDef::check_zomes].
+    pub fn build(self) -> Result<DnaDef, DnaError> {
+        let Self {
+            name,
+            uuid,
+            properties,
+            zomes,
+            reject_self_links,
+            max_entry_size,
+            update_conflict_policy,
+            sensitive_fns,
+        } = self;
+        let dna = DnaDef {
+            name: name.unwrap_or_else(|| "dna".to_string()),
+            uuid: uuid.unwrap_or_else(|| nanoid::nanoid!()),
+            properties: properties
+                .unwrap_or_else(|| SerializedBytes::try_from(()).expect("Unit always serializes")),
+            zomes,
+            reject_self_links,
+            max_entry_size,
+            update_conflict_policy,
+            sensitive_fns,
+        };
+        dna.check_zomes()?;
+        Ok(dna)
+    }
+}
+
 /// A DnaDef paired with its DnaHash
 pub type DnaDefHashed = HoloHashed<DnaDef>;
 
@@ -188,3 +369,91 @@ impl std::fmt::Debug for DnaFile {
         f.write_fmt(format_args!("DnaFile(dna_hash = {})", self.dna_hash))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn random_uuid_gives_distinct_dna_hashes() {
+        let wasm_hash = holo_hash::WasmHash::with_data(&wasm::DnaWasm::from(vec![])).await;
+        let zome: (ZomeName, zome::Zome) = ("test".into(), zome::Zome { wasm_hash });
+        let a = DnaDef::builder()
+            .name("test")
+            .random_uuid()
+            .add_zome(zome.clone())
+            .build()
+            .unwrap();
+        let b = DnaDef::builder()
+            .name("test")
+            .random_uuid()
+            .add_zome(zome)
+            .build()
+            .unwrap();
+        assert_ne!(a.uuid, b.uuid);
+        assert_ne!(a.dna_hash().await, b.dna_hash().await);
+    }
+
+    async fn test_zome(name: &str) -> (ZomeName, zome::Zome) {
+        let wasm_hash = holo_hash::WasmHash::with_data(&wasm::DnaWasm::from(vec![])).await;
+        (name.into(), zome::Zome { wasm_hash })
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn duplicate_zome_names_error() {
+        let r = DnaDef::builder()
+            .add_zome(test_zome("a").await)
+            .add_zome(test_zome("a").await)
+            .build();
+        assert!(matches!(r, Err(DnaError::DuplicateZomeName(name)) if name == "a"));
+    }
+
+    /// A [DnaDef] as it was before it had any options
+    #[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+    struct DnaDefWithoutOptions {
+        name: String,
+        uuid: String,
+        properties: SerializedBytes,
+        zomes: Zomes,
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn default_options_keep_the_dna_hash() {
+        let dna_def = DnaDef::builder()
+            .name("test")
+            .uuid("a4e1e6c8-5b8f-4e36-9d0b-2f4c1a7d3e95")
+            .add_zome(test_zome("a").await)
+            .build()
+            .unwrap();
+        let without_options = DnaDefWithoutOptions {
+            name: dna_def.name.clone(),
+            uuid: dna_def.uuid.clone(),
+            properties: dna_def.properties.clone(),
+            zomes: dna_def.zomes.clone(),
+        };
+        let sb = SerializedBytes::try_from(&without_options).unwrap();
+        assert_eq!(SerializedBytes::try_from(&dna_def).unwrap(), sb);
+        assert_eq!(DnaDef::try_from(sb).unwrap(), dna_def);
+
+        // Choosing an option is what changes the hash
+        let mut with_option = dna_def.clone();
+        with_option.reject_self_links = true;
+        assert_ne!(with_option.dna_hash().await, dna_def.dna_hash().await);
+    }
+
+    #[test]
+    fn empty_dna_errors() {
+        let r = DnaDef::builder().build();
+        assert!(matches!(r, Err(DnaError::EmptyDna)));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn valid_zomes_build() {
+        let dna = DnaDef::builder()
+            .add_zome(test_zome("a").await)
+            .add_zome(test_zome("b").await)
+            .build()
+            .unwrap();
+        assert_eq!(dna.zomes.len(), 2);
+    }
+}
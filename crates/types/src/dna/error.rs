@@ -37,6 +37,14 @@ pub enum DnaError {
     /// InvalidWasmHash
     #[error("InvalidWasmHash")]
     InvalidWasmHash,
+
+    /// DuplicateZomeName
+    #[error("Duplicate zome name: {0}")]
+    DuplicateZomeName(String),
+
+    /// EmptyDna
+    #[error("DNA has no zomes")]
+    EmptyDna,
 }
 
 impl From<std::io::Error> for DnaError {
@@ -53,7 +53,6 @@ use holochain_zome_types::zome::FunctionName;
 use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::Entry;
 use rand::seq::IteratorRandom;
-use rand::thread_rng;
 use rand::Rng;
 use std::collections::BTreeMap;
 use std::collections::HashSet;
@@ -86,7 +85,7 @@ fixturator!(
     CapSecret;
     curve Empty [0; CAP_SECRET_BYTES].into();
     curve Unpredictable {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         let upper = rng.gen::<[u8; CAP_SECRET_BYTES / 2]>();
         let lower = rng.gen::<[u8; CAP_SECRET_BYTES / 2]>();
         let mut inner = [0; CAP_SECRET_BYTES];
@@ -139,7 +138,7 @@ fixturator!(
     CurryPayloads;
     curve Empty CurryPayloads(BTreeMap::new());
     curve Unpredictable {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         let number_of_payloads = rng.gen_range(0, 5);
 
         let mut payloads: BTreeMap<GrantedFunction, SerializedBytes> = BTreeMap::new();
@@ -151,7 +150,7 @@ fixturator!(
         CurryPayloads(payloads)
     };
     curve Predictable {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         let number_of_payloads = rng.gen_range(0, 5);
 
         let mut payloads: BTreeMap<GrantedFunction, SerializedBytes> = BTreeMap::new();
@@ -171,7 +170,7 @@ fixturator!(
             StringFixturator::new(Empty).next().unwrap(),
             CapAccessFixturator::new(Empty).next().unwrap(),
             {
-                let mut rng = rand::thread_rng();
+                let mut rng = rng();
                 let number_of_zomes = rng.gen_range(0, 5);
 
                 let mut granted_functions: GrantedFunctions = HashSet::new();
@@ -187,7 +186,7 @@ fixturator!(
             StringFixturator::new(Unpredictable).next().unwrap(),
             CapAccessFixturator::new(Unpredictable).next().unwrap(),
             {
-                let mut rng = rand::thread_rng();
+                let mut rng = rng();
                 let number_of_zomes = rng.gen_range(0, 5);
 
                 let mut granted_functions: GrantedFunctions = HashSet::new();
@@ -247,7 +246,7 @@ fixturator!(
                 CapAccess::from(CapSecretFixturator::new_indexed(Unpredictable, self.0.index).next().unwrap())
             },
             CapAccessVariant::Assigned => {
-                let mut rng = rand::thread_rng();
+                let mut rng = rng();
                 let number_of_assigned = rng.gen_range(0, 5);
 
                 CapAccess::from((
@@ -350,7 +349,7 @@ fixturator!(
     EntryDefs;
     curve Empty Vec::new().into();
     curve Unpredictable {
-        let mut rng = rand::thread_rng();
+        let mut rng = rng();
         let number_of_defs = rng.gen_range(0, 5);
 
         let mut defs = vec![];
@@ -398,6 +397,10 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Empty, self.0.index)
             .next()
             .unwrap(),
+        reject_self_links: false,
+        max_entry_size: None,
+        update_conflict_policy: Default::default(),
+        sensitive_fns: Default::default(),
     };
 
     curve Unpredictable DnaDef {
@@ -413,6 +416,10 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Unpredictable, self.0.index)
             .next()
             .unwrap(),
+        reject_self_links: false,
+        max_entry_size: None,
+        update_conflict_policy: Default::default(),
+        sensitive_fns: Default::default(),
     };
 
     curve Predictable DnaDef {
@@ -428,6 +435,10 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Predictable, self.0.index)
             .next()
             .unwrap(),
+        reject_self_links: false,
+        max_entry_size: None,
+        update_conflict_policy: Default::default(),
+        sensitive_fns: Default::default(),
     };
 );
 
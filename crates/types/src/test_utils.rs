@@ -44,6 +44,10 @@ pub fn fake_dna_zomes(uuid: &str, zomes: Vec<(ZomeName, DnaWasm)>) -> DnaFile {
             .unwrap(),
         uuid: uuid.to_string(),
         zomes: Vec::new(),
+        reject_self_links: false,
+        max_entry_size: None,
+        update_conflict_policy: Default::default(),
+        sensitive_fns: Default::default(),
     };
     tokio_safe_block_on::tokio_safe_block_forever_on(async move {
         let mut wasm_code = Vec::new();
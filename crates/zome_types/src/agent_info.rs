@@ -1,3 +1,4 @@
+use crate::timestamp::Timestamp;
 use holo_hash::AgentPubKey;
 use holochain_serialized_bytes::prelude::*;
 
@@ -13,3 +14,16 @@ pub struct AgentInfo {
     /// The agent can revoke an old key and replace it with a new one, the latest appears here.
     pub agent_latest_pubkey: AgentPubKey,
 }
+
+/// Another agent on the same DNA that has opted into being discoverable.
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq)]
+pub struct PeerInfo {
+    /// The peer's pubkey.
+    pub agent_pub_key: AgentPubKey,
+    /// When the network last saw the peer join or take part in gossip.
+    pub last_seen: Timestamp,
+}
+
+/// Small struct to allow the return type of `get_peers!()` to be a vector of peers
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct PeerInfoVec(pub Vec<PeerInfo>);
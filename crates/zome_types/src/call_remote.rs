@@ -1,8 +1,9 @@
-use crate::capability::CapSecret;
+use crate::capability::{CapNonce, CapSecret};
+use crate::timestamp::Timestamp;
 use crate::zome::FunctionName;
 use crate::zome::ZomeName;
 use holo_hash::AgentPubKey;
-use holochain_serialized_bytes::prelude::SerializedBytes;
+use holochain_serialized_bytes::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct CallRemote {
@@ -11,6 +12,8 @@ pub struct CallRemote {
     fn_name: FunctionName,
     cap: Option<CapSecret>,
     request: SerializedBytes,
+    #[serde(default)]
+    nonce: Option<CapNonce>,
 }
 
 impl CallRemote {
@@ -27,9 +30,16 @@ impl CallRemote {
             fn_name,
             cap,
             request,
+            nonce: None,
         }
     }
 
+    /// Choose the nonce sent with the call instead of a random one
+    pub fn with_nonce(mut self, nonce: CapNonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
     pub fn to_agent(&self) -> AgentPubKey {
         self.to_agent.clone()
     }
@@ -49,4 +59,20 @@ impl CallRemote {
     pub fn request(&self) -> SerializedBytes {
         self.request.clone()
     }
+
+    pub fn nonce(&self) -> Option<CapNonce> {
+        self.nonce
+    }
+}
+
+/// Everything the caller of a remote call signs for the [crate::capability::SignedCapNonce].
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct CallRemoteSignedData {
+    pub to_agent: AgentPubKey,
+    pub zome_name: ZomeName,
+    pub fn_name: FunctionName,
+    pub cap: Option<CapSecret>,
+    pub nonce: CapNonce,
+    pub expires_at: Timestamp,
+    pub payload: SerializedBytes,
 }
@@ -23,10 +23,15 @@
 //! commit to their source chain as a private entry. This struct contains the
 //! information needed to refer to the capability as well as the secret needed
 //! to send to the Grantor.
+//!
+//! Every remote call also carries a **`CapNonce`** which the Grantor will only
+//! accept once, so a call that has been captured can't be replayed.
 
 mod claim;
 mod grant;
+mod nonce;
 mod secret;
 pub use claim::*;
 pub use grant::*;
+pub use nonce::*;
 pub use secret::*;
@@ -0,0 +1,44 @@
+use crate::signature::Signature;
+use crate::timestamp::Timestamp;
+use holochain_serialized_bytes::prelude::*;
+
+/// The number of bytes in a nonce.
+pub const CAP_NONCE_BYTES: usize = 32;
+
+/// How many seconds after a remote call is made the host makes it expire.
+pub const CAP_NONCE_EXPIRY_SECS: i64 = 60;
+
+/// A CapNonce is sent along with a CapSecret to make a call unique.
+///
+/// The callee remembers the nonces it has recently seen from each agent and
+/// rejects any call that reuses one, so a captured call can't be resent.
+/// Calls without a nonce are rejected, so if a zome doesn't pick one
+/// the host sends a fresh random nonce with every call.
+/// It's sent as a [SignedCapNonce].
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+pub struct CapNonce([u8; CAP_NONCE_BYTES]);
+
+/// Trivial new type derivation.
+impl From<[u8; CAP_NONCE_BYTES]> for CapNonce {
+    fn from(b: [u8; CAP_NONCE_BYTES]) -> Self {
+        Self(b)
+    }
+}
+
+/// The nonce a remote call is made with, signed by the caller.
+///
+/// The signature covers the call itself along with the nonce and `expires_at`
+/// so none of them can be changed without the callee noticing.
+/// The callee only accepts the call until `expires_at` and remembers the nonce
+/// until then, so a captured call can't be resent once its nonce is forgotten.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct SignedCapNonce {
+    /// The nonce making the call unique
+    pub nonce: CapNonce,
+    /// When the callee stops accepting the call
+    pub expires_at: Timestamp,
+    /// The caller's signature over the [crate::call_remote::CallRemoteSignedData]
+    pub signature: Signature,
+}
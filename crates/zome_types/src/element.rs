@@ -85,6 +85,78 @@ impl Element {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
 pub struct ElementVec(pub Vec<Element>);
 
+/// Which of the checks that an element is internally consistent passed.
+/// Useful before trusting an element that was received out of band.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct ElementVerification {
+    /// The header hash the element carries is the hash of its header
+    pub header_hash: bool,
+    /// The signature was made by the header's author
+    pub signature: bool,
+    /// The entry is the one the header refers to.
+    /// `None` if the header has no entry or the element doesn't include it.
+    pub entry_hash: Option<bool>,
+}
+
+impl ElementVerification {
+    /// Every check that could be made passed
+    pub fn is_valid(&self) -> bool {
+        self.header_hash && self.signature && self.entry_hash != Some(false)
+    }
+}
+
+/// The latest live update of an element and how it was reached.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct LatestElement {
+    /// The element at the end of the branch
+    pub element: Element,
+    /// The header of each update followed to reach `element`, oldest first.
+    /// Where updates diverged this is the branch that was chosen.
+    pub branch: Vec<HeaderHash>,
+}
+
+/// The elements found for a list of hashes, each distinct element only once.
+/// `indices` lines up with the hashes asked for and points into `elements`,
+/// so hashes that resolve to the same element share an index.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct IndexedElements {
+    /// Every distinct element that was found
+    pub elements: Vec<Element>,
+    /// For each hash asked for, where its element is in `elements`
+    /// or `None` if there was no live element
+    pub indices: Vec<Option<usize>>,
+}
+
+impl IndexedElements {
+    /// The element for the hash at this position in the list asked for
+    pub fn get(&self, index: usize) -> Option<&Element> {
+        self.indices
+            .get(index)
+            .copied()
+            .flatten()
+            .and_then(|i| self.elements.get(i))
+    }
+
+    /// Add the element found for the next hash.
+    /// An element that was already found for an earlier hash is not added again.
+    pub fn push(&mut self, element: Option<Element>) {
+        let index = element.map(|element| {
+            match self
+                .elements
+                .iter()
+                .position(|e| e.header_address() == element.header_address())
+            {
+                Some(i) => i,
+                None => {
+                    self.elements.push(element);
+                    self.elements.len() - 1
+                }
+            }
+        });
+        self.indices.push(index);
+    }
+}
+
 /// Represents the different ways the entry_address reference within a Header
 /// can be intepreted
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
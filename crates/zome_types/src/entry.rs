@@ -29,9 +29,25 @@ pub type CapGrantEntry = ZomeCallCapGrant;
 /// The data type written to the source chain to denote a capability claim
 pub type CapClaimEntry = CapClaim;
 
-#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
-/// @todo make some options for get
-pub struct GetOptions;
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+/// Options for getting an element from the DHT
+pub struct GetOptions {
+    /// Return the latest live update of the entry instead of the element
+    /// that was asked for, following updates of updates.
+    /// Diverging updates are resolved by following the update with the
+    /// latest timestamp then header hash.
+    /// Only `get` follows updates, `get_details` always returns the full history.
+    pub follow_updates: bool,
+}
+
+impl GetOptions {
+    /// Get options that follow updates to the latest live element
+    pub fn latest() -> Self {
+        Self {
+            follow_updates: true,
+        }
+    }
+}
 
 /// Structure holding the entry portion of a chain element.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, SerializedBytes)]
@@ -84,6 +100,31 @@ impl Entry {
     }
 }
 
+/// The stored bytes of an entry along with its type.
+/// Lets generic tooling forward an entry without knowing its schema.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, SerializedBytes)]
+pub struct RawEntry {
+    /// The entry type from the header that created the entry
+    pub entry_type: crate::header::EntryType,
+    /// For app entries the exact bytes the app committed,
+    /// for system entries the serialized [Entry]
+    pub bytes: SerializedBytes,
+}
+
+impl RawEntry {
+    /// Take the bytes out of an entry without deserializing app data
+    pub fn new(
+        entry_type: crate::header::EntryType,
+        entry: Entry,
+    ) -> Result<Self, SerializedBytesError> {
+        let bytes = match entry {
+            Entry::App(app_entry_bytes) => app_entry_bytes.into_sb(),
+            entry => SerializedBytes::try_from(entry)?,
+        };
+        Ok(Self { entry_type, bytes })
+    }
+}
+
 impl HashableContent for Entry {
     type HashType = hash_type::Entry;
 
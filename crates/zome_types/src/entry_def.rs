@@ -101,6 +101,11 @@ impl EntryDefs {
             .iter()
             .position(|entry_def| entry_def.id == entry_def_id)
     }
+
+    /// The entry def at this position, if there is one
+    pub fn get(&self, i: usize) -> Option<&EntryDef> {
+        self.0.get(i)
+    }
 }
 
 impl std::ops::Index<usize> for EntryDefs {
@@ -35,6 +35,8 @@ pub mod migrate_agent;
 pub mod post_commit;
 pub mod query;
 pub mod request;
+#[allow(missing_docs)]
+pub mod resolve_conflict;
 pub mod signature;
 pub mod timestamp;
 #[allow(missing_docs)]
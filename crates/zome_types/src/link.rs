@@ -1,6 +1,7 @@
 use crate::header::CreateLink;
 use crate::header::DeleteLink;
 use holochain_serialized_bytes::prelude::*;
+use holochain_serialized_bytes::UnsafeBytes;
 
 /// Opaque tag for the link applied at the app layer, used to differentiate
 /// between different semantics and validation rules for different links
@@ -26,6 +27,60 @@ impl LinkTag {
     {
         Self(t.into())
     }
+
+    /// New tag from a prefix followed by a payload.
+    ///
+    /// Every tag built with the same prefix will be returned by a `get_links`
+    /// query for `LinkTag::new(prefix)`, so the prefix acts as the "type" of
+    /// the tag and the payload is free to vary.
+    pub fn with_prefix<P, T>(prefix: P, payload: T) -> Self
+    where
+        P: Into<Vec<u8>>,
+        T: AsRef<[u8]>,
+    {
+        let mut bytes = prefix.into();
+        bytes.extend_from_slice(payload.as_ref());
+        Self(bytes)
+    }
+
+    /// New tag from a prefix followed by the serialized bytes of a payload.
+    pub fn encode<P, T>(prefix: P, payload: T) -> Result<Self, SerializedBytesError>
+    where
+        P: Into<Vec<u8>>,
+        T: TryInto<SerializedBytes, Error = SerializedBytesError>,
+    {
+        let payload_bytes: Vec<u8> = UnsafeBytes::from(payload.try_into()?).into();
+        Ok(Self::with_prefix(prefix, payload_bytes))
+    }
+
+    /// Deserialize the payload of a tag built with [LinkTag::encode].
+    /// Returns `None` if the tag does not start with `prefix`.
+    pub fn decode<T>(&self, prefix: &[u8]) -> Result<Option<T>, SerializedBytesError>
+    where
+        T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    {
+        match self.strip_prefix(prefix) {
+            Some(payload) => Ok(Some(T::try_from(SerializedBytes::from(
+                UnsafeBytes::from(payload.to_vec()),
+            ))?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Does this tag start with `prefix`?
+    /// This is the same test `get_links` applies when filtering by tag.
+    pub fn has_prefix(&self, prefix: &[u8]) -> bool {
+        self.0.starts_with(prefix)
+    }
+
+    /// The bytes after `prefix`, if this tag starts with it.
+    pub fn strip_prefix(&self, prefix: &[u8]) -> Option<&[u8]> {
+        if self.has_prefix(prefix) {
+            Some(&self.0[prefix.len()..])
+        } else {
+            None
+        }
+    }
 }
 
 impl From<Vec<u8>> for LinkTag {
@@ -102,3 +157,37 @@ impl LinkDetails {
         self.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize, serde::Deserialize, SerializedBytes, PartialEq, Clone, Debug)]
+    struct Rating {
+        stars: u8,
+        note: String,
+    }
+
+    #[test]
+    fn structured_tag_round_trip() {
+        let rating = Rating {
+            stars: 4,
+            note: "good".into(),
+        };
+        let tag = LinkTag::encode("rating:", rating.clone()).unwrap();
+
+        assert!(tag.has_prefix(b"rating:"));
+        assert!(tag.has_prefix(LinkTag::new("rating:").as_ref()));
+        assert!(!tag.has_prefix(b"review:"));
+
+        assert_eq!(Some(rating), tag.decode::<Rating>(b"rating:").unwrap());
+        assert_eq!(None, tag.decode::<Rating>(b"review:").unwrap());
+    }
+
+    #[test]
+    fn with_prefix_concatenates() {
+        let tag = LinkTag::with_prefix("a", [1, 2]);
+        assert_eq!(LinkTag::new(vec![b'a', 1, 2]), tag);
+        assert_eq!(Some(&[1u8, 2][..]), tag.strip_prefix(b"a"));
+    }
+}
@@ -1,9 +1,10 @@
 //! Metadata types for use in wasm
 use crate::{
-    element::Element,
+    element::{Element, SignedHeaderHashed},
     header::{Delete, Update},
     Entry, Header,
 };
+use holo_hash::HeaderHash;
 use holochain_serialized_bytes::prelude::*;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
@@ -37,6 +38,10 @@ pub struct EntryDetails {
     /// Create relationships.
     /// These can only be Create or Update headers
     pub headers: Vec<Header>,
+    /// The same headers along with their signatures, in the same order.
+    /// Only kept when get_details is asked to include signatures.
+    #[serde(default)]
+    pub signed_headers: Vec<SignedHeaderHashed>,
     /// Delete relationships
     pub deletes: Vec<Delete>,
     /// Update relationships.
@@ -44,6 +49,9 @@ pub struct EntryDetails {
     /// This is just the relationship and you will need call get
     /// if you want to get the new Entry.
     pub updates: Vec<Update>,
+    /// Live updates that replace the same header.
+    /// These are competing heads that the app may want to merge.
+    pub conflicts: Vec<HeaderHash>,
     /// The status of this entry currently
     /// according to your view of the metadata
     pub entry_dht_status: EntryDhtStatus,
@@ -0,0 +1,36 @@
+use crate::element::Element;
+use crate::zome_io::ExternOutput;
+use crate::CallbackResult;
+use holo_hash::HeaderHash;
+use holochain_serialized_bytes::prelude::*;
+
+/// The competing updates of an entry, in timestamp then header hash order
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct ResolveConflict(pub Vec<Element>);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub enum ResolveConflictCallbackResult {
+    /// The header hash of the update that wins
+    Winner(HeaderHash),
+    /// Leave the choice to the default policy
+    Pass,
+    Fail(String),
+}
+
+impl From<ExternOutput> for ResolveConflictCallbackResult {
+    fn from(guest_output: ExternOutput) -> Self {
+        match guest_output.into_inner().try_into() {
+            Ok(v) => v,
+            Err(e) => Self::Fail(format!("{:?}", e)),
+        }
+    }
+}
+
+impl CallbackResult for ResolveConflictCallbackResult {
+    fn is_definitive(&self) -> bool {
+        match self {
+            ResolveConflictCallbackResult::Pass => false,
+            _ => true,
+        }
+    }
+}
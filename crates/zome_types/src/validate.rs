@@ -1,6 +1,6 @@
 use crate::zome_io::ExternOutput;
 use crate::CallbackResult;
-use holo_hash::EntryHash;
+use holo_hash::{AnyDhtHash, EntryHash};
 use holochain_serialized_bytes::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
@@ -9,7 +9,8 @@ pub enum ValidateCallbackResult {
     Invalid(String),
     /// Subconscious needs to map this to either pending or abandoned based on context that the
     /// wasm can't possibly have.
-    UnresolvedDependencies(Vec<EntryHash>),
+    /// The host will fetch these dependencies and run validation again once they are found.
+    UnresolvedDependencies(Vec<AnyDhtHash>),
 }
 
 impl CallbackResult for ValidateCallbackResult {
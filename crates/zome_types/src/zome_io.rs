@@ -56,6 +56,10 @@ wasm_io_types!(
     // @todo
     pub struct DecryptInput(());
     pub struct DecryptOutput(());
+    // The entry def declared for an app entry type in this dna.
+    // None for system entry types or if no def is found.
+    pub struct EntryDefForInput(crate::header::EntryType);
+    pub struct EntryDefForOutput(Option<crate::entry_def::EntryDef>);
     // @todo
     pub struct EncryptInput(());
     pub struct EncryptOutput(());
@@ -111,6 +115,10 @@ wasm_io_types!(
         ),
     );
     pub struct CreateLinkOutput(holo_hash::HeaderHash);
+    // Close the source chain, naming the DNA the agent is migrating to.
+    pub struct CloseChainInput(holo_hash::DnaHash);
+    // Header hash of the CloseChain element.
+    pub struct CloseChainOutput(holo_hash::HeaderHash);
     // @todo
     pub struct KeystoreInput(());
     pub struct KeystoreOutput(());
@@ -122,8 +130,24 @@ wasm_io_types!(
     // Attempt to get a live entry from the cascade.
     pub struct GetInput((holo_hash::AnyDhtHash, crate::entry::GetOptions));
     pub struct GetOutput(Option<crate::element::Element>);
-    pub struct GetDetailsInput((holo_hash::AnyDhtHash, crate::entry::GetOptions));
+    // Attempt to get the live elements for many hashes from the cascade.
+    pub struct GetManyInput((Vec<holo_hash::AnyDhtHash>, crate::entry::GetOptions));
+    pub struct GetManyOutput(crate::element::IndexedElements);
+    // Follow the updates of an element, reporting the branch that was followed.
+    pub struct GetLatestInput((holo_hash::AnyDhtHash, crate::entry::GetOptions));
+    pub struct GetLatestOutput(Option<crate::element::LatestElement>);
+    pub struct GetRawInput((holo_hash::AnyDhtHash, crate::entry::GetOptions));
+    pub struct GetRawOutput(Option<crate::entry::RawEntry>);
+    pub struct GetPeersInput(());
+    pub struct GetPeersOutput(crate::agent_info::PeerInfoVec);
+    pub struct SetDiscoverableInput(bool);
+    pub struct SetDiscoverableOutput(());
+    // The bool asks for the signature of every header to be included.
+    pub struct GetDetailsInput((holo_hash::AnyDhtHash, crate::entry::GetOptions, bool));
     pub struct GetDetailsOutput(Option<crate::metadata::Details>);
+    // Get the details of many hashes at once, in the order asked for.
+    pub struct GetDetailsBatchInput(Vec<(holo_hash::AnyDhtHash, crate::entry::GetOptions)>);
+    pub struct GetDetailsBatchOutput(Vec<GetDetailsOutput>);
     // @todo
     pub struct EntryTypePropertiesInput(());
     pub struct EntryTypePropertiesOutput(());
@@ -141,6 +165,9 @@ wasm_io_types!(
     // Used to "defuse" host functions when side effects are not allowed.
     pub struct UnreachableInput(());
     pub struct UnreachableOutput(());
+    // Check an element is internally consistent without touching the DHT or source chain.
+    pub struct VerifyElementInput(crate::element::Element);
+    pub struct VerifyElementOutput(crate::element::ElementVerification);
     // Every externed function that the zome developer exposes to holochain returns `ExternOutput`.
     // The zome developer can expose callbacks in a "sparse" way based on names and the functions
     // can take different input (e.g. validation vs. hooks like init, etc.).